@@ -0,0 +1,312 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::commands::common::set_command;
+
+use std::collections::BTreeMap;
+
+pub const MAX_INCLUDE_DEPTH: usize = 16;
+
+pub enum BatchDirective<'a> {
+    SetVariable(&'a str, &'a str),
+    UnsetVariable(&'a str),
+    ShowVariable(Option<&'a str>),
+    Include(&'a str),
+    /// `set <setting> <value>` line, which is passed to `set` command as `set <setting>=<value>`.
+    Setting(&'a str, &'a str),
+}
+
+/// Recognizes variable directives: `set NAME=value` (or `set NAME value`), `unset NAME`,
+/// `show [NAME]` and `include <file>`.
+/// `set` and `show` lines whose argument is a `set` command setting or not a valid variable name
+/// (e.g. `set theme=none`, `set auto-confirm=true` or `show /home/file.txt`) are left to the regular commands.
+pub fn parse_directive(line: &str) -> Option<BatchDirective> {
+    let (word, tail) = split_first_word(line);
+    match word {
+        "set" => {
+            if let Some((name, value)) = tail.split_once(char::is_whitespace) {
+                if is_setting(name) {
+                    return Some(BatchDirective::Setting(name, value.trim()));
+                }
+            }
+            let (name, value) = tail
+                .split_once('=')
+                .or_else(|| tail.split_once(char::is_whitespace))?;
+            if is_setting(name) {
+                None
+            } else if is_variable_name(name) {
+                Some(BatchDirective::SetVariable(name, trim_quotes(value.trim())))
+            } else {
+                None
+            }
+        }
+        "unset" if is_variable_name(tail) => Some(BatchDirective::UnsetVariable(tail)),
+        "show" if tail.is_empty() => Some(BatchDirective::ShowVariable(None)),
+        "show" if is_variable_name(tail) => Some(BatchDirective::ShowVariable(Some(tail))),
+        "include" if !tail.is_empty() => Some(BatchDirective::Include(trim_quotes(tail))),
+        _ => None,
+    }
+}
+
+/// Returns the batch line to echo with the value of `set NAME=value` directive masked,
+/// so secrets kept in variables are not printed.
+pub fn mask_line(line: &str) -> String {
+    let (prefix, directive) = match line.strip_prefix('-') {
+        Some(directive) => ("-", directive),
+        None => ("", line),
+    };
+    match parse_directive(directive) {
+        Some(BatchDirective::SetVariable(name, _)) => format!("{}set {}=***", prefix, name),
+        _ => line.to_string(),
+    }
+}
+
+/// Replaces `$NAME`, `${NAME}` and `{{NAME}}` references to defined variables with their values.
+/// Any other text, including references to undefined variables, is left as is,
+/// so values like `key=pa$$word` are passed to commands unchanged.
+/// `$` put before a reference to a defined variable (`$$NAME`) keeps the reference literal.
+pub fn substitute(line: &str, variables: &BTreeMap<String, String>) -> String {
+    let mut res = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(pos) = rest.find(|c| c == '$' || c == '{') {
+        res.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with("$$") {
+            let escaped = &rest[1..];
+            if let Some((name, len)) = parse_reference(escaped) {
+                if variables.contains_key(name) {
+                    res.push_str(&escaped[..len]);
+                    rest = &escaped[len..];
+                    continue;
+                }
+            }
+        }
+
+        if let Some((name, len)) = parse_reference(rest) {
+            if let Some(value) = variables.get(name) {
+                res.push_str(value);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+
+        // both '$' and '{' are single byte
+        res.push_str(&rest[..1]);
+        rest = &rest[1..];
+    }
+    res.push_str(rest);
+
+    res
+}
+
+/// Parses a variable reference at the start of `text` returning the variable name and the reference length.
+fn parse_reference(text: &str) -> Option<(&str, usize)> {
+    let (name, len) = if let Some(tail) = text.strip_prefix("{{") {
+        let end = tail.find("}}")?;
+        (tail[..end].trim(), end + 4)
+    } else if let Some(tail) = text.strip_prefix("${") {
+        let end = tail.find('}')?;
+        (&tail[..end], end + 3)
+    } else {
+        let tail = text.strip_prefix('$')?;
+        let end = tail
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(tail.len());
+        (&tail[..end], end + 1)
+    };
+
+    if is_variable_name(name) {
+        Some((name, len))
+    } else {
+        None
+    }
+}
+
+fn is_setting(name: &str) -> bool {
+    set_command::new()
+        .metadata()
+        .params()
+        .iter()
+        .any(|param| param.name() == name)
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+fn split_first_word(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((word, tail)) => (word, tail.trim()),
+        None => (line, ""),
+    }
+}
+
+fn trim_quotes(value: &str) -> &str {
+    if value.len() > 1 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> BTreeMap<String, String> {
+        let mut variables = BTreeMap::new();
+        variables.insert("DID".to_string(), "V4SGRU86Z58d6TV7PBUe6f".to_string());
+        variables.insert("POOL_NAME".to_string(), "sandbox".to_string());
+        variables
+    }
+
+    #[test]
+    fn parse_directive_works_for_set() {
+        match parse_directive("set DID=\"V4SGRU86Z58d6TV7PBUe6f\"") {
+            Some(BatchDirective::SetVariable(name, value)) => {
+                assert_eq!("DID", name);
+                assert_eq!("V4SGRU86Z58d6TV7PBUe6f", value);
+            }
+            _ => panic!("set directive expected"),
+        }
+    }
+
+    #[test]
+    fn mask_line_works() {
+        assert_eq!("set KEY=***", mask_line("set KEY=\"secret\""));
+        assert_eq!("set KEY=***", mask_line("set KEY secret"));
+        assert_eq!("-set KEY=***", mask_line("-set KEY=secret"));
+        assert_eq!("set theme=none", mask_line("set theme=none"));
+        assert_eq!("did use $DID", mask_line("did use $DID"));
+    }
+
+    #[test]
+    fn parse_directive_skips_settings() {
+        assert!(parse_directive("set auto-confirm=true").is_none());
+        assert!(parse_directive("did use V4SGRU86Z58d6TV7PBUe6f").is_none());
+    }
+
+    #[test]
+    fn parse_directive_works_for_set_with_space() {
+        match parse_directive("set DID V4SGRU86Z58d6TV7PBUe6f") {
+            Some(BatchDirective::SetVariable(name, value)) => {
+                assert_eq!("DID", name);
+                assert_eq!("V4SGRU86Z58d6TV7PBUe6f", value);
+            }
+            _ => panic!("set directive expected"),
+        }
+    }
+
+    #[test]
+    fn parse_directive_skips_setting_with_variable_name() {
+        assert!(parse_directive("set theme=none").is_none());
+        assert!(matches!(
+            parse_directive("set theme dark"),
+            Some(BatchDirective::Setting("theme", "dark"))
+        ));
+        assert!(matches!(
+            parse_directive("set request-timeout 60"),
+            Some(BatchDirective::Setting("request-timeout", "60"))
+        ));
+    }
+
+    #[test]
+    fn parse_directive_works_for_unset_and_show() {
+        assert!(matches!(
+            parse_directive("unset DID"),
+            Some(BatchDirective::UnsetVariable("DID"))
+        ));
+        assert!(matches!(
+            parse_directive("show DID"),
+            Some(BatchDirective::ShowVariable(Some("DID")))
+        ));
+        assert!(matches!(
+            parse_directive("show"),
+            Some(BatchDirective::ShowVariable(None))
+        ));
+        assert!(parse_directive("show /home/file.txt").is_none());
+        assert!(parse_directive("unset").is_none());
+    }
+
+    #[test]
+    fn parse_directive_works_for_include() {
+        match parse_directive("include common/setup.txt") {
+            Some(BatchDirective::Include(path)) => assert_eq!("common/setup.txt", path),
+            _ => panic!("include directive expected"),
+        }
+        assert!(parse_directive("include").is_none());
+    }
+
+    #[test]
+    fn substitute_works() {
+        assert_eq!(
+            "ledger get-nym did=V4SGRU86Z58d6TV7PBUe6f",
+            substitute("ledger get-nym did=$DID", &variables())
+        );
+        assert_eq!(
+            "pool connect sandbox_pool",
+            substitute("pool connect ${POOL_NAME}_pool", &variables())
+        );
+        assert_eq!(
+            "did new seed=$DID",
+            substitute("did new seed=$$DID", &variables())
+        );
+    }
+
+    #[test]
+    fn substitute_works_for_braces() {
+        assert_eq!(
+            "ledger get-nym did=V4SGRU86Z58d6TV7PBUe6f",
+            substitute("ledger get-nym did={{DID}}", &variables())
+        );
+        assert_eq!(
+            "pool connect sandbox_pool",
+            substitute("pool connect {{ POOL_NAME }}_pool", &variables())
+        );
+        assert_eq!(
+            r#"ledger custom {"a":{"b":1}}"#,
+            substitute(r#"ledger custom {"a":{"b":1}}"#, &variables())
+        );
+        assert_eq!(
+            "did use {{UNKNOWN}}",
+            substitute("did use {{UNKNOWN}}", &variables())
+        );
+        assert_eq!("did use {{DID", substitute("did use {{DID", &variables()));
+    }
+
+    #[test]
+    fn substitute_keeps_undefined_variables() {
+        assert_eq!(
+            "did use $UNKNOWN",
+            substitute("did use $UNKNOWN", &variables())
+        );
+        assert_eq!("did use ${DID", substitute("did use ${DID", &variables()));
+        assert_eq!("did use $", substitute("did use $", &variables()));
+    }
+
+    #[test]
+    fn substitute_keeps_dollar_in_wallet_key() {
+        assert_eq!(
+            "wallet open mywallet key=pa$$word",
+            substitute("wallet open mywallet key=pa$$word", &variables())
+        );
+        assert_eq!(
+            "wallet create mywallet key=$ecret$",
+            substitute("wallet create mywallet key=$ecret$", &variables())
+        );
+        assert_eq!(
+            "wallet open mywallet key=$1V4SGRU86Z58d6TV7PBUe6f",
+            substitute("wallet open mywallet key=$1${DID}", &variables())
+        );
+    }
+}
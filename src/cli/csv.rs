@@ -0,0 +1,67 @@
+/// Splits a CSV line into fields. Fields may be quoted with `"` (a doubled quote inside
+/// a quoted field stands for a single one).
+pub fn parse_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+        .into_iter()
+        .map(|field| field.trim().to_string())
+        .collect()
+}
+
+/// Joins fields into a CSV line quoting them when needed.
+pub fn format_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_works() {
+        assert_eq!(vec!["a", "", "c"], parse_line("a, ,c"));
+    }
+
+    #[test]
+    fn parse_line_works_for_quoted_fields() {
+        assert_eq!(
+            vec!["a,b", "say \"hi\""],
+            parse_line(r#""a,b","say ""hi""""#)
+        );
+    }
+
+    #[test]
+    fn format_line_works() {
+        assert_eq!(
+            r#"a,"b,c","say ""hi""""#,
+            format_line(&["a", "b,c", "say \"hi\""])
+        );
+    }
+}
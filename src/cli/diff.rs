@@ -0,0 +1,93 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::utils::term::is_colored;
+
+use ansi_term::Color;
+
+#[derive(Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line based diff of two texts built from their longest common subsequence.
+pub fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<DiffLine<'a>> {
+    let before = before.lines().collect::<Vec<&str>>();
+    let after = after.lines().collect::<Vec<&str>>();
+
+    // lengths of common subsequences of the line suffixes
+    let mut lengths = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            lengths[i][j] = if before[i] == after[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() && j < after.len() {
+        if before[i] == after[j] {
+            diff.push(DiffLine::Same(before[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(before[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(after[j]));
+            j += 1;
+        }
+    }
+    diff.extend(before[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(after[j..].iter().map(|line| DiffLine::Added(line)));
+    diff
+}
+
+/// Prints the diff with removed lines in red and added lines in green.
+pub fn print_diff(before: &str, after: &str) {
+    for line in diff_lines(before, after) {
+        let (line, color) = match line {
+            DiffLine::Same(line) => (format!("  {}", line), None),
+            DiffLine::Removed(line) => (format!("- {}", line), Some(Color::Red)),
+            DiffLine::Added(line) => (format!("+ {}", line), Some(Color::Green)),
+        };
+        match color {
+            Some(color) if is_colored() => println!("{}", color.paint(line)),
+            _ => println!("{}", line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_lines_works() {
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nc\nd"),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lines_works_for_empty_text() {
+        assert_eq!(
+            diff_lines("", "a\nb"),
+            vec![DiffLine::Added("a"), DiffLine::Added("b")]
+        );
+        assert_eq!(diff_lines("a", "a"), vec![DiffLine::Same("a")]);
+    }
+}
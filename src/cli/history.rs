@@ -3,14 +3,14 @@ use std::fs::DirBuilder;
 
 use crate::utils::{environment::EnvironmentUtils, file::read_lines_from_file};
 
-const HISTORY_SIZE: usize = 100;
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
 const SECRET_DATA: [&str; 2] = [" seed=", " key="];
 
-pub fn load<T>(reader: &mut Interface<T>) -> Result<(), String>
+pub fn load<T>(reader: &mut Interface<T>, size: Option<usize>) -> Result<(), String>
 where
     T: Terminal,
 {
-    reader.set_history_size(HISTORY_SIZE);
+    reader.set_history_size(size.unwrap_or(DEFAULT_HISTORY_SIZE));
 
     let path = EnvironmentUtils::history_file_path();
 
@@ -0,0 +1,164 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use ansi_term::Style;
+
+const RULE_WIDTH: usize = 40;
+
+/// Renders the most common markdown constructs (headings, lists, quotes, code, emphasis and links)
+/// for reading in the terminal. Markup characters are removed, and if `styled` is set
+/// headings and emphasis are highlighted with terminal styles.
+pub fn render_markdown(text: &str, styled: bool) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(format!("    {}", line));
+            continue;
+        }
+
+        let rendered = if let Some(heading) = parse_heading(trimmed) {
+            let heading = render_inline(heading, styled);
+            if styled {
+                Style::new().bold().underline().paint(heading).to_string()
+            } else {
+                heading
+            }
+        } else if is_rule(trimmed) {
+            "─".repeat(RULE_WIDTH)
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            format!("{}• {}", indent, render_inline(item, styled))
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            format!("{}│ {}", indent, render_inline(quote.trim_start(), styled))
+        } else {
+            format!("{}{}", indent, render_inline(trimmed, styled))
+        };
+        lines.push(rendered);
+    }
+
+    lines.join("\n")
+}
+
+fn parse_heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&level) {
+        line[level..].strip_prefix(' ').map(str::trim)
+    } else {
+        None
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.replace(' ', "");
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|marker| line.chars().all(|c| c == *marker))
+}
+
+/// Renders emphasis (`**bold**`, `__bold__`, `*italic*`), inline code and links of a single line.
+fn render_inline(text: &str, styled: bool) -> String {
+    let mut rendered = String::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if let Some((inner, tail)) = ["**", "__"]
+            .iter()
+            .find_map(|marker| enclosed(rest, marker))
+        {
+            let inner = render_inline(inner, styled);
+            push_styled(&mut rendered, &inner, styled.then(|| Style::new().bold()));
+            rest = tail;
+        } else if let Some((inner, tail)) = enclosed(rest, "*") {
+            let inner = render_inline(inner, styled);
+            push_styled(&mut rendered, &inner, styled.then(|| Style::new().italic()));
+            rest = tail;
+        } else if let Some((inner, tail)) = enclosed(rest, "`") {
+            push_styled(&mut rendered, inner, styled.then(|| Style::new().dimmed()));
+            rest = tail;
+        } else if let Some((label, url, tail)) = link(rest) {
+            rendered.push_str(&render_inline(label, styled));
+            if label != url {
+                rendered.push_str(&format!(" ({})", url));
+            }
+            rest = tail;
+        } else {
+            let mut chars = rest.chars();
+            rendered.push(chars.next().unwrap());
+            rest = chars.as_str();
+        }
+    }
+
+    rendered
+}
+
+fn push_styled(rendered: &mut String, text: &str, style: Option<Style>) {
+    match style {
+        Some(style) => rendered.push_str(&style.paint(text).to_string()),
+        None => rendered.push_str(text),
+    }
+}
+
+/// Splits `<marker>inner<marker>tail` text. Markers surrounding whitespace are not emphasis.
+fn enclosed<'a>(text: &'a str, marker: &str) -> Option<(&'a str, &'a str)> {
+    let body = text.strip_prefix(marker)?;
+    let end = body.find(marker)?;
+    let inner = &body[..end];
+    if inner.is_empty() || inner.starts_with(' ') || inner.ends_with(' ') {
+        return None;
+    }
+    Some((inner, &body[end + marker.len()..]))
+}
+
+/// Splits `[label](url)tail` text.
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let body = text.strip_prefix('[')?;
+    let label_end = body.find("](")?;
+    let url_body = &body[label_end + 2..];
+    let url_end = url_body.find(')')?;
+    Some((
+        &body[..label_end],
+        &url_body[..url_end],
+        &url_body[url_end + 1..],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_markdown_works() {
+        let text = "# Agreement\n\nThe **author** must *accept* the `TAA`.\n- first\n  * nested\n> quoted\n---\nSee [terms](https://example.com/terms).";
+        assert_eq!(
+            "Agreement\n\nThe author must accept the TAA.\n• first\n  • nested\n│ quoted\n────────────────────────────────────────\nSee terms (https://example.com/terms).",
+            render_markdown(text, false)
+        );
+    }
+
+    #[test]
+    fn render_markdown_works_for_code_block() {
+        assert_eq!(
+            "text\n    **kept**",
+            render_markdown("text\n```\n**kept**\n```", false)
+        );
+    }
+
+    #[test]
+    fn render_markdown_works_for_plain_text() {
+        let text = "Indy transaction agreement 2 * 3 = 6 and snake_case_name";
+        assert_eq!(text, render_markdown(text, false));
+    }
+}
@@ -0,0 +1,12 @@
+//! Utilities used only by the CLI binary: terminal output, tables, batch scripts, history and timing.
+#[macro_use]
+pub mod logger;
+#[macro_use]
+pub mod term;
+pub mod batch;
+pub mod csv;
+pub mod diff;
+pub mod history;
+pub mod markdown;
+pub mod table;
+pub mod timing;
@@ -0,0 +1,227 @@
+use prettytable::{Cell, Row, Table};
+use term::{color, Attr};
+
+use crate::utils::{csv::format_line, file::write_file};
+
+pub fn print_list_table(rows: &[serde_json::Value], headers: &[(&str, &str)], empty_msg: &str) {
+    if rows.is_empty() {
+        return println_succ!("{}", empty_msg);
+    }
+
+    build_list_table(rows, headers).printstd();
+}
+
+pub fn format_list_table(rows: &[serde_json::Value], headers: &[(&str, &str)]) -> String {
+    build_list_table(rows, headers).to_string()
+}
+
+fn build_list_table(rows: &[serde_json::Value], headers: &[(&str, &str)]) -> Table {
+    let mut table = Table::new();
+
+    print_header(&mut table, headers);
+
+    for row in rows {
+        print_row(&mut table, row, headers);
+    }
+
+    table
+}
+
+pub fn print_table(row: &serde_json::Value, headers: &[(&str, &str)]) {
+    let mut table = Table::new();
+
+    print_header(&mut table, headers);
+
+    print_row(&mut table, row, headers);
+
+    table.printstd();
+}
+
+pub fn print_header(table: &mut Table, headers: &[(&str, &str)]) {
+    let tittles = headers
+        .iter()
+        .clone()
+        .map(|&(_, ref header)| {
+            Cell::new(header)
+                .with_style(Attr::Bold)
+                .with_style(Attr::ForegroundColor(color::GREEN))
+        })
+        .collect::<Vec<Cell>>();
+
+    table.add_row(Row::new(tittles));
+}
+
+pub fn print_row(table: &mut Table, row: &serde_json::Value, headers: &[(&str, &str)]) {
+    let columns = headers
+        .iter()
+        .clone()
+        .map(|&(ref key, _)| {
+            let value = format_cell(&row[key]).unwrap_or_else(|| "-".to_string());
+            Cell::new(&value)
+        })
+        .collect::<Vec<Cell>>();
+    table.add_row(Row::new(columns));
+}
+
+fn format_cell(value: &serde_json::Value) -> Option<String> {
+    if value.is_string() {
+        return Some(value.as_str().unwrap().to_string());
+    }
+    if value.is_i64() {
+        return Some(value.as_i64().unwrap().to_string());
+    }
+    if value.is_boolean() {
+        return Some(value.as_bool().unwrap().to_string());
+    }
+    if value.is_array() {
+        return Some(
+            value
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+    }
+    if value.is_object() {
+        let value = value
+            .as_object()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| format!("{}:{}", key, value))
+            .collect::<Vec<String>>()
+            .join(",");
+        return Some(format!("{{{}}}", value));
+    }
+    None
+}
+
+/// Format of the file list commands write their rows into instead of printing the table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Result<ExportFormat, String> {
+        match format.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(format!(
+                "Unsupported format \"{}\". One of: csv, json.",
+                format
+            )),
+        }
+    }
+}
+
+/// Prints the rows as a table, or writes them into `out` file in the given format (JSON by default).
+pub fn print_or_export_list_table(
+    rows: &[serde_json::Value],
+    headers: &[(&str, &str)],
+    empty_msg: &str,
+    out: Option<&str>,
+    format: Option<&str>,
+) -> Result<(), ()> {
+    let out = match (out, format) {
+        (Some(out), _) => out,
+        (None, Some(_)) => {
+            println_err!("\"format\" param can be used only together with \"out\"");
+            return Err(());
+        }
+        (None, None) => {
+            print_list_table(rows, headers, empty_msg);
+            return Ok(());
+        }
+    };
+    let format = format
+        .map(ExportFormat::parse)
+        .transpose()
+        .map_err(|err| println_err!("{}", err))?
+        .unwrap_or(ExportFormat::Json);
+
+    write_file(out, &export_list_table(rows, headers, format))
+        .map_err(|err| println_err!("{}", err))?;
+    println_succ!("{} row(s) have been written into \"{}\"", rows.len(), out);
+    Ok(())
+}
+
+/// Renders the columns listed in `headers` as CSV (a header line with the column keys first) or JSON array.
+pub fn export_list_table(
+    rows: &[serde_json::Value],
+    headers: &[(&str, &str)],
+    format: ExportFormat,
+) -> String {
+    match format {
+        ExportFormat::Csv => {
+            let mut lines = vec![format_line(
+                &headers.iter().map(|(key, _)| *key).collect::<Vec<&str>>(),
+            )];
+            for row in rows {
+                let values = headers
+                    .iter()
+                    .map(|(key, _)| format_cell(&row[key]).unwrap_or_default())
+                    .collect::<Vec<String>>();
+                lines.push(format_line(
+                    &values.iter().map(String::as_str).collect::<Vec<&str>>(),
+                ));
+            }
+            lines.join("\n")
+        }
+        ExportFormat::Json => {
+            let rows = rows
+                .iter()
+                .map(|row| {
+                    headers
+                        .iter()
+                        .map(|(key, _)| (key.to_string(), row[key].clone()))
+                        .collect::<serde_json::Map<String, serde_json::Value>>()
+                })
+                .collect::<Vec<serde_json::Map<String, serde_json::Value>>>();
+            serde_json::to_string_pretty(&rows).unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_list_table_works_for_csv() {
+        let rows = vec![
+            json!({"did": "VsKV7grR1BUE29mG2Fm2kX", "metadata": "Issuer, main", "extra": 1}),
+            json!({"did": "V4SGRU86Z58d6TV7PBUe6f"}),
+        ];
+        assert_eq!(
+            "did,metadata\nVsKV7grR1BUE29mG2Fm2kX,\"Issuer, main\"\nV4SGRU86Z58d6TV7PBUe6f,",
+            export_list_table(
+                &rows,
+                &[("did", "Did"), ("metadata", "Metadata")],
+                ExportFormat::Csv
+            )
+        );
+    }
+
+    #[test]
+    fn export_list_table_works_for_json() {
+        let rows = vec![json!({"did": "VsKV7grR1BUE29mG2Fm2kX", "extra": 1})];
+        let exported = export_list_table(
+            &rows,
+            &[("did", "Did"), ("metadata", "Metadata")],
+            ExportFormat::Json,
+        );
+        assert_eq!(
+            json!([{"did": "VsKV7grR1BUE29mG2Fm2kX", "metadata": null}]),
+            serde_json::from_str::<serde_json::Value>(&exported).unwrap()
+        );
+    }
+
+    #[test]
+    fn export_format_parse_works() {
+        assert_eq!(ExportFormat::Csv, ExportFormat::parse("CSV").unwrap());
+        ExportFormat::parse("xml").unwrap_err();
+    }
+}
@@ -0,0 +1,178 @@
+use ansi_term::{Color, Style};
+use atty;
+use std::{
+    env,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[macro_export]
+macro_rules! println_err {
+    ($($arg:tt)*) => (
+        if $crate::utils::term::is_term() {
+            error!($($arg)*);
+            println!("{}", $crate::utils::term::paint($crate::utils::term::MessageKind::Error, format!($($arg)*)))
+        } else {
+            println!($($arg)*)
+        }
+    )
+}
+
+#[macro_export]
+macro_rules! println_succ {
+    ($($arg:tt)*) => (
+        if $crate::utils::term::is_term() {
+            trace!($($arg)*);
+            println!("{}", $crate::utils::term::paint($crate::utils::term::MessageKind::Success, format!($($arg)*)))
+        } else {
+            println!($($arg)*)
+        }
+    )
+}
+
+#[macro_export]
+macro_rules! println_warn {
+    ($($arg:tt)*) => (
+        if $crate::utils::term::is_term() {
+            println!("{}", $crate::utils::term::paint($crate::utils::term::MessageKind::Warning, format!($($arg)*)))
+        } else {
+            trace!($($arg)*);
+            println!($($arg)*)
+        }
+    )
+}
+
+#[macro_export]
+macro_rules! println_acc {
+    ($($arg:tt)*) => (
+       if $crate::utils::term::is_term() {
+            trace!($($arg)*);
+           println!("{}", $crate::utils::term::paint($crate::utils::term::MessageKind::Accent, format!($($arg)*)))
+       } else {
+           println!($($arg)*)
+       }
+    )
+}
+
+pub fn is_term() -> bool {
+    atty::is(atty::Stream::Stdout)
+}
+
+/// Whether output may contain ANSI escape sequences (colors, styles and redrawn progress lines):
+/// stdout is a terminal and `none` theme is not selected.
+pub fn is_colored() -> bool {
+    is_term() && Theme::current() != Theme::None
+}
+
+/// Kind of the message printed by `println_err!`, `println_succ!`, `println_warn!` and `println_acc!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Error,
+    Success,
+    Warning,
+    Accent,
+}
+
+/// Colors of CLI output. `None` prints plain text, e.g. for CI logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+    None,
+}
+
+const THEME_UNSET: u8 = 0;
+
+static THEME: AtomicU8 = AtomicU8::new(THEME_UNSET);
+
+impl Theme {
+    pub const VALUES: [&'static str; 3] = ["dark", "light", "none"];
+
+    pub fn parse(value: &str) -> Result<Theme, String> {
+        match value {
+            "dark" => Ok(Theme::Dark),
+            "light" => Ok(Theme::Light),
+            "none" => Ok(Theme::None),
+            _ => Err(format!(
+                "Unknown theme \"{}\". One of: {}",
+                value,
+                Self::VALUES.join(", ")
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::None => "none",
+        }
+    }
+
+    /// Theme set with `Theme::set`. Defaults to `none` if `NO_COLOR` environment variable is set, `dark` otherwise.
+    pub fn current() -> Theme {
+        match THEME.load(Ordering::Relaxed) {
+            1 => Theme::Dark,
+            2 => Theme::Light,
+            3 => Theme::None,
+            _ if env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty()) => Theme::None,
+            _ => Theme::Dark,
+        }
+    }
+
+    pub fn set(theme: Theme) {
+        let value = match theme {
+            Theme::Dark => 1,
+            Theme::Light => 2,
+            Theme::None => 3,
+        };
+        THEME.store(value, Ordering::Relaxed);
+    }
+
+    fn style(&self, kind: MessageKind) -> Option<Style> {
+        match (self, kind) {
+            (Theme::None, _) => None,
+            (Theme::Dark, MessageKind::Error) => Some(Color::Red.bold()),
+            (Theme::Dark, MessageKind::Success) => Some(Color::Green.bold()),
+            (Theme::Dark, MessageKind::Warning) => Some(Color::Yellow.bold()),
+            // yellow and light green are hard to read on light background
+            (Theme::Light, MessageKind::Error) => Some(Color::Fixed(124).bold()),
+            (Theme::Light, MessageKind::Success) => Some(Color::Fixed(28).bold()),
+            (Theme::Light, MessageKind::Warning) => Some(Color::Fixed(130).bold()),
+            (_, MessageKind::Accent) => Some(Style::new().bold()),
+        }
+    }
+}
+
+/// Paints the message according to the current theme.
+pub fn paint(kind: MessageKind, message: String) -> String {
+    match Theme::current().style(kind) {
+        Some(style) => style.paint(message).to_string(),
+        None => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_works_for_themes() {
+        Theme::set(Theme::Dark);
+        assert_eq!(
+            Color::Red.bold().paint("error").to_string(),
+            paint(MessageKind::Error, "error".to_string())
+        );
+
+        Theme::set(Theme::None);
+        assert_eq!("error", paint(MessageKind::Error, "error".to_string()));
+
+        Theme::set(Theme::Dark);
+    }
+
+    #[test]
+    fn theme_parse_works() {
+        assert_eq!(Theme::Light, Theme::parse("light").unwrap());
+        assert_eq!("none", Theme::parse("none").unwrap().name());
+        Theme::parse("blue").unwrap_err();
+    }
+}
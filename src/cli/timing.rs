@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+/// Phase covering the rest of a ledger command after the reply was received.
+const PARSE_PHASE: &str = "parse";
+
+/// Wall-clock timing of the command being executed.
+/// Ledger commands split it into build/sign/submit/parse phases by calling `mark`.
+pub struct Timing {
+    started: Instant,
+    last_mark: Instant,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timing {
+    pub fn start() -> Timing {
+        let now = Instant::now();
+        Timing {
+            started: now,
+            last_mark: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Closes the phase which started at the previous mark (or at the command start).
+    pub fn mark(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push((phase, now - self.last_mark));
+        self.last_mark = now;
+    }
+
+    pub fn finish(mut self, command: String, success: bool) -> CommandTiming {
+        let finished = Instant::now();
+        if !self.phases.is_empty() {
+            self.phases.push((PARSE_PHASE, finished - self.last_mark));
+        }
+        CommandTiming {
+            command,
+            success,
+            elapsed_ms: to_millis(finished - self.started),
+            phases: self
+                .phases
+                .into_iter()
+                .map(|(phase, duration)| PhaseTiming {
+                    phase,
+                    elapsed_ms: to_millis(duration),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub success: bool,
+    pub elapsed_ms: f64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+impl CommandTiming {
+    pub fn print(&self) {
+        let phases = self
+            .phases
+            .iter()
+            .map(|phase| format!("{} {}", phase.phase, format_millis(phase.elapsed_ms)))
+            .collect::<Vec<String>>();
+        if phases.is_empty() {
+            println!("Elapsed: {}", format_millis(self.elapsed_ms));
+        } else {
+            println!(
+                "Elapsed: {} ({})",
+                format_millis(self.elapsed_ms),
+                phases.join(", ")
+            );
+        }
+    }
+}
+
+fn to_millis(duration: Duration) -> f64 {
+    (duration.as_micros() as f64) / 1000.0
+}
+
+pub fn format_millis(millis: f64) -> String {
+    if millis >= 1000.0 {
+        format!("{:.3} s", millis / 1000.0)
+    } else {
+        format!("{:.1} ms", millis)
+    }
+}
+
+/// Writes the JSON timing report requested by `--profile` option.
+pub fn write_profile(file: &str, timings: &[CommandTiming]) -> Result<(), String> {
+    let total_ms: f64 = timings.iter().map(|timing| timing.elapsed_ms).sum();
+    let report = json!({
+        "totalMs": total_ms,
+        "commands": timings,
+    });
+    crate::utils::file::write_file(
+        file,
+        &serde_json::to_string_pretty(&report).map_err(|err| err.to_string())?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_millis_works() {
+        assert_eq!("12.3 ms", format_millis(12.345));
+        assert_eq!("1.500 s", format_millis(1500.0));
+    }
+
+    #[test]
+    fn finish_works_without_phases() {
+        let timing = Timing::start().finish("did list".to_string(), true);
+        assert!(timing.phases.is_empty());
+    }
+
+    #[test]
+    fn finish_works_for_ledger_phases() {
+        let mut timing = Timing::start();
+        timing.mark("build");
+        timing.mark("sign");
+        timing.mark("submit");
+        let timing = timing.finish("ledger nym".to_string(), false);
+
+        let phases: Vec<&str> = timing.phases.iter().map(|phase| phase.phase).collect();
+        assert_eq!(vec!["build", "sign", "submit", "parse"], phases);
+
+        let phases_ms: f64 = timing.phases.iter().map(|phase| phase.elapsed_ms).sum();
+        assert!(phases_ms <= timing.elapsed_ms + 0.001);
+    }
+}
@@ -3,12 +3,23 @@ use unescape::unescape;
 use indy_utils::did::DidValue;
 use std::{
     cell::RefCell,
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::{Debug, Formatter},
     rc::Rc,
+    time::Duration,
 };
 
-use crate::tools::{pool::Pool, wallet::Wallet};
+use crate::{
+    error::{report_error, take_reported_error, CommandError, ErrorCode},
+    tools::{
+        pool::{
+            auto_refresh::{PoolAutoRefresh, RefreshUpdate},
+            Pool,
+        },
+        wallet::Wallet,
+    },
+    utils::timing::{self, CommandTiming, Timing},
+};
 use linefeed::{Interface, ReadResult};
 
 #[derive(Debug)]
@@ -18,6 +29,17 @@ pub struct ParamMetadata {
     is_deferred: bool,
     help: &'static str,
     dynamic_completion_type: Option<DynamicCompletionType>,
+    secret_policy: Option<SecretPolicy>,
+}
+
+/// Policy applied to the value of a deferred param entered interactively:
+/// the value is checked and requested twice for confirmation.
+#[derive(Debug, Clone, Copy)]
+pub struct SecretPolicy {
+    /// Whether the policy must be applied according to the rest of the params.
+    pub is_required: fn(&CommandParams) -> bool,
+    /// Check of the entered value, returns the reason of rejection.
+    pub check: fn(&str) -> Result<(), String>,
 }
 
 impl ParamMetadata {
@@ -34,6 +56,7 @@ impl ParamMetadata {
             is_deferred,
             help,
             dynamic_completion_type,
+            secret_policy: None,
         }
     }
 
@@ -52,6 +75,10 @@ impl ParamMetadata {
     pub fn help(&self) -> &'static str {
         self.help
     }
+
+    pub fn secret_policy(&self) -> Option<SecretPolicy> {
+        self.secret_policy
+    }
 }
 
 // Turn off "error: field is never read: `dynamic_completion_type`" warning
@@ -63,6 +90,7 @@ pub struct CommandMetadata {
     main_param: Option<ParamMetadata>,
     params: Vec<ParamMetadata>,
     examples: Vec<&'static str>,
+    preconditions: Vec<Precondition>,
     dynamic_completion_type: Option<DynamicCompletionType>,
 }
 
@@ -74,6 +102,7 @@ impl CommandMetadata {
             main_param: None,
             params: Vec::new(),
             examples: Vec::new(),
+            preconditions: Vec::new(),
             dynamic_completion_type: None,
         }
     }
@@ -97,6 +126,76 @@ impl CommandMetadata {
     pub fn examples(&self) -> &[&'static str] {
         self.examples.as_slice()
     }
+
+    pub fn preconditions(&self) -> &[Precondition] {
+        self.preconditions.as_slice()
+    }
+}
+
+/// Session state a command relies on: displayed by the command help and used
+/// to complete the example sequences of command groups with setup commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precondition {
+    OpenedWallet,
+    ActiveDid,
+    ConnectedPool,
+}
+
+impl Precondition {
+    pub const ALL: [Precondition; 3] = [
+        Precondition::OpenedWallet,
+        Precondition::ActiveDid,
+        Precondition::ConnectedPool,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Precondition::OpenedWallet => "opened wallet",
+            Precondition::ActiveDid => "active DID",
+            Precondition::ConnectedPool => "connected pool",
+        }
+    }
+
+    /// Returns the wallet, DID or pool satisfying the precondition in the current session.
+    pub fn current_value(&self, ctx: &CommandContext) -> Option<String> {
+        match self {
+            Precondition::OpenedWallet => ctx.get_wallet().map(|wallet| wallet.name.to_string()),
+            Precondition::ActiveDid => ctx.get_did().map(|did| did.to_string()),
+            Precondition::ConnectedPool => ctx.get_pool().map(|pool| pool.name.to_string()),
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Precondition::OpenedWallet => "open a wallet with \"wallet open <wallet> key\"",
+            Precondition::ActiveDid => "select a DID with \"did use <did>\"",
+            Precondition::ConnectedPool => "connect to a pool with \"pool connect <pool>\"",
+        }
+    }
+
+    /// Commands bringing a fresh session into the state required by the precondition.
+    pub fn setup_commands(&self) -> &'static [&'static str] {
+        match self {
+            Precondition::OpenedWallet => &["wallet create wallet1 key", "wallet open wallet1 key"],
+            Precondition::ActiveDid => &[
+                "did new seed=000000000000000000000000Trustee1",
+                "did use V4SGRU86Z58d6TV7PBUe6f",
+            ],
+            Precondition::ConnectedPool => &[
+                "pool create pool1 gen_txn_file=/home/pool_genesis_transactions",
+                "pool connect pool1",
+            ],
+        }
+    }
+
+    /// Group and name of the command putting the session into the required state.
+    fn established_by(&self) -> (&'static str, &'static str) {
+        match self {
+            Precondition::OpenedWallet => ("wallet", "open"),
+            Precondition::ActiveDid => ("did", "use"),
+            Precondition::ConnectedPool => ("pool", "connect"),
+        }
+    }
 }
 
 pub struct CommandMetadataBuilder {
@@ -105,6 +204,7 @@ pub struct CommandMetadataBuilder {
     main_param: Option<ParamMetadata>,
     params: Vec<ParamMetadata>,
     examples: Vec<&'static str>,
+    preconditions: Vec<Precondition>,
     dynamic_completion_type: Option<DynamicCompletionType>,
 }
 
@@ -117,6 +217,16 @@ impl CommandMetadataBuilder {
         self.main_param = Some(ParamMetadata::new(name, false, false, help, None));
         self
     }
+
+    /// Main param which can be omitted. A first word looking like `name=value` is not taken as its value.
+    pub fn add_optional_main_param(
+        mut self,
+        name: &'static str,
+        help: &'static str,
+    ) -> CommandMetadataBuilder {
+        self.main_param = Some(ParamMetadata::new(name, true, false, help, None));
+        self
+    }
     pub fn add_main_param_with_dynamic_completion(
         mut self,
         name: &'static str,
@@ -197,6 +307,18 @@ impl CommandMetadataBuilder {
         self
     }
 
+    pub fn add_required_deferred_param_with_policy(
+        mut self,
+        name: &'static str,
+        help: &'static str,
+        secret_policy: SecretPolicy,
+    ) -> CommandMetadataBuilder {
+        let mut param = ParamMetadata::new(name, false, true, help, None);
+        param.secret_policy = Some(secret_policy);
+        self.params.push(param);
+        self
+    }
+
     pub fn add_optional_deferred_param(
         mut self,
         name: &'static str,
@@ -212,6 +334,11 @@ impl CommandMetadataBuilder {
         self
     }
 
+    pub fn add_precondition(mut self, precondition: Precondition) -> CommandMetadataBuilder {
+        self.preconditions.push(precondition);
+        self
+    }
+
     pub fn finalize(self) -> CommandMetadata {
         CommandMetadata {
             name: self.name,
@@ -219,6 +346,7 @@ impl CommandMetadataBuilder {
             main_param: self.main_param,
             params: self.params,
             examples: self.examples,
+            preconditions: self.preconditions,
             dynamic_completion_type: self.dynamic_completion_type,
         }
     }
@@ -227,8 +355,10 @@ impl CommandMetadataBuilder {
 pub struct CommandContext {
     main_prompt: RefCell<String>,
     sub_prompts: RefCell<BTreeMap<usize, String>>,
+    prompt_template: RefCell<Option<String>>,
     is_exit: RefCell<bool>,
     pool: RefCell<Option<Rc<Pool>>>,
+    pool_auto_refresh: RefCell<Option<PoolAutoRefresh>>,
     wallet: RefCell<Option<Rc<Wallet>>>,
     did: RefCell<Option<Rc<DidValue>>>,
     int_values: RefCell<HashMap<&'static str, i32>>,
@@ -236,6 +366,20 @@ pub struct CommandContext {
     string_values: RefCell<HashMap<&'static str, String>>,
     taa_acceptance_mechanism: RefCell<String>,
     is_batch_mode: RefCell<bool>,
+    is_auto_confirm: RefCell<bool>,
+    is_trace_requests: RefCell<bool>,
+    is_txn_journal: RefCell<bool>,
+    is_json_errors: RefCell<bool>,
+    is_show_timing: RefCell<bool>,
+    timing: RefCell<Option<Timing>>,
+    profile: RefCell<Option<(String, Vec<CommandTiming>)>>,
+    last_error: RefCell<Option<CommandError>>,
+    variables: RefCell<BTreeMap<String, String>>,
+    schema_names: RefCell<BTreeSet<String>>,
+    role_titles: RefCell<BTreeMap<String, String>>,
+    txn_titles: RefCell<BTreeMap<String, String>>,
+    builtin_commands: RefCell<HashSet<(&'static str, &'static str)>>,
+    plugin_commands: RefCell<HashMap<&'static str, BTreeMap<&'static str, Rc<Command>>>>,
 }
 
 impl Debug for CommandContext {
@@ -244,12 +388,21 @@ impl Debug for CommandContext {
             &json!({
                 "main_prompt": self.main_prompt,
                 "sub_prompts": self.sub_prompts,
+                "prompt_template": self.prompt_template,
                 "is_exit": self.is_exit,
                 "int_values": self.int_values,
                 "uint_values": self.uint_values,
                 "string_values": self.string_values,
                 "taa_acceptance_mechanism": self.taa_acceptance_mechanism,
                 "is_batch_mode": self.is_batch_mode,
+                "is_auto_confirm": self.is_auto_confirm,
+                "is_trace_requests": self.is_trace_requests,
+                "is_txn_journal": self.is_txn_journal,
+                "is_json_errors": self.is_json_errors,
+                "is_show_timing": self.is_show_timing,
+                "variables": self.variables,
+                "role_titles": self.role_titles,
+                "txn_titles": self.txn_titles,
             })
             .to_string(),
         )
@@ -261,8 +414,10 @@ impl CommandContext {
         CommandContext {
             main_prompt: RefCell::new("indy-cli-rs".to_owned()),
             sub_prompts: RefCell::new(BTreeMap::new()),
+            prompt_template: RefCell::new(None),
             is_exit: RefCell::new(false),
             pool: RefCell::new(None),
+            pool_auto_refresh: RefCell::new(None),
             wallet: RefCell::new(None),
             did: RefCell::new(None),
             int_values: RefCell::new(HashMap::new()),
@@ -270,6 +425,20 @@ impl CommandContext {
             string_values: RefCell::new(HashMap::new()),
             taa_acceptance_mechanism: RefCell::new(String::new()),
             is_batch_mode: RefCell::new(false),
+            is_auto_confirm: RefCell::new(false),
+            is_trace_requests: RefCell::new(false),
+            is_txn_journal: RefCell::new(false),
+            is_json_errors: RefCell::new(false),
+            is_show_timing: RefCell::new(false),
+            timing: RefCell::new(None),
+            profile: RefCell::new(None),
+            last_error: RefCell::new(None),
+            variables: RefCell::new(BTreeMap::new()),
+            schema_names: RefCell::new(BTreeSet::new()),
+            role_titles: RefCell::new(BTreeMap::new()),
+            txn_titles: RefCell::new(BTreeMap::new()),
+            builtin_commands: RefCell::new(HashSet::new()),
+            plugin_commands: RefCell::new(HashMap::new()),
         }
     }
 
@@ -285,7 +454,21 @@ impl CommandContext {
         }
     }
 
+    pub fn set_prompt_template(&self, template: Option<String>) {
+        *self.prompt_template.borrow_mut() = template;
+    }
+
+    pub fn get_prompt_template(&self) -> Option<String> {
+        self.prompt_template.borrow().clone()
+    }
+
+    /// Builds the prompt from the template if it is set (`{pool}`, `{wallet}`, `{did}` and `{prompt}`
+    /// placeholders are replaced), otherwise joins the connected pool, the opened wallet and the active DID with the main prompt.
     pub fn get_prompt(&self) -> String {
+        if let Some(template) = self.prompt_template.borrow().as_ref() {
+            return self.render_prompt_template(template);
+        }
+
         let mut prompt = String::new();
 
         for (_key, value) in self.sub_prompts.borrow().iter() {
@@ -298,6 +481,30 @@ impl CommandContext {
         prompt
     }
 
+    fn render_prompt_template(&self, template: &str) -> String {
+        let pool = self
+            .get_pool()
+            .map(|pool| pool.name.to_string())
+            .unwrap_or_default();
+        let wallet = self
+            .get_wallet()
+            .map(|wallet| match wallet.profile {
+                Some(ref profile) => format!("{}/{}", wallet.name, profile),
+                None => wallet.name.to_string(),
+            })
+            .unwrap_or_default();
+        let did = self
+            .get_did()
+            .map(|did| did.to_string())
+            .unwrap_or_default();
+
+        template
+            .replace("{pool}", &pool)
+            .replace("{wallet}", &wallet)
+            .replace("{did}", &did)
+            .replace("{prompt}", &self.main_prompt.borrow())
+    }
+
     pub fn set_exit(&self) {
         *self.is_exit.borrow_mut() = true;
     }
@@ -329,6 +536,25 @@ impl CommandContext {
         self.pool.borrow().clone()
     }
 
+    pub fn set_pool_auto_refresh(&self, value: Option<PoolAutoRefresh>) {
+        self.pool_auto_refresh.replace(value);
+    }
+
+    /// Returns the name of the pool refreshed in background and the refresh interval.
+    pub fn get_pool_auto_refresh(&self) -> Option<(String, Duration)> {
+        self.pool_auto_refresh
+            .borrow()
+            .as_ref()
+            .map(|auto_refresh| (auto_refresh.pool_name.to_string(), auto_refresh.interval))
+    }
+
+    pub fn take_pool_auto_refresh_update(&self) -> Option<Result<RefreshUpdate, String>> {
+        self.pool_auto_refresh
+            .borrow()
+            .as_ref()
+            .and_then(|auto_refresh| auto_refresh.take_update())
+    }
+
     pub fn set_did(&self, value: Option<DidValue>) {
         match value {
             Some(value) => self.did.replace(Some(Rc::new(value))),
@@ -383,6 +609,185 @@ impl CommandContext {
     pub fn is_batch_mode(&self) -> bool {
         *self.is_batch_mode.borrow()
     }
+
+    pub fn set_auto_confirm(&self, auto_confirm: bool) {
+        *self.is_auto_confirm.borrow_mut() = auto_confirm;
+    }
+
+    pub fn is_auto_confirm(&self) -> bool {
+        *self.is_auto_confirm.borrow()
+    }
+
+    pub fn set_trace_requests(&self, trace_requests: bool) {
+        *self.is_trace_requests.borrow_mut() = trace_requests;
+    }
+
+    pub fn is_trace_requests(&self) -> bool {
+        *self.is_trace_requests.borrow()
+    }
+
+    pub fn set_txn_journal(&self, txn_journal: bool) {
+        *self.is_txn_journal.borrow_mut() = txn_journal;
+    }
+
+    pub fn is_txn_journal(&self) -> bool {
+        *self.is_txn_journal.borrow()
+    }
+
+    pub fn set_json_errors(&self, json_errors: bool) {
+        *self.is_json_errors.borrow_mut() = json_errors;
+    }
+
+    pub fn is_json_errors(&self) -> bool {
+        *self.is_json_errors.borrow()
+    }
+
+    pub fn set_show_timing(&self, show_timing: bool) {
+        *self.is_show_timing.borrow_mut() = show_timing;
+    }
+
+    pub fn is_show_timing(&self) -> bool {
+        *self.is_show_timing.borrow()
+    }
+
+    /// Enables collection of command timings written as JSON report into the file.
+    pub fn set_profile_file(&self, file: &str) {
+        *self.profile.borrow_mut() = Some((file.to_string(), Vec::new()));
+    }
+
+    /// Marks the end of the phase of the command being executed (e.g. `build`, `sign`, `submit`).
+    pub fn mark_timing_phase(&self, phase: &'static str) {
+        if let Some(timing) = self.timing.borrow_mut().as_mut() {
+            timing.mark(phase);
+        }
+    }
+
+    fn start_timing(&self) {
+        if self.is_show_timing() || self.profile.borrow().is_some() {
+            *self.timing.borrow_mut() = Some(Timing::start());
+        }
+    }
+
+    fn finish_timing(&self, command: String, success: bool) {
+        let timing = match self.timing.borrow_mut().take() {
+            Some(timing) => timing.finish(command, success),
+            None => return,
+        };
+
+        if self.is_show_timing() {
+            timing.print();
+        }
+
+        if let Some((ref file, ref mut timings)) = *self.profile.borrow_mut() {
+            timings.push(timing);
+            // the report is rewritten after every command so it is complete whenever CLI exits
+            if let Err(err) = timing::write_profile(file, timings) {
+                println_warn!("Unable to write timing report: {}", err);
+            }
+        }
+    }
+
+    pub fn set_last_error(&self, error: Option<CommandError>) {
+        *self.last_error.borrow_mut() = error;
+    }
+
+    /// Returns the failure of the last executed command.
+    pub fn get_last_error(&self) -> Option<CommandError> {
+        self.last_error.borrow().clone()
+    }
+
+    pub fn set_variable(&self, name: &str, value: &str) {
+        self.variables
+            .borrow_mut()
+            .insert(name.to_string(), value.to_string());
+    }
+
+    pub fn unset_variable(&self, name: &str) -> Option<String> {
+        self.variables.borrow_mut().remove(name)
+    }
+
+    pub fn get_variables(&self) -> BTreeMap<String, String> {
+        self.variables.borrow().clone()
+    }
+
+    pub fn add_schema_name(&self, name: &str) {
+        self.schema_names.borrow_mut().insert(name.to_string());
+    }
+
+    pub fn get_schema_names(&self) -> Vec<String> {
+        self.schema_names.borrow().iter().cloned().collect()
+    }
+
+    pub fn clear_schema_names(&self) {
+        self.schema_names.borrow_mut().clear();
+    }
+
+    pub fn set_role_title(&self, role: &str, title: &str) {
+        self.role_titles
+            .borrow_mut()
+            .insert(role.to_string(), title.to_string());
+    }
+
+    pub fn get_role_titles(&self) -> BTreeMap<String, String> {
+        self.role_titles.borrow().clone()
+    }
+
+    pub fn set_txn_title(&self, txn_type: &str, title: &str) {
+        self.txn_titles
+            .borrow_mut()
+            .insert(txn_type.to_string(), title.to_string());
+    }
+
+    pub fn get_txn_titles(&self) -> BTreeMap<String, String> {
+        self.txn_titles.borrow().clone()
+    }
+
+    /// Registers command created at runtime (e.g. from a plugin definition) in the group.
+    /// Checks that the plugin command does not conflict with the builtin and already loaded commands.
+    pub fn check_plugin_command(&self, group: &str, name: &str) -> Result<(), String> {
+        let is_builtin = self
+            .builtin_commands
+            .borrow()
+            .iter()
+            .any(|(builtin_group, builtin_name)| *builtin_group == group && *builtin_name == name);
+        if is_builtin {
+            return Err(format!("Command \"{} {}\" already exists", group, name));
+        }
+        if self.get_plugin_command(group, name).is_some() {
+            return Err(format!(
+                "Command \"{} {}\" has already been loaded from a plugin",
+                group, name
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn add_plugin_command(&self, group: &'static str, command: Command) -> Result<(), String> {
+        let name = command.metadata().name();
+        self.check_plugin_command(group, name)?;
+        self.plugin_commands
+            .borrow_mut()
+            .entry(group)
+            .or_default()
+            .insert(name, Rc::new(command));
+        Ok(())
+    }
+
+    pub fn get_plugin_command(&self, group: &str, name: &str) -> Option<Rc<Command>> {
+        self.plugin_commands
+            .borrow()
+            .get(group)
+            .and_then(|commands| commands.get(name))
+            .cloned()
+    }
+
+    pub fn get_plugin_commands(&self, group: &str) -> Vec<Rc<Command>> {
+        self.plugin_commands
+            .borrow()
+            .get(group)
+            .map(|commands| commands.values().cloned().collect())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -390,16 +795,21 @@ pub enum DynamicCompletionType {
     Wallet,
     Pool,
     Did,
+    /// Names of schemas published during the session.
+    Schema,
+    /// Aliases of nodes of the connected pool.
+    Node,
 }
 
 pub type CommandParams = HashMap<&'static str, String>;
 pub type CommandResult = Result<(), ()>;
 pub type CommandExecute = fn(&CommandContext, &CommandParams) -> CommandResult;
+pub type DynamicCommandExecute = Box<dyn Fn(&CommandContext, &CommandParams) -> CommandResult>;
 pub type CommandCleanup = fn(&CommandContext) -> ();
 
 pub struct Command {
     metadata: CommandMetadata,
-    executor: CommandExecute,
+    executor: DynamicCommandExecute,
     cleaner: Option<CommandCleanup>,
 }
 
@@ -411,11 +821,20 @@ impl Command {
     ) -> Command {
         Command {
             metadata,
-            executor,
+            executor: Box::new(executor),
             cleaner,
         }
     }
 
+    /// Creates command which is not known at compile time, e.g. described by a plugin definition.
+    pub fn new_dynamic(metadata: CommandMetadata, executor: DynamicCommandExecute) -> Command {
+        Command {
+            metadata,
+            executor,
+            cleaner: None,
+        }
+    }
+
     pub fn metadata(&self) -> &CommandMetadata {
         &self.metadata
     }
@@ -435,11 +854,22 @@ impl Command {
 pub struct CommandGroupMetadata {
     name: &'static str,
     help: &'static str,
+    examples: Vec<&'static str>,
 }
 
 impl CommandGroupMetadata {
     pub fn new(name: &'static str, help: &'static str) -> CommandGroupMetadata {
-        CommandGroupMetadata { name, help }
+        CommandGroupMetadata {
+            name,
+            help,
+            examples: Vec::new(),
+        }
+    }
+
+    /// Adds a line of the example command sequence shown by `help --examples <group>`.
+    pub fn add_example(mut self, example: &'static str) -> CommandGroupMetadata {
+        self.examples.push(example);
+        self
     }
 }
 
@@ -450,6 +880,9 @@ impl CommandGroupMetadata {
     pub fn help(&self) -> &'static str {
         self.help
     }
+    pub fn examples(&self) -> &[&'static str] {
+        self.examples.as_slice()
+    }
 }
 
 pub struct CommandGroup {
@@ -484,11 +917,31 @@ impl CommandExecutor {
     }
 
     pub fn execute(&self, line: &str) -> Result<(), ()> {
+        take_reported_error();
+
+        let res = self._execute(line);
+
+        let error = res.err().map(|_| {
+            take_reported_error().unwrap_or_else(|| CommandError {
+                code: ErrorCode::CommandFailed,
+                message: "Command execution failed".to_string(),
+            })
+        });
+        if let Some(ref error) = error {
+            if self.ctx.is_json_errors() {
+                println!("{}", error.to_json());
+            }
+        }
+        self.ctx.set_last_error(error);
+
+        res
+    }
+
+    fn _execute(&self, line: &str) -> Result<(), ()> {
         let (cmd, params) = CommandExecutor::_split_first_word(line);
 
         if cmd == "help" {
-            self._print_help();
-            return Ok(());
+            return self._execute_help(params);
         }
 
         if let Some(&(ref group, ref commands)) = self.grouped_commands.get(cmd) {
@@ -499,7 +952,9 @@ impl CommandExecutor {
             return self._execute_command(None, command, params);
         }
 
-        println_err!("Unknown group or command \"{}\"", cmd);
+        let message = format!("Unknown group or command \"{}\"", cmd);
+        report_error(ErrorCode::InvalidCommand, &message);
+        println_err!("{}", message);
         println!("Type \"help\" to display the help");
         Err(())
     }
@@ -515,14 +970,26 @@ impl CommandExecutor {
     ) -> Vec<(String, char)> {
         let completions = match dynamic_completion_type {
             DynamicCompletionType::Wallet => crate::commands::wallet::wallet_names(),
-            DynamicCompletionType::Did => crate::commands::did::did_list(self.ctx()),
+            DynamicCompletionType::Did => {
+                let mut dids = crate::commands::did::did_list(self.ctx());
+                dids.extend(crate::commands::did::did_alias_list(self.ctx()));
+                dids
+            }
             DynamicCompletionType::Pool => crate::commands::pool::pool_list(),
+            DynamicCompletionType::Schema => self.ctx().get_schema_names(),
+            DynamicCompletionType::Node => crate::commands::pool::node_aliases(self.ctx()),
+        };
+
+        // list params are split by comma, so only the last item is completed
+        let (prefix, word) = match word.rfind(',') {
+            Some(index) => word.split_at(index + 1),
+            None => ("", word),
         };
 
         completions
             .into_iter()
             .filter(|completion| completion.starts_with(word))
-            .map(|completion| (completion, ' '))
+            .map(|completion| (format!("{}{}", prefix, completion), ' '))
             .collect()
     }
 
@@ -590,7 +1057,11 @@ impl CommandExecutor {
     ) -> Vec<(String, char)> {
         let mut completes: Vec<(String, char)> = Vec::new();
 
-        if command.metadata().main_param.is_some()
+        if command
+            .metadata()
+            .main_param
+            .as_ref()
+            .map_or(false, |main_param| !main_param.is_optional())
             && (params.is_empty() && word.is_empty() || params.len() == 1 && !word.is_empty())
         {
             return self._get_main_param_dynamic_completions(command, word);
@@ -640,6 +1111,15 @@ impl CommandExecutor {
             .collect::<Vec<(String, char)>>()
     }
 
+    fn plugin_command_names(&self, group: &str, word: &str) -> Vec<(String, char)> {
+        self.ctx
+            .get_plugin_commands(group)
+            .iter()
+            .filter(|command| command.metadata().name().starts_with(word))
+            .map(|command| (command.metadata().name().to_owned(), ' '))
+            .collect()
+    }
+
     fn group_names(
         grouped_commands: &HashMap<&'static str, (CommandGroup, HashMap<&'static str, Command>)>,
         word: &str,
@@ -685,6 +1165,7 @@ impl CommandExecutor {
                     }
 
                     completes.extend(CommandExecutor::command_names(commands, word));
+                    completes.extend(self.plugin_command_names(command, word));
                     return completes;
                 }
 
@@ -727,6 +1208,15 @@ impl CommandExecutor {
                     return completes;
                 }
 
+                if let Some(sub_command) = self.ctx.get_plugin_command(command, sub_command) {
+                    if word == sub_command.metadata.name {
+                        completes.push((word.to_owned(), ' '));
+                    }
+
+                    completes.extend(self.command_params(&sub_command, &[], line, word, cursor));
+                    return completes;
+                }
+
                 if word.is_empty() {
                     completes = Vec::new();
                     return completes;
@@ -737,6 +1227,7 @@ impl CommandExecutor {
                 {
                     let (_, ref commands) = self.grouped_commands[command];
                     completes.extend(CommandExecutor::command_names(&commands, word));
+                    completes.extend(self.plugin_command_names(command, word));
                     return completes;
                 }
             }
@@ -778,6 +1269,17 @@ impl CommandExecutor {
                     return completes;
                 }
 
+                if let Some(sub_command) = self.ctx.get_plugin_command(command, sub_command) {
+                    completes.extend(self.command_params(
+                        &sub_command,
+                        &params,
+                        line,
+                        word,
+                        cursor,
+                    ));
+                    return completes;
+                }
+
                 if self.commands.contains_key(command) {
                     params.insert(0, sub_command);
                     completes.extend(self.command_params(
@@ -819,7 +1321,13 @@ impl CommandExecutor {
             return self._execute_command(Some(group), command, params);
         }
 
-        println_err!("Unknown command \"{} {}\"", group.metadata().name(), cmd);
+        if let Some(command) = self.ctx.get_plugin_command(group.metadata().name(), cmd) {
+            return self._execute_command(Some(group), &command, params);
+        }
+
+        let message = format!("Unknown command \"{} {}\"", group.metadata().name(), cmd);
+        report_error(ErrorCode::InvalidCommand, &message);
+        println_err!("{}", message);
         println!(
             "Type \"{} help\" to display the help for \"{}\" group",
             group.metadata().name(),
@@ -842,8 +1350,22 @@ impl CommandExecutor {
         }
 
         match CommandExecutor::_parse_params(command.metadata(), params) {
-            Ok(ref params) => command.execute(&self.ctx, params),
+            Ok(mut params) => {
+                self._resolve_did_aliases(command.metadata(), &mut params)?;
+                self.ctx.apply_pool_auto_refresh();
+                self.ctx.start_timing();
+                let res = command.execute(&self.ctx, &params);
+                let name = match group {
+                    Some(group) => {
+                        format!("{} {}", group.metadata().name(), command.metadata().name())
+                    }
+                    None => command.metadata().name().to_string(),
+                };
+                self.ctx.finish_timing(name, res.is_ok());
+                res
+            }
             Err(ref err) => {
+                report_error(ErrorCode::InvalidCommand, err);
                 println_err!("{}", err);
                 if group.is_some() {
                     println!(
@@ -865,6 +1387,37 @@ impl CommandExecutor {
         }
     }
 
+    /// Replaces `@alias` values of DID params with the DIDs assigned to the aliases in the opened wallet.
+    fn _resolve_did_aliases(
+        &self,
+        command: &CommandMetadata,
+        params: &mut CommandParams,
+    ) -> Result<(), ()> {
+        let did_params = command
+            .main_param()
+            .into_iter()
+            .chain(command.params())
+            .filter(|param| {
+                matches!(
+                    param.dynamic_completion_type,
+                    Some(DynamicCompletionType::Did)
+                )
+            });
+
+        for param in did_params {
+            let alias = match params
+                .get(param.name())
+                .and_then(|value| value.strip_prefix(crate::commands::did::DID_ALIAS_PREFIX))
+            {
+                Some(alias) => alias.to_string(),
+                None => continue,
+            };
+            let did = crate::commands::did::resolve_did_alias(&self.ctx, &alias)?;
+            params.insert(param.name(), did);
+        }
+        Ok(())
+    }
+
     fn _print_help(&self) {
         println_acc!("Hyperledger Indy CLI");
         println!();
@@ -875,6 +1428,7 @@ impl CommandExecutor {
         println!("\thelp - Display this help");
         println!("\t<command-group> help - Display the help for the specific command group");
         println!("\t[<command-group>] <command> help - Display the help for the specific command");
+        println!("\thelp --examples <command-group> - Display the example command sequence for the specific command group");
         println!();
         println_acc!("Command groups are:");
 
@@ -927,6 +1481,20 @@ impl CommandExecutor {
             )
         }
 
+        let plugin_commands = self.ctx.get_plugin_commands(group.metadata().name());
+        if !plugin_commands.is_empty() {
+            println!();
+            println_acc!("Plugin commands are:");
+
+            for command in plugin_commands {
+                println!(
+                    "\t{} - {}",
+                    command.metadata().name(),
+                    command.metadata().help()
+                )
+            }
+        }
+
         println!();
     }
 
@@ -962,7 +1530,11 @@ impl CommandExecutor {
         }
 
         if let Some(ref main_param) = command.metadata().main_param() {
-            print!(" <{}-value>", main_param.name());
+            if main_param.is_optional() {
+                print!(" [<{}-value>]", main_param.name());
+            } else {
+                print!(" <{}-value>", main_param.name());
+            }
         }
 
         for param in command.metadata().params() {
@@ -981,7 +1553,11 @@ impl CommandExecutor {
             println_acc!("Parameters are:");
 
             if let Some(ref main_param) = command.metadata().main_param() {
-                println!("\t{} - {}", main_param.name(), main_param.help())
+                if main_param.is_optional() {
+                    println!("\t{} - (optional) {}", main_param.name(), main_param.help())
+                } else {
+                    println!("\t{} - {}", main_param.name(), main_param.help())
+                }
             }
 
             for param in command.metadata().params() {
@@ -999,6 +1575,22 @@ impl CommandExecutor {
             }
         }
 
+        if !command.metadata().preconditions().is_empty() {
+            println!();
+            println_acc!("Preconditions:");
+
+            for precondition in command.metadata().preconditions() {
+                match precondition.current_value(&self.ctx) {
+                    Some(value) => println!("\t{} - met (\"{}\")", precondition.name(), value),
+                    None => println!(
+                        "\t{} - NOT MET, {}",
+                        precondition.name(),
+                        precondition.hint()
+                    ),
+                }
+            }
+        }
+
         if !command.metadata().examples().is_empty() {
             println!();
             println_acc!("Examples:");
@@ -1011,6 +1603,130 @@ impl CommandExecutor {
         println!();
     }
 
+    fn _execute_help(&self, params: &str) -> Result<(), ()> {
+        if params.is_empty() {
+            self._print_help();
+            return Ok(());
+        }
+
+        let group_name = match CommandExecutor::_split_first_word(params) {
+            ("--examples", group_name) if !group_name.is_empty() => group_name,
+            _ => {
+                let message = format!("Unknown help option \"{}\"", params);
+                report_error(ErrorCode::InvalidCommand, &message);
+                println_err!("{}", message);
+                println!("Type \"help\" to display the help");
+                return Err(());
+            }
+        };
+
+        let group = match self.grouped_commands.get(group_name) {
+            Some((group, _)) => group,
+            None => {
+                let message = format!("Unknown command group \"{}\"", group_name);
+                report_error(ErrorCode::InvalidCommand, &message);
+                println_err!("{}", message);
+                println!("Type \"help\" to display the help");
+                return Err(());
+            }
+        };
+
+        if group.metadata().examples().is_empty() {
+            println_warn!(
+                "There are no examples for \"{}\" group. Type \"{} help\" to display the help for the group",
+                group_name,
+                group_name
+            );
+            return Ok(());
+        }
+
+        println_acc!("Examples for \"{}\" group:", group_name);
+        for line in self._example_sequence(group.metadata().examples()) {
+            println!("\t{}", line);
+        }
+        println!();
+        Ok(())
+    }
+
+    /// Builds a runnable command sequence from the group examples: setup commands are inserted
+    /// before the first command whose preconditions are not met in the current session.
+    fn _example_sequence(&self, examples: &[&'static str]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut met = BTreeSet::new();
+
+        for precondition in Precondition::ALL {
+            if let Some(value) = precondition.current_value(&self.ctx) {
+                lines.push(format!(
+                    "# {} \"{}\" of the current session is used",
+                    precondition.name(),
+                    value
+                ));
+                met.insert(precondition);
+            }
+        }
+
+        for example in examples {
+            self._add_example_line(example, &mut met, &mut lines);
+        }
+        lines
+    }
+
+    fn _add_example_line(
+        &self,
+        line: &str,
+        met: &mut BTreeSet<Precondition>,
+        lines: &mut Vec<String>,
+    ) {
+        let command = self._find_command(line);
+
+        if let Some((_, _, ref preconditions)) = command {
+            for precondition in preconditions {
+                if met.insert(*precondition) {
+                    lines.push(format!("# setup: {} is required", precondition.name()));
+                    for setup_line in precondition.setup_commands() {
+                        self._add_example_line(setup_line, met, lines);
+                    }
+                }
+            }
+        }
+
+        lines.push(line.to_string());
+
+        if let Some((group, name, _)) = command {
+            for precondition in Precondition::ALL {
+                if precondition.established_by() == (group, name) {
+                    met.insert(precondition);
+                }
+            }
+        }
+    }
+
+    /// Finds the group command invoked by the line, returns its group, name and preconditions.
+    fn _find_command(&self, line: &str) -> Option<(&'static str, &'static str, Vec<Precondition>)> {
+        let (cmd, params) = CommandExecutor::_split_first_word(line);
+        let (name, _) = CommandExecutor::_split_first_word(params);
+
+        let (group, commands) = self.grouped_commands.get(cmd)?;
+        let group_name = group.metadata().name();
+        match commands.get(name) {
+            Some(command) => Some((
+                group_name,
+                command.metadata().name(),
+                command.metadata().preconditions().to_vec(),
+            )),
+            None => self
+                .ctx
+                .get_plugin_command(group_name, name)
+                .map(|command| {
+                    (
+                        group_name,
+                        command.metadata().name(),
+                        command.metadata().preconditions().to_vec(),
+                    )
+                }),
+        }
+    }
+
     fn _parse_params(command: &CommandMetadata, params: &str) -> Result<CommandParams, String> {
         let mut res = CommandParams::new();
         let mut params = params;
@@ -1018,6 +1734,11 @@ impl CommandExecutor {
         // Read main param
         if let Some(param_metadata) = command.main_param() {
             let (param_value, tail) = CommandExecutor::_split_first_word(params);
+            let is_omitted = param_value.is_empty() || param_value.contains('=');
+
+            if param_metadata.is_optional() && is_omitted {
+                return CommandExecutor::_parse_named_params(command, params, res);
+            }
             params = tail;
 
             if param_value.is_empty() {
@@ -1037,6 +1758,15 @@ impl CommandExecutor {
             }
         }
 
+        CommandExecutor::_parse_named_params(command, params, res)
+    }
+
+    fn _parse_named_params(
+        command: &CommandMetadata,
+        params: &str,
+        mut res: CommandParams,
+    ) -> Result<CommandParams, String> {
+        let mut params = params;
         let mut deferred_params = Vec::new();
 
         // Read rest params
@@ -1070,7 +1800,7 @@ impl CommandExecutor {
                             .map(|param_value| res.insert(param_metadata.name(), param_value))?;
                     }
                     _ if param_metadata.is_deferred() => {
-                        deferred_params.push(param_metadata.name());
+                        deferred_params.push(param_metadata);
                     }
                     _ => return Err(format!("No value for \"{}\" parameter present", param_name)),
                 },
@@ -1078,29 +1808,54 @@ impl CommandExecutor {
             }
         }
 
-        for param in deferred_params {
-            println!("Enter value for {}:", param);
-            let val;
-            loop {
-                match rpassword::read_password() {
-                    Ok(v) => {
-                        if v.is_empty() {
-                            println!("Please enter value for {}:", param);
-                        } else {
-                            val = v;
-                            break;
-                        }
-                    }
-                    Err(err) => {
-                        println_err!("{}", err.to_string());
+        for param_metadata in deferred_params {
+            let param = param_metadata.name();
+            let val = match param_metadata.secret_policy() {
+                Some(policy) if (policy.is_required)(&res) => {
+                    CommandExecutor::_read_confirmed_deferred_param(param, policy)
+                }
+                _ => CommandExecutor::_read_deferred_param(param),
+            };
+            res.insert(param, val);
+        }
+        Ok(res)
+    }
+
+    fn _read_deferred_param(param: &str) -> String {
+        println!("Enter value for {}:", param);
+        loop {
+            match rpassword::read_password() {
+                Ok(v) => {
+                    if v.is_empty() {
                         println!("Please enter value for {}:", param);
+                    } else {
+                        return v;
                     }
                 }
+                Err(err) => {
+                    println_err!("{}", err.to_string());
+                    println!("Please enter value for {}:", param);
+                }
             }
+        }
+    }
 
-            res.insert(param, val);
+    fn _read_confirmed_deferred_param(param: &str, policy: SecretPolicy) -> String {
+        loop {
+            let val = CommandExecutor::_read_deferred_param(param);
+
+            if let Err(reason) = (policy.check)(&val) {
+                println_err!("{}", reason);
+                continue;
+            }
+
+            println!("Confirm value for {}:", param);
+            match rpassword::read_password() {
+                Ok(confirmation) if confirmation == val => return val,
+                Ok(_) => println_err!("Values for {} do not match", param),
+                Err(err) => println_err!("{}", err.to_string()),
+            }
         }
-        Ok(res)
     }
 
     fn _split_first_word(s: &str) -> (&str, &str) {
@@ -1196,8 +1951,14 @@ impl CommandExecutorBuilder {
     }
 
     pub fn finalize(self) -> CommandExecutor {
+        let ctx = CommandContext::new();
+        for (group, (_, commands)) in self.grouped_commands.iter() {
+            let mut builtin_commands = ctx.builtin_commands.borrow_mut();
+            builtin_commands.extend(commands.keys().map(|name| (*group, *name)));
+        }
+
         CommandExecutor {
-            ctx: CommandContext::new(),
+            ctx,
             commands: self.commands,
             grouped_commands: self.grouped_commands,
         }
@@ -1236,6 +1997,11 @@ pub fn wait_for_user_reply(ctx: &CommandContext) -> bool {
         return true;
     }
 
+    if ctx.is_auto_confirm() {
+        println!("y (auto-confirm is enabled)");
+        return true;
+    }
+
     let reader = Interface::new("User Reply Reader").unwrap();
 
     while let Ok(ReadResult::Input(line)) = reader.read_line() {
@@ -1255,6 +2021,20 @@ pub fn wait_for_user_reply(ctx: &CommandContext) -> bool {
     false
 }
 
+pub fn read_user_input(ctx: &CommandContext, prompt: &str) -> Option<String> {
+    if ctx.is_batch_mode() || cfg!(test) {
+        return None;
+    }
+
+    let reader = Interface::new("User Input Reader").ok()?;
+    reader.set_prompt(prompt).ok()?;
+
+    match reader.read_line() {
+        Ok(ReadResult::Input(line)) => Some(line.trim().to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1279,6 +2059,59 @@ mod tests {
         }
     }
 
+    pub mod test_wallet_command {
+        use super::*;
+
+        command!(
+            CommandMetadata::build("test_wallet_command", "Test command help")
+                .add_precondition(Precondition::OpenedWallet)
+                .finalize()
+        );
+
+        fn execute(_ctx: &CommandContext, _params: &CommandParams) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    pub mod test_completion_command {
+        use super::*;
+
+        command!(
+            CommandMetadata::build("test_completion_command", "Test command help")
+                .add_optional_param_with_dynamic_completion(
+                    "schemas",
+                    "Schemas help",
+                    DynamicCompletionType::Schema
+                )
+                .finalize()
+        );
+
+        fn execute(_ctx: &CommandContext, _params: &CommandParams) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn complete_works_for_dynamic_completion_list() {
+        let cmd_executor = CommandExecutor::build()
+            .add_command(test_completion_command::new())
+            .finalize();
+        cmd_executor.ctx().add_schema_name("gvt");
+        cmd_executor.ctx().add_schema_name("transcript");
+
+        let line = "test_completion_command schemas=t";
+        assert_eq!(
+            vec![("transcript".to_string(), ' ')],
+            cmd_executor.complete(line, "t", line.len())
+        );
+
+        let line = "test_completion_command schemas=gvt,t";
+        assert_eq!(
+            vec![("gvt,transcript".to_string(), ' ')],
+            cmd_executor.complete(line, "gvt,t", line.len())
+        );
+    }
+
     #[test]
     pub fn execute_works() {
         let cmd_executor = CommandExecutor::build()
@@ -1290,6 +2123,98 @@ mod tests {
         cmd_executor.execute("test_group test_command \"main param\" param1=\"param1 value\" param2=param2-value").unwrap();
     }
 
+    #[test]
+    pub fn parse_params_works_for_optional_main_param() {
+        let command = CommandMetadata::build("test_command", "Test command help")
+            .add_optional_main_param("main_param", "Main param help")
+            .add_optional_param("param1", "Param1 help")
+            .finalize();
+
+        let params = CommandExecutor::_parse_params(&command, "main param1=value").unwrap();
+        assert_eq!("main", params["main_param"]);
+        assert_eq!("value", params["param1"]);
+
+        let params = CommandExecutor::_parse_params(&command, "param1=value").unwrap();
+        assert!(!params.contains_key("main_param"));
+        assert_eq!("value", params["param1"]);
+
+        let params = CommandExecutor::_parse_params(&command, "").unwrap();
+        assert!(params.is_empty());
+    }
+
+    #[test]
+    pub fn execute_works_for_error_code() {
+        let cmd_executor = CommandExecutor::build()
+            .add_command(test_command::new())
+            .finalize();
+
+        cmd_executor.execute("unknown_command").unwrap_err();
+        assert_eq!(
+            ErrorCode::InvalidCommand,
+            cmd_executor.ctx().get_last_error().unwrap().code
+        );
+
+        cmd_executor.execute("test_command value").unwrap_err();
+        assert_eq!(
+            ErrorCode::InvalidCommand,
+            cmd_executor.ctx().get_last_error().unwrap().code
+        );
+
+        cmd_executor
+            .execute("test_command value param1=value")
+            .unwrap();
+        assert!(cmd_executor.ctx().get_last_error().is_none());
+    }
+
+    #[test]
+    pub fn example_sequence_works() {
+        let cmd_executor = CommandExecutor::build()
+            .add_group(test_group::new())
+            .add_command(test_wallet_command::new())
+            .finalize_group()
+            .finalize();
+
+        assert_eq!(
+            vec![
+                "test_group unknown_command",
+                "# setup: opened wallet is required",
+                "wallet create wallet1 key",
+                "wallet open wallet1 key",
+                "test_group test_wallet_command",
+                "test_group test_wallet_command value",
+            ],
+            cmd_executor._example_sequence(&[
+                "test_group unknown_command",
+                "test_group test_wallet_command",
+                "test_group test_wallet_command value",
+            ])
+        );
+    }
+
+    #[test]
+    pub fn execute_works_for_help_examples() {
+        let cmd_executor = CommandExecutor::build()
+            .add_group(test_group::new())
+            .add_command(test_wallet_command::new())
+            .finalize_group()
+            .finalize();
+
+        cmd_executor.execute("help").unwrap();
+        cmd_executor.execute("help --examples test_group").unwrap();
+        cmd_executor
+            .execute("test_group test_wallet_command help")
+            .unwrap();
+
+        cmd_executor.execute("help --examples").unwrap_err();
+        cmd_executor
+            .execute("help --examples unknown_group")
+            .unwrap_err();
+        assert_eq!(
+            ErrorCode::InvalidCommand,
+            cmd_executor.ctx().get_last_error().unwrap().code
+        );
+    }
+
     #[test]
     pub fn _trim_quites_works() {
         assert_eq!(CommandExecutor::_trim_quotes(""), "");
@@ -2,27 +2,61 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::command_executor::{
-    Command, CommandContext, CommandMetadata, CommandParams, CommandResult,
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, CommandResult},
+    ledger::load_plugin,
+    params_parser::ParamParser,
 };
 
 pub mod load_plugin_command {
     use super::*;
 
-    command!(
-        CommandMetadata::build("load-plugin", "Load plugin in Libindy")
-            .add_required_param(
-                "library",
-                "Name of plugin (can be absolute or relative path)"
-            )
-            .add_required_param("initializer", "Name of plugin init function")
-            .add_example("load-plugin library=libpostgre initializer=libpostgre_init")
-            .finalize()
-    );
-
-    fn execute(_ctx: &CommandContext, params: &CommandParams) -> CommandResult {
+    command!(CommandMetadata::build(
+        "load-plugin",
+        "Load ledger plugin definition adding commands for plugin transactions to `ledger` group"
+    )
+    .add_optional_param("file", "Path to the plugin definition file")
+    .add_optional_param(
+        "library",
+        "DEPRECATED. Name of Libindy plugin (can be absolute or relative path)"
+    )
+    .add_optional_param(
+        "initializer",
+        "DEPRECATED. Name of Libindy plugin init function"
+    )
+    .add_example("load-plugin file=/home/plugins/fees.json")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> CommandResult {
         trace!("execute >> params: {:?}", params);
-        println_warn!("Command DEPRECATED!");
+
+        let file = ParamParser::get_opt_str_param("file", params)?;
+        let library = ParamParser::get_opt_str_param("library", params)?;
+
+        let file = match (file, library) {
+            (Some(file), _) => file,
+            (None, Some(_)) => {
+                println_warn!("Loading Libindy plugins is DEPRECATED!");
+                return Ok(());
+            }
+            (None, None) => {
+                println_err!("Plugin definition file is not specified.");
+                return Err(());
+            }
+        };
+
+        let (name, commands) = load_plugin(ctx, file).map_err(|err| println_err!("{}", err))?;
+
+        println_succ!(
+            "Plugin \"{}\" has been loaded. Added commands: {}",
+            name,
+            commands
+                .iter()
+                .map(|command| format!("ledger {}", command))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
         trace!("execute << ");
         Ok(())
     }
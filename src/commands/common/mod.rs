@@ -7,6 +7,7 @@ pub mod exit;
 pub mod init_logger;
 pub mod load_plugin;
 pub mod prompt;
+pub mod set;
 pub mod show;
 
-pub use self::{about::*, exit::*, init_logger::*, load_plugin::*, prompt::*, show::*};
+pub use self::{about::*, exit::*, init_logger::*, load_plugin::*, prompt::*, set::*, show::*};
@@ -0,0 +1,610 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, CommandResult},
+    params_parser::ParamParser,
+    utils::term::Theme,
+    wallet::validate_key_derivation_method,
+};
+
+/// Value of `prompt-template` param restoring the default prompt.
+const DEFAULT_PROMPT_TEMPLATE: &str = "default";
+
+/// Value of `auto-backup-dir` param turning automatic wallet backups off.
+const AUTO_BACKUP_OFF: &str = "off";
+
+pub mod set_command {
+    use super::*;
+
+    command!(CommandMetadata::build("set", "Change CLI context settings")
+        .add_optional_param(
+            "auto-confirm",
+            "Answer `yes` to all interactive confirmations (false by default)"
+        )
+        .add_optional_param(
+            "request-timeout",
+            "Time to wait for the Ledger reply (in sec). Can be overridden by `timeout` parameter of ledger commands"
+        )
+        .add_optional_param(
+            "request-retries",
+            "How many times to repeat a Ledger request which failed because of timeout or unavailable nodes (0 by default)"
+        )
+        .add_optional_param(
+            "pool-ack-timeout",
+            "Time to wait for node acknowledgement (in sec) for pools connected later. Can be overridden by `timeout` parameter of `pool connect`"
+        )
+        .add_optional_param(
+            "pool-conn-request-limit",
+            "Maximal number of requests sent over one pool connection for pools connected later. \
+            Can be overridden by `conn-request-limit` parameter of `pool connect`"
+        )
+        .add_optional_param(
+            "pool-read-nodes",
+            "Number of nodes to send read requests to for pools connected later. \
+            Can be overridden by `number-read-nodes` parameter of `pool connect`"
+        )
+        .add_optional_param(
+            "trace-requests",
+            "Print the exact request sent to the Ledger and the raw reply for every ledger command (false by default)"
+        )
+        .add_optional_param(
+            "txn-journal",
+            "Record write transactions accepted by the Ledger into the journal of the connected pool (false by default). \
+            Recorded transactions are not resubmitted by write commands called with `idempotent=true`"
+        )
+        .add_optional_param(
+            "json-errors",
+            "Print the code and the message of a failed command as JSON object (false by default)"
+        )
+        .add_optional_param(
+            "show-timing",
+            "Print elapsed wall-clock time of every executed command. \
+            Ledger commands also show build/sign/submit/parse phases (off by default)"
+        )
+        .add_optional_param(
+            "key-derivation-method",
+            "Default wallet key derivation method used when `key_derivation_method` is not specified. One of: argon2m, argon2i, raw (argon2m by default)"
+        )
+        .add_optional_param(
+            "role-alias",
+            "Title to display for a role code in the form `<code>=<title>`"
+        )
+        .add_optional_param(
+            "txn-alias",
+            "Title to display for a transaction type code in the form `<code>=<title>`"
+        )
+        .add_optional_param(
+            "theme",
+            "Colors of CLI output. One of: dark, light, none (plain text without colors, e.g. for CI logs). \
+            dark by default or none if NO_COLOR environment variable is set"
+        )
+        .add_optional_param(
+            "prompt-template",
+            "Command prompt built from `{pool}`, `{wallet}`, `{did}` and `{prompt}` placeholders \
+            replaced with the connected pool, the opened wallet, the active DID and the prompt set with `prompt` command. \
+            `default` restores the default prompt"
+        )
+        .add_optional_param(
+            "auto-backup-dir",
+            "Directory to export a wallet into (protected with the wallet key) before `wallet delete` \
+            and replacing the wallet key with `wallet open rekey`. `off` turns automatic backups off (off by default)"
+        )
+        .add_optional_param(
+            "auto-backup-keep",
+            "How many latest automatic backups of every wallet to keep (5 by default)"
+        )
+        .add_example("set auto-confirm=true")
+        .add_example("set request-timeout=60")
+        .add_example("set request-retries=3")
+        .add_example("set pool-ack-timeout=60 pool-conn-request-limit=20 pool-read-nodes=3")
+        .add_example("set trace-requests=true")
+        .add_example("set txn-journal=true")
+        .add_example("set json-errors=true")
+        .add_example("set show-timing=on")
+        .add_example("set key-derivation-method=argon2i")
+        .add_example("set role-alias=205=CUSTOM_ROLE")
+        .add_example("set txn-alias=20000=PLUGIN_TXN")
+        .add_example("set theme=none")
+        .add_example("set auto-backup-dir=/home/indy/wallet-backups auto-backup-keep=10")
+        .add_example("set prompt-template={pool}|{wallet}|{did}>")
+        .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> CommandResult {
+        trace!("execute >> ctx: {:?}, params: {:?}", ctx, params);
+
+        let auto_confirm = ParamParser::get_opt_bool_param("auto-confirm", params)?;
+        let request_timeout = ParamParser::get_opt_number_param::<u64>("request-timeout", params)?;
+        let request_retries = ParamParser::get_opt_number_param::<u64>("request-retries", params)?;
+        let pool_ack_timeout =
+            ParamParser::get_opt_number_param::<u64>("pool-ack-timeout", params)?;
+        let pool_conn_request_limit =
+            ParamParser::get_opt_number_param::<u64>("pool-conn-request-limit", params)?;
+        let pool_read_nodes = ParamParser::get_opt_number_param::<u64>("pool-read-nodes", params)?;
+        let trace_requests = ParamParser::get_opt_bool_param("trace-requests", params)?;
+        let txn_journal = ParamParser::get_opt_bool_param("txn-journal", params)?;
+        let json_errors = ParamParser::get_opt_bool_param("json-errors", params)?;
+        let show_timing = ParamParser::get_opt_str_param("show-timing", params)?
+            .map(parse_switch)
+            .transpose()?;
+        let key_derivation_method =
+            ParamParser::get_opt_str_param("key-derivation-method", params)?;
+        let role_alias = ParamParser::get_opt_str_param("role-alias", params)?
+            .map(parse_alias)
+            .transpose()?;
+        let txn_alias = ParamParser::get_opt_str_param("txn-alias", params)?
+            .map(parse_alias)
+            .transpose()?;
+        let theme = ParamParser::get_opt_str_param("theme", params)?
+            .map(|theme| Theme::parse(theme).map_err(|err| println_err!("{}", err)))
+            .transpose()?;
+        let prompt_template = ParamParser::get_opt_str_param("prompt-template", params)?;
+        let auto_backup_dir = ParamParser::get_opt_str_param("auto-backup-dir", params)?;
+        let auto_backup_keep =
+            ParamParser::get_opt_number_param::<u64>("auto-backup-keep", params)?;
+
+        if auto_confirm.is_none()
+            && request_timeout.is_none()
+            && request_retries.is_none()
+            && pool_ack_timeout.is_none()
+            && pool_conn_request_limit.is_none()
+            && pool_read_nodes.is_none()
+            && trace_requests.is_none()
+            && txn_journal.is_none()
+            && json_errors.is_none()
+            && show_timing.is_none()
+            && key_derivation_method.is_none()
+            && role_alias.is_none()
+            && txn_alias.is_none()
+            && theme.is_none()
+            && prompt_template.is_none()
+            && auto_backup_dir.is_none()
+            && auto_backup_keep.is_none()
+        {
+            println_err!("No setting to change provided.");
+            return Err(());
+        }
+
+        if auto_backup_keep == Some(0) {
+            println_err!("Number of automatic backups to keep must be greater than 0.");
+            return Err(());
+        }
+
+        if request_timeout == Some(0) || pool_ack_timeout == Some(0) {
+            println_err!("Request timeout must be greater than 0.");
+            return Err(());
+        }
+
+        if pool_conn_request_limit == Some(0) || pool_read_nodes == Some(0) {
+            println_err!(
+                "Number of read nodes and connection request limit must be greater than 0."
+            );
+            return Err(());
+        }
+
+        if let Some(auto_confirm) = auto_confirm {
+            ctx.set_auto_confirm(auto_confirm);
+            println_succ!("Auto confirmation has been set to \"{}\"", auto_confirm);
+        }
+
+        if let Some(request_timeout) = request_timeout {
+            if let Some(pool) = ctx.get_connected_pool() {
+                let pool = pool
+                    .with_reply_timeout(request_timeout)
+                    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+                ctx.set_connected_pool(pool);
+            }
+            ctx.set_request_timeout(Some(request_timeout));
+            println_succ!(
+                "Request timeout has been set to \"{}\" sec",
+                request_timeout
+            );
+        }
+
+        if let Some(request_retries) = request_retries {
+            ctx.set_request_retries(request_retries);
+            println_succ!("Request retries have been set to \"{}\"", request_retries);
+        }
+
+        if let Some(pool_ack_timeout) = pool_ack_timeout {
+            ctx.set_pool_ack_timeout(Some(pool_ack_timeout));
+            println_succ!(
+                "Pool ack timeout has been set to \"{}\" sec for pools connected later",
+                pool_ack_timeout
+            );
+        }
+
+        if let Some(pool_conn_request_limit) = pool_conn_request_limit {
+            ctx.set_pool_conn_request_limit(Some(pool_conn_request_limit));
+            println_succ!(
+                "Pool connection request limit has been set to \"{}\" for pools connected later",
+                pool_conn_request_limit
+            );
+        }
+
+        if let Some(pool_read_nodes) = pool_read_nodes {
+            ctx.set_pool_read_nodes(Some(pool_read_nodes));
+            println_succ!(
+                "Number of pool read nodes has been set to \"{}\" for pools connected later",
+                pool_read_nodes
+            );
+        }
+
+        if let Some(trace_requests) = trace_requests {
+            ctx.set_trace_requests(trace_requests);
+            println_succ!("Requests tracing has been set to \"{}\"", trace_requests);
+        }
+
+        if let Some(txn_journal) = txn_journal {
+            ctx.set_txn_journal(txn_journal);
+            println_succ!("Transactions journal has been set to \"{}\"", txn_journal);
+        }
+
+        if let Some(json_errors) = json_errors {
+            ctx.set_json_errors(json_errors);
+            println_succ!("JSON errors output has been set to \"{}\"", json_errors);
+        }
+
+        if let Some(show_timing) = show_timing {
+            ctx.set_show_timing(show_timing);
+            println_succ!(
+                "Commands timing output has been turned {}",
+                if show_timing { "on" } else { "off" }
+            );
+        }
+
+        if let Some(method) = key_derivation_method {
+            validate_key_derivation_method(method).map_err(|err| println_err!("{}", err))?;
+            ctx.set_key_derivation_method(Some(method.to_string()));
+            println_succ!(
+                "Default key derivation method has been set to \"{}\"",
+                method
+            );
+        }
+
+        if let Some((code, title)) = role_alias {
+            ctx.set_role_title(code, title);
+            println_succ!("Role \"{}\" will be displayed as \"{}\"", code, title);
+        }
+
+        if let Some((code, title)) = txn_alias {
+            ctx.set_txn_title(code, title);
+            println_succ!(
+                "Transaction type \"{}\" will be displayed as \"{}\"",
+                code,
+                title
+            );
+        }
+
+        if let Some(theme) = theme {
+            Theme::set(theme);
+            println_succ!("Theme has been set to \"{}\"", theme.name());
+        }
+
+        match prompt_template {
+            Some(DEFAULT_PROMPT_TEMPLATE) => {
+                ctx.set_prompt_template(None);
+                println_succ!("Default command prompt has been restored");
+            }
+            Some(template) => {
+                ctx.set_prompt_template(Some(template.to_string()));
+                println_succ!("Command prompt template has been set to \"{}\"", template);
+            }
+            None => {}
+        }
+
+        match auto_backup_dir {
+            Some(AUTO_BACKUP_OFF) => {
+                ctx.set_auto_backup_dir(None);
+                println_succ!("Automatic wallet backups have been turned off");
+            }
+            Some(dir) => {
+                ctx.set_auto_backup_dir(Some(dir.to_string()));
+                println_succ!(
+                    "Wallets will be backed up to \"{}\" before deletion and rekey",
+                    dir
+                );
+            }
+            None => {}
+        }
+
+        if let Some(auto_backup_keep) = auto_backup_keep {
+            ctx.set_auto_backup_keep(auto_backup_keep);
+            println_succ!(
+                "{} latest automatic backups of every wallet will be kept",
+                auto_backup_keep
+            );
+        }
+
+        let res = Ok(());
+
+        trace!("execute << {:?}", res);
+        res
+    }
+
+    fn parse_switch(value: &str) -> Result<bool, ()> {
+        match value {
+            "on" | "true" => Ok(true),
+            "off" | "false" => Ok(false),
+            _ => {
+                println_err!(
+                    "Invalid value \"{}\". Expected one of: on, off, true, false",
+                    value
+                );
+                Err(())
+            }
+        }
+    }
+
+    fn parse_alias(alias: &str) -> Result<(&str, &str), ()> {
+        match alias.split_once('=') {
+            Some((code, title)) if !code.trim().is_empty() && !title.trim().is_empty() => {
+                Ok((code.trim(), title.trim()))
+            }
+            _ => {
+                println_err!(
+                    "Invalid alias \"{}\". Expected format: <code>=<title>",
+                    alias
+                );
+                Err(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+
+    mod set {
+        use super::*;
+
+        #[test]
+        pub fn set_works_for_auto_confirm() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("auto-confirm", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.is_auto_confirm());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_request_timeout_and_retries() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("request-timeout", "60".to_string());
+                params.insert("request-retries", "3".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(Some(60), ctx.get_request_timeout());
+            assert_eq!(3, ctx.get_request_retries());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_pool_config() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("pool-ack-timeout", "60".to_string());
+                params.insert("pool-conn-request-limit", "20".to_string());
+                params.insert("pool-read-nodes", "3".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(Some(60), ctx.get_pool_ack_timeout());
+            assert_eq!(Some(20), ctx.get_pool_conn_request_limit());
+            assert_eq!(Some(3), ctx.get_pool_read_nodes());
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("pool-read-nodes", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(Some(3), ctx.get_pool_read_nodes());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_trace_requests() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("trace-requests", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.is_trace_requests());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_txn_journal() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn-journal", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.is_txn_journal());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_json_errors() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("json-errors", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.is_json_errors());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_show_timing() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("show-timing", "on".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.is_show_timing());
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("show-timing", "sometimes".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ctx.is_show_timing());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_key_derivation_method() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key-derivation-method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(Some("raw".to_string()), ctx.get_key_derivation_method());
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key-derivation-method", "scrypt".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_role_and_txn_alias() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("role-alias", "205=CUSTOM_ROLE".to_string());
+                params.insert("txn-alias", "20000=PLUGIN_TXN".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(
+                Some("CUSTOM_ROLE"),
+                ctx.get_role_titles().get("205").map(String::as_str)
+            );
+            assert_eq!(
+                Some("PLUGIN_TXN"),
+                ctx.get_txn_titles().get("20000").map(String::as_str)
+            );
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_invalid_role_alias() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("role-alias", "CUSTOM_ROLE".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ctx.get_role_titles().is_empty());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_prompt_template() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("prompt-template", "{pool}|{wallet}|{prompt}> ".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!("||indy-cli-rs> ", ctx.get_prompt());
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("prompt-template", "default".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(None, ctx.get_prompt_template());
+            assert_eq!("indy-cli-rs> ", ctx.get_prompt());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_auto_backup() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("auto-backup-dir", "/tmp/backups".to_string());
+                params.insert("auto-backup-keep", "3".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(Some("/tmp/backups".to_string()), ctx.get_auto_backup_dir());
+            assert_eq!(3, ctx.get_auto_backup_keep());
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("auto-backup-dir", "off".to_string());
+                params.insert("auto-backup-keep", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+                params.remove("auto-backup-keep");
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(None, ctx.get_auto_backup_dir());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_unknown_theme() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("theme", "blue".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_zero_request_timeout() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let mut params = CommandParams::new();
+                params.insert("request-timeout", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(None, ctx.get_request_timeout());
+            tear_down();
+        }
+
+        #[test]
+        pub fn set_works_for_no_settings() {
+            let ctx = setup();
+            {
+                let cmd = set_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    utils::{
+        environment::EnvironmentUtils, history::DEFAULT_HISTORY_SIZE, table::print_list_table,
+        term::Theme,
+    },
+};
+
+pub mod show_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "show",
+        "Show CLI settings in effect: defaults from configuration files overridden in the current session."
+    )
+    .add_example("config show")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let not_set = || "-".to_string();
+
+        let settings = [
+            (
+                "config files",
+                ctx.get_config_files().unwrap_or_else(|| {
+                    format!(
+                        "- (\"{}\" is not found)",
+                        EnvironmentUtils::cli_config_path().to_string_lossy()
+                    )
+                }),
+            ),
+            (
+                "taa acceptance mechanism",
+                Some(ctx.get_taa_acceptance_mechanism())
+                    .filter(|mechanism| !mechanism.is_empty())
+                    .unwrap_or_else(not_set),
+            ),
+            (
+                "key derivation method",
+                ctx.get_key_derivation_method()
+                    .unwrap_or_else(|| "argon2m".to_string()),
+            ),
+            (
+                "history size",
+                ctx.get_history_size()
+                    .unwrap_or(DEFAULT_HISTORY_SIZE)
+                    .to_string(),
+            ),
+            (
+                "default pool",
+                ctx.get_default_pool().unwrap_or_else(not_set),
+            ),
+            (
+                "request timeout",
+                ctx.get_request_timeout()
+                    .map(|timeout| format!("{} sec", timeout))
+                    .unwrap_or_else(not_set),
+            ),
+            ("request retries", ctx.get_request_retries().to_string()),
+            (
+                "pool ack timeout",
+                ctx.get_pool_ack_timeout()
+                    .map(|timeout| format!("{} sec", timeout))
+                    .unwrap_or_else(not_set),
+            ),
+            (
+                "pool conn request limit",
+                ctx.get_pool_conn_request_limit()
+                    .map(|limit| limit.to_string())
+                    .unwrap_or_else(not_set),
+            ),
+            (
+                "pool read nodes",
+                ctx.get_pool_read_nodes()
+                    .map(|nodes| nodes.to_string())
+                    .unwrap_or_else(not_set),
+            ),
+            ("auto confirm", ctx.is_auto_confirm().to_string()),
+            ("json errors", ctx.is_json_errors().to_string()),
+            ("show timing", ctx.is_show_timing().to_string()),
+            (
+                "auto backup dir",
+                ctx.get_auto_backup_dir().unwrap_or_else(not_set),
+            ),
+            ("auto backup keep", ctx.get_auto_backup_keep().to_string()),
+            ("theme", Theme::current().name().to_string()),
+            (
+                "prompt template",
+                ctx.get_prompt_template().unwrap_or_else(not_set),
+            ),
+        ];
+
+        let rows = settings
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect::<Vec<_>>();
+
+        print_list_table(
+            &rows,
+            &[("name", "Setting"), ("value", "Value")],
+            "There are no settings",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+
+    mod show {
+        use super::*;
+
+        #[test]
+        pub fn show_works() {
+            let ctx = setup();
+            ctx.set_key_derivation_method(Some("raw".to_string()));
+            {
+                let cmd = show_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,223 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    error::{report_error, ErrorCode},
+    params_parser::ParamParser,
+    tools::did::Did,
+    utils::table::print_list_table,
+};
+
+/// Prefix of a DID param value referring to the DID by its local alias, e.g. `@acme`.
+pub const DID_ALIAS_PREFIX: char = '@';
+
+pub mod alias_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "alias",
+        "Manage local aliases of DIDs. An alias prefixed with `@` can be passed instead of a DID \
+            to any DID param (e.g. `did`, `endorser` or `origin`) while the wallet is opened."
+    )
+    .add_main_param("action", "One of: set, delete, list")
+    .add_optional_param(
+        "name",
+        "Alias name (letters, digits, `_`, `-` and `.`). Required for `set` and `delete` actions"
+    )
+    .add_optional_param_with_dynamic_completion(
+        "did",
+        "DID to assign the alias to. Required for `set` action. Does not have to be stored in the wallet",
+        DynamicCompletionType::Did
+    )
+    .add_example("did alias set name=acme did=VsKV7grR1BUE29mG2Fm2kX")
+    .add_example("did alias delete name=acme")
+    .add_example("did alias list")
+    .add_example("ledger get-nym did=@acme")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let action = ParamParser::get_str_param("action", params)?;
+
+        match action {
+            "set" => {
+                let name = get_alias_name(params)?;
+                let did = ParamParser::get_did_param("did", params)?;
+                Did::set_alias(&wallet, name, &did)
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+                println_succ!(
+                    "Alias \"{}{}\" has been set to DID \"{}\"",
+                    DID_ALIAS_PREFIX,
+                    name,
+                    did
+                );
+            }
+            "delete" => {
+                let name = get_alias_name(params)?;
+                Did::delete_alias(&wallet, name)
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+                println_succ!("Alias \"{}{}\" has been deleted", DID_ALIAS_PREFIX, name);
+            }
+            "list" => {
+                let aliases = Did::list_aliases(&wallet)
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+                print_list_table(
+                    &aliases
+                        .iter()
+                        .map(|(name, did)| {
+                            json!({ "alias": format!("{}{}", DID_ALIAS_PREFIX, name), "did": did })
+                        })
+                        .collect::<Vec<serde_json::Value>>(),
+                    &[("alias", "Alias"), ("did", "Did")],
+                    "There are no DID aliases",
+                );
+            }
+            action => {
+                println_err!("Unknown action \"{}\". One of: set, delete, list.", action);
+                return Err(());
+            }
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn get_alias_name(params: &CommandParams) -> Result<&str, ()> {
+        let name = ParamParser::get_str_param("name", params)?;
+        let name = name.strip_prefix(DID_ALIAS_PREFIX).unwrap_or(name);
+        if !is_valid_alias_name(name) {
+            println_err!(
+                "Invalid alias name \"{}\". Only letters, digits, `_`, `-` and `.` are allowed.",
+                name
+            );
+            return Err(());
+        }
+        Ok(name)
+    }
+}
+
+pub fn is_valid_alias_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Returns the DID assigned to the alias in the opened wallet.
+pub fn resolve_did_alias(ctx: &CommandContext, alias: &str) -> Result<String, ()> {
+    let wallet = ctx.get_opened_wallet().ok_or_else(|| {
+        let message = format!(
+            "DID alias \"{}{}\" can't be resolved: there is no opened wallet",
+            DID_ALIAS_PREFIX, alias
+        );
+        report_error(ErrorCode::InvalidEntityState, &message);
+        println_err!("{}", message)
+    })?;
+    match Did::get_alias(&wallet, alias).map_err(|err| println_err!("{}", err.report(None)))? {
+        Some(did) => Ok(did),
+        None => {
+            let message = format!(
+                "DID alias \"{}{}\" does not exist in the wallet",
+                DID_ALIAS_PREFIX, alias
+            );
+            report_error(ErrorCode::NotFound, &message);
+            println_err!("{}", message);
+            Err(())
+        }
+    }
+}
+
+/// Aliases of the opened wallet prefixed with `@` offered by DID params completion.
+pub fn did_alias_list(ctx: &CommandContext) -> Vec<String> {
+    ctx.get_opened_wallet()
+        .and_then(|wallet| Did::list_aliases(&wallet).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, _)| format!("{}{}", DID_ALIAS_PREFIX, name))
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod did_alias {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet, tear_down_with_wallet},
+            did::tests::DID_MY1,
+        };
+
+        fn set_alias(ctx: &CommandContext, name: &str, did: &str) -> Result<(), ()> {
+            let cmd = alias_command::new();
+            let mut params = CommandParams::new();
+            params.insert("action", "set".to_string());
+            params.insert("name", name.to_string());
+            params.insert("did", did.to_string());
+            cmd.execute(&ctx, &params)
+        }
+
+        #[test]
+        pub fn alias_set_works() {
+            let ctx = setup_with_wallet();
+            set_alias(&ctx, "acme", DID_MY1).unwrap();
+            assert_eq!(DID_MY1, resolve_did_alias(&ctx, "acme").unwrap());
+            assert_eq!(vec!["@acme".to_string()], did_alias_list(&ctx));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn alias_set_works_for_replace() {
+            let ctx = setup_with_wallet();
+            set_alias(&ctx, "acme", "V4SGRU86Z58d6TV7PBUe6f").unwrap();
+            set_alias(&ctx, "@acme", DID_MY1).unwrap();
+            assert_eq!(DID_MY1, resolve_did_alias(&ctx, "acme").unwrap());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn alias_set_works_for_invalid_name() {
+            let ctx = setup_with_wallet();
+            set_alias(&ctx, "ac me", DID_MY1).unwrap_err();
+            set_alias(&ctx, "@", DID_MY1).unwrap_err();
+            assert!(did_alias_list(&ctx).is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn alias_delete_works() {
+            let ctx = setup_with_wallet();
+            set_alias(&ctx, "acme", DID_MY1).unwrap();
+            {
+                let cmd = alias_command::new();
+                let mut params = CommandParams::new();
+                params.insert("action", "delete".to_string());
+                params.insert("name", "acme".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            resolve_did_alias(&ctx, "acme").unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn alias_works_for_unknown_action() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = alias_command::new();
+                let mut params = CommandParams::new();
+                params.insert("action", "rename".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::did::{seed::Seed, Did},
+    utils::table::print_table,
+};
+
+use hex::FromHex;
+use indy_utils::base58;
+
+pub mod convert_verkey_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "convert-verkey",
+        "Convert verkey between representations used by different tools. Nothing is stored into the wallet."
+    )
+    .add_required_param(
+        "value",
+        "Verkey (full or abbreviated) or seed to take the verkey from"
+    )
+    .add_optional_param(
+        "from",
+        "Format of the value. One of: base58, hex, seed. (base58 by default)"
+    )
+    .add_optional_param(
+        "to",
+        "Format to convert the verkey to. One of: abbreviated, full, hex, multibase. (all of them are shown by default)"
+    )
+    .add_optional_param(
+        "did",
+        "DID the verkey belongs to. Required for abbreviated verkey value. (DID derived from the verkey by default)"
+    )
+    .add_example("did convert-verkey value=GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL")
+    .add_example("did convert-verkey value=~CoRER63DVYnWZtK8uAzNbx did=V4SGRU86Z58d6TV7PBUe6f to=full")
+    .add_example("did convert-verkey value=000000000000000000000000Trustee1 from=seed to=multibase")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let value = ParamParser::get_str_param("value", params)?;
+        let from = ParamParser::get_opt_str_param("from", params)?.unwrap_or("base58");
+        let to = ParamParser::get_opt_str_param("to", params)?;
+        let did = ParamParser::get_opt_str_param("did", params)?;
+
+        let (verkey, did) =
+            parse_verkey(value, from, did).map_err(|err| println_err!("{}", err))?;
+
+        let abbreviated = Did::abbreviate_verkey(&did, &verkey)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+        let hex = base58::decode(&verkey)
+            .map(hex::encode)
+            .map_err(|_| println_err!("Invalid verkey {} provided.", verkey))?;
+        let multibase = Did::multibase_verkey(&verkey).ok();
+
+        match to {
+            Some("abbreviated") => println!("{}", abbreviated),
+            Some("full") => println!("{}", verkey),
+            Some("hex") => println!("{}", hex),
+            Some("multibase") => match multibase {
+                Some(multibase) => println!("{}", multibase),
+                None => {
+                    println_err!("Only Ed25519 verkey can be encoded as multibase.");
+                    return Err(());
+                }
+            },
+            Some(to) => {
+                println_err!(
+                    "Unsupported verkey format \"{}\". One of: abbreviated, full, hex, multibase.",
+                    to
+                );
+                return Err(());
+            }
+            None => print_table(
+                &json!({
+                    "did": did,
+                    "full": verkey,
+                    "abbreviated": abbreviated,
+                    "hex": hex,
+                    "multibase": multibase,
+                }),
+                &[
+                    ("did", "Did"),
+                    ("full", "Verkey (full)"),
+                    ("abbreviated", "Verkey (abbreviated)"),
+                    ("hex", "Verkey (hex)"),
+                    ("multibase", "Verkey (multibase)"),
+                ],
+            ),
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+/// Returns the full base58 verkey and the DID it is abbreviated against.
+fn parse_verkey(value: &str, from: &str, did: Option<&str>) -> Result<(String, String), String> {
+    let verkey = match from {
+        "seed" => {
+            let seed = Seed::from_str(value).map_err(|err| err.message(None))?;
+            let (_, verkey) = Did::derive(&seed).map_err(|err| err.message(None))?;
+            verkey
+        }
+        "hex" => Vec::from_hex(value)
+            .map(base58::encode)
+            .map_err(|_| format!("Verkey {} is invalid hex.", value))?,
+        "base58" if value.starts_with('~') => match did {
+            Some(did) => Did::expand_verkey(did, value).map_err(|err| err.message(None))?,
+            None => {
+                return Err(
+                    "DID must be passed with `did` parameter to expand abbreviated verkey."
+                        .to_string(),
+                )
+            }
+        },
+        "base58" => {
+            base58::decode(value).map_err(|_| format!("Invalid verkey {} provided.", value))?;
+            value.to_string()
+        }
+        from => {
+            return Err(format!(
+                "Unsupported verkey format \"{}\". One of: base58, hex, seed.",
+                from
+            ))
+        }
+    };
+
+    let did = match did {
+        Some(did) => did.to_string(),
+        None => Did::did_for_verkey(&verkey).map_err(|err| err.message(None))?,
+    };
+    Ok((verkey, did))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        did::tests::{DID_TRUSTEE, SEED_TRUSTEE, VERKEY_TRUSTEE},
+    };
+
+    mod convert_verkey {
+        use super::*;
+
+        fn convert_verkey(ctx: &CommandContext, params: &[(&'static str, &str)]) -> Result<(), ()> {
+            let cmd = convert_verkey_command::new();
+            let mut cmd_params = CommandParams::new();
+            for (name, value) in params {
+                cmd_params.insert(*name, value.to_string());
+            }
+            cmd.execute(ctx, &cmd_params)
+        }
+
+        #[test]
+        pub fn convert_verkey_works() {
+            let ctx = setup();
+            convert_verkey(&ctx, &[("value", VERKEY_TRUSTEE)]).unwrap();
+            convert_verkey(&ctx, &[("value", VERKEY_TRUSTEE), ("to", "multibase")]).unwrap();
+            tear_down();
+        }
+
+        #[test]
+        pub fn convert_verkey_works_for_abbreviated_without_did() {
+            let ctx = setup();
+            let abbreviated = Did::abbreviate_verkey(DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            convert_verkey(&ctx, &[("value", &abbreviated)]).unwrap_err();
+            tear_down();
+        }
+
+        #[test]
+        pub fn convert_verkey_works_for_unknown_format() {
+            let ctx = setup();
+            convert_verkey(&ctx, &[("value", VERKEY_TRUSTEE), ("to", "jwk")]).unwrap_err();
+            convert_verkey(&ctx, &[("value", VERKEY_TRUSTEE), ("from", "jwk")]).unwrap_err();
+            tear_down();
+        }
+
+        #[test]
+        pub fn parse_verkey_works() {
+            let expected = (VERKEY_TRUSTEE.to_string(), DID_TRUSTEE.to_string());
+
+            assert_eq!(expected, parse_verkey(SEED_TRUSTEE, "seed", None).unwrap());
+
+            let hex = hex::encode(base58::decode(VERKEY_TRUSTEE).unwrap());
+            assert_eq!(expected, parse_verkey(&hex, "hex", None).unwrap());
+
+            let abbreviated = Did::abbreviate_verkey(DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            assert_eq!(
+                expected,
+                parse_verkey(&abbreviated, "base58", Some(DID_TRUSTEE)).unwrap()
+            );
+        }
+
+        #[test]
+        pub fn multibase_verkey_works() {
+            let multibase = Did::multibase_verkey(VERKEY_TRUSTEE).unwrap();
+            assert!(multibase.starts_with("z6Mk"));
+        }
+    }
+}
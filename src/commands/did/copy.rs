@@ -0,0 +1,235 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    params_parser::ParamParser,
+    tools::{
+        did::Did,
+        wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+    },
+    utils::table::print_list_table,
+};
+
+pub mod copy_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "copy",
+        "Copy DIDs and their keys from another wallet into the opened wallet."
+    )
+    .add_required_param_with_dynamic_completion(
+        "from_wallet",
+        "Identifier of the wallet to copy DIDs from",
+        DynamicCompletionType::Wallet
+    )
+    .add_required_deferred_param("from_key", "Key or passphrase of the wallet to copy DIDs from")
+    .add_optional_param(
+        "from_key_derivation_method",
+        "Algorithm used for the source wallet key derivation. One of: argon2m, argon2i, raw (argon2m by default)"
+    )
+    .add_optional_param(
+        "from_storage_credentials",
+        "The list of key:value pairs defined by the source wallet storage type."
+    )
+    .add_required_param(
+        "dids",
+        "DIDs to copy split by comma or `all` to copy every DID of the source wallet"
+    )
+    .add_example("did copy from_wallet=wallet2 from_key dids=VsKV7grR1BUE29mG2Fm2kX")
+    .add_example("did copy from_wallet=wallet2 from_key dids=all")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, secret!(params));
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let from_wallet = ParamParser::get_str_param("from_wallet", params)?;
+        let from_key = ParamParser::get_str_param("from_key", params)?;
+        let from_key_derivation_method =
+            ParamParser::get_opt_str_param("from_key_derivation_method", params)?;
+        let from_storage_credentials =
+            ParamParser::get_opt_object_param("from_storage_credentials", params)?;
+        let dids = ParamParser::get_str_array_param("dids", params)?;
+        let dids = match dids.as_slice() {
+            ["all"] => None,
+            dids => Some(dids),
+        };
+
+        if wallet.name == from_wallet {
+            println_err!("DIDs cannot be copied from the opened wallet into itself.");
+            return Err(());
+        }
+
+        let config = WalletConfig::read(from_wallet)
+            .map_err(|_| println_err!("Wallet \"{}\" isn't attached to CLI", from_wallet))?;
+
+        let credentials = Credentials {
+            key: from_key.to_string(),
+            key_derivation_method: from_key_derivation_method.map(String::from),
+            storage_credentials: from_storage_credentials,
+            ..Credentials::default()
+        };
+
+        let source = Wallet::open(&config, &credentials, None, None)
+            .map_err(|err| println_err!("{}", err.report(Some(from_wallet))))?;
+
+        let report = Did::copy(&source, &wallet, dids);
+
+        source
+            .close()
+            .map_err(|err| println_err!("{}", err.report(Some(from_wallet))))?;
+
+        let report = report.map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        for did in report.copied.iter() {
+            println_succ!("Did \"{}\" has been copied", did);
+        }
+
+        if !report.conflicts.is_empty() {
+            let conflicts = report
+                .conflicts
+                .iter()
+                .map(|(did, reason)| json!({ "did": did, "reason": reason }))
+                .collect::<Vec<_>>();
+            println_warn!("Following DIDs have not been copied:");
+            print_list_table(&conflicts, &[("did", "Did"), ("reason", "Reason")], "");
+        }
+
+        println_succ!(
+            "{} DID(s) have been copied from wallet \"{}\"",
+            report.copied.len(),
+            from_wallet
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{get_did_info, new_did, DID_MY1, DID_MY3, SEED_MY1, SEED_MY3},
+        wallet::tests::WALLET_KEY_RAW,
+    };
+
+    const SOURCE_WALLET: &str = "copy_source_wallet";
+
+    fn source_config() -> WalletConfig {
+        WalletConfig {
+            id: SOURCE_WALLET.to_string(),
+            storage_type: "default".to_string(),
+            storage_config: None,
+            storage_credentials: None,
+            active_did: None,
+        }
+    }
+
+    fn source_credentials() -> Credentials {
+        Credentials {
+            key: WALLET_KEY_RAW.to_string(),
+            key_derivation_method: Some("raw".to_string()),
+            ..Credentials::default()
+        }
+    }
+
+    fn create_source_wallet(seeds: &[&str]) {
+        let config = source_config();
+        Wallet::create(&config, &source_credentials()).unwrap();
+        config.store().unwrap();
+
+        let wallet = Wallet::open(&config, &source_credentials(), None, None).unwrap();
+        for seed in seeds {
+            Did::create(&wallet, None, Some(seed), None, None, None, None).unwrap();
+        }
+        wallet.close().unwrap();
+    }
+
+    fn delete_source_wallet() {
+        let config = source_config();
+        Wallet::delete(&config, &source_credentials()).unwrap();
+        config.delete().ok();
+    }
+
+    fn copy_params(dids: &str) -> CommandParams {
+        let mut params = CommandParams::new();
+        params.insert("from_wallet", SOURCE_WALLET.to_string());
+        params.insert("from_key", WALLET_KEY_RAW.to_string());
+        params.insert("from_key_derivation_method", "raw".to_string());
+        params.insert("dids", dids.to_string());
+        params
+    }
+
+    mod copy {
+        use super::*;
+
+        #[test]
+        pub fn copy_works_for_all() {
+            let ctx = setup_with_wallet();
+            create_source_wallet(&[SEED_MY1, SEED_MY3]);
+            {
+                let cmd = copy_command::new();
+                cmd.execute(&ctx, &copy_params("all")).unwrap();
+            }
+            get_did_info(&ctx, DID_MY1);
+            get_did_info(&ctx, DID_MY3);
+            delete_source_wallet();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn copy_works_for_selected_did() {
+            let ctx = setup_with_wallet();
+            create_source_wallet(&[SEED_MY1, SEED_MY3]);
+            {
+                let cmd = copy_command::new();
+                cmd.execute(&ctx, &copy_params(DID_MY1)).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let dids = Did::list(&wallet).unwrap();
+            assert_eq!(1, dids.len());
+            assert_eq!(DID_MY1, dids[0].did);
+            delete_source_wallet();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn copy_works_for_existing_did() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            create_source_wallet(&[SEED_MY1, SEED_MY3]);
+            {
+                let cmd = copy_command::new();
+                cmd.execute(&ctx, &copy_params("all")).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            assert_eq!(2, Did::list(&wallet).unwrap().len());
+            get_did_info(&ctx, DID_MY3);
+            delete_source_wallet();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn copy_works_for_invalid_key() {
+            let ctx = setup_with_wallet();
+            create_source_wallet(&[SEED_MY1]);
+            {
+                let cmd = copy_command::new();
+                let mut params = copy_params("all");
+                params.insert("from_key", "invalid".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            delete_source_wallet();
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    params_parser::ParamParser,
+    tools::did::Did,
+};
+
+pub mod delete_command {
+    use super::*;
+    use crate::ledger::get_nym_data;
+
+    command!(
+        CommandMetadata::build("delete", "Delete DID and its keys from the wallet.")
+            .add_main_param_with_dynamic_completion(
+                "did",
+                "Did stored in wallet",
+                DynamicCompletionType::Did
+            )
+            .add_optional_param(
+                "force",
+                "Delete DID even if it is written on the connected Ledger (False by default)"
+            )
+            .add_example("did delete VsKV7grR1BUE29mG2Fm2kX")
+            .add_example("did delete VsKV7grR1BUE29mG2Fm2kX force=true")
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?}, params {:?}", ctx, params);
+
+        let store = ctx.ensure_opened_wallet()?;
+        let did = ParamParser::get_did_param("did", params)?;
+        let force = ParamParser::get_opt_bool_param("force", params)?.unwrap_or(false);
+
+        Did::get(&store, &did).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        if let Some(active_did) = ctx.get_active_did()? {
+            if *active_did == did {
+                println_err!(
+                    "DID \"{}\" is active. Use another DID before deleting this one.",
+                    did
+                );
+                return Err(());
+            }
+        }
+
+        match ctx.get_connected_pool() {
+            Some(pool) => {
+                if get_nym_data(&pool, None, &did)?.is_some() {
+                    if !force {
+                        println_err!(
+                            "DID \"{}\" is written on the Ledger of pool \"{}\". \
+                            Its keys will be lost and the DID can no longer be updated. \
+                            Use force=true to delete it anyway.",
+                            did,
+                            pool.name
+                        );
+                        return Err(());
+                    }
+                    println_warn!(
+                        "DID \"{}\" is written on the Ledger of pool \"{}\" and can no longer be updated after deletion.",
+                        did,
+                        pool.name
+                    );
+                }
+            }
+            None => {
+                println_warn!(
+                    "There is no connected pool. Unable to check whether DID \"{}\" is written on the Ledger.",
+                    did
+                );
+            }
+        }
+
+        Did::delete(&store, &did).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("Did \"{}\" has been deleted", did);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{
+            setup_with_wallet, setup_with_wallet_and_pool, tear_down_with_wallet,
+            tear_down_with_wallet_and_pool,
+        },
+        did::tests::{
+            get_did_info, new_did, use_did, DID_MY1, DID_TRUSTEE, SEED_MY1, SEED_TRUSTEE,
+            VERKEY_MY1,
+        },
+        utils::futures::block_on,
+    };
+    use indy_utils::did::DidValue;
+
+    mod did_delete {
+        use super::*;
+
+        #[test]
+        pub fn delete_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_MY1.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Did::get(&wallet, &DidValue(DID_MY1.to_string())).unwrap_err();
+            assert!(block_on(wallet.fetch_key_entry(VERKEY_MY1))
+                .unwrap()
+                .is_none());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn delete_works_for_active_did() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            use_did(&ctx, DID_MY1);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_MY1.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            get_did_info(&ctx, DID_MY1);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn delete_works_for_unknown_did() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_MY1.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn delete_works_for_did_written_on_ledger() {
+            let ctx = setup_with_wallet_and_pool();
+            new_did(&ctx, SEED_TRUSTEE);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            get_did_info(&ctx, DID_TRUSTEE);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("force", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Did::get(&wallet, &DidValue(DID_TRUSTEE.to_string())).unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::did::Did,
+    utils::table::print_list_table,
+};
+
+pub mod find_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "find",
+        "Find my DIDs stored in the opened wallet by a structured metadata field. \
+            Only fields set by `did set-metadata` with `merge=true` can be searched."
+    )
+    .add_required_param("meta_key", "Name of the metadata field")
+    .add_required_param("meta_value", "Value of the metadata field")
+    .add_example("did find meta_key=team meta_value=issuers")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let store = ctx.ensure_opened_wallet()?;
+        let meta_key = ParamParser::get_str_param("meta_key", params)?;
+        let meta_value = ParamParser::get_str_param("meta_value", params)?;
+
+        let mut dids = Did::find_by_metadata(&store, meta_key, meta_value)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        for did_info in dids.iter_mut() {
+            did_info.verkey = Did::abbreviate_verkey(&did_info.did, &did_info.verkey)
+                .unwrap_or_else(|_| did_info.verkey.clone());
+        }
+
+        print_list_table(
+            &dids
+                .iter()
+                .map(|did| json!(did))
+                .collect::<Vec<serde_json::Value>>(),
+            &[
+                ("did", "Did"),
+                ("verkey", "Verkey"),
+                ("metadata", "Metadata"),
+            ],
+            &format!(
+                "There are no dids with metadata \"{}\" equal to \"{}\"",
+                meta_key, meta_value
+            ),
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod did_find {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet, tear_down_with_wallet},
+            did::{
+                set_metadata_command,
+                tests::{new_did, DID_TRUSTEE, SEED_MY1, SEED_TRUSTEE},
+            },
+            tools::did::Did,
+        };
+
+        #[test]
+        pub fn find_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            new_did(&ctx, SEED_MY1);
+            {
+                let cmd = set_metadata_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("metadata", "team=issuers".to_string());
+                params.insert("merge", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = find_command::new();
+                let mut params = CommandParams::new();
+                params.insert("meta_key", "team".to_string());
+                params.insert("meta_value", "issuers".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let dids = Did::find_by_metadata(&wallet, "team", "issuers").unwrap();
+            assert_eq!(1, dids.len());
+            assert_eq!(DID_TRUSTEE, dids[0].did);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn find_works_for_no_matches() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            {
+                let cmd = find_command::new();
+                let mut params = CommandParams::new();
+                params.insert("meta_key", "team".to_string());
+                params.insert("meta_value", "issuers".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
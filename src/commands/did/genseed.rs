@@ -0,0 +1,153 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::did::{seed::Seed, Did},
+    utils::table::print_table,
+};
+
+pub mod genseed_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "genseed",
+        "Generate a random seed and print it together with the DID and verkey derived from it. \
+            Nothing is stored into the wallet unless `store` is set."
+    )
+    .add_optional_param(
+        "store",
+        "Create DID from the generated seed in the opened wallet (false by default)"
+    )
+    .add_optional_param("method", "Method name to create fully qualified DID")
+    .add_optional_param("metadata", "DID metadata")
+    .add_example("did genseed")
+    .add_example("did genseed store=true metadata=steward")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let store = ParamParser::get_opt_bool_param("store", params)?.unwrap_or(false);
+        let method = ParamParser::get_opt_str_param("method", params)?;
+        let metadata = ParamParser::get_opt_empty_str_param("metadata", params)?;
+
+        let seed = Seed::generate();
+
+        let (did, verkey) = if store {
+            let wallet = ctx.ensure_opened_wallet()?;
+            Did::create(
+                &wallet,
+                None,
+                Some(&seed.to_hex()),
+                metadata,
+                method,
+                None,
+                None,
+            )
+        } else {
+            Did::derive(&seed)
+        }
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        print_table(
+            &json!({
+                "hex": seed.to_hex(),
+                "base64": seed.to_base64(),
+                "did": did,
+                "verkey": verkey,
+            }),
+            &[
+                ("hex", "Seed (hex)"),
+                ("base64", "Seed (base64)"),
+                ("did", "Did"),
+                ("verkey", "Verkey"),
+            ],
+        );
+
+        if store {
+            println_succ!(
+                "Did \"{}\" has been created with \"{}\" verkey",
+                did,
+                verkey
+            );
+        }
+        println_warn!("Keep the seed secret: anyone knowing it can act on behalf of the DID.");
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod did_genseed {
+        use super::*;
+        use crate::{
+            commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+            did::tests::{DID_TRUSTEE, SEED_TRUSTEE, VERKEY_TRUSTEE},
+        };
+
+        #[test]
+        pub fn genseed_works() {
+            let ctx = setup();
+            {
+                let cmd = genseed_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn genseed_works_for_store() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = genseed_command::new();
+                let mut params = CommandParams::new();
+                params.insert("store", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            assert_eq!(1, Did::list(&wallet).unwrap().len());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn genseed_works_for_store_without_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = genseed_command::new();
+                let mut params = CommandParams::new();
+                params.insert("store", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn printed_seed_can_be_parsed() {
+            let seed = Seed::generate();
+            assert_eq!(
+                seed.value(),
+                Seed::from_str(&seed.to_hex()).unwrap().value()
+            );
+            assert_eq!(
+                seed.value(),
+                Seed::from_str(&seed.to_base64()).unwrap().value()
+            );
+        }
+
+        #[test]
+        pub fn derive_works() {
+            let seed = Seed::from_str(SEED_TRUSTEE).unwrap();
+            let (did, verkey) = Did::derive(&seed).unwrap();
+            assert_eq!(DID_TRUSTEE, did);
+            assert_eq!(VERKEY_TRUSTEE, verkey);
+        }
+    }
+}
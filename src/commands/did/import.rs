@@ -3,31 +3,44 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
-    tools::did::Did,
+    tools::{did::Did, wallet::Wallet},
 };
 
+use super::alias::{is_valid_alias_name, DID_ALIAS_PREFIX};
+use indy_utils::did::DidValue;
+use serde_json::Value as JsonValue;
+
 pub mod import_command {
     use super::*;
-    use crate::utils::file::read_file;
+    use crate::utils::{csv::parse_line, file::read_file, table::print_list_table};
+
+    /// Columns of CSV import file. The first line of the file must contain their names.
+    const CSV_COLUMNS: [&str; 4] = ["seed", "did", "alias", "metadata"];
 
     #[derive(Debug, Deserialize)]
     struct DidImportConfig {
         version: usize,
-        dids: Vec<DidImportInfo>,
+        dids: Vec<DidImportEntry>,
     }
 
     #[derive(Debug, Deserialize)]
-    struct DidImportInfo {
+    struct DidImportEntry {
         did: Option<String>,
         seed: String,
+        alias: Option<String>,
+        metadata: Option<JsonValue>,
     }
 
     command!(CommandMetadata::build(
         "import",
         "Import DIDs entities from file to the current wallet.
-        File format:
+        Every DID is imported independently: failed entries are reported in the summary table and do not abort the import.
+        File formats:
+        - JSON array: [{\"seed\": \"UTF-8, base64 or hex string\", \"did\": \"did\", \"alias\": \"alias\", \"metadata\": \"metadata\"}]
+        - CSV file with header line: seed,did,alias,metadata (only `seed` column is required)
+        - Legacy format:
         {
             \"version\": 1,
             \"dids\": [{
@@ -37,6 +50,9 @@ pub mod import_command {
         }"
     )
     .add_main_param("file", "Path to file with DIDs")
+    .add_example("did import /home/dids.json")
+    .add_example("did import /home/dids.csv")
+    .add_precondition(Precondition::OpenedWallet)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -49,27 +65,60 @@ pub mod import_command {
         let data = read_file(path)
             .map_err(|_| println_err!("Unable to read DID import config from the provided file"))?;
 
-        let config: DidImportConfig = serde_json::from_str(&data)
-            .map_err(|_| println_err!("Unable to read DID import config from the provided file"))?;
+        let entries = parse_entries(&data)?;
 
-        if config.version != 1 {
-            println_err!("Unsupported DID import config version");
-            return Err(());
-        }
+        let mut results = Vec::new();
+        let mut failed = 0;
 
-        for did in config.dids {
-            let (did, vk) = Did::create(
-                &store,
-                did.did.as_ref().map(String::as_str),
-                Some(&did.seed),
-                None,
-                None,
-            )
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+        for (index, entry) in entries.iter().enumerate() {
+            let alias = entry.alias.as_ref().map(|alias| {
+                format!(
+                    "{}{}",
+                    DID_ALIAS_PREFIX,
+                    alias.trim_start_matches(DID_ALIAS_PREFIX)
+                )
+            });
+            let result = match import_entry(&store, entry) {
+                Ok((did, verkey)) => json!({
+                    "entry": index + 1,
+                    "did": did,
+                    "verkey": verkey,
+                    "alias": alias,
+                    "result": "Created",
+                }),
+                Err(err) => {
+                    failed += 1;
+                    json!({
+                        "entry": index + 1,
+                        "did": entry.did,
+                        "alias": alias,
+                        "result": format!("Failed: {}", err),
+                    })
+                }
+            };
+            results.push(result);
+        }
 
-            let vk = Did::abbreviate_verkey(&did, &vk).unwrap_or(vk);
+        print_list_table(
+            &results,
+            &[
+                ("entry", "Entry"),
+                ("did", "Did"),
+                ("verkey", "Verkey"),
+                ("alias", "Alias"),
+                ("result", "Result"),
+            ],
+            "There are no DIDs to import",
+        );
 
-            println_succ!("Did \"{}\" has been created with \"{}\" verkey", did, vk)
+        if failed > 0 {
+            println_err!(
+                "{} of {} DID(s) have been imported, {} failed",
+                entries.len() - failed,
+                entries.len(),
+                failed
+            );
+            return Err(());
         }
 
         println_succ!("DIDs import finished");
@@ -77,4 +126,238 @@ pub mod import_command {
         trace!("execute << ");
         Ok(())
     }
+
+    /// Detects the file format by its first character: `{` for the legacy config,
+    /// `[` for JSON array of entries, CSV otherwise.
+    fn parse_entries(data: &str) -> Result<Vec<DidImportEntry>, ()> {
+        let content = data.trim_start();
+
+        if content.starts_with('{') {
+            let config: DidImportConfig = serde_json::from_str(content).map_err(|_| {
+                println_err!("Unable to read DID import config from the provided file")
+            })?;
+
+            if config.version != 1 {
+                println_err!("Unsupported DID import config version");
+                return Err(());
+            }
+            return Ok(config.dids);
+        }
+
+        if content.starts_with('[') {
+            return serde_json::from_str(content).map_err(|err| {
+                println_err!("Unable to read DIDs from the provided file: {}", err)
+            });
+        }
+
+        parse_csv_entries(content)
+    }
+
+    fn parse_csv_entries(content: &str) -> Result<Vec<DidImportEntry>, ()> {
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        let header = match lines.next() {
+            Some(header) => parse_line(header),
+            None => return Ok(Vec::new()),
+        };
+        if let Some(column) = header
+            .iter()
+            .find(|column| !CSV_COLUMNS.contains(&column.to_lowercase().as_str()))
+        {
+            println_err!(
+                "Unexpected CSV column \"{}\". The first line must contain names of the columns: {}.",
+                column,
+                CSV_COLUMNS.join(", ")
+            );
+            return Err(());
+        }
+        let position = |name: &str| {
+            header
+                .iter()
+                .position(|column| column.eq_ignore_ascii_case(name))
+        };
+        let seed_position = position("seed").ok_or_else(|| {
+            println_err!("CSV file must contain \"seed\" column");
+        })?;
+
+        Ok(lines
+            .map(|line| {
+                let fields = parse_line(line);
+                let field = |position: Option<usize>| {
+                    position
+                        .and_then(|position| fields.get(position))
+                        .filter(|value| !value.is_empty())
+                        .map(String::to_string)
+                };
+                DidImportEntry {
+                    did: field(position("did")),
+                    seed: field(Some(seed_position)).unwrap_or_default(),
+                    alias: field(position("alias")),
+                    metadata: field(position("metadata")).map(JsonValue::String),
+                }
+            })
+            .collect())
+    }
+
+    /// Creates the DID together with its alias: the DID is removed again if the alias can't be set.
+    fn import_entry(store: &Wallet, entry: &DidImportEntry) -> Result<(String, String), String> {
+        if entry.seed.is_empty() {
+            return Err("seed is missing".to_string());
+        }
+
+        let alias = entry
+            .alias
+            .as_deref()
+            .map(|alias| alias.trim_start_matches(DID_ALIAS_PREFIX));
+        if let Some(alias) = alias {
+            if !is_valid_alias_name(alias) {
+                return Err(format!(
+                    "invalid alias name \"{}\". Only letters, digits, `_`, `-` and `.` are allowed",
+                    alias
+                ));
+            }
+            if let Some(did) = Did::get_alias(store, alias).map_err(|err| err.message(None))? {
+                return Err(format!(
+                    "alias \"{}{}\" is already assigned to DID \"{}\"",
+                    DID_ALIAS_PREFIX, alias, did
+                ));
+            }
+        }
+
+        let metadata = entry.metadata.as_ref().map(|metadata| {
+            metadata
+                .as_str()
+                .map(String::from)
+                .unwrap_or_else(|| metadata.to_string())
+        });
+
+        let (did, verkey) = Did::create(
+            store,
+            entry.did.as_deref(),
+            Some(&entry.seed),
+            metadata.as_deref(),
+            None,
+            None,
+            None,
+        )
+        .map_err(|err| err.message(None))?;
+
+        if let Some(alias) = alias {
+            if let Err(err) = Did::set_alias(store, alias, &DidValue(did.clone())) {
+                Did::delete(store, &DidValue(did)).ok();
+                return Err(err.message(None));
+            }
+        }
+
+        let verkey = Did::abbreviate_verkey(&did, &verkey).unwrap_or(verkey);
+        Ok((did, verkey))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::alias::resolve_did_alias,
+        utils::{environment::EnvironmentUtils, file::write_file},
+    };
+
+    mod did_import {
+        use super::*;
+
+        const SEED_1: &str = "000000000000000000000000000Seed1";
+        const SEED_2: &str = "000000000000000000000000000Seed2";
+
+        fn import(ctx: &CommandContext, content: &str) -> Result<(), ()> {
+            let path = EnvironmentUtils::tmp_file_path("did_import");
+            let path = path.to_str().unwrap();
+            write_file(path, content).unwrap();
+
+            let cmd = import_command::new();
+            let mut params = CommandParams::new();
+            params.insert("file", path.to_string());
+            cmd.execute(ctx, &params)
+        }
+
+        fn dids(ctx: &CommandContext) -> usize {
+            Did::list(&ctx.ensure_opened_wallet().unwrap())
+                .unwrap()
+                .len()
+        }
+
+        #[test]
+        pub fn import_works_for_legacy_format() {
+            let ctx = setup_with_wallet();
+            import(
+                &ctx,
+                &json!({ "version": 1, "dids": [{ "seed": SEED_1 }] }).to_string(),
+            )
+            .unwrap();
+            assert_eq!(1, dids(&ctx));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn import_works_for_json_array() {
+            let ctx = setup_with_wallet();
+            import(
+                &ctx,
+                &json!([
+                    { "seed": SEED_1, "alias": "issuer", "metadata": "Issuer DID" },
+                    { "seed": SEED_2 }
+                ])
+                .to_string(),
+            )
+            .unwrap();
+            assert_eq!(2, dids(&ctx));
+            resolve_did_alias(&ctx, "issuer").unwrap();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn import_works_for_csv() {
+            let ctx = setup_with_wallet();
+            import(
+                &ctx,
+                &format!(
+                    "seed,alias,metadata\n{},issuer,\"Issuer, main\"\n{},,\n",
+                    SEED_1, SEED_2
+                ),
+            )
+            .unwrap();
+            assert_eq!(2, dids(&ctx));
+            resolve_did_alias(&ctx, "issuer").unwrap();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn import_works_for_failed_entries() {
+            let ctx = setup_with_wallet();
+            import(
+                &ctx,
+                &json!([
+                    { "seed": "short" },
+                    { "seed": SEED_1, "alias": "issuer" },
+                    { "seed": SEED_2, "alias": "issuer" }
+                ])
+                .to_string(),
+            )
+            .unwrap_err();
+            // invalid entries do not abort the import and leave nothing behind
+            assert_eq!(1, dids(&ctx));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn import_works_for_unknown_csv_column() {
+            let ctx = setup_with_wallet();
+            import(&ctx, &format!("seed,role\n{},TRUSTEE\n", SEED_1)).unwrap_err();
+            assert_eq!(0, dids(&ctx));
+            tear_down_with_wallet(&ctx);
+        }
+    }
 }
@@ -3,43 +3,63 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
     tools::did::Did,
-    utils::table::print_list_table,
+    utils::table::print_or_export_list_table,
 };
 
 pub mod list_command {
     use super::*;
 
     command!(
-        CommandMetadata::build("list", "List my DIDs stored in the opened wallet.").finalize()
+        CommandMetadata::build("list", "List my DIDs stored in the opened wallet.")
+            .add_optional_param(
+                "out",
+                "Path to the file to write the list into instead of printing the table"
+            )
+            .add_optional_param(
+                "format",
+                "Format of the `out` file. One of: csv, json (json by default)"
+            )
+            .add_example("did list")
+            .add_example("did list out=/home/dids.csv format=csv")
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
     );
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
+        let out = ParamParser::get_opt_str_param("out", params)?;
+        let format = ParamParser::get_opt_str_param("format", params)?;
+
         let store = ctx.ensure_opened_wallet()?;
 
-        let mut dids = Did::list(&store).map_err(|err| println_err!("{}", err.message(None)))?;
+        let mut dids = Did::list(&store).map_err(|err| println_err!("{}", err.report(None)))?;
 
         for did_info in dids.iter_mut() {
             did_info.verkey = Did::abbreviate_verkey(&did_info.did, &did_info.verkey)
                 .unwrap_or_else(|_| did_info.verkey.clone());
         }
 
-        print_list_table(
+        let mut headers = vec![("did", "Did"), ("verkey", "Verkey")];
+        if dids.iter().any(|did| did.key_agreement.is_some()) {
+            headers.push(("key_agreement", "Key agreement"));
+        }
+        headers.push(("metadata", "Metadata"));
+
+        print_or_export_list_table(
             &dids
                 .iter()
                 .map(|did| json!(did))
                 .collect::<Vec<serde_json::Value>>(),
-            &[
-                ("did", "Did"),
-                ("verkey", "Verkey"),
-                ("metadata", "Metadata"),
-            ],
+            &headers,
             "There are no dids",
-        );
-        if let Some(cur_did) = ctx.get_active_did()? {
+            out,
+            format,
+        )?;
+        if let (None, Some(cur_did)) = (out, ctx.get_active_did()?) {
             println_succ!("Current did \"{}\"", cur_did);
         }
 
@@ -65,7 +85,8 @@ pub mod tests {
         use super::*;
         use crate::{
             commands::{setup_with_wallet, tear_down, tear_down_with_wallet},
-            did::tests::{new_did, SEED_TRUSTEE},
+            did::tests::{new_did, DID_TRUSTEE, SEED_TRUSTEE},
+            utils::{environment::EnvironmentUtils, file::read_file},
             wallet::tests::close_and_delete_wallet,
         };
 
@@ -81,6 +102,37 @@ pub mod tests {
             tear_down_with_wallet(&ctx);
         }
 
+        #[test]
+        pub fn list_works_for_csv_out() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            let path = EnvironmentUtils::tmp_file_path("dids.csv");
+            {
+                let cmd = list_command::new();
+                let mut params = CommandParams::new();
+                params.insert("out", path.to_str().unwrap().to_string());
+                params.insert("format", "csv".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let content = read_file(&path).unwrap();
+            let lines = content.lines().collect::<Vec<&str>>();
+            assert_eq!("did,verkey,metadata", lines[0]);
+            assert!(lines[1].starts_with(DID_TRUSTEE));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn list_works_for_format_without_out() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = list_command::new();
+                let mut params = CommandParams::new();
+                params.insert("format", "csv".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
         #[test]
         pub fn list_works_for_empty_result() {
             let ctx = setup_with_wallet();
@@ -4,6 +4,12 @@
 */
 use crate::command_executor::{CommandGroup, CommandGroupMetadata};
 
+pub mod alias;
+pub mod convert_verkey;
+pub mod copy;
+pub mod delete;
+pub mod find;
+pub mod genseed;
 pub mod import;
 pub mod list;
 pub mod new;
@@ -13,16 +19,20 @@ pub mod set_metadata;
 pub mod use_did;
 
 pub use self::{
-    import::*, list::*, new::*, qualify::*, rotate_key::*, set_metadata::*, use_did::*,
+    alias::*, convert_verkey::*, copy::*, delete::*, find::*, genseed::*, import::*, list::*, new::*,
+    qualify::*, rotate_key::*, set_metadata::*, use_did::*,
 };
 
 pub mod group {
     use super::*;
 
-    command_group!(CommandGroupMetadata::new(
-        "did",
-        "Identity management commands"
-    ));
+    command_group!(
+        CommandGroupMetadata::new("did", "Identity management commands")
+            .add_example("did new seed=00000000000000000000000000000My1 metadata=did_metadata")
+            .add_example("did new")
+            .add_example("did list")
+            .add_example("did use VsKV7grR1BUE29mG2Fm2kX")
+    );
 }
 
 #[cfg(test)]
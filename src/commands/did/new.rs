@@ -3,11 +3,28 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    ledger::{
+        get_nym_data, ha_endpoint_attrib, handle_transaction_response, set_author_agreement,
+        sign_and_submit_request,
+    },
     params_parser::ParamParser,
-    tools::did::Did,
+    tools::{
+        did::{key::Key, Did, KeyAgreement},
+        ledger::{Ledger, Response},
+        pool::Pool,
+        wallet::Wallet,
+    },
 };
 
+use indy_utils::did::DidValue;
+use indy_vdr::{
+    ledger::constants::{ENDORSER, STEWARD, TRUSTEE},
+    pool::PreparedRequest,
+};
+use serde_json::Value as JsonValue;
+use std::rc::Rc;
+
 pub mod new_command {
     use super::*;
 
@@ -19,11 +36,38 @@ pub mod new_command {
         )
         .add_optional_param("method", "Method name to create fully qualified DID")
         .add_optional_param("metadata", "DID metadata")
+        .add_optional_param(
+            "key_agreement",
+            "Create X25519 key agreement key: `derive` from the signing key or `generate` a separate one"
+        )
         .add_example("did new")
         .add_example("did new did=VsKV7grR1BUE29mG2Fm2kX")
         .add_example("did new did=VsKV7grR1BUE29mG2Fm2kX method=indy")
         .add_example("did new did=VsKV7grR1BUE29mG2Fm2kX seed=00000000000000000000000000000My1")
         .add_example("did new seed=00000000000000000000000000000My1 metadata=did_metadata")
+        .add_optional_param(
+            "publish",
+            "Send NYM transaction for the created DID to the connected Ledger using the active DID as submitter (False by default).
+                        The active DID must be TRUSTEE, STEWARD or ENDORSER on the Ledger"
+        )
+        .add_optional_param(
+            "role",
+            "Role of the published DID. One of: STEWARD, TRUSTEE, TRUST_ANCHOR, ENDORSER, NETWORK_MONITOR or associated number (requires publish=true)"
+        )
+        .add_optional_param(
+            "endpoint",
+            "Endpoint address (host:port) of the published DID sent as ATTRIB transaction signed by the created DID (requires publish=true)"
+        )
+        .add_optional_param(
+            "key_alg",
+            "Algorithm of the DID signing key. One of: ed25519 (used by default), secp256k1.
+                        Note that Indy Ledger accepts only ed25519 signatures"
+        )
+        .add_example("did new key_agreement=derive")
+        .add_example("did new key_alg=secp256k1")
+        .add_example("did new publish=true")
+        .add_example("did new seed=00000000000000000000000000000My1 publish=true role=ENDORSER endpoint=127.0.0.1:5555")
+        .add_precondition(Precondition::OpenedWallet)
         .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -35,28 +79,164 @@ pub mod new_command {
         let seed = ParamParser::get_opt_str_param("seed", params)?;
         let method = ParamParser::get_opt_str_param("method", params)?;
         let metadata = ParamParser::get_opt_empty_str_param("metadata", params)?;
+        let key_agreement = ParamParser::get_opt_str_param("key_agreement", params)?
+            .map(KeyAgreement::from_str)
+            .transpose()
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+        let key_alg = ParamParser::get_opt_str_param("key_alg", params)?
+            .map(Key::parse_alg)
+            .transpose()
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
-        let (did, vk) = Did::create(&store, did, seed, metadata, method)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+        let publish = ParamParser::get_opt_bool_param("publish", params)?.unwrap_or(false);
+        let role = ParamParser::get_opt_str_param("role", params)?;
+        let endpoint = ParamParser::get_opt_str_param("endpoint", params)?;
+
+        let publication = if publish {
+            Some(prepare_publication(ctx, endpoint)?)
+        } else {
+            if role.is_some() || endpoint.is_some() {
+                println_err!(
+                    "Parameters \"role\" and \"endpoint\" can be used only with publish=true"
+                );
+                return Err(());
+            }
+            None
+        };
+
+        let (did, vk) = Did::create(&store, did, seed, metadata, method, key_agreement, key_alg)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let vk = Did::abbreviate_verkey(&did, &vk).unwrap_or(vk);
 
         println_succ!("Did \"{}\" has been created with \"{}\" verkey", did, vk);
 
+        if key_agreement.is_some() {
+            if let Ok(did_info) = Did::get(&store, &DidValue(did.to_string())) {
+                if let Some(key_agreement) = did_info.key_agreement {
+                    println_succ!("Key agreement key \"{}\" has been created", key_agreement);
+                }
+            }
+        }
+
+        if let Some(publication) = publication {
+            publish_did(
+                ctx,
+                &store,
+                &publication,
+                &DidValue(did.to_string()),
+                &vk,
+                role,
+            )?;
+        }
+
         trace!("execute <<");
         Ok(())
     }
+
+    struct Publication {
+        pool: Rc<Pool>,
+        submitter_did: Rc<DidValue>,
+        endpoint: Option<JsonValue>,
+    }
+
+    /// Checks that the DID can be published before creating it:
+    /// a pool is connected and the active DID has a role allowed to write NYMs.
+    fn prepare_publication(
+        ctx: &CommandContext,
+        endpoint: Option<&str>,
+    ) -> Result<Publication, ()> {
+        let pool = ctx.ensure_connected_pool()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let endpoint = endpoint.map(ha_endpoint_attrib).transpose()?;
+
+        let submitter_role = get_nym_data(&pool, None, &submitter_did)?
+            .and_then(|data| data["role"].as_str().map(String::from));
+
+        match submitter_role.as_deref() {
+            Some(TRUSTEE) | Some(STEWARD) | Some(ENDORSER) => Ok(Publication {
+                pool,
+                submitter_did,
+                endpoint,
+            }),
+            _ => {
+                println_err!(
+                    "Active DID \"{}\" must be TRUSTEE, STEWARD or ENDORSER on the Ledger to publish the DID",
+                    submitter_did
+                );
+                Err(())
+            }
+        }
+    }
+
+    fn publish_did(
+        ctx: &CommandContext,
+        store: &Wallet,
+        publication: &Publication,
+        did: &DidValue,
+        verkey: &str,
+        role: Option<&str>,
+    ) -> Result<(), ()> {
+        let pool: &Pool = &publication.pool;
+        let submitter_did: &DidValue = &publication.submitter_did;
+        let mut request =
+            Ledger::build_nym_request(Some(pool), submitter_did, did, Some(verkey), None, role)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
+        let result = submit_request(ctx, store, pool, submitter_did, &mut request)?;
+        println_succ!(
+            "Did \"{}\" has been published to the Ledger (seqNo {})",
+            did,
+            result["txnMetadata"]["seqNo"]
+        );
+
+        if let Some(endpoint) = publication.endpoint.as_ref() {
+            // ATTRIB can be written only by the DID owner
+            let mut request =
+                Ledger::build_attrib_request(Some(pool), did, did, None, Some(endpoint), None)
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+            let result = submit_request(ctx, store, pool, did, &mut request)?;
+            println_succ!(
+                "Endpoint of Did \"{}\" has been set on the Ledger (seqNo {})",
+                did,
+                result["txnMetadata"]["seqNo"]
+            );
+        }
+
+        Ok(())
+    }
+
+    fn submit_request(
+        ctx: &CommandContext,
+        store: &Wallet,
+        pool: &Pool,
+        submitter_did: &DidValue,
+        request: &mut PreparedRequest,
+    ) -> Result<JsonValue, ()> {
+        set_author_agreement(ctx, request)?;
+        let response = sign_and_submit_request(ctx, store, pool, submitter_did, request)
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        let response = serde_json::from_str::<Response<JsonValue>>(&response)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+        handle_transaction_response(response)
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::tools::did::DidInfo;
+    use crate::{tools::did::DidInfo, utils::futures::block_on};
     mod did_new {
         use super::*;
         use crate::{
-            commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
-            did::tests::{get_did_info, DID_TRUSTEE, SEED_TRUSTEE, VERKEY_TRUSTEE},
+            commands::{
+                setup, setup_with_wallet, setup_with_wallet_and_pool, tear_down,
+                tear_down_with_wallet, tear_down_with_wallet_and_pool,
+            },
+            did::tests::{
+                get_did_info, new_did, use_did, DID_MY1, DID_TRUSTEE, SEED_MY1, SEED_TRUSTEE,
+                VERKEY_TRUSTEE,
+            },
+            ledger::tests::use_trustee,
         };
 
         #[test]
@@ -104,6 +284,115 @@ pub mod tests {
             tear_down_with_wallet(&ctx);
         }
 
+        #[test]
+        pub fn new_works_for_derived_key_agreement() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("seed", SEED_TRUSTEE.to_string());
+                params.insert("key_agreement", "derive".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let did = get_did_info(&ctx, DID_TRUSTEE);
+            assert!(did.key_agreement.is_some());
+            assert_ne!(did.key_agreement, Some(did.verkey));
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_generated_key_agreement() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key_agreement", "generate".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let dids = get_dids(&ctx);
+            assert!(dids[0].key_agreement.is_some());
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_invalid_key_agreement() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key_agreement", "other".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_dids(&ctx).is_empty());
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_secp256k1_key() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("seed", SEED_MY1.to_string());
+                params.insert("key_alg", "secp256k1".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let dids = get_dids(&ctx);
+            assert_eq!(1, dids.len());
+            assert_eq!("secp256k1", dids[0].verkey_type);
+            assert_ne!(DID_MY1, dids[0].did);
+
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                let message = b"message";
+                let signature =
+                    block_on(Did::sign(&wallet, &dids[0].did, message.as_slice())).unwrap();
+                let valid = Key::verify_message(
+                    Some(&wallet),
+                    Some(&dids[0].verkey),
+                    None,
+                    message.as_slice(),
+                    &signature,
+                )
+                .unwrap();
+                assert!(valid);
+            }
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_unsupported_key_alg() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key_alg", "x25519".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_dids(&ctx).is_empty());
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_secp256k1_key_and_derived_key_agreement() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("key_alg", "secp256k1".to_string());
+                params.insert("key_agreement", "derive".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_dids(&ctx).is_empty());
+
+            tear_down_with_wallet(&ctx);
+        }
+
         #[test]
         pub fn new_works_for_hex_seed() {
             let ctx = setup_with_wallet();
@@ -207,6 +496,67 @@ pub mod tests {
 
             tear_down_with_wallet(&ctx);
         }
+
+        #[test]
+        pub fn new_works_for_publish() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("publish", "true".to_string());
+                params.insert("role", "ENDORSER".to_string());
+                params.insert("endpoint", "127.0.0.1:5555".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(2, get_dids(&ctx).len());
+
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_publish_without_pool() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("publish", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_dids(&ctx).is_empty());
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_publish_by_not_privileged_did() {
+            let ctx = setup_with_wallet_and_pool();
+            new_did(&ctx, SEED_MY1);
+            use_did(&ctx, DID_MY1);
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("publish", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(1, get_dids(&ctx).len());
+
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn new_works_for_role_without_publish() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = new_command::new();
+                let mut params = CommandParams::new();
+                params.insert("role", "ENDORSER".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_dids(&ctx).is_empty());
+
+            tear_down_with_wallet(&ctx);
+        }
     }
 
     pub fn get_dids(ctx: &CommandContext) -> Vec<DidInfo> {
@@ -5,6 +5,7 @@
 use crate::{
     command_executor::{
         Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
     },
     params_parser::ParamParser,
     tools::did::Did,
@@ -27,6 +28,7 @@ pub mod qualify_command {
         "Method to apply to the DID. Note that `did:` prefix must be omitted`"
     )
     .add_example("did qualify VsKV7grR1BUE29mG2Fm2kX method=peer")
+    .add_precondition(Precondition::OpenedWallet)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -43,7 +45,7 @@ pub mod qualify_command {
         };
 
         let full_qualified_did = Did::qualify(&wallet, &did, &method)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         println_succ!("Fully qualified DID \"{}\"", full_qualified_did);
 
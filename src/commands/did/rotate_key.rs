@@ -3,7 +3,7 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
     tools::did::Did,
 };
@@ -25,6 +25,8 @@ pub mod rotate_key_command {
             .add_optional_param("resume", "Resume interrupted operation")
             .add_example("did rotate-key")
             .add_example("did rotate-key seed=00000000000000000000000000000My2")
+            .add_precondition(Precondition::OpenedWallet)
+            .add_precondition(Precondition::ActiveDid)
             .finalize()
     );
 
@@ -51,7 +53,7 @@ pub mod rotate_key_command {
             // get temp and current verkey from wallet.
 
             let did_info =
-                Did::get(&store, &did).map_err(|err| println_err!("{}", err.message(None)))?;
+                Did::get(&store, &did).map_err(|err| println_err!("{}", err.report(None)))?;
 
             let temp_verkey = did_info.next_verkey.ok_or_else(|| {
                 println_err!("Unable to resume, have you already run rotate-key?")
@@ -94,7 +96,7 @@ pub mod rotate_key_command {
             }?
         } else {
             let new_verkey = Did::replace_keys_start(&store, &did, seed)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
             (new_verkey, true)
         };
@@ -109,17 +111,17 @@ pub mod rotate_key_command {
                         println_err!("Use command `did rotate-key resume=true` to complete");
                     }
                     _ => {
-                        println_err!("{}", err.message(Some(&pool.name)));
+                        println_err!("{}", err.report(Some(&pool.name)));
                     }
                 },
                 _ => {
-                    println_err!("{}", err.message(Some(&pool.name)));
+                    println_err!("{}", err.report(Some(&pool.name)));
                 }
             })?;
         };
 
         Did::replace_keys_apply(&store, &did)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let vk = Did::abbreviate_verkey(&did, &new_verkey).unwrap_or(new_verkey);
 
@@ -5,18 +5,22 @@
 use crate::{
     command_executor::{
         Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
     },
     params_parser::ParamParser,
     tools::did::Did,
 };
 
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
 pub mod set_metadata_command {
     use super::*;
 
     command!(CommandMetadata::build(
         "set-metadata",
         "Updated metadata for a DID in the wallet.\
-            DID must be either passed as the parameter or set as the active."
+            DID must be either passed as the parameter or set as the active.\
+            The existing metadata is replaced unless `merge=true` is passed."
     )
     .add_optional_param_with_dynamic_completion(
         "did",
@@ -24,7 +28,12 @@ pub mod set_metadata_command {
         DynamicCompletionType::Did
     )
     .add_required_param("metadata", "Metadata to set.")
-    .add_example(r#"did set-metadata did=VsKV7grR1BUE29mG2Fm2kX metadata={"label":"Main"}"#)
+    .add_optional_param("merge", "Treat metadata as fields (JSON object or comma separated key=value pairs) and merge them with the existing ones. A field with an empty value is removed (False by default)")
+    .add_example(r#"did set-metadata did=VsKV7grR1BUE29mG2Fm2kX metadata="Main DID""#)
+    .add_example(r#"did set-metadata did=VsKV7grR1BUE29mG2Fm2kX metadata={"label":"Main"} merge=true"#)
+    .add_example(r#"did set-metadata did=VsKV7grR1BUE29mG2Fm2kX metadata=label=Main,team=issuers merge=true"#)
+    .add_example(r#"did set-metadata did=VsKV7grR1BUE29mG2Fm2kX metadata=team= merge=true"#)
+    .add_precondition(Precondition::OpenedWallet)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -33,6 +42,7 @@ pub mod set_metadata_command {
         let wallet = ctx.ensure_opened_wallet()?;
         let did_param = ParamParser::get_opt_did_param("did", params)?;
         let metadata = ParamParser::get_str_param("metadata", params)?;
+        let merge = ParamParser::get_opt_bool_param("merge", params)?.unwrap_or(false);
         let active_did = ctx.get_active_did()?;
 
         let did = match did_param {
@@ -42,12 +52,107 @@ pub mod set_metadata_command {
             })?,
         };
 
-        Did::set_metadata(&wallet, &did, metadata)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
-
-        println_succ!("DID Metadata updated");
+        if merge {
+            let fields = parse_metadata_fields(metadata).ok_or_else(|| {
+                println_err!("Metadata to merge must be either JSON object or comma separated key=value pairs")
+            })?;
+            let metadata = Did::merge_metadata(&wallet, &did, fields)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
+            println_succ!("DID Metadata updated: {}", metadata);
+        } else {
+            Did::set_metadata(&wallet, &did, metadata)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
+            println_succ!("DID Metadata updated");
+        }
 
         trace!("execute <<");
         Ok(())
     }
 }
+
+fn parse_metadata_fields(metadata: &str) -> Option<JsonMap<String, JsonValue>> {
+    if let Ok(fields) = serde_json::from_str::<JsonMap<String, JsonValue>>(metadata) {
+        return Some(fields);
+    }
+
+    let mut fields = JsonMap::new();
+    for pair in metadata.split(',') {
+        match pair.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                fields.insert(
+                    key.trim().to_string(),
+                    JsonValue::String(value.trim().to_string()),
+                );
+            }
+            _ => return None,
+        }
+    }
+    Some(fields)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{get_did_info, new_did, DID_TRUSTEE, SEED_TRUSTEE},
+    };
+
+    mod did_set_metadata {
+        use super::*;
+
+        fn set_metadata(ctx: &CommandContext, metadata: &str, merge: bool) -> Result<(), ()> {
+            let cmd = set_metadata_command::new();
+            let mut params = CommandParams::new();
+            params.insert("did", DID_TRUSTEE.to_string());
+            params.insert("metadata", metadata.to_string());
+            params.insert("merge", merge.to_string());
+            cmd.execute(&ctx, &params)
+        }
+
+        #[test]
+        pub fn set_metadata_works_for_free_form() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            set_metadata(&ctx, "Main DID", false).unwrap();
+            let did = get_did_info(&ctx, DID_TRUSTEE);
+            assert_eq!(did.metadata.unwrap(), "Main DID");
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn set_metadata_works_for_free_form_with_equal_sign() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            set_metadata(&ctx, "role=issuer, main", false).unwrap();
+            let did = get_did_info(&ctx, DID_TRUSTEE);
+            assert_eq!(did.metadata.unwrap(), "role=issuer, main");
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn set_metadata_works_for_merging_invalid_fields() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            set_metadata(&ctx, "Main DID", true).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn set_metadata_works_for_merging_fields() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            set_metadata(&ctx, r#"{"label":"Main"}"#, true).unwrap();
+            set_metadata(&ctx, "team=issuers", true).unwrap();
+            let fields = get_did_info(&ctx, DID_TRUSTEE).metadata_fields().unwrap();
+            assert_eq!(fields["label"], "Main");
+            assert_eq!(fields["team"], "issuers");
+
+            set_metadata(&ctx, "label=", true).unwrap();
+            let fields = get_did_info(&ctx, DID_TRUSTEE).metadata_fields().unwrap();
+            assert!(fields.get("label").is_none());
+            assert_eq!(fields["team"], "issuers");
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -5,22 +5,27 @@
 use crate::{
     command_executor::{
         Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
     },
     params_parser::ParamParser,
-    tools::did::Did,
+    tools::{did::Did, wallet::wallet_config::WalletConfig},
 };
 
 pub mod use_command {
     use super::*;
 
-    command!(CommandMetadata::build("use", "Use DID")
-        .add_main_param_with_dynamic_completion(
-            "did",
-            "Did stored in wallet",
-            DynamicCompletionType::Did
-        )
-        .add_example("did use VsKV7grR1BUE29mG2Fm2kX")
-        .finalize());
+    command!(CommandMetadata::build(
+        "use",
+        "Use DID. The DID is activated again when the wallet is opened next time"
+    )
+    .add_main_param_with_dynamic_completion(
+        "did",
+        "Did stored in wallet",
+        DynamicCompletionType::Did
+    )
+    .add_example("did use VsKV7grR1BUE29mG2Fm2kX")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?}, params {:?}", ctx, params);
@@ -29,9 +34,16 @@ pub mod use_command {
 
         let store = ctx.ensure_opened_wallet()?;
 
-        Did::get(&store, &did).map_err(|err| println_err!("{}", err.message(None)))?;
+        Did::get(&store, &did).map_err(|err| println_err!("{}", err.report(None)))?;
 
         println_succ!("Did \"{}\" has been set as active", did);
+        if let Err(err) = WalletConfig::store_active_did(&store.name, Some(&did)) {
+            println_warn!(
+                "Active DID can't be remembered for wallet \"{}\": {}",
+                store.name,
+                err
+            );
+        }
         ctx.set_active_did(did);
 
         trace!("execute <<");
@@ -51,7 +63,7 @@ pub mod tests {
                 new_command,
                 tests::{new_did, DID_TRUSTEE, SEED_TRUSTEE},
             },
-            wallet::tests::close_and_delete_wallet,
+            wallet::tests::{close_and_delete_wallet, WALLET},
         };
 
         #[test]
@@ -65,6 +77,10 @@ pub mod tests {
                 cmd.execute(&ctx, &params).unwrap();
             }
             assert_eq!(ctx.ensure_active_did().unwrap().to_string(), DID_TRUSTEE);
+            assert_eq!(
+                Some(DID_TRUSTEE.to_string()),
+                WalletConfig::read(WALLET).unwrap().active_did
+            );
             tear_down_with_wallet(&ctx);
         }
 
@@ -0,0 +1,152 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::did::key::Key,
+};
+
+pub mod create_command {
+    use super::*;
+
+    command!(
+        CommandMetadata::build("create", "Create new key and store it into the wallet")
+            .add_optional_param(
+                "name",
+                "Name to store the key under (base58 encoded public key by default)"
+            )
+            .add_optional_param(
+                "alg",
+                "Key algorithm. One of: ed25519, x25519, secp256k1 (ed25519 by default)"
+            )
+            .add_optional_deferred_param("seed", "Seed for creating the key (UTF-8, base64 or hex)")
+            .add_optional_param("metadata", "Key metadata")
+            .add_example("key create")
+            .add_example("key create name=signing-key")
+            .add_example("key create name=exchange-key alg=x25519")
+            .add_example(
+                "key create name=signing-key seed=00000000000000000000000000000My1 metadata=backup"
+            )
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, secret!(params));
+
+        let store = ctx.ensure_opened_wallet()?;
+
+        let name = ParamParser::get_opt_str_param("name", params)?;
+        let alg = ParamParser::get_opt_str_param("alg", params)?.unwrap_or("ed25519");
+        let seed = ParamParser::get_opt_str_param("seed", params)?;
+        let metadata = ParamParser::get_opt_empty_str_param("metadata", params)?;
+
+        let alg = Key::parse_alg(alg).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let key = Key::create_named(&store, name, alg, seed, metadata)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!(
+            "Key \"{}\" has been created with \"{}\" public key",
+            key.name,
+            key.verkey
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_create {
+        use super::*;
+        use crate::{
+            commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+            did::tests::{SEED_TRUSTEE, VERKEY_TRUSTEE},
+            key::tests::{create_key, get_keys, KEY_NAME},
+        };
+
+        #[test]
+        pub fn create_works() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = create_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let keys = get_keys(&ctx);
+            assert_eq!(1, keys.len());
+            assert_eq!(keys[0].name, keys[0].verkey);
+            assert_eq!("ed25519", keys[0].algorithm);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_works_for_seed_and_metadata() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("seed", SEED_TRUSTEE.to_string());
+                params.insert("metadata", "some metadata".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let keys = get_keys(&ctx);
+            assert_eq!(KEY_NAME, keys[0].name);
+            assert_eq!(VERKEY_TRUSTEE, keys[0].verkey);
+            assert_eq!(Some("some metadata".to_string()), keys[0].metadata);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_works_for_x25519() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, Some("x25519"));
+            let keys = get_keys(&ctx);
+            assert_eq!("x25519", keys[0].algorithm);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_works_for_duplicate_name() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, None);
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_works_for_unknown_alg() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("alg", "rsa".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = create_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
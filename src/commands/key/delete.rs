@@ -0,0 +1,115 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        wait_for_user_reply, Command, CommandContext, CommandMetadata, CommandParams, Precondition,
+    },
+    params_parser::ParamParser,
+    tools::did::{key::Key, Did},
+};
+
+pub mod delete_command {
+    use super::*;
+
+    command!(
+        CommandMetadata::build("delete", "Delete key from the wallet.")
+            .add_main_param("name", "Name of the key to delete")
+            .add_example("key delete signing-key")
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let store = ctx.ensure_opened_wallet()?;
+
+        let name = ParamParser::get_str_param("name", params)?;
+
+        let dids = Did::list(&store).map_err(|err| println_err!("{}", err.report(None)))?;
+        if let Some(did) = dids.iter().find(|did| {
+            did.verkey == name
+                || did.next_verkey.as_deref() == Some(name)
+                || did.key_agreement.as_deref() == Some(name)
+        }) {
+            println_err!(
+                "Key \"{}\" is used by DID \"{}\" and cannot be deleted.",
+                name,
+                did.did
+            );
+            return Err(());
+        }
+
+        println!(
+            "Would you like to delete key \"{}\"? It cannot be restored. (y/n)",
+            name
+        );
+        if !wait_for_user_reply(ctx) {
+            println!("The key has not been deleted.");
+            return Ok(());
+        }
+
+        Key::delete(&store, name).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("Key \"{}\" has been deleted", name);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_delete {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet, tear_down_with_wallet},
+            did::tests::{new_did, SEED_TRUSTEE, VERKEY_TRUSTEE},
+            key::tests::{create_key, get_keys, KEY_NAME},
+        };
+
+        #[test]
+        pub fn delete_works() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, None);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(get_keys(&ctx).is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn delete_works_for_unknown_key() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn delete_works_for_did_key() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", VERKEY_TRUSTEE.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(1, get_keys(&ctx).len());
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::did::key::Key,
+};
+
+pub mod gen_bls_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "gen-bls",
+        "Create BLS key of a pool node (BN254 curve used by indy-node) and store it into the wallet. \
+            Prints the public key and its proof of possession to pass as `blskey` and `blskey_pop` to `ledger node` command."
+    )
+    .add_optional_param(
+        "name",
+        "Name to store the key under (base58 encoded public key by default)"
+    )
+    .add_optional_deferred_param("seed", "Seed for creating the key (UTF-8, base64 or hex)")
+    .add_optional_param("metadata", "Key metadata")
+    .add_example("key gen-bls")
+    .add_example("key gen-bls name=node5-bls")
+    .add_example("key gen-bls name=node5-bls seed=000000000000000000000000000Node5")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, secret!(params));
+
+        let store = ctx.ensure_opened_wallet()?;
+
+        let name = ParamParser::get_opt_str_param("name", params)?;
+        let seed = ParamParser::get_opt_str_param("seed", params)?;
+        let metadata = ParamParser::get_opt_empty_str_param("metadata", params)?;
+
+        let key = Key::create_bls(&store, name, seed, metadata)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("BLS key \"{}\" has been created", key.name);
+        println!("blskey: {}", key.blskey);
+        println!("blskey_pop: {}", key.blskey_pop);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_gen_bls {
+        use super::*;
+        use crate::{
+            commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+            key::tests::{get_keys, KEY_NAME},
+            tools::did::constants::CATEGORY_BLS_KEY,
+            utils::futures::block_on,
+        };
+        use indy_blssignatures::VerKey;
+        use indy_utils::base58;
+
+        const SEED_NODE: &str = "000000000000000000000000000Node5";
+
+        #[test]
+        pub fn gen_bls_works() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = gen_bls_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("seed", SEED_NODE.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let record = block_on(wallet.fetch_record(CATEGORY_BLS_KEY, KEY_NAME, false)).unwrap();
+            assert!(record.is_some());
+            assert!(get_keys(&ctx).is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn gen_bls_works_for_proof_of_possession() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let key = Key::create_bls(&wallet, None, Some(SEED_NODE), None).unwrap();
+            assert_eq!(key.blskey, key.name);
+            assert!(Key::verify_bls_proof_of_possession(&key.blskey, &key.blskey_pop).unwrap());
+
+            let other = Key::create_bls(&wallet, Some(KEY_NAME), None, None).unwrap();
+            assert_ne!(key.blskey, other.blskey);
+            assert!(!Key::verify_bls_proof_of_possession(&key.blskey, &other.blskey_pop).unwrap());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn gen_bls_works_for_indy_multi_signature() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let key = Key::create_bls(&wallet, None, Some(SEED_NODE), None).unwrap();
+
+            // the key must be accepted the same way as BLS keys of NODE transactions
+            let verkey = base58::decode(&key.blskey).unwrap();
+            assert!(VerKey::from_bytes(&verkey).is_ok());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn gen_bls_works_for_duplicate_name() {
+            let ctx = setup_with_wallet();
+            let cmd = gen_bls_command::new();
+            let mut params = CommandParams::new();
+            params.insert("name", KEY_NAME.to_string());
+            cmd.execute(&ctx, &params).unwrap();
+            cmd.execute(&ctx, &params).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn gen_bls_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = gen_bls_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,79 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    tools::did::key::Key,
+    utils::table::print_list_table,
+};
+
+pub mod list_command {
+    use super::*;
+
+    command!(
+        CommandMetadata::build("list", "List keys stored in the opened wallet.")
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let store = ctx.ensure_opened_wallet()?;
+
+        let keys = Key::list(&store).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let keys: Vec<serde_json::Value> = keys.iter().map(|key| json!(key)).collect();
+
+        print_list_table(
+            &keys,
+            &[
+                ("name", "Name"),
+                ("algorithm", "Algorithm"),
+                ("verkey", "Public key"),
+                ("metadata", "Metadata"),
+            ],
+            "There are no keys",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_list {
+        use super::*;
+        use crate::{
+            commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+            key::tests::{create_key, KEY_NAME},
+        };
+
+        #[test]
+        pub fn list_works() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, None);
+            {
+                let cmd = list_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn list_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = list_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
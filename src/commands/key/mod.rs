@@ -0,0 +1,49 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::command_executor::{CommandGroup, CommandGroupMetadata};
+
+pub mod create;
+pub mod delete;
+pub mod gen_bls;
+pub mod list;
+pub mod sign;
+pub mod verify;
+
+pub use self::{create::*, delete::*, gen_bls::*, list::*, sign::*, verify::*};
+
+pub mod group {
+    use super::*;
+
+    command_group!(CommandGroupMetadata::new("key", "Key management commands")
+        .add_example("key create name=signing-key")
+        .add_example("key sign name=signing-key data=aGVsbG8=")
+        .add_example("key list"));
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        command_executor::{CommandContext, CommandParams},
+        tools::did::key::{Key, KeyInfo},
+    };
+
+    pub const KEY_NAME: &'static str = "my-key";
+
+    pub fn create_key(ctx: &CommandContext, name: &str, alg: Option<&str>) {
+        let cmd = create_command::new();
+        let mut params = CommandParams::new();
+        params.insert("name", name.to_string());
+        if let Some(alg) = alg {
+            params.insert("alg", alg.to_string());
+        }
+        cmd.execute(&ctx, &params).unwrap();
+    }
+
+    pub fn get_keys(ctx: &CommandContext) -> Vec<KeyInfo> {
+        let wallet = ctx.ensure_opened_wallet().unwrap();
+        Key::list(&wallet).unwrap()
+    }
+}
@@ -0,0 +1,100 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::did::key::Key,
+};
+
+use indy_utils::base64;
+
+pub mod sign_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "sign",
+        "Sign base64 encoded data with a key stored in the wallet."
+    )
+    .add_required_param("name", "Name of the key to sign with")
+    .add_required_param("data", "Base64 encoded data to sign")
+    .add_example("key sign name=signing-key data=aGVsbG8=")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let store = ctx.ensure_opened_wallet()?;
+
+        let name = ParamParser::get_str_param("name", params)?;
+        let data = ParamParser::get_str_param("data", params)?;
+
+        let data = base64::decode(data)
+            .map_err(|_| println_err!("Data must be a base64 encoded string"))?;
+
+        let signature = Key::sign_message(&store, name, &data)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("Signature: {}", base64::encode(&signature));
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_sign {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet, tear_down_with_wallet},
+            key::tests::{create_key, KEY_NAME},
+        };
+
+        #[test]
+        pub fn sign_works() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, None);
+            {
+                let cmd = sign_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("data", "aGVsbG8=".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn sign_works_for_invalid_data() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, None);
+            {
+                let cmd = sign_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("data", "not base64!".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn sign_works_for_x25519_key() {
+            let ctx = setup_with_wallet();
+            create_key(&ctx, KEY_NAME, Some("x25519"));
+            {
+                let cmd = sign_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("data", "aGVsbG8=".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::did::key::Key,
+};
+
+use indy_utils::base64;
+
+pub mod verify_command {
+    use super::*;
+
+    command!(CommandMetadata::build("verify", "Verify signature of base64 encoded data.")
+        .add_optional_param("name", "Name of the key stored in the wallet to verify with")
+        .add_optional_param("verkey", "Ed25519 verkey to verify with if the key is not stored in the wallet")
+        .add_required_param("data", "Base64 encoded data")
+        .add_required_param("signature", "Base64 encoded signature")
+        .add_example("key verify name=signing-key data=aGVsbG8= signature=Zm9v")
+        .add_example("key verify verkey=GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL data=aGVsbG8= signature=Zm9v")
+        .add_precondition(Precondition::OpenedWallet)
+        .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let name = ParamParser::get_opt_str_param("name", params)?;
+        let verkey = ParamParser::get_opt_str_param("verkey", params)?;
+        let data = ParamParser::get_str_param("data", params)?;
+        let signature = ParamParser::get_str_param("signature", params)?;
+
+        let store = match name {
+            Some(_) => Some(ctx.ensure_opened_wallet()?),
+            None => None,
+        };
+
+        let data = base64::decode(data)
+            .map_err(|_| println_err!("Data must be a base64 encoded string"))?;
+        let signature = base64::decode(signature)
+            .map_err(|_| println_err!("Signature must be a base64 encoded string"))?;
+
+        let valid = Key::verify_message(store.as_deref(), name, verkey, &data, &signature)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        if !valid {
+            println_err!("Signature is invalid");
+            return Err(());
+        }
+
+        println_succ!("Signature is valid");
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod key_verify {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet, tear_down_with_wallet},
+            did::tests::{SEED_TRUSTEE, VERKEY_TRUSTEE},
+            key::tests::KEY_NAME,
+        };
+
+        fn create_and_sign(ctx: &CommandContext) -> String {
+            let store = ctx.ensure_opened_wallet().unwrap();
+            Key::create_named(
+                &store,
+                Some(KEY_NAME),
+                Key::parse_alg("ed25519").unwrap(),
+                Some(SEED_TRUSTEE),
+                None,
+            )
+            .unwrap();
+            base64::encode(&Key::sign_message(&store, KEY_NAME, b"hello").unwrap())
+        }
+
+        #[test]
+        pub fn verify_works_for_name() {
+            let ctx = setup_with_wallet();
+            let signature = create_and_sign(&ctx);
+            {
+                let cmd = verify_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("data", "aGVsbG8=".to_string());
+                params.insert("signature", signature);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn verify_works_for_verkey() {
+            let ctx = setup_with_wallet();
+            let signature = create_and_sign(&ctx);
+            {
+                let cmd = verify_command::new();
+                let mut params = CommandParams::new();
+                params.insert("verkey", VERKEY_TRUSTEE.to_string());
+                params.insert("data", "aGVsbG8=".to_string());
+                params.insert("signature", signature);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn verify_works_for_other_data() {
+            let ctx = setup_with_wallet();
+            let signature = create_and_sign(&ctx);
+            {
+                let cmd = verify_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", KEY_NAME.to_string());
+                params.insert("data", "Z29vZGJ5ZQ==".to_string());
+                params.insert("signature", signature);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn verify_works_for_no_key() {
+            let ctx = setup_with_wallet();
+            let signature = create_and_sign(&ctx);
+            {
+                let cmd = verify_command::new();
+                let mut params = CommandParams::new();
+                params.insert("data", "aGVsbG8=".to_string());
+                params.insert("signature", signature);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
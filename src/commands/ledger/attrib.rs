@@ -3,34 +3,59 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
-    tools::ledger::{Ledger, Response},
+    tools::{
+        did::key::Key,
+        ledger::{Ledger, Response},
+        pool::Pool,
+    },
+    utils::table::print_list_table,
 };
 
-use serde_json::Value as JsonValue;
+use indy_utils::{did::DidValue, hash::SHA256};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 
 use super::common::{
     handle_transaction_response, print_transaction_response, set_author_agreement,
 };
 
+const ENDPOINT_ATTRIB: &str = "endpoint";
+const DEFAULT_ENDPOINT_TYPE: &str = "ha";
+
 pub mod attrib_command {
     use super::*;
 
     command!(CommandMetadata::build("attrib", r#"Send Attribute transaction to the Ledger for exists NYM."#)
-                .add_required_param("did",  "DID of identity presented in Ledger")
+                .add_required_param_with_dynamic_completion("did",  "DID of identity presented in Ledger", DynamicCompletionType::Did)
                 .add_optional_param("hash", "Hash of attribute data")
                 .add_optional_param("raw", "JSON representation of attribute data")
                 .add_optional_param("enc", "Encrypted attribute data")
+                .add_optional_param("hash_of", "JSON representation of attribute data to calculate the hash of locally and send it instead of `hash`")
+                .add_optional_param("enc_with_key", "Name of the wallet key (ed25519 or x25519, e.g. DID verkey) to encrypt `raw` data with locally and send it as `enc` value. \
+                    The data is encrypted into a sealed box which can be opened only with the same key")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
-                .add_optional_param("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
                     Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
-                    The created request will be printed and stored into CLI context.")
+                    The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
                 .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX raw={"endpoint":{"ha":"127.0.0.1:5555"}}"#)
                 .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX hash=83d907821df1c87db829e96569a11f6fc2e7880acba5e43d07ab786959e13bd3"#)
                 .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX enc=aa3f41f619aa7e5e6b6d0d"#)
+                .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX hash_of={"endpoint":{"ha":"127.0.0.1:5555"}}"#)
+                .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX raw={"email":"issuer@example.com"} enc_with_key=GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa"#)
                 .add_example(r#"ledger attrib did=VsKV7grR1BUE29mG2Fm2kX raw={"endpoint":{"ha":"127.0.0.1:5555"}} send=false"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -45,16 +70,48 @@ pub mod attrib_command {
         let hash = ParamParser::get_opt_str_param("hash", params)?;
         let raw = ParamParser::get_opt_object_param("raw", params)?;
         let enc = ParamParser::get_opt_str_param("enc", params)?;
+        let hash_of = ParamParser::get_opt_str_param("hash_of", params)?;
+        let enc_with_key = ParamParser::get_opt_str_param("enc_with_key", params)?;
+
+        let hash = match (hash, hash_of) {
+            (Some(_), Some(_)) => {
+                println_err!("Only one of \"hash\" and \"hash_of\" params can be specified.");
+                return Err(());
+            }
+            (None, Some(data)) => {
+                let hash = hash_attrib_data(data)?;
+                println!("Attribute data hash: {}", hash);
+                Some(hash)
+            }
+            (hash, None) => hash.map(String::from),
+        };
+
+        let (raw, enc) = match (raw, enc, enc_with_key) {
+            (_, Some(_), Some(_)) => {
+                println_err!("Only one of \"enc\" and \"enc_with_key\" params can be specified.");
+                return Err(());
+            }
+            (None, None, Some(_)) => {
+                println_err!("\"raw\" param with the data to encrypt must be specified together with \"enc_with_key\".");
+                return Err(());
+            }
+            (Some(raw), None, Some(key)) => {
+                let enc = Key::seal_message(&wallet, key, raw.to_string().as_bytes())
+                    .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+                (None, Some(hex::encode(enc)))
+            }
+            (raw, enc, None) => (raw, enc.map(String::from)),
+        };
 
         let mut request = Ledger::build_attrib_request(
             pool.as_deref(),
             &submitter_did,
             &target_did,
-            hash,
+            hash.as_deref(),
             raw.as_ref(),
-            enc,
+            enc.as_deref(),
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         set_author_agreement(ctx, &mut request)?;
 
@@ -84,18 +141,28 @@ pub mod attrib_command {
     }
 }
 
+/// Calculates SHA-256 hash of the attribute data as expected by `hash` value of ATTRIB transaction.
+/// The data is hashed exactly as provided, so the same JSON string must be used to verify it later.
+fn hash_attrib_data(data: &str) -> Result<String, ()> {
+    serde_json::from_str::<JsonValue>(data)
+        .map_err(|err| println_err!("Invalid JSON attribute data provided: {}", err))?;
+    Ok(hex::encode(SHA256::digest(data.as_bytes())))
+}
+
 pub mod get_attrib_command {
     use super::*;
 
     command!(CommandMetadata::build("get-attrib", "Get ATTRIB from Ledger.")
-                .add_required_param("did", "DID of identity presented in Ledger")
+                .add_required_param_with_dynamic_completion("did", "DID of identity presented in Ledger", DynamicCompletionType::Did)
                 .add_optional_param("raw", "Name of attribute")
                 .add_optional_param("hash", "Hash of attribute data")
                 .add_optional_param("enc", "Encrypted value of attribute data")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger get-attrib did=VsKV7grR1BUE29mG2Fm2kX raw=endpoint")
                 .add_example("ledger get-attrib did=VsKV7grR1BUE29mG2Fm2kX hash=83d907821df1c87db829e96569a11f6fc2e7880acba5e43d07ab786959e13bd3")
                 .add_example("ledger get-attrib did=VsKV7grR1BUE29mG2Fm2kX enc=aa3f41f619aa7e5e6b6d0d")
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -118,7 +185,7 @@ pub mod get_attrib_command {
             hash,
             enc,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, mut response) = send_read_request!(&ctx, params, &request);
 
@@ -152,6 +219,197 @@ pub mod get_attrib_command {
     }
 }
 
+pub mod set_endpoint_command {
+    use super::*;
+
+    command!(CommandMetadata::build("set-endpoint", r#"Set endpoint of the DID on the Ledger by sending ATTRIB transaction with `endpoint` attribute.
+                Endpoints of other types already set on the Ledger are preserved."#)
+                .add_required_param_with_dynamic_completion("did", "DID of identity presented in Ledger", DynamicCompletionType::Did)
+                .add_required_param("address", "Endpoint address: host:port for `ha` endpoint type or URL for others")
+                .add_optional_param("type", "Endpoint type (`ha` by default). For example: endpoint, profile, linked_domains")
+                .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
+                    Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
+                    The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
+                .add_example("did set-endpoint did=VsKV7grR1BUE29mG2Fm2kX address=127.0.0.1:5555")
+                .add_example("did set-endpoint did=VsKV7grR1BUE29mG2Fm2kX address=https://agent.example.com type=endpoint")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let pool = ctx.get_connected_pool();
+        let submitter_did = ctx.ensure_active_did()?;
+
+        let target_did = ParamParser::get_did_param("did", params)?;
+        let address = ParamParser::get_str_param("address", params)?;
+        let endpoint_type =
+            ParamParser::get_opt_str_param("type", params)?.unwrap_or(DEFAULT_ENDPOINT_TYPE);
+
+        validate_endpoint_address(endpoint_type, address)?;
+
+        let mut endpoint = match pool {
+            Some(ref pool) => get_endpoint(pool, Some(&submitter_did), &target_did)?,
+            None => None,
+        }
+        .unwrap_or_default();
+        endpoint.insert(endpoint_type.to_string(), JsonValue::from(address));
+
+        let raw = json!({ ENDPOINT_ATTRIB: endpoint });
+
+        let mut request = Ledger::build_attrib_request(
+            pool.as_deref(),
+            &submitter_did,
+            &target_did,
+            None,
+            Some(&raw),
+            None,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        set_author_agreement(ctx, &mut request)?;
+
+        let (_, response): (String, Response<JsonValue>) =
+            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+
+        handle_transaction_response(response).map(|result| {
+            print_transaction_response(
+                result,
+                "Endpoint has been set on the Ledger.",
+                None,
+                &[("raw", "Raw value")],
+                true,
+            )
+        })?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod get_endpoint_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "get-endpoint",
+        "Get endpoints of the DID set on the Ledger."
+    )
+    .add_required_param_with_dynamic_completion(
+        "did",
+        "DID of identity presented in Ledger",
+        DynamicCompletionType::Did
+    )
+    .add_example("did get-endpoint did=VsKV7grR1BUE29mG2Fm2kX")
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+        let submitter_did = ctx.get_active_did()?;
+
+        let target_did = ParamParser::get_did_param("did", params)?;
+
+        let endpoint = get_endpoint(&pool, submitter_did.as_deref(), &target_did)?;
+
+        let endpoint = match endpoint {
+            Some(endpoint) if !endpoint.is_empty() => endpoint,
+            _ => {
+                println_err!("Endpoint not found");
+                return Err(());
+            }
+        };
+
+        println_succ!("Following endpoints have been received.");
+
+        let rows: Vec<JsonValue> = endpoint
+            .into_iter()
+            .map(|(endpoint_type, address)| json!({"type": endpoint_type, "address": address}))
+            .collect();
+
+        print_list_table(
+            &rows,
+            &[("type", "Type"), ("address", "Address")],
+            "Endpoint not found",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+/// Builds ATTRIB raw data setting `ha` endpoint of the DID to `host:port` address.
+pub fn ha_endpoint_attrib(address: &str) -> Result<JsonValue, ()> {
+    validate_endpoint_address(DEFAULT_ENDPOINT_TYPE, address)?;
+    Ok(json!({ ENDPOINT_ATTRIB: { DEFAULT_ENDPOINT_TYPE: address } }))
+}
+
+fn validate_endpoint_address(endpoint_type: &str, address: &str) -> Result<(), ()> {
+    if endpoint_type != DEFAULT_ENDPOINT_TYPE {
+        return if address.is_empty() {
+            println_err!("Endpoint address must not be empty");
+            Err(())
+        } else {
+            Ok(())
+        };
+    }
+
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+        _ => {
+            println_err!(
+                "Invalid endpoint address \"{}\". Expected format: <host>:<port>",
+                address
+            );
+            Err(())
+        }
+    }
+}
+
+/// Reads `endpoint` ATTRIB of the DID as a map of endpoint type to address.
+pub fn get_endpoint(
+    pool: &Pool,
+    submitter_did: Option<&DidValue>,
+    did: &DidValue,
+) -> Result<Option<JsonMap<String, JsonValue>>, ()> {
+    let response = Ledger::build_get_attrib_request(
+        Some(pool),
+        submitter_did,
+        did,
+        Some(ENDPOINT_ATTRIB),
+        None,
+        None,
+    )
+    .and_then(|request| Ledger::submit_request(pool, &request))
+    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+
+    let response = serde_json::from_str::<Response<JsonValue>>(&response)
+        .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+    let result = handle_transaction_response(response)?;
+
+    let data = match result["data"].as_str() {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let data = serde_json::from_str::<JsonValue>(data)
+        .map_err(|_| println_err!("Wrong data has been received"))?;
+
+    Ok(data[ENDPOINT_ATTRIB].as_object().cloned())
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -166,7 +424,6 @@ pub mod tests {
             tests::{create_new_did, send_nym, use_new_endorser, use_trustee, ReplyResult},
         },
     };
-    use indy_utils::did::DidValue;
 
     const ATTRIB_RAW_DATA: &str = r#"{"endpoint":{"ha":"127.0.0.1:5555"}}"#;
     const ATTRIB_HASH_DATA: &str =
@@ -221,6 +478,62 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn attrib_works_for_hash_of_value() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = attrib_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("hash_of", ATTRIB_RAW_DATA.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_attrib_added(&ctx, &did, None, Some(ATTRIB_HASH_DATA), None).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn attrib_works_for_enc_with_key() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, verkey) = use_new_endorser(&ctx);
+            {
+                let cmd = attrib_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("raw", ATTRIB_RAW_DATA.to_string());
+                params.insert("enc_with_key", verkey.clone());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let transaction = ctx.get_context_transaction().unwrap();
+            let transaction: JsonValue = serde_json::from_str(&transaction).unwrap();
+            assert!(transaction["operation"]["raw"].is_null());
+            let enc = hex::decode(transaction["operation"]["enc"].as_str().unwrap()).unwrap();
+
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let data = Key::open_sealed_message(&wallet, &verkey, &enc).unwrap();
+            assert_eq!(
+                serde_json::from_str::<JsonValue>(ATTRIB_RAW_DATA).unwrap(),
+                serde_json::from_slice::<JsonValue>(&data).unwrap()
+            );
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn attrib_works_for_enc_with_key_without_raw() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, verkey) = use_new_endorser(&ctx);
+            {
+                let cmd = attrib_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did);
+                params.insert("enc_with_key", verkey);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn attrib_works_for_missed_attribute() {
             let ctx = setup_with_wallet_and_pool();
@@ -475,4 +788,81 @@ pub mod tests {
                 })
         })
     }
+
+    mod endpoint {
+        use super::*;
+
+        #[test]
+        pub fn set_endpoint_works() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = set_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("address", "127.0.0.1:5555".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_attrib_added(&ctx, &did, Some(ATTRIB_RAW_DATA), None, None).is_ok());
+            {
+                let cmd = get_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn set_endpoint_works_for_preserving_other_types() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = set_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("address", "127.0.0.1:5555".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_attrib_added(&ctx, &did, Some(ATTRIB_RAW_DATA), None, None).is_ok());
+            {
+                let cmd = set_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("address", "https://agent.example.com".to_string());
+                params.insert("type", "endpoint".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let expected = json!({"endpoint": {"endpoint": "https://agent.example.com", "ha": "127.0.0.1:5555"}}).to_string();
+            assert!(ensure_attrib_added(&ctx, &did, Some(&expected), None, None).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn set_endpoint_works_for_invalid_address() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = set_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("address", "127.0.0.1".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_endpoint_works_for_no_endpoint() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = get_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
 }
@@ -3,17 +3,29 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        read_user_input, wait_for_user_reply, Command, CommandContext, CommandMetadata,
+        CommandParams, Precondition,
+    },
+    pager::print_paged,
     params_parser::ParamParser,
-    tools::ledger::{Ledger, LedgerHelpers, Response},
-    utils::table::print_list_table,
+    tools::{
+        ledger::{Ledger, LedgerHelpers, Response, ResponseType},
+        pool::Pool,
+    },
+    utils::{
+        diff::print_diff,
+        file::{read_file, write_file},
+        table::{format_list_table, print_list_table, print_or_export_list_table},
+    },
 };
 
+use indy_vdr::ledger::constants::{txn_name_to_code, ENDORSER, NETWORK_MONITOR, STEWARD, TRUSTEE};
 use serde_json::Value as JsonValue;
 
 use super::common::{handle_transaction_response, print_transaction_response};
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct AuthRuleData {
     pub auth_type: String,
     pub auth_action: String,
@@ -25,17 +37,33 @@ pub struct AuthRuleData {
 
 pub type AuthRulesData = Vec<AuthRuleData>;
 
+/// Value of `ledger auth-rule` main param listing the constraint presets.
+const SHOW_TEMPLATES: &str = "templates";
+
+/// Constraint presets which can be used instead of constraint JSON: (name, description).
+const AUTH_RULE_TEMPLATES: &[(&str, &str)] = &[
+    ("trustee-only", "Signature of one TRUSTEE is required"),
+    (
+        "steward-or-trustee",
+        "Signature of one STEWARD or one TRUSTEE is required",
+    ),
+    (
+        "owner",
+        "Signature of the owner of the transaction is required",
+    ),
+];
+
 pub mod auth_rule_command {
     use super::*;
-    use indy_vdr::ledger::constants::txn_name_to_code;
 
     command!(CommandMetadata::build("auth-rule", "Send AUTH_RULE request to change authentication rules for a ledger transaction.")
+                .add_optional_main_param("show", "Pass `templates` to list constraint presets which can be used by `template` parameter instead of sending the request. Listing templates requires neither an opened wallet, an active DID nor a connected pool")
                 .add_required_param("txn_type", "Ledger transaction alias or associated value")
                 .add_required_param("action", "Type of an action. One of: ADD, EDIT")
                 .add_required_param("field", "Transaction field")
                 .add_optional_param("old_value", "Old value of field, which can be changed to a new_value (mandatory for EDIT action)")
                 .add_optional_param("new_value", "New value that can be used to fill the field")
-                .add_required_param("constraint", r#"Set of constraints required for execution of an action
+                .add_optional_param("constraint", r#"Set of constraints required for execution of an action
          {
              constraint_id - type of a constraint. Can be either "ROLE" to specify final constraint or  "AND"/"OR" to combine constraints, or "FORBIDDEN" to forbid action.
              role - (optional) role associated value {TRUSTEE: 0, STEWARD: 2, TRUST_ANCHOR: 101, ENDORSER: 101, NETWORK_MONITOR: 201, ANY: *}.
@@ -49,51 +77,130 @@ pub mod auth_rule_command {
              constraint_id: <"AND" or "OR">
              auth_constraints: [<constraint_1>, <constraint_2>]
          }
+         Either `constraint` or `template` must be specified.
                 "#)
+                .add_optional_param("template", "Name of the constraint preset to use instead of `constraint`. One of: trustee-only, steward-or-trustee, owner (see `ledger auth-rule templates`)")
+                .add_optional_param("review", "Fetch the current constraint from the Ledger, print the changes and ask for confirmation before sending (False by default)")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example(r#"ledger auth-rule txn_type=NYM action=ADD field=role new_value=101 constraint="{"sig_count":1,"role":"0","constraint_id":"ROLE","need_to_be_owner":false}""#)
                 .add_example(r#"ledger auth-rule txn_type=NYM action=ADD field=role new_value=101 constraint="{"sig_count":1,"role":"0","constraint_id":"ROLE","need_to_be_owner":false,"off_ledger_signature":true}""#)
                 .add_example(r#"ledger auth-rule txn_type=NYM action=EDIT field=role old_value=101 new_value=0 constraint="{"sig_count":1,"role":"0","constraint_id":"ROLE","need_to_be_owner":false}""#)
                 .add_example(r#"ledger auth-rule txn_type=NYM action=ADD field=role new_value=101 constraint="{"constraint_id":"FORBIDDEN"}""#)
+                .add_example(r#"ledger auth-rule txn_type=NYM action=ADD field=role new_value=101 template=steward-or-trustee"#)
+                .add_example(r#"ledger auth-rule txn_type=NYM action=ADD field=role new_value=101 template=trustee-only review=true"#)
+                .add_example(r#"ledger auth-rule templates"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
-        let wallet = ctx.ensure_opened_wallet()?;
-        let submitter_did = ctx.ensure_active_did()?;
-        let pool = ctx.get_connected_pool();
+        if let Some(show) = ParamParser::get_opt_str_param("show", params)? {
+            if show != SHOW_TEMPLATES {
+                println_err!(
+                    "Unsupported value \"{}\". Use `ledger auth-rule {}` to list constraint presets",
+                    show,
+                    SHOW_TEMPLATES
+                );
+                return Err(());
+            }
+            print_auth_rule_templates();
+            trace!("execute << ");
+            return Ok(());
+        }
 
         let txn_type = ParamParser::get_str_param("txn_type", params)?;
         let action = ParamParser::get_str_param("action", params)?;
         let field = ParamParser::get_str_param("field", params)?;
         let old_value = ParamParser::get_opt_str_param("old_value", params)?;
         let new_value = ParamParser::get_opt_str_param("new_value", params)?;
-        let constraint = ParamParser::get_str_param("constraint", params)?;
+        let constraint = ParamParser::get_opt_str_param("constraint", params)?;
+        let template = ParamParser::get_opt_str_param("template", params)?;
 
-        let txn_type = txn_name_to_code(txn_type)
-            .ok_or_else(|| println_err!("Unsupported ledger transaction."))?;
+        let constraint = match (constraint, template) {
+            (Some(constraint), None) => serde_json::from_str(constraint)
+                .map_err(|err| println_err!("Invalid constraint: {}", err))?,
+            (None, Some(template)) => {
+                auth_rule_template(template).ok_or_else(|| print_unknown_template(template))?
+            }
+            _ => {
+                println_err!("Either `constraint` or `template` must be specified");
+                return Err(());
+            }
+        };
+
+        send_auth_rule(
+            ctx,
+            params,
+            AuthRuleData {
+                auth_type: txn_type.to_string(),
+                auth_action: action.to_string(),
+                field: field.to_string(),
+                old_value: old_value.map(String::from),
+                new_value: new_value.map(String::from),
+                constraint,
+            },
+        )?;
+
+        trace!("execute << ");
+        Ok(())
+    }
+
+    /// Sends the rule to the Ledger. `auth_type` of the rule can be either the transaction alias or its code.
+    pub fn send_auth_rule(
+        ctx: &CommandContext,
+        params: &CommandParams,
+        rule: AuthRuleData,
+    ) -> Result<(), ()> {
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let rule = AuthRuleData {
+            auth_type: txn_name_to_code(&rule.auth_type)
+                .ok_or_else(|| println_err!("Unsupported ledger transaction."))?
+                .to_string(),
+            auth_action: rule.auth_action.to_uppercase(),
+            ..rule
+        };
+
+        if ParamParser::get_opt_bool_param("review", params)?.unwrap_or(false) {
+            let pool = ctx.ensure_connected_pool()?;
+            if !review_auth_rule(ctx, &pool, &rule)? {
+                println!("AUTH_RULE request has not been sent.");
+                return Ok(());
+            }
+        }
 
         let mut request = Ledger::build_auth_rule_request(
             pool.as_deref(),
             &submitter_did,
-            &txn_type,
-            &action.to_uppercase(),
-            field,
-            old_value,
-            new_value,
-            constraint,
+            &rule.auth_type,
+            &rule.auth_action,
+            &rule.field,
+            rule.old_value.as_deref(),
+            rule.new_value.as_deref(),
+            &rule.constraint.to_string(),
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, mut response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
 
         if let Some(result) = response.result.as_mut() {
-            result["txn"]["data"]["auth_type"] =
-                LedgerHelpers::get_txn_title(&result["txn"]["data"]["auth_type"]);
+            result["txn"]["data"]["auth_type"] = LedgerHelpers::get_txn_title(
+                &result["txn"]["data"]["auth_type"],
+                &ctx.get_txn_titles(),
+            );
             result["txn"]["data"]["constraint"] = JsonValue::String(
                 serde_json::to_string_pretty(&result["txn"]["data"]["constraint"]).unwrap(),
             );
@@ -114,21 +221,171 @@ pub mod auth_rule_command {
                 ],
                 false,
             )
-        })?;
+        })
+    }
+}
+
+/// Prints the changes of the rule constraint against the one set on the Ledger and asks for confirmation.
+fn review_auth_rule(ctx: &CommandContext, pool: &Pool, rule: &AuthRuleData) -> Result<bool, ()> {
+    let rule = normalize_auth_rule(rule)?;
+
+    let response = Ledger::build_get_auth_rule_request(
+        Some(pool),
+        None,
+        Some(&rule.auth_type),
+        Some(&rule.auth_action),
+        Some(&rule.field),
+        rule.old_value.as_deref(),
+        rule.new_value.as_deref(),
+    )
+    .and_then(|request| Ledger::submit_request(pool, &request))
+    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+    let response = serde_json::from_str::<Response<JsonValue>>(&response)
+        .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+
+    // the Ledger rejects reading a rule which is not set
+    let current = match (response.op, response.result) {
+        (ResponseType::REPLY, Some(result)) => result["data"][0]
+            .get("constraint")
+            .map(normalize_constraint),
+        _ => None,
+    };
+    let before = current
+        .as_ref()
+        .map(|constraint| serde_json::to_string_pretty(constraint).unwrap())
+        .unwrap_or_default();
+    let after = serde_json::to_string_pretty(&rule.constraint).unwrap();
+
+    let txn_title = LedgerHelpers::get_txn_title(
+        &JsonValue::String(rule.auth_type.clone()),
+        &ctx.get_txn_titles(),
+    );
+    println_acc!(
+        "Auth rule for {} {} of \"{}\" field:",
+        rule.auth_action,
+        txn_title.as_str().unwrap_or_default(),
+        rule.field
+    );
+    if current.is_none() {
+        println_warn!("The rule is not set on the Ledger");
+    } else if before == after {
+        println_warn!("The constraint is the same as set on the Ledger");
+    }
+    print_diff(&before, &after);
+
+    println!("Would you like to send AUTH_RULE request? (y/n)");
+    Ok(wait_for_user_reply(ctx))
+}
+
+pub mod auth_rule_wizard_command {
+    use super::*;
+
+    command!(CommandMetadata::build("auth-rule-wizard", "Interactively compose AUTH_RULE request step by step and send it to the Ledger.
+        Values passed as parameters are not prompted.")
+                .add_optional_param("txn_type", "Ledger transaction alias or associated value")
+                .add_optional_param("action", "Type of an action. One of: ADD, EDIT")
+                .add_optional_param("field", "Transaction field")
+                .add_optional_param("old_value", "Old value of field, which can be changed to a new_value (mandatory for EDIT action)")
+                .add_optional_param("new_value", "New value that can be used to fill the field")
+                .add_optional_param("review", "Fetch the current constraint from the Ledger, print the changes and ask for confirmation before sending (False by default)")
+                .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger auth-rule-wizard")
+                .add_example("ledger auth-rule-wizard txn_type=NYM action=ADD field=role new_value=101")
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let mut input = |prompt: &str| read_user_input(ctx, prompt);
+
+        let txn_type = get_or_ask_param(
+            params,
+            "txn_type",
+            "Ledger transaction alias or associated value (e.g. NYM): ",
+            &mut input,
+        )?;
+        let action =
+            get_or_ask_param(params, "action", "Action (ADD, EDIT): ", &mut input)?.to_uppercase();
+        if action != "ADD" && action != "EDIT" {
+            println_err!("Unsupported action \"{}\". One of: ADD, EDIT", action);
+            return Err(());
+        }
+        let field = get_or_ask_param(params, "field", "Transaction field: ", &mut input)?;
+        let old_value = match ParamParser::get_opt_str_param("old_value", params)? {
+            Some(old_value) => Some(old_value.to_string()),
+            None if action == "EDIT" => Some(ask(&mut input, "Old value: ")?),
+            None => None,
+        };
+        let new_value = match ParamParser::get_opt_str_param("new_value", params)? {
+            Some(new_value) => Some(new_value.to_string()),
+            None => Some(ask(&mut input, "New value (* for any): ")?),
+        };
+
+        let constraint = build_constraint(&mut input, 0)?;
+
+        println!("Constraint has been composed:");
+        println!("{}", serde_json::to_string_pretty(&constraint).unwrap());
+        println!("Would you like to proceed with AUTH_RULE request? (y/n)");
+
+        if !wait_for_user_reply(ctx) {
+            println!("AUTH_RULE request has not been built.");
+            return Ok(());
+        }
+
+        auth_rule_command::send_auth_rule(
+            ctx,
+            params,
+            AuthRuleData {
+                auth_type: txn_type,
+                auth_action: action,
+                field,
+                old_value,
+                new_value,
+                constraint,
+            },
+        )?;
 
         trace!("execute << ");
         Ok(())
     }
+
+    fn get_or_ask_param(
+        params: &CommandParams,
+        name: &str,
+        prompt: &str,
+        input: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> Result<String, ()> {
+        match ParamParser::get_opt_str_param(name, params)? {
+            Some(value) => Ok(value.to_string()),
+            None => ask(input, prompt),
+        }
+    }
 }
 
 pub mod auth_rules_command {
     use super::*;
 
     command!(CommandMetadata::build("auth-rules", "Send AUTH_RULES request to change authentication rules for multiple ledger transactions.")
-                .add_main_param("rules", r#"A list of auth rules: [{"auth_type", "auth_action", "field", "old_value", "new_value"},{...}]"#)
+                .add_main_param("rules", r#"A list of auth rules: [{"auth_type", "auth_action", "field", "old_value", "new_value", "constraint"},{...}]. `template` preset name can be used instead of `constraint` (see `ledger auth-rule templates`)"#)
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example(r#"ledger auth-rules [{"auth_type":"1","auth_action":"ADD","field":"role","new_value":"101","constraint":{"sig_count":1,"role":"0","constraint_id":"ROLE","need_to_be_owner":false}}]"#)
+                .add_example(r#"ledger auth-rules [{"auth_type":"1","auth_action":"ADD","field":"role","new_value":"101","template":"trustee-only"}]"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -140,9 +397,10 @@ pub mod auth_rules_command {
         let pool = ctx.get_connected_pool();
 
         let rules = ParamParser::get_str_param("rules", params)?;
+        let rules = expand_auth_rules_templates(rules)?;
 
         let mut request = Ledger::build_auth_rules_request(pool.as_deref(), &submitter_did, &rules)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
@@ -152,13 +410,38 @@ pub mod auth_rules_command {
 
         let rules: AuthRulesData = serde_json::from_value(result["txn"]["data"]["rules"].clone())
             .map_err(|_| println_err!("Wrong data has been received"))?;
-        print_auth_rules(rules);
+        print_auth_rules(ctx, rules);
 
         trace!("execute << ");
         Ok(())
     }
 }
 
+/// Prints constraint presets which can be used by `ledger auth-rule` and `ledger auth-rules` commands.
+fn print_auth_rule_templates() {
+    let templates = AUTH_RULE_TEMPLATES
+        .iter()
+        .map(|(name, description)| {
+            json!({
+                "name": name,
+                "description": description,
+                "constraint": auth_rule_template(name)
+                    .map(|constraint| serde_json::to_string_pretty(&constraint).unwrap()),
+            })
+        })
+        .collect::<Vec<JsonValue>>();
+
+    print_list_table(
+        &templates,
+        &[
+            ("name", "Name"),
+            ("description", "Description"),
+            ("constraint", "Constraint"),
+        ],
+        "There are no templates",
+    );
+}
+
 pub mod get_auth_rule_command {
     use super::*;
 
@@ -170,9 +453,15 @@ pub mod get_auth_rule_command {
                 .add_optional_param("old_value", "Old value of field, which can be changed to a new_value (mandatory for EDIT action)")
                 .add_required_param("new_value", "New value that can be used to fill the field")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param("out", "Path to the file to write received rules as JSON instead of printing them")
+                .add_optional_param("format", "Write the rules table into `out` file instead of the raw rules. One of: csv, json")
                 .add_example(r#"ledger get-auth-rule txn_type=NYM action=ADD field=role new_value=101"#)
                 .add_example(r#"ledger get-auth-rule txn_type=NYM action=EDIT field=role old_value=101 new_value=0"#)
                 .add_example(r#"ledger get-auth-rule"#)
+                .add_example(r#"ledger get-auth-rule out=/home/auth_rules.json"#)
+                .add_example(r#"ledger get-auth-rule out=/home/auth_rules.csv format=csv"#)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -187,6 +476,8 @@ pub mod get_auth_rule_command {
         let field = ParamParser::get_opt_str_param("field", params)?;
         let old_value = ParamParser::get_opt_str_param("old_value", params)?;
         let new_value = ParamParser::get_opt_str_param("new_value", params)?;
+        let out = ParamParser::get_opt_str_param("out", params)?;
+        let format = ParamParser::get_opt_str_param("format", params)?;
 
         let request = Ledger::build_get_auth_rule_request(
             pool.as_deref(),
@@ -197,28 +488,524 @@ pub mod get_auth_rule_command {
             old_value,
             new_value,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) = send_read_request!(&ctx, params, &request);
 
         let result = handle_transaction_response(response)?;
 
+        if let (Some(out), None) = (out, format) {
+            write_file(out, &serde_json::to_string_pretty(&result["data"]).unwrap())
+                .map_err(|err| println_err!("{}", err))?;
+            println_succ!("Auth rules have been saved into \"{}\"", out);
+            return Ok(());
+        }
+
         let rules: AuthRulesData = serde_json::from_value(result["data"].clone())
             .map_err(|_| println_err!("Wrong data has been received"))?;
 
-        print_auth_rules(rules);
+        if format.is_some() {
+            print_or_export_list_table(
+                &auth_rules_rows(ctx, rules),
+                &AUTH_RULES_HEADERS,
+                "There are no rules set",
+                out,
+                format,
+            )?;
+            trace!("execute << ");
+            return Ok(());
+        }
+
+        print_auth_rules(ctx, rules);
 
         trace!("execute << ");
         Ok(())
     }
 }
 
-fn print_auth_rules(rules: AuthRulesData) {
-    let constraints = rules
+pub mod auth_rules_diff_command {
+    use super::*;
+
+    command!(CommandMetadata::build("auth-rules-diff", "Compare authentication rules set on the ledger with a local policy file.
+        Prints rules which are added or changed by the policy, and rules set on the ledger but missing in the policy.")
+                .add_required_param("file", r#"Path to the policy file containing a list of auth rules: [{"auth_type", "auth_action", "field", "old_value", "new_value", "constraint"},{...}]"#)
+                .add_optional_param("reconcile", "Build AUTH_RULES request applying added and changed rules and store it into CLI context (false by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger auth-rules-diff file=/home/policy.json")
+                .add_example("ledger auth-rules-diff file=/home/policy.json reconcile=true")
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let submitter_did = ctx.get_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let file = ParamParser::get_str_param("file", params)?;
+        let reconcile = ParamParser::get_opt_bool_param("reconcile", params)?.unwrap_or(false);
+
+        let policy = read_file(file).map_err(|err| println_err!("{}", err))?;
+        let policy: AuthRulesData = serde_json::from_str(&policy)
+            .map_err(|err| println_err!("Invalid policy file: {}", err))?;
+
+        let request = Ledger::build_get_auth_rule_request(
+            pool.as_deref(),
+            submitter_did.as_deref(),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response) = send_read_request!(&ctx, params, &request);
+
+        let result = handle_transaction_response(response)?;
+
+        let ledger_rules: AuthRulesData = serde_json::from_value(result["data"].clone())
+            .map_err(|_| println_err!("Wrong data has been received"))?;
+
+        let diff = diff_auth_rules(&ledger_rules, &policy)?;
+        print_auth_rules_diff(ctx, &diff);
+
+        if reconcile {
+            let rules = diff
+                .iter()
+                .filter(|change| change.status != AuthRuleStatus::Removed)
+                .filter_map(|change| change.policy.as_ref())
+                .map(auth_rule_to_json)
+                .collect::<Vec<JsonValue>>();
+
+            if rules.is_empty() {
+                println_succ!("There are no rules to reconcile");
+                return Ok(());
+            }
+
+            let submitter_did = ctx.ensure_active_did()?;
+
+            let request = Ledger::build_auth_rules_request(
+                pool.as_deref(),
+                &submitter_did,
+                &JsonValue::Array(rules).to_string(),
+            )
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+            let request_json = json!(&request.req_json).to_string();
+            println_succ!(
+                "AUTH_RULES transaction reconciling the ledger with the policy has been created:"
+            );
+            println!("     {}", request_json);
+            ctx.set_context_transaction(Some(request_json));
+        }
+
+        trace!("execute << ");
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum AuthRuleStatus {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug)]
+struct AuthRuleChange {
+    status: AuthRuleStatus,
+    ledger: Option<AuthRuleData>,
+    policy: Option<AuthRuleData>,
+}
+
+fn normalize_auth_rule(rule: &AuthRuleData) -> Result<AuthRuleData, ()> {
+    // plugin transactions are unknown for the CLI but can be referenced by their codes
+    let auth_type = match txn_name_to_code(&rule.auth_type) {
+        Some(auth_type) => auth_type,
+        None if rule.auth_type.chars().all(|c| c.is_ascii_digit()) => rule.auth_type.as_str(),
+        None => {
+            println_err!("Unsupported ledger transaction \"{}\".", rule.auth_type);
+            return Err(());
+        }
+    };
+    let auth_action = rule.auth_action.to_uppercase();
+    let old_value = if auth_action == "ADD" {
+        None
+    } else {
+        rule.old_value.clone()
+    };
+
+    Ok(AuthRuleData {
+        auth_type: auth_type.to_string(),
+        auth_action,
+        field: rule.field.clone(),
+        old_value,
+        new_value: rule.new_value.clone(),
+        constraint: normalize_constraint(&rule.constraint),
+    })
+}
+
+// drops optional constraint fields holding default values so they do not affect comparison
+fn normalize_constraint(constraint: &JsonValue) -> JsonValue {
+    match constraint {
+        JsonValue::Object(fields) => JsonValue::Object(
+            fields
+                .iter()
+                .filter(|(key, value)| match (key.as_str(), value) {
+                    ("metadata", JsonValue::Object(metadata)) => !metadata.is_empty(),
+                    ("need_to_be_owner", JsonValue::Bool(false))
+                    | ("off_ledger_signature", JsonValue::Bool(false)) => false,
+                    _ => true,
+                })
+                .map(|(key, value)| (key.clone(), normalize_constraint(value)))
+                .collect(),
+        ),
+        JsonValue::Array(items) => {
+            JsonValue::Array(items.iter().map(normalize_constraint).collect())
+        }
+        value => value.clone(),
+    }
+}
+
+fn diff_auth_rules(
+    ledger_rules: &AuthRulesData,
+    policy_rules: &AuthRulesData,
+) -> Result<Vec<AuthRuleChange>, ()> {
+    let ledger_rules = ledger_rules
+        .iter()
+        .map(normalize_auth_rule)
+        .collect::<Result<Vec<AuthRuleData>, ()>>()?;
+    let policy_rules = policy_rules
+        .iter()
+        .map(normalize_auth_rule)
+        .collect::<Result<Vec<AuthRuleData>, ()>>()?;
+
+    let same_rule = |a: &AuthRuleData, b: &AuthRuleData| {
+        a.auth_type == b.auth_type
+            && a.auth_action == b.auth_action
+            && a.field == b.field
+            && a.old_value == b.old_value
+            && a.new_value == b.new_value
+    };
+
+    let mut changes = Vec::new();
+
+    for policy_rule in policy_rules.iter() {
+        match ledger_rules
+            .iter()
+            .find(|ledger_rule| same_rule(ledger_rule, policy_rule))
+        {
+            Some(ledger_rule) if ledger_rule.constraint == policy_rule.constraint => {}
+            Some(ledger_rule) => changes.push(AuthRuleChange {
+                status: AuthRuleStatus::Changed,
+                ledger: Some(ledger_rule.clone()),
+                policy: Some(policy_rule.clone()),
+            }),
+            None => changes.push(AuthRuleChange {
+                status: AuthRuleStatus::Added,
+                ledger: None,
+                policy: Some(policy_rule.clone()),
+            }),
+        }
+    }
+
+    for ledger_rule in ledger_rules.iter() {
+        if !policy_rules
+            .iter()
+            .any(|policy_rule| same_rule(ledger_rule, policy_rule))
+        {
+            changes.push(AuthRuleChange {
+                status: AuthRuleStatus::Removed,
+                ledger: Some(ledger_rule.clone()),
+                policy: None,
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+fn auth_rule_to_json(rule: &AuthRuleData) -> JsonValue {
+    let mut rule_json = json!({
+        "auth_type": rule.auth_type,
+        "auth_action": rule.auth_action,
+        "field": rule.field,
+        "new_value": rule.new_value,
+        "constraint": rule.constraint,
+    });
+    if let Some(old_value) = rule.old_value.as_ref() {
+        rule_json["old_value"] = JsonValue::String(old_value.to_string());
+    }
+    rule_json
+}
+
+fn print_auth_rules_diff(ctx: &CommandContext, diff: &[AuthRuleChange]) {
+    let txn_titles = ctx.get_txn_titles();
+    let rows = diff
+        .iter()
+        .map(|change| {
+            let rule = change
+                .policy
+                .as_ref()
+                .or(change.ledger.as_ref())
+                .expect("either ledger or policy rule is set");
+            let constraint = |rule: Option<&AuthRuleData>| {
+                rule.map(|rule| serde_json::to_string_pretty(&rule.constraint).unwrap())
+            };
+
+            json!({
+                "status": format!("{:?}", change.status),
+                "auth_type": LedgerHelpers::get_txn_title(&JsonValue::String(rule.auth_type.clone()), &txn_titles),
+                "auth_action": rule.auth_action,
+                "field": rule.field,
+                "old_value": rule.old_value,
+                "new_value": rule.new_value,
+                "ledger_constraint": constraint(change.ledger.as_ref()),
+                "policy_constraint": constraint(change.policy.as_ref()),
+            })
+        })
+        .collect::<Vec<JsonValue>>();
+
+    print_list_table(
+        &rows,
+        &[
+            ("status", "Status"),
+            ("auth_type", "Type"),
+            ("auth_action", "Action"),
+            ("field", "Field"),
+            ("old_value", "Old Value"),
+            ("new_value", "New Value"),
+            ("ledger_constraint", "Ledger Constraint"),
+            ("policy_constraint", "Policy Constraint"),
+        ],
+        "Auth rules set on the ledger match the policy",
+    );
+}
+
+fn role_constraint(role: &str, need_to_be_owner: bool) -> JsonValue {
+    json!({
+        "constraint_id": "ROLE",
+        "role": role,
+        "sig_count": 1,
+        "need_to_be_owner": need_to_be_owner,
+        "metadata": {},
+    })
+}
+
+/// Expands constraint preset into full constraint JSON.
+fn auth_rule_template(name: &str) -> Option<JsonValue> {
+    match name {
+        "trustee-only" => Some(role_constraint(TRUSTEE, false)),
+        "steward-or-trustee" => Some(json!({
+            "constraint_id": "OR",
+            "auth_constraints": [role_constraint(STEWARD, false), role_constraint(TRUSTEE, false)],
+        })),
+        "owner" => Some(role_constraint("*", true)),
+        _ => None,
+    }
+}
+
+fn print_unknown_template(name: &str) {
+    println_err!(
+        "Unknown auth rule template \"{}\". One of: {}",
+        name,
+        AUTH_RULE_TEMPLATES
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(", ")
+    )
+}
+
+/// Replaces `template` field of every rule in the list with the expanded `constraint`.
+fn expand_auth_rules_templates(rules: &str) -> Result<String, ()> {
+    let mut rules = serde_json::from_str::<JsonValue>(rules)
+        .map_err(|err| println_err!("Invalid format of rules JSON: {}", err))?;
+
+    if let Some(rules) = rules.as_array_mut() {
+        for rule in rules.iter_mut().filter_map(JsonValue::as_object_mut) {
+            let template = match rule.remove("template") {
+                Some(template) => template,
+                None => continue,
+            };
+            if rule.contains_key("constraint") {
+                println_err!("Either `constraint` or `template` must be specified for a rule");
+                return Err(());
+            }
+            let name = template
+                .as_str()
+                .ok_or_else(|| println_err!("Auth rule template must be a string"))?;
+            let constraint =
+                auth_rule_template(name).ok_or_else(|| print_unknown_template(name))?;
+            rule.insert("constraint".to_string(), constraint);
+        }
+    }
+
+    Ok(rules.to_string())
+}
+
+fn ask(input: &mut dyn FnMut(&str) -> Option<String>, prompt: &str) -> Result<String, ()> {
+    loop {
+        match input(prompt) {
+            Some(value) if value.trim().is_empty() => continue,
+            Some(value) => return Ok(value.trim().to_string()),
+            None => {
+                println_err!(
+                    "Interactive input is not available. Use `ledger auth-rule` command instead."
+                );
+                return Err(());
+            }
+        }
+    }
+}
+
+fn ask_with_default(
+    input: &mut dyn FnMut(&str) -> Option<String>,
+    prompt: &str,
+    default: &str,
+) -> Result<String, ()> {
+    match input(&format!("{} [{}]: ", prompt, default)) {
+        Some(value) if value.trim().is_empty() => Ok(default.to_string()),
+        Some(value) => Ok(value.trim().to_string()),
+        None => ask(input, prompt),
+    }
+}
+
+fn ask_bool(input: &mut dyn FnMut(&str) -> Option<String>, prompt: &str) -> Result<bool, ()> {
+    loop {
+        match ask_with_default(input, &format!("{} (y/n)", prompt), "n")?.as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => continue,
+        }
+    }
+}
+
+fn parse_constraint_role(role: &str) -> Option<&'static str> {
+    match role.to_uppercase().as_str() {
+        "TRUSTEE" | TRUSTEE => Some(TRUSTEE),
+        "STEWARD" | STEWARD => Some(STEWARD),
+        "TRUST_ANCHOR" | "ENDORSER" | ENDORSER => Some(ENDORSER),
+        "NETWORK_MONITOR" | NETWORK_MONITOR => Some(NETWORK_MONITOR),
+        "ANY" | "*" => Some("*"),
+        _ => None,
+    }
+}
+
+fn build_constraint(
+    input: &mut dyn FnMut(&str) -> Option<String>,
+    depth: usize,
+) -> Result<JsonValue, ()> {
+    let indent = "  ".repeat(depth);
+
+    loop {
+        let constraint_id = ask(
+            input,
+            &format!("{}Constraint type (ROLE, AND, OR, FORBIDDEN): ", indent),
+        )?
+        .to_uppercase();
+
+        match constraint_id.as_str() {
+            "ROLE" => {
+                let role = loop {
+                    let role = ask_with_default(
+                        input,
+                        &format!(
+                            "{}Role (TRUSTEE, STEWARD, ENDORSER, NETWORK_MONITOR, ANY)",
+                            indent
+                        ),
+                        "ANY",
+                    )?;
+                    match parse_constraint_role(&role) {
+                        Some(role) => break role,
+                        None => println_err!("Unsupported role \"{}\"", role),
+                    }
+                };
+                let sig_count = loop {
+                    let sig_count =
+                        ask_with_default(input, &format!("{}Number of signatures", indent), "1")?;
+                    match sig_count.parse::<u32>() {
+                        Ok(sig_count) => break sig_count,
+                        Err(_) => println_err!("Invalid number of signatures \"{}\"", sig_count),
+                    }
+                };
+                let need_to_be_owner = ask_bool(
+                    input,
+                    &format!("{}Must be an owner of transaction?", indent),
+                )?;
+                let off_ledger_signature = ask_bool(
+                    input,
+                    &format!("{}Allow signature of DID unknown for ledger?", indent),
+                )?;
+
+                return Ok(json!({
+                    "constraint_id": "ROLE",
+                    "role": role,
+                    "sig_count": sig_count,
+                    "need_to_be_owner": need_to_be_owner,
+                    "off_ledger_signature": off_ledger_signature,
+                    "metadata": {},
+                }));
+            }
+            "AND" | "OR" => {
+                let count = loop {
+                    let count = ask_with_default(
+                        input,
+                        &format!("{}Number of constraints to combine", indent),
+                        "2",
+                    )?;
+                    match count.parse::<usize>() {
+                        Ok(count) if count >= 2 => break count,
+                        _ => println_err!("At least 2 constraints must be combined"),
+                    }
+                };
+
+                let mut auth_constraints = Vec::with_capacity(count);
+                for i in 0..count {
+                    println!(
+                        "{}{} constraint {} of {}:",
+                        indent,
+                        constraint_id,
+                        i + 1,
+                        count
+                    );
+                    auth_constraints.push(build_constraint(input, depth + 1)?);
+                }
+
+                return Ok(json!({
+                    "constraint_id": constraint_id,
+                    "auth_constraints": auth_constraints,
+                }));
+            }
+            "FORBIDDEN" => {
+                return Ok(json!({ "constraint_id": "FORBIDDEN" }));
+            }
+            _ => println_err!("Unsupported constraint type \"{}\"", constraint_id),
+        }
+    }
+}
+
+const AUTH_RULES_HEADERS: [(&str, &str); 6] = [
+    ("auth_type", "Type"),
+    ("auth_action", "Action"),
+    ("field", "Field"),
+    ("old_value", "Old Value"),
+    ("new_value", "New Value"),
+    ("constraint", "Constraint"),
+];
+
+fn auth_rules_rows(ctx: &CommandContext, rules: AuthRulesData) -> Vec<JsonValue> {
+    let txn_titles = ctx.get_txn_titles();
+    rules
         .into_iter()
         .map(|rule| {
-            let auth_type =
-                LedgerHelpers::get_txn_title(&JsonValue::String(rule.auth_type.clone()));
+            let auth_type = LedgerHelpers::get_txn_title(
+                &JsonValue::String(rule.auth_type.clone()),
+                &txn_titles,
+            );
             let action = rule.auth_action;
             let field = rule.field;
             let old_value = if action == "ADD" {
@@ -237,32 +1024,31 @@ fn print_auth_rules(rules: AuthRulesData) {
                 "constraint": ::serde_json::to_string_pretty(&rule.constraint).unwrap(),
             })
         })
-        .collect::<Vec<JsonValue>>();
+        .collect::<Vec<JsonValue>>()
+}
 
-    print_list_table(
-        &constraints,
-        &[
-            ("auth_type", "Type"),
-            ("auth_action", "Action"),
-            ("field", "Field"),
-            ("old_value", "Old Value"),
-            ("new_value", "New Value"),
-            ("constraint", "Constraint"),
-        ],
-        "There are no rules set",
-    );
+fn print_auth_rules(ctx: &CommandContext, rules: AuthRulesData) {
+    let constraints = auth_rules_rows(ctx, rules);
+
+    if constraints.is_empty() {
+        println_succ!("There are no rules set");
+        return;
+    }
+
+    print_paged(ctx, &format_list_table(&constraints, &AUTH_RULES_HEADERS));
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use crate::{
-        commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool},
+        commands::{setup, setup_with_wallet_and_pool, tear_down, tear_down_with_wallet_and_pool},
         ledger::tests::use_trustee,
     };
 
     mod auth_rule {
         use super::*;
+        use crate::utils::environment::EnvironmentUtils;
 
         const AUTH_TYPE: &str = "NYM";
         const AUTH_ACTION: &str = "ADD";
@@ -310,6 +1096,24 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn auth_rule_works_for_review() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn_type", AUTH_TYPE.to_string());
+                params.insert("action", AUTH_ACTION.to_string());
+                params.insert("field", FIELD.to_string());
+                params.insert("new_value", NEW_VALUE.to_string());
+                params.insert("template", "trustee-only".to_string());
+                params.insert("review", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn get_auth_rule_works_for_one_constraint() {
             let ctx = setup_with_wallet_and_pool();
@@ -351,6 +1155,22 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn get_auth_rule_works_for_out() {
+            let ctx = setup_with_wallet_and_pool();
+            let path = EnvironmentUtils::tmp_file_path("auth_rules.json");
+            {
+                let cmd = get_auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("out", path.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let rules = read_file(&path).unwrap();
+            serde_json::from_str::<AuthRulesData>(&rules).unwrap();
+
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn get_auth_rule_works_for_no_constraint() {
             let ctx = setup_with_wallet_and_pool();
@@ -386,5 +1206,267 @@ pub mod tests {
             assert!(ctx.get_context_transaction().is_some());
             tear_down_with_wallet_and_pool(&ctx);
         }
+
+        #[test]
+        pub fn auth_rule_works_for_template() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn_type", AUTH_TYPE.to_string());
+                params.insert("action", AUTH_ACTION.to_string());
+                params.insert("field", FIELD.to_string());
+                params.insert("new_value", NEW_VALUE.to_string());
+                params.insert("template", "steward-or-trustee".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn auth_rule_works_for_unknown_template() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn_type", AUTH_TYPE.to_string());
+                params.insert("action", AUTH_ACTION.to_string());
+                params.insert("field", FIELD.to_string());
+                params.insert("new_value", NEW_VALUE.to_string());
+                params.insert("template", "anyone".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn auth_rule_works_for_constraint_and_template() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn_type", AUTH_TYPE.to_string());
+                params.insert("action", AUTH_ACTION.to_string());
+                params.insert("field", FIELD.to_string());
+                params.insert("new_value", NEW_VALUE.to_string());
+                params.insert("constraint", ROLE_CONSTRAINT.to_string());
+                params.insert("template", "trustee-only".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod auth_rule_templates {
+        use super::*;
+
+        #[test]
+        pub fn auth_rule_templates_works() {
+            let ctx = setup();
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("show", "templates".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn auth_rule_templates_works_for_unknown_value() {
+            let ctx = setup();
+            {
+                let cmd = auth_rule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("show", "rules".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn auth_rule_template_works_for_all_presets() {
+            for (name, _) in AUTH_RULE_TEMPLATES {
+                assert!(auth_rule_template(name).is_some());
+            }
+            assert!(auth_rule_template("unknown").is_none());
+        }
+
+        #[test]
+        pub fn expand_auth_rules_templates_works() {
+            let rules = r#"[{"auth_type":"1","auth_action":"ADD","field":"role","new_value":"101","template":"owner"}]"#;
+            let rules =
+                serde_json::from_str::<AuthRulesData>(&expand_auth_rules_templates(rules).unwrap())
+                    .unwrap();
+            assert_eq!(auth_rule_template("owner").unwrap(), rules[0].constraint);
+        }
+
+        #[test]
+        pub fn expand_auth_rules_templates_works_for_constraint_and_template() {
+            let rules = r#"[{"auth_type":"1","auth_action":"ADD","field":"role","new_value":"101","template":"owner","constraint":{"constraint_id":"FORBIDDEN"}}]"#;
+            expand_auth_rules_templates(rules).unwrap_err();
+        }
+    }
+
+    mod auth_rule_wizard {
+        use super::*;
+
+        fn scripted_input(answers: &[&str]) -> impl FnMut(&str) -> Option<String> {
+            let mut answers = answers
+                .iter()
+                .map(|answer| answer.to_string())
+                .collect::<Vec<String>>()
+                .into_iter();
+            move |_| answers.next()
+        }
+
+        #[test]
+        pub fn build_constraint_works_for_role() {
+            let mut input = scripted_input(&["ROLE", "TRUSTEE", "2", "y", ""]);
+            let constraint = build_constraint(&mut input, 0).unwrap();
+            assert_eq!(
+                json!({
+                    "constraint_id": "ROLE",
+                    "role": "0",
+                    "sig_count": 2,
+                    "need_to_be_owner": true,
+                    "off_ledger_signature": false,
+                    "metadata": {},
+                }),
+                constraint
+            );
+        }
+
+        #[test]
+        pub fn build_constraint_works_for_combination() {
+            let mut input = scripted_input(&["or", "", "ROLE", "", "", "", "", "FORBIDDEN"]);
+            let constraint = build_constraint(&mut input, 0).unwrap();
+            assert_eq!("OR", constraint["constraint_id"]);
+            assert_eq!("*", constraint["auth_constraints"][0]["role"]);
+            assert_eq!(1, constraint["auth_constraints"][0]["sig_count"]);
+            assert_eq!(
+                "FORBIDDEN",
+                constraint["auth_constraints"][1]["constraint_id"]
+            );
+        }
+
+        #[test]
+        pub fn build_constraint_works_for_retrying_invalid_answers() {
+            let mut input =
+                scripted_input(&["UNKNOWN", "ROLE", "OWNER", "STEWARD", "x", "1", "", ""]);
+            let constraint = build_constraint(&mut input, 0).unwrap();
+            assert_eq!("2", constraint["role"]);
+        }
+
+        #[test]
+        pub fn build_constraint_works_for_unavailable_input() {
+            let mut input = scripted_input(&["AND", "2", "ROLE"]);
+            build_constraint(&mut input, 0).unwrap_err();
+        }
+
+        #[test]
+        pub fn auth_rule_wizard_works_for_batch_mode() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = auth_rule_wizard_command::new();
+                let mut params = CommandParams::new();
+                params.insert("txn_type", "NYM".to_string());
+                params.insert("action", "ADD".to_string());
+                params.insert("field", "role".to_string());
+                params.insert("new_value", "101".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod auth_rules_diff {
+        use super::*;
+        use crate::utils::environment::EnvironmentUtils;
+
+        fn rule(auth_type: &str, new_value: &str, constraint: JsonValue) -> AuthRuleData {
+            AuthRuleData {
+                auth_type: auth_type.to_string(),
+                auth_action: "ADD".to_string(),
+                field: "role".to_string(),
+                old_value: Some("*".to_string()),
+                new_value: Some(new_value.to_string()),
+                constraint,
+            }
+        }
+
+        fn role_constraint(role: &str) -> JsonValue {
+            json!({"constraint_id": "ROLE", "role": role, "sig_count": 1, "need_to_be_owner": false, "metadata": {}})
+        }
+
+        #[test]
+        pub fn diff_auth_rules_works_for_equal_rules() {
+            let ledger = vec![rule("1", "101", role_constraint("0"))];
+            let policy = vec![rule(
+                "NYM",
+                "101",
+                json!({"constraint_id": "ROLE", "role": "0", "sig_count": 1}),
+            )];
+            let diff = diff_auth_rules(&ledger, &policy).unwrap();
+            assert!(diff.is_empty());
+        }
+
+        #[test]
+        pub fn diff_auth_rules_works_for_changes() {
+            let ledger = vec![
+                rule("1", "101", role_constraint("0")),
+                rule("1", "201", role_constraint("0")),
+            ];
+            let policy = vec![
+                rule("NYM", "101", role_constraint("2")),
+                rule("NYM", "2", role_constraint("0")),
+            ];
+            let diff = diff_auth_rules(&ledger, &policy).unwrap();
+            assert_eq!(3, diff.len());
+            assert_eq!(AuthRuleStatus::Changed, diff[0].status);
+            assert_eq!(AuthRuleStatus::Added, diff[1].status);
+            assert_eq!(AuthRuleStatus::Removed, diff[2].status);
+            assert_eq!(
+                Some("201".to_string()),
+                diff[2].ledger.as_ref().unwrap().new_value
+            );
+        }
+
+        #[test]
+        pub fn diff_auth_rules_works_for_unknown_txn_alias() {
+            let policy = vec![rule("UNKNOWN", "101", role_constraint("0"))];
+            diff_auth_rules(&vec![], &policy).unwrap_err();
+        }
+
+        #[test]
+        pub fn auth_rules_diff_works() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let path = EnvironmentUtils::tmp_file_path("auth_rules_policy.json");
+            write_file(
+                &path,
+                &json!([{
+                    "auth_type": "NYM",
+                    "auth_action": "ADD",
+                    "field": "role",
+                    "new_value": "101",
+                    "constraint": role_constraint("0"),
+                }])
+                .to_string(),
+            )
+            .unwrap();
+            {
+                let cmd = auth_rules_diff_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path.to_str().unwrap().to_string());
+                params.insert("reconcile", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
     }
 }
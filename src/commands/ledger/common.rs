@@ -3,20 +3,40 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::CommandContext,
+    command_executor::{CommandContext, CommandParams},
     error::CliResult,
-    tools::ledger::{parse_transaction_response, Ledger, Response, ResponseType},
-    utils::table::print_table,
+    params_parser::ParamParser,
+    tools::ledger::{
+        helpers::LedgerHelpers, parse_transaction_response, reject_reason::reject_hint,
+        ActionProgress, Ledger, Response, ResponseType, ACTION_TIMEOUT_REPLY,
+    },
+    utils::{
+        file::write_file,
+        table::{print_list_table, print_table},
+        term::is_colored,
+    },
 };
 
+use super::auth_rule::{AuthRuleData, AuthRulesData};
 use crate::{
-    error::CliError,
-    tools::{pool::Pool, wallet::Wallet},
+    error::{report_error, CliError, ErrorCode},
+    tools::{
+        pool::{pool_config::JournalEntry, Pool},
+        wallet::Wallet,
+    },
 };
-use indy_utils::did::DidValue;
-use indy_vdr::pool::PreparedRequest;
+use indy_utils::{did::DidValue, hash::SHA256};
+use indy_vdr::{ledger::constants::NYM, pool::PreparedRequest};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, Write},
+    rc::Rc,
+};
+
+/// Spinner shown while waiting for node replies, switching the frame every `SPINNER_FRAME_MS`.
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_FRAME_MS: u128 = 200;
 
 macro_rules! send_write_request {
     ($ctx:expr, $params:expr, $request:expr, $wallet:expr, $submitter_did:expr) => {{
@@ -30,19 +50,44 @@ macro_rules! send_write_request {
             Some(endorser_did) => {
                 send = false;
                 Ledger::append_request_endorser($request, &endorser_did).map_err(|err| {
-                    println_err!("{}", err.message(None));
+                    println_err!("{}", err.report(None));
                 })?
             }
             None => {}
         };
 
+        if send {
+            if let Some(entry) =
+                super::super::common::find_journal_entry($ctx, $params, $request)?
+            {
+                println_warn!(
+                    "Identical transaction has already been accepted by the Ledger (seqNo {}). It is not sent again.",
+                    entry.seq_no
+                );
+                return Ok(());
+            }
+        }
+
+        if send {
+            super::super::common::precheck_write_request($ctx, $params, $request, $submitter_did)?;
+        }
+
+        $ctx.mark_timing_phase("build");
         if sign {
             Ledger::sign_request($wallet, $submitter_did, $request).map_err(|err| {
-                println_err!("{}", err.message(None));
+                println_err!("{}", err.report(None));
             })?;
+            $ctx.mark_timing_phase("sign");
         };
 
-        send_request!($ctx, $params, $request, send)
+        if super::super::common::print_dry_run($ctx, $params, $request, $submitter_did)? {
+            return Ok(());
+        }
+
+        let (response_json, response) = send_request!($ctx, $params, $request, send);
+        super::super::common::add_journal_entry($ctx, $params, $request, &response);
+        super::super::common::write_receipt($ctx, $params, $request, &response);
+        (response_json, response)
     }};
 }
 
@@ -50,6 +95,7 @@ macro_rules! send_read_request {
     ($ctx:expr, $params:expr, $request:expr) => {{
         let send = ParamParser::get_opt_bool_param("send", $params)?
             .unwrap_or(super::super::constants::SEND_REQUEST);
+        $ctx.mark_timing_phase("build");
         send_request!($ctx, $params, $request, send)
     }};
 }
@@ -57,10 +103,15 @@ macro_rules! send_read_request {
 macro_rules! send_request {
     ($ctx:expr, $params:expr, $request:expr, $send:expr) => {{
         if $send {
-            let pool = $ctx.ensure_connected_pool()?;
-            let response_json = Ledger::submit_request(&pool, $request).map_err(|err| {
-                println_err!("{}", err.message(None));
-            })?;
+            let pool = super::super::common::get_request_pool($ctx, $params)?;
+            super::super::common::trace_request($ctx, $request);
+            let response_json =
+                Ledger::submit_request_with_retries(&pool, $request, $ctx.get_request_retries())
+                    .map_err(|err| {
+                        println_err!("{}", err.report(None));
+                    })?;
+            $ctx.mark_timing_phase("submit");
+            super::super::common::trace_response($ctx, &response_json);
 
             let response = serde_json::from_str::<Response<JsonValue>>(&response_json)
                 .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
@@ -104,6 +155,430 @@ macro_rules! get_transaction_to_use {
     })
 }
 
+/// Returns the connected pool waiting for the replies up to `timeout` parameter if it is set.
+/// The connection to the pool is reused.
+pub fn get_request_pool(ctx: &CommandContext, params: &CommandParams) -> Result<Rc<Pool>, ()> {
+    let pool = ctx.ensure_connected_pool()?;
+    match ParamParser::get_opt_number_param::<u64>("timeout", params)? {
+        Some(timeout) => pool
+            .with_request_timeout(timeout)
+            .map(Rc::new)
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name)))),
+        None => Ok(pool),
+    }
+}
+
+/// Returns the number of requests to submit concurrently from `parallel` parameter (1 by default).
+pub fn get_parallel_param(params: &CommandParams) -> Result<usize, ()> {
+    match ParamParser::get_opt_number_param::<usize>("parallel", params)? {
+        Some(0) => {
+            println_err!("Number of concurrent requests must be greater than 0.");
+            Err(())
+        }
+        Some(parallel) => Ok(parallel),
+        None => Ok(1),
+    }
+}
+
+/// Returns the journaled transaction identical to the request if `idempotent=true` parameter is passed.
+pub fn find_journal_entry(
+    ctx: &CommandContext,
+    params: &CommandParams,
+    request: &PreparedRequest,
+) -> Result<Option<JournalEntry>, ()> {
+    let idempotent = ParamParser::get_opt_bool_param("idempotent", params)?.unwrap_or(false);
+    if !idempotent {
+        return Ok(None);
+    }
+    let pool = ctx.ensure_connected_pool()?;
+    pool.find_journal_entry(&Ledger::request_digest(request))
+        .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))
+}
+
+/// Warns if the write transaction is likely to be rejected because of the submitter role
+/// when `precheck=true` parameter is passed. Only rules for adding new ledger entries are checked.
+pub fn precheck_write_request(
+    ctx: &CommandContext,
+    params: &CommandParams,
+    request: &PreparedRequest,
+    submitter_did: &DidValue,
+) -> Result<(), ()> {
+    let precheck = ParamParser::get_opt_bool_param("precheck", params)?.unwrap_or(false);
+    if !precheck {
+        return Ok(());
+    }
+    let pool = ctx.ensure_connected_pool()?;
+
+    let submitter_role = match get_nym_data(&pool, Some(submitter_did), submitter_did)? {
+        Some(data) => data["role"].as_str().unwrap_or_default().to_string(),
+        None => {
+            println_warn!(
+                "Precheck: submitter DID \"{}\" is not written on the Ledger. The transaction will be rejected.",
+                submitter_did
+            );
+            return Ok(());
+        }
+    };
+
+    let operation = &request.req_json["operation"];
+    if request.txn_type == NYM {
+        if let Some(dest) = operation["dest"].as_str() {
+            let dest = DidValue(dest.to_string());
+            if get_nym_data(&pool, Some(submitter_did), &dest)?.is_some() {
+                println_warn!("Precheck: NYM \"{}\" already exists. Only rules for adding new entries are checked.", dest);
+                return Ok(());
+            }
+        }
+    }
+
+    let rules = get_applicable_auth_rules(&pool, request, submitter_did)?;
+    if rules.is_empty() {
+        println_warn!(
+            "Precheck: no auth rules found for adding {} transaction.",
+            LedgerHelpers::get_txn_title(&json!(request.txn_type), &ctx.get_txn_titles())
+        );
+        return Ok(());
+    }
+
+    let is_owner = operation["dest"]
+        .as_str()
+        .map(|dest| dest == submitter_did.to_short().to_string())
+        .unwrap_or(true);
+    let role_titles = ctx.get_role_titles();
+
+    for rule in rules {
+        if !is_constraint_satisfied(&rule.constraint, &submitter_role, is_owner) {
+            println_warn!(
+                "Precheck: the transaction will likely be rejected. Submitter role is {}, but the rule for field \"{}\" requires: {}",
+                role_title(&submitter_role, &role_titles),
+                rule.field,
+                describe_constraint(&rule.constraint, &role_titles)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reads the Ledger auth rules for adding the transaction of the request type.
+fn get_applicable_auth_rules(
+    pool: &Pool,
+    request: &PreparedRequest,
+    submitter_did: &DidValue,
+) -> Result<Vec<AuthRuleData>, ()> {
+    let rules = Ledger::build_get_auth_rule_request(
+        Some(pool),
+        Some(submitter_did),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .and_then(|request| Ledger::submit_request(pool, &request))
+    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+    let rules = serde_json::from_str::<Response<JsonValue>>(&rules)
+        .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+    let rules: AuthRulesData =
+        serde_json::from_value(handle_transaction_response(rules)?["data"].clone())
+            .map_err(|_| println_err!("Wrong data has been received"))?;
+
+    Ok(rules
+        .into_iter()
+        .filter(|rule| {
+            is_auth_rule_applicable(rule, &request.txn_type, &request.req_json["operation"])
+        })
+        .collect())
+}
+
+/// Prints the prepared write request instead of sending it when `dry_run=true` parameter is passed:
+/// its size, the auth rules for adding it and whether the transaction author agreement acceptance is attached.
+/// Returns `true` if the request must not be sent.
+pub fn print_dry_run(
+    ctx: &CommandContext,
+    params: &CommandParams,
+    request: &PreparedRequest,
+    submitter_did: &DidValue,
+) -> Result<bool, ()> {
+    let dry_run = ParamParser::get_opt_bool_param("dry_run", params)?.unwrap_or(false);
+    if !dry_run {
+        return Ok(false);
+    }
+    let pool = ctx.get_connected_pool();
+
+    let active_taa = match pool.as_deref() {
+        Some(pool) => get_active_transaction_author_agreement(pool)?,
+        None => None,
+    };
+    let size = serde_json::to_vec(&request.req_json)
+        .map(|bytes| bytes.len())
+        .unwrap_or_default();
+
+    println_succ!("Dry run: the transaction has been prepared but not sent.");
+    print_table(
+        &json!({
+            "type": LedgerHelpers::get_txn_title(&json!(request.txn_type), &ctx.get_txn_titles()),
+            "digest": Ledger::request_digest(request),
+            "size": format!("{} bytes", size),
+            "signed": !request.req_json["signature"].is_null()
+                || !request.req_json["signatures"].is_null(),
+            "taa": describe_taa_acceptance(&request.req_json, active_taa.as_ref(), pool.is_some()),
+        }),
+        &[
+            ("type", "Transaction"),
+            ("digest", "Digest"),
+            ("size", "Size"),
+            ("signed", "Signed"),
+            ("taa", "TAA acceptance"),
+        ],
+    );
+
+    let pool = match pool {
+        Some(pool) => pool,
+        None => {
+            println_warn!("Auth rules are not shown: there is no opened pool.");
+            return Ok(true);
+        }
+    };
+    let role_titles = ctx.get_role_titles();
+    let rules = get_applicable_auth_rules(&pool, request, submitter_did)?
+        .into_iter()
+        .map(|rule| {
+            json!({
+                "field": rule.field,
+                "new_value": rule.new_value,
+                "constraint": describe_constraint(&rule.constraint, &role_titles),
+            })
+        })
+        .collect::<Vec<JsonValue>>();
+    print_list_table(
+        &rules,
+        &[
+            ("field", "Field"),
+            ("new_value", "New value"),
+            ("constraint", "Constraint"),
+        ],
+        "No auth rules found for adding the transaction.",
+    );
+    Ok(true)
+}
+
+/// Describes the transaction author agreement acceptance of the request
+/// against the agreement active on the Ledger.
+fn describe_taa_acceptance(
+    req_json: &JsonValue,
+    active_taa: Option<&(String, String, Option<String>)>,
+    connected: bool,
+) -> String {
+    let digest = req_json["taaAcceptance"]["taaDigest"].as_str();
+    let active = active_taa.map(|(text, version, digest)| {
+        let digest = digest
+            .clone()
+            .unwrap_or_else(|| calculate_taa_digest(text, version));
+        (version, digest)
+    });
+    match (digest, active) {
+        (Some(digest), Some((version, active_digest))) if digest == active_digest => {
+            format!("Attached (active version {})", version)
+        }
+        (Some(_), Some((version, _))) => {
+            format!("Attached, but does not match active version {}", version)
+        }
+        (Some(_), None) if connected => {
+            "Attached, but the Ledger has no active agreement".to_string()
+        }
+        (Some(_), None) => "Attached".to_string(),
+        (None, Some((version, _))) => {
+            format!("Not attached, but the Ledger requires version {}", version)
+        }
+        (None, None) if connected => "Not required".to_string(),
+        (None, None) => "Not attached".to_string(),
+    }
+}
+
+/// Auth rule is applicable to the request if it is a rule for adding the transaction type
+/// and the rule field is either `*` or the request contains the field with the rule value.
+fn is_auth_rule_applicable(rule: &AuthRuleData, txn_type: &str, operation: &JsonValue) -> bool {
+    if rule.auth_type != txn_type || rule.auth_action != "ADD" {
+        return false;
+    }
+    if rule.field == "*" {
+        return true;
+    }
+    let value = if operation[&rule.field].is_null() {
+        &operation["data"][&rule.field]
+    } else {
+        &operation[&rule.field]
+    };
+    let value = match value {
+        // NYM without role is added as an identity owner
+        JsonValue::Null if rule.field == "role" => String::new(),
+        JsonValue::Null => return false,
+        JsonValue::String(value) => value.to_string(),
+        value => value.to_string().replace('"', "'"),
+    };
+    match rule.new_value.as_deref() {
+        Some("*") => true,
+        Some(new_value) => new_value.replace(' ', "") == value.replace(' ', ""),
+        None => value.is_empty(),
+    }
+}
+
+/// Evaluates the auth constraint for the single submitter signature.
+fn is_constraint_satisfied(constraint: &JsonValue, role: &str, is_owner: bool) -> bool {
+    match constraint["constraint_id"].as_str() {
+        Some("ROLE") => {
+            let sig_count = constraint["sig_count"].as_u64().unwrap_or(1);
+            if sig_count == 0 {
+                return true;
+            }
+            let role_matches = match constraint["role"].as_str() {
+                Some("*") => true,
+                Some(required) => required == role,
+                None => role.is_empty(),
+            };
+            let owner_matches =
+                !constraint["need_to_be_owner"].as_bool().unwrap_or(false) || is_owner;
+            sig_count == 1 && role_matches && owner_matches
+        }
+        Some("AND") => constraint["auth_constraints"]
+            .as_array()
+            .map(|constraints| {
+                constraints
+                    .iter()
+                    .all(|constraint| is_constraint_satisfied(constraint, role, is_owner))
+            })
+            .unwrap_or(false),
+        Some("OR") => constraint["auth_constraints"]
+            .as_array()
+            .map(|constraints| {
+                constraints
+                    .iter()
+                    .any(|constraint| is_constraint_satisfied(constraint, role, is_owner))
+            })
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn describe_constraint(constraint: &JsonValue, role_titles: &BTreeMap<String, String>) -> String {
+    match constraint["constraint_id"].as_str() {
+        Some("ROLE") => {
+            let mut description = format!(
+                "{} {}",
+                constraint["sig_count"].as_u64().unwrap_or(1),
+                role_title(constraint["role"].as_str().unwrap_or_default(), role_titles)
+            );
+            if constraint["need_to_be_owner"].as_bool().unwrap_or(false) {
+                description.push_str(" (owner)");
+            }
+            description
+        }
+        Some(id @ "AND") | Some(id @ "OR") => {
+            let constraints = constraint["auth_constraints"]
+                .as_array()
+                .map(|constraints| {
+                    constraints
+                        .iter()
+                        .map(|constraint| describe_constraint(constraint, role_titles))
+                        .collect::<Vec<String>>()
+                })
+                .unwrap_or_default();
+            format!("({})", constraints.join(&format!(" {} ", id)))
+        }
+        _ => "FORBIDDEN".to_string(),
+    }
+}
+
+fn role_title(role: &str, role_titles: &BTreeMap<String, String>) -> String {
+    match role {
+        "*" => "ANY".to_string(),
+        "" => "IDENTITY_OWNER".to_string(),
+        role => LedgerHelpers::get_role_title(&json!(role), role_titles)
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// Records the accepted write transaction into the pool journal
+/// if `set txn-journal=true` was called or `idempotent=true` parameter is passed.
+pub fn add_journal_entry(
+    ctx: &CommandContext,
+    params: &CommandParams,
+    request: &PreparedRequest,
+    response: &Response<JsonValue>,
+) {
+    let idempotent = ParamParser::get_opt_bool_param("idempotent", params)
+        .ok()
+        .flatten()
+        .unwrap_or(false);
+    if !idempotent && !ctx.is_txn_journal() {
+        return;
+    }
+
+    let (pool, result) = match (ctx.get_connected_pool(), &response.result) {
+        (Some(pool), Some(result)) if response.op == ResponseType::REPLY => (pool, result),
+        _ => return,
+    };
+    let seq_no = match result["txnMetadata"]["seqNo"].as_u64() {
+        Some(seq_no) => seq_no,
+        None => return,
+    };
+
+    let entry = JournalEntry {
+        digest: Ledger::request_digest(request),
+        txn_type: request.txn_type.to_string(),
+        seq_no,
+        txn_time: result["txnMetadata"]["txnTime"].as_u64(),
+    };
+    if let Err(err) = pool.add_journal_entry(entry) {
+        println_warn!(
+            "Transaction has not been recorded into the journal: {}",
+            err.message(Some(&pool.name))
+        );
+    }
+}
+
+/// Writes JSON receipt of the write transaction accepted by the Ledger
+/// into the file passed as `receipt` parameter.
+pub fn write_receipt(
+    ctx: &CommandContext,
+    params: &CommandParams,
+    request: &PreparedRequest,
+    response: &Response<JsonValue>,
+) {
+    let path = match ParamParser::get_opt_str_param("receipt", params)
+        .ok()
+        .flatten()
+    {
+        Some(path) => path,
+        None => return,
+    };
+    let result = match &response.result {
+        Some(result) if response.op == ResponseType::REPLY => result,
+        _ => return,
+    };
+
+    let receipt = json!({
+        "pool": ctx.get_connected_pool().map(|pool| pool.name.clone()),
+        "digest": Ledger::request_digest(request),
+        "reqId": request.req_id,
+        "txnType": request.txn_type,
+        "seqNo": result["txnMetadata"]["seqNo"],
+        "txnTime": result["txnMetadata"]["txnTime"],
+        "txnId": result["txnMetadata"]["txnId"],
+        "rootHash": result["rootHash"],
+        "auditPath": result["auditPath"],
+        "reqSignature": result["reqSignature"],
+        "multiSignature": result["multiSignature"],
+    });
+    let content = serde_json::to_string_pretty(&receipt).unwrap_or_default();
+    match write_file(path, &content) {
+        Ok(()) => println_succ!("Transaction receipt has been written to \"{}\"", path),
+        Err(err) => println_warn!("Transaction receipt has not been written: {}", err),
+    }
+}
+
 pub fn handle_transaction_response(response: Response<JsonValue>) -> Result<JsonValue, ()> {
     match response {
         Response {
@@ -121,7 +596,12 @@ pub fn handle_transaction_response(response: Response<JsonValue>) -> Result<Json
             result: None,
             reason: Some(reason),
         } => {
-            println_err!("Transaction has been rejected: {}", reason);
+            let message = format!("Transaction has been rejected: {}", reason);
+            report_error(ErrorCode::LedgerReject, &message);
+            println_err!("{}", message);
+            if let Some(hint) = reject_hint(&reason) {
+                println_acc!("Hint: {}", hint);
+            }
             Err(())
         }
         _ => {
@@ -176,7 +656,7 @@ pub fn get_current_verkey(
     //TODO: There nym is requested. Due to freshness issues response might be stale or outdated. Something should be done with it
     let response_json = Ledger::build_get_nym_request(Some(pool), Some(did), did)
         .and_then(|mut request| Ledger::sign_and_submit_request(pool, store, did, &mut request))
-        .map_err(|err| println_err!("{}", err.message(Some(&pool.name))))?;
+        .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
     let response: Response<serde_json::Value> =
         serde_json::from_str::<Response<serde_json::Value>>(&response_json)
             .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
@@ -187,12 +667,34 @@ pub fn get_current_verkey(
     Ok(verkey)
 }
 
+/// Reads NYM data of the DID, `None` is returned if the DID is not written on the Ledger.
+pub fn get_nym_data(
+    pool: &Pool,
+    submitter_did: Option<&DidValue>,
+    did: &DidValue,
+) -> Result<Option<JsonValue>, ()> {
+    let response = Ledger::build_get_nym_request(Some(pool), submitter_did, did)
+        .and_then(|request| Ledger::submit_request(pool, &request))
+        .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+
+    let response = serde_json::from_str::<Response<JsonValue>>(&response)
+        .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+    let result = handle_transaction_response(response)?;
+
+    match result["data"].as_str() {
+        Some(data) => serde_json::from_str::<JsonValue>(data)
+            .map(Some)
+            .map_err(|_| println_err!("Wrong data has been received")),
+        None => Ok(None),
+    }
+}
+
 pub fn get_active_transaction_author_agreement(
     pool: &Pool,
 ) -> Result<Option<(String, String, Option<String>)>, ()> {
     let response = Ledger::build_get_txn_author_agreement_request(Some(pool), None, None)
         .and_then(|request| Ledger::submit_request(pool, &request))
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
     let response = serde_json::from_str::<JsonValue>(&response)
         .map_err(|err| println_err!("Invalid transaction response: {:?}", err))?;
@@ -212,7 +714,75 @@ pub fn get_active_transaction_author_agreement(
     }
 }
 
+/// Calculates the transaction author agreement digest the same way the Ledger does:
+/// hex encoded sha256 of the version followed by the text.
+pub fn calculate_taa_digest(text: &str, version: &str) -> String {
+    hex::encode(SHA256::digest(format!("{}{}", version, text).as_bytes()))
+}
+
+/// Returns the latest acceptance mechanisms list set on the Ledger as `(label, description)` pairs.
+pub fn get_acceptance_mechanisms(pool: &Pool) -> Result<Vec<(String, String)>, ()> {
+    let response = Ledger::build_get_acceptance_mechanisms_request(Some(pool), None, None, None)
+        .and_then(|request| Ledger::submit_request(pool, &request))
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+    let response = serde_json::from_str::<JsonValue>(&response)
+        .map_err(|err| println_err!("Invalid transaction response: {:?}", err))?;
+
+    let aml = match response["result"]["data"]["aml"].as_object() {
+        Some(aml) => aml
+            .iter()
+            .map(|(label, description)| {
+                let description = description
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| description.to_string());
+                (label.to_string(), description)
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok(aml)
+}
+
+/// Prints the exact request sent to the Ledger if `set trace-requests=true` was called.
+pub fn trace_request(ctx: &CommandContext, request: &PreparedRequest) {
+    if ctx.is_trace_requests() {
+        println_acc!("Request:");
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&request.req_json).unwrap_or_default()
+        );
+    }
+}
+
+/// Prints the raw Ledger reply if `set trace-requests=true` was called.
+pub fn trace_response(ctx: &CommandContext, response: &str) {
+    if ctx.is_trace_requests() {
+        println_acc!("Reply:");
+        println!("{}", response);
+    }
+}
+
+pub fn sign_and_submit_request(
+    ctx: &CommandContext,
+    wallet: &Wallet,
+    pool: &Pool,
+    submitter_did: &DidValue,
+    request: &mut PreparedRequest,
+) -> CliResult<String> {
+    ctx.mark_timing_phase("build");
+    Ledger::sign_request(wallet, submitter_did, request)?;
+    ctx.mark_timing_phase("sign");
+    trace_request(ctx, request);
+    let response = Ledger::submit_request(pool, request)?;
+    ctx.mark_timing_phase("submit");
+    trace_response(ctx, &response);
+    Ok(response)
+}
+
 pub fn sign_and_submit_action(
+    ctx: &CommandContext,
     wallet: &Wallet,
     pool: &Pool,
     submitter_did: &DidValue,
@@ -225,16 +795,71 @@ pub fn sign_and_submit_action(
         None => None,
     };
 
+    ctx.mark_timing_phase("build");
     Ledger::sign_request(wallet, submitter_did, request)?;
-    let replies =
-        Ledger::submit_action(pool, &request, nodes.as_ref().map(String::as_ref), timeout)?;
+    ctx.mark_timing_phase("sign");
+    trace_request(ctx, request);
+    let replies = Ledger::submit_action(
+        pool,
+        &request,
+        nodes.as_ref().map(String::as_ref),
+        timeout,
+        &mut print_action_progress,
+    );
+    if is_colored() {
+        print!("\r\x1b[2K");
+        io::stdout().flush().ok();
+    }
+    let replies = replies?;
+    ctx.mark_timing_phase("submit");
 
     let replies: HashMap<String, String> = replies
         .into_iter()
         .map(|(node, reply)| (node, reply.to_string()))
         .collect();
 
-    Ok(json!(replies).to_string())
+    let replies = json!(replies).to_string();
+    trace_response(ctx, &replies);
+    Ok(replies)
+}
+
+/// Prints node replies as they arrive and a spinner with the nodes which have not replied yet.
+fn print_action_progress(progress: ActionProgress) {
+    match progress {
+        ActionProgress::Replied {
+            node,
+            reply,
+            elapsed,
+        } => {
+            if is_colored() {
+                print!("\r\x1b[2K");
+            }
+            let elapsed = elapsed.as_secs_f32();
+            let op = serde_json::from_str::<JsonValue>(reply)
+                .ok()
+                .and_then(|reply| reply["op"].as_str().map(String::from));
+            if reply == ACTION_TIMEOUT_REPLY {
+                println_warn!("{}: no reply within {:.1}s", node, elapsed);
+            } else if op.as_deref() == Some("REPLY") {
+                println!("{}: replied in {:.1}s", node, elapsed);
+            } else {
+                println_warn!("{}: rejected in {:.1}s", node, elapsed);
+            }
+        }
+        ActionProgress::Waiting { pending, elapsed } => {
+            if is_colored() && !pending.is_empty() {
+                let frame = SPINNER_FRAMES
+                    [(elapsed.as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_FRAMES.len()];
+                print!(
+                    "\r\x1b[2K{} Waiting for {}... {}s",
+                    frame,
+                    pending.join(", "),
+                    elapsed.as_secs()
+                );
+                io::stdout().flush().ok();
+            }
+        }
+    }
 }
 
 pub fn set_author_agreement(ctx: &CommandContext, request: &mut PreparedRequest) -> Result<(), ()> {
@@ -257,7 +882,7 @@ pub fn set_author_agreement(ctx: &CommandContext, request: &mut PreparedRequest)
             &acc_mech_type,
             time_of_acceptance,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
     };
     Ok(())
 }
@@ -273,7 +898,7 @@ pub fn print_transaction_response(
 
     let (metadata_headers, metadata, data) = match parse_transaction_response(result) {
         Ok(val) => val,
-        Err(_) => return,
+        Err(err) => return println_err!("{}", err.message(None)),
     };
 
     println_succ!("Metadata:");
@@ -292,3 +917,213 @@ pub fn print_transaction_response(
     println_succ!("Data:");
     print_table(data, &data_headers);
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    mod precheck {
+        use super::*;
+
+        fn rule(field: &str, new_value: &str, constraint: JsonValue) -> AuthRuleData {
+            AuthRuleData {
+                auth_type: NYM.to_string(),
+                auth_action: "ADD".to_string(),
+                field: field.to_string(),
+                old_value: None,
+                new_value: Some(new_value.to_string()),
+                constraint,
+            }
+        }
+
+        fn role_constraint(role: &str) -> JsonValue {
+            json!({"constraint_id": "ROLE", "role": role, "sig_count": 1, "need_to_be_owner": false})
+        }
+
+        #[test]
+        pub fn is_auth_rule_applicable_works() {
+            let operation = json!({"type": "1", "dest": "did", "role": "0"});
+            let trustee_rule = rule("role", "0", role_constraint("0"));
+            assert!(is_auth_rule_applicable(&trustee_rule, NYM, &operation));
+
+            let endorser_rule = rule("role", "101", role_constraint("0"));
+            assert!(!is_auth_rule_applicable(&endorser_rule, NYM, &operation));
+
+            let owner_rule = rule("role", "", role_constraint("101"));
+            assert!(is_auth_rule_applicable(
+                &owner_rule,
+                NYM,
+                &json!({"dest": "did"})
+            ));
+            assert!(!is_auth_rule_applicable(&owner_rule, "101", &operation));
+        }
+
+        #[test]
+        pub fn is_constraint_satisfied_works() {
+            let constraint = json!({
+                "constraint_id": "OR",
+                "auth_constraints": [role_constraint("0"), role_constraint("2")]
+            });
+            assert!(is_constraint_satisfied(&constraint, "0", false));
+            assert!(is_constraint_satisfied(&constraint, "2", false));
+            assert!(!is_constraint_satisfied(&constraint, "101", false));
+            assert!(!is_constraint_satisfied(&constraint, "", false));
+
+            let owner = json!({"constraint_id": "ROLE", "role": "*", "sig_count": 1, "need_to_be_owner": true});
+            assert!(is_constraint_satisfied(&owner, "", true));
+            assert!(!is_constraint_satisfied(&owner, "0", false));
+
+            let multi = json!({"constraint_id": "ROLE", "role": "0", "sig_count": 2});
+            assert!(!is_constraint_satisfied(&multi, "0", false));
+
+            assert!(!is_constraint_satisfied(
+                &json!({"constraint_id": "FORBIDDEN"}),
+                "0",
+                false
+            ));
+        }
+
+        #[test]
+        pub fn describe_constraint_works() {
+            let constraint = json!({
+                "constraint_id": "OR",
+                "auth_constraints": [role_constraint("0"), role_constraint("*")]
+            });
+            assert_eq!(
+                "(1 TRUSTEE OR 1 ANY)",
+                describe_constraint(&constraint, &BTreeMap::new())
+            );
+        }
+    }
+
+    mod dry_run {
+        use super::*;
+
+        #[test]
+        pub fn describe_taa_acceptance_works() {
+            let active = (
+                "indy agreement".to_string(),
+                "1.0".to_string(),
+                Some(calculate_taa_digest("indy agreement", "1.0")),
+            );
+            let accepted = json!({"taaAcceptance": {"taaDigest": active.2.clone().unwrap()}});
+            let outdated = json!({"taaAcceptance": {"taaDigest": "outdated"}});
+
+            assert_eq!(
+                "Attached (active version 1.0)",
+                describe_taa_acceptance(&accepted, Some(&active), true)
+            );
+            assert_eq!(
+                "Attached, but does not match active version 1.0",
+                describe_taa_acceptance(&outdated, Some(&active), true)
+            );
+            assert_eq!(
+                "Not attached, but the Ledger requires version 1.0",
+                describe_taa_acceptance(&json!({}), Some(&active), true)
+            );
+            assert_eq!(
+                "Not required",
+                describe_taa_acceptance(&json!({}), None, true)
+            );
+            assert_eq!("Attached", describe_taa_acceptance(&accepted, None, false));
+        }
+    }
+
+    mod taa_digest {
+        use super::*;
+
+        #[test]
+        pub fn calculate_taa_digest_works() {
+            assert_eq!(
+                "a7f0752ba35b283b47e70f683572340e7f1d78d7dae632e6425b5ef7848dfa74",
+                calculate_taa_digest("indy agreement", "1.0")
+            );
+        }
+    }
+
+    mod request_pool {
+        use super::*;
+        use crate::commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool};
+        use std::time::Duration;
+
+        #[test]
+        pub fn get_request_pool_works_for_timeout() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let mut params = CommandParams::new();
+                params.insert("timeout", "30".to_string());
+                let pool = get_request_pool(&ctx, &params).unwrap();
+                assert_eq!(Some(Duration::from_secs(30)), pool.request_timeout);
+                assert!(ctx.get_connected_pool().unwrap().request_timeout.is_none());
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_request_pool_works_for_no_timeout() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let pool = get_request_pool(&ctx, &CommandParams::new()).unwrap();
+                assert!(Rc::ptr_eq(&ctx.get_connected_pool().unwrap(), &pool));
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_request_pool_works_for_zero_timeout() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let mut params = CommandParams::new();
+                params.insert("timeout", "0".to_string());
+                assert!(get_request_pool(&ctx, &params).is_err());
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod receipt {
+        use super::*;
+        use crate::{
+            did::tests::{DID_MY1, DID_TRUSTEE},
+            utils::{environment::EnvironmentUtils, file::read_file},
+        };
+
+        #[test]
+        pub fn write_receipt_works() {
+            let ctx = CommandContext::new();
+            let request = Ledger::build_nym_request(
+                None,
+                &DidValue(DID_TRUSTEE.to_string()),
+                &DidValue(DID_MY1.to_string()),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            let response: Response<JsonValue> = serde_json::from_value(json!({
+                "op": "REPLY",
+                "result": {
+                    "txnMetadata": {"seqNo": 42, "txnTime": 1700000000, "txnId": "txn-id"},
+                    "rootHash": "root-hash",
+                    "auditPath": ["audit-path"],
+                    "reqSignature": {"type": "ED25519", "values": []}
+                }
+            }))
+            .unwrap();
+
+            let path = EnvironmentUtils::tmp_file_path("write_receipt_works.json");
+            let path = path.to_str().unwrap();
+            let mut params = CommandParams::new();
+            params.insert("receipt", path.to_string());
+
+            write_receipt(&ctx, &params, &request, &response);
+
+            let receipt: JsonValue = serde_json::from_str(&read_file(path).unwrap()).unwrap();
+            assert_eq!(json!(42), receipt["seqNo"]);
+            assert_eq!(json!(1700000000), receipt["txnTime"]);
+            assert_eq!(json!("root-hash"), receipt["rootHash"]);
+            assert_eq!(json!(Ledger::request_digest(&request)), receipt["digest"]);
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
@@ -4,3 +4,5 @@
 */
 pub const SIGN_REQUEST: bool = true;
 pub const SEND_REQUEST: bool = true;
+pub const MAX_ATTRIBUTES_COUNT: usize = 125;
+pub const DOMAIN_LEDGER_TYPE: i32 = 1;
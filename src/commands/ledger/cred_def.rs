@@ -3,7 +3,10 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
 };
@@ -30,11 +33,19 @@ pub mod cred_def_command {
                 .add_required_param("primary", "Primary key in json format")
                 .add_optional_param("revocation", "Revocation key in json format")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
-                .add_optional_param("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
                     Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
-                    The created request will be printed and stored into CLI context.")
+                    The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
                 .add_example(r#"ledger cred-def schema_id=1 signature_type=CL tag=1 primary={"n":"1","s":"2","rms":"3","r":{"age":"4","name":"5"},"rctxt":"6","z":"7"}"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -70,7 +81,7 @@ pub mod cred_def_command {
         });
 
         let mut request = Ledger::build_cred_def_request(pool.as_deref(), &submitter_did, cred_def)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         set_author_agreement(ctx, &mut request)?;
 
@@ -99,9 +110,11 @@ pub mod get_cred_def_command {
                 .add_required_param("schema_id", "Sequence number of schema")
                 .add_required_param("signature_type", "Signature type (only CL supported now)")
                 .add_optional_param("tag", "Allows to distinct between credential definitions for the same issuer and schema. Note that it is mandatory for indy-node version 1.4.x and higher")
-                .add_required_param("origin", "Credential definition owner DID")
+                .add_required_param_with_dynamic_completion("origin", "Credential definition owner DID", DynamicCompletionType::Did)
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger get-cred-def schema_id=1 signature_type=CL tag=1 origin=VsKV7grR1BUE29mG2Fm2kX")
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -121,7 +134,7 @@ pub mod get_cred_def_command {
 
         let request =
             Ledger::build_get_cred_def_request(pool.as_deref(), submitter_did.as_deref(), &id)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) = send_read_request!(&ctx, params, &request);
 
@@ -3,7 +3,7 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response, ResponseType},
 };
@@ -20,6 +20,7 @@ pub mod custom_command {
                 .add_example(r#"ledger custom {"reqId":1,"identifier":"V4SGRU86Z58d6TV7PBUe6f","operation":{"type":"105","dest":"V4SGRU86Z58d6TV7PBUe6f"},"protocolVersion":2}"#)
                 .add_example(r#"ledger custom {"reqId":2,"identifier":"V4SGRU86Z58d6TV7PBUe6f","operation":{"type":"1","dest":"VsKV7grR1BUE29mG2Fm2kX"},"protocolVersion":2} sign=true"#)
                 .add_example(r#"ledger custom context"#)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -66,10 +67,10 @@ pub mod custom_command {
             let wallet = ctx.ensure_opened_wallet()?;
             let submitter_did = ctx.ensure_active_did()?;
             Ledger::sign_and_submit_request(&pool, &wallet, &submitter_did, &mut transaction)
-                .map_err(|err| println_err!("{}", err.message(Some(&pool.name))))?
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?
         } else {
             Ledger::submit_request(&pool, &transaction)
-                .map_err(|err| println_err!("{}", err.message(Some(&pool.name))))?
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?
         };
 
         let response = serde_json::from_str::<Response<JsonValue>>(&response_json)
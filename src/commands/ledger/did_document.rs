@@ -0,0 +1,282 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    params_parser::ParamParser,
+    tools::{
+        did::{document::DidDocument, Did},
+        pool::Pool,
+    },
+};
+
+use indy_utils::did::DidValue;
+
+use serde_json::Value as JsonValue;
+
+use super::{attrib::get_endpoint, common::get_nym_data};
+
+const METHOD_SOV: &str = "sov";
+const METHOD_INDY: &str = "indy";
+
+pub mod did_document_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "did-document",
+        "Compose DID Document of the DID from NYM and `endpoint` ATTRIB set on the Ledger."
+    )
+    .add_required_param_with_dynamic_completion(
+        "did",
+        "DID of identity presented in Ledger",
+        DynamicCompletionType::Did
+    )
+    .add_optional_param(
+        "method",
+        "DID method to use in the document. One of: sov, indy (sov by default)"
+    )
+    .add_optional_param(
+        "namespace",
+        "Namespace of the Ledger for `indy` method (name of the connected pool by default)"
+    )
+    .add_example("ledger did-document did=VsKV7grR1BUE29mG2Fm2kX")
+    .add_example(
+        "ledger did-document did=VsKV7grR1BUE29mG2Fm2kX method=indy namespace=sovrin:staging"
+    )
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+        let submitter_did = ctx.get_active_did()?;
+
+        let did = ParamParser::get_did_param("did", params)?;
+        let method = ParamParser::get_opt_str_param("method", params)?.unwrap_or(METHOD_SOV);
+        let namespace = ParamParser::get_opt_str_param("namespace", params)?;
+
+        let document = compose_did_document(
+            &pool,
+            submitter_did.as_deref(),
+            &did,
+            method,
+            namespace.unwrap_or(&pool.name),
+        )?;
+
+        println_succ!("Following DID Document has been composed:");
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&document)
+                .map_err(|err| println_err!("Invalid DID Document: {}", err))?
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+/// Composes DID Document of the DID from its NYM and `endpoint` ATTRIB set on the Ledger.
+/// `namespace` is used only for `indy` method.
+fn compose_did_document(
+    pool: &Pool,
+    submitter_did: Option<&DidValue>,
+    did: &DidValue,
+    method: &str,
+    namespace: &str,
+) -> Result<JsonValue, ()> {
+    let did = did.to_short();
+    let id = match method {
+        METHOD_SOV => format!("did:{}:{}", METHOD_SOV, did),
+        METHOD_INDY => format!("did:{}:{}:{}", METHOD_INDY, namespace, did),
+        _ => {
+            println_err!(
+                "Unsupported DID method \"{}\". One of: {}, {}",
+                method,
+                METHOD_SOV,
+                METHOD_INDY
+            );
+            return Err(());
+        }
+    };
+
+    let nym = match get_nym_data(pool, submitter_did, &did)? {
+        Some(nym) => nym,
+        None => {
+            println_err!("NYM not found");
+            return Err(());
+        }
+    };
+
+    let verkey = match nym["verkey"].as_str() {
+        Some(verkey) => Did::expand_verkey(&did.to_string(), verkey)
+            .map_err(|err| println_err!("{}", err.report(None)))?,
+        None => {
+            println_err!("DID \"{}\" has no verkey set on the Ledger", did);
+            return Err(());
+        }
+    };
+
+    let diddoc_content = match &nym["diddocContent"] {
+        JsonValue::String(content) => Some(
+            serde_json::from_str::<JsonValue>(content)
+                .map_err(|_| println_err!("Invalid diddocContent has been received"))?,
+        ),
+        JsonValue::Null => None,
+        content => Some(content.clone()),
+    };
+
+    let endpoint = match diddoc_content {
+        Some(_) => None,
+        None => get_endpoint(pool, submitter_did, &did)?,
+    };
+
+    DidDocument::build(&id, &verkey, endpoint.as_ref(), diddoc_content.as_ref())
+        .map_err(|err| println_err!("{}", err.report(None)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{
+        did::tests::{DID_TRUSTEE, VERKEY_TRUSTEE},
+        ledger::{
+            attrib::{set_endpoint_command, tests::ensure_attrib_added},
+            tests::{use_new_endorser, use_new_identity},
+        },
+        setup_with_wallet_and_pool, tear_down_with_wallet_and_pool,
+    };
+
+    mod did_document {
+        use super::*;
+
+        fn compose(ctx: &CommandContext, did: &str, method: &str) -> JsonValue {
+            let pool = ctx.ensure_connected_pool().unwrap();
+            let submitter_did = ctx.get_active_did().unwrap();
+            compose_did_document(
+                &pool,
+                submitter_did.as_deref(),
+                &DidValue(did.to_string()),
+                method,
+                "test",
+            )
+            .unwrap()
+        }
+
+        #[test]
+        pub fn did_document_works() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = did_document_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let document = compose(&ctx, DID_TRUSTEE, METHOD_SOV);
+            let id = format!("did:sov:{}", DID_TRUSTEE);
+            assert_eq!(json!(id), document["id"]);
+            assert_eq!(json!(id), document["verificationMethod"][0]["controller"]);
+            assert_eq!(
+                json!(VERKEY_TRUSTEE),
+                document["verificationMethod"][0]["publicKeyBase58"]
+            );
+            assert_eq!(
+                document["verificationMethod"][0]["id"],
+                document["authentication"][0]
+            );
+            assert!(document.get("service").is_none());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn did_document_works_for_indy_method() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, verkey) = use_new_identity(&ctx);
+            {
+                let cmd = did_document_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("method", "indy".to_string());
+                params.insert("namespace", "test".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let document = compose(&ctx, &did, METHOD_INDY);
+            assert_eq!(json!(format!("did:indy:test:{}", did)), document["id"]);
+            assert_eq!(
+                json!(Did::expand_verkey(&did, &verkey).unwrap()),
+                document["verificationMethod"][0]["publicKeyBase58"]
+            );
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn did_document_works_for_endpoint() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = set_endpoint_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("address", "127.0.0.1:5555".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            ensure_attrib_added(
+                &ctx,
+                &did,
+                Some(r#"{"endpoint":{"ha":"127.0.0.1:5555"}}"#),
+                None,
+                None,
+            )
+            .unwrap();
+            let document = compose(&ctx, &did, METHOD_SOV);
+            assert_eq!(
+                json!([{
+                    "id": format!("did:sov:{}#ha", did),
+                    "type": "ha",
+                    "serviceEndpoint": "127.0.0.1:5555",
+                }]),
+                document["service"]
+            );
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn did_document_works_for_unknown_did() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = did_document_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", "LnXR1rPnncTPZvRdmJKhJQ".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn did_document_works_for_unsupported_method() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = did_document_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("method", "web".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn expand_verkey_works() {
+            let abbreviated = Did::abbreviate_verkey(DID_TRUSTEE, VERKEY_TRUSTEE).unwrap();
+            assert!(abbreviated.starts_with('~'));
+            assert_eq!(
+                VERKEY_TRUSTEE,
+                Did::expand_verkey(DID_TRUSTEE, &abbreviated).unwrap()
+            );
+        }
+    }
+}
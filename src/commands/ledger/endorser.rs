@@ -3,15 +3,33 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        read_user_input, Command, CommandContext, CommandMetadata, CommandParams, Precondition,
+    },
     params_parser::ParamParser,
-    tools::ledger::{parse_transaction_response, Ledger, Response},
-    utils::table::print_table,
+    tools::{
+        did::{key::Key, Did},
+        ledger::{parse_transaction_response, Ledger, Response},
+        pool::Pool,
+        wallet::Wallet,
+    },
+    utils::table::{print_list_table, print_table},
 };
 
-use super::common::handle_transaction_response;
+use super::{
+    common::{
+        get_nym_data, get_parallel_param, get_request_pool, handle_transaction_response,
+        trace_request, trace_response,
+    },
+    transaction::{check_envelope_network, RequestEnvelope},
+};
+use indy_utils::{base58, did::DidValue};
 use indy_vdr::pool::PreparedRequest;
 use serde_json::Value as JsonValue;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub mod endorse_transaction_command {
     use super::*;
@@ -26,6 +44,9 @@ pub mod endorse_transaction_command {
     )
     .add_example(r#"ledger endorse txn={"reqId":123456789,"type":"100"}"#)
     .add_example(r#"ledger endorse"#)
+    .add_precondition(Precondition::OpenedWallet)
+    .add_precondition(Precondition::ActiveDid)
+    .add_precondition(Precondition::ConnectedPool)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -38,13 +59,21 @@ pub mod endorse_transaction_command {
 
         let mut request = get_transaction_to_use!(ctx, param_txn);
 
+        verify_endorser(&request, &submitter_did)?;
+        if let Some(pool) = ctx.get_connected_pool() {
+            verify_author_signature(&pool, &submitter_did, &request)?;
+        }
+
         Ledger::multi_sign_request(&wallet, &submitter_did, &mut request)
-            .map_err(|err| println_err!("{}", err.message(Some(&wallet.name))))?;
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
 
         let (_, response) = send_request!(&ctx, params, &request, true);
 
-        let (metadata_headers, metadata, data) = handle_transaction_response(response)
-            .and_then(|result| parse_transaction_response(result))?;
+        let (metadata_headers, metadata, data) =
+            handle_transaction_response(response).and_then(|result| {
+                parse_transaction_response(result)
+                    .map_err(|err| println_err!("{}", err.report(None)))
+            })?;
 
         println_succ!("Transaction has been sent to Ledger.");
 
@@ -58,3 +87,509 @@ pub mod endorse_transaction_command {
         Ok(())
     }
 }
+
+pub mod endorse_inbox_command {
+    use super::*;
+
+    /// Subfolder of the inbox the processed requests are moved into.
+    const DONE_DIR: &str = "done";
+
+    struct InboxEntry {
+        path: PathBuf,
+        envelope: RequestEnvelope,
+    }
+
+    command!(CommandMetadata::build(
+        "endorse-inbox",
+        "List requests exported by `ledger export-request` into a directory and endorse selected ones."
+    )
+    .add_required_param("dir", "The path to directory containing exported requests awaiting endorsement.")
+    .add_optional_param("all", "Endorse all requests of the directory without asking (false by default)")
+    .add_optional_param("timeout", "Timeout for waiting for reply (in sec)")
+    .add_optional_param("parallel", "Number of endorsed requests to submit concurrently over the pool connection (1 by default)")
+    .add_example(r#"ledger endorse-inbox dir=/home/inbox"#)
+    .add_example(r#"ledger endorse-inbox dir=/home/inbox all=true"#)
+    .add_example(r#"ledger endorse-inbox dir=/home/inbox all=true parallel=10"#)
+    .add_precondition(Precondition::OpenedWallet)
+    .add_precondition(Precondition::ActiveDid)
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = get_request_pool(ctx, params)?;
+
+        let dir = ParamParser::get_str_param("dir", params)?;
+        let all = ParamParser::get_opt_bool_param("all", params)?.unwrap_or(false);
+        let parallel = get_parallel_param(params)?;
+
+        let entries = read_inbox(Path::new(dir))?;
+        if entries.is_empty() {
+            println_succ!("There are no requests awaiting endorsement in \"{}\".", dir);
+            return Ok(());
+        }
+
+        print_inbox(&entries);
+
+        let selected = if all {
+            (0..entries.len()).collect()
+        } else {
+            match read_user_input(
+                ctx,
+                "Requests to endorse (numbers separated by comma, empty to skip): ",
+            ) {
+                Some(selection) => parse_selection(&selection, entries.len())
+                    .map_err(|err| println_err!("{}", err))?,
+                None => {
+                    println!(
+                        "No requests have been selected. Use `all=true` to endorse all of them."
+                    );
+                    return Ok(());
+                }
+            }
+        };
+
+        let done_dir = Path::new(dir).join(DONE_DIR);
+        let mut failed = 0;
+        for chunk in selected.chunks(parallel) {
+            let chunk = chunk
+                .iter()
+                .map(|index| &entries[*index])
+                .collect::<Vec<&InboxEntry>>();
+
+            let results = endorse_entries(ctx, &wallet, &pool, &submitter_did, &chunk);
+            for (entry, endorsed) in chunk.iter().zip(results) {
+                if endorsed
+                    .and_then(|_| move_to_done(&entry.path, &done_dir))
+                    .is_err()
+                {
+                    failed += 1;
+                }
+            }
+        }
+
+        println!(
+            "{} of {} selected requests have been endorsed.",
+            selected.len() - failed,
+            selected.len()
+        );
+
+        trace!("execute <<");
+        if failed > 0 {
+            Err(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Reads all request envelopes of the directory. Other files are skipped with a warning.
+    fn read_inbox(dir: &Path) -> Result<Vec<InboxEntry>, ()> {
+        let files = fs::read_dir(dir).map_err(|err| {
+            println_err!("Unable to read directory \"{}\": {}", dir.display(), err)
+        })?;
+
+        let mut paths = files
+            .filter_map(|file| file.ok().map(|file| file.path()))
+            .filter(|path| path.is_file())
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+
+        let mut entries = Vec::new();
+        for path in paths {
+            let envelope = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<RequestEnvelope>(&content).map_err(|err| err.to_string())
+                })
+                .and_then(|envelope| envelope.validate().map(|_| envelope));
+            match envelope {
+                Ok(envelope) => entries.push(InboxEntry { path, envelope }),
+                Err(err) => println_warn!(
+                    "File \"{}\" is skipped as it does not contain a valid exported request: {}",
+                    path.display(),
+                    err
+                ),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn print_inbox(entries: &[InboxEntry]) {
+        let rows = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let operation = &entry.envelope.request["operation"];
+                json!({
+                    "number": index + 1,
+                    "file": entry.path.file_name().map(|name| name.to_string_lossy().to_string()),
+                    "author": entry.envelope.author,
+                    "type": operation["type"],
+                    "target": operation["dest"].as_str().or_else(|| operation["data"]["name"].as_str()),
+                    "endorser": entry.envelope.endorser,
+                })
+            })
+            .collect::<Vec<JsonValue>>();
+
+        print_list_table(
+            &rows,
+            &[
+                ("number", "#"),
+                ("file", "File"),
+                ("author", "Author"),
+                ("type", "Type"),
+                ("target", "Target"),
+                ("endorser", "Endorser"),
+            ],
+            "",
+        );
+    }
+
+    /// Verifies and signs the requests one by one and then submits them to the pool concurrently.
+    /// Results are returned in the order of the entries.
+    fn endorse_entries(
+        ctx: &CommandContext,
+        wallet: &Wallet,
+        pool: &Pool,
+        submitter_did: &DidValue,
+        entries: &[&InboxEntry],
+    ) -> Vec<Result<(), ()>> {
+        let prepared = entries
+            .iter()
+            .map(|entry| {
+                println!("Endorsing \"{}\"...", entry.path.display());
+                prepare_entry(wallet, pool, submitter_did, entry)
+            })
+            .collect::<Vec<Result<PreparedRequest, ()>>>();
+
+        let requests = prepared
+            .iter()
+            .filter_map(|request| request.as_ref().ok())
+            .collect::<Vec<&PreparedRequest>>();
+        for request in requests.iter() {
+            trace_request(ctx, request);
+        }
+        let mut replies = match Ledger::submit_requests(pool, &requests, ctx.get_request_retries())
+        {
+            Ok(replies) => replies.into_iter(),
+            Err(err) => {
+                println_err!("{}", err.report(Some(&pool.name)));
+                return entries.iter().map(|_| Err(())).collect();
+            }
+        };
+
+        prepared
+            .into_iter()
+            .zip(entries)
+            .map(|(request, entry)| {
+                request?;
+                let response_json = replies
+                    .next()
+                    .ok_or(())?
+                    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+                trace_response(ctx, &response_json);
+
+                let response = serde_json::from_str::<Response<JsonValue>>(&response_json)
+                    .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+                let result = handle_transaction_response(response)?;
+                println_succ!(
+                    "Transaction \"{}\" has been sent to Ledger (seqNo {}).",
+                    entry.path.display(),
+                    result["txnMetadata"]["seqNo"]
+                );
+                Ok(())
+            })
+            .collect()
+    }
+
+    fn prepare_entry(
+        wallet: &Wallet,
+        pool: &Pool,
+        submitter_did: &DidValue,
+        entry: &InboxEntry,
+    ) -> Result<PreparedRequest, ()> {
+        check_envelope_network(pool, &entry.envelope)?;
+
+        let mut request = PreparedRequest::from_request_json(entry.envelope.request.to_string())
+            .map_err(|_| println_err!("Invalid formatted transaction provided."))?;
+
+        verify_endorser(&request, submitter_did)?;
+        verify_author_signature(pool, submitter_did, &request)?;
+
+        Ledger::multi_sign_request(wallet, submitter_did, &mut request)
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+        Ok(request)
+    }
+
+    fn move_to_done(path: &Path, done_dir: &Path) -> Result<(), ()> {
+        let file_name = path.file_name().unwrap_or_default();
+        fs::create_dir_all(done_dir)
+            .and_then(|_| fs::rename(path, done_dir.join(file_name)))
+            .map_err(|err| {
+                println_err!(
+                    "Request has been endorsed, but the file \"{}\" cannot be moved into \"{}\": {}",
+                    path.display(),
+                    done_dir.display(),
+                    err
+                )
+            })
+    }
+}
+
+/// Parses 1-based request numbers separated by comma into indexes.
+fn parse_selection(selection: &str, count: usize) -> Result<Vec<usize>, String> {
+    let mut indexes = Vec::new();
+    for number in selection
+        .split(',')
+        .map(str::trim)
+        .filter(|number| !number.is_empty())
+    {
+        let index = match number.parse::<usize>() {
+            Ok(number) if number >= 1 && number <= count => number - 1,
+            _ => return Err(format!("Invalid request number \"{}\".", number)),
+        };
+        if !indexes.contains(&index) {
+            indexes.push(index);
+        }
+    }
+    Ok(indexes)
+}
+
+/// Checks that the transaction names the active DID as its endorser.
+fn verify_endorser(request: &PreparedRequest, submitter_did: &DidValue) -> Result<(), ()> {
+    let submitter_did = submitter_did.to_short().to_string();
+    match request.req_json["endorser"].as_str() {
+        Some(endorser) if endorser == submitter_did => Ok(()),
+        Some(endorser) => {
+            println_err!(
+                "Transaction must be endorsed by DID \"{}\" set as its endorser, but active DID is \"{}\".",
+                endorser,
+                submitter_did
+            );
+            println!(
+                "Use `did use {}` command to switch to the endorser DID.",
+                endorser
+            );
+            Err(())
+        }
+        None => {
+            println_err!("Transaction does not have an endorser set.");
+            println!(
+                "Build the transaction with `endorser={}` parameter to endorse it.",
+                submitter_did
+            );
+            Err(())
+        }
+    }
+}
+
+/// Verifies the author signature of the transaction with the author verkey set on the Ledger.
+fn verify_author_signature(
+    pool: &Pool,
+    submitter_did: &DidValue,
+    request: &PreparedRequest,
+) -> Result<(), ()> {
+    let author = match request.req_json["identifier"].as_str() {
+        Some(author) => author.to_string(),
+        None => {
+            println_err!("Transaction does not have an author (`identifier` field) set.");
+            return Err(());
+        }
+    };
+
+    let signature = request.req_json["signatures"][&author]
+        .as_str()
+        .or_else(|| request.req_json["signature"].as_str());
+    let signature = match signature {
+        Some(signature) => base58::decode(signature)
+            .map_err(|_| println_err!("Invalid author signature \"{}\" provided.", signature))?,
+        None => {
+            println_err!("Transaction is not signed by its author \"{}\".", author);
+            println!("The author must sign the transaction before it is endorsed.");
+            return Err(());
+        }
+    };
+
+    let author_did = DidValue(author.to_string());
+    let nym = match get_nym_data(pool, Some(submitter_did), &author_did)? {
+        Some(nym) => nym,
+        None => {
+            println_err!(
+                "Author DID \"{}\" is not written on the Ledger. The author NYM must be published before endorsing its transactions.",
+                author
+            );
+            return Err(());
+        }
+    };
+    let verkey = match nym["verkey"].as_str() {
+        Some(verkey) => Did::expand_verkey(&author, verkey)
+            .map_err(|err| println_err!("{}", err.report(None)))?,
+        None => {
+            println_err!("Author DID \"{}\" has no verkey set on the Ledger.", author);
+            return Err(());
+        }
+    };
+
+    let message = request
+        .get_signature_input()
+        .map_err(|err| println_err!("Invalid formatted transaction provided: {}", err))?;
+    let is_valid = Key::verify_message(None, None, Some(&verkey), message.as_bytes(), &signature)
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+    if !is_valid {
+        println_err!(
+            "Author signature does not match the verkey of DID \"{}\" set on the Ledger.",
+            author
+        );
+        println!(
+            "The transaction might have been modified after signing or signed by a rotated key."
+        );
+        return Err(());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::did::tests::{DID_MY1, DID_TRUSTEE};
+
+    fn request(endorser: Option<&str>) -> PreparedRequest {
+        let mut txn = json!({
+            "reqId": 1,
+            "identifier": DID_MY1,
+            "operation": {"type": "101", "data": {"name": "gvt", "version": "1.0", "attr_names": ["name"]}},
+            "protocolVersion": 2,
+        });
+        if let Some(endorser) = endorser {
+            txn["endorser"] = json!(endorser);
+        }
+        PreparedRequest::from_request_json(txn.to_string()).unwrap()
+    }
+
+    mod endorse {
+        use super::*;
+
+        #[test]
+        pub fn verify_endorser_works() {
+            verify_endorser(
+                &request(Some(DID_TRUSTEE)),
+                &DidValue(DID_TRUSTEE.to_string()),
+            )
+            .unwrap();
+        }
+
+        #[test]
+        pub fn verify_endorser_works_for_other_endorser() {
+            verify_endorser(&request(Some(DID_MY1)), &DidValue(DID_TRUSTEE.to_string()))
+                .unwrap_err();
+        }
+
+        #[test]
+        pub fn verify_endorser_works_for_no_endorser() {
+            verify_endorser(&request(None), &DidValue(DID_TRUSTEE.to_string())).unwrap_err();
+        }
+    }
+
+    mod endorse_inbox {
+        use super::*;
+        use crate::{
+            commands::{
+                did::tests::use_did, setup_with_wallet_and_pool, tear_down_with_wallet_and_pool,
+            },
+            ledger::{
+                export_request_command,
+                schema::tests::ensure_schema_added,
+                schema_command,
+                tests::{create_new_did, send_nym, use_new_endorser},
+            },
+            utils::environment::EnvironmentUtils,
+        };
+
+        fn inbox_dir() -> PathBuf {
+            let dir = EnvironmentUtils::tmp_file_path("endorse_inbox");
+            fs::remove_dir_all(&dir).ok();
+            fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+
+        fn endorse_inbox(ctx: &CommandContext, dir: &Path, all: bool) -> Result<(), ()> {
+            let cmd = endorse_inbox_command::new();
+            let mut params = CommandParams::new();
+            params.insert("dir", dir.to_str().unwrap().to_string());
+            params.insert("all", all.to_string());
+            cmd.execute(ctx, &params)
+        }
+
+        #[test]
+        pub fn endorse_inbox_works_for_all() {
+            let ctx = setup_with_wallet_and_pool();
+            let (endorser_did, _) = use_new_endorser(&ctx);
+
+            let (did, verkey) = create_new_did(&ctx);
+            send_nym(&ctx, &did, &verkey, None);
+            use_did(&ctx, &did);
+
+            let dir = inbox_dir();
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("attr_names", "name,age".to_string());
+                params.insert("endorser", endorser_did.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = export_request_command::new();
+                let mut params = CommandParams::new();
+                params.insert(
+                    "file",
+                    dir.join("schema.json").to_str().unwrap().to_string(),
+                );
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            fs::write(dir.join("notes.txt"), "not a request").unwrap();
+
+            use_did(&ctx, &endorser_did);
+            endorse_inbox(&ctx, &dir, true).unwrap();
+
+            assert!(ensure_schema_added(&ctx, &did).is_ok());
+            assert!(!dir.join("schema.json").exists());
+            assert!(dir.join("done").join("schema.json").exists());
+            assert!(dir.join("notes.txt").exists());
+
+            fs::remove_dir_all(&dir).ok();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn endorse_inbox_works_for_empty_dir() {
+            let ctx = setup_with_wallet_and_pool();
+            use_new_endorser(&ctx);
+            let dir = inbox_dir();
+            endorse_inbox(&ctx, &dir, true).unwrap();
+            fs::remove_dir_all(&dir).ok();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn endorse_inbox_works_for_not_existing_dir() {
+            let ctx = setup_with_wallet_and_pool();
+            use_new_endorser(&ctx);
+            endorse_inbox(&ctx, Path::new("/not/existing/inbox"), true).unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn parse_selection_works() {
+            assert_eq!(vec![0, 2], parse_selection("1, 3,1", 3).unwrap());
+            assert!(parse_selection("", 3).unwrap().is_empty());
+            parse_selection("4", 3).unwrap_err();
+            parse_selection("a", 3).unwrap_err();
+        }
+    }
+}
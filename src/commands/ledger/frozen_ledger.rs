@@ -3,12 +3,16 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
-    tools::ledger::{Ledger, Response},
+    tools::{
+        ledger::{Ledger, Response, ResponseType},
+        pool::Pool,
+    },
     utils::table::print_list_table,
 };
 
+use indy_utils::did::DidValue;
 use serde_json::Value as JsonValue;
 
 use super::common::handle_transaction_response;
@@ -19,21 +23,40 @@ pub mod ledgers_freeze_command {
     command!(
         CommandMetadata::build("ledgers-freeze", r#"Freeze ledgers"#)
             .add_required_param("ledgers_ids", "List of ledgers IDs for freezing.")
+            .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+            .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+            .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+            .add_optional_param("dry_run", "Print which ledgers are unknown, already frozen or would be frozen, then build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
             .add_example("ledger ledgers-freeze ledgers_ids=1,2,3")
+            .add_example("ledger ledgers-freeze ledgers_ids=1,2,3 dry_run=true")
+            .add_precondition(Precondition::OpenedWallet)
+            .add_precondition(Precondition::ActiveDid)
+            .add_precondition(Precondition::ConnectedPool)
             .finalize()
     );
 
+    /// Ledgers of the pool protocol itself which the Ledger refuses to freeze.
+    const BASE_LEDGERS_IDS: [u64; 4] = [0, 1, 2, 3];
+
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
-        let ledgers_ids = ParamParser::get_number_tuple_array_param("ledgers_ids", params);
+        let ledgers_ids = ParamParser::get_number_tuple_array_param("ledgers_ids", params)?;
+        let dry_run = ParamParser::get_opt_bool_param("dry_run", params)?.unwrap_or(false);
         let submitter_did = ctx.ensure_active_did()?;
+
+        // the transaction itself is reported by `send_write_request` which does not send it on dry run
+        if dry_run {
+            let pool = ctx.ensure_connected_pool()?;
+            print_freeze_impact(&pool, &submitter_did, &ledgers_ids)?;
+        }
+
         let pool = ctx.get_connected_pool();
 
         let wallet = ctx.ensure_opened_wallet()?;
 
         let mut request =
-            Ledger::build_ledgers_freeze_request(pool.as_deref(), &submitter_did, ledgers_ids?)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+            Ledger::build_ledgers_freeze_request(pool.as_deref(), &submitter_did, ledgers_ids)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) =
             send_write_request!(&ctx, params, &mut request, &wallet, &submitter_did);
@@ -45,6 +68,57 @@ pub mod ledgers_freeze_command {
         trace!("execute <<");
         Ok(())
     }
+
+    fn print_freeze_impact(
+        pool: &Pool,
+        submitter_did: &DidValue,
+        ledgers_ids: &[u64],
+    ) -> Result<(), ()> {
+        let frozen_ledgers = Ledger::build_get_frozen_ledgers_request(Some(pool), submitter_did)
+            .and_then(|request| Ledger::submit_request(pool, &request))
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        let frozen_ledgers = serde_json::from_str::<Response<JsonValue>>(&frozen_ledgers)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+        let frozen_ledgers = handle_transaction_response(frozen_ledgers)?;
+
+        let mut rows = Vec::new();
+        for ledger_id in ledgers_ids {
+            let status = if BASE_LEDGERS_IDS.contains(ledger_id) {
+                "Base ledger, can't be frozen"
+            } else if frozen_ledgers.get(ledger_id.to_string()).is_some() {
+                "Already frozen"
+            } else if !is_ledger_present(pool, *ledger_id)? {
+                "Unknown"
+            } else {
+                "Will be frozen"
+            };
+            rows.push(json!({
+                "ledger_id": ledger_id,
+                "status": status,
+            }));
+        }
+
+        println_succ!("Ledgers freeze impact:");
+        print_list_table(
+            &rows,
+            &[("ledger_id", "Ledger id"), ("status", "Status")],
+            "",
+        );
+        Ok(())
+    }
+
+    /// Nodes reject reading transactions of the ledger which does not exist in the pool.
+    fn is_ledger_present(pool: &Pool, ledger_id: u64) -> Result<bool, ()> {
+        let ledger_type = i32::try_from(ledger_id).map_err(|_| {
+            println_err!("Invalid ledger id: {}", ledger_id);
+        })?;
+        let response = Ledger::build_get_txn_request(Some(pool), None, ledger_type, 1)
+            .and_then(|request| Ledger::submit_request(pool, &request))
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        let response = serde_json::from_str::<Response<JsonValue>>(&response)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+        Ok(matches!(response.op, ResponseType::REPLY))
+    }
 }
 
 pub mod get_frozen_ledgers_command {
@@ -53,6 +127,8 @@ pub mod get_frozen_ledgers_command {
     command!(
         CommandMetadata::build("get-frozen-ledgers", r#"Get a list of frozen ledgers"#)
             .add_example("ledger get-frozen-ledgers")
+            .add_precondition(Precondition::ActiveDid)
+            .add_precondition(Precondition::ConnectedPool)
             .finalize()
     );
 
@@ -63,7 +139,7 @@ pub mod get_frozen_ledgers_command {
         let pool = ctx.get_connected_pool();
 
         let request = Ledger::build_get_frozen_ledgers_request(pool.as_deref(), &submitter_did)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) = send_read_request!(&ctx, params, &request);
         let handle_response = handle_transaction_response(response)?;
@@ -115,7 +191,10 @@ pub mod get_frozen_ledgers_command {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::commands::{setup, tear_down};
+    use crate::{
+        commands::{setup, setup_with_wallet_and_pool, tear_down, tear_down_with_wallet_and_pool},
+        ledger::tests::use_trustee,
+    };
 
     mod frozen_ledgers {
         use super::*;
@@ -134,6 +213,35 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn ledgers_freeze_works_for_dry_run_without_pool() {
+            let ctx = setup();
+
+            {
+                let cmd = ledgers_freeze_command::new();
+                let mut params = CommandParams::new();
+                params.insert("ledgers_ids", "0,1,10,237".to_string());
+                params.insert("dry_run", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn ledgers_freeze_works_for_dry_run() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = ledgers_freeze_command::new();
+                let mut params = CommandParams::new();
+                params.insert("ledgers_ids", "0,1,10,237".to_string());
+                params.insert("dry_run", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn get_frozen_ledgers() {
             let ctx = setup();
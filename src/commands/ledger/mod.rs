@@ -11,32 +11,42 @@ pub mod auth_rule;
 pub mod constants;
 pub mod cred_def;
 pub mod custom;
+pub mod did_document;
 pub mod endorser;
 pub mod frozen_ledger;
+pub mod multisig;
 pub mod node;
 pub mod nym;
+pub mod plugin;
 pub mod pool_config;
 pub mod pool_restart;
 pub mod pool_upgrade;
+pub mod rich_schema;
 pub mod schema;
 pub mod sign_multi;
 pub mod transaction;
 pub mod transaction_author_agreement;
 pub mod validator_info;
+pub mod verify_txn;
 
 pub use self::{
-    attrib::*, auth_rule::*, common::*, cred_def::*, custom::*, endorser::*, frozen_ledger::*,
-    node::*, nym::*, pool_config::*, pool_restart::*, pool_upgrade::*, schema::*, sign_multi::*,
-    transaction::*, transaction_author_agreement::*, validator_info::*,
+    attrib::*, auth_rule::*, common::*, cred_def::*, custom::*, did_document::*, endorser::*,
+    frozen_ledger::*, multisig::*, node::*, nym::*, plugin::*, pool_config::*, pool_restart::*,
+    pool_upgrade::*, rich_schema::*, schema::*, sign_multi::*, transaction::*,
+    transaction_author_agreement::*, validator_info::*, verify_txn::*,
 };
 
 pub mod group {
     use super::*;
 
-    command_group!(CommandGroupMetadata::new(
-        "ledger",
-        "Ledger management commands"
-    ));
+    command_group!(
+        CommandGroupMetadata::new("ledger", "Ledger management commands")
+            .add_example("ledger get-nym did=V4SGRU86Z58d6TV7PBUe6f")
+            .add_example("did new seed=00000000000000000000000000000My1")
+            .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX verkey=GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa role=ENDORSER")
+            .add_example("ledger schema name=gvt version=1.0 attr_names=name,age")
+            .add_example("ledger get-schema did=V4SGRU86Z58d6TV7PBUe6f name=gvt version=1.0")
+    );
 }
 
 #[cfg(test)]
@@ -57,7 +67,7 @@ pub mod tests {
 
     pub fn create_new_did(ctx: &CommandContext) -> (String, String) {
         let wallet = ctx.get_opened_wallet().unwrap();
-        Did::create(&wallet, None, None, None, None).unwrap()
+        Did::create(&wallet, None, None, None, None, None, None).unwrap()
     }
 
     pub fn use_trustee(ctx: &CommandContext) {
@@ -0,0 +1,313 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::ledger::{multisig::MultisigFile, parse_transaction_response, Ledger, Response},
+    utils::table::{print_list_table, print_table},
+};
+
+use indy_vdr::pool::PreparedRequest;
+use serde_json::Value as JsonValue;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::common::handle_transaction_response;
+
+pub mod multisig_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "multisig",
+        "Coordinate multi-signature of a transaction by several DIDs using a shared file."
+    )
+    .add_main_param(
+        "action",
+        "One of: init (create the file), sign (add signature by current DID), submit (send the transaction once the threshold is met), status"
+    )
+    .add_optional_param(
+        "txn",
+        "Transaction to sign (init). Skip to use a transaction stored into CLI context."
+    )
+    .add_optional_param("signers", "DIDs expected to sign the transaction split by comma (init)")
+    .add_optional_param(
+        "threshold",
+        "Number of signatures required to submit the transaction (init). All signers by default"
+    )
+    .add_optional_param("out", "Path to the file to create (init)")
+    .add_optional_param("file", "Path to the file created by `ledger multisig init` (sign, submit, status)")
+    .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+    .add_example(r#"ledger multisig init txn={"reqId":123456789,"type":"100"} signers=V4SGRU86Z58d6TV7PBUe6f,VsKV7grR1BUE29mG2Fm2kX threshold=2 out=/tmp/ceremony.json"#)
+    .add_example("ledger multisig sign file=/tmp/ceremony.json")
+    .add_example("ledger multisig status file=/tmp/ceremony.json")
+    .add_example("ledger multisig submit file=/tmp/ceremony.json")
+    .add_precondition(Precondition::OpenedWallet)
+    .add_precondition(Precondition::ActiveDid)
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let action = ParamParser::get_str_param("action", params)?;
+
+        let res = match action {
+            "init" => init(ctx, params),
+            "sign" => sign(ctx, params),
+            "submit" => submit(ctx, params),
+            "status" => status(params),
+            _ => {
+                println_err!(
+                    "Unknown action \"{}\". One of: init, sign, submit, status",
+                    action
+                );
+                Err(())
+            }
+        };
+
+        trace!("execute << {:?}", res);
+        res
+    }
+
+    fn init(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        let param_txn = ParamParser::get_opt_str_param("txn", params)?;
+        let signers = ParamParser::get_str_array_param("signers", params)?;
+        let threshold = ParamParser::get_opt_number_param::<usize>("threshold", params)?;
+        let out = ParamParser::get_str_param("out", params)?;
+
+        let txn = get_transaction_to_use!(ctx, param_txn);
+
+        let file = MultisigFile::new(txn.req_json, &signers, threshold)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+        file.write(out)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!(
+            "Multi-signature file \"{}\" has been created. {} of {} signatures are required.",
+            out,
+            file.threshold,
+            file.signers.len()
+        );
+        Ok(())
+    }
+
+    fn sign(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let path = ParamParser::get_str_param("file", params)?;
+
+        let mut file = read(path)?;
+
+        if !file.is_signer(&submitter_did) {
+            println_err!(
+                "DID \"{}\" is not listed as a signer of the transaction",
+                submitter_did
+            );
+            return Err(());
+        }
+
+        if file.is_signed_by(&submitter_did) {
+            println_warn!(
+                "Transaction has already been signed by DID \"{}\"",
+                submitter_did
+            );
+            return Ok(());
+        }
+
+        let mut request = request(&file)?;
+        Ledger::multi_sign_request(&wallet, &submitter_did, &mut request)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let signed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        file.add_signature(&submitter_did, &request, signed_at);
+        file.write(path)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("Transaction has been signed by DID \"{}\"", submitter_did);
+        print_status(&file);
+        Ok(())
+    }
+
+    fn submit(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        let path = ParamParser::get_str_param("file", params)?;
+
+        let file = read(path)?;
+
+        if !file.is_complete() {
+            print_status(&file);
+            println_err!(
+                "Transaction cannot be submitted: {} of {} required signatures are present",
+                file.signed_dids().len(),
+                file.threshold
+            );
+            return Err(());
+        }
+
+        let request = request(&file)?;
+        let (_, response): (String, Response<JsonValue>) =
+            send_request!(ctx, params, &request, true);
+
+        let result = handle_transaction_response(response)?;
+
+        println_succ!("Transaction has been sent to Ledger.");
+        let (metadata_headers, metadata, _) = parse_transaction_response(result)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+        println_succ!("Metadata:");
+        print_table(&metadata, &metadata_headers);
+        Ok(())
+    }
+
+    fn status(params: &CommandParams) -> Result<(), ()> {
+        let path = ParamParser::get_str_param("file", params)?;
+        let file = read(path)?;
+        print_status(&file);
+        Ok(())
+    }
+
+    fn read(path: &str) -> Result<MultisigFile, ()> {
+        MultisigFile::read(path).map_err(|err| println_err!("{}", err.report(None)))
+    }
+
+    fn request(file: &MultisigFile) -> Result<PreparedRequest, ()> {
+        file.request()
+            .map_err(|_| println_err!("Invalid formatted transaction provided."))
+    }
+
+    fn print_status(file: &MultisigFile) {
+        let signed = file.signed_dids();
+        let rows = file
+            .signers
+            .iter()
+            .map(|signer| {
+                json!({
+                    "did": signer,
+                    "signed": if signed.contains(&signer.as_str()) { "yes" } else { "no" },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        print_list_table(&rows, &[("did", "Signer"), ("signed", "Signed")], "");
+        println!(
+            "{} of {} required signatures are present",
+            signed.len(),
+            file.threshold
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{
+            did::tests::{new_did, use_did, DID_MY1, DID_MY3, DID_TRUSTEE, SEED_MY1, SEED_MY3},
+            setup_with_wallet_and_pool, tear_down_with_wallet_and_pool,
+        },
+        ledger::tests::{use_trustee, TRANSACTION},
+        utils::environment::EnvironmentUtils,
+    };
+
+    fn file_path(name: &str) -> String {
+        EnvironmentUtils::tmp_file_path(name)
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn execute(ctx: &CommandContext, action: &str, file: &str) -> Result<(), ()> {
+        let cmd = multisig_command::new();
+        let mut params = CommandParams::new();
+        params.insert("action", action.to_string());
+        params.insert("file", file.to_string());
+        cmd.execute(ctx, &params)
+    }
+
+    fn init(ctx: &CommandContext, out: &str, threshold: &str) {
+        let cmd = multisig_command::new();
+        let mut params = CommandParams::new();
+        params.insert("action", "init".to_string());
+        params.insert("txn", TRANSACTION.to_string());
+        params.insert("signers", format!("{},{}", DID_TRUSTEE, DID_MY1));
+        params.insert("threshold", threshold.to_string());
+        params.insert("out", out.to_string());
+        cmd.execute(ctx, &params).unwrap();
+    }
+
+    mod multisig {
+        use super::*;
+
+        #[test]
+        pub fn multisig_sign_works() {
+            let ctx = setup_with_wallet_and_pool();
+            let path = file_path("multisig_sign_works.json");
+            init(&ctx, &path, "2");
+
+            use_trustee(&ctx);
+            execute(&ctx, "sign", &path).unwrap();
+            // repeated signing is skipped
+            execute(&ctx, "sign", &path).unwrap();
+
+            new_did(&ctx, SEED_MY1);
+            use_did(&ctx, DID_MY1);
+            execute(&ctx, "sign", &path).unwrap();
+
+            let file = MultisigFile::read(&path).unwrap();
+            assert_eq!(vec![DID_TRUSTEE, DID_MY1], file.signed_dids());
+            assert_eq!(2, file.signed.len());
+            assert!(file.is_complete());
+            execute(&ctx, "status", &path).unwrap();
+
+            std::fs::remove_file(&path).unwrap();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn multisig_sign_works_for_unknown_signer() {
+            let ctx = setup_with_wallet_and_pool();
+            let path = file_path("multisig_sign_works_for_unknown_signer.json");
+            init(&ctx, &path, "1");
+
+            new_did(&ctx, SEED_MY3);
+            use_did(&ctx, DID_MY3);
+            execute(&ctx, "sign", &path).unwrap_err();
+
+            std::fs::remove_file(&path).unwrap();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn multisig_submit_works_for_threshold_not_met() {
+            let ctx = setup_with_wallet_and_pool();
+            let path = file_path("multisig_submit_works_for_threshold_not_met.json");
+            init(&ctx, &path, "2");
+
+            use_trustee(&ctx);
+            execute(&ctx, "sign", &path).unwrap();
+            execute(&ctx, "submit", &path).unwrap_err();
+
+            std::fs::remove_file(&path).unwrap();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn multisig_init_works_for_invalid_threshold() {
+            let ctx = setup_with_wallet_and_pool();
+            let cmd = multisig_command::new();
+            let mut params = CommandParams::new();
+            params.insert("action", "init".to_string());
+            params.insert("txn", TRANSACTION.to_string());
+            params.insert("signers", DID_TRUSTEE.to_string());
+            params.insert("threshold", "2".to_string());
+            params.insert(
+                "out",
+                file_path("multisig_init_works_for_invalid_threshold.json"),
+            );
+            cmd.execute(&ctx, &params).unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+}
@@ -3,11 +3,18 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
-    tools::ledger::{Ledger, Response},
+    tools::{
+        ledger::{Ledger, Response},
+        pool::NodeInfo,
+    },
 };
 
+use indy_utils::did::DidValue;
 use indy_vdr::ledger::requests::node::{NodeOperationData, Services};
 use serde_json::Value as JsonValue;
 
@@ -18,7 +25,7 @@ pub mod node_command {
 
     command!(CommandMetadata::build("node", "Send Node transaction to the Ledger.")
                 .add_required_param("target", "Node identifier")
-                .add_required_param("alias", "Node alias (can't be changed in case of update)")
+                .add_required_param_with_dynamic_completion("alias", "Node alias (can't be changed in case of update)", DynamicCompletionType::Node)
                 .add_optional_param("node_ip", "Node Ip. Note that it is mandatory for adding node case")
                 .add_optional_param("node_port", "Node port. Note that it is mandatory for adding node case")
                 .add_optional_param("client_ip", "Client Ip. Note that it is mandatory for adding node case")
@@ -26,12 +33,22 @@ pub mod node_command {
                 .add_optional_param("blskey",  "Node BLS key")
                 .add_optional_param("blskey_pop",  "Node BLS key proof of possession. Note that it is mandatory if blskey specified")
                 .add_optional_param("services", "Node type. One of: VALIDATOR, OBSERVER or empty in case of blacklisting node")
+                .add_optional_param("merge", "Take the values of not specified params from the current node data in the pool ledger, so only changed fields have to be passed (False by default)")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger node target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y node_ip=127.0.0.1 node_port=9710 client_ip=127.0.0.1 client_port=9711 alias=Node5 services=VALIDATOR blskey=2zN3bHM1m4rLz54MJHYSwvqzPchYp8jkHswveCLAEJVcX6Mm1wHQD1SkPYMzUDTZvWvhuE6VNAkK3KxVeEmsanSmvjVkReDeBEMxeDaayjcZjFGPydyey1qxBHmTvAnBKoPydvuTAqx5f7YNNRAdeLmUi99gERUU7TD8KfAa6MpQ9bw blskey_pop=RPLagxaR5xdimFzwmzYnz4ZhWtYQEj8iR5ZU53T2gitPCyCHQneUn2Huc4oeLd2B2HzkGnjAff4hWTJT6C7qHYB1Mv2wU5iHHGFWkhnTX9WsEAbunJCV2qcaXScKj4tTfvdDKfLiVuU2av6hbsMztirRze7LvYBkRHV3tGwyCptsrP")
                 .add_example("ledger node target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y node_ip=127.0.0.1 node_port=9710 client_ip=127.0.0.1 client_port=9711 alias=Node5 services=VALIDATOR")
                 .add_example("ledger node target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y alias=Node5 services=VALIDATOR")
                 .add_example("ledger node target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y alias=Node5 services=")
+                .add_example("ledger node target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y alias=Node5 client_ip=127.0.0.3 merge=true")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -51,25 +68,11 @@ pub mod node_command {
         let blskey = ParamParser::get_opt_str_param("blskey", params)?;
         let blskey_pop = ParamParser::get_opt_str_param("blskey_pop", params)?;
         let services = ParamParser::get_opt_str_array_param("services", params)?;
+        let merge = ParamParser::get_opt_bool_param("merge", params)?.unwrap_or(false);
 
-        let services = match services {
-            Some(services) => Some(
-                services
-                    .into_iter()
-                    .map(|service| match service {
-                        "VALIDATOR" => Ok(Services::VALIDATOR),
-                        "OBSERVER" => Ok(Services::OBSERVER),
-                        service => {
-                            println_err!("Unsupported service \"{}\"!", service);
-                            Err(())
-                        }
-                    })
-                    .collect::<Result<Vec<Services>, ()>>()?,
-            ),
-            None => None,
-        };
+        let services = services.map(parse_services).transpose()?;
 
-        let node_data = NodeOperationData {
+        let mut node_data = NodeOperationData {
             node_ip: node_ip.map(String::from),
             node_port,
             client_ip: client_ip.map(String::from),
@@ -80,9 +83,41 @@ pub mod node_command {
             blskey_pop: blskey_pop.map(String::from),
         };
 
+        if merge {
+            let pool = ctx.ensure_connected_pool()?;
+            let node = pool
+                .find_node(alias)
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?
+                .ok_or_else(|| {
+                    println_err!(
+                        "Node \"{}\" not found in the pool ledger. Nothing to merge with",
+                        alias
+                    )
+                })?;
+            if node.dest != target_did.0 {
+                println_err!(
+                    "Node \"{}\" has identifier \"{}\" in the pool ledger, but \"{}\" is provided",
+                    alias,
+                    node.dest,
+                    target_did.0
+                );
+                return Err(());
+            }
+            merge_node_data(&mut node_data, &node)?;
+        } else if let Some(pool) = pool.as_deref() {
+            if let Ok(Some(node)) = pool.find_node(alias) {
+                if node.data["blskey"].is_string() && node_data.blskey.is_none() {
+                    println_warn!(
+                        "Node \"{}\" has BLS key in the pool ledger, but the transaction does not include it. Use merge=true to keep the current BLS key",
+                        alias
+                    );
+                }
+            }
+        }
+
         let mut request =
             Ledger::build_node_request(pool.as_deref(), &submitter_did, &target_did, node_data)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
@@ -108,6 +143,211 @@ pub mod node_command {
         trace!("execute <<");
         Ok(())
     }
+
+    fn parse_services(services: Vec<&str>) -> Result<Vec<Services>, ()> {
+        services
+            .into_iter()
+            .map(|service| match service {
+                "VALIDATOR" => Ok(Services::VALIDATOR),
+                "OBSERVER" => Ok(Services::OBSERVER),
+                service => {
+                    println_err!("Unsupported service \"{}\"!", service);
+                    Err(())
+                }
+            })
+            .collect()
+    }
+
+    /// Fills not specified fields with the current node data from the pool ledger.
+    /// A new BLS key must come with its own proof of possession, so the current one is never merged to it.
+    fn merge_node_data(node_data: &mut NodeOperationData, node: &NodeInfo) -> Result<(), ()> {
+        let current = &node.data;
+        let current_str = |name: &str| current[name].as_str().map(String::from);
+        let current_port = |name: &str| current[name].as_i64().map(|port| port as i32);
+
+        node_data.node_ip = node_data.node_ip.take().or_else(|| current_str("node_ip"));
+        node_data.node_port = node_data.node_port.or_else(|| current_port("node_port"));
+        node_data.client_ip = node_data
+            .client_ip
+            .take()
+            .or_else(|| current_str("client_ip"));
+        node_data.client_port = node_data
+            .client_port
+            .or_else(|| current_port("client_port"));
+
+        if node_data.services.is_none() {
+            node_data.services = current["services"]
+                .as_array()
+                .map(|services| {
+                    parse_services(services.iter().filter_map(JsonValue::as_str).collect())
+                })
+                .transpose()?;
+        }
+
+        match (&node_data.blskey, &node_data.blskey_pop) {
+            (None, None) => {
+                node_data.blskey = current_str("blskey");
+                node_data.blskey_pop = current_str("blskey_pop");
+            }
+            (Some(_), None) => {
+                println_err!("\"blskey_pop\" parameter is mandatory if \"blskey\" is specified");
+                return Err(());
+            }
+            (None, Some(_)) => {
+                println_err!("\"blskey\" parameter is mandatory if \"blskey_pop\" is specified");
+                return Err(());
+            }
+            (Some(_), Some(_)) => {}
+        }
+
+        if node_data.blskey.is_none() {
+            println_warn!(
+                "Node \"{}\" has no BLS key in the pool ledger. It will not take part in BLS multi-signatures",
+                node_data.alias
+            );
+        }
+
+        Ok(())
+    }
+}
+
+pub mod node_demote_command {
+    use super::*;
+
+    command!(CommandMetadata::build("node-demote", "Send Node transaction removing the node from validators. Current node data is taken from the pool ledger.")
+                .add_required_param_with_dynamic_completion("alias", "Node alias", DynamicCompletionType::Node)
+                .add_optional_param("target", "Node identifier. Checked against the pool ledger if specified")
+                .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger node-demote alias=Node5")
+                .add_example("ledger node-demote alias=Node5 target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y")
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+        let res = node_services::send_services_update(ctx, params, false);
+        trace!("execute << {:?}", res);
+        res
+    }
+}
+
+pub mod node_promote_command {
+    use super::*;
+
+    command!(CommandMetadata::build("node-promote", "Send Node transaction making the node a validator again. Current node data is taken from the pool ledger.")
+                .add_required_param_with_dynamic_completion("alias", "Node alias", DynamicCompletionType::Node)
+                .add_optional_param("target", "Node identifier. Checked against the pool ledger if specified")
+                .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger node-promote alias=Node5")
+                .add_example("ledger node-promote alias=Node5 target=A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+        let res = node_services::send_services_update(ctx, params, true);
+        trace!("execute << {:?}", res);
+        res
+    }
+}
+
+mod node_services {
+    use super::*;
+
+    /// Sends NODE transaction containing only the `services` change for the node found in the pool ledger,
+    /// so the node addresses and BLS keys do not have to be supplied again.
+    pub fn send_services_update(
+        ctx: &CommandContext,
+        params: &CommandParams,
+        validator: bool,
+    ) -> Result<(), ()> {
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.ensure_connected_pool()?;
+
+        let alias = ParamParser::get_str_param("alias", params)?;
+        let target = ParamParser::get_opt_did_param("target", params)?;
+
+        let node = pool
+            .find_node(alias)
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?
+            .ok_or_else(|| println_err!("Node \"{}\" not found in the pool ledger", alias))?;
+
+        if let Some(target) = target {
+            if target.0 != node.dest {
+                println_err!(
+                    "Node \"{}\" has identifier \"{}\" in the pool ledger, but \"{}\" is provided",
+                    alias,
+                    node.dest,
+                    target.0
+                );
+                return Err(());
+            }
+        }
+
+        if node.is_validator() == validator {
+            println_err!(
+                "Node \"{}\" is already {}",
+                alias,
+                if validator { "a validator" } else { "demoted" }
+            );
+            return Err(());
+        }
+
+        let services = if validator {
+            vec![Services::VALIDATOR]
+        } else {
+            vec![]
+        };
+
+        let node_data = NodeOperationData {
+            node_ip: None,
+            node_port: None,
+            client_ip: None,
+            client_port: None,
+            alias: alias.to_string(),
+            services: Some(services),
+            blskey: None,
+            blskey_pop: None,
+        };
+
+        let mut request = Ledger::build_node_request(
+            Some(pool.as_ref()),
+            &submitter_did,
+            &DidValue(node.dest),
+            node_data,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response): (String, Response<JsonValue>) =
+            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+
+        handle_transaction_response(response).map(|result| {
+            print_transaction_response(
+                result,
+                "NodeConfig request has been sent to Ledger.",
+                Some("data"),
+                &[("alias", "Alias"), ("services", "Services")],
+                true,
+            )
+        })?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -151,4 +391,164 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
     }
+
+    mod node_merge {
+        use super::*;
+
+        const NODE_ALIAS: &str = "Node1";
+        const NODE_DEST: &str = "Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv";
+
+        #[test]
+        pub fn node_works_for_merge() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_command::new();
+                let mut params = CommandParams::new();
+                params.insert("target", NODE_DEST.to_string());
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert("client_port", "9799".to_string());
+                params.insert("merge", "true".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let transaction = ctx.get_context_transaction().unwrap();
+            let transaction = serde_json::from_str::<JsonValue>(&transaction).unwrap();
+            let data = &transaction["operation"]["data"];
+            assert_eq!(json!(9799), data["client_port"]);
+            assert!(data["node_ip"].is_string());
+            assert!(data["node_port"].is_number());
+            assert!(data["blskey"].is_string());
+            assert!(data["blskey_pop"].is_string());
+            assert_eq!(json!(["VALIDATOR"]), data["services"]);
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_works_for_merge_and_blskey_without_pop() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_command::new();
+                let mut params = CommandParams::new();
+                params.insert("target", NODE_DEST.to_string());
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert("blskey", "2zN3bHM1m4rLz54MJHYSwvqzPchYp8jkHswveCLAEJVcX6Mm1wHQD1SkPYMzUDTZvWvhuE6VNAkK3KxVeEmsanSmvjVkReDeBEMxeDaayjcZjFGPydyey1qxBHmTvAnBKoPydvuTAqx5f7YNNRAdeLmUi99gERUU7TD8KfAa6MpQ9bw".to_string());
+                params.insert("merge", "true".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_works_for_merge_and_wrong_target() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_command::new();
+                let mut params = CommandParams::new();
+                params.insert(
+                    "target",
+                    "A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y".to_string(),
+                );
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert("merge", "true".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_works_for_merge_and_unknown_alias() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_command::new();
+                let mut params = CommandParams::new();
+                params.insert(
+                    "target",
+                    "A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y".to_string(),
+                );
+                params.insert("alias", "UnknownNode".to_string());
+                params.insert("merge", "true".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod node_demote {
+        use super::*;
+
+        const NODE_ALIAS: &str = "Node1";
+        const NODE_DEST: &str = "Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv";
+
+        #[test]
+        pub fn node_demote_works_for_send_false() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_demote_command::new();
+                let mut params = CommandParams::new();
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert("target", NODE_DEST.to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let transaction = ctx.get_context_transaction().unwrap();
+            let transaction = serde_json::from_str::<JsonValue>(&transaction).unwrap();
+            assert_eq!(json!([]), transaction["operation"]["data"]["services"]);
+            assert!(transaction["operation"]["data"]["node_ip"].is_null());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_demote_works_for_unknown_alias() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_demote_command::new();
+                let mut params = CommandParams::new();
+                params.insert("alias", "UnknownNode".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_demote_works_for_wrong_target() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_demote_command::new();
+                let mut params = CommandParams::new();
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert(
+                    "target",
+                    "A5iWQVT3k8Zo9nXj4otmeqaUziPQPCiDqcydXkAJBk1Y".to_string(),
+                );
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_promote_works_for_validator() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = node_promote_command::new();
+                let mut params = CommandParams::new();
+                params.insert("alias", NODE_ALIAS.to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
 }
@@ -3,15 +3,19 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
-    tools::ledger::{Ledger, Response},
+    tools::ledger::{Ledger, Response, ResponseType},
 };
 
 use serde_json::Value as JsonValue;
 
 use super::common::{
-    handle_transaction_response, print_transaction_response, set_author_agreement,
+    get_parallel_param, handle_transaction_response, print_transaction_response,
+    set_author_agreement,
 };
 
 pub mod nym_command {
@@ -20,19 +24,28 @@ pub mod nym_command {
 
     command!(
     CommandMetadata::build("nym", r#"Send NYM transaction to the Ledger."#)
-        .add_required_param("did", "DID of new identity")
+        .add_required_param_with_dynamic_completion("did", "DID of new identity", DynamicCompletionType::Did)
         .add_optional_param("verkey", "Verification key of new identity")
         .add_optional_param("role", "Role of identity. One of: STEWARD, TRUSTEE, TRUST_ANCHOR, ENDORSER, NETWORK_MONITOR or associated number, or empty in case of blacklisting NYM")
         .add_optional_param("sign","Sign the request (True by default)")
+        .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+        .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+        .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+        .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
         .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
-        .add_optional_param("endorser","DID of the Endorser that will submit the transaction to the ledger. \
+        .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+        .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger. \
             Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
-            The created request will be printed and stored into CLI context.")
+            The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
         .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX")
         .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX verkey=GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa")
         .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX role=TRUSTEE")
         .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX role=")
         .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX send=false")
+        .add_example("ledger nym did=VsKV7grR1BUE29mG2Fm2kX role=ENDORSER dry_run=true")
+        .add_precondition(Precondition::OpenedWallet)
+        .add_precondition(Precondition::ActiveDid)
+        .add_precondition(Precondition::ConnectedPool)
         .finalize()
     );
 
@@ -78,7 +91,7 @@ pub mod nym_command {
             None,
             role,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         set_author_agreement(ctx, &mut request)?;
 
@@ -86,9 +99,10 @@ pub mod nym_command {
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
 
         if let Some(result) = response.result.as_mut() {
+            let role_titles = ctx.get_role_titles();
             result["txn"]["data"]["role"] =
-                LedgerHelpers::get_role_title(&result["txn"]["data"]["role"]);
-            result["role"] = LedgerHelpers::get_role_title(&result["role"]);
+                LedgerHelpers::get_role_title(&result["txn"]["data"]["role"], &role_titles);
+            result["role"] = LedgerHelpers::get_role_title(&result["role"], &role_titles);
         }
 
         handle_transaction_response(response).map(|result| {
@@ -106,14 +120,123 @@ pub mod nym_command {
     }
 }
 
+pub mod nym_blacklist_command {
+    use super::super::common::get_nym_data;
+    use super::*;
+    use crate::{tools::ledger::LedgerHelpers, utils::table::print_table};
+
+    command!(
+    CommandMetadata::build("nym-blacklist", r#"Remove the role of an identity on the Ledger (blacklist NYM).
+            The current role and alias of the identity are read from the Ledger and shown before asking for confirmation."#)
+        .add_required_param_with_dynamic_completion("did", "DID of identity to blacklist", DynamicCompletionType::Did)
+        .add_optional_param("sign","Sign the request (True by default)")
+        .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+        .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+        .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+        .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+        .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger. \
+            Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
+            The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
+        .add_example("ledger nym-blacklist did=VsKV7grR1BUE29mG2Fm2kX")
+        .add_example("ledger nym-blacklist did=VsKV7grR1BUE29mG2Fm2kX send=false")
+        .add_precondition(Precondition::OpenedWallet)
+        .add_precondition(Precondition::ActiveDid)
+        .add_precondition(Precondition::ConnectedPool)
+        .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let pool = ctx.ensure_connected_pool()?;
+        let submitter_did = ctx.ensure_active_did()?;
+
+        let target_did = ParamParser::get_did_param("did", params)?;
+
+        let nym = match get_nym_data(&pool, Some(&submitter_did), &target_did)? {
+            Some(nym) => nym,
+            None => {
+                println_err!("NYM \"{}\" not found on the Ledger", target_did);
+                return Err(());
+            }
+        };
+
+        let role = LedgerHelpers::get_role_title(&nym["role"], &ctx.get_role_titles());
+        print_table(
+            &json!({
+                "did": target_did.to_string(),
+                "role": role,
+                "alias": nym["alias"].as_str().unwrap_or("-"),
+            }),
+            &[("did", "Did"), ("role", "Current Role"), ("alias", "Alias")],
+        );
+
+        if nym["role"].is_null() || nym["role"].as_str() == Some("") {
+            println_warn!(
+                "NYM \"{}\" has no role on the Ledger. Nothing to remove.",
+                target_did
+            );
+            return Ok(());
+        }
+
+        if target_did == *submitter_did {
+            println_warn!("You are going to remove the role of the active DID.");
+        }
+        println_warn!(
+            "Do you really want to remove role {} from NYM \"{}\"? (y/n)",
+            role.as_str().unwrap_or("-"),
+            target_did
+        );
+        if !crate::command_executor::wait_for_user_reply(ctx) {
+            println!("The transaction has not been sent.");
+            return Ok(());
+        }
+
+        let mut request = Ledger::build_nym_request(
+            Some(&pool),
+            &submitter_did,
+            &target_did,
+            None,
+            None,
+            Some(""),
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        set_author_agreement(ctx, &mut request)?;
+
+        let (_, response): (String, Response<JsonValue>) =
+            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+
+        handle_transaction_response(response).map(|result| {
+            print_transaction_response(
+                result,
+                &format!("Role of NYM \"{}\" has been removed.", target_did),
+                None,
+                &[("dest", "Did")],
+                true,
+            )
+        })?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
 pub mod get_nym_command {
     use super::*;
     use crate::tools::ledger::LedgerHelpers;
 
     command!(CommandMetadata::build("get-nym", "Get NYM from Ledger.")
-                .add_required_param("did","DID of identity presented in Ledger")
+                .add_required_param_with_dynamic_completion("did","DID of identity presented in Ledger", DynamicCompletionType::Did)
+                .add_optional_param("seq_no", "Get the NYM state as of the ledger transaction with this sequence number (requires Indy Node 1.13+)")
+                .add_optional_param("timestamp", "Get the NYM state as of this time, in seconds since epoch (requires Indy Node 1.13+)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger get-nym did=VsKV7grR1BUE29mG2Fm2kX")
+                .add_example("ledger get-nym did=VsKV7grR1BUE29mG2Fm2kX seq_no=12")
+                .add_example("ledger get-nym did=VsKV7grR1BUE29mG2Fm2kX timestamp=1600000000")
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -124,10 +247,22 @@ pub mod get_nym_command {
         let pool = ctx.get_connected_pool();
 
         let target_did = ParamParser::get_did_param("did", params)?;
+        let seq_no = ParamParser::get_opt_number_param::<i32>("seq_no", params)?;
+        let timestamp = ParamParser::get_opt_number_param::<u64>("timestamp", params)?;
 
-        let request =
-            Ledger::build_get_nym_request(pool.as_deref(), submitter_did.as_deref(), &target_did)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+        if seq_no.is_some() && timestamp.is_some() {
+            println_err!("Only one of the parameters `seq_no` and `timestamp` can be specified");
+            return Err(());
+        }
+
+        let request = Ledger::build_historical_get_nym_request(
+            pool.as_deref(),
+            submitter_did.as_deref(),
+            &target_did,
+            seq_no,
+            timestamp,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, mut response) = send_read_request!(&ctx, params, &request);
 
@@ -135,7 +270,8 @@ pub mod get_nym_command {
             let data = serde_json::from_str::<JsonValue>(&result["data"].as_str().unwrap_or(""));
             match data {
                 Ok(mut data) => {
-                    data["role"] = LedgerHelpers::get_role_title(&data["role"]);
+                    data["role"] =
+                        LedgerHelpers::get_role_title(&data["role"], &ctx.get_role_titles());
                     result["data"] = data;
                 }
                 Err(_) => {
@@ -165,9 +301,413 @@ pub mod get_nym_command {
     }
 }
 
+pub mod nym_batch_command {
+    use super::*;
+    use crate::{
+        tools::{pool::Pool, wallet::Wallet},
+        utils::{
+            csv::{format_line, parse_line},
+            file::{read_file, write_file},
+        },
+    };
+    use indy_utils::did::DidValue;
+    use indy_vdr::pool::PreparedRequest;
+
+    command!(
+    CommandMetadata::build("nym-batch", r#"Send NYM transaction to the Ledger for every row of a CSV file and save results into another CSV file."#)
+        .add_required_param("file", "Path to the CSV file. Every row contains: did,verkey,role,alias (verkey, role and alias can be empty). \
+            Header row, empty lines and lines starting with # are skipped.")
+        .add_optional_param("out", "Path to the file to save results into (<file>.results.csv by default)")
+        .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transactions to the ledger. \
+            Note that specifying of this parameter implies that transactions will be prepared to pass to the endorser instead of sending to the ledger.\
+            The prepared requests are saved into the results file.", DynamicCompletionType::Did)
+        .add_optional_param("parallel", "Number of requests to submit concurrently over the pool connection (1 by default). Results keep the order of the rows")
+        .add_example("ledger nym-batch file=/home/issuers.csv")
+        .add_example("ledger nym-batch file=/home/issuers.csv out=/home/issuers_results.csv")
+        .add_example("ledger nym-batch file=/home/issuers.csv parallel=10")
+        .add_example("ledger nym-batch file=/home/issuers.csv endorser=V4SGRU86Z58d6TV7PBUe6f")
+        .add_precondition(Precondition::OpenedWallet)
+        .add_precondition(Precondition::ActiveDid)
+        .add_precondition(Precondition::ConnectedPool)
+        .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+
+        let file = ParamParser::get_str_param("file", params)?;
+        let out = ParamParser::get_opt_str_param("out", params)?
+            .map(String::from)
+            .unwrap_or_else(|| format!("{}.results.csv", file));
+        let endorser = ParamParser::get_opt_did_param("endorser", params)?;
+        let parallel = get_parallel_param(params)?;
+
+        let pool = match endorser {
+            Some(_) => ctx.get_connected_pool(),
+            None => Some(ctx.ensure_connected_pool()?),
+        };
+
+        let content = read_file(file).map_err(|err| println_err!("{}", err))?;
+
+        let mut rows = Vec::new();
+        let mut first_row = true;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            // the header is the first row after leading blank and comment lines
+            let fields = parse_line(line);
+            if std::mem::take(&mut first_row) && fields[0].eq_ignore_ascii_case("did") {
+                continue;
+            }
+            rows.push(fields);
+        }
+
+        let mut results = vec![format_line(&["did", "status", "seqNo", "details"])];
+        let mut failed = 0;
+
+        for chunk in rows.chunks(parallel) {
+            let chunk = chunk
+                .iter()
+                .map(|fields| {
+                    let field = |index: usize| {
+                        fields
+                            .get(index)
+                            .map(String::as_str)
+                            .filter(|value| !value.is_empty())
+                    };
+                    NymRow {
+                        did: fields[0].as_str(),
+                        verkey: field(1),
+                        role: field(2),
+                        alias: field(3),
+                    }
+                })
+                .collect::<Vec<NymRow>>();
+
+            let chunk_results = send_nyms(
+                ctx,
+                &wallet,
+                pool.as_deref(),
+                &submitter_did,
+                endorser.as_ref(),
+                &chunk,
+            );
+
+            for (row, result) in chunk.iter().zip(chunk_results) {
+                let did = row.did;
+                let result = match result {
+                    Ok(NymResult::Written(seq_no)) => {
+                        println_succ!("NYM for \"{}\" has been written (seqNo {})", did, seq_no);
+                        format_line(&[did, "written", &seq_no, ""])
+                    }
+                    Ok(NymResult::Prepared(request)) => {
+                        println_succ!("NYM for \"{}\" has been prepared for the endorser", did);
+                        format_line(&[did, "prepared", "", &request])
+                    }
+                    Err(reason) => {
+                        failed += 1;
+                        println_err!("NYM for \"{}\" has failed: {}", did, reason);
+                        format_line(&[did, "failed", "", &reason])
+                    }
+                };
+                results.push(result);
+            }
+        }
+
+        write_file(&out, &results.join("\n"))
+            .map_err(|err| println_err!("Unable to save results: {}", err))?;
+
+        let total = results.len() - 1;
+        println_succ!(
+            "{} of {} NYM transaction(s) have been processed. Results have been saved into \"{}\"",
+            total - failed,
+            total,
+            out
+        );
+
+        trace!("execute <<");
+
+        if failed > 0 {
+            return Err(());
+        }
+        Ok(())
+    }
+
+    pub(super) struct NymRow<'a> {
+        pub did: &'a str,
+        pub verkey: Option<&'a str>,
+        pub role: Option<&'a str>,
+        pub alias: Option<&'a str>,
+    }
+
+    pub(super) enum NymResult {
+        Written(String),
+        Prepared(String),
+    }
+
+    pub(super) fn send_nym(
+        ctx: &CommandContext,
+        wallet: &Wallet,
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        endorser: Option<&DidValue>,
+        row: &NymRow,
+    ) -> Result<NymResult, String> {
+        send_nyms(
+            ctx,
+            wallet,
+            pool,
+            submitter_did,
+            endorser,
+            std::slice::from_ref(row),
+        )
+        .remove(0)
+    }
+
+    /// Builds and signs NYM requests for all rows one by one and then submits them to the pool
+    /// concurrently. Results are returned in the order of the rows.
+    pub(super) fn send_nyms(
+        ctx: &CommandContext,
+        wallet: &Wallet,
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        endorser: Option<&DidValue>,
+        rows: &[NymRow],
+    ) -> Vec<Result<NymResult, String>> {
+        let prepared = rows
+            .iter()
+            .map(|row| prepare_nym(ctx, wallet, pool, submitter_did, endorser, row))
+            .collect::<Vec<Result<PreparedRequest, String>>>();
+
+        if endorser.is_some() {
+            return prepared
+                .into_iter()
+                .map(|request| {
+                    request.map(|request| NymResult::Prepared(json!(&request.req_json).to_string()))
+                })
+                .collect();
+        }
+
+        let pool = match pool {
+            Some(pool) => pool,
+            None => {
+                return rows
+                    .iter()
+                    .map(|_| Err("There is no opened pool now.".to_string()))
+                    .collect()
+            }
+        };
+
+        let requests = prepared
+            .iter()
+            .filter_map(|request| request.as_ref().ok())
+            .collect::<Vec<&PreparedRequest>>();
+        let mut replies = match Ledger::submit_requests(pool, &requests, ctx.get_request_retries())
+        {
+            Ok(replies) => replies.into_iter(),
+            Err(err) => {
+                let message = err.message(Some(&pool.name));
+                return rows.iter().map(|_| Err(message.clone())).collect();
+            }
+        };
+
+        prepared
+            .into_iter()
+            .map(|request| {
+                request?;
+                match replies.next() {
+                    Some(Ok(response_json)) => parse_nym_response(&response_json),
+                    Some(Err(err)) => Err(err.message(Some(&pool.name))),
+                    None => Err("Invalid data has been received".to_string()),
+                }
+            })
+            .collect()
+    }
+
+    fn prepare_nym(
+        ctx: &CommandContext,
+        wallet: &Wallet,
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        endorser: Option<&DidValue>,
+        row: &NymRow,
+    ) -> Result<PreparedRequest, String> {
+        let target_did = DidValue(row.did.to_string());
+
+        let mut request = Ledger::build_nym_request(
+            pool,
+            submitter_did,
+            &target_did,
+            row.verkey,
+            row.alias,
+            row.role,
+        )
+        .map_err(|err| err.message(None))?;
+
+        set_author_agreement(ctx, &mut request)
+            .map_err(|_| "Unable to append transaction author agreement".to_string())?;
+
+        if let Some(endorser) = endorser {
+            Ledger::append_request_endorser(&mut request, endorser)
+                .map_err(|err| err.message(None))?;
+        }
+        Ledger::sign_request(wallet, submitter_did, &mut request)
+            .map_err(|err| err.message(None))?;
+        Ok(request)
+    }
+
+    fn parse_nym_response(response_json: &str) -> Result<NymResult, String> {
+        let response = serde_json::from_str::<Response<JsonValue>>(response_json)
+            .map_err(|err| format!("Invalid data has been received: {:?}", err))?;
+
+        match response {
+            Response {
+                op: ResponseType::REPLY,
+                result: Some(result),
+                ..
+            } => Ok(NymResult::Written(
+                result["txnMetadata"]["seqNo"].to_string(),
+            )),
+            Response {
+                reason: Some(reason),
+                ..
+            } => Err(reason),
+            _ => Err("Invalid data has been received".to_string()),
+        }
+    }
+}
+
+pub mod seed_identities_command {
+    use super::nym_batch_command::{send_nym, NymResult, NymRow};
+    use super::*;
+    use crate::{
+        tools::did::{seed::Seed, Did},
+        utils::{csv::format_line, file::write_file},
+    };
+    use std::{thread, time::Duration};
+
+    const DEFAULT_DELAY_MS: u64 = 100;
+
+    command!(
+    CommandMetadata::build("seed-identities", r#"Create DIDs in the wallet and send NYM transactions for them to the Ledger. Created identities are saved into CSV file for test harnesses."#)
+        .add_required_param("count", "Number of identities to create")
+        .add_required_param("role", "Role of created identities. One of: STEWARD, TRUSTEE, TRUST_ANCHOR, ENDORSER, NETWORK_MONITOR or associated number, or empty for identities without role")
+        .add_optional_param("prefix", "Alias prefix of created identities. Aliases are numbered starting from 1 (`identity` by default)")
+        .add_optional_param("out", "Path to the CSV file to save did,verkey,seed,alias,status,seqNo of created identities into (<prefix>.csv by default)")
+        .add_optional_param("delay", "Delay between transactions in milliseconds to throttle load on the pool (100 by default)")
+        .add_example("ledger seed-identities count=10 role=ENDORSER")
+        .add_example("ledger seed-identities count=100 role= prefix=holder out=/home/holders.csv delay=500")
+        .add_precondition(Precondition::OpenedWallet)
+        .add_precondition(Precondition::ActiveDid)
+        .add_precondition(Precondition::ConnectedPool)
+        .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.ensure_connected_pool()?;
+
+        let count = ParamParser::get_number_param::<usize>("count", params)?;
+        let role =
+            ParamParser::get_opt_empty_str_param("role", params)?.filter(|role| !role.is_empty());
+        let prefix = ParamParser::get_opt_str_param("prefix", params)?.unwrap_or("identity");
+        let out = ParamParser::get_opt_str_param("out", params)?
+            .map(String::from)
+            .unwrap_or_else(|| format!("{}.csv", prefix));
+        let delay =
+            ParamParser::get_opt_number_param::<u64>("delay", params)?.unwrap_or(DEFAULT_DELAY_MS);
+
+        if count == 0 {
+            println_err!("Number of identities must be greater than 0");
+            return Err(());
+        }
+
+        let mut results = vec![format_line(&[
+            "did", "verkey", "seed", "alias", "status", "seqNo",
+        ])];
+        let mut failed = 0;
+
+        for index in 1..=count {
+            if index > 1 && delay > 0 {
+                thread::sleep(Duration::from_millis(delay));
+            }
+
+            let seed = Seed::generate().to_hex();
+            let alias = format!("{}{}", prefix, index);
+
+            let (did, verkey) =
+                match Did::create(&wallet, None, Some(&seed), Some(&alias), None, None, None) {
+                    Ok(did) => did,
+                    Err(err) => {
+                        println_err!("{}", err.report(None));
+                        break;
+                    }
+                };
+
+            let row = NymRow {
+                did: &did,
+                verkey: Some(&verkey),
+                role,
+                alias: Some(&alias),
+            };
+
+            let (status, seq_no) =
+                match send_nym(ctx, &wallet, Some(&pool), &submitter_did, None, &row) {
+                    Ok(NymResult::Written(seq_no)) => {
+                        println_succ!(
+                            "NYM for \"{}\" ({}/{}) has been written (seqNo {})",
+                            did,
+                            index,
+                            count,
+                            seq_no
+                        );
+                        ("written", seq_no)
+                    }
+                    Ok(NymResult::Prepared(_)) => ("prepared", String::new()),
+                    Err(reason) => {
+                        failed += 1;
+                        println_err!("NYM for \"{}\" has failed: {}", did, reason);
+                        ("failed", String::new())
+                    }
+                };
+            results.push(format_line(&[
+                &did, &verkey, &seed, &alias, status, &seq_no,
+            ]));
+        }
+
+        write_file(&out, &results.join("\n"))
+            .map_err(|err| println_err!("Unable to save identities: {}", err))?;
+
+        let total = results.len() - 1;
+        println_succ!(
+            "{} of {} identities have been published. Identities have been saved into \"{}\"",
+            total - failed,
+            count,
+            out
+        );
+
+        trace!("execute <<");
+
+        if failed > 0 || total < count {
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::utils::file::read_file;
     use crate::{
         commands::{
             did::tests::{
@@ -180,8 +720,10 @@ pub mod tests {
         },
         ledger::tests::{create_new_did, use_trustee, ReplyResult},
         pool::constants::DEFAULT_POOL_PROTOCOL_VERSION,
+        tools::pool::pool_config::PoolDirectory,
     };
     use indy_utils::did::DidValue;
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     mod nym {
         use super::*;
@@ -220,6 +762,62 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn nym_works_for_idempotent() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            for _ in 0..2 {
+                let cmd = nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("verkey", verkey.clone());
+                params.insert("idempotent", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_nym_added(&ctx, &did).is_ok());
+            let pool = ctx.ensure_connected_pool().unwrap();
+            let journal = PoolDirectory::from(&pool.name).read_journal().unwrap();
+            assert_eq!(1, journal.entries.len());
+            assert_eq!("1", journal.entries[0].txn_type);
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_works_for_precheck() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            {
+                let cmd = nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("verkey", verkey);
+                params.insert("role", "TRUSTEE".to_string());
+                params.insert("precheck", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_nym_added(&ctx, &did).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_works_for_dry_run() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            {
+                let cmd = nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                params.insert("verkey", verkey);
+                params.insert("dry_run", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_context_transaction().is_none());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn nym_works_for_wrong_role() {
             let ctx = setup_with_wallet_and_pool();
@@ -372,6 +970,286 @@ pub mod tests {
         }
     }
 
+    mod nym_blacklist {
+        use super::*;
+        use crate::ledger::{get_nym_data, tests::send_nym};
+
+        #[test]
+        pub fn nym_blacklist_works() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            send_nym(&ctx, &did, &verkey, Some("ENDORSER"));
+            {
+                let cmd = nym_blacklist_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let pool = ctx.get_connected_pool().unwrap();
+            let nym = get_nym_data(&pool, None, &DidValue(did)).unwrap().unwrap();
+            assert!(nym["role"].is_null());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_blacklist_works_for_nym_without_role() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            send_nym(&ctx, &did, &verkey, None);
+            {
+                let cmd = nym_blacklist_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_blacklist_works_for_unknown_did() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, _) = create_new_did(&ctx);
+            {
+                let cmd = nym_blacklist_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", did);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_blacklist_works_for_no_connected_pool() {
+            let ctx = setup_with_wallet();
+            use_trustee(&ctx);
+            {
+                let cmd = nym_blacklist_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_MY1.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+
+    mod seed_identities {
+        use super::*;
+        use crate::utils::environment::EnvironmentUtils;
+
+        #[test]
+        pub fn seed_identities_works() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let out = EnvironmentUtils::tmp_file_path("seed_identities.csv");
+            {
+                let cmd = seed_identities_command::new();
+                let mut params = CommandParams::new();
+                params.insert("count", "2".to_string());
+                params.insert("role", "ENDORSER".to_string());
+                params.insert("prefix", "endorser".to_string());
+                params.insert("out", out.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let results = read_file(out).unwrap();
+            assert_eq!(3, results.lines().count());
+
+            let row = results.lines().nth(1).unwrap();
+            assert!(row.contains("endorser1"));
+            assert!(row.contains("written"));
+            let did = row.split(',').next().unwrap();
+            assert!(ensure_nym_added(&ctx, did).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn seed_identities_works_for_zero_count() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = seed_identities_command::new();
+                let mut params = CommandParams::new();
+                params.insert("count", "0".to_string());
+                params.insert("role", "ENDORSER".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn seed_identities_works_for_invalid_role() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let out = EnvironmentUtils::tmp_file_path("seed_identities_invalid.csv");
+            {
+                let cmd = seed_identities_command::new();
+                let mut params = CommandParams::new();
+                params.insert("count", "1".to_string());
+                params.insert("role", "ROLE".to_string());
+                params.insert("out", out.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            let results = read_file(out).unwrap();
+            assert!(results.lines().nth(1).unwrap().contains("failed"));
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod nym_batch {
+        use super::*;
+        use crate::utils::{environment::EnvironmentUtils, file::write_file};
+
+        fn write_csv(name: &str, content: &str) -> String {
+            let path = EnvironmentUtils::tmp_file_path(name);
+            let path_str = path.to_str().unwrap().to_string();
+            write_file(&path_str, content).unwrap();
+            path_str
+        }
+
+        #[test]
+        pub fn nym_batch_works() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did_1, verkey_1) = create_new_did(&ctx);
+            let (did_2, _) = create_new_did(&ctx);
+            let file = write_csv(
+                "nym_batch.csv",
+                &format!(
+                    "did,verkey,role,alias\n{},{},ENDORSER,Issuer 1\n\n# comment\n{},,,\n",
+                    did_1, verkey_1, did_2
+                ),
+            );
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_nym_added(&ctx, &did_1).is_ok());
+            assert!(ensure_nym_added(&ctx, &did_2).is_ok());
+
+            let results = read_file(format!("{}.results.csv", file)).unwrap();
+            assert_eq!(3, results.lines().count());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_batch_works_for_comment_before_header() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            let file = write_csv(
+                "nym_batch_comment.csv",
+                &format!(
+                    "# nyms to register\n\ndid,verkey,role,alias\n{},{},,\n",
+                    did, verkey
+                ),
+            );
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_nym_added(&ctx, &did).is_ok());
+
+            let results = read_file(format!("{}.results.csv", file)).unwrap();
+            assert_eq!(2, results.lines().count());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_batch_works_for_invalid_row() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            let out = EnvironmentUtils::tmp_file_path("nym_batch_invalid_results.csv");
+            let file = write_csv(
+                "nym_batch_invalid.csv",
+                &format!("{},{},ROLE,\n", did, verkey),
+            );
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                params.insert("out", out.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            let results = read_file(out).unwrap();
+            assert!(results.lines().nth(1).unwrap().contains("failed"));
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_batch_works_for_endorser() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            let file = write_csv("nym_batch_endorser.csv", &format!("{},{},,\n", did, verkey));
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file.clone());
+                params.insert("endorser", DID_TRUSTEE.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let results = read_file(format!("{}.results.csv", file)).unwrap();
+            assert!(results.lines().nth(1).unwrap().contains("prepared"));
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_batch_works_for_parallel() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let dids = (0..3)
+                .map(|_| create_new_did(&ctx))
+                .collect::<Vec<(String, String)>>();
+            let file = write_csv(
+                "nym_batch_parallel.csv",
+                &format!(
+                    "{},{},,\n{},{},ROLE,\n{},{},,\n",
+                    dids[0].0, dids[0].1, dids[1].0, dids[1].1, dids[2].0, dids[2].1
+                ),
+            );
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file.clone());
+                params.insert("parallel", "2".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ensure_nym_added(&ctx, &dids[0].0).is_ok());
+            assert!(ensure_nym_added(&ctx, &dids[2].0).is_ok());
+
+            // results keep the order of the rows
+            let results = read_file(format!("{}.results.csv", file)).unwrap();
+            let rows = results.lines().skip(1).collect::<Vec<&str>>();
+            assert!(rows[0].starts_with(&dids[0].0) && rows[0].contains("written"));
+            assert!(rows[1].starts_with(&dids[1].0) && rows[1].contains("failed"));
+            assert!(rows[2].starts_with(&dids[2].0) && rows[2].contains("written"));
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn nym_batch_works_for_zero_parallel() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+            let file = write_csv("nym_batch_zero.csv", &format!("{},{},,\n", did, verkey));
+            {
+                let cmd = nym_batch_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                params.insert("parallel", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
     mod get_nym {
         use super::*;
 
@@ -388,6 +1266,21 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn get_nym_works_for_timeout() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            ctx.set_request_retries(1);
+            {
+                let cmd = get_nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("timeout", "30".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn get_nym_works_for_no_active_did() {
             let ctx = setup_with_wallet_and_pool();
@@ -400,6 +1293,50 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn get_nym_works_for_seq_no() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("seq_no", "1".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_nym_works_for_timestamp() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+                params.insert("timestamp", now.as_secs().to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_nym_works_for_seq_no_and_timestamp() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_nym_command::new();
+                let mut params = CommandParams::new();
+                params.insert("did", DID_TRUSTEE.to_string());
+                params.insert("seq_no", "1".to_string());
+                params.insert("timestamp", "1600000000".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
         #[test]
         pub fn get_nym_works_for_unknown_did() {
             let ctx = setup_with_wallet_and_pool();
@@ -0,0 +1,280 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    params_parser::ParamParser,
+    tools::ledger::{Ledger, PluginCommandDefinition, PluginDefinition, Response},
+};
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use super::common::{handle_transaction_response, set_author_agreement};
+
+/// Reads plugin definition file and registers its commands in `ledger` group.
+/// Nothing is registered if any of the commands conflicts with the existing ones.
+/// Returns the plugin name and the names of the registered commands.
+pub fn load_plugin(ctx: &CommandContext, path: &str) -> Result<(String, Vec<String>), String> {
+    let definition = PluginDefinition::read(path).map_err(|err| err.message(None))?;
+    for command in definition.commands.iter() {
+        ctx.check_plugin_command("ledger", &command.name)?;
+    }
+
+    let mut names = Vec::new();
+    for command in definition.commands {
+        let name = command.name.to_string();
+        if let Some(ref title) = command.txn_title {
+            ctx.set_txn_title(&command.txn_type, title);
+        }
+        ctx.add_plugin_command("ledger", plugin_txn_command::new(command))?;
+        names.push(name);
+    }
+    Ok((definition.name, names))
+}
+
+pub mod plugin_txn_command {
+    use super::*;
+
+    /// Command metadata keeps `'static` strings, so the definition strings are leaked.
+    /// Plugins are loaded once per CLI session, so the amount of leaked memory is bounded.
+    fn leak(value: &str) -> &'static str {
+        Box::leak(value.to_string().into_boxed_str())
+    }
+
+    pub fn new(definition: PluginCommandDefinition) -> Command {
+        let mut metadata = CommandMetadata::build(leak(&definition.name), leak(&definition.help));
+
+        for param in definition.params.iter() {
+            metadata = if param.optional {
+                metadata.add_optional_param(leak(&param.name), leak(&param.help))
+            } else {
+                metadata.add_required_param(leak(&param.name), leak(&param.help))
+            };
+        }
+
+        if definition.write {
+            metadata = metadata
+                .add_optional_param("sign", "Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param_with_dynamic_completion(
+                    "endorser",
+                    "DID of the Endorser that will submit the transaction to the ledger later.",
+                    DynamicCompletionType::Did,
+                );
+        }
+
+        metadata = metadata
+            .add_optional_param("send", "Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+            .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value");
+
+        for example in definition.examples.iter() {
+            metadata = metadata.add_example(leak(example));
+        }
+
+        Command::new_dynamic(
+            metadata
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize(),
+            Box::new(move |ctx: &CommandContext, params: &CommandParams| {
+                execute(ctx, params, &definition)
+            }),
+        )
+    }
+
+    fn execute(
+        ctx: &CommandContext,
+        params: &CommandParams,
+        definition: &PluginCommandDefinition,
+    ) -> Result<(), ()> {
+        trace!(
+            "execute >> ctx {:?} params {:?} definition {:?}",
+            ctx,
+            params,
+            definition
+        );
+
+        let values = definition
+            .params
+            .iter()
+            .filter_map(|param| {
+                params
+                    .get(param.name.as_str())
+                    .map(|value| (param.name.as_str(), value.as_str()))
+            })
+            .collect::<HashMap<&str, &str>>();
+
+        let operation = definition
+            .build_operation(&values)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let pool = ctx.get_connected_pool();
+
+        let (_, response): (String, Response<JsonValue>) = if definition.write {
+            let wallet = ctx.ensure_opened_wallet()?;
+            let submitter_did = ctx.ensure_active_did()?;
+
+            let mut request = Ledger::build_custom_request(
+                pool.as_deref(),
+                Some(submitter_did.as_ref()),
+                operation,
+            )
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+            set_author_agreement(ctx, &mut request)?;
+
+            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did)
+        } else {
+            let submitter_did = ctx.get_active_did()?;
+
+            let request =
+                Ledger::build_custom_request(pool.as_deref(), submitter_did.as_deref(), operation)
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+
+            send_read_request!(ctx, params, &request)
+        };
+
+        let result = handle_transaction_response(response)?;
+
+        println_succ!(
+            "\"{}\" request has been sent to Ledger.",
+            definition.txn_title.as_deref().unwrap_or(&definition.name)
+        );
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&result).unwrap_or_default()
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{new_did, use_did, DID_TRUSTEE, SEED_TRUSTEE},
+        utils::{environment::EnvironmentUtils, file::write_file},
+    };
+
+    const PLUGIN: &str = r#"{
+        "name": "fees",
+        "commands": [
+            {
+                "name": "set-fees",
+                "help": "Set transaction fees",
+                "txnType": "20000",
+                "txnTitle": "SET_FEES",
+                "params": [
+                    {"name": "fees", "help": "Fees JSON", "type": "json"},
+                    {"name": "ledger_id", "help": "Ledger", "optional": true, "type": "number"}
+                ],
+                "operation": {"fees": "$fees", "ledgerId": "$ledger_id"}
+            }
+        ]
+    }"#;
+
+    fn write_plugin(name: &str, content: &str) -> String {
+        let path = EnvironmentUtils::tmp_file_path(name);
+        write_file(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    mod plugin {
+        use super::*;
+
+        #[test]
+        pub fn plugin_command_works_for_send_false() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            use_did(&ctx, DID_TRUSTEE);
+
+            let path = write_plugin("fees_plugin.json", PLUGIN);
+            let (name, commands) = load_plugin(&ctx, &path).unwrap();
+            assert_eq!("fees", name);
+            assert_eq!(vec!["set-fees".to_string()], commands);
+            assert_eq!(
+                Some("SET_FEES"),
+                ctx.get_txn_titles().get("20000").map(String::as_str)
+            );
+            {
+                let cmd = ctx.get_plugin_command("ledger", "set-fees").unwrap();
+                let mut params = CommandParams::new();
+                params.insert("fees", r#"{"1":4}"#.to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let transaction = ctx.get_context_transaction().unwrap();
+            let transaction = serde_json::from_str::<JsonValue>(&transaction).unwrap();
+            assert_eq!(
+                json!({"type": "20000", "fees": {"1": 4}}),
+                transaction["operation"]
+            );
+            assert_eq!(DID_TRUSTEE, transaction["identifier"]);
+            assert!(transaction["signature"].is_string());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn load_plugin_fails_for_duplicate_command() {
+            let ctx = setup_with_wallet();
+            let path = write_plugin("fees_plugin_duplicate.json", PLUGIN);
+            load_plugin(&ctx, &path).unwrap();
+            load_plugin(&ctx, &path).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn load_plugin_registers_nothing_for_conflicting_command() {
+            let ctx = setup_with_wallet();
+            load_plugin(&ctx, &write_plugin("fees_plugin_first.json", PLUGIN)).unwrap();
+            let path = write_plugin(
+                "fees_plugin_conflicting.json",
+                r#"{"name": "fees2", "commands": [
+                    {"name": "get-fees", "help": "", "txnType": "20001", "write": false, "operation": {}},
+                    {"name": "set-fees", "help": "", "txnType": "20000", "operation": {}}
+                ]}"#,
+            );
+            load_plugin(&ctx, &path).unwrap_err();
+            assert!(ctx.get_plugin_command("ledger", "get-fees").is_none());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn load_plugin_fails_for_command_defined_twice() {
+            let ctx = setup_with_wallet();
+            let path = write_plugin(
+                "fees_plugin_twice.json",
+                r#"{"name": "fees", "commands": [
+                    {"name": "get-fees", "help": "", "txnType": "20001", "write": false, "operation": {}},
+                    {"name": "get-fees", "help": "", "txnType": "20001", "write": false, "operation": {}}
+                ]}"#,
+            );
+            load_plugin(&ctx, &path).unwrap_err();
+            assert!(ctx.get_plugin_command("ledger", "get-fees").is_none());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn load_plugin_fails_for_invalid_definition() {
+            let ctx = setup_with_wallet();
+            let path = write_plugin(
+                "invalid_plugin.json",
+                r#"{"name": "invalid", "commands": [{"name": "set-fees", "help": "", "txnType": "20000", "operation": {"fees": "$fees"}}]}"#,
+            );
+            load_plugin(&ctx, &path).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
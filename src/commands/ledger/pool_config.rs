@@ -3,7 +3,7 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
 };
@@ -19,9 +19,17 @@ pub mod pool_config_command {
                 .add_required_param("writes", "Accept write transactions.")
                 .add_optional_param("force", "Forced configuration applying without reaching pool consensus.")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger pool-config writes=true")
                 .add_example("ledger pool-config writes=true force=true")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -37,7 +45,7 @@ pub mod pool_config_command {
 
         let mut request =
             Ledger::indy_build_pool_config_request(pool.as_deref(), &submitter_did, writes, force)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
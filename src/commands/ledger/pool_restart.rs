@@ -3,7 +3,10 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
     utils::table::print_table,
@@ -12,20 +15,23 @@ use crate::{
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 
-use super::common::{handle_transaction_response, sign_and_submit_action};
+use super::common::{handle_transaction_response, sign_and_submit_action, sign_and_submit_request};
 
 pub mod pool_restart_command {
     use super::*;
 
     command!(CommandMetadata::build("pool-restart", "Send instructions to nodes to restart themselves.")
                 .add_required_param("action", "Restart type. Either start or cancel.")
-                .add_optional_param("nodes","The list of node names to send the request")
+                .add_optional_param_with_dynamic_completion("nodes","The list of node names to send the request", DynamicCompletionType::Node)
                 .add_optional_param("timeout"," Time to wait respond from nodes")
                 .add_optional_param("datetime", "Node restart datetime (only for action=start).")
                 .add_example(r#"ledger pool-restart action=start datetime=2020-01-25T12:49:05.258870+00:00"#)
                 .add_example(r#"ledger pool-restart action=start datetime=2020-01-25T12:49:05.258870+00:00 nodes=Node1,Node2"#)
                 .add_example(r#"ledger pool-restart action=start datetime=2020-01-25T12:49:05.258870+00:00 nodes=Node1,Node2 timeout=100"#)
                 .add_example(r#"ledger pool-restart action=cancel"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -43,14 +49,22 @@ pub mod pool_restart_command {
 
         let mut request =
             Ledger::indy_build_pool_restart_request(Some(&pool), &submitter_did, action, datetime)
-                .map_err(|err| println_err!("{}", err.message(Some(&pool.name))))?;
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
 
         let response = if nodes.is_some() || timeout.is_some() {
-            sign_and_submit_action(&wallet, &pool, &submitter_did, &mut request, nodes, timeout)
-                .map_err(|err| println_err!("{}", err.message(None)))?
+            sign_and_submit_action(
+                ctx,
+                &wallet,
+                &pool,
+                &submitter_did,
+                &mut request,
+                nodes,
+                timeout,
+            )
+            .map_err(|err| println_err!("{}", err.report(None)))?
         } else {
-            Ledger::sign_and_submit_request(&pool, &wallet, &submitter_did, &mut request)
-                .map_err(|err| println_err!("{}", err.message(None)))?
+            sign_and_submit_request(ctx, &wallet, &pool, &submitter_did, &mut request)
+                .map_err(|err| println_err!("{}", err.report(None)))?
         };
 
         let responses = match serde_json::from_str::<HashMap<String, String>>(&response) {
@@ -3,12 +3,17 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
+    utils::table::print_list_table,
 };
 
-use serde_json::Value as JsonValue;
+use chrono::{DateTime, Duration, FixedOffset, SecondsFormat, Utc};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 
 use super::common::{handle_transaction_response, print_transaction_response};
 
@@ -31,10 +36,17 @@ pub mod pool_upgrade_command {
                 .add_optional_param("force", "Whether we should apply transaction without waiting for consensus of this transaction. False by default.")
                 .add_optional_param("package", "Package to be upgraded.")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
                 .add_example(r#"ledger pool-upgrade name=upgrade-1 version=2.0 action=start sha256=f284bdc3c1c9e24a494e285cb387c69510f28de51c15bb93179d9c7f28705398 schedule={"Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv":"2020-01-25T12:49:05.258870+00:00"}"#)
                 .add_example(r#"ledger pool-upgrade name=upgrade-1 version=2.0 action=start sha256=f284bdc3c1c9e24a494e285cb387c69510f28de51c15bb93179d9c7f28705398 schedule={"Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv":"2020-01-25T12:49:05.258870+00:00"} package=some_package"#)
                 .add_example(r#"ledger pool-upgrade name=upgrade-1 version=2.0 action=cancel sha256=ac3eb2cc3ac9e24a494e285cb387c69510f28de51c15bb93179d9c7f28705398"#)
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -70,10 +82,14 @@ pub mod pool_upgrade_command {
             force,
             package,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        // `timeout` parameter limits the upgrade time so it must not override the reply timeout
+        let mut request_params = params.clone();
+        request_params.remove("timeout");
 
         let (_, response): (String, Response<JsonValue>) =
-            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+            send_write_request!(ctx, &request_params, &mut request, &wallet, &submitter_did);
 
         let mut schedule = None;
         let mut hash = None;
@@ -126,6 +142,145 @@ pub mod pool_upgrade_command {
     }
 }
 
+pub mod pool_upgrade_schedule_command {
+    use super::*;
+
+    /// Name of the CLI variable the generated schedule is stored into.
+    pub const SCHEDULE_VARIABLE: &str = "UPGRADE_SCHEDULE";
+    const MIN_INTERVAL_MINUTES: i64 = 5;
+
+    command!(CommandMetadata::build("pool-upgrade-schedule", "Generate node upgrade schedule for the pool-upgrade command.")
+                .add_required_param("start", "Upgrade time of the first node in ISO 8601 format (e.g. 2020-01-25T12:49:05.258870+00:00).")
+                .add_required_param("interval", "Time difference between upgrades of the nodes in minutes.")
+                .add_optional_param_with_dynamic_completion("nodes", "Comma separated list of node names or identifiers to upgrade in the given order. \n                  \
+                                              All validator nodes of the pool ledger are used by default (from-ledger).", DynamicCompletionType::Node)
+                .add_optional_param("force", "Allow interval less than 5 minutes (False by default).")
+                .add_example("ledger pool-upgrade-schedule start=2020-01-25T12:49:05.258870+00:00 interval=10")
+                .add_example("ledger pool-upgrade-schedule start=2020-01-25T12:49:05.258870+00:00 interval=10 nodes=Node1,Node3")
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+
+        let start = ParamParser::get_str_param("start", params)?;
+        let interval = ParamParser::get_number_param::<i64>("interval", params)?;
+        let nodes = ParamParser::get_opt_str_array_param("nodes", params)?;
+        let force = ParamParser::get_opt_bool_param("force", params)?.unwrap_or(false);
+
+        let start = DateTime::parse_from_rfc3339(start)
+            .map_err(|err| println_err!("Invalid format of start time \"{}\": {}", start, err))?;
+
+        if interval <= 0 || (interval < MIN_INTERVAL_MINUTES && !force) {
+            println_err!(
+                "Interval must be at least {} minutes. Use force=true to allow a shorter interval.",
+                MIN_INTERVAL_MINUTES
+            );
+            return Err(());
+        }
+
+        if start < Utc::now() {
+            println_warn!(
+                "Start time is in the past. The Ledger rejects upgrades scheduled in the past."
+            );
+        }
+
+        let ledger_nodes = pool
+            .nodes()
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+
+        let nodes = match nodes.as_deref() {
+            None | Some(["from-ledger"]) => ledger_nodes
+                .iter()
+                .filter(|node| node.is_validator())
+                .collect::<Vec<_>>(),
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    ledger_nodes
+                        .iter()
+                        .find(|node| node.alias() == Some(*name) || node.dest == *name)
+                        .ok_or_else(|| {
+                            println_err!("Node \"{}\" not found in the pool ledger", name)
+                        })
+                })
+                .collect::<Result<Vec<_>, ()>>()?,
+        };
+
+        if nodes.is_empty() {
+            println_err!("There are no validator nodes in the pool ledger");
+            return Err(());
+        }
+
+        let schedule = build_schedule(
+            start,
+            interval,
+            &nodes
+                .iter()
+                .map(|node| node.dest.as_str())
+                .collect::<Vec<_>>(),
+        );
+
+        let rows = nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "alias": node.alias().unwrap_or_default(),
+                    "dest": node.dest,
+                    "time": schedule[&node.dest],
+                })
+            })
+            .collect::<Vec<JsonValue>>();
+        print_list_table(
+            &rows,
+            &[
+                ("alias", "Alias"),
+                ("dest", "Node"),
+                ("time", "Upgrade Time"),
+            ],
+            "",
+        );
+
+        let schedule = JsonValue::Object(schedule).to_string();
+        ctx.set_variable(SCHEDULE_VARIABLE, &schedule);
+
+        println_succ!(
+            "Schedule has been stored into variable \"{}\":",
+            SCHEDULE_VARIABLE
+        );
+        println!("{}", schedule);
+        println!(
+            "Use it in the upgrade transaction: ledger pool-upgrade ... schedule={{{{{}}}}}",
+            SCHEDULE_VARIABLE
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    /// Assigns upgrade times to the nodes in order, starting at `start` and `interval` minutes apart.
+    pub fn build_schedule(
+        start: DateTime<FixedOffset>,
+        interval: i64,
+        nodes: &[&str],
+    ) -> JsonMap<String, JsonValue> {
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let time = start + Duration::minutes(interval * index as i64);
+                (
+                    node.to_string(),
+                    JsonValue::String(time.to_rfc3339_opts(SecondsFormat::Micros, false)),
+                )
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -179,4 +334,105 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
     }
+
+    mod pool_upgrade_schedule {
+        use super::*;
+        use crate::commands::{setup, tear_down};
+
+        #[test]
+        pub fn build_schedule_works() {
+            let start = DateTime::parse_from_rfc3339("2020-01-25T12:49:05.258870+00:00").unwrap();
+            let schedule = pool_upgrade_schedule_command::build_schedule(
+                start,
+                10,
+                &[
+                    "Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv",
+                    "8ECVSk179mjsjKRLWiQtssMLgp6EPhWXtaYyStWPSGAb",
+                ],
+            );
+            assert_eq!(
+                JsonValue::Object(schedule),
+                json!({
+                    "Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv": "2020-01-25T12:49:05.258870+00:00",
+                    "8ECVSk179mjsjKRLWiQtssMLgp6EPhWXtaYyStWPSGAb": "2020-01-25T12:59:05.258870+00:00",
+                })
+            );
+        }
+
+        #[test]
+        pub fn pool_upgrade_schedule_works() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = pool_upgrade_schedule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("start", "2030-01-25T12:49:05.258870+00:00".to_string());
+                params.insert("interval", "10".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let schedule = ctx
+                .get_variables()
+                .get(pool_upgrade_schedule_command::SCHEDULE_VARIABLE)
+                .cloned()
+                .unwrap();
+            let schedule = serde_json::from_str::<JsonValue>(&schedule).unwrap();
+            assert!(schedule.as_object().unwrap().len() >= 4);
+            assert_eq!(
+                schedule["Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv"],
+                "2030-01-25T12:49:05.258870+00:00"
+            );
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn pool_upgrade_schedule_works_for_nodes() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = pool_upgrade_schedule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("start", "2030-01-25T12:49:05.258870+00:00".to_string());
+                params.insert("interval", "10".to_string());
+                params.insert("nodes", "Node2,Node1".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let schedule = ctx
+                .get_variables()
+                .get(pool_upgrade_schedule_command::SCHEDULE_VARIABLE)
+                .cloned()
+                .unwrap();
+            assert_eq!(
+                serde_json::from_str::<JsonValue>(&schedule).unwrap(),
+                json!({
+                    "8ECVSk179mjsjKRLWiQtssMLgp6EPhWXtaYyStWPSGAb": "2030-01-25T12:49:05.258870+00:00",
+                    "Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv": "2030-01-25T12:59:05.258870+00:00",
+                })
+            );
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn pool_upgrade_schedule_works_for_short_interval() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = pool_upgrade_schedule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("start", "2030-01-25T12:49:05.258870+00:00".to_string());
+                params.insert("interval", "1".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn pool_upgrade_schedule_works_for_no_connected_pool() {
+            let ctx = setup();
+            {
+                let cmd = pool_upgrade_schedule_command::new();
+                let mut params = CommandParams::new();
+                params.insert("start", "2030-01-25T12:49:05.258870+00:00".to_string());
+                params.insert("interval", "10".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
 }
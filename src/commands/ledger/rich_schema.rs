@@ -0,0 +1,384 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
+    params_parser::ParamParser,
+    tools::ledger::{Ledger, Response},
+    utils::file::read_file,
+};
+
+use indy_vdr::ledger::{
+    identifiers::RichSchemaId,
+    requests::rich_schema::{RSContent, RichSchema},
+};
+use serde_json::Value as JsonValue;
+
+use super::common::{
+    handle_transaction_response, print_transaction_response, set_author_agreement,
+};
+
+pub const RICH_SCHEMA_TYPES: [&str; 6] = ["sch", "map", "ctx", "enc", "cdf", "pdf"];
+pub const RICH_SCHEMA_OBJECT_VERSION: &str = "1";
+
+const RICH_SCHEMA_HEADERS: [(&str, &str); 5] = [
+    ("id", "Id"),
+    ("rsType", "Type"),
+    ("rsName", "Name"),
+    ("rsVersion", "Version"),
+    ("content", "Content"),
+];
+
+pub mod rich_schema_command {
+    use super::*;
+
+    command!(CommandMetadata::build("rich-schema", r#"Send Rich Schema object transaction to the Ledger."#)
+                .add_required_param("id", "Identifier of the object (DID-like string, e.g. did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD)")
+                .add_required_param("type", "Type of the object: sch (schema), map (mapping), ctx (context), enc (encoding), cdf (credential definition) or pdf (presentation definition)")
+                .add_required_param("name", "Name of the object")
+                .add_required_param("version", "Version of the object")
+                .add_optional_param("content", "JSON-LD content of the object. Its `@id` field must match `id` parameter")
+                .add_optional_param("file", "Path to the file containing JSON-LD content of the object (alternative to `content` parameter)")
+                .add_optional_param("ver", "Version of the object format (1 by default)")
+                .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
+                    Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
+                    The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
+                .add_example(r#"ledger rich-schema id=did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD type=sch name=gvt version=1.0 content={"@id":"did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD","@type":"rdfs:Class"}"#)
+                .add_example("ledger rich-schema id=did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD type=ctx name=gvt-context version=1.0 file=/home/context.json")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let id = ParamParser::get_str_param("id", params)?;
+        let rs_type = get_rich_schema_type_param(params)?;
+        let name = ParamParser::get_str_param("name", params)?;
+        let version = ParamParser::get_str_param("version", params)?;
+        let ver =
+            ParamParser::get_opt_str_param("ver", params)?.unwrap_or(RICH_SCHEMA_OBJECT_VERSION);
+
+        let content = match (
+            ParamParser::get_opt_str_param("content", params)?,
+            ParamParser::get_opt_str_param("file", params)?,
+        ) {
+            (Some(content), None) => content.to_string(),
+            (None, Some(file)) => read_file(file).map_err(|err| println_err!("{}", err))?,
+            _ => {
+                println_err!("Either \"content\" or \"file\" parameter must be specified");
+                return Err(());
+            }
+        };
+        validate_content(id, &content).map_err(|err| println_err!("{}", err))?;
+
+        let rich_schema = RichSchema::new(
+            RichSchemaId::new(id.to_string()),
+            RSContent(content),
+            name.to_string(),
+            version.to_string(),
+            rs_type.to_string(),
+            ver.to_string(),
+        );
+
+        let mut request =
+            Ledger::build_rich_schema_request(pool.as_deref(), &submitter_did, rich_schema)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        set_author_agreement(ctx, &mut request)?;
+
+        let (_, response): (String, Response<JsonValue>) =
+            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+
+        handle_transaction_response(response).map(|result| {
+            print_transaction_response(
+                result,
+                "Rich Schema object request has been sent to Ledger.",
+                None,
+                &RICH_SCHEMA_HEADERS,
+                true,
+            )
+        })?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod get_rich_schema_by_id_command {
+    use super::*;
+
+    command!(CommandMetadata::build("get-rich-schema-by-id", "Get Rich Schema object from Ledger by its identifier.")
+                .add_required_param("id", "Identifier of the object")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger get-rich-schema-by-id id=did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD")
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let id = ParamParser::get_str_param("id", params)?;
+
+        let request = Ledger::build_get_rich_schema_by_id_request(
+            pool.as_deref(),
+            &submitter_did,
+            &RichSchemaId::new(id.to_string()),
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response) = send_read_request!(&ctx, params, &request);
+
+        print_rich_schema_response(response)?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod get_rich_schema_by_metadata_command {
+    use super::*;
+
+    command!(CommandMetadata::build("get-rich-schema-by-metadata", "Get Rich Schema object from Ledger by its type, name and version.")
+                .add_required_param("type", "Type of the object: sch, map, ctx, enc, cdf or pdf")
+                .add_required_param("name", "Name of the object")
+                .add_required_param("version", "Version of the object")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger get-rich-schema-by-metadata type=sch name=gvt version=1.0")
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let submitter_did = ctx.ensure_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let rs_type = get_rich_schema_type_param(params)?;
+        let name = ParamParser::get_str_param("name", params)?;
+        let version = ParamParser::get_str_param("version", params)?;
+
+        let request = Ledger::build_get_rich_schema_by_metadata_request(
+            pool.as_deref(),
+            &submitter_did,
+            rs_type,
+            name,
+            version,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response) = send_read_request!(&ctx, params, &request);
+
+        print_rich_schema_response(response)?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+fn get_rich_schema_type_param(params: &CommandParams) -> Result<&str, ()> {
+    let rs_type = ParamParser::get_str_param("type", params)?;
+    if !RICH_SCHEMA_TYPES.contains(&rs_type) {
+        println_err!(
+            "Invalid Rich Schema object type \"{}\". Supported types: {}",
+            rs_type,
+            RICH_SCHEMA_TYPES.join(", ")
+        );
+        return Err(());
+    }
+    Ok(rs_type)
+}
+
+fn validate_content(id: &str, content: &str) -> Result<(), String> {
+    let content = serde_json::from_str::<JsonValue>(content)
+        .map_err(|err| format!("Invalid Rich Schema object content: {}", err))?;
+    if !content.is_object() {
+        return Err("Rich Schema object content must be a JSON object".to_string());
+    }
+    match content["@id"].as_str() {
+        Some(content_id) if content_id == id => Ok(()),
+        Some(content_id) => Err(format!(
+            "Rich Schema object content \"@id\" \"{}\" does not match object id \"{}\"",
+            content_id, id
+        )),
+        None => Err("Rich Schema object content must contain \"@id\" field".to_string()),
+    }
+}
+
+fn print_rich_schema_response(response: Response<JsonValue>) -> Result<(), ()> {
+    if let Some(result) = response.result.as_ref() {
+        if result["data"].is_null() {
+            println_err!("Rich Schema object not found");
+            return Err(());
+        }
+    };
+
+    handle_transaction_response(response).map(|result| {
+        print_transaction_response(
+            result,
+            "Following Rich Schema object has been received.",
+            Some("data"),
+            &RICH_SCHEMA_HEADERS,
+            true,
+        )
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool},
+        ledger::tests::use_trustee,
+    };
+
+    const RS_ID: &str = "did:sov:2f9F8ZmxuvDqRiqqY29x6dx9oU4qwFTkPbDpWtwGbdUsrCD";
+
+    fn content() -> String {
+        json!({"@id": RS_ID, "@type": "rdfs:Class"}).to_string()
+    }
+
+    mod rich_schema {
+        use super::*;
+
+        #[test]
+        pub fn rich_schema_works_without_sending() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = rich_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", RS_ID.to_string());
+                params.insert("type", "sch".to_string());
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("content", content());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let transaction = ctx.get_context_transaction().unwrap();
+            let transaction: JsonValue = serde_json::from_str(&transaction).unwrap();
+            assert_eq!(RS_ID, transaction["operation"]["id"].as_str().unwrap());
+            assert_eq!("sch", transaction["operation"]["rsType"].as_str().unwrap());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn rich_schema_works_for_unknown_type() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = rich_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", RS_ID.to_string());
+                params.insert("type", "unknown".to_string());
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("content", content());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn rich_schema_works_for_mismatched_content_id() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = rich_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", RS_ID.to_string());
+                params.insert("type", "sch".to_string());
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert(
+                    "content",
+                    json!({"@id": "did:sov:other", "@type": "rdfs:Class"}).to_string(),
+                );
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn rich_schema_works_for_missed_content() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = rich_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", RS_ID.to_string());
+                params.insert("type", "sch".to_string());
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod get_rich_schema {
+        use super::*;
+
+        #[test]
+        pub fn get_rich_schema_by_id_works_without_sending() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_rich_schema_by_id_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", RS_ID.to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_context_transaction().is_some());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_rich_schema_by_metadata_works_without_sending() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_rich_schema_by_metadata_command::new();
+                let mut params = CommandParams::new();
+                params.insert("type", "sch".to_string());
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("send", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_context_transaction().is_some());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+}
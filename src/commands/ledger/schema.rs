@@ -3,7 +3,10 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        Precondition,
+    },
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
 };
@@ -14,24 +17,40 @@ use indy_vdr::ledger::{
 };
 use serde_json::Value as JsonValue;
 
-use super::common::{
-    handle_transaction_response, print_transaction_response, set_author_agreement,
+use super::{
+    common::{handle_transaction_response, print_transaction_response, set_author_agreement},
+    constants::{DOMAIN_LEDGER_TYPE, MAX_ATTRIBUTES_COUNT},
 };
 
 pub mod schema_command {
     use super::*;
+    use crate::{command_executor::CommandResult, tools::wallet::Wallet, utils::file::read_file};
+    use indy_utils::did::DidValue;
 
     command!(CommandMetadata::build("schema", r#"Send Schema transaction to the Ledger."#)
-                .add_required_param("name", "Schema name")
-                .add_required_param("version", "Schema version")
-                .add_required_param("attr_names", "Schema attributes split by comma (the number of attributes should be less or equal than 125)")
+                .add_optional_param("name", "Schema name")
+                .add_optional_param("version", "Schema version")
+                .add_optional_param("attr_names", "Schema attributes split by comma (the number of attributes should be less or equal than 125)")
+                .add_optional_param("attr_names_file", "Path to the file with schema attributes (one per line or split by comma, or a JSON array). Used instead of `attr_names`")
+                .add_optional_param("file", "Path to the JSON file containing a schema object with `name`, `version` and `attr_names` fields \
+                    or an array of such objects to publish sequentially. Cannot be combined with `name`, `version` and `attr_names`.")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
-                .add_optional_param("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_optional_param_with_dynamic_completion("endorser","DID of the Endorser that will submit the transaction to the ledger later. \
                     Note that specifying of this parameter implies send=false so the transaction will be prepared to pass to the endorser instead of sending to the ledger.\
-                    The created request will be printed and stored into CLI context.")
+                    The created request will be printed and stored into CLI context.", DynamicCompletionType::Did)
                 .add_example("ledger schema name=gvt version=1.0 attr_names=name,age")
                 .add_example("ledger schema name=gvt version=1.0 attr_names=name,age send=false")
+                .add_example("ledger schema name=gvt version=1.0 attr_names_file=/home/gvt_attributes.txt")
+                .add_example("ledger schema file=/home/schemas.json")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -40,30 +59,108 @@ pub mod schema_command {
 
         let wallet = ctx.ensure_opened_wallet()?;
         let submitter_did = ctx.ensure_active_did()?;
-        let pool = ctx.get_connected_pool();
 
-        let name = ParamParser::get_str_param("name", params)?;
-        let version = ParamParser::get_str_param("version", params)?;
-        let attr_names = ParamParser::get_str_array_param("attr_names", params)?;
+        let schemas = match ParamParser::get_opt_str_param("file", params)? {
+            Some(file) => {
+                if ["name", "version", "attr_names", "attr_names_file"]
+                    .iter()
+                    .any(|name| params.contains_key(name))
+                {
+                    println_err!(
+                        "Parameter \"file\" cannot be combined with \"name\", \"version\", \"attr_names\" and \"attr_names_file\""
+                    );
+                    return Err(());
+                }
+                read_schemas(file)?
+            }
+            None => {
+                let name = ParamParser::get_str_param("name", params)?;
+                let version = ParamParser::get_str_param("version", params)?;
+                let attr_names = match ParamParser::get_opt_str_param("attr_names_file", params)? {
+                    Some(_) if params.contains_key("attr_names") => {
+                        println_err!(
+                            "Parameter \"attr_names_file\" cannot be combined with \"attr_names\""
+                        );
+                        return Err(());
+                    }
+                    Some(attr_names_file) => read_attr_names(attr_names_file)?,
+                    None => ParamParser::get_str_array_param("attr_names", params)?
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                };
+                vec![SchemaEntry {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    attr_names,
+                }]
+            }
+        };
+
+        for schema in schemas.iter() {
+            schema.validate().map_err(|err| {
+                println_err!("{}", err);
+                if let Some(suggestion) = schema.suggest_attr_names() {
+                    println!("Suggested attributes: {}", suggestion);
+                }
+            })?;
+        }
+
+        let count = schemas.len();
+        for (index, schema) in schemas.into_iter().enumerate() {
+            if count > 1 {
+                println!(
+                    "Publishing schema {} of {}: \"{}\" version \"{}\"",
+                    index + 1,
+                    count,
+                    schema.name,
+                    schema.version
+                );
+            }
+            send_schema(ctx, params, &wallet, &submitter_did, schema).map_err(|_| {
+                if count > 1 {
+                    println_err!(
+                        "Publishing stopped: {} of {} schemas have been processed",
+                        index,
+                        count
+                    );
+                }
+            })?;
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn send_schema(
+        ctx: &CommandContext,
+        params: &CommandParams,
+        wallet: &Wallet,
+        submitter_did: &DidValue,
+        schema: SchemaEntry,
+    ) -> CommandResult {
+        let pool = ctx.get_connected_pool();
 
-        let id = SchemaId::new(&submitter_did, name, version);
+        let name = schema.name.clone();
+        let id = SchemaId::new(submitter_did, &schema.name, &schema.version);
         let schema = Schema::SchemaV1(SchemaV1 {
             id,
-            name: name.to_string(),
-            version: version.to_string(),
-            attr_names: AttributeNames::from(attr_names.as_slice()),
+            name: schema.name,
+            version: schema.version,
+            attr_names: AttributeNames::from(schema.attr_names),
             seq_no: None,
         });
 
-        let mut request = Ledger::build_schema_request(pool.as_deref(), &submitter_did, schema)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+        let mut request = Ledger::build_schema_request(pool.as_deref(), submitter_did, schema)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         set_author_agreement(ctx, &mut request)?;
 
         let (_, response): (String, Response<JsonValue>) =
-            send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
+            send_write_request!(ctx, params, &mut request, wallet, submitter_did);
 
         handle_transaction_response(response).map(|result| {
+            ctx.add_schema_name(&name);
             print_transaction_response(
                 result,
                 "Schema request has been sent to Ledger.",
@@ -75,22 +172,173 @@ pub mod schema_command {
                 ],
                 true,
             )
-        })?;
+        })
+    }
 
-        trace!("execute <<");
-        Ok(())
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum SchemaFile {
+        Single(SchemaEntry),
+        Multiple(Vec<SchemaEntry>),
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub(super) struct SchemaEntry {
+        pub(super) name: String,
+        pub(super) version: String,
+        #[serde(alias = "attrNames")]
+        pub(super) attr_names: Vec<String>,
+    }
+
+    impl SchemaEntry {
+        pub(super) fn validate(&self) -> Result<(), String> {
+            if self.name.is_empty() || self.version.is_empty() {
+                return Err("Schema name and version must not be empty".to_string());
+            }
+            if self.attr_names.is_empty() {
+                return Err(format!(
+                    "Schema \"{}\" must contain at least one attribute",
+                    self.name
+                ));
+            }
+            if let Some(attr) = self.attr_names.iter().find(|attr| attr.trim().is_empty()) {
+                return Err(format!(
+                    "Schema \"{}\" contains an empty attribute name \"{}\"",
+                    self.name, attr
+                ));
+            }
+            if let Some(attr) = self.attr_names.iter().find(|attr| attr.trim() != *attr) {
+                return Err(format!(
+                    "Schema \"{}\" attribute name \"{}\" has leading or trailing whitespace. Did you mean \"{}\"?",
+                    self.name,
+                    attr,
+                    attr.trim()
+                ));
+            }
+            for (index, attr) in self.attr_names.iter().enumerate() {
+                if let Some(other) = self.attr_names[..index]
+                    .iter()
+                    .find(|other| normalize_attr_name(other) == normalize_attr_name(attr))
+                {
+                    return Err(if other == attr {
+                        format!(
+                            "Schema \"{}\" contains duplicate attribute \"{}\"",
+                            self.name, attr
+                        )
+                    } else {
+                        format!(
+                            "Schema \"{}\" attributes \"{}\" and \"{}\" differ only by case or spaces, \
+                            so credential values can't be told apart",
+                            self.name, other, attr
+                        )
+                    });
+                }
+            }
+            if self.attr_names.len() > MAX_ATTRIBUTES_COUNT {
+                return Err(format!(
+                    "Schema \"{}\" contains {} attributes but the ledger accepts at most {}. \
+                    Split the attributes into several schemas.",
+                    self.name,
+                    self.attr_names.len(),
+                    MAX_ATTRIBUTES_COUNT
+                ));
+            }
+            Ok(())
+        }
+
+        /// Returns the attributes list with trimmed names and without empty names and duplicates
+        /// if it differs from the original one.
+        pub(super) fn suggest_attr_names(&self) -> Option<String> {
+            let mut suggested: Vec<&str> = Vec::new();
+            for attr in self.attr_names.iter().map(|attr| attr.trim()) {
+                if !attr.is_empty()
+                    && !suggested
+                        .iter()
+                        .any(|other| normalize_attr_name(other) == normalize_attr_name(attr))
+                {
+                    suggested.push(attr);
+                }
+            }
+            if suggested.is_empty() || suggested == self.attr_names {
+                return None;
+            }
+            Some(suggested.join(","))
+        }
+    }
+
+    /// Attribute names are matched by credential tooling ignoring case and spaces.
+    fn normalize_attr_name(attr: &str) -> String {
+        attr.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Reads attribute names from the file: a JSON array or names split by new lines and commas.
+    pub(super) fn read_attr_names(file: &str) -> Result<Vec<String>, ()> {
+        let content = read_file(file).map_err(|err| println_err!("{}", err))?;
+
+        if content.trim_start().starts_with('[') {
+            return serde_json::from_str::<Vec<String>>(&content)
+                .map_err(|err| println_err!("Invalid attributes file \"{}\": {}", file, err));
+        }
+
+        let attr_names = content
+            .lines()
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|attr| !attr.is_empty())
+            .map(String::from)
+            .collect::<Vec<String>>();
+        if attr_names.is_empty() {
+            println_err!(
+                "Attributes file \"{}\" does not contain any attribute",
+                file
+            );
+            return Err(());
+        }
+        Ok(attr_names)
+    }
+
+    pub(super) fn read_schemas(file: &str) -> Result<Vec<SchemaEntry>, ()> {
+        let content = read_file(file).map_err(|err| println_err!("{}", err))?;
+
+        let schemas = serde_json::from_str::<SchemaFile>(&content)
+            .map_err(|_| {
+                println_err!(
+                    "Invalid schema file \"{}\": expected a schema object with \"name\", \"version\" and \"attr_names\" fields or an array of them",
+                    file
+                )
+            })?;
+
+        match schemas {
+            SchemaFile::Single(schema) => Ok(vec![schema]),
+            SchemaFile::Multiple(schemas) if schemas.is_empty() => {
+                println_err!("Schema file \"{}\" does not contain any schema", file);
+                Err(())
+            }
+            SchemaFile::Multiple(schemas) => Ok(schemas),
+        }
     }
 }
 
 pub mod get_schema_command {
     use super::*;
-
-    command!(CommandMetadata::build("get-schema", "Get Schema from Ledger.")
-                .add_required_param("did", "DID of identity presented in Ledger")
-                .add_required_param("name", "Schema name")
-                .add_required_param("version", "Schema version")
+    use indy_utils::did::DidValue;
+    use indy_vdr::ledger::constants::SCHEMA;
+
+    command!(CommandMetadata::build("get-schema", "Get Schema from Ledger by DID, name and version, by schema id or by sequence number of the schema transaction.")
+                .add_optional_param_with_dynamic_completion("did", "DID of identity presented in Ledger", DynamicCompletionType::Did)
+                .add_optional_param_with_dynamic_completion("name", "Schema name", DynamicCompletionType::Schema)
+                .add_optional_param("version", "Schema version")
+                .add_optional_param("id", "Schema id (used instead of `did`, `name` and `version`)")
+                .add_optional_param("seq_no", "Sequence number of the schema transaction, e.g. referenced by credential definitions (used instead of `did`, `name` and `version`)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger get-schema did=VsKV7grR1BUE29mG2Fm2kX name=gvt version=1.0")
+                .add_example("ledger get-schema id=VsKV7grR1BUE29mG2Fm2kX:2:gvt:1.0")
+                .add_example("ledger get-schema seq_no=10")
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -100,15 +348,31 @@ pub mod get_schema_command {
         let submitter_did = ctx.get_active_did()?;
         let pool = ctx.get_connected_pool();
 
-        let target_did = ParamParser::get_did_param("did", params)?;
-        let name = ParamParser::get_str_param("name", params)?;
-        let version = ParamParser::get_str_param("version", params)?;
+        let target_did = ParamParser::get_opt_did_param("did", params)?;
+        let name = ParamParser::get_opt_str_param("name", params)?;
+        let version = ParamParser::get_opt_str_param("version", params)?;
+        let id = ParamParser::get_opt_str_param("id", params)?;
+        let seq_no = ParamParser::get_opt_number_param::<i32>("seq_no", params)?;
 
-        let id = SchemaId::new(&target_did, name, version);
+        let id = match (target_did, name, version, id, seq_no) {
+            (Some(target_did), Some(name), Some(version), None, None) => {
+                SchemaId::new(&target_did, name, version)
+            }
+            (None, None, None, Some(id), None) => SchemaId::from(id.to_string()),
+            (None, None, None, None, Some(seq_no)) => {
+                return get_schema_by_seq_no(ctx, params, submitter_did.as_deref(), seq_no);
+            }
+            _ => {
+                println_err!(
+                    "Either `did`, `name` and `version` or `id` or `seq_no` params must be specified"
+                );
+                return Err(());
+            }
+        };
 
         let request =
             Ledger::build_get_schema_request(pool.as_deref(), submitter_did.as_deref(), &id)
-                .map_err(|err| println_err!("{}", err.message(None)))?;
+                .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) = send_read_request!(&ctx, params, &request);
 
@@ -119,23 +383,72 @@ pub mod get_schema_command {
             }
         };
 
-        handle_transaction_response(response).map(|result| {
-            print_transaction_response(
-                result,
-                "Following Schema has been received.",
-                Some("data"),
-                &[
-                    ("name", "Name"),
-                    ("version", "Version"),
-                    ("attr_names", "Attributes"),
-                ],
-                true,
-            )
+        handle_transaction_response(response).map(|result| print_schema(result, &id.0))?;
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    /// Reads the schema transaction with GET_TXN request and prints it in the same form as GET_SCHEMA reply.
+    fn get_schema_by_seq_no(
+        ctx: &CommandContext,
+        params: &CommandParams,
+        submitter_did: Option<&DidValue>,
+        seq_no: i32,
+    ) -> Result<(), ()> {
+        let pool = ctx.get_connected_pool();
+
+        let request = Ledger::build_get_txn_request(
+            pool.as_deref(),
+            submitter_did,
+            DOMAIN_LEDGER_TYPE,
+            seq_no,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response) = send_read_request!(&ctx, params, &request);
+
+        let result = handle_transaction_response(response)?;
+        let (id, result) = parse_schema_txn(&result).ok_or_else(|| {
+            println_err!("Schema with sequence number {} not found", seq_no);
         })?;
 
+        print_schema(result, &id);
+
         trace!("execute <<");
         Ok(())
     }
+
+    /// Converts GET_TXN reply containing SCHEMA transaction into the schema id and GET_SCHEMA reply fields.
+    pub fn parse_schema_txn(result: &JsonValue) -> Option<(String, JsonValue)> {
+        let txn = &result["data"];
+        if txn["txn"]["type"].as_str() != Some(SCHEMA) {
+            return None;
+        }
+        let id = txn["txnMetadata"]["txnId"].as_str()?.to_string();
+        let schema = json!({
+            "identifier": txn["txn"]["metadata"]["from"],
+            "seqNo": txn["txnMetadata"]["seqNo"],
+            "txnTime": txn["txnMetadata"]["txnTime"],
+            "data": txn["txn"]["data"]["data"],
+        });
+        Some((id, schema))
+    }
+
+    fn print_schema(result: JsonValue, id: &str) {
+        print_transaction_response(
+            result,
+            "Following Schema has been received.",
+            Some("data"),
+            &[
+                ("name", "Name"),
+                ("version", "Version"),
+                ("attr_names", "Attributes"),
+            ],
+            true,
+        );
+        println!("Schema id: {}", id);
+    }
 }
 
 #[cfg(test)]
@@ -284,6 +597,185 @@ pub mod tests {
         }
     }
 
+    mod schema_file {
+        use super::*;
+        use crate::utils::{environment::EnvironmentUtils, file::write_file};
+
+        fn write_schema_file(name: &str, content: &str) -> String {
+            let path = EnvironmentUtils::tmp_file_path(name);
+            let path_str = path.to_str().unwrap().to_string();
+            write_file(&path_str, content).unwrap();
+            path_str
+        }
+
+        #[test]
+        pub fn schema_works_for_file() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            let file = write_schema_file(
+                "schema.json",
+                r#"{"name":"gvt","version":"1.0","attr_names":["name","age"]}"#,
+            );
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_schema_added(&ctx, &did).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn schema_works_for_file_with_array() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            let file = write_schema_file(
+                "schemas.json",
+                r#"[
+                    {"name":"gvt","version":"1.0","attr_names":["name","age"]},
+                    {"name":"xyz","version":"1.0","attrNames":["status","period"]}
+                ]"#,
+            );
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_schema_added(&ctx, &did).is_ok());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn schema_works_for_file_combined_with_name() {
+            let ctx = setup_with_wallet_and_pool();
+            use_new_endorser(&ctx);
+            let file = write_schema_file(
+                "schema_combined.json",
+                r#"{"name":"gvt","version":"1.0","attr_names":["name","age"]}"#,
+            );
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                params.insert("name", "gvt".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn schema_works_for_invalid_file() {
+            let ctx = setup_with_wallet_and_pool();
+            use_new_endorser(&ctx);
+            let file = write_schema_file("schema_invalid.json", r#"{"name":"gvt"}"#);
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", file);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn schema_works_for_too_many_attributes() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            let attr_names = (0..=MAX_ATTRIBUTES_COUNT)
+                .map(|index| format!("attr_{}", index))
+                .collect::<Vec<String>>();
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("attr_names", attr_names.join(","));
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ensure_schema_added(&ctx, &did).is_err());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
+
+    mod schema_validation {
+        use super::*;
+        use crate::utils::{environment::EnvironmentUtils, file::write_file};
+        use schema_command::{read_attr_names, SchemaEntry};
+
+        fn schema(attr_names: &[&str]) -> SchemaEntry {
+            SchemaEntry {
+                name: "gvt".to_string(),
+                version: "1.0".to_string(),
+                attr_names: attr_names.iter().map(|attr| attr.to_string()).collect(),
+            }
+        }
+
+        #[test]
+        pub fn validate_works() {
+            let schema = schema(&["name", "age"]);
+            schema.validate().unwrap();
+            assert_eq!(None, schema.suggest_attr_names());
+        }
+
+        #[test]
+        pub fn validate_works_for_duplicates() {
+            let schema = schema(&["name", "age", "name"]);
+            schema.validate().unwrap_err();
+            assert_eq!(Some("name,age".to_string()), schema.suggest_attr_names());
+
+            let schema = schema(&["first name", "age", "FirstName"]);
+            schema.validate().unwrap_err();
+            assert_eq!(
+                Some("first name,age".to_string()),
+                schema.suggest_attr_names()
+            );
+        }
+
+        #[test]
+        pub fn validate_works_for_whitespace() {
+            let schema = schema(&["name", " age"]);
+            assert!(schema
+                .validate()
+                .unwrap_err()
+                .contains("Did you mean \"age\"?"));
+            assert_eq!(Some("name,age".to_string()), schema.suggest_attr_names());
+
+            let schema = schema(&["name", ""]);
+            schema.validate().unwrap_err();
+            assert_eq!(Some("name".to_string()), schema.suggest_attr_names());
+        }
+
+        #[test]
+        pub fn validate_works_for_too_many_attributes() {
+            let attr_names = (0..=MAX_ATTRIBUTES_COUNT)
+                .map(|index| format!("attr_{}", index))
+                .collect::<Vec<String>>();
+            let schema = schema(&attr_names.iter().map(String::as_str).collect::<Vec<&str>>());
+            schema.validate().unwrap_err();
+            assert_eq!(None, schema.suggest_attr_names());
+        }
+
+        #[test]
+        pub fn read_attr_names_works() {
+            let path = EnvironmentUtils::tmp_file_path("schema_attr_names.txt");
+            let path = path.to_str().unwrap();
+
+            write_file(path, "name, age\nsex\n\nheight\n").unwrap();
+            assert_eq!(
+                vec!["name", "age", "sex", "height"],
+                read_attr_names(path).unwrap()
+            );
+
+            write_file(path, r#"["name", "age"]"#).unwrap();
+            assert_eq!(vec!["name", "age"], read_attr_names(path).unwrap());
+
+            write_file(path, "\n").unwrap();
+            read_attr_names(path).unwrap_err();
+        }
+    }
+
     mod get_schema {
         use super::*;
 
@@ -311,6 +803,92 @@ pub mod tests {
             tear_down_with_wallet_and_pool(&ctx);
         }
 
+        #[test]
+        pub fn get_schema_works_for_id_and_seq_no() {
+            let ctx = setup_with_wallet_and_pool();
+            let (did, _) = use_new_endorser(&ctx);
+            {
+                let cmd = schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", "gvt".to_string());
+                params.insert("version", "1.0".to_string());
+                params.insert("attr_names", "name,age".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ensure_schema_added(&ctx, &did).is_ok());
+            let id = SchemaId::new(&DidValue(did.to_string()), "gvt", "1.0");
+            {
+                let cmd = get_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", id.0.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let seq_no = {
+                let pool = ctx.get_connected_pool().unwrap();
+                let request = Ledger::build_get_schema_request(Some(&pool), None, &id).unwrap();
+                let response = Ledger::submit_request(&pool, &request).unwrap();
+                let response: JsonValue = serde_json::from_str(&response).unwrap();
+                response["result"]["seqNo"].as_i64().unwrap()
+            };
+            {
+                let cmd = get_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("seq_no", seq_no.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                // NYM transaction of the endorser is not a schema
+                let cmd = get_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("seq_no", "1".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_schema_works_for_ambiguous_params() {
+            let ctx = setup_with_wallet_and_pool();
+            {
+                let cmd = get_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("id", "VsKV7grR1BUE29mG2Fm2kX:2:gvt:1.0".to_string());
+                params.insert("seq_no", "10".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            {
+                let cmd = get_schema_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", "gvt".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn parse_schema_txn_works() {
+            let result = json!({
+                "seqNo": 10,
+                "data": {
+                    "txn": {
+                        "type": "101",
+                        "data": {"data": {"name": "gvt", "version": "1.0", "attr_names": ["name"]}},
+                        "metadata": {"from": "VsKV7grR1BUE29mG2Fm2kX"}
+                    },
+                    "txnMetadata": {"seqNo": 10, "txnTime": 1576674598, "txnId": "VsKV7grR1BUE29mG2Fm2kX:2:gvt:1.0"}
+                }
+            });
+            let (id, schema) = get_schema_command::parse_schema_txn(&result).unwrap();
+            assert_eq!("VsKV7grR1BUE29mG2Fm2kX:2:gvt:1.0", id);
+            assert_eq!("gvt", schema["data"]["name"]);
+            assert_eq!(10, schema["seqNo"]);
+
+            let mut nym = result.clone();
+            nym["data"]["txn"]["type"] = json!("1");
+            assert!(get_schema_command::parse_schema_txn(&nym).is_none());
+            assert!(get_schema_command::parse_schema_txn(&json!({"data": null})).is_none());
+        }
+
         #[test]
         pub fn schema_works_for_unknown_schema() {
             let ctx = setup_with_wallet_and_pool();
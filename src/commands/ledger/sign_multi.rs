@@ -3,7 +3,7 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
     tools::ledger::Ledger,
 };
@@ -24,6 +24,8 @@ pub mod sign_multi_command {
         "Transaction to sign. Skip to use a transaction stored into CLI context."
     )
     .add_example(r#"ledger sign-multi txn={"reqId":123456789,"type":"100"}"#)
+    .add_precondition(Precondition::OpenedWallet)
+    .add_precondition(Precondition::ActiveDid)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -48,11 +50,11 @@ pub mod sign_multi_command {
                         println_err!("Signer DID: \"{}\" not found", submitter_did);
                     }
                     _ => {
-                        println_err!("{}", err.message(None));
+                        println_err!("{}", err.report(None));
                     }
                 },
                 _ => {
-                    println_err!("{}", err.message(None));
+                    println_err!("{}", err.report(None));
                 }
             },
         };
@@ -3,13 +3,149 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
+    tools::{
+        ledger::{Ledger, Response},
+        pool::Pool,
+    },
     utils::file::{read_file, write_file},
 };
 
+use indy_vdr::pool::PreparedRequest;
 use serde_json::Value as JsonValue;
 
+use super::common::{
+    get_active_transaction_author_agreement, handle_transaction_response, trace_request,
+    trace_response,
+};
+
+pub const REQUEST_ENVELOPE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEnvelope {
+    pub version: u32,
+    pub network: Option<RequestNetwork>,
+    pub taa_digest: Option<String>,
+    pub author: String,
+    pub endorser: Option<String>,
+    pub signatures: Vec<String>,
+    pub request: JsonValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestNetwork {
+    pub pool: String,
+    pub genesis_node: String,
+}
+
+impl RequestEnvelope {
+    pub fn new(request: JsonValue, network: Option<RequestNetwork>) -> Result<Self, String> {
+        serde_json::from_value::<load_transaction_command::Request>(request.clone())
+            .map_err(|err| format!("Transaction is invalid: {}", err))?;
+
+        Ok(RequestEnvelope {
+            version: REQUEST_ENVELOPE_VERSION,
+            network,
+            taa_digest: Self::request_taa_digest(&request),
+            author: request["identifier"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            endorser: request["endorser"].as_str().map(String::from),
+            signatures: Self::request_signers(&request),
+            request,
+        })
+    }
+
+    /// Checks that the envelope metadata describes the wrapped request.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != REQUEST_ENVELOPE_VERSION {
+            return Err(format!(
+                "Unsupported request envelope version: {}",
+                self.version
+            ));
+        }
+
+        serde_json::from_value::<load_transaction_command::Request>(self.request.clone())
+            .map_err(|err| format!("Envelope contains invalid transaction: {}", err))?;
+
+        if self.request["identifier"].as_str() != Some(self.author.as_str()) {
+            return Err(format!(
+                "Envelope author \"{}\" does not match the transaction author",
+                self.author
+            ));
+        }
+        if self.request["endorser"].as_str() != self.endorser.as_deref() {
+            return Err("Envelope endorser does not match the transaction endorser".to_string());
+        }
+        if Self::request_taa_digest(&self.request) != self.taa_digest {
+            return Err(
+                "Envelope TAA digest does not match the transaction TAA acceptance".to_string(),
+            );
+        }
+
+        let mut signatures = self.signatures.clone();
+        signatures.sort();
+        if Self::request_signers(&self.request) != signatures {
+            return Err("Envelope signatures do not match the transaction signatures".to_string());
+        }
+
+        Ok(())
+    }
+
+    fn request_taa_digest(request: &JsonValue) -> Option<String> {
+        request["taaAcceptance"]["taaDigest"]
+            .as_str()
+            .map(String::from)
+    }
+
+    fn request_signers(request: &JsonValue) -> Vec<String> {
+        let mut signers: Vec<String> = match request["signatures"].as_object() {
+            Some(signatures) => signatures.keys().cloned().collect(),
+            None => Vec::new(),
+        };
+        if request["signature"].is_string() {
+            if let Some(identifier) = request["identifier"].as_str() {
+                signers.push(identifier.to_string());
+            }
+        }
+        signers.sort();
+        signers.dedup();
+        signers
+    }
+}
+
+/// Checks that the envelope has been prepared for the network of the given pool.
+pub(super) fn check_envelope_network(pool: &Pool, envelope: &RequestEnvelope) -> Result<(), ()> {
+    let network = match envelope.network {
+        Some(ref network) => network,
+        None => return Ok(()),
+    };
+
+    let genesis_node = pool
+        .genesis_node()
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+    if genesis_node != network.genesis_node {
+        println_err!(
+            "The request has been prepared for another network (pool \"{}\") than the opened pool \"{}\".",
+            network.pool,
+            pool.name
+        );
+        return Err(());
+    }
+    if let Some(ref taa_digest) = envelope.taa_digest {
+        let active_digest =
+            get_active_transaction_author_agreement(pool)?.and_then(|(_, _, digest)| digest);
+        if active_digest.as_ref() != Some(taa_digest) {
+            println_warn!("The request accepts transaction author agreement which is not active on the ledger anymore.");
+        }
+    }
+    Ok(())
+}
+
 pub mod save_transaction_command {
     use super::*;
 
@@ -86,11 +222,212 @@ pub mod load_transaction_command {
     }
 }
 
+pub mod export_request_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "export-request",
+        "Save transaction from CLI context into a file together with metadata describing where and by whom it was prepared."
+    )
+    .add_required_param("file", "The path to file.")
+    .add_example(r#"ledger export-request file=/home/request.json"#)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let file = ParamParser::get_str_param("file", params)?;
+
+        let transaction = ctx.ensure_context_transaction()?;
+        let request = serde_json::from_str::<JsonValue>(&transaction)
+            .map_err(|err| println_err!("Transaction stored into context is invalid: {:?}", err))?;
+
+        let network = match ctx.get_connected_pool() {
+            Some(pool) => {
+                let genesis_node = pool
+                    .genesis_node()
+                    .map_err(|err| println_err!("{}", err.report(None)))?;
+                Some(RequestNetwork {
+                    pool: pool.name.to_string(),
+                    genesis_node,
+                })
+            }
+            None => {
+                println_warn!("There is no opened pool now. The network will not be recorded.");
+                None
+            }
+        };
+
+        let envelope =
+            RequestEnvelope::new(request, network).map_err(|err| println_err!("{}", err))?;
+
+        let envelope = serde_json::to_string_pretty(&envelope)
+            .map_err(|err| println_err!("Unable to serialize request envelope: {:?}", err))?;
+
+        write_file(file, &envelope)
+            .map_err(|err| println_err!("Cannot save request into the file: {:?}", err))?;
+
+        println_succ!("The request has been exported into \"{}\".", file);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod import_request_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "import-request",
+        "Read transaction exported by `ledger export-request`, validate its metadata and store it into CLI context."
+    )
+    .add_required_param("file", "The path to file containing an exported request.")
+    .add_example(r#"ledger import-request file=/home/request.json"#)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let file = ParamParser::get_str_param("file", params)?;
+
+        let envelope = read_file(file).map_err(|err| println_err!("{}", err))?;
+
+        let envelope = serde_json::from_str::<RequestEnvelope>(&envelope)
+            .map_err(|err| println_err!("File contains invalid request envelope: {:?}", err))?;
+
+        envelope.validate().map_err(|err| println_err!("{}", err))?;
+
+        match (ctx.get_connected_pool(), envelope.network.as_ref()) {
+            (Some(pool), Some(_)) => check_envelope_network(&pool, &envelope)?,
+            (Some(_), None) => {
+                println_warn!("The request does not contain network metadata. It cannot be checked against the opened pool.");
+            }
+            (None, Some(network)) => {
+                println_warn!(
+                    "There is no opened pool now. The request has been prepared for pool \"{}\".",
+                    network.pool
+                );
+            }
+            (None, None) => {}
+        }
+
+        println!("Author: {}", envelope.author);
+        if let Some(ref endorser) = envelope.endorser {
+            println!("Endorser: {}", endorser);
+        }
+        println!(
+            "Signatures: {}",
+            if envelope.signatures.is_empty() {
+                "-".to_string()
+            } else {
+                envelope.signatures.join(", ")
+            }
+        );
+
+        let transaction = envelope.request.to_string();
+        println!("Transaction has been loaded: {}", transaction);
+
+        ctx.set_context_transaction(Some(transaction));
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod submit_signed_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "submit-signed",
+        "Send a fully signed transaction prepared elsewhere to the Ledger. Opened wallet is not required."
+    )
+    .add_required_param("file", "The path to file containing a signed transaction or a request exported by `ledger export-request`.")
+    .add_example(r#"ledger submit-signed file=/home/signed_request.json"#)
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+
+        let file = ParamParser::get_str_param("file", params)?;
+
+        let content = read_file(file).map_err(|err| println_err!("{}", err))?;
+        let content = serde_json::from_str::<JsonValue>(&content)
+            .map_err(|err| println_err!("File contains invalid JSON: {:?}", err))?;
+
+        let request = if content.get("request").is_some() {
+            let envelope = serde_json::from_value::<RequestEnvelope>(content)
+                .map_err(|err| println_err!("File contains invalid request envelope: {:?}", err))?;
+            envelope.validate().map_err(|err| println_err!("{}", err))?;
+            check_envelope_network(&pool, &envelope)?;
+            envelope.request
+        } else {
+            serde_json::from_value::<load_transaction_command::Request>(content.clone())
+                .map_err(|err| println_err!("File contains invalid transaction: {:?}", err))?;
+            content
+        };
+
+        check_signatures(&request)?;
+
+        let request = PreparedRequest::from_request_json(request.to_string())
+            .map_err(|_| println_err!("Invalid formatted transaction provided."))?;
+
+        trace_request(ctx, &request);
+        let response_json =
+            Ledger::submit_request_with_retries(&pool, &request, ctx.get_request_retries())
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        trace_response(ctx, &response_json);
+
+        let response = serde_json::from_str::<Response<JsonValue>>(&response_json)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+
+        let result = handle_transaction_response(response)?;
+
+        let metadata = &result["txnMetadata"];
+        match (metadata["seqNo"].as_u64(), metadata["txnTime"].as_u64()) {
+            (Some(seq_no), Some(txn_time)) => println_succ!(
+                "Transaction has been written to the Ledger with seqNo {} at {}",
+                seq_no,
+                txn_time
+            ),
+            _ => println_succ!("Transaction has been sent to the Ledger"),
+        }
+        println!("Response: \n{}", response_json);
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    /// The ledger rejects unsigned requests anyway, but checking it here gives a clear reason.
+    fn check_signatures(request: &JsonValue) -> Result<(), ()> {
+        let signers = RequestEnvelope::request_signers(request);
+        if signers.is_empty() {
+            println_err!("Transaction is not signed.");
+            return Err(());
+        }
+        if let Some(endorser) = request["endorser"].as_str() {
+            if !signers.iter().any(|signer| signer == endorser) {
+                println_err!(
+                    "Transaction must be signed by the endorser \"{}\" as well.",
+                    endorser
+                );
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use crate::{
-        commands::{did::tests::DID_TRUSTEE, setup, tear_down},
+        commands::{
+            did::tests::{DID_MY1, DID_TRUSTEE},
+            setup, tear_down,
+        },
         ledger::tests::TRANSACTION,
     };
 
@@ -214,4 +551,191 @@ pub mod tests {
             tear_down();
         }
     }
+
+    mod request_envelope {
+        use super::*;
+
+        fn signed_request() -> JsonValue {
+            json!({
+                "reqId": 1,
+                "identifier": DID_TRUSTEE,
+                "operation": {"type": "1", "dest": DID_TRUSTEE},
+                "protocolVersion": 2,
+                "taaAcceptance": {"mechanism": "on_file", "taaDigest": "abc", "time": 1},
+                "signature": "sig"
+            })
+        }
+
+        #[test]
+        pub fn export_import_request_works() {
+            let ctx = setup();
+
+            let (_, path_str) = path();
+            ctx.set_context_transaction(Some(signed_request().to_string()));
+            {
+                let cmd = export_request_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path_str.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let envelope =
+                serde_json::from_str::<RequestEnvelope>(&read_file(&path_str).unwrap()).unwrap();
+            assert_eq!(REQUEST_ENVELOPE_VERSION, envelope.version);
+            assert_eq!(DID_TRUSTEE, envelope.author);
+            assert_eq!(Some("abc".to_string()), envelope.taa_digest);
+            assert_eq!(vec![DID_TRUSTEE.to_string()], envelope.signatures);
+            assert_eq!(None, envelope.network);
+
+            ctx.set_context_transaction(None);
+            {
+                let cmd = import_request_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path_str);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let context_txn = ctx.get_context_transaction().unwrap();
+            assert_eq!(
+                signed_request(),
+                serde_json::from_str::<JsonValue>(&context_txn).unwrap()
+            );
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn import_request_works_for_modified_request() {
+            let ctx = setup();
+
+            let mut envelope = RequestEnvelope::new(signed_request(), None).unwrap();
+            envelope.request["taaAcceptance"]["taaDigest"] = json!("other");
+
+            let (_, path_str) = path();
+            write_file(&path_str, &serde_json::to_string(&envelope).unwrap()).unwrap();
+            {
+                let cmd = import_request_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path_str);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ctx.get_context_transaction().is_none());
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn import_request_works_for_raw_transaction() {
+            let ctx = setup();
+
+            let (_, path_str) = path();
+            write_file(&path_str, TRANSACTION).unwrap();
+            {
+                let cmd = import_request_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path_str);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn request_envelope_validate_works_for_unknown_version() {
+            let mut envelope = RequestEnvelope::new(signed_request(), None).unwrap();
+            envelope.version = REQUEST_ENVELOPE_VERSION + 1;
+            envelope.validate().unwrap_err();
+        }
+
+        #[test]
+        pub fn request_envelope_works_for_multi_signatures() {
+            let mut request = signed_request();
+            request.as_object_mut().unwrap().remove("signature");
+            request["signatures"] = json!({DID_MY1: "sig1", DID_TRUSTEE: "sig2"});
+
+            let envelope = RequestEnvelope::new(request, None).unwrap();
+            assert_eq!(2, envelope.signatures.len());
+            envelope.validate().unwrap();
+        }
+    }
+
+    mod submit_signed {
+        use super::*;
+        use crate::{
+            commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool},
+            ledger::{
+                nym::tests::ensure_nym_added,
+                tests::{create_new_did, use_trustee},
+            },
+        };
+        use indy_utils::did::DidValue;
+
+        fn submit_signed(ctx: &CommandContext, file: &str) -> Result<(), ()> {
+            let cmd = submit_signed_command::new();
+            let mut params = CommandParams::new();
+            params.insert("file", file.to_string());
+            cmd.execute(ctx, &params)
+        }
+
+        #[test]
+        pub fn submit_signed_works() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let (did, verkey) = create_new_did(&ctx);
+
+            let mut request = {
+                let pool = ctx.get_connected_pool().unwrap();
+                Ledger::build_nym_request(
+                    Some(&pool),
+                    &DidValue(DID_TRUSTEE.to_string()),
+                    &DidValue(did.clone()),
+                    Some(&verkey),
+                    None,
+                    None,
+                )
+                .unwrap()
+            };
+            {
+                let wallet = ctx.get_opened_wallet().unwrap();
+                Ledger::sign_request(&wallet, &DidValue(DID_TRUSTEE.to_string()), &mut request)
+                    .unwrap();
+            }
+
+            let (_, path_str) = path();
+            write_file(&path_str, &request.req_json.to_string()).unwrap();
+            submit_signed(&ctx, &path_str).unwrap();
+
+            ensure_nym_added(&ctx, &did).unwrap();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn submit_signed_works_for_unsigned_request() {
+            let ctx = setup_with_wallet_and_pool();
+            let (_, path_str) = path();
+            write_file(&path_str, TRANSACTION).unwrap();
+            submit_signed(&ctx, &path_str).unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn submit_signed_works_for_missed_endorser_signature() {
+            let ctx = setup_with_wallet_and_pool();
+            let mut request = serde_json::from_str::<JsonValue>(TRANSACTION).unwrap();
+            request["signature"] = json!("sig");
+            request["endorser"] = json!(DID_MY1);
+
+            let (_, path_str) = path();
+            write_file(&path_str, &request.to_string()).unwrap();
+            submit_signed(&ctx, &path_str).unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn submit_signed_works_for_no_file() {
+            let ctx = setup_with_wallet_and_pool();
+            submit_signed(&ctx, "/tmp/not_existing_file.json").unwrap_err();
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+    }
 }
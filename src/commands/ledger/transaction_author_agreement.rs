@@ -3,15 +3,25 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{
+        wait_for_user_reply, Command, CommandContext, CommandMetadata, CommandParams, Precondition,
+    },
     params_parser::ParamParser,
-    tools::ledger::{Ledger, Response},
-    utils::{file::read_file, table::print_list_table},
+    tools::{
+        ledger::{Ledger, Response},
+        pool::Pool,
+    },
+    utils::{
+        diff::print_diff, file::read_file, markdown::render_markdown, table::print_list_table,
+        term::is_colored, time::timestamp_to_datetime,
+    },
 };
 
 use serde_json::Value as JsonValue;
 
-use super::common::{handle_transaction_response, print_transaction_response};
+use super::common::{
+    calculate_taa_digest, handle_transaction_response, print_transaction_response,
+};
 
 pub mod taa_command {
     use super::*;
@@ -40,12 +50,22 @@ pub mod taa_command {
                                     Must be omitted in case of adding a new (latest) TAA.
                                     Should be used for updating (deactivating) non-latest TAA on the ledger.
                 "#)
+                .add_optional_param("review", "Fetch the agreement of the same version (or the latest one) from the Ledger, print the changes and ask for confirmation before sending (False by default)")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger txn-author-agreement text=\"Indy transaction agreement\" version=1")
                 .add_example("ledger txn-author-agreement text= version=1")
                 .add_example("ledger txn-author-agreement file=/home/agreement_content.txt version=1")
                 .add_example("ledger txn-author-agreement text=\"Indy transaction agreement\" version=1 send=false")
+                .add_example("ledger txn-author-agreement version=1 retirement-timestamp=1700000000 review=true")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -63,6 +83,7 @@ pub mod taa_command {
             ParamParser::get_opt_number_param::<u64>("ratification-timestamp", params)?;
         let retirement_ts =
             ParamParser::get_opt_number_param::<u64>("retirement-timestamp", params)?;
+        let review = ParamParser::get_opt_bool_param("review", params)?.unwrap_or(false);
 
         let text: Option<String> = match (text, file) {
             (Some(text_), None) => Some(text_.to_string()),
@@ -74,6 +95,20 @@ pub mod taa_command {
             (None, None) => None,
         };
 
+        if review {
+            let pool = ctx.ensure_connected_pool()?;
+            let agreement = json!({
+                "text": text,
+                "version": version,
+                "ratification_ts": ratification_ts,
+                "retirement_ts": retirement_ts,
+            });
+            if !review_agreement(ctx, &pool, agreement)? {
+                println!("Transaction Author Agreement has not been sent.");
+                return Ok(());
+            }
+        }
+
         let mut request = Ledger::build_txn_author_agreement_request(
             pool.as_deref(),
             &submitter_did,
@@ -82,7 +117,7 @@ pub mod taa_command {
             ratification_ts,
             retirement_ts,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
@@ -102,6 +137,7 @@ pub mod taa_command {
                     ],
                     true,
                 );
+                println!("Digest: {}", calculate_taa_digest(&text, &version));
                 crate::commands::pool::accept_transaction_author_agreement(ctx, &text, &version);
             }
         })?;
@@ -109,6 +145,102 @@ pub mod taa_command {
         trace!("execute <<");
         Ok(())
     }
+
+    /// Prints the changes of the agreement against the same version set on the Ledger
+    /// (or the latest agreement for a new version) and asks for confirmation.
+    fn review_agreement(
+        ctx: &CommandContext,
+        pool: &Pool,
+        mut agreement: JsonValue,
+    ) -> Result<bool, ()> {
+        let version = agreement["version"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let mut current = get_agreement(pool, Some(&version))?;
+        if current.is_none() {
+            current = get_agreement(pool, None)?;
+        }
+
+        let fields = ["text", "version", "ratification_ts", "retirement_ts"];
+        let current = current.map(|current| {
+            fields
+                .iter()
+                .map(|field| (field.to_string(), current[field].clone()))
+                .collect::<serde_json::Map<String, JsonValue>>()
+        });
+
+        // fields omitted for updating an existing agreement keep their values
+        if let Some(current) = current.as_ref() {
+            if current["version"] == agreement["version"] {
+                for field in fields {
+                    if agreement[field].is_null() {
+                        agreement[field] = current[field].clone();
+                    }
+                }
+            }
+        }
+
+        let before = current
+            .as_ref()
+            .map(|current| serde_json::to_string_pretty(current).unwrap())
+            .unwrap_or_default();
+        let after = serde_json::to_string_pretty(&agreement).unwrap();
+
+        match current.as_ref().map(|current| &current["version"]) {
+            Some(current_version) if current_version == &agreement["version"] => {
+                println_acc!(
+                    "Transaction Author Agreement version \"{}\" changes:",
+                    version
+                )
+            }
+            Some(current_version) => println_acc!(
+                "Transaction Author Agreement version \"{}\" replaces version {}:",
+                version,
+                current_version
+            ),
+            None => println_warn!("There is no Transaction Author Agreement on the Ledger"),
+        }
+        print_diff(&before, &after);
+
+        if let Some(text) = agreement["text"].as_str() {
+            let digest = calculate_taa_digest(text, &version);
+            println_acc!("Preview:");
+            println!("{}", render_markdown(text, is_colored()));
+            println!("Digest: {}", digest);
+
+            let current_text = current
+                .as_ref()
+                .filter(|current| current["version"] == agreement["version"])
+                .and_then(|current| current["text"].as_str());
+            if let Some(current_text) = current_text {
+                if calculate_taa_digest(current_text, &version) != digest {
+                    println_warn!(
+                        "The text differs from the agreement of the same version set on the Ledger. An existing agreement text can not be changed."
+                    );
+                }
+            }
+        }
+
+        println!("Would you like to send Transaction Author Agreement? (y/n)");
+        Ok(wait_for_user_reply(ctx))
+    }
+
+    fn get_agreement(pool: &Pool, version: Option<&str>) -> Result<Option<JsonValue>, ()> {
+        let data = version.map(|version| json!({ "version": version }).to_string());
+        let response =
+            Ledger::build_get_txn_author_agreement_request(Some(pool), None, data.as_deref())
+                .and_then(|request| Ledger::submit_request(pool, &request))
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        let response = serde_json::from_str::<Response<JsonValue>>(&response)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+        let result = handle_transaction_response(response)?;
+
+        match result["data"] {
+            JsonValue::Object(_) => Ok(Some(result["data"].clone())),
+            _ => Ok(None),
+        }
+    }
 }
 
 pub mod taa_disable_all_command {
@@ -116,9 +248,17 @@ pub mod taa_disable_all_command {
 
     command!(CommandMetadata::build("disable-all-txn-author-agreements", r#"Disable All Transaction Author Agreements on the ledger"#)
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger disable-all-txn-author-agreements")
                 .add_example("ledger disable-all-txn-author-agreements send=false")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -133,7 +273,7 @@ pub mod taa_disable_all_command {
             pool.as_deref(),
             &submitter_did,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
@@ -157,11 +297,19 @@ pub mod aml_command {
                 .add_required_param("version", "The version of a new set of acceptance mechanisms.")
                 .add_optional_param("context", "Common context information about acceptance mechanisms (may be a URL to external resource).")
                 .add_optional_param("sign","Sign the request (True by default)")
+                .add_optional_param("idempotent", "Skip sending if an identical transaction has already been accepted by the Ledger according to the transactions journal (False by default)")
+                .add_optional_param("receipt", "Path to the file to write JSON receipt of the accepted transaction into: digest, seqNo, txnTime, root hash and signatures from the Ledger reply")
+                .add_optional_param("precheck", "Check the submitter role against the Ledger auth rules and warn if the transaction is likely to be rejected (False by default)")
+                .add_optional_param("dry_run", "Build and sign the transaction and print its size, the auth rules for adding it and the TAA acceptance state without sending it (False by default)")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger txn-acceptance-mechanisms aml={\"Click Agreement\":\"some description\"} version=1")
                 .add_example("ledger txn-acceptance-mechanisms file=/home/mechanism.txt version=1")
                 .add_example("ledger txn-acceptance-mechanisms aml={\"Click Agreement\":\"some description\"} version=1 context=\"some context\"")
                 .add_example("ledger txn-acceptance-mechanisms aml={\"Click Agreement\":\"some description\"} version=1 send=false")
+                .add_precondition(Precondition::OpenedWallet)
+                .add_precondition(Precondition::ActiveDid)
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -197,7 +345,7 @@ pub mod aml_command {
             &version,
             context,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response): (String, Response<JsonValue>) =
             send_write_request!(ctx, params, &mut request, &wallet, &submitter_did);
@@ -228,10 +376,12 @@ pub mod get_acceptance_mechanisms_command {
                 .add_optional_param("timestamp","The time (as timestamp) to get an active acceptance mechanisms. Skip to get the latest one")
                 .add_optional_param("version","The version of acceptance mechanisms")
                 .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
                 .add_example("ledger get-acceptance-mechanisms")
                 .add_example("ledger get-acceptance-mechanisms timestamp=1576674598")
                 .add_example("ledger get-acceptance-mechanisms version=1.0")
                 .add_example("ledger get-acceptance-mechanisms send=false")
+                .add_precondition(Precondition::ConnectedPool)
                 .finalize()
     );
 
@@ -250,7 +400,7 @@ pub mod get_acceptance_mechanisms_command {
             timestamp,
             version,
         )
-        .map_err(|err| println_err!("{}", err.message(None)))?;
+        .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let (_, response) = send_read_request!(&ctx, params, &request);
 
@@ -298,6 +448,97 @@ pub mod get_acceptance_mechanisms_command {
     }
 }
 
+pub mod get_taa_command {
+    use super::*;
+
+    command!(CommandMetadata::build("get-txn-author-agreement", r#"Get Transaction Author Agreement set on the ledger. Without selection params the latest active agreement is returned"#)
+                .add_optional_param("version", "The version of the agreement to get")
+                .add_optional_param("timestamp", "The time (as timestamp) to get the agreement active at")
+                .add_optional_param("digest", "The digest of the agreement to get")
+                .add_optional_param("send","Send the request to the Ledger (True by default). If false then created request will be printed and stored into CLI context.")
+                .add_optional_param("timeout", "Time to wait for the Ledger reply (in sec). Overrides `set request-timeout` value")
+                .add_example("ledger get-txn-author-agreement")
+                .add_example("ledger get-txn-author-agreement version=1.0")
+                .add_example("ledger get-txn-author-agreement timestamp=1576674598")
+                .add_example("ledger get-txn-author-agreement digest=ba4dd8c0eaf7b9e66b0ae4d4d3e4c8da2d6a8e88a2ad2bd6d8b3b7f5e5e3a9c1")
+                .add_example("ledger get-txn-author-agreement send=false")
+                .add_precondition(Precondition::ConnectedPool)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let submitter_did = ctx.get_active_did()?;
+        let pool = ctx.get_connected_pool();
+
+        let version = ParamParser::get_opt_str_param("version", params)?;
+        let timestamp = ParamParser::get_opt_number_param::<u64>("timestamp", params)?;
+        let digest = ParamParser::get_opt_str_param("digest", params)?;
+
+        let data = match (version, timestamp, digest) {
+            (None, None, None) => None,
+            (Some(version), None, None) => Some(json!({ "version": version })),
+            (None, Some(timestamp), None) => Some(json!({ "timestamp": timestamp })),
+            (None, None, Some(digest)) => Some(json!({ "digest": digest })),
+            _ => {
+                println_err!(
+                    "Only one of \"version\", \"timestamp\" and \"digest\" parameters can be specified."
+                );
+                return Err(());
+            }
+        };
+
+        let request = Ledger::build_get_txn_author_agreement_request(
+            pool.as_deref(),
+            submitter_did.as_deref(),
+            data.map(|data| data.to_string()).as_deref(),
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let (_, response) = send_read_request!(&ctx, params, &request);
+
+        let result = handle_transaction_response(response)?;
+        print_txn_author_agreement(&result["data"]);
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn print_txn_author_agreement(agreement: &JsonValue) {
+        let text = match agreement["text"].as_str() {
+            Some(text) if !text.is_empty() => text,
+            _ => {
+                println!("There is no Transaction Author Agreement matching the request");
+                return;
+            }
+        };
+
+        println_acc!("Text:");
+        println!("{}", render_markdown(text, is_colored()));
+        println!(
+            "Version: {}",
+            agreement["version"].as_str().unwrap_or_default()
+        );
+        if let Some(digest) = agreement["digest"].as_str() {
+            println!("Digest: {}", digest);
+        }
+        if let Some(ratification_ts) = agreement["ratification_ts"].as_i64() {
+            println!(
+                "Ratification time: {}",
+                timestamp_to_datetime(ratification_ts)
+            );
+        }
+        match agreement["retirement_ts"].as_i64() {
+            Some(retirement_ts) => {
+                println!("Retirement time: {}", timestamp_to_datetime(retirement_ts))
+            }
+            None => println!("Retirement time: not retired"),
+        }
+        println!();
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -344,6 +585,7 @@ pub mod tests {
 
     mod taa {
         use super::*;
+        use chrono::Utc;
 
         #[test]
         pub fn taa_works() {
@@ -375,6 +617,74 @@ pub mod tests {
                 params.insert("did", did);
                 cmd.execute(&ctx, &params).unwrap();
             }
+            {
+                let cmd = get_taa_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = get_taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("version", "1.0".to_string());
+                params.insert("timestamp", "123456789".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            {
+                // Disable all TAAs
+                let cmd = taa_disable_all_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_taa_works_for_selectors() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let text = "test taa selectors";
+            let version = super::aml::_get_version();
+            {
+                // Set AML
+                let cmd = aml_command::new();
+                let mut params = CommandParams::new();
+                params.insert("aml", super::aml::AML.to_string());
+                params.insert("version", super::aml::_get_version());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                // Set TAA
+                let cmd = taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("text", text.to_string());
+                params.insert("version", version.clone());
+                params.insert("ratification-timestamp", "123456789".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = get_taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("version", version.clone());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = get_taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("timestamp", Utc::now().timestamp().to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = get_taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("digest", calculate_taa_digest(text, &version));
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = get_taa_command::new();
+                let mut params = CommandParams::new();
+                params.insert("timestamp", "not a timestamp".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
             {
                 // Disable all TAAs
                 let cmd = taa_disable_all_command::new();
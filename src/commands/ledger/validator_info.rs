@@ -3,15 +3,17 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    pager::print_paged,
     params_parser::ParamParser,
     tools::ledger::{Ledger, Response},
+    utils::{file::write_file, table::format_list_table},
 };
 
-use serde_json::Value as JsonValue;
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::collections::BTreeMap;
 
-use super::common::{handle_transaction_response, sign_and_submit_action};
+use super::common::{handle_transaction_response, sign_and_submit_action, sign_and_submit_request};
 
 pub mod get_validator_info_command {
     use super::*;
@@ -20,10 +22,22 @@ pub mod get_validator_info_command {
         CommandMetadata::build("get-validator-info", "Get validator info from all nodes.")
             .add_optional_param("nodes", "The list of node names to send the request")
             .add_optional_param("timeout", " Time to wait respond from nodes")
-            .add_optional_param("timeout", " Time to wait respond from nodes")
+            .add_optional_param(
+                "out",
+                "Path to the file to write received validator info as JSON instead of printing it"
+            )
+            .add_optional_param(
+                "fields",
+                "Paths of validator info fields split by comma (nested fields are separated by dot) to show as a table comparing nodes"
+            )
             .add_example(r#"ledger get-validator-info"#)
             .add_example(r#"ledger get-validator-info nodes=Node1,Node2"#)
             .add_example(r#"ledger get-validator-info nodes=Node1,Node2 timeout=150"#)
+            .add_example(r#"ledger get-validator-info out=/home/validator_info.json"#)
+            .add_example(r#"ledger get-validator-info fields=Pool_info.Reachable_nodes_count,Software.indy-node"#)
+            .add_precondition(Precondition::OpenedWallet)
+            .add_precondition(Precondition::ActiveDid)
+            .add_precondition(Precondition::ConnectedPool)
             .finalize()
     );
 
@@ -36,16 +50,31 @@ pub mod get_validator_info_command {
 
         let nodes = ParamParser::get_opt_str_array_param("nodes", params)?;
         let timeout = ParamParser::get_opt_number_param::<i64>("timeout", params)?;
+        let out = ParamParser::get_opt_str_param("out", params)?;
+        let fields = ParamParser::get_opt_str_array_param("fields", params)?;
+
+        if out.is_some() && fields.is_some() {
+            println_err!("Parameters \"out\" and \"fields\" cannot be used together");
+            return Err(());
+        }
 
         let mut request = Ledger::build_get_validator_info_request(Some(&pool), &submitter_did)
-            .map_err(|err| println_err!("{}", err.message(None)))?;
+            .map_err(|err| println_err!("{}", err.report(None)))?;
 
         let response = if nodes.is_some() || timeout.is_some() {
-            sign_and_submit_action(&wallet, &pool, &submitter_did, &mut request, nodes, timeout)
-                .map_err(|err| println_err!("{}", err.message(None)))?
+            sign_and_submit_action(
+                ctx,
+                &wallet,
+                &pool,
+                &submitter_did,
+                &mut request,
+                nodes,
+                timeout,
+            )
+            .map_err(|err| println_err!("{}", err.report(None)))?
         } else {
-            Ledger::sign_and_submit_request(&pool, &wallet, &submitter_did, &mut request)
-                .map_err(|err| println_err!("{}", err.message(None)))?
+            sign_and_submit_request(ctx, &wallet, &pool, &submitter_did, &mut request)
+                .map_err(|err| println_err!("{}", err.report(None)))?
         };
 
         let responses = match serde_json::from_str::<BTreeMap<String, String>>(&response) {
@@ -53,42 +82,128 @@ pub mod get_validator_info_command {
             Err(_) => {
                 let response = serde_json::from_str::<Response<JsonValue>>(&response)
                     .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
-                return handle_transaction_response(response)
-                    .map(|result| println_succ!("{}", result));
+                let result = handle_transaction_response(response)?;
+                return match (out, fields) {
+                    (Some(out), _) => save_validator_info(out, &result),
+                    (None, Some(fields)) => {
+                        let node = result["data"]["Node_info"]["Name"]
+                            .as_str()
+                            .unwrap_or("-")
+                            .to_string();
+                        let mut infos = JsonMap::new();
+                        infos.insert(node, result);
+                        print_validator_info_fields(ctx, &infos, &fields);
+                        Ok(())
+                    }
+                    (None, None) => {
+                        println_succ!("{}", result);
+                        Ok(())
+                    }
+                };
             }
         };
 
-        println_succ!("Validator Info:");
-
-        let mut lines: Vec<String> = Vec::new();
+        let mut infos = JsonMap::new();
 
         for (node, response) in responses {
             if response.eq("timeout") {
-                lines.push(format!("\t{:?}: {:?}", node, "Timeout"));
+                infos.insert(node, JsonValue::from("Timeout"));
                 continue;
             }
             let response = match serde_json::from_str::<Response<JsonValue>>(&response) {
                 Ok(resp) => resp,
                 Err(err) => {
-                    lines.push(format!(
-                        "\t{:?}: \"Invalid data has been received: {:?}\"",
-                        node, err
-                    ));
+                    infos.insert(
+                        node,
+                        JsonValue::from(format!("Invalid data has been received: {:?}", err)),
+                    );
                     continue;
                 }
             };
 
             match handle_transaction_response(response) {
-                Ok(result) => lines.push(format!("\t{:?}: {}", node, result)),
+                Ok(result) => {
+                    infos.insert(node, result);
+                }
                 Err(_) => {}
             };
         }
 
-        println!("{{\n{}\n}}", lines.join(",\n"));
+        if let Some(out) = out {
+            return save_validator_info(out, &JsonValue::Object(infos));
+        }
+
+        if let Some(fields) = fields {
+            print_validator_info_fields(ctx, &infos, &fields);
+            trace!("execute <<");
+            return Ok(());
+        }
+
+        println_succ!("Validator Info:");
+
+        let lines = infos
+            .iter()
+            .map(|(node, info)| format!("\t{:?}: {}", node, info))
+            .collect::<Vec<String>>();
+
+        print_paged(ctx, &format!("{{\n{}\n}}", lines.join(",\n")));
 
         trace!("execute <<");
         Ok(())
     }
+
+    /// Prints the table with a row per node and a column per requested field.
+    fn print_validator_info_fields(
+        ctx: &CommandContext,
+        infos: &JsonMap<String, JsonValue>,
+        fields: &[&str],
+    ) {
+        let rows = infos
+            .iter()
+            .map(|(node, info)| {
+                let mut row = JsonMap::new();
+                row.insert("node".to_string(), JsonValue::from(node.as_str()));
+                for field in fields {
+                    row.insert(field.to_string(), get_validator_info_field(info, field));
+                }
+                JsonValue::Object(row)
+            })
+            .collect::<Vec<JsonValue>>();
+
+        let mut headers = vec![("node", "Node")];
+        headers.extend(fields.iter().map(|field| (*field, *field)));
+
+        println_succ!("Validator Info:");
+        print_paged(ctx, &format_list_table(&rows, &headers));
+    }
+
+    fn save_validator_info(out: &str, info: &JsonValue) -> Result<(), ()> {
+        write_file(out, &serde_json::to_string_pretty(info).unwrap())
+            .map_err(|err| println_err!("{}", err))?;
+        println_succ!("Validator Info has been saved into \"{}\"", out);
+        Ok(())
+    }
+}
+
+/// Extracts the field set by dot-separated path from node reply.
+/// The path is resolved against `data` of the reply, nodes which failed to reply keep the error message.
+fn get_validator_info_field(info: &JsonValue, path: &str) -> JsonValue {
+    if info.is_string() {
+        return info.clone();
+    }
+    let data = if info["data"].is_object() {
+        &info["data"]
+    } else {
+        info
+    };
+    let value = path.split('.').try_fold(data, |value, key| {
+        value.as_object().and_then(|value| value.get(key))
+    });
+    match value {
+        Some(JsonValue::String(value)) => JsonValue::from(value.as_str()),
+        Some(JsonValue::Null) | None => JsonValue::Null,
+        Some(value) => JsonValue::from(value.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +212,7 @@ pub mod tests {
     use crate::{
         commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool},
         ledger::tests::use_trustee,
+        utils::{environment::EnvironmentUtils, file::read_file},
     };
 
     mod get_validator_info {
@@ -153,5 +269,66 @@ pub mod tests {
             }
             tear_down_with_wallet_and_pool(&ctx);
         }
+
+        #[test]
+        pub fn get_validator_info_works_for_out() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            let path = EnvironmentUtils::tmp_file_path("validator_info.json");
+            {
+                let cmd = get_validator_info_command::new();
+                let mut params = CommandParams::new();
+                params.insert("nodes", "Node1,Node2".to_string());
+                params.insert("out", path.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let info = read_file(&path).unwrap();
+            let info = serde_json::from_str::<JsonValue>(&info).unwrap();
+            assert!(info["Node1"].is_object());
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_validator_info_works_for_fields() {
+            let ctx = setup_with_wallet_and_pool();
+            use_trustee(&ctx);
+            {
+                let cmd = get_validator_info_command::new();
+                let mut params = CommandParams::new();
+                params.insert("nodes", "Node1,Node2".to_string());
+                params.insert(
+                    "fields",
+                    "Pool_info.Reachable_nodes_count,Software.indy-node".to_string(),
+                );
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn get_validator_info_field_works() {
+            let info = json!({
+                "data": {
+                    "Pool_info": {"Reachable_nodes_count": 4},
+                    "Software": {"indy-node": "1.12.6"}
+                }
+            });
+            assert_eq!(
+                json!("4"),
+                get_validator_info_field(&info, "Pool_info.Reachable_nodes_count")
+            );
+            assert_eq!(
+                json!("1.12.6"),
+                get_validator_info_field(&info, "Software.indy-node")
+            );
+            assert_eq!(
+                JsonValue::Null,
+                get_validator_info_field(&info, "Software.unknown")
+            );
+            assert_eq!(
+                json!("Timeout"),
+                get_validator_info_field(&json!("Timeout"), "Software.indy-node")
+            );
+        }
     }
 }
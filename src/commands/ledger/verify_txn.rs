@@ -0,0 +1,497 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::ledger::{
+        proof::{
+            audit_path_root, decode_audit_path, decode_hash, multi_signature_quorum, txn_leaf_hash,
+            verify_multi_signature,
+        },
+        Ledger, Response,
+    },
+    utils::{file::read_file, table::print_list_table},
+};
+
+use super::{common::handle_transaction_response, constants::DOMAIN_LEDGER_TYPE};
+use crate::error::{CliError, CliResult};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+
+pub mod verify_txn_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "verify-txn",
+        "Re-read a transaction from the ledger and verify its merkle audit path, \
+            the root hash and the BLS multi-signature of the nodes against the pool node keys. \
+            Prints PASS/FAIL for every check."
+    )
+    .add_optional_param("seq_no", "Sequence number of the transaction (taken from the receipt if omitted)")
+    .add_optional_param(
+        "receipt",
+        "Path to the JSON receipt written by a write command with `receipt` parameter. Its root hash and audit path are verified too"
+    )
+    .add_optional_param("ledger", "Ledger of the transaction: POOL, DOMAIN (used by default), CONFIG or a ledger id")
+    .add_example("ledger verify-txn seq_no=7")
+    .add_example("ledger verify-txn seq_no=2 ledger=POOL")
+    .add_example("ledger verify-txn receipt=/home/receipts/nym.json")
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+
+        let receipt = match ParamParser::get_opt_str_param("receipt", params)? {
+            Some(path) => {
+                Some(read_receipt(path).map_err(|err| println_err!("{}", err.report(None)))?)
+            }
+            None => None,
+        };
+        let seq_no = match ParamParser::get_opt_number_param::<u64>("seq_no", params)? {
+            Some(seq_no) => seq_no,
+            None => match receipt
+                .as_ref()
+                .and_then(|receipt| receipt["seqNo"].as_u64())
+            {
+                Some(seq_no) => seq_no,
+                None => {
+                    println_err!("Either `seq_no` or `receipt` containing seqNo must be specified");
+                    return Err(());
+                }
+            },
+        };
+        let ledger_type = match ParamParser::get_opt_str_param("ledger", params)? {
+            Some(ledger) => parse_ledger_type(ledger)?,
+            None => DOMAIN_LEDGER_TYPE,
+        };
+
+        let request = Ledger::build_get_txn_request(
+            Some(&pool),
+            None,
+            ledger_type,
+            i32::try_from(seq_no).map_err(|_| println_err!("Invalid seq_no: {}", seq_no))?,
+        )
+        .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let response_json =
+            Ledger::submit_request_with_retries(&pool, &request, ctx.get_request_retries())
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        let response = serde_json::from_str::<Response<JsonValue>>(&response_json)
+            .map_err(|err| println_err!("Invalid data has been received: {:?}", err))?;
+        let result = handle_transaction_response(response)?;
+
+        if result["data"].is_null() {
+            println_err!(
+                "Transaction with seqNo {} does not exist in the ledger {}",
+                seq_no,
+                ledger_type
+            );
+            return Err(());
+        }
+
+        let nodes = pool
+            .nodes()
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?
+            .into_iter()
+            .filter(|node| node.is_validator())
+            .filter_map(|node| {
+                let alias = node.alias()?.to_string();
+                let blskey = node.data["blskey"].as_str().map(String::from);
+                Some((alias, blskey))
+            })
+            .collect::<Vec<(String, Option<String>)>>();
+
+        let checks = verify_txn_reply(&result, seq_no, ledger_type, &nodes, receipt.as_ref());
+
+        let rows: Vec<JsonValue> = checks
+            .iter()
+            .map(|check| {
+                json!({
+                    "check": check.name,
+                    "result": if check.passed { "PASS" } else { "FAIL" },
+                    "details": check.details,
+                })
+            })
+            .collect();
+        print_list_table(
+            &rows,
+            &[
+                ("check", "Check"),
+                ("result", "Result"),
+                ("details", "Details"),
+            ],
+            "",
+        );
+
+        let res = if checks.iter().all(|check| check.passed) {
+            println_succ!("Transaction {} has been verified", seq_no);
+            Ok(())
+        } else {
+            println_err!("Transaction {} has not passed verification", seq_no);
+            Err(())
+        };
+
+        trace!("execute <<");
+        res
+    }
+}
+
+/// Result of a single verification step of `ledger verify-txn`.
+#[derive(Debug)]
+pub struct ProofCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub details: String,
+}
+
+impl ProofCheck {
+    fn new(name: &'static str, result: CliResult<String>) -> ProofCheck {
+        match result {
+            Ok(details) => ProofCheck {
+                name,
+                passed: true,
+                details,
+            },
+            Err(err) => ProofCheck {
+                name,
+                passed: false,
+                details: err.message(None),
+            },
+        }
+    }
+}
+
+fn parse_ledger_type(ledger: &str) -> Result<i32, ()> {
+    match ledger.to_uppercase().as_str() {
+        "POOL" => Ok(0),
+        "DOMAIN" => Ok(DOMAIN_LEDGER_TYPE),
+        "CONFIG" => Ok(2),
+        ledger => ledger.parse::<i32>().map_err(|_| {
+            println_err!(
+                "Invalid ledger \"{}\". One of: POOL, DOMAIN, CONFIG or a ledger id.",
+                ledger
+            )
+        }),
+    }
+}
+
+fn read_receipt(path: &str) -> CliResult<JsonValue> {
+    let content = read_file(path).map_err(CliError::InvalidInput)?;
+    serde_json::from_str(&content).map_err(|err| {
+        CliError::InvalidInput(format!("Invalid receipt file \"{}\": {}", path, err))
+    })
+}
+
+/// Verifies GET_TXN reply: the audit path of the transaction leads to the returned root hash,
+/// the root hash is the one signed by the nodes, and the multi-signature is valid for
+/// enough validator nodes. `nodes` are validator aliases with their BLS keys.
+pub fn verify_txn_reply(
+    result: &JsonValue,
+    seq_no: u64,
+    ledger_type: i32,
+    nodes: &[(String, Option<String>)],
+    receipt: Option<&JsonValue>,
+) -> Vec<ProofCheck> {
+    let data = &result["data"];
+    let root_hash = data["rootHash"].as_str().unwrap_or_default();
+    let multi_signature = &result["state_proof"]["multi_signature"];
+
+    let mut checks = vec![ProofCheck::new(
+        "Audit path",
+        check_audit_path(
+            data,
+            seq_no,
+            data["ledgerSize"].as_u64().unwrap_or(seq_no),
+            &data["auditPath"],
+            root_hash,
+        ),
+    )];
+
+    checks.push(ProofCheck::new(
+        "Signed root hash",
+        check_signed_root(multi_signature, root_hash, ledger_type),
+    ));
+
+    let participants = check_participants(multi_signature, nodes);
+    let blskeys = participants.as_ref().map(|(_, blskeys)| blskeys.clone());
+    checks.push(ProofCheck::new(
+        "Signing nodes",
+        participants.map(|(details, _)| details),
+    ));
+
+    checks.push(ProofCheck::new(
+        "Multi-signature",
+        blskeys.and_then(|blskeys| check_multi_signature(multi_signature, &blskeys)),
+    ));
+
+    if let Some(receipt) = receipt {
+        checks.push(ProofCheck::new(
+            "Receipt",
+            check_receipt(data, seq_no, receipt),
+        ));
+    }
+
+    checks
+}
+
+fn check_audit_path(
+    txn: &JsonValue,
+    seq_no: u64,
+    tree_size: u64,
+    audit_path: &JsonValue,
+    root_hash: &str,
+) -> CliResult<String> {
+    let leaf_hash = txn_leaf_hash(txn)?;
+    let audit_path = decode_audit_path(audit_path)?;
+    let expected = decode_hash(root_hash)?;
+    let index = seq_no
+        .checked_sub(1)
+        .ok_or_else(|| CliError::InvalidInput("Sequence numbers start from 1".to_string()))?;
+
+    match audit_path_root(&leaf_hash, index, tree_size, &audit_path) {
+        Some(root) if root == expected => Ok(format!(
+            "Transaction is included into the tree of {} transactions with root hash {}",
+            tree_size, root_hash
+        )),
+        _ => Err(CliError::InvalidEntityState(format!(
+            "Audit path does not lead to root hash {}",
+            root_hash
+        ))),
+    }
+}
+
+fn check_signed_root(
+    multi_signature: &JsonValue,
+    root_hash: &str,
+    ledger_type: i32,
+) -> CliResult<String> {
+    let value = signed_value(multi_signature)?;
+    if value["ledger_id"].as_i64() != Some(ledger_type as i64) {
+        return Err(CliError::InvalidEntityState(format!(
+            "Multi-signature is made for ledger {} instead of {}",
+            value["ledger_id"], ledger_type
+        )));
+    }
+    match value["txn_root_hash"].as_str() {
+        Some(signed_root) if signed_root == root_hash => Ok(format!(
+            "Root hash has been signed by the nodes at {}",
+            value["timestamp"]
+        )),
+        signed_root => Err(CliError::InvalidEntityState(format!(
+            "Signed root hash {} differs from the returned one {}",
+            signed_root.unwrap_or("-"),
+            root_hash
+        ))),
+    }
+}
+
+fn check_participants(
+    multi_signature: &JsonValue,
+    nodes: &[(String, Option<String>)],
+) -> CliResult<(String, Vec<String>)> {
+    signed_value(multi_signature)?;
+    let participants: Vec<&str> = multi_signature["participants"]
+        .as_array()
+        .map(|participants| participants.iter().filter_map(JsonValue::as_str).collect())
+        .unwrap_or_else(Vec::new);
+
+    // every node must be counted once, otherwise a single node could make up the quorum
+    if participants.iter().collect::<HashSet<_>>().len() != participants.len() {
+        return Err(CliError::InvalidEntityState(format!(
+            "Multi-signature participants contain duplicates: {}",
+            participants.join(", ")
+        )));
+    }
+
+    let mut blskeys = Vec::new();
+    for participant in participants.iter() {
+        match nodes.iter().find(|(alias, _)| alias == participant) {
+            Some((_, Some(blskey))) => blskeys.push(blskey.to_string()),
+            Some((_, None)) => {
+                return Err(CliError::InvalidEntityState(format!(
+                    "Node \"{}\" has no BLS key in the pool ledger",
+                    participant
+                )))
+            }
+            None => {
+                return Err(CliError::InvalidEntityState(format!(
+                    "\"{}\" is not a validator node of the pool",
+                    participant
+                )))
+            }
+        }
+    }
+
+    let quorum = multi_signature_quorum(nodes.len());
+    if blskeys.len() < quorum {
+        return Err(CliError::InvalidEntityState(format!(
+            "Only {} of {} validator nodes have signed, {} required",
+            blskeys.len(),
+            nodes.len(),
+            quorum
+        )));
+    }
+    Ok((
+        format!(
+            "{} of {} validator nodes ({} required): {}",
+            blskeys.len(),
+            nodes.len(),
+            quorum,
+            participants.join(", ")
+        ),
+        blskeys,
+    ))
+}
+
+fn check_multi_signature(multi_signature: &JsonValue, blskeys: &[String]) -> CliResult<String> {
+    let value = signed_value(multi_signature)?;
+    let signature = multi_signature["signature"].as_str().unwrap_or_default();
+    let blskeys: Vec<&str> = blskeys.iter().map(String::as_str).collect();
+    match verify_multi_signature(signature, value, &blskeys)? {
+        true => Ok("BLS multi-signature matches the pool node keys".to_string()),
+        false => Err(CliError::InvalidEntityState(
+            "BLS multi-signature does not match the pool node keys".to_string(),
+        )),
+    }
+}
+
+fn check_receipt(txn: &JsonValue, seq_no: u64, receipt: &JsonValue) -> CliResult<String> {
+    for field in ["txnId", "txnTime"] {
+        if !receipt[field].is_null() && receipt[field] != txn["txnMetadata"][field] {
+            return Err(CliError::InvalidEntityState(format!(
+                "Receipt {} {} differs from the ledger one {}",
+                field, receipt[field], txn["txnMetadata"][field]
+            )));
+        }
+    }
+    // the write reply proves the transaction against the tree it was the last leaf of
+    let root_hash = receipt["rootHash"].as_str().unwrap_or_default();
+    check_audit_path(txn, seq_no, seq_no, &receipt["auditPath"], root_hash)
+}
+
+fn signed_value(multi_signature: &JsonValue) -> CliResult<&JsonValue> {
+    match &multi_signature["value"] {
+        JsonValue::Object(_) => Ok(&multi_signature["value"]),
+        _ => Err(CliError::NotFound(
+            "The reply contains no multi-signature of the nodes".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+    use indy_utils::base58;
+
+    const SEQ_NO: u64 = 1;
+
+    fn reply() -> JsonValue {
+        let txn = json!({
+            "txn": {"type": "1", "data": {"dest": "V4SGRU86Z58d6TV7PBUe6f"}},
+            "txnMetadata": {"seqNo": SEQ_NO, "txnTime": 1600000000},
+            "ver": "1",
+        });
+        let mut data = txn.clone();
+        data["auditPath"] = json!([]);
+        data["ledgerSize"] = json!(1);
+        data["rootHash"] = json!(base58::encode(txn_leaf_hash(&txn).unwrap()));
+        json!({ "data": data })
+    }
+
+    mod verify_txn {
+        use super::*;
+
+        #[test]
+        pub fn verify_txn_reply_works_for_audit_path() {
+            let reply = reply();
+            let receipt = json!({
+                "seqNo": SEQ_NO,
+                "txnTime": 1600000000,
+                "rootHash": reply["data"]["rootHash"],
+                "auditPath": [],
+            });
+            let checks = verify_txn_reply(&reply, SEQ_NO, DOMAIN_LEDGER_TYPE, &[], Some(&receipt));
+            assert_eq!(5, checks.len());
+            assert!(checks[0].passed);
+            assert!(checks[4].passed);
+        }
+
+        #[test]
+        pub fn verify_txn_reply_fails_for_modified_transaction() {
+            let mut reply = reply();
+            reply["data"]["txn"]["data"]["dest"] = json!("VsKV7grR1BUE29mG2Fm2kX");
+            let receipt = json!({"seqNo": SEQ_NO, "txnTime": 1600000001});
+            let checks = verify_txn_reply(&reply, SEQ_NO, DOMAIN_LEDGER_TYPE, &[], Some(&receipt));
+            assert!(!checks[0].passed);
+            assert!(!checks[4].passed);
+        }
+
+        #[test]
+        pub fn verify_txn_reply_fails_for_missed_multi_signature() {
+            let checks = verify_txn_reply(&reply(), SEQ_NO, DOMAIN_LEDGER_TYPE, &[], None);
+            assert_eq!(4, checks.len());
+            assert!(checks[1..].iter().all(|check| !check.passed));
+        }
+
+        #[test]
+        pub fn verify_txn_reply_fails_for_unknown_participants() {
+            let mut reply = reply();
+            reply["state_proof"] = json!({
+                "multi_signature": {
+                    "participants": ["Node1", "Node5"],
+                    "signature": "",
+                    "value": {
+                        "ledger_id": DOMAIN_LEDGER_TYPE,
+                        "txn_root_hash": reply["data"]["rootHash"],
+                        "timestamp": 1600000000,
+                    }
+                }
+            });
+            let nodes = vec![
+                ("Node1".to_string(), Some("blskey1".to_string())),
+                ("Node2".to_string(), Some("blskey2".to_string())),
+            ];
+            let checks = verify_txn_reply(&reply, SEQ_NO, DOMAIN_LEDGER_TYPE, &nodes, None);
+            assert!(checks[1].passed);
+            assert!(!checks[2].passed);
+            assert!(!checks[3].passed);
+        }
+
+        #[test]
+        pub fn check_participants_fails_for_duplicated_participant() {
+            let multi_signature = json!({
+                "participants": ["Node1", "Node1", "Node1"],
+                "signature": "",
+                "value": {"ledger_id": DOMAIN_LEDGER_TYPE},
+            });
+            let nodes = vec![
+                ("Node1".to_string(), Some("blskey1".to_string())),
+                ("Node2".to_string(), Some("blskey2".to_string())),
+                ("Node3".to_string(), Some("blskey3".to_string())),
+                ("Node4".to_string(), Some("blskey4".to_string())),
+            ];
+            match check_participants(&multi_signature, &nodes) {
+                Err(CliError::InvalidEntityState(_)) => {}
+                _ => panic!("duplicated participants must be rejected"),
+            }
+        }
+
+        #[test]
+        pub fn verify_txn_works_for_no_connected_pool() {
+            let ctx = setup();
+            {
+                let cmd = verify_txn_command::new();
+                let mut params = CommandParams::new();
+                params.insert("seq_no", "1".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
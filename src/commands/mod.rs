@@ -3,14 +3,21 @@
     https://digital.gov.bc.ca/digital-trust
 */
 pub mod common;
+pub mod config;
 pub mod did;
+pub mod key;
 pub mod ledger;
+pub mod network;
 pub mod pool;
+pub mod session;
 pub mod wallet;
 
 use crate::command_executor::CommandContext;
 
-use self::pool::constants::DEFAULT_POOL_PROTOCOL_VERSION;
+use self::{
+    pool::constants::{DEFAULT_POOL_PROTOCOL_VERSION, DEFAULT_REQUEST_RETRIES},
+    wallet::DEFAULT_AUTO_BACKUP_KEEP,
+};
 
 use indy_utils::did::DidValue;
 use std::rc::Rc;
@@ -51,7 +58,11 @@ impl CommandContext {
     }
 
     pub fn set_opened_wallet(&self, wallet: Wallet) {
-        self.set_sub_prompt(2, Some(wallet.name.clone()));
+        let sub_prompt = match wallet.profile {
+            Some(ref profile) => format!("{}/{}", wallet.name, profile),
+            None => wallet.name.clone(),
+        };
+        self.set_sub_prompt(2, Some(sub_prompt));
         self.set_wallet(Some(wallet));
     }
 
@@ -85,7 +96,28 @@ impl CommandContext {
         self.set_sub_prompt(2, None);
     }
 
+    /// Connected pools are identified by their network (see `Pool::genesis_node`):
+    /// connecting to another network drops the context values bound to the previous one.
     pub fn set_connected_pool(&self, pool: Pool) {
+        let network = pool
+            .genesis_node()
+            .unwrap_or_else(|_| pool.name.to_string());
+        if self.get_string_value("NETWORK_ID").as_deref() != Some(network.as_str()) {
+            self.reset_pool_scoped_values();
+            let transaction_network = self.get_string_value("LEDGER_TRANSACTION_NETWORK");
+            if transaction_network.is_some() && transaction_network.as_deref() != Some(&network) {
+                self.set_context_transaction(None);
+                println_warn!(
+                    "Transaction stored into CLI context targets another network and has been removed."
+                );
+            }
+            self.set_string_value("NETWORK_ID", Some(network));
+        }
+        if let Some((pool_name, _)) = self.get_pool_auto_refresh() {
+            if pool_name != pool.name {
+                self.stop_pool_auto_refresh();
+            }
+        }
         self.set_sub_prompt(1, Some(format!("pool({})", pool.name)));
         self.set_pool(Some(pool));
     }
@@ -104,13 +136,81 @@ impl CommandContext {
         self.get_pool()
     }
 
+    /// The stored transaction is kept on disconnect and removed only on connecting to another network.
     pub fn reset_connected_pool(&self) {
+        self.stop_pool_auto_refresh();
+        self.reset_pool_scoped_values();
         self.set_sub_prompt(1, None);
         self.set_pool(None);
     }
 
+    pub fn stop_pool_auto_refresh(&self) {
+        if let Some((pool_name, _)) = self.get_pool_auto_refresh() {
+            self.set_pool_auto_refresh(None);
+            println_warn!("Auto-refresh of pool \"{}\" has been stopped.", pool_name);
+        }
+    }
+
+    /// Swaps the connected pool with the one extended by the transactions received by `pool auto-refresh`.
+    /// Called before every command, so the pool is never replaced while a command is using it.
+    pub fn apply_pool_auto_refresh(&self) {
+        let (update, pool) = match (self.take_pool_auto_refresh_update(), self.get_pool()) {
+            (Some(update), Some(pool)) => (update, pool),
+            _ => return,
+        };
+        let update = match update {
+            Ok(update) => update,
+            Err(err) => {
+                println_warn!("Auto-refresh of pool \"{}\" has failed: {}", pool.name, err);
+                return;
+            }
+        };
+
+        let transactions = update.missing_transactions(pool.txn_count());
+        if transactions.is_empty() {
+            return;
+        }
+        match pool.with_new_transactions(transactions) {
+            Ok(refreshed) => {
+                println_succ!(
+                    "Pool \"{}\" has been refreshed in background: {} new transactions received",
+                    pool.name,
+                    transactions.len()
+                );
+                self.set_pool(Some(refreshed));
+            }
+            Err(err) => println_warn!(
+                "Auto-refresh of pool \"{}\" has not been applied: {}",
+                pool.name,
+                err.message(Some(&pool.name))
+            ),
+        }
+    }
+
+    fn reset_pool_scoped_values(&self) {
+        if self.get_transaction_author_info().is_some() {
+            self.set_transaction_author_info(None);
+            println_warn!("Transaction Author Agreement acceptance has been reset.");
+        }
+        if !self.get_schema_names().is_empty() {
+            self.clear_schema_names();
+            println_warn!("Schema names cached for completion have been cleared.");
+        }
+    }
+
+    /// Returns the identifier of the network the connected pool (or the last connected one) belongs to.
+    pub fn get_network_id(&self) -> Option<String> {
+        self.get_string_value("NETWORK_ID")
+    }
+
+    /// Transaction is bound to the network of the connected pool if there is one.
     pub fn set_context_transaction(&self, request: Option<String>) {
-        self.set_string_value("LEDGER_TRANSACTION", request.clone());
+        let network = match (&request, self.get_pool()) {
+            (Some(_), Some(_)) => self.get_network_id(),
+            _ => None,
+        };
+        self.set_string_value("LEDGER_TRANSACTION_NETWORK", network);
+        self.set_string_value("LEDGER_TRANSACTION", request);
     }
 
     pub fn get_context_transaction(&self) -> Option<String> {
@@ -159,6 +259,24 @@ impl CommandContext {
 
     pub fn set_pool_protocol_version(&self, protocol_version: usize) {
         self.set_uint_value("POOL_PROTOCOL_VERSION", Some(protocol_version as u64));
+        self.set_uint_value("POOL_PROTOCOL_VERSION_DETECTED", None);
+    }
+
+    /// Sets the protocol version detected from the pool transactions on connect.
+    /// Unlike the version set by user it is replaced on the next connect.
+    pub fn set_detected_pool_protocol_version(&self, protocol_version: usize) {
+        self.set_uint_value("POOL_PROTOCOL_VERSION", Some(protocol_version as u64));
+        self.set_uint_value("POOL_PROTOCOL_VERSION_DETECTED", Some(1));
+    }
+
+    /// Returns the protocol version set by `pool set-protocol-version` command.
+    pub fn get_user_pool_protocol_version(&self) -> Option<usize> {
+        match self.get_uint_value("POOL_PROTOCOL_VERSION_DETECTED") {
+            Some(_) => None,
+            None => self
+                .get_uint_value("POOL_PROTOCOL_VERSION")
+                .map(|protocol_version| protocol_version as usize),
+        }
     }
 
     pub fn get_pool_protocol_version(&self) -> usize {
@@ -167,6 +285,108 @@ impl CommandContext {
             None => DEFAULT_POOL_PROTOCOL_VERSION,
         }
     }
+
+    pub fn set_request_timeout(&self, timeout: Option<u64>) {
+        self.set_uint_value("REQUEST_TIMEOUT", timeout);
+    }
+
+    pub fn get_request_timeout(&self) -> Option<u64> {
+        self.get_uint_value("REQUEST_TIMEOUT")
+    }
+
+    pub fn set_request_retries(&self, retries: u64) {
+        self.set_uint_value("REQUEST_RETRIES", Some(retries));
+    }
+
+    pub fn get_request_retries(&self) -> u64 {
+        self.get_uint_value("REQUEST_RETRIES")
+            .unwrap_or(DEFAULT_REQUEST_RETRIES)
+    }
+
+    /// Pool ack timeout (in sec) used by `pool connect` when `timeout` is not specified.
+    pub fn set_pool_ack_timeout(&self, timeout: Option<u64>) {
+        self.set_uint_value("POOL_ACK_TIMEOUT", timeout);
+    }
+
+    pub fn get_pool_ack_timeout(&self) -> Option<u64> {
+        self.get_uint_value("POOL_ACK_TIMEOUT")
+    }
+
+    /// Limit of requests sent over one pool connection used by `pool connect` when `conn-request-limit` is not specified.
+    pub fn set_pool_conn_request_limit(&self, limit: Option<u64>) {
+        self.set_uint_value("POOL_CONN_REQUEST_LIMIT", limit);
+    }
+
+    pub fn get_pool_conn_request_limit(&self) -> Option<u64> {
+        self.get_uint_value("POOL_CONN_REQUEST_LIMIT")
+    }
+
+    /// Number of nodes to send read requests to used by `pool connect` when `number-read-nodes` is not specified.
+    pub fn set_pool_read_nodes(&self, nodes: Option<u64>) {
+        self.set_uint_value("POOL_READ_NODES", nodes);
+    }
+
+    pub fn get_pool_read_nodes(&self) -> Option<u64> {
+        self.get_uint_value("POOL_READ_NODES")
+    }
+
+    /// Key derivation method used by wallet commands when `key_derivation_method` is not specified.
+    pub fn set_key_derivation_method(&self, method: Option<String>) {
+        self.set_string_value("KEY_DERIVATION_METHOD", method);
+    }
+
+    pub fn get_key_derivation_method(&self) -> Option<String> {
+        self.get_string_value("KEY_DERIVATION_METHOD")
+    }
+
+    pub fn set_history_size(&self, size: usize) {
+        self.set_uint_value("HISTORY_SIZE", Some(size as u64));
+    }
+
+    pub fn get_history_size(&self) -> Option<usize> {
+        self.get_uint_value("HISTORY_SIZE")
+            .map(|size| size as usize)
+    }
+
+    pub fn set_default_pool(&self, pool: Option<String>) {
+        self.set_string_value("DEFAULT_POOL", pool);
+    }
+
+    pub fn get_default_pool(&self) -> Option<String> {
+        self.get_string_value("DEFAULT_POOL")
+    }
+
+    /// Directory to export wallets into before they are deleted or rekeyed.
+    pub fn set_auto_backup_dir(&self, dir: Option<String>) {
+        self.set_string_value("AUTO_BACKUP_DIR", dir);
+    }
+
+    pub fn get_auto_backup_dir(&self) -> Option<String> {
+        self.get_string_value("AUTO_BACKUP_DIR")
+    }
+
+    pub fn set_auto_backup_keep(&self, count: u64) {
+        self.set_uint_value("AUTO_BACKUP_KEEP", Some(count));
+    }
+
+    /// How many automatic backups of every wallet are kept.
+    pub fn get_auto_backup_keep(&self) -> usize {
+        self.get_uint_value("AUTO_BACKUP_KEEP")
+            .unwrap_or(DEFAULT_AUTO_BACKUP_KEEP) as usize
+    }
+
+    /// Remembers the configuration files applied on startup to show them by `config show`.
+    pub fn add_config_file(&self, file: &str) {
+        let files = match self.get_string_value("CONFIG_FILES") {
+            Some(files) => format!("{}, {}", files, file),
+            None => file.to_string(),
+        };
+        self.set_string_value("CONFIG_FILES", Some(files));
+    }
+
+    pub fn get_config_files(&self) -> Option<String> {
+        self.get_string_value("CONFIG_FILES")
+    }
 }
 
 #[cfg(test)]
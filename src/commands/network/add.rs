@@ -0,0 +1,115 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::network::NetworkConfig,
+};
+
+pub mod add_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "add",
+        "Add network profile combining the pool to connect, TAA acceptance mechanism and the DID to use."
+    )
+    .add_main_param("name", "The name of the network")
+    .add_required_param("pool", "The name of the pool created by `pool create` command")
+    .add_optional_param(
+        "taa_mechanism",
+        "Transaction author agreement acceptance mechanism to use for the network"
+    )
+    .add_optional_param(
+        "default_did",
+        "DID to set as active once the network is used (must be present in the opened wallet)"
+    )
+    .add_optional_param("replace", "Replace the existing profile with the same name (False by default)")
+    .add_example("network add sovrin-staging pool=staging")
+    .add_example("network add sovrin-staging pool=staging taa_mechanism=for_session default_did=VsKV7grR1BUE29mG2Fm2kX")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let name = ParamParser::get_str_param("name", params)?;
+        let pool = ParamParser::get_str_param("pool", params)?;
+        let taa_mechanism = ParamParser::get_opt_str_param("taa_mechanism", params)?;
+        let default_did = ParamParser::get_opt_did_param("default_did", params)?;
+        let replace = ParamParser::get_opt_bool_param("replace", params)?.unwrap_or(false);
+
+        let config = NetworkConfig {
+            name: name.to_string(),
+            pool: pool.to_string(),
+            taa_mechanism: taa_mechanism.map(String::from),
+            default_did: default_did.map(|did| did.to_string()),
+        };
+
+        if config.exists() && !replace {
+            println_err!(
+                "Network \"{}\" already exists. Use `replace=true` to replace it.",
+                name
+            );
+            return Err(());
+        }
+
+        config
+            .store()
+            .map_err(|err| println_err!("{}", err.report(Some(name))))?;
+
+        println_succ!("Network \"{}\" has been added", name);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        network::tests::{add_network, NETWORK},
+        pool::tests::POOL,
+    };
+
+    mod add {
+        use super::*;
+
+        #[test]
+        pub fn add_works() {
+            let ctx = setup();
+            add_network(&ctx, None);
+
+            let config = NetworkConfig::read(NETWORK).unwrap();
+            assert_eq!(POOL, config.pool);
+            assert_eq!(Some("for_session".to_string()), config.taa_mechanism);
+            assert_eq!(None, config.default_did);
+            tear_down();
+        }
+
+        #[test]
+        pub fn add_works_for_twice() {
+            let ctx = setup();
+            add_network(&ctx, None);
+            {
+                let cmd = add_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                params.insert("pool", POOL.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            {
+                let cmd = add_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                params.insert("pool", "other".to_string());
+                params.insert("replace", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!("other", NetworkConfig::read(NETWORK).unwrap().pool);
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,75 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::network::NetworkConfig,
+};
+
+pub mod delete_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "delete",
+        "Delete network profile. The pool and the wallet are kept."
+    )
+    .add_main_param("name", "The name of the network")
+    .add_example("network delete sovrin-staging")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let name = ParamParser::get_str_param("name", params)?;
+
+        NetworkConfig::read(name)
+            .and_then(|config| config.delete())
+            .map_err(|err| println_err!("{}", err.report(Some(name))))?;
+
+        println_succ!("Network \"{}\" has been deleted", name);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        network::tests::{add_network, NETWORK},
+    };
+
+    mod delete {
+        use super::*;
+
+        #[test]
+        pub fn delete_works() {
+            let ctx = setup();
+            add_network(&ctx, None);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(NetworkConfig::list().is_empty());
+            tear_down();
+        }
+
+        #[test]
+        pub fn delete_works_for_unknown_network() {
+            let ctx = setup();
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,64 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    tools::network::NetworkConfig,
+    utils::table::print_list_table,
+};
+
+pub mod list_command {
+    use super::*;
+
+    command!(CommandMetadata::build("list", "List added network profiles.").finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let networks = NetworkConfig::list()
+            .iter()
+            .map(|network| json!(network))
+            .collect::<Vec<_>>();
+
+        print_list_table(
+            &networks,
+            &[
+                ("name", "Name"),
+                ("pool", "Pool"),
+                ("taa_mechanism", "TAA Mechanism"),
+                ("default_did", "Default DID"),
+            ],
+            "There are no networks",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        network::tests::add_network,
+    };
+
+    mod list {
+        use super::*;
+
+        #[test]
+        pub fn list_works() {
+            let ctx = setup();
+            add_network(&ctx, None);
+            {
+                let cmd = list_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(1, NetworkConfig::list().len());
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::command_executor::{CommandGroup, CommandGroupMetadata};
+
+pub mod add;
+pub mod delete;
+pub mod list;
+pub mod use_network;
+
+pub use self::{add::*, delete::*, list::*, use_network::*};
+
+pub mod group {
+    use super::*;
+
+    command_group!(CommandGroupMetadata::new(
+        "network",
+        "Network profiles management commands"
+    ));
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        command_executor::{CommandContext, CommandParams},
+        pool::tests::POOL,
+    };
+
+    pub const NETWORK: &str = "local";
+
+    pub fn add_network(ctx: &CommandContext, default_did: Option<&str>) {
+        let cmd = add_command::new();
+        let mut params = CommandParams::new();
+        params.insert("name", NETWORK.to_string());
+        params.insert("pool", POOL.to_string());
+        params.insert("taa_mechanism", "for_session".to_string());
+        if let Some(default_did) = default_did {
+            params.insert("default_did", default_did.to_string());
+        }
+        cmd.execute(ctx, &params).unwrap();
+    }
+}
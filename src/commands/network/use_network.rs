@@ -0,0 +1,132 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    pool::{connect_pool, set_transaction_author_agreement},
+    tools::{did::Did, network::NetworkConfig},
+};
+
+use indy_utils::did::DidValue;
+
+pub mod use_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "use",
+        "Use network profile: connect to its pool, set TAA acceptance mechanism and make its default DID active."
+    )
+    .add_main_param("name", "The name of the network")
+    .add_example("network use sovrin-staging")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let name = ParamParser::get_str_param("name", params)?;
+
+        let config =
+            NetworkConfig::read(name).map_err(|err| println_err!("{}", err.report(Some(name))))?;
+
+        if let Some(ref taa_mechanism) = config.taa_mechanism {
+            ctx.set_taa_acceptance_mechanism(taa_mechanism);
+            println_succ!(
+                "\"{}\" is used as transaction author agreement acceptance mechanism",
+                taa_mechanism
+            );
+        }
+
+        let pool = connect_pool(ctx, &config.pool, None)?;
+        if ctx.get_transaction_author_info().is_none() {
+            set_transaction_author_agreement(ctx, &pool, true)?;
+        }
+
+        if let Some(default_did) = config.default_did {
+            use_default_did(ctx, DidValue(default_did));
+        }
+
+        println_succ!("Network \"{}\" is used", name);
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn use_default_did(ctx: &CommandContext, did: DidValue) {
+        let wallet = match ctx.get_opened_wallet() {
+            Some(wallet) => wallet,
+            None => {
+                println_warn!(
+                    "There is no opened wallet now. Open the wallet and call `did use {}` to set the default DID.",
+                    did
+                );
+                return;
+            }
+        };
+
+        match Did::get(&wallet, &did) {
+            Ok(_) => {
+                println_succ!("Did \"{}\" has been set as active", did);
+                ctx.set_active_did(did);
+            }
+            Err(err) => println_warn!(
+                "Default DID \"{}\" cannot be used: {}",
+                did,
+                err.message(None)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+        did::tests::{new_did, DID_TRUSTEE, SEED_TRUSTEE},
+        network::tests::{add_network, NETWORK},
+        pool::{
+            close_pool,
+            tests::{create_pool, delete_pool, POOL},
+        },
+    };
+
+    mod network_use {
+        use super::*;
+
+        #[test]
+        pub fn use_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            create_pool(&ctx);
+            add_network(&ctx, Some(DID_TRUSTEE));
+            {
+                let cmd = use_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let pool = ctx.ensure_connected_pool().unwrap();
+            assert_eq!(POOL, pool.name);
+            assert_eq!(DID_TRUSTEE, ctx.ensure_active_did().unwrap().to_string());
+            assert_eq!("for_session", ctx.get_taa_acceptance_mechanism());
+
+            close_pool(&ctx, &pool).unwrap();
+            delete_pool(&ctx);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn use_works_for_unknown_network() {
+            let ctx = setup();
+            {
+                let cmd = use_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", NETWORK.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,139 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::pool::auto_refresh::PoolAutoRefresh,
+};
+
+use std::time::Duration;
+
+pub mod auto_refresh_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "auto-refresh",
+        "Periodically refresh the connected pool in background, so long sessions keep up with the validator set changes. \
+                 New pool transactions are applied before the next command. Refreshing stops on disconnect."
+    )
+    .add_required_param("interval", "Refresh interval in minutes. 0 to stop refreshing")
+    .add_example("pool auto-refresh interval=30")
+    .add_example("pool auto-refresh interval=0")
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+        let interval = ParamParser::get_number_param::<u64>("interval", params)?;
+
+        if interval == 0 {
+            if ctx.get_pool_auto_refresh().is_none() {
+                println_warn!("Auto-refresh of pool \"{}\" is not running.", pool.name);
+                return Ok(());
+            }
+            ctx.stop_pool_auto_refresh();
+            trace!("execute <<");
+            return Ok(());
+        }
+
+        if pool.proxy.is_some() {
+            println_err!(
+                "Pool \"{}\" is connected through ledger proxy and cannot be refreshed.",
+                pool.name
+            );
+            return Err(());
+        }
+
+        let auto_refresh =
+            PoolAutoRefresh::start(&pool, Duration::from_secs(interval.saturating_mul(60)))
+                .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+        ctx.set_pool_auto_refresh(Some(auto_refresh));
+
+        println_succ!(
+            "Pool \"{}\" will be refreshed in background every {} min",
+            pool.name,
+            interval
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+
+    mod auto_refresh {
+        use super::*;
+        use crate::pool::{
+            disconnect_command,
+            tests::{
+                create_and_connect_pool, create_pool, delete_pool, disconnect_and_delete_pool,
+            },
+        };
+
+        #[test]
+        pub fn auto_refresh_works() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = auto_refresh_command::new();
+                let mut params = CommandParams::new();
+                params.insert("interval", "30".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let (pool_name, interval) = ctx.get_pool_auto_refresh().unwrap();
+            assert_eq!(crate::pool::tests::POOL, pool_name);
+            assert_eq!(Duration::from_secs(30 * 60), interval);
+            {
+                let cmd = auto_refresh_command::new();
+                let mut params = CommandParams::new();
+                params.insert("interval", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_pool_auto_refresh().is_none());
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn auto_refresh_works_for_disconnect() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = auto_refresh_command::new();
+                let mut params = CommandParams::new();
+                params.insert("interval", "30".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = disconnect_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_pool_auto_refresh().is_none());
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn auto_refresh_works_for_not_connected() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = auto_refresh_command::new();
+                let mut params = CommandParams::new();
+                params.insert("interval", "30".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            delete_pool(&ctx);
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,256 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+    },
+    params_parser::ParamParser,
+    tools::{
+        ledger::{Ledger, Response, ResponseType},
+        pool::{pool_config::PoolConfig, GenesisInfo, Pool},
+    },
+    utils::file::write_file,
+};
+
+use super::constants::POOL_LEDGER_TYPE;
+use indy_vdr::{config::PoolConfig as OpenPoolConfig, pool::ProtocolVersion};
+use serde_json::Value as JsonValue;
+use std::{env, fs, rc::Rc};
+
+pub mod clone_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "clone",
+        "Create new pool ledger config with genesis transactions pulled from the pool ledger of a connected network."
+    )
+    .add_main_param("name", "The name of new pool ledger config")
+    .add_optional_param_with_dynamic_completion(
+        "from",
+        "The name of existing pool to pull transactions from (the connected pool by default)",
+        DynamicCompletionType::Pool
+    )
+    .add_optional_param("ledger", "Ledger to pull transactions from. Only POOL is supported (used by default)")
+    .add_optional_param("file", "Path to the file to write genesis transactions to for distribution")
+    .add_example("pool clone pool2")
+    .add_example("pool clone pool2 from=pool1 ledger=POOL")
+    .add_example("pool clone pool2 from=pool1 file=/home/pool_genesis_transactions")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let name = ParamParser::get_str_param("name", params)?;
+        let from = ParamParser::get_opt_str_param("from", params)?;
+        let ledger = ParamParser::get_opt_str_param("ledger", params)?.unwrap_or("POOL");
+        let file = ParamParser::get_opt_str_param("file", params)?;
+
+        if !ledger.eq_ignore_ascii_case("POOL") {
+            println_err!(
+                "Unsupported ledger \"{}\". Genesis transactions can be pulled only from POOL ledger.",
+                ledger
+            );
+            return Err(());
+        }
+
+        let pool = source_pool(ctx, from)?;
+
+        println!("Pulling transactions from pool \"{}\" ledger...", pool.name);
+        let transactions = pull_pool_transactions(&pool)?;
+
+        let genesis = transactions
+            .iter()
+            .map(|txn| txn.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let genesis_info = GenesisInfo::parse(&genesis)
+            .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+
+        let path = match file {
+            Some(file) => file.to_string(),
+            None => env::temp_dir()
+                .join(format!("{}_genesis.txn", name))
+                .to_string_lossy()
+                .to_string(),
+        };
+
+        write_file(&path, &genesis).map_err(|err| println_err!("{}", err))?;
+
+        let config = PoolConfig { genesis_txn: path };
+        let res = Pool::create(name, &config);
+
+        // genesis transactions are copied into the pool directory, so the temporary file is not needed
+        if file.is_none() {
+            fs::remove_file(&config.genesis_txn).ok();
+        }
+        res.map_err(|err| println_err!("{}", err.report(Some(name))))?;
+
+        println_succ!(
+            "Pool config \"{}\" has been created from {} transactions ({} NODE) of pool \"{}\"",
+            name,
+            transactions.len(),
+            genesis_info.node_txn_count,
+            pool.name
+        );
+        if file.is_some() {
+            println_succ!(
+                "Genesis transactions have been written to \"{}\"",
+                config.genesis_txn
+            );
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn source_pool(ctx: &CommandContext, from: Option<&str>) -> Result<Rc<Pool>, ()> {
+        match (ctx.get_connected_pool(), from) {
+            (Some(pool), None) => Ok(pool),
+            (Some(pool), Some(from)) if pool.name == from => Ok(pool),
+            (_, Some(from)) => {
+                let genesis_info = Pool::read_genesis_info(from)
+                    .map_err(|err| println_err!("{}", err.report(Some(from))))?;
+                let protocol_version = ProtocolVersion::from_id(
+                    genesis_info.protocol_version as i64,
+                )
+                .map_err(|_| {
+                    println_err!(
+                        "Unexpected Pool protocol version \"{}\".",
+                        genesis_info.protocol_version
+                    )
+                })?;
+                let config = OpenPoolConfig {
+                    protocol_version,
+                    ..OpenPoolConfig::default()
+                };
+                Pool::open(from, config, None, None)
+                    .map(Rc::new)
+                    .map_err(|err| println_err!("{}", err.report(Some(from))))
+            }
+            (None, None) => ctx.ensure_connected_pool(),
+        }
+    }
+
+    /// Reads the pool ledger transactions one by one until the ledger size is reached.
+    fn pull_pool_transactions(pool: &Pool) -> Result<Vec<JsonValue>, ()> {
+        let mut transactions = Vec::new();
+        let mut ledger_size = None;
+
+        for seq_no in 1.. {
+            if matches!(ledger_size, Some(ledger_size) if seq_no > ledger_size) {
+                break;
+            }
+
+            let response =
+                Ledger::build_get_txn_request(Some(pool), None, POOL_LEDGER_TYPE, seq_no)
+                    .and_then(|request| Ledger::submit_request(pool, &request))
+                    .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))?;
+
+            let mut data = match serde_json::from_str::<Response<JsonValue>>(&response) {
+                Ok(Response {
+                    op: ResponseType::REPLY,
+                    result: Some(mut result),
+                    ..
+                }) => result["data"].take(),
+                Ok(response) => {
+                    println_err!(
+                        "Transaction {} has not been received: {}",
+                        seq_no,
+                        response
+                            .reason
+                            .unwrap_or_else(|| format!("{:?}", response.op))
+                    );
+                    return Err(());
+                }
+                Err(err) => {
+                    println_err!("Invalid data has been received: {:?}", err);
+                    return Err(());
+                }
+            };
+
+            let txn = match data.as_object_mut() {
+                Some(txn) => txn,
+                None => break,
+            };
+
+            // GET_TXN reply extends the transaction with the ledger state it has been read from
+            if let Some(size) = txn.remove("ledgerSize").and_then(|size| size.as_i64()) {
+                ledger_size = Some(size as i32);
+            }
+            txn.remove("auditPath");
+            txn.remove("rootHash");
+
+            transactions.push(data);
+        }
+
+        Ok(transactions)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        tools::pool::pool_config::PoolDirectory,
+    };
+
+    const CLONED_POOL: &str = "cloned_pool";
+
+    mod clone {
+        use super::*;
+        use crate::pool::tests::{create_and_connect_pool, disconnect_and_delete_pool};
+
+        #[test]
+        pub fn clone_works() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = clone_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", CLONED_POOL.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let genesis_info = Pool::read_genesis_info(CLONED_POOL).unwrap();
+            assert!(genesis_info.node_txn_count >= 4);
+
+            Pool::delete(CLONED_POOL).unwrap();
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn clone_works_for_not_connected_pool() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            disconnect_and_delete_pool(&ctx);
+            {
+                let cmd = clone_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", CLONED_POOL.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(PoolDirectory::from(CLONED_POOL).read_config().is_err());
+            tear_down();
+        }
+
+        #[test]
+        pub fn clone_works_for_unsupported_ledger() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = clone_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", CLONED_POOL.to_string());
+                params.insert("ledger", "DOMAIN".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+    }
+}
@@ -7,17 +7,20 @@ use crate::{
         wait_for_user_reply, Command, CommandContext, CommandMetadata, CommandParams,
         DynamicCompletionType,
     },
-    ledger::get_active_transaction_author_agreement,
+    ledger::{calculate_taa_digest, get_active_transaction_author_agreement},
+    pager::print_paged,
     params_parser::ParamParser,
+    pool::{close_pool, print_refresh_progress},
     tools::pool::Pool,
+    utils::{markdown::render_markdown, term::is_colored},
 };
 
 use chrono::prelude::*;
 use indy_vdr::{config::PoolConfig, pool::ProtocolVersion};
+use std::rc::Rc;
 
 pub mod connect_command {
     use super::*;
-    use crate::pool::close_pool;
 
     command_with_cleanup!(CommandMetadata::build(
         "connect",
@@ -26,12 +29,15 @@ pub mod connect_command {
     .add_main_param_with_dynamic_completion("name", "The name of pool", DynamicCompletionType::Pool)
     .add_optional_param(
         "protocol-version",
-        "Pool protocol version will be used for requests. One of: 1, 2. (2 by default)"
+        "Pool protocol version will be used for requests. One of: 1, 2. (Detected from pool transactions by default)"
+    )
+    .add_optional_param(
+        "timeout",
+        "Timeout for waiting for node acknowledgement (in sec). Defaults to `set pool-ack-timeout` value if it is set"
     )
-    .add_optional_param("timeout", "Timeout for network request (in sec)")
     .add_optional_param(
         "extended-timeout",
-        "Extended timeout for network request (in sec)"
+        "Extended timeout for network request (in sec). Defaults to `set request-timeout` value if it is set"
     )
     .add_optional_param(
         "pre-ordered-nodes",
@@ -39,13 +45,33 @@ pub mod connect_command {
     )
     .add_optional_param(
         "number-read-nodes",
-        "The number of nodes to send read requests (2 by default)"
+        "The number of nodes to send read requests (2 by default). Defaults to `set pool-read-nodes` value if it is set"
+    )
+    .add_optional_param(
+        "conn-request-limit",
+        "The maximal number of requests sent over one pool connection before a new one is opened (5 by default). \
+        Defaults to `set pool-conn-request-limit` value if it is set"
+    )
+    .add_optional_param(
+        "max-age",
+        "Skip catch-up of pool transactions if they were refreshed less than the given number of seconds ago"
+    )
+    .add_optional_param(
+        "type",
+        "Ledger backend to use. One of: nodes, proxy. (nodes by default)"
+    )
+    .add_optional_param(
+        "url",
+        "URL of indy-vdr-proxy to send read requests through (required for `proxy` type). Write transactions cannot be sent through the proxy"
     )
     .add_example("pool connect pool1")
     .add_example("pool connect pool1 protocol-version=2")
     .add_example("pool connect pool1 protocol-version=2 timeout=100")
     .add_example("pool connect pool1 protocol-version=2 extended-timeout=100")
     .add_example("pool connect pool1 protocol-version=2 pre-ordered-nodes=Node2,Node1")
+    .add_example("pool connect pool1 timeout=60 extended-timeout=120 conn-request-limit=20 number-read-nodes=3")
+    .add_example("pool connect pool1 max-age=3600")
+    .add_example("pool connect pool1 type=proxy url=http://127.0.0.1:3030")
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -53,33 +79,84 @@ pub mod connect_command {
 
         let name = ParamParser::get_str_param("name", params)?;
         let protocol_version =
-            ParamParser::get_opt_number_param::<usize>("protocol-version", params)?
-                .unwrap_or(ctx.get_pool_protocol_version());
+            ParamParser::get_opt_number_param::<usize>("protocol-version", params)?;
         let timeout = ParamParser::get_opt_number_param::<i64>("timeout", params)?;
         let extended_timeout =
             ParamParser::get_opt_number_param::<i64>("extended-timeout", params)?;
         let pre_ordered_nodes = ParamParser::get_opt_str_array_param("pre-ordered-nodes", params)?;
         let number_read_nodes =
             ParamParser::get_opt_number_param::<usize>("number-read-nodes", params)?;
+        let conn_request_limit =
+            ParamParser::get_opt_number_param::<usize>("conn-request-limit", params)?;
+        let max_age = ParamParser::get_opt_number_param::<u64>("max-age", params)?;
+        let backend_type = ParamParser::get_opt_str_param("type", params)?.unwrap_or("nodes");
+        let url = ParamParser::get_opt_str_param("url", params)?;
+        let protocol_version = resolve_protocol_version(ctx, name, protocol_version);
         let protocol_version = ProtocolVersion::from_id(protocol_version as i64).map_err(|_| {
             println_err!("Unexpected Pool protocol version \"{}\".", protocol_version)
         })?;
 
+        if number_read_nodes == Some(0) || conn_request_limit == Some(0) {
+            println_err!(
+                "Number of read nodes and connection request limit must be greater than 0."
+            );
+            return Err(());
+        }
+
         let config = PoolConfig {
             protocol_version,
-            ack_timeout: timeout.unwrap_or(PoolConfig::default_ack_timeout()),
-            reply_timeout: extended_timeout.unwrap_or(PoolConfig::default_reply_timeout()),
+            ack_timeout: timeout
+                .or(ctx.get_pool_ack_timeout().map(|timeout| timeout as i64))
+                .unwrap_or(PoolConfig::default_ack_timeout()),
+            reply_timeout: extended_timeout
+                .or(ctx.get_request_timeout().map(|timeout| timeout as i64))
+                .unwrap_or(PoolConfig::default_reply_timeout()),
             request_read_nodes: number_read_nodes
+                .or(ctx.get_pool_read_nodes().map(|nodes| nodes as usize))
                 .unwrap_or(PoolConfig::default_request_read_nodes()),
+            conn_request_limit: conn_request_limit
+                .or(ctx
+                    .get_pool_conn_request_limit()
+                    .map(|limit| limit as usize))
+                .unwrap_or(PoolConfig::default_conn_request_limit()),
             ..PoolConfig::default()
         };
 
+        let url = match (backend_type, url) {
+            ("nodes", None) => None,
+            ("nodes", Some(_)) => {
+                println_err!("Parameter \"url\" can be used only with \"proxy\" type.");
+                return Err(());
+            }
+            ("proxy", Some(url)) => Some(url),
+            ("proxy", None) => {
+                println_err!("Parameter \"url\" is required for \"proxy\" type.");
+                return Err(());
+            }
+            (backend_type, _) => {
+                println_err!(
+                    "Unexpected ledger backend type \"{}\". One of: nodes, proxy.",
+                    backend_type
+                );
+                return Err(());
+            }
+        };
+
         if let Some(pool) = ctx.get_connected_pool() {
             close_pool(ctx, &pool)?;
         }
 
-        let pool = Pool::open(name, config, pre_ordered_nodes)
-            .map_err(|err| println_err!("{}", err.message(Some(&name))))?;
+        let pool = match url {
+            Some(url) => Pool::open_proxy(name, config, url),
+            None => Pool::open_with_progress(
+                name,
+                config,
+                pre_ordered_nodes,
+                max_age,
+                &mut |progress| print_refresh_progress(name, progress),
+            ),
+        }
+        .map_err(|err| println_err!("{}", err.report(Some(&name))))?;
 
         ctx.set_connected_pool(pool);
         println_succ!("Pool \"{}\" has been connected", name);
@@ -102,6 +179,88 @@ pub mod connect_command {
     }
 }
 
+/// Connects to the pool with default settings, disconnecting from the previously connected one.
+/// Unless the protocol version is passed, it is detected from the pool transactions.
+pub fn connect_pool(
+    ctx: &CommandContext,
+    name: &str,
+    protocol_version: Option<usize>,
+) -> Result<Rc<Pool>, ()> {
+    if let Some(pool) = ctx.get_connected_pool() {
+        if pool.name == name {
+            return Ok(pool);
+        }
+        close_pool(ctx, &pool)?;
+    }
+
+    let protocol_version = resolve_protocol_version(ctx, name, protocol_version);
+    let protocol_version = ProtocolVersion::from_id(protocol_version as i64)
+        .map_err(|_| println_err!("Unexpected Pool protocol version \"{}\".", protocol_version))?;
+    let config = PoolConfig {
+        protocol_version,
+        reply_timeout: ctx
+            .get_request_timeout()
+            .map(|timeout| timeout as i64)
+            .unwrap_or(PoolConfig::default_reply_timeout()),
+        ..PoolConfig::default()
+    };
+
+    let pool = Pool::open(name, config, None, None)
+        .map_err(|err| println_err!("{}", err.report(Some(name))))?;
+    ctx.set_connected_pool(pool);
+    println_succ!("Pool \"{}\" has been connected", name);
+
+    ctx.ensure_connected_pool()
+}
+
+/// Chooses the protocol version for the connection.
+/// Unless it is passed explicitly, the version required by the pool transactions format is used
+/// and stored into CLI context instead of the one set by `pool set-protocol-version`.
+fn resolve_protocol_version(
+    ctx: &CommandContext,
+    name: &str,
+    protocol_version: Option<usize>,
+) -> usize {
+    let genesis_info = match Pool::read_genesis_info(name) {
+        Ok(genesis_info) => genesis_info,
+        Err(_) => return protocol_version.unwrap_or(ctx.get_pool_protocol_version()),
+    };
+    let detected = genesis_info.protocol_version;
+
+    match protocol_version {
+        Some(protocol_version) => {
+            if protocol_version != detected {
+                println_warn!(
+                    "Protocol version \"{}\" conflicts with pool \"{}\" transactions requiring protocol version \"{}\".",
+                    protocol_version,
+                    name,
+                    detected
+                );
+            }
+            protocol_version
+        }
+        None => {
+            match ctx.get_user_pool_protocol_version() {
+                Some(user_version) if user_version != detected => {
+                    println_warn!(
+                        "Protocol version \"{}\" set by `pool set-protocol-version` conflicts with pool \"{}\" transactions. Protocol version \"{}\" will be used.",
+                        user_version,
+                        name,
+                        detected
+                    );
+                }
+                _ => {}
+            }
+            println!(
+                "Pool transactions contain {} NODE transaction(s) and require protocol version \"{}\".",
+                genesis_info.node_txn_count, detected
+            );
+            ctx.set_detected_pool_protocol_version(detected);
+            detected
+        }
+    }
+}
+
 pub fn accept_transaction_author_agreement(ctx: &CommandContext, text: &str, version: &str) {
     println!("Would you like to accept it? (y/n)");
 
@@ -148,12 +307,25 @@ pub fn set_transaction_author_agreement(
             }
         }
 
+        let calculated_digest = calculate_taa_digest(&text, &version);
+
         println!("Transaction Author Agreement");
-        println!("Version: {:?}", version);
-        if let Some(digest_) = digest {
-            println!("Digest: {:?}", digest_);
+        println!("Version: {}", version);
+        println!("Digest: {}", calculated_digest);
+        println!("Content:");
+        print_paged(ctx, &render_markdown(&text, is_colored()));
+
+        if let Some(digest) = digest {
+            if digest != calculated_digest {
+                println_err!(
+                    "The Transaction Author Agreement digest \"{}\" set on the Ledger does not match the digest \"{}\" calculated for the received text and version.",
+                    digest,
+                    calculated_digest
+                );
+                println_warn!("The Transaction Author Agreement has NOT been Accepted.");
+                return Ok(Some(()));
+            }
         }
-        println!("Content: \n {:?}", text);
 
         accept_transaction_author_agreement(ctx, &text, &version);
 
@@ -166,7 +338,10 @@ pub fn set_transaction_author_agreement(
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::commands::{setup, tear_down};
+    use crate::{
+        commands::{setup, tear_down},
+        tools::pool::GenesisInfo,
+    };
 
     mod connect {
         use super::*;
@@ -189,6 +364,105 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn connect_works_for_pool_config_params() {
+            let ctx = setup();
+            create_pool(&ctx);
+            ctx.set_pool_ack_timeout(Some(30));
+            ctx.set_pool_read_nodes(Some(3));
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("conn-request-limit", "20".to_string());
+                params.insert("number-read-nodes", "4".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let pool = ctx.ensure_connected_pool().unwrap();
+            let config = pool.pool.get_config();
+            assert_eq!(30, config.ack_timeout);
+            assert_eq!(20, config.conn_request_limit);
+            assert_eq!(4, config.request_read_nodes);
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn connect_works_for_zero_conn_request_limit() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("conn-request-limit", "0".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn connect_works_for_detected_protocol_version() {
+            let ctx = setup();
+            create_pool(&ctx);
+            ctx.set_pool_protocol_version(1);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(2, ctx.get_pool_protocol_version());
+            assert_eq!(None, ctx.get_user_pool_protocol_version());
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn read_genesis_info_works() {
+            let ctx = setup();
+            create_pool(&ctx);
+            assert_eq!(
+                GenesisInfo {
+                    protocol_version: 2,
+                    node_txn_count: 4,
+                },
+                Pool::read_genesis_info(POOL).unwrap()
+            );
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn genesis_info_parse_works_for_legacy_transactions() {
+            let transactions = r#"{"data":{"alias":"Node1","client_ip":"10.0.0.2","client_port":9702,"node_ip":"10.0.0.2","node_port":9701,"services":["VALIDATOR"]},"dest":"Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv","identifier":"FYmoFw55GeQH7SRFa37dkx1d2dZ3zUF8ckg7wmL7ofN4","txnId":"fea82e10e894419fe2bea7d96296a6d46f50f93f9eeda954ec461b2ed2950b62","type":"0"}
+{"dest":"V4SGRU86Z58d6TV7PBUe6f","role":"0","type":"1","verkey":"~CoRER63DVYnWZtK8uAzNbx"}"#;
+            assert_eq!(
+                GenesisInfo {
+                    protocol_version: 1,
+                    node_txn_count: 1,
+                },
+                GenesisInfo::parse(transactions).unwrap()
+            );
+        }
+
+        #[test]
+        pub fn connect_works_for_max_age() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("max-age", "3600".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            ctx.ensure_connected_pool().unwrap();
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
         #[test]
         pub fn connect_works_for_twice() {
             let ctx = setup();
@@ -213,6 +487,54 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn connect_works_for_proxy_without_url() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("type", "proxy".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ctx.get_connected_pool().is_none());
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn connect_works_for_invalid_proxy_url() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("type", "proxy".to_string());
+                params.insert("url", "127.0.0.1:3030".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(ctx.get_connected_pool().is_none());
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn connect_works_for_unknown_type() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = connect_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("type", "zmq".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            delete_pool(&ctx);
+            tear_down();
+        }
+
         #[test]
         pub fn connect_works_for_invalid_protocol_version() {
             let ctx = setup();
@@ -3,3 +3,5 @@
     https://digital.gov.bc.ca/digital-trust
 */
 pub const DEFAULT_POOL_PROTOCOL_VERSION: usize = 2;
+pub const POOL_LEDGER_TYPE: i32 = 0;
+pub const DEFAULT_REQUEST_RETRIES: u64 = 0;
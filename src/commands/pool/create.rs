@@ -10,22 +10,37 @@ use crate::{
 
 pub mod create_command {
     use super::*;
-    use crate::tools::pool::pool_config::PoolConfig;
+    use crate::tools::pool::{pool_config::PoolConfig, GenesisInfo};
+    use serde_json::Value as JsonValue;
+    use std::io::Read;
+
+    /// Value of `gen_txn_file` parameter to read genesis transactions from the standard input.
+    const STDIN_FILE: &str = "-";
 
     command!(CommandMetadata::build(
         "create",
         "Create new pool ledger config with specified name"
     )
     .add_main_param("name", "The name of new pool ledger config")
-    .add_required_param("gen_txn_file", "Path to file with genesis transactions")
+    .add_optional_param(
+        "gen_txn_file",
+        "Path to file with genesis transactions. Use `-` to read them from the standard input"
+    )
+    .add_optional_param(
+        "gen_txn_json",
+        "JSON array of genesis transactions (used instead of `gen_txn_file`)"
+    )
     .add_example("pool create pool1 gen_txn_file=/home/pool_genesis_transactions")
+    .add_example("pool create pool1 gen_txn_file=-")
+    .add_example("pool create pool1 gen_txn_json=$GENESIS_TXNS_JSON")
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
         let name = ParamParser::get_str_param("name", params)?;
-        let gen_txn_file = ParamParser::get_str_param("gen_txn_file", params)?;
+        let gen_txn_file = ParamParser::get_opt_str_param("gen_txn_file", params)?;
+        let gen_txn_json = ParamParser::get_opt_str_param("gen_txn_json", params)?;
 
         trace!(
             r#"Pool::create_pool_ledger_config try: name {}, gen_txn_file {:?}"#,
@@ -33,17 +48,82 @@ pub mod create_command {
             gen_txn_file
         );
 
-        let config = PoolConfig {
-            genesis_txn: gen_txn_file.to_string(),
+        let res = match (gen_txn_file, gen_txn_json) {
+            (Some(STDIN_FILE), None) => {
+                let transactions = read_stdin_transactions()?;
+                Pool::create_with_transactions(name, &transactions)
+            }
+            (Some(gen_txn_file), None) => {
+                let config = PoolConfig {
+                    genesis_txn: gen_txn_file.to_string(),
+                };
+                Pool::create(name, &config)
+            }
+            (None, Some(gen_txn_json)) => {
+                let transactions = parse_json_transactions(gen_txn_json)?;
+                Pool::create_with_transactions(name, &transactions)
+            }
+            (Some(_), Some(_)) => {
+                println_err!(
+                    "Only one of \"gen_txn_file\" and \"gen_txn_json\" params can be specified."
+                );
+                return Err(());
+            }
+            (None, None) => {
+                println_err!(
+                    "Either \"gen_txn_file\" or \"gen_txn_json\" param must be specified."
+                );
+                return Err(());
+            }
         };
 
-        Pool::create(name, &config).map_err(|err| println_err!("{}", err.message(Some(&name))))?;
+        res.map_err(|err| println_err!("{}", err.report(Some(&name))))?;
 
         println_succ!("Pool config \"{}\" has been created", name);
 
         trace!("execute <<");
         Ok(())
     }
+
+    fn read_stdin_transactions() -> Result<String, ()> {
+        let mut transactions = String::new();
+        std::io::stdin()
+            .read_to_string(&mut transactions)
+            .map_err(|err| {
+                println_err!(
+                    "Unable to read genesis transactions from the standard input: {}",
+                    err
+                )
+            })?;
+        validate_transactions(&transactions)?;
+        Ok(transactions)
+    }
+
+    /// Converts JSON array of transactions into the genesis file format: one transaction per line.
+    fn parse_json_transactions(gen_txn_json: &str) -> Result<String, ()> {
+        let transactions = serde_json::from_str::<Vec<JsonValue>>(gen_txn_json)
+            .map_err(|err| println_err!("Invalid genesis transactions JSON: {}", err))?;
+        if let Some(txn) = transactions.iter().find(|txn| !txn.is_object()) {
+            println_err!(
+                "Invalid genesis transaction \"{}\": JSON object expected.",
+                txn
+            );
+            return Err(());
+        }
+        let transactions = transactions
+            .iter()
+            .map(JsonValue::to_string)
+            .collect::<Vec<String>>()
+            .join("\n");
+        validate_transactions(&transactions)?;
+        Ok(transactions)
+    }
+
+    fn validate_transactions(transactions: &str) -> Result<(), ()> {
+        GenesisInfo::parse(transactions)
+            .map(|_| ())
+            .map_err(|err| println_err!("Invalid genesis transactions: {}", err.report(None)))
+    }
 }
 
 #[cfg(test)]
@@ -96,6 +176,72 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn create_works_for_gen_txn_json() {
+            let ctx = setup();
+            {
+                let transactions =
+                    crate::utils::file::read_file("docker_pool_transactions_genesis")
+                        .unwrap()
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                        .collect::<Vec<serde_json::Value>>();
+
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("gen_txn_json", json!(transactions).to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let pools = get_pools();
+            assert_eq!(1, pools.len());
+            assert_eq!(pools[0]["pool"].as_str().unwrap(), POOL);
+
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn create_works_for_invalid_gen_txn_json() {
+            let ctx = setup();
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("gen_txn_json", r#"["not a transaction"]"#.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert("gen_txn_json", "[]".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_pools().is_empty());
+            tear_down();
+        }
+
+        #[test]
+        pub fn create_works_for_gen_txn_file_and_gen_txn_json() {
+            let ctx = setup();
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                params.insert(
+                    "gen_txn_file",
+                    "docker_pool_transactions_genesis".to_string(),
+                );
+                params.insert("gen_txn_json", "[]".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(get_pools().is_empty());
+            tear_down();
+        }
+
         #[test]
         pub fn create_works_for_missed_gen_txn_file() {
             let ctx = setup();
@@ -36,7 +36,7 @@ pub mod delete_command {
             close_pool(ctx, &pool)?;
         }
 
-        Pool::delete(name).map_err(|err| println_err!("{}", err.message(Some(&name))))?;
+        Pool::delete(name).map_err(|err| println_err!("{}", err.report(Some(&name))))?;
 
         println_succ!("Pool \"{}\" has been deleted.", name);
 
@@ -3,14 +3,18 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     tools::pool::Pool,
 };
 
 pub mod disconnect_command {
     use super::*;
 
-    command!(CommandMetadata::build("disconnect", "Disconnect from current pool.").finalize());
+    command!(
+        CommandMetadata::build("disconnect", "Disconnect from current pool.")
+            .add_precondition(Precondition::ConnectedPool)
+            .finalize()
+    );
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
@@ -28,10 +32,9 @@ pub fn close_pool(ctx: &CommandContext, pool: &Pool) -> Result<(), ()> {
     pool.close()
         .map(|_| {
             ctx.reset_connected_pool();
-            ctx.set_transaction_author_info(None);
             println_succ!("Pool \"{}\" has been disconnected", pool.name)
         })
-        .map_err(|err| println_err!("{}", err.message(Some(&pool.name))))
+        .map_err(|err| println_err!("{}", err.report(Some(&pool.name))))
 }
 
 #[cfg(test)]
@@ -73,5 +76,26 @@ pub mod tests {
             delete_pool(&ctx);
             tear_down();
         }
+
+        #[test]
+        pub fn disconnect_works_for_pool_scoped_values() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            let network = ctx.get_network_id().unwrap();
+            ctx.set_transaction_author_info(Some(("text".to_string(), "1.0".to_string(), 1)));
+            ctx.add_schema_name("gvt");
+            ctx.set_context_transaction(Some(r#"{"reqId":1}"#.to_string()));
+            {
+                let cmd = disconnect_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_transaction_author_info().is_none());
+            assert!(ctx.get_schema_names().is_empty());
+            assert!(ctx.get_context_transaction().is_some());
+            assert_eq!(Some(network), ctx.get_network_id());
+            delete_pool(&ctx);
+            tear_down();
+        }
     }
 }
@@ -0,0 +1,175 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+    },
+    error::{report_error, ErrorCode},
+    params_parser::ParamParser,
+    tools::pool::{genesis::GenesisReport, Pool},
+    utils::{file::read_file, table::print_list_table},
+};
+
+pub mod inspect_genesis_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "inspect-genesis",
+        "Check pool genesis transactions file without connecting to the pool: list the nodes and report malformed transactions and nodes sharing alias, identifier or address."
+    )
+    .add_optional_param("file", "Path to the genesis transactions file")
+    .add_optional_param_with_dynamic_completion(
+        "name",
+        "The name of existing pool config to check genesis transactions of (used instead of `file`)",
+        DynamicCompletionType::Pool
+    )
+    .add_example("pool inspect-genesis file=/home/pool_genesis_transactions")
+    .add_example("pool inspect-genesis name=pool1")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let file = ParamParser::get_opt_str_param("file", params)?;
+        let name = ParamParser::get_opt_str_param("name", params)?;
+
+        let path = match (file, name) {
+            (Some(file), None) => file.to_string(),
+            (None, Some(name)) => Pool::genesis_txn_path(name)
+                .map_err(|err| println_err!("{}", err.report(Some(name))))?,
+            (Some(_), Some(_)) => {
+                println_err!("Only one of \"file\" and \"name\" params can be specified.");
+                return Err(());
+            }
+            (None, None) => {
+                println_err!("Either \"file\" or \"name\" param must be specified.");
+                return Err(());
+            }
+        };
+
+        let transactions = read_file(&path).map_err(|err| println_err!("{}", err))?;
+        let report = GenesisReport::inspect(&transactions);
+
+        let nodes = report
+            .nodes
+            .iter()
+            .map(|node| {
+                json!({
+                    "line": node.line,
+                    "alias": node.alias,
+                    "dest": node.dest,
+                    "node": node.node_address,
+                    "client": node.client_address,
+                    "bls": if node.bls_key { "yes" } else { "no" },
+                    "steward": node.steward,
+                    "services": node.services.join(","),
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+
+        print_list_table(
+            &nodes,
+            &[
+                ("line", "Line"),
+                ("alias", "Alias"),
+                ("dest", "Node identifier"),
+                ("node", "Node address"),
+                ("client", "Client address"),
+                ("bls", "BLS key"),
+                ("steward", "Steward"),
+                ("services", "Services"),
+            ],
+            "There are no nodes in the genesis transactions",
+        );
+        println!(
+            "{} node(s), {} validator(s), {} with BLS key",
+            report.nodes.len(),
+            report.validators_count(),
+            report.bls_keys_count()
+        );
+        if report.bls_keys_count() < report.nodes.len() {
+            println_warn!(
+                "State proofs can't be verified against nodes without BLS key, so read requests need replies from several nodes"
+            );
+        }
+
+        if !report.problems.is_empty() {
+            for problem in report.problems.iter() {
+                println_err!("{}", problem);
+            }
+            report_error(
+                ErrorCode::InvalidInput,
+                &format!(
+                    "Genesis transactions file \"{}\" contains {} problem(s)",
+                    path,
+                    report.problems.len()
+                ),
+            );
+            return Err(());
+        }
+
+        println_succ!("Genesis transactions file \"{}\" is valid", path);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        utils::{environment::EnvironmentUtils, file::write_file},
+    };
+
+    mod inspect_genesis {
+        use super::*;
+        use crate::pool::tests::{create_pool, delete_pool, POOL};
+
+        #[test]
+        pub fn inspect_genesis_works_for_file() {
+            let ctx = setup();
+            {
+                let cmd = inspect_genesis_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", "docker_pool_transactions_genesis".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down();
+        }
+
+        #[test]
+        pub fn inspect_genesis_works_for_pool() {
+            let ctx = setup();
+            create_pool(&ctx);
+            {
+                let cmd = inspect_genesis_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", POOL.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn inspect_genesis_works_for_duplicated_node() {
+            let ctx = setup();
+            let transactions = read_file("docker_pool_transactions_genesis").unwrap();
+            let first = transactions.lines().next().unwrap().to_string();
+            let path = EnvironmentUtils::tmp_file_path("duplicated_genesis");
+            let path = path.to_str().unwrap();
+            write_file(path, &format!("{}\n{}", transactions.trim_end(), first)).unwrap();
+            {
+                let cmd = inspect_genesis_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path.to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -4,26 +4,49 @@
 */
 use crate::{
     command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
     tools::pool::Pool,
-    utils::table::print_list_table,
+    utils::table::print_or_export_list_table,
 };
 
 pub mod list_command {
     use super::*;
 
-    command!(CommandMetadata::build("list", "List existing pool configs.").finalize());
+    command!(
+        CommandMetadata::build("list", "List existing pool configs.")
+            .add_optional_param(
+                "out",
+                "Path to the file to write the list into instead of printing the table"
+            )
+            .add_optional_param(
+                "format",
+                "Format of the `out` file. One of: csv, json (json by default)"
+            )
+            .add_example("pool list")
+            .add_example("pool list out=/home/pools.csv format=csv")
+            .finalize()
+    );
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
-        let pools = Pool::list().map_err(|err| println_err!("{}", err.message(None)))?;
+        let out = ParamParser::get_opt_str_param("out", params)?;
+        let format = ParamParser::get_opt_str_param("format", params)?;
+
+        let pools = Pool::list().map_err(|err| println_err!("{}", err.report(None)))?;
 
         let pools: Vec<serde_json::Value> = serde_json::from_str(&pools)
             .map_err(|_| println_err!("Wrong data has been received"))?;
 
-        print_list_table(&pools, &[("pool", "Pool")], "There are no pools defined");
+        print_or_export_list_table(
+            &pools,
+            &[("pool", "Pool")],
+            "There are no pools defined",
+            out,
+            format,
+        )?;
 
-        if let Some(pool) = ctx.get_connected_pool() {
+        if let (None, Some(pool)) = (out, ctx.get_connected_pool()) {
             println_succ!("Current pool \"{}\"", pool.name);
         }
 
@@ -47,6 +70,16 @@ pub fn pool_list() -> Vec<String> {
         .collect()
 }
 
+/// Aliases of the nodes known to the connected pool.
+pub fn node_aliases(ctx: &CommandContext) -> Vec<String> {
+    ctx.get_connected_pool()
+        .and_then(|pool| pool.nodes().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|node| node.alias().map(String::from))
+        .collect()
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -80,4 +113,24 @@ pub mod tests {
             tear_down();
         }
     }
+
+    mod node_aliases {
+        use super::*;
+        use crate::commands::{setup_with_wallet_and_pool, tear_down_with_wallet_and_pool};
+
+        #[test]
+        pub fn node_aliases_works() {
+            let ctx = setup_with_wallet_and_pool();
+            let aliases = node_aliases(&ctx);
+            assert!(aliases.contains(&"Node1".to_string()));
+            tear_down_with_wallet_and_pool(&ctx);
+        }
+
+        #[test]
+        pub fn node_aliases_works_for_no_connected_pool() {
+            let ctx = setup();
+            assert!(node_aliases(&ctx).is_empty());
+            tear_down();
+        }
+    }
 }
@@ -0,0 +1,107 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::pool::{localnet::localnet_genesis, Pool},
+    utils::file::write_file,
+};
+
+pub mod localnet_genesis_command {
+    use super::*;
+
+    const DEFAULT_NODES_COUNT: usize = 4;
+    const DEFAULT_IP: &str = "127.0.0.1";
+
+    command!(CommandMetadata::build(
+        "localnet-genesis",
+        "Generate genesis transactions file for a local docker pool (like von-network) whose nodes use well-known seeds `000...Node<N>` and `000...Steward<N>`.
+        BLS keys are not included into the transactions."
+    )
+    .add_optional_param("nodes", "Number of nodes (4 by default)")
+    .add_optional_param("ips", "Node IP shared by all nodes or IPs of every node split by comma (127.0.0.1 by default)")
+    .add_required_param("out", "Path to the file to write genesis transactions to")
+    .add_optional_param("name", "Also create pool config with the generated transactions under this name")
+    .add_example("pool localnet-genesis out=/home/localnet_genesis")
+    .add_example("pool localnet-genesis nodes=4 ips=127.0.0.1 out=/home/localnet_genesis")
+    .add_example("pool localnet-genesis nodes=2 ips=172.17.0.2,172.17.0.3 out=/home/localnet_genesis name=localnet")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let nodes = ParamParser::get_opt_number_param::<usize>("nodes", params)?
+            .unwrap_or(DEFAULT_NODES_COUNT);
+        let ips = ParamParser::get_opt_str_array_param("ips", params)?
+            .unwrap_or_else(|| vec![DEFAULT_IP]);
+        let out = ParamParser::get_str_param("out", params)?;
+        let name = ParamParser::get_opt_str_param("name", params)?;
+
+        let genesis =
+            localnet_genesis(nodes, &ips).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        write_file(out, &genesis).map_err(|err| println_err!("{}", err))?;
+        println_succ!(
+            "Genesis transactions of {} node(s) have been written into \"{}\"",
+            nodes,
+            out
+        );
+
+        if let Some(name) = name {
+            Pool::create_with_transactions(name, &genesis)
+                .map_err(|err| println_err!("{}", err.report(Some(name))))?;
+            println_succ!("Pool config \"{}\" has been created", name);
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        utils::{environment::EnvironmentUtils, file::read_file},
+    };
+
+    mod localnet_genesis {
+        use super::*;
+        use crate::pool::tests::get_pools;
+
+        #[test]
+        pub fn localnet_genesis_works() {
+            let ctx = setup();
+            let path = EnvironmentUtils::tmp_file_path("localnet_genesis");
+            let path = path.to_str().unwrap();
+            {
+                let cmd = localnet_genesis_command::new();
+                let mut params = CommandParams::new();
+                params.insert("out", path.to_string());
+                params.insert("name", "localnet".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(4, read_file(path).unwrap().lines().count());
+            assert_eq!(1, get_pools().len());
+            tear_down();
+        }
+
+        #[test]
+        pub fn localnet_genesis_works_for_ips_mismatch() {
+            let ctx = setup();
+            let path = EnvironmentUtils::tmp_file_path("localnet_genesis");
+            {
+                let cmd = localnet_genesis_command::new();
+                let mut params = CommandParams::new();
+                params.insert("nodes", "3".to_string());
+                params.insert("ips", "10.0.0.2,10.0.0.3".to_string());
+                params.insert("out", path.to_str().unwrap().to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
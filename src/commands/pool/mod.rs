@@ -4,28 +4,36 @@
 */
 use crate::command_executor::{CommandGroup, CommandGroupMetadata};
 
+pub mod auto_refresh;
+pub mod clone;
 pub mod connect;
 pub mod constants;
 pub mod create;
 pub mod delete;
 pub mod disconnect;
+pub mod inspect_genesis;
 pub mod list;
+pub mod localnet_genesis;
+pub mod ping;
 pub mod refresh;
 pub mod set_protocol_version;
 pub mod show_taa;
 
 pub use self::{
-    connect::*, create::*, delete::*, disconnect::*, list::*, refresh::*, set_protocol_version::*,
-    show_taa::*,
+    auto_refresh::*, clone::*, connect::*, create::*, delete::*, disconnect::*, inspect_genesis::*,
+    list::*, localnet_genesis::*, ping::*, refresh::*, set_protocol_version::*, show_taa::*,
 };
 
 pub mod group {
     use super::*;
 
-    command_group!(CommandGroupMetadata::new(
-        "pool",
-        "Pool management commands"
-    ));
+    command_group!(
+        CommandGroupMetadata::new("pool", "Pool management commands")
+            .add_example("pool create pool1 gen_txn_file=/home/pool_genesis_transactions")
+            .add_example("pool connect pool1")
+            .add_example("pool ping")
+            .add_example("pool disconnect")
+    );
 }
 
 #[cfg(test)]
@@ -0,0 +1,165 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::ledger::{Ledger, Response, ResponseType},
+    utils::table::print_list_table,
+};
+
+use super::constants::POOL_LEDGER_TYPE;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+pub mod ping_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "ping",
+        "Check reachability of pool nodes by sending a lightweight read request to each of them."
+    )
+    .add_optional_param("nodes", "The list of node names to send the request")
+    .add_optional_param("timeout", "Time to wait respond from nodes")
+    .add_example("pool ping")
+    .add_example("pool ping nodes=Node1,Node2")
+    .add_example("pool ping nodes=Node1,Node2 timeout=5")
+    .add_precondition(Precondition::ConnectedPool)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let pool = ctx.ensure_connected_pool()?;
+
+        let nodes = ParamParser::get_opt_str_array_param("nodes", params)?;
+        let timeout = ParamParser::get_opt_number_param::<i64>("timeout", params)?;
+
+        // the first pool ledger transaction exists on every network and is cheap to read
+        let request = Ledger::build_get_txn_request(Some(&pool), None, POOL_LEDGER_TYPE, 1)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let nodes = nodes.map(|nodes| json!(nodes).to_string());
+
+        let (replies, timing) =
+            Ledger::submit_action_with_timing(&pool, &request, nodes.as_deref(), timeout)
+                .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        let timing = timing.unwrap_or_default();
+
+        let replies: BTreeMap<String, String> = replies.into_iter().collect();
+
+        let rows: Vec<JsonValue> = replies
+            .iter()
+            .map(|(node, reply)| {
+                let status = node_status(reply);
+                let latency = match timing.get(node) {
+                    Some(latency) if reply.ne("timeout") => {
+                        format!("{:.0} ms", latency * 1000.0)
+                    }
+                    _ => "-".to_string(),
+                };
+                json!({
+                    "node": node,
+                    "status": status,
+                    "latency": latency,
+                })
+            })
+            .collect();
+
+        print_list_table(
+            &rows,
+            &[
+                ("node", "Node"),
+                ("status", "Status"),
+                ("latency", "Latency"),
+            ],
+            "No replies have been received from pool nodes",
+        );
+
+        let unreachable = rows
+            .iter()
+            .filter(|row| row["status"].as_str() != Some("Reachable"))
+            .count();
+
+        if unreachable > 0 {
+            println_warn!(
+                "{} of {} nodes did not respond properly",
+                unreachable,
+                rows.len()
+            );
+        } else {
+            println_succ!("All {} nodes are reachable", rows.len());
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn node_status(reply: &str) -> String {
+        if reply.eq("timeout") {
+            return "Timeout".to_string();
+        }
+        match serde_json::from_str::<Response<JsonValue>>(reply) {
+            Ok(response) if response.op == ResponseType::REPLY => "Reachable".to_string(),
+            Ok(response) => format!(
+                "Rejected: {}",
+                response
+                    .reason
+                    .unwrap_or_else(|| format!("{:?}", response.op))
+            ),
+            Err(_) => "Invalid response".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+
+    mod ping {
+        use super::*;
+        use crate::pool::tests::{create_and_connect_pool, disconnect_and_delete_pool};
+
+        #[test]
+        pub fn ping_works() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = ping_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn ping_works_for_nodes() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let cmd = ping_command::new();
+                let mut params = CommandParams::new();
+                params.insert("nodes", "Node1,Node2".to_string());
+                params.insert("timeout", "10".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn ping_works_for_no_connected_pool() {
+            let ctx = setup();
+            {
+                let cmd = ping_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
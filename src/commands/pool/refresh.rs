@@ -2,7 +2,13 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::command_executor::{Command, CommandContext, CommandMetadata, CommandParams};
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    tools::pool::RefreshProgress,
+    utils::term::is_term,
+};
+
+use std::io::{self, Write};
 
 pub mod refresh_command {
     use super::*;
@@ -11,6 +17,7 @@ pub mod refresh_command {
         "refresh",
         "Refresh a local copy of a pool ledger and updates pool nodes connections."
     )
+    .add_precondition(Precondition::ConnectedPool)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -18,8 +25,8 @@ pub mod refresh_command {
 
         let pool = ctx.ensure_connected_pool()?;
 
-        pool.refresh()
-            .map_err(|err| println_err!("Unable to refresh pool. Reason: {}", err.message(None)))?;
+        pool.refresh_with_progress(&mut |progress| print_refresh_progress(&pool.name, progress))
+            .map_err(|err| println_err!("Unable to refresh pool. Reason: {}", err.report(None)))?;
 
         println_succ!("Pool \"{}\"  has been refreshed", pool.name);
 
@@ -28,6 +35,39 @@ pub mod refresh_command {
     }
 }
 
+/// Prints the catch-up progress instead of waiting silently.
+/// The elapsed time is updated in place and only when the output is a terminal.
+pub fn print_refresh_progress(pool_name: &str, progress: RefreshProgress) {
+    match progress {
+        RefreshProgress::Started { txn_count, nodes } => {
+            println!(
+                "Catching up pool \"{}\" ledger: {} transactions known, {} nodes",
+                pool_name, txn_count, nodes
+            );
+        }
+        RefreshProgress::Waiting { elapsed } => {
+            if is_term() {
+                print!("\rWaiting for nodes... {}s", elapsed.as_secs());
+                io::stdout().flush().ok();
+            }
+        }
+        RefreshProgress::Finished(stats) => {
+            if is_term() {
+                print!("\r{:40}\r", "");
+            }
+            println!(
+                "Catch-up has been completed in {:.1}s: {} new transactions fetched, {} of {} nodes responded",
+                stats.elapsed.as_secs_f32(),
+                stats.fetched,
+                stats.responded,
+                stats.nodes
+            );
+            println!("Pool ledger size: {}", stats.txn_count);
+            println!("Merkle root: {}", stats.merkle_root);
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -52,6 +92,35 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn refresh_works_for_progress() {
+            let ctx = setup();
+            create_and_connect_pool(&ctx);
+            {
+                let pool = ctx.ensure_connected_pool().unwrap();
+                let mut started = false;
+                let mut stats = None;
+                pool.refresh_with_progress(&mut |progress| match progress {
+                    RefreshProgress::Started { txn_count, .. } => {
+                        started = true;
+                        assert!(txn_count > 0);
+                    }
+                    RefreshProgress::Finished(finished) => {
+                        stats = Some((finished.txn_count, finished.merkle_root.clone()))
+                    }
+                    RefreshProgress::Waiting { .. } => {}
+                })
+                .unwrap();
+
+                assert!(started);
+                let (txn_count, merkle_root) = stats.unwrap();
+                assert!(txn_count >= 4);
+                assert!(!merkle_root.is_empty());
+            }
+            disconnect_and_delete_pool(&ctx);
+            tear_down();
+        }
+
         #[test]
         pub fn refresh_works_for_not_opened() {
             let ctx = setup();
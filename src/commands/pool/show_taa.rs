@@ -2,7 +2,15 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::command_executor::{Command, CommandContext, CommandMetadata, CommandParams};
+use crate::{
+    command_executor::{
+        read_user_input, Command, CommandContext, CommandMetadata, CommandParams, Precondition,
+    },
+    ledger::get_acceptance_mechanisms,
+    params_parser::ParamParser,
+    tools::pool::Pool,
+    utils::table::print_list_table,
+};
 
 pub mod show_taa_command {
     use super::*;
@@ -10,18 +18,31 @@ pub mod show_taa_command {
 
     command!(CommandMetadata::build(
         "show-taa",
-        "Show transaction author agreement set on Ledger."
+        "Show transaction author agreement set on Ledger and select the acceptance mechanism to use."
     )
+    .add_optional_param(
+        "acceptance_mechanism",
+        "Label of the acceptance mechanism to use. Asked interactively if not specified"
+    )
+    .add_example("pool show-taa")
+    .add_example("pool show-taa acceptance_mechanism=for_session")
+    .add_precondition(Precondition::ConnectedPool)
     .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
+        let acceptance_mechanism = ParamParser::get_opt_str_param("acceptance_mechanism", params)?;
+
         let pool = ctx.ensure_connected_pool()?;
 
         match set_transaction_author_agreement(ctx, &pool, false) {
             Err(_) => (),
-            Ok(Some(_)) => (),
+            Ok(Some(_)) => {
+                if ctx.get_transaction_author_info().is_some() {
+                    select_acceptance_mechanism(ctx, &pool, acceptance_mechanism)?;
+                }
+            }
             Ok(None) => {
                 println!("There is no transaction agreement set on the Pool.");
             }
@@ -31,3 +52,121 @@ pub mod show_taa_command {
         Ok(())
     }
 }
+
+/// Shows acceptance mechanisms set on the Ledger and stores the chosen one into CLI context.
+fn select_acceptance_mechanism(
+    ctx: &CommandContext,
+    pool: &Pool,
+    acceptance_mechanism: Option<&str>,
+) -> Result<(), ()> {
+    let aml = get_acceptance_mechanisms(pool)?;
+
+    if aml.is_empty() {
+        println_warn!("There are no acceptance mechanisms set on the Ledger.");
+        return Ok(());
+    }
+
+    let current = ctx.get_taa_acceptance_mechanism();
+
+    let rows = aml
+        .iter()
+        .enumerate()
+        .map(|(index, (label, description))| {
+            json!({
+                "index": index + 1,
+                "label": label,
+                "description": description,
+                "selected": if *label == current { "*" } else { "" },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    println!("Following Acceptance Mechanisms are set on the Ledger");
+    print_list_table(
+        &rows,
+        &[
+            ("index", "#"),
+            ("label", "Label"),
+            ("description", "Description"),
+            ("selected", "Selected"),
+        ],
+        "",
+    );
+
+    let label = match acceptance_mechanism {
+        Some(acceptance_mechanism) => find_acceptance_mechanism(&aml, acceptance_mechanism)
+            .ok_or_else(|| {
+                println_err!(
+                    "Acceptance mechanism \"{}\" is not set on the Ledger",
+                    acceptance_mechanism
+                )
+            })?,
+        None => loop {
+            let prompt = if current.is_empty() {
+                "Select acceptance mechanism (number or label): ".to_string()
+            } else {
+                format!(
+                    "Select acceptance mechanism (number or label, Enter to keep \"{}\"): ",
+                    current
+                )
+            };
+
+            match read_user_input(ctx, &prompt) {
+                Some(input) if !input.is_empty() => match find_acceptance_mechanism(&aml, &input) {
+                    Some(label) => break label,
+                    None => println_err!("Unknown acceptance mechanism \"{}\"", input),
+                },
+                Some(_) if current.is_empty() => continue,
+                _ => {
+                    if current.is_empty() {
+                        println_warn!("Acceptance mechanism has not been selected. Use `pool show-taa acceptance_mechanism=<label>` to select it.");
+                    }
+                    return Ok(());
+                }
+            }
+        },
+    };
+
+    ctx.set_taa_acceptance_mechanism(label);
+    println_succ!("Acceptance mechanism \"{}\" will be used", label);
+    Ok(())
+}
+
+/// Resolves the user choice given either as 1-based position in the list or as a label.
+fn find_acceptance_mechanism<'a>(aml: &'a [(String, String)], input: &str) -> Option<&'a str> {
+    let input = input.trim();
+    let by_index = input
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|index| aml.get(index));
+
+    by_index
+        .or_else(|| aml.iter().find(|(label, _)| label == input))
+        .map(|(label, _)| label.as_str())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn aml() -> Vec<(String, String)> {
+        vec![
+            ("at_submission".to_string(), "At submission".to_string()),
+            ("for_session".to_string(), "For session".to_string()),
+        ]
+    }
+
+    #[test]
+    pub fn find_acceptance_mechanism_works() {
+        let aml = aml();
+        assert_eq!(Some("for_session"), find_acceptance_mechanism(&aml, "2"));
+        assert_eq!(
+            Some("at_submission"),
+            find_acceptance_mechanism(&aml, "at_submission")
+        );
+        assert_eq!(None, find_acceptance_mechanism(&aml, "0"));
+        assert_eq!(None, find_acceptance_mechanism(&aml, "3"));
+        assert_eq!(None, find_acceptance_mechanism(&aml, "on_file"));
+    }
+}
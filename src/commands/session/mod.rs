@@ -0,0 +1,19 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::command_executor::{CommandGroup, CommandGroupMetadata};
+
+pub mod restore;
+pub mod save;
+
+pub use self::{restore::*, save::*};
+
+pub mod group {
+    use super::*;
+
+    command_group!(CommandGroupMetadata::new(
+        "session",
+        "CLI session management commands"
+    ));
+}
@@ -0,0 +1,147 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    pool::connect_pool,
+    tools::session::SessionState,
+};
+
+use indy_utils::did::DidValue;
+
+pub mod restore_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "restore",
+        "Restore the CLI session state saved by `session save` command: connect to the pool, set the active DID, accepted TAA, protocol version and stored transaction.
+        The wallet is not opened: use `wallet open` command with the wallet key."
+    )
+    .add_main_param("file", "Path to the file with the session state")
+    .add_example("session restore /home/session.json")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let file = ParamParser::get_str_param("file", params)?;
+
+        let state = SessionState::read(file).map_err(|err| println_err!("{}", err.report(None)))?;
+
+        if let Some(protocol_version) = state.protocol_version {
+            ctx.set_pool_protocol_version(protocol_version);
+        }
+
+        if let Some(ref name) = state.pool {
+            connect_pool(ctx, name, state.protocol_version)?;
+        }
+
+        if let Some(taa) = state.taa {
+            ctx.set_taa_acceptance_mechanism(&taa.acceptance_mechanism);
+            ctx.set_transaction_author_info(Some((taa.text, taa.version, taa.time_of_acceptance)));
+        }
+
+        if let Some(did) = state.did {
+            ctx.set_active_did(DidValue(did.clone()));
+            println_succ!("Did \"{}\" has been set as active", did);
+        }
+
+        if state.transaction.is_some() {
+            ctx.set_context_transaction(state.transaction);
+        }
+
+        println_succ!("Session state has been restored from \"{}\"", file);
+
+        if let Some(wallet) = state.wallet {
+            let is_opened = ctx
+                .get_opened_wallet()
+                .map(|opened| opened.name == wallet)
+                .unwrap_or(false);
+            if !is_opened {
+                println_warn!(
+                    "Wallet \"{}\" was opened in the saved session. Use `wallet open {} key` to open it.",
+                    wallet,
+                    wallet
+                );
+            }
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, tear_down},
+        did::tests::DID_TRUSTEE,
+        pool::{
+            close_pool,
+            tests::{create_pool, delete_pool, POOL},
+        },
+        tools::session::SessionTaa,
+        utils::environment::EnvironmentUtils,
+    };
+
+    mod restore {
+        use super::*;
+
+        #[test]
+        pub fn restore_works() {
+            let ctx = setup();
+            create_pool(&ctx);
+
+            let path = EnvironmentUtils::tmp_file_path("session_restore_works.json");
+            let path = path.to_str().unwrap();
+            SessionState {
+                pool: Some(POOL.to_string()),
+                did: Some(DID_TRUSTEE.to_string()),
+                protocol_version: Some(1),
+                taa: Some(SessionTaa {
+                    text: "text".to_string(),
+                    version: "1.0".to_string(),
+                    acceptance_mechanism: "on_file".to_string(),
+                    time_of_acceptance: 1700000000,
+                }),
+                transaction: Some(r#"{"reqId":1}"#.to_string()),
+                ..SessionState::default()
+            }
+            .write(path)
+            .unwrap();
+            {
+                let cmd = restore_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            assert_eq!(POOL, ctx.ensure_connected_pool().unwrap().name);
+            assert_eq!(DID_TRUSTEE, ctx.ensure_active_did().unwrap().to_string());
+            assert_eq!(1, ctx.get_pool_protocol_version());
+            assert_eq!(Some(1), ctx.get_user_pool_protocol_version());
+            assert!(ctx.get_transaction_author_info().is_some());
+            assert!(ctx.get_context_transaction().is_some());
+
+            std::fs::remove_file(path).unwrap();
+            close_pool(&ctx, &ctx.ensure_connected_pool().unwrap()).unwrap();
+            delete_pool(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn restore_works_for_missed_file() {
+            let ctx = setup();
+            {
+                let cmd = restore_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", "/tmp/unknown_session.json".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
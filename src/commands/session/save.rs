@@ -0,0 +1,94 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::session::{SessionState, SessionTaa},
+};
+
+pub mod save_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "save",
+        "Save non-secret state of the CLI session (connected pool, opened wallet name, active DID, accepted TAA, protocol version, stored transaction) into the file."
+    )
+    .add_main_param("file", "Path to the file to store the session state")
+    .add_example("session save /home/session.json")
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let file = ParamParser::get_str_param("file", params)?;
+
+        let state = SessionState {
+            pool: ctx.get_connected_pool().map(|pool| pool.name.to_string()),
+            wallet: ctx
+                .get_opened_wallet()
+                .map(|wallet| wallet.name.to_string()),
+            did: ctx.get_active_did()?.map(|did| did.to_string()),
+            protocol_version: ctx.get_user_pool_protocol_version(),
+            taa: ctx.get_transaction_author_info().map(
+                |(text, version, acceptance_mechanism, time_of_acceptance)| SessionTaa {
+                    text,
+                    version,
+                    acceptance_mechanism,
+                    time_of_acceptance,
+                },
+            ),
+            transaction: ctx.get_context_transaction(),
+        };
+
+        state
+            .write(file)
+            .map_err(|err| println_err!("{}", err.report(None)))?;
+
+        println_succ!("Session state has been saved into \"{}\"", file);
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{new_did, use_did, DID_TRUSTEE, SEED_TRUSTEE},
+        utils::environment::EnvironmentUtils,
+    };
+
+    mod save {
+        use super::*;
+
+        #[test]
+        pub fn save_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            use_did(&ctx, DID_TRUSTEE);
+            ctx.set_context_transaction(Some(r#"{"reqId":1}"#.to_string()));
+
+            let path = EnvironmentUtils::tmp_file_path("session_save_works.json");
+            let path = path.to_str().unwrap();
+            {
+                let cmd = save_command::new();
+                let mut params = CommandParams::new();
+                params.insert("file", path.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let state = SessionState::read(path).unwrap();
+            assert_eq!(Some(DID_TRUSTEE.to_string()), state.did);
+            assert!(state.wallet.is_some());
+            assert!(state.pool.is_none());
+            assert_eq!(Some(r#"{"reqId":1}"#.to_string()), state.transaction);
+
+            std::fs::remove_file(path).unwrap();
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
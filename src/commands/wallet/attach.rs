@@ -35,6 +35,8 @@ pub mod attach_command {
             id: id.to_string(),
             storage_type: storage_type.to_string(),
             storage_config,
+            storage_credentials: None,
+            active_did: None,
         };
 
         if config.exists() {
@@ -0,0 +1,249 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    tools::wallet::check::WalletIssue,
+    utils::table::print_list_table,
+};
+
+pub mod check_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "check",
+        "Check integrity of DID and key records of the opened wallet."
+    )
+    .add_optional_param(
+        "repair",
+        "Remove or fix the records which can be repaired (false by default)"
+    )
+    .add_example("wallet check")
+    .add_example("wallet check repair=true")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let repair = ParamParser::get_opt_bool_param("repair", params)?.unwrap_or(false);
+
+        let report = wallet
+            .check()
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        println!(
+            "Checked {} DID records and {} keys of wallet \"{}\".",
+            report.dids, report.keys, wallet.name
+        );
+
+        if report.issues.is_empty() {
+            println_succ!("No problems have been found");
+            return Ok(());
+        }
+
+        print_issues(&report.issues);
+
+        if !repair {
+            println_err!(
+                "{} problems have been found. Use `repair=true` to fix the repairable ones.",
+                report.issues.len()
+            );
+            return Err(());
+        }
+
+        let mut unresolved = 0;
+        for issue in report.issues.iter() {
+            if issue.repair_action().is_none() {
+                unresolved += 1;
+                continue;
+            }
+            match wallet.repair(issue) {
+                Ok(()) => println_succ!(
+                    "{}: {}",
+                    issue.subject(),
+                    issue.repair_action().unwrap_or_default()
+                ),
+                Err(err) => {
+                    unresolved += 1;
+                    println_err!(
+                        "{}: unable to repair: {}",
+                        issue.subject(),
+                        err.report(Some(&wallet.name))
+                    )
+                }
+            }
+        }
+
+        if unresolved > 0 {
+            println_warn!("{} problems have not been repaired", unresolved);
+            return Err(());
+        }
+
+        println_succ!("Wallet \"{}\" has been repaired", wallet.name);
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn print_issues(issues: &[WalletIssue]) {
+        let rows: Vec<serde_json::Value> = issues
+            .iter()
+            .map(|issue| {
+                json!({
+                    "subject": issue.subject(),
+                    "problem": issue.description(),
+                    "repair": issue.repair_action().unwrap_or("Manual action required"),
+                })
+            })
+            .collect();
+
+        print_list_table(
+            &rows,
+            &[
+                ("subject", "DID or key"),
+                ("problem", "Problem"),
+                ("repair", "Repair"),
+            ],
+            "",
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{new_did, DID_TRUSTEE, SEED_TRUSTEE},
+        tools::{
+            did::{constants::CATEGORY_DID, key::Key, Did},
+            wallet::Wallet,
+        },
+        utils::futures::block_on,
+    };
+    use aries_askar::kms::KeyAlg;
+    use indy_utils::did::DidValue;
+
+    fn set_next_verkey(wallet: &Wallet, did: &str, verkey: &str) {
+        let mut did_info = Did::get(wallet, &DidValue(did.to_string())).unwrap();
+        did_info.next_verkey = Some(verkey.to_string());
+        block_on(wallet.store_record(
+            CATEGORY_DID,
+            did,
+            &did_info.to_bytes().unwrap(),
+            Some(&did_info.tags()),
+            false,
+        ))
+        .unwrap();
+    }
+
+    mod check {
+        use super::*;
+
+        #[test]
+        pub fn check_works_for_consistent_wallet() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            {
+                let cmd = check_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn check_works_for_dangling_next_verkey() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            set_next_verkey(
+                &wallet,
+                DID_TRUSTEE,
+                "CnEDk9HrMnmiHXEV1WFgbVCRteYnPqsJwrTdcZaNhFVW",
+            );
+            {
+                let cmd = check_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            {
+                let cmd = check_command::new();
+                let mut params = CommandParams::new();
+                params.insert("repair", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let did_info = Did::get(&wallet, &DidValue(DID_TRUSTEE.to_string())).unwrap();
+            assert_eq!(None, did_info.next_verkey);
+            assert!(wallet.check().unwrap().issues.is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn check_works_for_invalid_did_record() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            block_on(wallet.store_record(CATEGORY_DID, "broken", b"not a did", None, true))
+                .unwrap();
+            {
+                let cmd = check_command::new();
+                let mut params = CommandParams::new();
+                params.insert("repair", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(0, wallet.check().unwrap().dids);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn check_works_for_orphaned_key() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let (did, _) = Did::create(&wallet, None, None, None, None, None, None).unwrap();
+            block_on(wallet.remove_record(CATEGORY_DID, &did)).unwrap();
+
+            let report = wallet.check().unwrap();
+            assert_eq!(1, report.issues.len());
+            assert!(matches!(report.issues[0], WalletIssue::OrphanedKey { .. }));
+            {
+                let cmd = check_command::new();
+                let mut params = CommandParams::new();
+                params.insert("repair", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            // orphaned keys are only reported
+            assert_eq!(1, wallet.check().unwrap().keys);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn check_works_for_standalone_and_rotated_keys() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Key::create_named(&wallet, None, KeyAlg::Ed25519, None, None).unwrap();
+            let (did, _) = Did::create(&wallet, None, None, None, None, None, None).unwrap();
+            Did::replace_keys_start(&wallet, &did, None).unwrap();
+            Did::replace_keys_apply(&wallet, &did).unwrap();
+
+            let report = wallet.check().unwrap();
+            assert_eq!(3, report.keys);
+            assert!(report.issues.is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn check_works_for_secp256k1_did() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Did::create(&wallet, None, None, None, None, None, Some(KeyAlg::K256)).unwrap();
+
+            assert!(wallet.check().unwrap().issues.is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
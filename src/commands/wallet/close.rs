@@ -36,7 +36,7 @@ pub fn close_wallet(ctx: &CommandContext, wallet: Wallet) -> Result<(), ()> {
             ctx.reset_active_did();
             println_succ!("Wallet \"{}\" has been closed", name);
         })
-        .map_err(|err| println_err!("{}", err.message(Some(&name))))
+        .map_err(|err| println_err!("{}", err.report(Some(&name))))
 }
 
 #[cfg(test)]
@@ -3,9 +3,10 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, SecretPolicy},
     params_parser::ParamParser,
     tools::wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+    wallet::key_derivation_method_or_default,
 };
 
 pub mod create_command {
@@ -13,8 +14,9 @@ pub mod create_command {
 
     command!(CommandMetadata::build("create", "Create new wallet and attach to Indy CLI")
                 .add_main_param("name", "Identifier of the wallet")
-                .add_required_deferred_param("key", "Key or passphrase used for wallet key derivation.
-                                               Look to key_derivation_method param for information about supported key derivation methods.")
+                .add_required_deferred_param_with_policy("key", "Key or passphrase used for wallet key derivation.
+                                               Look to key_derivation_method param for information about supported key derivation methods.
+                                               Deferred passphrase is requested twice and must not be too weak (except raw keys).", KEY_POLICY)
                 .add_optional_param("key_derivation_method", "Algorithm to use for wallet key derivation. One of:
                                     argon2m - derive secured wallet key (used by default)
                                     argon2i - derive secured wallet key (less secured but faster)
@@ -22,12 +24,29 @@ pub mod create_command {
                 .add_optional_param("storage_type", "Type of the wallet storage.")
                 .add_optional_param("storage_config", "The list of key:value pairs defined by storage type.")
                 .add_optional_param("storage_credentials", "The list of key:value pairs defined by storage type.")
+                .add_optional_param("no-confirm", "Skip confirmation and strength check of deferred key (False by default). Intended for automation")
                 .add_example("wallet create wallet1 key")
+                .add_example("wallet create wallet1 key no-confirm=true")
                 .add_example("wallet create wallet1 key storage_type=default")
                 .add_example(r#"wallet create wallet1 key storage_type=default storage_config={"key1":"value1","key2":"value2"}"#)
                 .finalize()
     );
 
+    const KEY_POLICY: SecretPolicy = SecretPolicy {
+        is_required: is_key_policy_required,
+        check: check_key,
+    };
+
+    pub(super) fn is_key_policy_required(params: &CommandParams) -> bool {
+        let no_confirm = params.get("no-confirm").map(String::as_str) == Some("true");
+        let is_raw = params.get("key_derivation_method").map(String::as_str) == Some("raw");
+        !no_confirm && !is_raw
+    }
+
+    fn check_key(key: &str) -> Result<(), String> {
+        Credentials::check_key_strength(key).map_err(|err| err.message(None))
+    }
+
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, secret!(params));
 
@@ -44,10 +63,12 @@ pub mod create_command {
             id: id.to_string(),
             storage_type: storage_type.to_string(),
             storage_config,
+            storage_credentials: None,
+            active_did: None,
         };
         let credentials = Credentials {
             key: key.to_string(),
-            key_derivation_method: key_derivation_method.map(String::from),
+            key_derivation_method: key_derivation_method_or_default(ctx, key_derivation_method),
             storage_credentials,
             ..Credentials::default()
         };
@@ -55,7 +76,7 @@ pub mod create_command {
         trace!("Wallet::create_wallet try: config {:?}", config);
 
         Wallet::create(&config, &credentials)
-            .map_err(|err| println_err!("{}", err.message(Some(&id))))?;
+            .map_err(|err| println_err!("{}", err.report(Some(&id))))?;
 
         config
             .store()
@@ -202,6 +223,7 @@ pub mod tests {
                 params.insert("name", WALLET.to_string());
                 params.insert("key", WALLET_KEY.to_string());
                 params.insert("key_derivation_method", "argon2m".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&ctx, &params).unwrap();
             }
             tear_down();
@@ -220,5 +242,19 @@ pub mod tests {
             }
             tear_down();
         }
+
+        #[test]
+        pub fn is_key_policy_required_works() {
+            let mut params = CommandParams::new();
+            params.insert("name", WALLET.to_string());
+            assert!(create_command::is_key_policy_required(&params));
+
+            params.insert("no-confirm", "true".to_string());
+            assert!(!create_command::is_key_policy_required(&params));
+
+            params.insert("no-confirm", "false".to_string());
+            params.insert("key_derivation_method", "raw".to_string());
+            assert!(!create_command::is_key_policy_required(&params));
+        }
     }
 }
@@ -4,11 +4,12 @@
 */
 use crate::{
     command_executor::{
-        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+        read_user_input, Command, CommandContext, CommandMetadata, CommandParams,
+        DynamicCompletionType,
     },
     params_parser::ParamParser,
     tools::wallet::{wallet_config::WalletConfig, Credentials, Wallet},
-    wallet::close_wallet,
+    wallet::{auto_backup_wallet, close_wallet, key_derivation_method_or_default},
 };
 
 pub mod delete_command {
@@ -23,7 +24,9 @@ pub mod delete_command {
                                     argon2i - derive secured wallet key (less secured but faster)
                                     raw - raw key provided (skip derivation)")
                 .add_optional_param("storage_credentials", "The list of key:value pairs defined by storage type.")
+                .add_optional_param("force", "Delete the wallet without typing its name to confirm (false by default). Required in batch mode")
                 .add_example("wallet delete wallet1 key")
+                .add_example("wallet delete wallet1 key force=true")
                 .finalize()
     );
 
@@ -35,23 +38,32 @@ pub mod delete_command {
         let key_derivation_method =
             ParamParser::get_opt_str_param("key_derivation_method", params)?;
         let storage_credentials = ParamParser::get_opt_object_param("storage_credentials", params)?;
+        let force = ParamParser::get_opt_bool_param("force", params)?.unwrap_or(false);
 
         let config = WalletConfig::read(id)
             .map_err(|_| println_err!("Wallet \"{}\" isn't attached to CLI", id))?;
 
         let credentials = Credentials {
             key: key.to_string(),
-            key_derivation_method: key_derivation_method.map(String::from),
+            key_derivation_method: key_derivation_method_or_default(ctx, key_derivation_method),
             storage_credentials,
             ..Credentials::default()
         };
 
+        print_records_count(ctx, &config, &credentials);
+
+        if !force && !confirm_deletion(ctx, id) {
+            return Err(());
+        }
+
+        auto_backup_wallet(ctx, &config, &credentials, "delete")?;
+
         if let Some(wallet) = ctx.take_opened_wallet()? {
             close_wallet(ctx, wallet)?;
         }
 
         Wallet::delete(&config, &credentials)
-            .map_err(|err| println_err!("{}", err.message(Some(id))))?;
+            .map_err(|err| println_err!("{}", err.report(Some(id))))?;
 
         config
             .delete()
@@ -62,12 +74,62 @@ pub mod delete_command {
         trace!("execute <<");
         Ok(())
     }
+
+    /// Prints the number of records which will be destroyed. The wallet is opened only for counting.
+    fn print_records_count(ctx: &CommandContext, config: &WalletConfig, credentials: &Credentials) {
+        let counts = match ctx.get_opened_wallet() {
+            Some(wallet) if wallet.name == config.id => wallet.count_records(),
+            _ => Wallet::open(config, credentials, None, None).and_then(|wallet| {
+                let counts = wallet.count_records();
+                wallet.close()?;
+                counts
+            }),
+        };
+
+        match counts {
+            Ok(counts) => println_warn!(
+                "Wallet \"{}\" contains {} DID(s) and {} key(s) which will be destroyed",
+                config.id,
+                counts.get("did").unwrap_or(&0),
+                counts.get("key").unwrap_or(&0)
+            ),
+            Err(err) => println_warn!(
+                "Unable to count records of wallet \"{}\": {}",
+                config.id,
+                err.message(Some(&config.id))
+            ),
+        }
+    }
+
+    /// Asks to retype the wallet name as deletion is unrecoverable. Batch mode requires `force=true`.
+    fn confirm_deletion(ctx: &CommandContext, id: &str) -> bool {
+        if ctx.is_batch_mode() {
+            println_err!("Wallet deletion in batch mode requires `force=true` parameter");
+            return false;
+        }
+
+        let prompt = format!("Type the wallet name \"{}\" to confirm the deletion: ", id);
+        match read_user_input(ctx, &prompt) {
+            Some(name) if name == id => true,
+            _ => {
+                println_err!(
+                    "Wallet name does not match. Wallet \"{}\" has not been deleted",
+                    id
+                );
+                false
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::commands::{setup, tear_down};
+    use crate::{
+        commands::{setup, tear_down},
+        tools::wallet::backup::AutoBackupDir,
+        utils::environment::EnvironmentUtils,
+    };
 
     mod delete {
         use super::*;
@@ -85,6 +147,7 @@ pub mod tests {
                 params.insert("name", WALLET.to_string());
                 params.insert("key", WALLET_KEY_RAW.to_string());
                 params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&CommandContext::new(), &params).unwrap();
             }
             let wallets = Wallet::list();
@@ -93,6 +156,32 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn delete_works_for_auto_backup() {
+            let ctx = setup();
+            let dir = EnvironmentUtils::tmp_file_path("auto_backups");
+            ctx.set_auto_backup_dir(Some(dir.to_string_lossy().to_string()));
+            ctx.set_auto_backup_keep(1);
+            for _ in 0..2 {
+                create_wallet(&ctx);
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert_eq!(0, Wallet::list().len());
+            // the older backup has been pruned
+            let backups = AutoBackupDir::new(dir.to_str().unwrap())
+                .list(WALLET)
+                .unwrap();
+            assert_eq!(1, backups.len());
+            assert!(backups[0].to_string_lossy().ends_with("-delete.backup"));
+            tear_down();
+        }
+
         #[test]
         pub fn delete_works_for_not_created() {
             let ctx = setup();
@@ -101,6 +190,7 @@ pub mod tests {
             let mut params = CommandParams::new();
             params.insert("name", WALLET.to_string());
             params.insert("key", WALLET_KEY.to_string());
+            params.insert("force", "true".to_string());
             cmd.execute(&ctx, &params).unwrap_err();
 
             tear_down();
@@ -116,6 +206,7 @@ pub mod tests {
                 params.insert("name", WALLET.to_string());
                 params.insert("key", WALLET_KEY_RAW.to_string());
                 params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&ctx, &params).unwrap();
             }
             tear_down();
@@ -130,9 +221,45 @@ pub mod tests {
                 let mut params = CommandParams::new();
                 params.insert("name", WALLET.to_string());
                 params.insert("key", "other_key".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&ctx, &params).unwrap(); // Askar does not check credentials!
             }
             tear_down();
         }
+
+        #[test]
+        pub fn delete_works_for_not_confirmed() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(1, Wallet::list().len());
+            delete_wallet(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn delete_works_for_batch_mode_without_force() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            ctx.set_batch_mode();
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert_eq!(1, Wallet::list().len());
+            delete_wallet(&ctx);
+            tear_down();
+        }
     }
 }
@@ -3,23 +3,31 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
     params_parser::ParamParser,
 };
 
 pub mod export_command {
     use super::*;
-    use crate::tools::wallet::ExportConfig;
+    use crate::{commands::wallet::get_key_params, tools::wallet::ExportConfig};
 
     command!(CommandMetadata::build("export", "Export opened wallet to the file")
                 .add_required_param("export_path", "Path to the export file")
-                .add_required_deferred_param("export_key", "Key or passphrase used for export wallet key derivation.
+                .add_optional_deferred_param("export_key", "Key or passphrase used for export wallet key derivation (required unless export_key_file is set).
                                                Look to key_derivation_method param for information about supported key derivation methods.")
+                .add_optional_param("export_key_file", "Path to the file containing raw export key: 32 bytes or their base58 encoding.
+                                    The file must be accessible only by its owner. Implies `raw` export key derivation method.")
                 .add_optional_param("export_key_derivation_method", "Algorithm to use for export key derivation. One of:
                                     argon2m - derive secured export key (used by default)
                                     argon2i - derive secured export key (less secured but faster)
                                     raw - raw export key provided (skip derivation)")
+                .add_optional_param("export_format", "Format of the export file. One of:
+                                    askar - copy of the wallet as Askar SQLite store (used by default)
+                                    stream - single file of encrypted and integrity protected chunks")
                 .add_example("wallet export export_path=/home/indy/export_wallet export_key")
+                .add_example("wallet export export_path=/home/indy/export_wallet export_key export_format=stream")
+                .add_example("wallet export export_path=/home/indy/export_wallet export_key_file=/run/secrets/export_key")
+                .add_precondition(Precondition::OpenedWallet)
                 .finalize()
     );
 
@@ -29,14 +37,21 @@ pub mod export_command {
         let wallet = ctx.ensure_opened_wallet()?;
 
         let export_path = ParamParser::get_str_param("export_path", params)?;
-        let export_key = ParamParser::get_str_param("export_key", params)?;
-        let export_key_derivation_method =
-            ParamParser::get_opt_str_param("export_key_derivation_method", params)?;
+        let (export_key, export_key_derivation_method) = get_key_params(
+            params,
+            "export_key",
+            "export_key_file",
+            "export_key_derivation_method",
+        )?;
+        let export_key = export_key
+            .ok_or_else(|| println_err!("No required \"export_key\" parameter present"))?;
+        let export_format = ParamParser::get_opt_str_param("export_format", params)?;
 
         let export_config = ExportConfig {
             path: export_path.to_string(),
-            key: export_key.to_string(),
-            key_derivation_method: export_key_derivation_method.map(String::from),
+            key: export_key,
+            key_derivation_method: export_key_derivation_method,
+            format: export_format.map(String::from),
         };
 
         trace!(
@@ -46,8 +61,10 @@ pub mod export_command {
         );
 
         wallet
-            .export(&export_config)
-            .map_err(|err| println_err!("{}", err.message(Some(&wallet.name))))?;
+            .export(&export_config, &|copied| {
+                println!("Copied {} records...", copied)
+            })
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
 
         println_succ!(
             "Wallet \"{}\" has been exported to the file \"{}\"",
@@ -68,7 +85,8 @@ pub mod tests {
     mod export {
         use super::*;
         use crate::wallet::tests::{
-            export_wallet, export_wallet_path, EXPORT_KEY, EXPORT_KEY_DERIVATION_METHOD,
+            export_wallet, export_wallet_path, write_key_file, EXPORT_KEY,
+            EXPORT_KEY_DERIVATION_METHOD,
         };
 
         #[test]
@@ -92,6 +110,100 @@ pub mod tests {
             tear_down_with_wallet(&ctx);
         }
 
+        #[test]
+        pub fn export_works_for_stream_format() {
+            let ctx = setup_with_wallet();
+
+            let (path, path_str) = export_wallet_path();
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str);
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert(
+                    "export_key_derivation_method",
+                    EXPORT_KEY_DERIVATION_METHOD.to_string(),
+                );
+                params.insert("export_format", "stream".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            assert!(path.is_file());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn export_works_for_unknown_format() {
+            let ctx = setup_with_wallet();
+
+            let (path, path_str) = export_wallet_path();
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str);
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert("export_format", "tar".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+
+            assert!(!path.exists());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn export_works_for_key_file() {
+            let ctx = setup_with_wallet();
+
+            let (path, path_str) = export_wallet_path();
+            let key_file = write_key_file("export_key_file", 0o600);
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str);
+                params.insert("export_key_file", key_file);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            assert!(path.exists());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        #[cfg(unix)]
+        pub fn export_works_for_key_file_accessible_by_others() {
+            let ctx = setup_with_wallet();
+
+            let (path, path_str) = export_wallet_path();
+            let key_file = write_key_file("export_key_file_open", 0o644);
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str);
+                params.insert("export_key_file", key_file);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+
+            assert!(!path.exists());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn export_works_for_key_and_key_file() {
+            let ctx = setup_with_wallet();
+
+            let (_, path_str) = export_wallet_path();
+            let key_file = write_key_file("export_key_file_both", 0o600);
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str);
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert("export_key_file", key_file);
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
         #[test]
         pub fn export_works_for_file_already_exists() {
             let ctx = setup_with_wallet();
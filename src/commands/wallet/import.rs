@@ -7,17 +7,20 @@ use crate::{
         Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
     },
     params_parser::ParamParser,
-    tools::wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+    tools::wallet::{wallet_config::WalletConfig, Credentials, ImportReport, Wallet},
+    utils::table::print_list_table,
 };
 
 pub mod import_command {
     use super::*;
-    use crate::tools::wallet::ImportConfig;
+    use crate::{commands::wallet::get_key_params, tools::wallet::ImportConfig};
 
     command!(CommandMetadata::build("import", "Create new wallet, attach to Indy CLI and then import content from the specified file")
                 .add_main_param_with_dynamic_completion("name", "The name of new wallet", DynamicCompletionType::Wallet)
-                .add_required_deferred_param("key", "Key or passphrase used for wallet key derivation.
+                .add_optional_deferred_param("key", "Key or passphrase used for wallet key derivation (required unless dry_run is set).
                                                Look to key_derivation_method param for information about supported key derivation methods.")
+                .add_optional_param("key_file", "Path to the file containing raw wallet key: 32 bytes or their base58 encoding.
+                                    The file must be accessible only by its owner. Implies `raw` key derivation method.")
                 .add_optional_param("key_derivation_method", "Algorithm to use for wallet key derivation. One of:
                                     argon2m - derive secured wallet key (used by default)
                                     argon2i - derive secured wallet key (less secured but faster)
@@ -26,10 +29,15 @@ pub mod import_command {
                 .add_optional_param("storage_config", "The list of key:value pairs defined by storage type.")
                 .add_optional_param("storage_credentials", "The list of key:value pairs defined by storage type.")
                 .add_required_param("export_path", "Path to the file that contains exported wallet content")
-                .add_required_deferred_param("export_key", "Key used for export of the wallet")
-                .add_required_deferred_param("export_key_derivation_method", "Algorithm to use for export key derivation")
+                .add_optional_deferred_param("export_key", "Key used for export of the wallet (required unless export_key_file is set)")
+                .add_optional_param("export_key_file", "Path to the file containing raw key used for export of the wallet. Implies `raw` export key derivation method.")
+                .add_optional_deferred_param("export_key_derivation_method", "Algorithm to use for export key derivation")
+                .add_optional_param("preserve_unknown", "Libindy backups only: keep records of types unknown to CLI under their original type and keep original record tags (false by default)")
+                .add_optional_param("dry_run", "Only validate all records of the backup and print their counts per type without creating the wallet (false by default)")
                 .add_example("wallet import wallet1 key export_path=/home/indy/export_wallet export_key")
                 .add_example(r#"wallet import wallet1 key export_path=/home/indy/export_wallet export_key storage_type=default storage_config={"key1":"value1","key2":"value2"}"#)
+                .add_example("wallet import wallet1 export_path=/home/indy/export_wallet export_key dry_run=true")
+                .add_example("wallet import wallet1 key_file=/run/secrets/wallet_key export_path=/home/indy/export_wallet export_key_file=/run/secrets/export_key")
                 .finalize()
     );
 
@@ -37,33 +45,51 @@ pub mod import_command {
         trace!("execute >> ctx {:?} params {:?}", ctx, secret!(params));
 
         let id = ParamParser::get_str_param("name", params)?;
-        let key = ParamParser::get_str_param("key", params)?;
-        let key_derivation_method =
-            ParamParser::get_opt_str_param("key_derivation_method", params)?;
+        let (key, key_derivation_method) =
+            get_key_params(params, "key", "key_file", "key_derivation_method")?;
         let export_path = ParamParser::get_str_param("export_path", params)?;
-        let export_key = ParamParser::get_str_param("export_key", params)?;
-        let export_key_derivation_method =
-            ParamParser::get_opt_str_param("export_key_derivation_method", params)?;
+        let (export_key, export_key_derivation_method) = get_key_params(
+            params,
+            "export_key",
+            "export_key_file",
+            "export_key_derivation_method",
+        )?;
+        let export_key = export_key
+            .ok_or_else(|| println_err!("No required \"export_key\" parameter present"))?;
         let storage_type =
             ParamParser::get_opt_str_param("storage_type", params)?.unwrap_or("default");
         let storage_config = ParamParser::get_opt_object_param("storage_config", params)?;
         let storage_credentials = ParamParser::get_opt_object_param("storage_credentials", params)?;
+        let dry_run = ParamParser::get_opt_bool_param("dry_run", params)?.unwrap_or(false);
+        let preserve_unknown =
+            ParamParser::get_opt_bool_param("preserve_unknown", params)?.unwrap_or(false);
+
+        let import_config = ImportConfig {
+            path: export_path.to_string(),
+            key: export_key,
+            key_derivation_method: export_key_derivation_method,
+            preserve_unknown,
+        };
+
+        if dry_run {
+            let report = Wallet::inspect_backup(&import_config)
+                .map_err(|err| println_err!("{}", err.report(Some(export_path))))?;
+            return print_import_report(export_path, &report, preserve_unknown);
+        }
+
+        let key = key.ok_or_else(|| println_err!("No required \"key\" parameter present"))?;
 
         let config = WalletConfig {
             id: id.to_string(),
             storage_type: storage_type.to_string(),
             storage_config,
-        };
-
-        let import_config = ImportConfig {
-            path: export_path.to_string(),
-            key: export_key.to_string(),
-            key_derivation_method: export_key_derivation_method.map(String::from),
+            storage_credentials: None,
+            active_did: None,
         };
 
         let credentials = Credentials {
-            key: key.to_string(),
-            key_derivation_method: key_derivation_method.map(String::from),
+            key,
+            key_derivation_method,
             rekey: None,
             rekey_derivation_method: None,
             storage_credentials,
@@ -80,8 +106,18 @@ pub mod import_command {
             secret!(&import_config)
         );
 
-        Wallet::import(&config, &credentials, &import_config)
-            .map_err(|err| println_err!("{}", err.message(Some(id))))?;
+        let report = Wallet::import(&config, &credentials, &import_config, &|copied| {
+            println!("Copied {} records...", copied)
+        })
+        .map_err(|err| println_err!("{}", err.report(Some(id))))?;
+
+        for (type_, count) in report.unsupported.iter() {
+            println_warn!(
+                "{} record(s) of unsupported type \"{}\" have been skipped. Use preserve_unknown=true to keep them.",
+                count,
+                type_
+            );
+        }
 
         config
             .store()
@@ -92,6 +128,54 @@ pub mod import_command {
         trace!("execute <<");
         Ok(())
     }
+
+    fn print_import_report(
+        export_path: &str,
+        report: &ImportReport,
+        preserve_unknown: bool,
+    ) -> Result<(), ()> {
+        let records = report
+            .records
+            .iter()
+            .map(|(type_, count)| json!({ "type": type_, "count": count }))
+            .collect::<Vec<serde_json::Value>>();
+
+        print_list_table(
+            &records,
+            &[("type", "Record Type"), ("count", "Count")],
+            "There are no records to import",
+        );
+
+        for (type_, count) in report.unsupported.iter() {
+            if preserve_unknown {
+                println!(
+                    "{} record(s) of unsupported type \"{}\" will be stored as is",
+                    count, type_
+                );
+            } else {
+                println_warn!(
+                    "{} record(s) of unsupported type \"{}\" will be skipped",
+                    count,
+                    type_
+                );
+            }
+        }
+
+        for invalid in report.invalid.iter() {
+            println_err!("Invalid {}", invalid);
+        }
+
+        if !report.invalid.is_empty() {
+            println_err!("Wallet backup \"{}\" contains invalid records", export_path);
+            return Err(());
+        }
+
+        println_succ!(
+            "Wallet backup \"{}\" has been validated. Nothing has been imported",
+            export_path
+        );
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -104,12 +188,16 @@ pub mod tests {
         use crate::{
             commands::setup_with_wallet,
             did::tests::{new_did, use_did, DID_MY1, SEED_MY1},
+            tools::wallet::{
+                stream_backup::{StreamRecord, StreamWriter},
+                ImportConfig,
+            },
             wallet::{
                 close_command, create_command, delete_command, export_command, open_command,
                 tests::{
                     close_and_delete_wallet, create_and_open_wallet, export_wallet,
-                    export_wallet_path, EXPORT_KEY, EXPORT_KEY_DERIVATION_METHOD, WALLET,
-                    WALLET_KEY, WALLET_KEY_RAW,
+                    export_wallet_path, write_key_file, EXPORT_KEY, EXPORT_KEY_DERIVATION_METHOD,
+                    WALLET, WALLET_KEY, WALLET_KEY_RAW,
                 },
             },
         };
@@ -162,12 +250,207 @@ pub mod tests {
                 params.insert("name", wallet_name.to_string());
                 params.insert("key", WALLET_KEY_RAW.to_string());
                 params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&CommandContext::new(), &params).unwrap();
             }
 
             tear_down();
         }
 
+        #[test]
+        pub fn import_works_for_key_files() {
+            let ctx = setup_with_wallet();
+
+            new_did(&ctx, SEED_MY1);
+            use_did(&ctx, DID_MY1);
+
+            let (_, path_str) = export_wallet_path();
+            export_wallet(&ctx, &path_str);
+
+            let key_file = write_key_file("import_key_file", 0o600);
+
+            let wallet_name = "imported_wallet";
+            {
+                let cmd = import_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("key_file", key_file.clone());
+                params.insert("export_path", path_str);
+                params.insert("export_key_file", key_file);
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            // the wallet key read from the file is raw
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("key", EXPORT_KEY.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let cmd = close_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn import_works_for_dry_run() {
+            let ctx = setup_with_wallet();
+
+            new_did(&ctx, SEED_MY1);
+
+            let (_, path_str) = export_wallet_path();
+            export_wallet(&ctx, &path_str);
+
+            let wallet_name = "imported_wallet";
+            {
+                let cmd = import_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("export_path", path_str.clone());
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert(
+                    "export_key_derivation_method",
+                    EXPORT_KEY_DERIVATION_METHOD.to_string(),
+                );
+                params.insert("dry_run", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let report = Wallet::inspect_backup(&ImportConfig {
+                path: path_str,
+                key: EXPORT_KEY.to_string(),
+                key_derivation_method: Some(EXPORT_KEY_DERIVATION_METHOD.to_string()),
+                preserve_unknown: false,
+            })
+            .unwrap();
+            assert_eq!(Some(&1), report.records.get("did"));
+            assert!(report.invalid.is_empty());
+
+            WalletConfig::read(wallet_name).unwrap_err();
+
+            close_and_delete_wallet(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn import_works_for_stream_format() {
+            let ctx = setup_with_wallet();
+
+            new_did(&ctx, SEED_MY1);
+            use_did(&ctx, DID_MY1);
+
+            let (_, path_str) = export_wallet_path();
+            {
+                let cmd = export_command::new();
+                let mut params = CommandParams::new();
+                params.insert("export_path", path_str.clone());
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert(
+                    "export_key_derivation_method",
+                    EXPORT_KEY_DERIVATION_METHOD.to_string(),
+                );
+                params.insert("export_format", "stream".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            let report = Wallet::inspect_backup(&ImportConfig {
+                path: path_str.clone(),
+                key: EXPORT_KEY.to_string(),
+                key_derivation_method: None,
+                preserve_unknown: false,
+            })
+            .unwrap();
+            assert_eq!(Some(&1), report.records.get("did"));
+            assert_eq!(Some(&1), report.records.get("key"));
+
+            let wallet_name = "imported_wallet";
+            {
+                let cmd = import_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("export_path", path_str);
+                params.insert("export_key", EXPORT_KEY.to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            use_did(&ctx, DID_MY1);
+
+            close_and_delete_wallet(&ctx);
+
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", wallet_name.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
+                cmd.execute(&CommandContext::new(), &params).unwrap();
+            }
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn import_works_for_stream_format_with_duplicate_records() {
+            let ctx = setup();
+
+            let (path, path_str) = export_wallet_path();
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            {
+                let record = StreamRecord::Entry {
+                    category: "did".to_string(),
+                    name: DID_MY1.to_string(),
+                    value: hex::encode(b"{}"),
+                    tags: Vec::new(),
+                };
+                let mut writer = StreamWriter::init(
+                    std::fs::File::create(&path).unwrap(),
+                    EXPORT_KEY,
+                    Some(EXPORT_KEY_DERIVATION_METHOD),
+                    0,
+                )
+                .unwrap();
+                writer.write_record(&record).unwrap();
+                writer.write_record(&record).unwrap();
+                writer.finish().unwrap();
+            }
+
+            {
+                let cmd = import_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY.to_string());
+                params.insert("export_path", path_str);
+                params.insert("export_key", EXPORT_KEY.to_string());
+                params.insert(
+                    "export_key_derivation_method",
+                    EXPORT_KEY_DERIVATION_METHOD.to_string(),
+                );
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+
+            WalletConfig::read(WALLET).unwrap_err();
+
+            tear_down();
+        }
+
         #[test]
         pub fn import_works_for_not_found_file() {
             let ctx = setup();
@@ -363,6 +646,7 @@ pub mod tests {
                 params.insert("name", WALLET.to_string());
                 params.insert("key", WALLET_KEY.to_string());
                 params.insert("key_derivation_method", "argon2i".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&CommandContext::new(), &params).unwrap();
             }
 
@@ -373,6 +657,7 @@ pub mod tests {
                 params.insert("name", wallet_name.to_string());
                 params.insert("key", key.to_string());
                 params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
                 cmd.execute(&CommandContext::new(), &params).unwrap();
             }
 
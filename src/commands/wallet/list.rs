@@ -4,27 +4,54 @@
 */
 use crate::{
     command_executor::{Command, CommandContext, CommandMetadata, CommandParams},
+    params_parser::ParamParser,
     tools::wallet::Wallet,
-    utils::table::print_list_table,
+    utils::table::print_or_export_list_table,
 };
 
 pub mod list_command {
     use super::*;
 
-    command!(CommandMetadata::build("list", "List attached wallets.").finalize());
+    command!(CommandMetadata::build(
+        "list",
+        "List attached wallets with their storage location, size and timestamps."
+    )
+    .add_optional_param(
+        "out",
+        "Path to the file to write the list into instead of printing the table"
+    )
+    .add_optional_param(
+        "format",
+        "Format of the `out` file. One of: csv, json (json by default)"
+    )
+    .add_example("wallet list")
+    .add_example("wallet list out=/home/wallets.json")
+    .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
         trace!("execute >> ctx {:?} params {:?}", ctx, params);
 
+        let out = ParamParser::get_opt_str_param("out", params)?;
+        let format = ParamParser::get_opt_str_param("format", params)?;
+
         let wallets = Wallet::list();
 
-        print_list_table(
+        print_or_export_list_table(
             &wallets,
-            &[("id", "Name"), ("storage_type", "Type")],
+            &[
+                ("id", "Name"),
+                ("storage_type", "Type"),
+                ("location", "Location"),
+                ("size", "Size"),
+                ("created", "Created"),
+                ("modified", "Modified"),
+            ],
             "There are no wallets",
-        );
+            out,
+            format,
+        )?;
 
-        if let Some(wallet) = ctx.get_opened_wallet() {
+        if let (None, Some(wallet)) = (out, ctx.get_opened_wallet()) {
             println_succ!("Current wallet \"{}\"", wallet.name);
         }
 
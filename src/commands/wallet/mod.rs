@@ -3,11 +3,16 @@
     https://digital.gov.bc.ca/digital-trust
 */
 use crate::{
-    command_executor::{CommandGroup, CommandGroupMetadata},
-    tools::wallet::Wallet,
+    command_executor::{CommandContext, CommandGroup, CommandGroupMetadata, CommandParams},
+    params_parser::ParamParser,
+    tools::wallet::{
+        backup::AutoBackupDir, wallet_config::WalletConfig, Credentials, ExportConfig, Wallet,
+    },
+    utils::file::read_key_file,
 };
 
 pub mod attach;
+pub mod check;
 pub mod close;
 pub mod create;
 pub mod delete;
@@ -16,18 +21,31 @@ pub mod export;
 pub mod import;
 pub mod list;
 pub mod open;
+pub mod profile;
+pub mod rebuild_dids;
+pub mod search;
+pub mod stats;
+pub mod status;
+pub mod storage_credentials;
+pub mod tag;
 
 pub use self::{
-    attach::*, close::*, create::*, delete::*, detach::*, export::*, import::*, list::*, open::*,
+    attach::*, check::*, close::*, create::*, delete::*, detach::*, export::*, import::*, list::*,
+    open::*, profile::*, rebuild_dids::*, search::*, stats::*, status::*, storage_credentials::*,
+    tag::*,
 };
 
 pub mod group {
     use super::*;
 
-    command_group!(CommandGroupMetadata::new(
-        "wallet",
-        "Wallet management commands"
-    ));
+    command_group!(
+        CommandGroupMetadata::new("wallet", "Wallet management commands")
+            .add_example("wallet create wallet1 key")
+            .add_example("wallet open wallet1 key")
+            .add_example("wallet status")
+            .add_example("wallet export export_path=/home/indy/export_wallet export_key")
+            .add_example("wallet close")
+    );
 }
 
 pub fn wallet_names() -> Vec<String> {
@@ -42,6 +60,140 @@ pub fn wallet_names() -> Vec<String> {
         .collect()
 }
 
+const KEY_DERIVATION_METHODS: [&str; 3] = ["argon2m", "argon2i", "raw"];
+
+pub fn validate_key_derivation_method(method: &str) -> Result<(), String> {
+    if KEY_DERIVATION_METHODS.contains(&method) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported key derivation method \"{}\". One of: {}.",
+            method,
+            KEY_DERIVATION_METHODS.join(", ")
+        ))
+    }
+}
+
+/// Returns the wallet key derivation method falling back to the default one set in the CLI context.
+pub fn key_derivation_method_or_default(
+    ctx: &CommandContext,
+    method: Option<&str>,
+) -> Option<String> {
+    method
+        .map(String::from)
+        .or_else(|| ctx.get_key_derivation_method())
+}
+
+/// Returns the key and its derivation method passed either as `key_param` or read from the `key_file_param` file.
+/// A key read from the file is raw, so only the `raw` derivation method can be used with it.
+pub fn get_key_params(
+    params: &CommandParams,
+    key_param: &str,
+    key_file_param: &str,
+    key_derivation_method_param: &str,
+) -> Result<(Option<String>, Option<String>), ()> {
+    let key = ParamParser::get_opt_str_param(key_param, params)?;
+    let key_file = ParamParser::get_opt_str_param(key_file_param, params)?;
+    let key_derivation_method =
+        ParamParser::get_opt_str_param(key_derivation_method_param, params)?;
+
+    match (key, key_file) {
+        (Some(_), Some(_)) => {
+            println_err!(
+                "Only one of the parameters `{}` and `{}` can be specified",
+                key_param,
+                key_file_param
+            );
+            Err(())
+        }
+        (None, Some(key_file)) => {
+            if let Some(method) = key_derivation_method.filter(|method| *method != "raw") {
+                println_err!(
+                    "Key read from `{}` is raw and can't be used with \"{}\" key derivation method",
+                    key_file_param,
+                    method
+                );
+                return Err(());
+            }
+            let key = read_key_file(key_file).map_err(|err| println_err!("{}", err))?;
+            Ok((Some(key), Some("raw".to_string())))
+        }
+        (key, None) => Ok((
+            key.map(String::from),
+            key_derivation_method.map(String::from),
+        )),
+    }
+}
+
+pub const DEFAULT_AUTO_BACKUP_KEEP: u64 = 5;
+
+/// Exports the wallet into the automatic backups directory (if it is set with `set auto-backup-dir`)
+/// before a destructive `operation`, and removes the oldest backups of the wallet.
+/// The backup is protected with the wallet key, so it can be imported back with the same key.
+pub fn auto_backup_wallet(
+    ctx: &CommandContext,
+    config: &WalletConfig,
+    credentials: &Credentials,
+    operation: &str,
+) -> Result<(), ()> {
+    let dir = match ctx.get_auto_backup_dir() {
+        Some(dir) => AutoBackupDir::new(&dir),
+        None => return Ok(()),
+    };
+
+    let path = dir.backup_path(&config.id, operation);
+    let export_config = ExportConfig {
+        path: path.to_string_lossy().to_string(),
+        key: credentials.key.to_string(),
+        key_derivation_method: credentials.key_derivation_method.clone(),
+        format: Some("stream".to_string()),
+    };
+
+    let result = match ctx.get_opened_wallet() {
+        Some(wallet) if wallet.name == config.id => wallet.export(&export_config, &|_| {}),
+        _ => {
+            let open_credentials = Credentials {
+                key: credentials.key.to_string(),
+                key_derivation_method: credentials.key_derivation_method.clone(),
+                storage_credentials: credentials.storage_credentials.clone(),
+                ..Credentials::default()
+            };
+            Wallet::open(config, &open_credentials, None, None).and_then(|wallet| {
+                let result = wallet.export(&export_config, &|_| {});
+                wallet.close()?;
+                result
+            })
+        }
+    };
+    result.map_err(|err| {
+        println_err!(
+            "Unable to back up wallet \"{}\" before {}: {}",
+            config.id,
+            operation,
+            err.report(Some(&config.id))
+        )
+    })?;
+    println_succ!(
+        "Wallet \"{}\" has been backed up to \"{}\"",
+        config.id,
+        export_config.path
+    );
+
+    match dir.prune(&config.id, ctx.get_auto_backup_keep()) {
+        Ok(removed) => {
+            for path in removed {
+                println!("Old backup \"{}\" has been removed", path.to_string_lossy());
+            }
+        }
+        Err(err) => println_warn!(
+            "Unable to remove old backups of wallet \"{}\": {}",
+            config.id,
+            err.message(None)
+        ),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -120,6 +272,7 @@ pub mod tests {
             params.insert("name", WALLET.to_string());
             params.insert("key", WALLET_KEY_RAW.to_string());
             params.insert("key_derivation_method", "raw".to_string());
+            params.insert("force", "true".to_string());
             cmd.execute(&CommandContext::new(), &params).unwrap();
         }
     }
@@ -139,6 +292,7 @@ pub mod tests {
             params.insert("name", WALLET.to_string());
             params.insert("key", WALLET_KEY_RAW.to_string());
             params.insert("key_derivation_method", "raw".to_string());
+            params.insert("force", "true".to_string());
             cmd.execute(&ctx, &params).unwrap();
         }
     }
@@ -148,6 +302,20 @@ pub mod tests {
         (path.clone(), path.to_str().unwrap().to_string())
     }
 
+    pub fn write_key_file(name: &str, mode: u32) -> String {
+        let path = EnvironmentUtils::tmp_file_path(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, EXPORT_KEY).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+        path.to_str().unwrap().to_string()
+    }
+
     pub fn export_wallet(ctx: &CommandContext, path: &str) {
         let cmd = export_command::new();
         let mut params = CommandParams::new();
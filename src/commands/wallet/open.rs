@@ -7,9 +7,13 @@ use crate::{
         Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
     },
     params_parser::ParamParser,
-    tools::wallet::{wallet_config::WalletConfig, Credentials, Wallet},
-    wallet::close_wallet,
+    tools::{
+        did::Did,
+        wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+    },
+    wallet::{auto_backup_wallet, close_wallet, key_derivation_method_or_default},
 };
+use indy_utils::did::DidValue;
 
 pub mod open_command {
     use super::*;
@@ -28,8 +32,16 @@ pub mod open_command {
                                                 argon2i - derive secured wallet key (less secured but faster)
                                                 raw - raw key provided (skip derivation)")
                             .add_optional_param("storage_credentials", "The list of key:value pairs defined by storage type.")
+                            .add_optional_param("profile", "Name of the wallet profile to use. Records of different profiles are isolated (the default profile by default)")
+                            .add_optional_param("open_timeout", "Time (in sec) to wait until the wallet is released if it is used by another process (0 by default)")
+                            .add_optional_param("auto_use_did", "Activate the DID used in the wallet last time (true by default)")
+                            .add_optional_param("readonly", "Open the wallet in read-only mode: storing records, keys or profiles is rejected (false by default)")
                             .add_example("wallet open wallet1 key")
                             .add_example("wallet open wallet1 key rekey")
+                            .add_example("wallet open wallet1 key open_timeout=30")
+                            .add_example("wallet open wallet1 key profile=tenant1")
+                            .add_example("wallet open wallet1 key auto_use_did=false")
+                            .add_example("wallet open wallet1 key readonly=true")
                             .finalize());
 
     fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
@@ -43,15 +55,26 @@ pub mod open_command {
         let rekey_derivation_method =
             ParamParser::get_opt_str_param("rekey_derivation_method", params)?;
         let storage_credentials = ParamParser::get_opt_object_param("storage_credentials", params)?;
+        let profile = ParamParser::get_opt_str_param("profile", params)?;
+        let open_timeout = ParamParser::get_opt_number_param::<u64>("open_timeout", params)?;
+        let auto_use_did = ParamParser::get_opt_bool_param("auto_use_did", params)?.unwrap_or(true);
+        let read_only = ParamParser::get_opt_bool_param("readonly", params)?.unwrap_or(false);
+
+        if read_only && rekey.is_some() {
+            println_err!(
+                "Wallet key cannot be replaced when the wallet is opened in read-only mode"
+            );
+            return Err(());
+        }
 
         let config = WalletConfig::read(id)
             .map_err(|_| println_err!("Wallet \"{}\" isn't attached to CLI", id))?;
 
         let credentials = Credentials {
             key: key.to_string(),
-            key_derivation_method: key_derivation_method.map(String::from),
+            key_derivation_method: key_derivation_method_or_default(ctx, key_derivation_method),
             rekey: rekey.map(String::from),
-            rekey_derivation_method: rekey_derivation_method.map(String::from),
+            rekey_derivation_method: key_derivation_method_or_default(ctx, rekey_derivation_method),
             storage_credentials,
         };
 
@@ -67,16 +90,64 @@ pub mod open_command {
             close_wallet(ctx, wallet)?;
         }
 
-        let wallet = Wallet::open(&config, &credentials)
-            .map_err(|err| println_err!("{}", err.message(Some(&id))))?;
+        if credentials.rekey.is_some() {
+            auto_backup_wallet(ctx, &config, &credentials, "rekey")?;
+        }
+
+        let wallet = Wallet::open(&config, &credentials, profile, open_timeout)
+            .map_err(|err| println_err!("{}", err.report(Some(&id))))?;
+        let wallet = match read_only {
+            true => wallet.into_read_only(),
+            false => wallet,
+        };
 
+        let rekeyed = wallet.rekeyed;
+        let active_did = match config.active_did {
+            Some(did) if auto_use_did => restore_active_did(&wallet, did),
+            _ => None,
+        };
         ctx.set_opened_wallet(wallet);
-        println_succ!("Wallet \"{}\" has been opened", id);
+        match profile {
+            Some(profile) => println_succ!(
+                "Wallet \"{}\" has been opened with profile \"{}\"",
+                id,
+                profile
+            ),
+            None => println_succ!("Wallet \"{}\" has been opened", id),
+        }
+        if read_only {
+            println_warn!("Wallet \"{}\" is opened in read-only mode", id);
+        }
+        if rekeyed {
+            println_succ!(
+                "Wallet \"{}\" key has been replaced. Use the new key to open the wallet from now on",
+                id
+            );
+        }
+        if let Some(did) = active_did {
+            println_succ!("Did \"{}\" has been set as active", did);
+            ctx.set_active_did(did);
+        }
 
         trace!("execute << {:?}", ());
         Ok(())
     }
 
+    /// Returns the DID used last time if it is still stored in the wallet (or its opened profile).
+    fn restore_active_did(wallet: &Wallet, did: String) -> Option<DidValue> {
+        let did = DidValue(did);
+        match Did::get(wallet, &did) {
+            Ok(_) => Some(did),
+            Err(_) => {
+                println_warn!(
+                    "Did \"{}\" used last time is not stored in the wallet anymore",
+                    did
+                );
+                None
+            }
+        }
+    }
+
     pub fn cleanup(ctx: &CommandContext) {
         trace!("cleanup >> ctx {:?}", ctx);
 
@@ -84,6 +155,8 @@ pub mod open_command {
             close_wallet(ctx, wallet).ok();
         }
 
+        Wallet::close_cached().ok();
+
         trace!("cleanup <<");
     }
 }
@@ -95,9 +168,15 @@ pub mod tests {
 
     mod open {
         use super::*;
-        use crate::wallet::tests::{
-            close_and_delete_wallet, create_wallet, delete_wallet, WALLET, WALLET_KEY,
-            WALLET_KEY_RAW,
+        use crate::{
+            did::tests::{new_did, use_did, DID_TRUSTEE, SEED_MY1, SEED_TRUSTEE},
+            wallet::{
+                create_command, delete_command,
+                tests::{
+                    close_and_delete_wallet, close_wallet, create_wallet, delete_wallet,
+                    open_wallet, WALLET, WALLET_KEY, WALLET_KEY_RAW,
+                },
+            },
         };
 
         #[test]
@@ -118,6 +197,163 @@ pub mod tests {
             tear_down();
         }
 
+        #[test]
+        pub fn open_works_for_rekey() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            let new_key = "CwMHrEQJnwvuE8q9zbR49jyYtVxVBHNTjCPEPk1aV3cP";
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("rekey", new_key.to_string());
+                params.insert("rekey_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.ensure_opened_wallet().unwrap().rekeyed);
+            close_wallet(&ctx);
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", new_key.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(!ctx.ensure_opened_wallet().unwrap().rekeyed);
+            close_wallet(&ctx);
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", new_key.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("force", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+
+            tear_down();
+        }
+
+        #[test]
+        pub fn open_works_for_read_only() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            open_wallet(&ctx);
+            new_did(&ctx, SEED_TRUSTEE);
+            close_wallet(&ctx);
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("readonly", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                assert!(wallet.read_only);
+                Did::get(&wallet, &DidValue(DID_TRUSTEE.to_string())).unwrap();
+                Did::create(&wallet, None, Some(SEED_MY1), None, None, None, None).unwrap_err();
+                wallet.create_profile("tenant1").unwrap_err();
+            }
+            close_and_delete_wallet(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn open_works_for_read_only_and_rekey() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert(
+                    "rekey",
+                    "CwMHrEQJnwvuE8q9zbR49jyYtVxVBHNTjCPEPk1aV3cP".to_string(),
+                );
+                params.insert("rekey_derivation_method", "raw".to_string());
+                params.insert("readonly", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            ctx.ensure_opened_wallet().unwrap_err();
+            delete_wallet(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn open_works_for_cached_key_derivation() {
+            let ctx = setup();
+            {
+                let cmd = create_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY.to_string());
+                params.insert("no-confirm", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            for _ in 0..2 {
+                {
+                    let cmd = open_command::new();
+                    let mut params = CommandParams::new();
+                    params.insert("name", WALLET.to_string());
+                    params.insert("key", WALLET_KEY.to_string());
+                    cmd.execute(&ctx, &params).unwrap();
+                }
+                assert!(!Wallet::is_cached(WALLET));
+                close_wallet(&ctx);
+                assert!(Wallet::is_cached(WALLET));
+            }
+            // store opened with another key is not reused
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", "other_key".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(!Wallet::is_cached(WALLET));
+            {
+                let cmd = delete_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY.to_string());
+                params.insert("force", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(!Wallet::is_cached(WALLET));
+            tear_down();
+        }
+
+        #[test]
+        pub fn open_works_for_auto_use_did() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_TRUSTEE);
+            use_did(&ctx, DID_TRUSTEE);
+            close_wallet(&ctx);
+
+            open_wallet(&ctx);
+            assert_eq!(DID_TRUSTEE, ctx.ensure_active_did().unwrap().to_string());
+            close_wallet(&ctx);
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("auto_use_did", "false".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            assert!(ctx.get_active_did().unwrap().is_none());
+            tear_down_with_wallet(&ctx);
+        }
+
         #[test]
         pub fn open_works_for_twice() {
             let ctx = setup_with_wallet();
@@ -173,5 +409,20 @@ pub mod tests {
             delete_wallet(&ctx);
             tear_down();
         }
+
+        #[test]
+        pub fn open_works_for_wallet_used_by_another_context() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert("open_timeout", "1".to_string());
+                cmd.execute(&CommandContext::new(), &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
     }
 }
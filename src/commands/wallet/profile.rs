@@ -0,0 +1,211 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    utils::table::print_list_table,
+};
+
+pub mod create_profile_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "create-profile",
+        "Create new profile in the opened wallet. Profiles share the wallet storage but their records are isolated."
+    )
+    .add_main_param("name", "The name of new profile")
+    .add_example("wallet create-profile tenant1")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let name = ParamParser::get_str_param("name", params)?;
+
+        let profile = wallet
+            .create_profile(name)
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        println_succ!(
+            "Profile \"{}\" has been created in wallet \"{}\"",
+            profile,
+            wallet.name
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+pub mod list_profiles_command {
+    use super::*;
+
+    command!(
+        CommandMetadata::build("list-profiles", "List profiles of the opened wallet.")
+            .add_precondition(Precondition::OpenedWallet)
+            .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let (profiles, default_profile) = wallet
+            .list_profiles()
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        let active_profile = wallet.profile.as_ref().unwrap_or(&default_profile);
+
+        let rows = profiles
+            .iter()
+            .map(|profile| {
+                json!({
+                    "name": profile,
+                    "default": if *profile == default_profile { "yes" } else { "" },
+                    "active": if profile == active_profile { "yes" } else { "" },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        print_list_table(
+            &rows,
+            &[
+                ("name", "Name"),
+                ("default", "Default"),
+                ("active", "Active"),
+            ],
+            "There are no profiles",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+        did::tests::{new_did, SEED_MY1},
+        tools::did::Did,
+        wallet::{
+            close_command, open_command,
+            tests::{close_and_delete_wallet, create_wallet, WALLET, WALLET_KEY_RAW},
+        },
+    };
+
+    const PROFILE: &str = "tenant1";
+
+    fn open_wallet_with_profile(ctx: &CommandContext, profile: &str) -> Result<(), ()> {
+        let cmd = open_command::new();
+        let mut params = CommandParams::new();
+        params.insert("name", WALLET.to_string());
+        params.insert("key", WALLET_KEY_RAW.to_string());
+        params.insert("key_derivation_method", "raw".to_string());
+        params.insert("profile", profile.to_string());
+        cmd.execute(ctx, &params)
+    }
+
+    fn close_wallet(ctx: &CommandContext) {
+        let cmd = close_command::new();
+        let params = CommandParams::new();
+        cmd.execute(ctx, &params).unwrap();
+    }
+
+    fn create_profile(ctx: &CommandContext, name: &str) -> Result<(), ()> {
+        let cmd = create_profile_command::new();
+        let mut params = CommandParams::new();
+        params.insert("name", name.to_string());
+        cmd.execute(ctx, &params)
+    }
+
+    mod create_profile {
+        use super::*;
+
+        #[test]
+        pub fn create_profile_works() {
+            let ctx = setup_with_wallet();
+            create_profile(&ctx, PROFILE).unwrap();
+
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                let (profiles, _) = wallet.list_profiles().unwrap();
+                assert!(profiles.contains(&PROFILE.to_string()));
+            }
+
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_profile_works_for_duplicate() {
+            let ctx = setup_with_wallet();
+            create_profile(&ctx, PROFILE).unwrap();
+            create_profile(&ctx, PROFILE).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn create_profile_works_for_no_opened_wallet() {
+            let ctx = setup();
+            create_profile(&ctx, PROFILE).unwrap_err();
+            tear_down();
+        }
+    }
+
+    mod list_profiles {
+        use super::*;
+
+        #[test]
+        pub fn list_profiles_works() {
+            let ctx = setup_with_wallet();
+            create_profile(&ctx, PROFILE).unwrap();
+            {
+                let cmd = list_profiles_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+    }
+
+    mod open_with_profile {
+        use super::*;
+
+        #[test]
+        pub fn open_works_for_profile_isolation() {
+            let ctx = setup();
+            create_wallet(&ctx);
+
+            open_wallet_with_profile(&ctx, PROFILE).unwrap_err();
+
+            {
+                let cmd = open_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            create_profile(&ctx, PROFILE).unwrap();
+            new_did(&ctx, SEED_MY1);
+            close_wallet(&ctx);
+
+            open_wallet_with_profile(&ctx, PROFILE).unwrap();
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                assert_eq!(Some(PROFILE.to_string()), wallet.profile);
+                assert!(Did::list(&wallet).unwrap().is_empty());
+            }
+
+            close_and_delete_wallet(&ctx);
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,193 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    utils::table::print_list_table,
+};
+
+pub mod rebuild_dids_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "rebuild-dids",
+        "Re-create DID records of the opened wallet from the keys not used by any DID, \
+        e.g. when the DID records have been lost but the keys survived a failed import. \
+        Only prints the DIDs which would be restored unless `apply=true` is passed."
+    )
+    .add_optional_param(
+        "apply",
+        "Store the restored DID records into the wallet (false by default)"
+    )
+    .add_example("wallet rebuild-dids")
+    .add_example("wallet rebuild-dids apply=true")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let apply = ParamParser::get_opt_bool_param("apply", params)?.unwrap_or(false);
+
+        let lost_dids = wallet
+            .find_lost_dids()
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        if lost_dids.is_empty() {
+            println_succ!(
+                "There are no keys without DID in wallet \"{}\"",
+                wallet.name
+            );
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        let rows = lost_dids
+            .iter()
+            .map(|did_info| {
+                let result = if apply {
+                    match wallet.restore_did(did_info) {
+                        Ok(()) => "Restored".to_string(),
+                        Err(err) => {
+                            failed += 1;
+                            format!("Failed: {}", err.message(None))
+                        }
+                    }
+                } else {
+                    "Will be restored".to_string()
+                };
+                json!({
+                    "did": did_info.did,
+                    "verkey": did_info.verkey,
+                    "verkey_type": did_info.verkey_type,
+                    "metadata": did_info.metadata,
+                    "result": result,
+                })
+            })
+            .collect::<Vec<serde_json::Value>>();
+
+        print_list_table(
+            &rows,
+            &[
+                ("did", "Did"),
+                ("verkey", "Verkey"),
+                ("verkey_type", "Verkey type"),
+                ("metadata", "Metadata"),
+                ("result", "Result"),
+            ],
+            "",
+        );
+
+        if !apply {
+            println!(
+                "{} DID(s) can be restored. Use `apply=true` to store them into the wallet.",
+                lost_dids.len()
+            );
+            return Ok(());
+        }
+
+        if failed > 0 {
+            println_err!(
+                "{} of {} DID(s) have been restored, {} failed",
+                lost_dids.len() - failed,
+                lost_dids.len(),
+                failed
+            );
+            return Err(());
+        }
+
+        println_succ!(
+            "{} DID(s) have been restored in wallet \"{}\"",
+            lost_dids.len(),
+            wallet.name
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        tools::did::{constants::CATEGORY_DID, key::Key, Did},
+        utils::futures::block_on,
+    };
+    use aries_askar::kms::KeyAlg;
+    use indy_utils::did::DidValue;
+
+    mod rebuild_dids {
+        use super::*;
+
+        fn rebuild_dids(ctx: &CommandContext, apply: bool) {
+            let cmd = rebuild_dids_command::new();
+            let mut params = CommandParams::new();
+            params.insert("apply", apply.to_string());
+            cmd.execute(ctx, &params).unwrap();
+        }
+
+        #[test]
+        pub fn rebuild_dids_works() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let (did, verkey) = Did::create(
+                &wallet,
+                None,
+                None,
+                Some(r#"{"name":"issuer"}"#),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            Did::create(&wallet, None, None, None, None, None, None).unwrap();
+            block_on(wallet.remove_record(CATEGORY_DID, &did)).unwrap();
+
+            rebuild_dids(&ctx, false);
+            assert_eq!(1, Did::list(&wallet).unwrap().len());
+
+            rebuild_dids(&ctx, true);
+            let did_info = Did::get(&wallet, &DidValue(did.clone())).unwrap();
+            assert_eq!(verkey, did_info.verkey);
+            assert_eq!(Some(r#"{"name":"issuer"}"#.to_string()), did_info.metadata);
+            assert_eq!(
+                vec![did_info.did],
+                Did::find_by_metadata(&wallet, "name", "issuer")
+                    .unwrap()
+                    .into_iter()
+                    .map(|did_info| did_info.did)
+                    .collect::<Vec<String>>()
+            );
+            assert!(wallet.find_lost_dids().unwrap().is_empty());
+            assert!(wallet.check().unwrap().issues.is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn rebuild_dids_works_for_standalone_key() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Key::create_named(&wallet, None, KeyAlg::Ed25519, None, None).unwrap();
+            assert!(wallet.find_lost_dids().unwrap().is_empty());
+            rebuild_dids(&ctx, true);
+            assert!(Did::list(&wallet).unwrap().is_empty());
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn rebuild_dids_works_for_no_lost_dids() {
+            let ctx = setup_with_wallet();
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            Did::create(&wallet, None, None, None, None, None, None).unwrap();
+            rebuild_dids(&ctx, true);
+            assert_eq!(1, Did::list(&wallet).unwrap().len());
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    params_parser::ParamParser,
+    utils::table::print_list_table,
+};
+
+use aries_askar::{Entry, EntryTag};
+
+pub mod search_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "search",
+        "Search records of the opened wallet by their tags."
+    )
+    .add_required_param("category", "Category of the records (e.g. `did`)")
+    .add_optional_param(
+        "query",
+        "WQL tag query. Plaintext tags are referenced with `~` name prefix. All records of the category are returned if not specified"
+    )
+    .add_optional_param("limit", "Maximum number of records to return")
+    .add_optional_param(
+        "values",
+        "Print values of the records (false by default)"
+    )
+    .add_example(r#"wallet search category=did query={"verkey":"GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa"}"#)
+    .add_example(r#"wallet search category=did query={"meta:team":"issuers"} limit=10"#)
+    .add_example("wallet search category=did values=true")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let category = ParamParser::get_str_param("category", params)?;
+        let query = ParamParser::get_opt_str_param("query", params)?;
+        let limit = ParamParser::get_opt_number_param::<usize>("limit", params)?;
+        let values = ParamParser::get_opt_bool_param("values", params)?.unwrap_or(false);
+
+        let records = wallet
+            .search(category, query, limit)
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        let rows = records
+            .iter()
+            .map(|record| record_to_json(record, values))
+            .collect::<Vec<_>>();
+
+        let mut headers = vec![("id", "Id"), ("tags", "Tags")];
+        if values {
+            headers.push(("value", "Value"));
+        }
+
+        print_list_table(
+            &rows,
+            &headers,
+            &format!("There are no \"{}\" records matching the query", category),
+        );
+
+        if !rows.is_empty() {
+            println_succ!("{} record(s) found", rows.len());
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+
+    fn record_to_json(record: &Entry, with_value: bool) -> serde_json::Value {
+        let mut json = json!({
+            "id": record.name,
+            "tags": format_tags(&record.tags),
+        });
+        if with_value {
+            json["value"] = serde_json::Value::String(match std::str::from_utf8(&record.value) {
+                Ok(value) => value.to_string(),
+                Err(_) => hex::encode(&record.value),
+            });
+        }
+        json
+    }
+}
+
+/// Formats record tags one per line, plaintext tags are prefixed with `~` as in WQL queries.
+pub fn format_tags(tags: &[EntryTag]) -> String {
+    tags.iter()
+        .map(|tag| match tag {
+            EntryTag::Encrypted(name, value) => format!("{}={}", name, value),
+            EntryTag::Plaintext(name, value) => format!("~{}={}", name, value),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet},
+        did::tests::{new_did, DID_MY1, DID_MY3, SEED_MY1, SEED_MY3, VERKEY_MY1},
+    };
+
+    mod search {
+        use super::*;
+
+        #[test]
+        pub fn search_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            new_did(&ctx, SEED_MY3);
+            {
+                let cmd = search_command::new();
+                let mut params = CommandParams::new();
+                params.insert("category", "did".to_string());
+                params.insert("query", format!(r#"{{"verkey":"{}"}}"#, VERKEY_MY1));
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            let records = wallet
+                .search(
+                    "did",
+                    Some(&format!(r#"{{"verkey":"{}"}}"#, VERKEY_MY1)),
+                    None,
+                )
+                .unwrap();
+            assert_eq!(1, records.len());
+            assert_eq!(DID_MY1, records[0].name);
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn search_works_for_limit() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            new_did(&ctx, SEED_MY3);
+            {
+                let cmd = search_command::new();
+                let mut params = CommandParams::new();
+                params.insert("category", "did".to_string());
+                params.insert("limit", "1".to_string());
+                params.insert("values", "true".to_string());
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            assert_eq!(1, wallet.search("did", None, Some(1)).unwrap().len());
+            let all = wallet.search("did", None, None).unwrap();
+            assert_eq!(2, all.len());
+            assert!(all.iter().any(|record| record.name == DID_MY3));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn search_works_for_invalid_query() {
+            let ctx = setup_with_wallet();
+            {
+                let cmd = search_command::new();
+                let mut params = CommandParams::new();
+                params.insert("category", "did".to_string());
+                params.insert("query", "verkey".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn search_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = search_command::new();
+                let mut params = CommandParams::new();
+                params.insert("category", "did".to_string());
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
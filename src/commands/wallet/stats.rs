@@ -0,0 +1,104 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    tools::wallet::wallet_config::format_size,
+    utils::table::print_list_table,
+};
+
+pub mod stats_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "stats",
+        "Show statistics of the opened wallet: number of records per category, keys per algorithm, unreadable records and database size.
+        Only categories used by CLI are counted."
+    )
+    .add_example("wallet stats")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let stats = wallet
+            .stats()
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        let mut rows = stats
+            .records
+            .iter()
+            .map(|(category, count)| json!({ "category": category, "count": count.to_string() }))
+            .collect::<Vec<_>>();
+        rows.extend(stats.key_algorithms.iter().map(|(algorithm, count)| {
+            json!({ "category": format!("key ({})", algorithm), "count": count.to_string() })
+        }));
+        rows.push(json!({ "category": "unknown", "count": stats.unknown.to_string() }));
+
+        println!(
+            "Wallet \"{}\" (profile \"{}\")",
+            wallet.name,
+            wallet.profile.as_deref().unwrap_or("default")
+        );
+        print_list_table(
+            &rows,
+            &[("category", "Category"), ("count", "Records")],
+            "There are no records",
+        );
+
+        match stats.storage_size {
+            Some(size) => println!("Database size: {}", format_size(size)),
+            None => println!("Database size: unknown (not a SQLite wallet)"),
+        }
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet};
+
+    mod stats {
+        use super::*;
+        use crate::did::tests::{new_did, SEED_MY1};
+
+        #[test]
+        pub fn stats_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            {
+                let cmd = stats_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                let stats = wallet.stats().unwrap();
+                assert_eq!(Some(&1), stats.records.get("did"));
+                assert_eq!(Some(&0), stats.records.get("did_alias"));
+                assert_eq!(Some(&1), stats.key_algorithms.get("ed25519"));
+                assert_eq!(0, stats.unknown);
+                assert!(stats.storage_size.unwrap() > 0);
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn stats_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = stats_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
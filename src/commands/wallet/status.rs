@@ -0,0 +1,107 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    tools::wallet::wallet_config::WalletConfig,
+    utils::table::{print_list_table, print_table},
+};
+
+pub mod status_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "status",
+        "Show the opened wallet: storage type, profile, number of records and whether its key has been replaced on opening."
+    )
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+
+        let storage_type = WalletConfig::read(&wallet.name)
+            .map(|config| config.storage_type)
+            .unwrap_or_else(|_| "-".to_string());
+
+        let counts = wallet
+            .count_records()
+            .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+
+        print_table(
+            &json!({
+                "name": wallet.name,
+                "storage_type": storage_type,
+                "profile": wallet.profile.as_deref().unwrap_or("default"),
+                "rekeyed": if wallet.rekeyed { "yes" } else { "no" },
+                "read_only": if wallet.read_only { "yes" } else { "no" },
+            }),
+            &[
+                ("name", "Name"),
+                ("storage_type", "Storage Type"),
+                ("profile", "Profile"),
+                ("rekeyed", "Key Replaced"),
+                ("read_only", "Read-only"),
+            ],
+        );
+
+        let rows = counts
+            .iter()
+            .map(|(category, count)| json!({ "category": category, "count": count.to_string() }))
+            .collect::<Vec<_>>();
+
+        print_list_table(
+            &rows,
+            &[("category", "Category"), ("count", "Records")],
+            "There are no records",
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, setup_with_wallet, tear_down, tear_down_with_wallet};
+
+    mod status {
+        use super::*;
+        use crate::did::tests::{new_did, SEED_MY1};
+
+        #[test]
+        pub fn status_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            {
+                let cmd = status_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap();
+            }
+            {
+                let wallet = ctx.ensure_opened_wallet().unwrap();
+                let counts = wallet.count_records().unwrap();
+                assert_eq!(Some(&1), counts.get("did"));
+                assert_eq!(Some(&1), counts.get("key"));
+                assert!(!wallet.rekeyed);
+                assert!(!wallet.read_only);
+            }
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn status_works_for_no_opened_wallet() {
+            let ctx = setup();
+            {
+                let cmd = status_command::new();
+                let params = CommandParams::new();
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{
+        Command, CommandContext, CommandMetadata, CommandParams, DynamicCompletionType,
+    },
+    params_parser::ParamParser,
+    tools::wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+    wallet::key_derivation_method_or_default,
+};
+
+pub mod change_storage_credentials_command {
+    use super::*;
+
+    command!(CommandMetadata::build("change-storage-credentials", "Replace storage credentials (e.g. Postgres account and password) stored for the attached wallet. The wallet key is not changed.")
+                .add_main_param_with_dynamic_completion("name", "Identifier of the wallet", DynamicCompletionType::Wallet)
+                .add_required_deferred_param("key", "Key or passphrase used for wallet key derivation.
+                                               Look to key_derivation_method param for information about supported key derivation methods.")
+                .add_optional_param("key_derivation_method", "Algorithm to use for wallet key derivation. One of:
+                                    argon2m - derive secured wallet key (used by default)
+                                    argon2i - derive secured wallet key (less secured but faster)
+                                    raw - raw key provided (skip derivation)")
+                .add_required_param("storage_credentials", "The list of key:value pairs defined by storage type.")
+                .add_example(r#"wallet change-storage-credentials wallet1 key storage_credentials={"account":"postgres","password":"new_password"}"#)
+                .finalize()
+    );
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx: {:?} params {:?}", ctx, secret!(params));
+
+        let id = ParamParser::get_str_param("name", params)?;
+        let key = ParamParser::get_str_param("key", params)?;
+        let key_derivation_method =
+            ParamParser::get_opt_str_param("key_derivation_method", params)?;
+        let storage_credentials = ParamParser::get_object_param("storage_credentials", params)?;
+
+        let mut config = WalletConfig::read(id)
+            .map_err(|_| println_err!("Wallet \"{}\" isn't attached to CLI", id))?;
+
+        let credentials = Credentials {
+            key: key.to_string(),
+            key_derivation_method: key_derivation_method_or_default(ctx, key_derivation_method),
+            storage_credentials: Some(storage_credentials),
+            ..Credentials::default()
+        };
+
+        Wallet::change_storage_credentials(&mut config, &credentials)
+            .map_err(|err| println_err!("{}", err.report(Some(id))))?;
+
+        println_succ!(
+            "Storage credentials of wallet \"{}\" have been checked and stored",
+            id
+        );
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commands::{setup, tear_down};
+
+    mod change_storage_credentials {
+        use super::*;
+        use crate::wallet::tests::{create_wallet, delete_wallet, WALLET, WALLET_KEY_RAW};
+
+        #[test]
+        pub fn change_storage_credentials_works_for_sqlite_wallet() {
+            let ctx = setup();
+            create_wallet(&ctx);
+            {
+                let cmd = change_storage_credentials_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert("key_derivation_method", "raw".to_string());
+                params.insert(
+                    "storage_credentials",
+                    r#"{"account":"postgres","password":"password"}"#.to_string(),
+                );
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            assert!(WalletConfig::read(WALLET)
+                .unwrap()
+                .storage_credentials
+                .is_none());
+            delete_wallet(&ctx);
+            tear_down();
+        }
+
+        #[test]
+        pub fn change_storage_credentials_works_for_not_attached_wallet() {
+            let ctx = setup();
+            {
+                let cmd = change_storage_credentials_command::new();
+                let mut params = CommandParams::new();
+                params.insert("name", WALLET.to_string());
+                params.insert("key", WALLET_KEY_RAW.to_string());
+                params.insert(
+                    "storage_credentials",
+                    r#"{"account":"postgres","password":"password"}"#.to_string(),
+                );
+                cmd.execute(&ctx, &params).unwrap_err();
+            }
+            tear_down();
+        }
+    }
+}
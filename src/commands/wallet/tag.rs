@@ -0,0 +1,185 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    command_executor::{Command, CommandContext, CommandMetadata, CommandParams, Precondition},
+    commands::wallet::search::format_tags,
+    params_parser::ParamParser,
+};
+
+pub mod tag_command {
+    use super::*;
+
+    command!(CommandMetadata::build(
+        "tag",
+        "Add or remove a tag of a wallet record without changing the record value, \
+            e.g. to fix a wrong `verkey` tag of a DID record after manual edits."
+    )
+    .add_main_param("action", "One of: add, remove")
+    .add_required_param("category", "Category of the record (e.g. `did`)")
+    .add_required_param("id", "Identifier of the record")
+    .add_required_param(
+        "tag",
+        "Tag as `<name>:<value>`. A tag of the same name is replaced by `add` action. \
+            Only the name can be passed to `remove` action to remove the tag regardless of its value"
+    )
+    .add_optional_param(
+        "encrypted",
+        "Whether the tag is encrypted (true by default). Plaintext tags are searched with `~` name prefix"
+    )
+    .add_example("wallet tag add category=did id=VsKV7grR1BUE29mG2Fm2kX tag=verkey:GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa")
+    .add_example("wallet tag add category=did id=VsKV7grR1BUE29mG2Fm2kX tag=team:issuers encrypted=false")
+    .add_example("wallet tag remove category=did id=VsKV7grR1BUE29mG2Fm2kX tag=team encrypted=false")
+    .add_precondition(Precondition::OpenedWallet)
+    .finalize());
+
+    fn execute(ctx: &CommandContext, params: &CommandParams) -> Result<(), ()> {
+        trace!("execute >> ctx {:?} params {:?}", ctx, params);
+
+        let wallet = ctx.ensure_opened_wallet()?;
+        let action = ParamParser::get_str_param("action", params)?;
+        let category = ParamParser::get_str_param("category", params)?;
+        let id = ParamParser::get_str_param("id", params)?;
+        let tag = ParamParser::get_str_param("tag", params)?;
+        let encrypted = ParamParser::get_opt_bool_param("encrypted", params)?.unwrap_or(true);
+
+        let (name, value) = match tag.split_once(':') {
+            Some((name, value)) => (name, Some(value)),
+            None => (tag, None),
+        };
+        if name.is_empty() {
+            println_err!(
+                "Invalid tag \"{}\". Tag must be passed as <name>:<value>.",
+                tag
+            );
+            return Err(());
+        }
+
+        let tags = match action {
+            "add" => {
+                let value = value.ok_or_else(|| {
+                    println_err!(
+                        "Tag value is missing in \"{}\". Tag must be passed as <name>:<value>.",
+                        tag
+                    )
+                })?;
+                let tags = wallet
+                    .add_record_tag(category, id, name, value, encrypted)
+                    .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+                println_succ!(
+                    "Tag \"{}\" has been added to \"{}\" record of \"{}\" category",
+                    name,
+                    id,
+                    category
+                );
+                tags
+            }
+            "remove" => {
+                let tags = wallet
+                    .remove_record_tag(category, id, name, value, encrypted)
+                    .map_err(|err| println_err!("{}", err.report(Some(&wallet.name))))?;
+                println_succ!(
+                    "Tag \"{}\" has been removed from \"{}\" record of \"{}\" category",
+                    name,
+                    id,
+                    category
+                );
+                tags
+            }
+            action => {
+                println_err!("Unknown action \"{}\". One of: add, remove.", action);
+                return Err(());
+            }
+        };
+
+        println!("Record tags:");
+        println!("{}", format_tags(&tags));
+
+        trace!("execute <<");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        commands::{setup_with_wallet, tear_down_with_wallet},
+        did::tests::{new_did, DID_MY1, SEED_MY1, VERKEY_MY1},
+    };
+
+    mod tag {
+        use super::*;
+
+        fn tag(ctx: &CommandContext, action: &str, tag: &str, encrypted: bool) -> Result<(), ()> {
+            let cmd = tag_command::new();
+            let mut params = CommandParams::new();
+            params.insert("action", action.to_string());
+            params.insert("category", "did".to_string());
+            params.insert("id", DID_MY1.to_string());
+            params.insert("tag", tag.to_string());
+            params.insert("encrypted", encrypted.to_string());
+            cmd.execute(ctx, &params)
+        }
+
+        fn search(ctx: &CommandContext, query: &str) -> usize {
+            let wallet = ctx.ensure_opened_wallet().unwrap();
+            wallet.search("did", Some(query), None).unwrap().len()
+        }
+
+        #[test]
+        pub fn tag_works() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+
+            tag(&ctx, "add", "team:issuers", true).unwrap();
+            assert_eq!(1, search(&ctx, r#"{"team":"issuers"}"#));
+
+            tag(&ctx, "add", "team:verifiers", true).unwrap();
+            assert_eq!(0, search(&ctx, r#"{"team":"issuers"}"#));
+            assert_eq!(1, search(&ctx, r#"{"team":"verifiers"}"#));
+
+            tag(&ctx, "remove", "team:issuers", true).unwrap_err();
+            tag(&ctx, "remove", "team", true).unwrap();
+            assert_eq!(0, search(&ctx, r#"{"team":"verifiers"}"#));
+            assert_eq!(
+                1,
+                search(&ctx, &format!(r#"{{"verkey":"{}"}}"#, VERKEY_MY1))
+            );
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn tag_works_for_plaintext_tag() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+
+            tag(&ctx, "add", "team:issuers", false).unwrap();
+            assert_eq!(1, search(&ctx, r#"{"~team":"issuers"}"#));
+            assert_eq!(0, search(&ctx, r#"{"team":"issuers"}"#));
+
+            tag(&ctx, "remove", "team", true).unwrap_err();
+            tag(&ctx, "remove", "team:issuers", false).unwrap();
+            assert_eq!(0, search(&ctx, r#"{"~team":"issuers"}"#));
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn tag_works_for_invalid_params() {
+            let ctx = setup_with_wallet();
+            new_did(&ctx, SEED_MY1);
+            tag(&ctx, "add", "team", true).unwrap_err();
+            tag(&ctx, "add", ":issuers", true).unwrap_err();
+            tag(&ctx, "update", "team:issuers", true).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+
+        #[test]
+        pub fn tag_works_for_unknown_record() {
+            let ctx = setup_with_wallet();
+            tag(&ctx, "add", "team:issuers", true).unwrap_err();
+            tear_down_with_wallet(&ctx);
+        }
+    }
+}
@@ -2,9 +2,14 @@ use aries_askar::{Error as AskarError, ErrorKind as AskarErrorKind};
 use indy_utils::{ConversionError, ValidationError};
 use indy_vdr::common::error::{VdrError, VdrErrorKind};
 use serde_json::Error as SerdeError;
-use std::io::Error as FileSystemError;
+use std::{cell::RefCell, io::Error as FileSystemError};
 use thiserror::Error;
 
+thread_local! {
+    /// The last error reported to the user, used to describe the failure of the command.
+    static LAST_ERROR: RefCell<Option<CommandError>> = const { RefCell::new(None) };
+}
+
 #[derive(Error, Debug)]
 pub enum CliError {
     #[error("`{0}`")]
@@ -21,6 +26,10 @@ pub enum CliError {
     VdrError(VdrError),
     #[error("File System error occurred `{0}`")]
     FileSystemError(FileSystemError),
+    #[error("Ledger proxy error occurred `{0}`")]
+    ProxyError(String),
+    #[error("Operation has been cancelled")]
+    Cancelled,
 }
 
 impl From<ValidationError> for CliError {
@@ -61,7 +70,138 @@ impl From<ConversionError> for CliError {
 
 pub type CliResult<T> = Result<T, CliError>;
 
+/// Stable codes of command failures for calling systems.
+/// Numeric values are used as process exit codes in batch mode, so they must never be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Command failed for a reason which is not classified.
+    CommandFailed = 1,
+    /// Unknown command or invalid command syntax.
+    InvalidCommand = 2,
+    NotFound = 10,
+    Duplicate = 20,
+    /// Transaction has been rejected by the Ledger.
+    LedgerReject = 30,
+    /// Ledger reply has not been received in time or nodes did not reach consensus.
+    LedgerTimeout = 31,
+    /// Pool is unavailable, not connected or misconfigured.
+    PoolError = 32,
+    InvalidInput = 40,
+    InvalidEntityState = 41,
+    WalletError = 50,
+    /// Invalid key provided for the wallet.
+    WalletAccessDenied = 51,
+    FileSystemError = 60,
+    Cancelled = 70,
+}
+
+impl ErrorCode {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorCode::CommandFailed => "CommandFailed",
+            ErrorCode::InvalidCommand => "InvalidCommand",
+            ErrorCode::NotFound => "NotFound",
+            ErrorCode::Duplicate => "Duplicate",
+            ErrorCode::LedgerReject => "LedgerReject",
+            ErrorCode::LedgerTimeout => "LedgerTimeout",
+            ErrorCode::PoolError => "PoolError",
+            ErrorCode::InvalidInput => "InvalidInput",
+            ErrorCode::InvalidEntityState => "InvalidEntityState",
+            ErrorCode::WalletError => "WalletError",
+            ErrorCode::WalletAccessDenied => "WalletAccessDenied",
+            ErrorCode::FileSystemError => "FileSystemError",
+            ErrorCode::Cancelled => "Cancelled",
+        }
+    }
+}
+
+/// Failure of a command: the code with the message reported to the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "error": {
+                "code": self.code.code(),
+                "name": self.code.name(),
+                "message": self.message,
+            }
+        })
+    }
+}
+
+/// Remembers the error reported to the user so the failed command can be described by its code.
+pub fn report_error(code: ErrorCode, message: &str) {
+    LAST_ERROR.with(|last_error| {
+        *last_error.borrow_mut() = Some(CommandError {
+            code,
+            message: message.to_string(),
+        })
+    });
+}
+
+pub fn take_reported_error() -> Option<CommandError> {
+    LAST_ERROR.with(|last_error| last_error.borrow_mut().take())
+}
+
 impl CliError {
+    /// Whether the error is caused by the network so the request may succeed if repeated.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            CliError::VdrError(vdr_error) => matches!(
+                vdr_error.kind(),
+                VdrErrorKind::PoolTimeout | VdrErrorKind::Unavailable | VdrErrorKind::Connection
+            ),
+            CliError::ProxyError(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CliError::Duplicate(_) => ErrorCode::Duplicate,
+            CliError::NotFound(_) => ErrorCode::NotFound,
+            CliError::InvalidEntityState(_) => ErrorCode::InvalidEntityState,
+            CliError::InvalidInput(_) => ErrorCode::InvalidInput,
+            CliError::VdrError(vdr_error) => match vdr_error.kind() {
+                VdrErrorKind::PoolRequestFailed(_) => ErrorCode::LedgerReject,
+                VdrErrorKind::PoolTimeout | VdrErrorKind::PoolNoConsensus => {
+                    ErrorCode::LedgerTimeout
+                }
+                VdrErrorKind::Input => ErrorCode::InvalidInput,
+                VdrErrorKind::FileSystem(_) => ErrorCode::FileSystemError,
+                _ => ErrorCode::PoolError,
+            },
+            CliError::AskarError(askar_error) => match askar_error.kind() {
+                AskarErrorKind::Duplicate => ErrorCode::Duplicate,
+                AskarErrorKind::NotFound => ErrorCode::NotFound,
+                AskarErrorKind::Encryption => ErrorCode::WalletAccessDenied,
+                AskarErrorKind::Input => ErrorCode::InvalidInput,
+                _ => ErrorCode::WalletError,
+            },
+            CliError::FileSystemError(_) => ErrorCode::FileSystemError,
+            CliError::ProxyError(_) => ErrorCode::PoolError,
+            CliError::Cancelled => ErrorCode::Cancelled,
+        }
+    }
+
+    /// Returns the message to print when a command fails because of the error
+    /// and remembers the error as the reason of the command failure.
+    pub fn report(&self, extra: Option<&str>) -> String {
+        let message = self.message(extra);
+        report_error(self.code(), &message);
+        message
+    }
+
+    /// Returns the message describing the error to the user.
     pub fn message(&self, extra: Option<&str>) -> String {
         match self {
             CliError::InvalidInput(message)
@@ -127,6 +267,45 @@ impl CliError {
                 }
             },
             CliError::FileSystemError(fs_error) => fs_error.to_string(),
+            CliError::ProxyError(message) => format!("Ledger proxy request failed: {}", message),
+            CliError::Cancelled => "Operation has been cancelled by user.".to_string(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_does_not_report_error() {
+        take_reported_error();
+        CliError::NotFound("DID does not exist".to_string()).message(None);
+        assert_eq!(take_reported_error(), None);
+    }
+
+    #[test]
+    fn report_works() {
+        take_reported_error();
+        let message = CliError::NotFound("DID does not exist".to_string()).report(None);
+        assert_eq!(
+            take_reported_error(),
+            Some(CommandError {
+                code: ErrorCode::NotFound,
+                message,
+            })
+        );
+        assert_eq!(take_reported_error(), None);
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        assert_eq!(ErrorCode::NotFound.code(), 10);
+        assert_eq!(ErrorCode::Duplicate.code(), 20);
+        assert_eq!(ErrorCode::LedgerReject.code(), 30);
+        assert_eq!(
+            CliError::Duplicate(String::new()).code(),
+            ErrorCode::Duplicate
+        );
+    }
+}
@@ -0,0 +1,44 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+//! In-process API of Indy CLI.
+//!
+//! The library exposes the layer the CLI commands are built on, so wallets, pool connections,
+//! DIDs and ledger requests can be managed from other Rust code and tests without driving
+//! the interactive command executor:
+//!
+//! * [`tools::wallet::Wallet`] - create, open, export and import Askar wallets.
+//! * [`tools::pool::Pool`] - manage pool configurations and connect to the pool.
+//! * [`tools::did::Did`] - create and list DIDs stored in a wallet.
+//! * [`tools::ledger::Ledger`] - build, sign and submit ledger requests.
+//!
+//! All operations return [`error::CliResult`]. Wallets and pool configurations are stored
+//! in the same locations as used by the CLI binary (see [`utils::environment::EnvironmentUtils`]).
+//!
+//! ```no_run
+//! use indy_cli_rs::tools::{
+//!     did::Did,
+//!     wallet::{wallet_config::WalletConfig, Credentials, Wallet},
+//! };
+//!
+//! let config = WalletConfig::read("wallet1").unwrap();
+//! let credentials = Credentials {
+//!     key: "key".to_string(),
+//!     ..Default::default()
+//! };
+//! let wallet = Wallet::open(&config, &credentials, None, None).unwrap();
+//! let dids = Did::list(&wallet).unwrap();
+//! ```
+#![cfg_attr(feature = "fatal_warnings", deny(warnings))]
+
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+
+pub mod error;
+pub mod tools;
+pub mod utils;
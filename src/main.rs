@@ -8,18 +8,34 @@ extern crate serde_derive;
 extern crate serde_json;
 
 #[macro_use]
-mod utils;
+mod cli;
+#[macro_use]
+mod macros;
 mod command_executor;
+mod pager;
 mod params_parser;
 #[macro_use]
 mod commands;
-mod error;
-mod tools;
+
+use indy_cli_rs::{error, tools};
+
+/// Library utilities together with the ones used only by the CLI.
+mod utils {
+    pub use crate::cli::*;
+    pub use indy_cli_rs::utils::*;
+}
 
 use crate::{
     command_executor::CommandExecutor,
-    commands::{common, did, ledger, pool, wallet},
-    utils::history,
+    commands::{common, config, did, key, ledger, network, pool, session, wallet},
+    error::{CommandError, ErrorCode},
+    tools::wallet::Wallet,
+    utils::{
+        batch::{self, BatchDirective},
+        environment::EnvironmentUtils,
+        history,
+        term::Theme,
+    },
 };
 
 use linefeed::{
@@ -27,79 +43,197 @@ use linefeed::{
     Interface, Prompter, ReadResult, Signal, Terminal,
 };
 
-use std::{env, fs::File, io::BufReader, sync::Arc};
+use std::{collections::BTreeMap, env, fs::File, io::BufReader, path::Path, sync::Arc};
 
 fn main() {
     #[cfg(target_os = "windows")]
     let _ = ansi_term::enable_ansi_support().is_ok();
 
+    let exit_code = run();
+    close_cached_wallets();
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
+}
+
+/// Runs CLI and returns the process exit code.
+/// In batch mode the code of the command which stopped the execution is returned.
+fn run() -> i32 {
     let mut args = env::args();
     args.next(); // skip library
 
     let command_executor = build_executor();
+    utils::futures::init_cancellation(close_cached_wallets);
+
+    let default_config = EnvironmentUtils::cli_config_path();
+    if default_config.exists() && !env::args().any(|arg| arg == "-h" || arg == "--help") {
+        let file = default_config.to_string_lossy();
+        if let Err(err) = CliConfig::apply(&file, &command_executor) {
+            println_err!("{}", err);
+            return ErrorCode::InvalidInput.code();
+        }
+    }
+
+    let mut exit_code = 0;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
-            "-h" | "--help" => return _print_help(),
+            "-h" | "--help" => {
+                _print_help();
+                return 0;
+            }
+            "-y" | "--yes" => {
+                command_executor.ctx().set_auto_confirm(true);
+            }
+            "--no-color" => {
+                Theme::set(Theme::None);
+            }
             "--config" => {
-                let file = unwrap_or_return!(
-                    args.next(),
-                    println_err!("CLI configuration file is not specified")
-                );
+                let file = unwrap_or_return!(args.next(), {
+                    println_err!("CLI configuration file is not specified");
+                    ErrorCode::InvalidCommand.code()
+                });
 
-                match CliConfig::read_from_file(&file)
-                    .and_then(|config| config.handle(&command_executor))
-                {
+                match CliConfig::apply(&file, &command_executor) {
                     Ok(()) => {}
-                    Err(err) => return println_err!("{}", err),
+                    Err(err) => {
+                        println_err!("{}", err);
+                        return ErrorCode::InvalidInput.code();
+                    }
                 }
             }
             "--logger-config" => {
-                let file = unwrap_or_return!(
-                    args.next(),
-                    println_err!("Logger config file is not specified")
-                );
+                let file = unwrap_or_return!(args.next(), {
+                    println_err!("Logger config file is not specified");
+                    ErrorCode::InvalidCommand.code()
+                });
                 match utils::logger::IndyCliLogger::init(&file) {
                     Ok(()) => println_succ!(
                         "Logger has been initialized according to the config file: \"{}\"",
                         file
                     ),
-                    Err(err) => return println_err!("{}", err),
+                    Err(err) => {
+                        println_err!("{}", err);
+                        return ErrorCode::InvalidInput.code();
+                    }
                 }
             }
+            "--profile" => {
+                let file = unwrap_or_return!(args.next(), {
+                    println_err!("Timing report file is not specified");
+                    ErrorCode::InvalidCommand.code()
+                });
+                command_executor.ctx().set_profile_file(&file);
+            }
             "--plugins" => {
-                unwrap_or_return!(args.next(), println_err!("Plugins are not specified"));
+                unwrap_or_return!(args.next(), {
+                    println_err!("Plugins are not specified");
+                    ErrorCode::InvalidCommand.code()
+                });
                 println_warn!("Option DEPRECATED!");
             }
             _ if args.len() == 0 => {
-                execute_batch(&command_executor, Some(&arg));
+                if !execute_batch(&command_executor, Some(&arg)) {
+                    exit_code = batch_exit_code(&command_executor);
+                }
 
                 if command_executor.ctx().is_exit() {
-                    return;
+                    return exit_code;
                 }
             }
             _ => {
                 println_err!("Unknown option");
-                return _print_help();
+                _print_help();
+                return ErrorCode::InvalidCommand.code();
             }
         }
     }
-    execute_stdin(command_executor);
+
+    match execute_stdin(command_executor) {
+        0 => exit_code,
+        code => code,
+    }
 }
 
+/// Closes wallet stores kept open within the session and releases their wallet locks before exit.
+fn close_cached_wallets() {
+    if let Err(err) = Wallet::close_cached() {
+        warn!("Unable to close cached wallets: {}", err.message(None));
+    }
+}
+
+/// Exit code describing the failure of the batch execution.
+fn batch_exit_code(command_executor: &CommandExecutor) -> i32 {
+    command_executor
+        .ctx()
+        .get_last_error()
+        .map(|error| error.code)
+        .unwrap_or(ErrorCode::CommandFailed)
+        .code()
+}
+
+/// CLI defaults read from JSON file (camelCase keys) or TOML file (snake_case keys).
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
 pub struct CliConfig {
+    #[serde(alias = "logger_config")]
     pub logger_config: Option<String>,
+    #[serde(alias = "taa_acceptance_mechanism")]
     pub taa_acceptance_mechanism: Option<String>,
+    #[serde(alias = "role_titles")]
+    pub role_titles: Option<BTreeMap<String, String>>,
+    #[serde(alias = "txn_titles")]
+    pub txn_titles: Option<BTreeMap<String, String>>,
+    /// Libindy plugins of the original CLI. Not supported anymore.
+    pub plugins: Option<serde_json::Value>,
+    #[serde(alias = "ledger_plugins")]
+    pub ledger_plugins: Option<Vec<String>>,
+    #[serde(alias = "key_derivation_method")]
+    pub key_derivation_method: Option<String>,
+    #[serde(alias = "history_size")]
+    pub history_size: Option<usize>,
+    #[serde(alias = "default_pool")]
+    pub default_pool: Option<String>,
+    #[serde(alias = "request_timeout")]
+    pub request_timeout: Option<u64>,
+    #[serde(alias = "request_retries")]
+    pub request_retries: Option<u64>,
+    #[serde(alias = "pool_ack_timeout")]
+    pub pool_ack_timeout: Option<u64>,
+    #[serde(alias = "pool_conn_request_limit")]
+    pub pool_conn_request_limit: Option<u64>,
+    #[serde(alias = "pool_read_nodes")]
+    pub pool_read_nodes: Option<u64>,
+    #[serde(alias = "auto_confirm")]
+    pub auto_confirm: Option<bool>,
+    #[serde(alias = "json_errors")]
+    pub json_errors: Option<bool>,
+    pub theme: Option<String>,
+    #[serde(alias = "prompt_template")]
+    pub prompt_template: Option<String>,
+    #[serde(alias = "auto_backup_dir")]
+    pub auto_backup_dir: Option<String>,
+    #[serde(alias = "auto_backup_keep")]
+    pub auto_backup_keep: Option<u64>,
 }
 
 impl CliConfig {
+    fn apply(file: &str, command_executor: &CommandExecutor) -> Result<(), String> {
+        Self::read_from_file(file)?.handle(command_executor)?;
+        command_executor.ctx().add_config_file(file);
+        Ok(())
+    }
+
     fn read_from_file(file: &str) -> Result<CliConfig, String> {
         let content = utils::file::read_file(file)?;
-        let config: CliConfig = serde_json::from_str(&content)
-            .map_err(|err| format!("Invalid CLI configuration file: {:?}", err))?;
+        let config: CliConfig = if file.ends_with(".toml") {
+            toml::from_str(&content)
+                .map_err(|err| format!("Invalid CLI configuration file \"{}\": {}", file, err))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|err| format!("Invalid CLI configuration file: {:?}", err))?
+        };
         Ok(config)
     }
 
@@ -120,6 +254,104 @@ impl CliConfig {
                 taa_acceptance_mechanism
             );
         }
+        for (role, title) in self.role_titles.iter().flatten() {
+            command_executor.ctx().set_role_title(role, title);
+        }
+        for (txn_type, title) in self.txn_titles.iter().flatten() {
+            command_executor.ctx().set_txn_title(txn_type, title);
+        }
+        if self.plugins.is_some() {
+            println_warn!("Config field \"plugins\" is DEPRECATED and ignored. Use \"ledgerPlugins\" to load ledger plugins.");
+        }
+        for plugin in self.ledger_plugins.iter().flatten() {
+            let (name, _) = ledger::load_plugin(command_executor.ctx(), plugin)?;
+            println_succ!("Plugin \"{}\" has been loaded", name);
+        }
+        if let Some(ref method) = self.key_derivation_method {
+            wallet::validate_key_derivation_method(method)?;
+            command_executor
+                .ctx()
+                .set_key_derivation_method(Some(method.to_string()));
+        }
+        if let Some(history_size) = self.history_size {
+            command_executor.ctx().set_history_size(history_size);
+        }
+        if let Some(request_timeout) = self.request_timeout {
+            if request_timeout == 0 {
+                return Err("Request timeout must be greater than 0.".to_string());
+            }
+            command_executor
+                .ctx()
+                .set_request_timeout(Some(request_timeout));
+        }
+        if let Some(request_retries) = self.request_retries {
+            command_executor.ctx().set_request_retries(request_retries);
+        }
+        if let Some(pool_ack_timeout) = self.pool_ack_timeout {
+            if pool_ack_timeout == 0 {
+                return Err("Pool ack timeout must be greater than 0.".to_string());
+            }
+            command_executor
+                .ctx()
+                .set_pool_ack_timeout(Some(pool_ack_timeout));
+        }
+        if let Some(pool_conn_request_limit) = self.pool_conn_request_limit {
+            if pool_conn_request_limit == 0 {
+                return Err("Pool connection request limit must be greater than 0.".to_string());
+            }
+            command_executor
+                .ctx()
+                .set_pool_conn_request_limit(Some(pool_conn_request_limit));
+        }
+        if let Some(pool_read_nodes) = self.pool_read_nodes {
+            if pool_read_nodes == 0 {
+                return Err("Number of pool read nodes must be greater than 0.".to_string());
+            }
+            command_executor
+                .ctx()
+                .set_pool_read_nodes(Some(pool_read_nodes));
+        }
+        if let Some(auto_confirm) = self.auto_confirm {
+            command_executor.ctx().set_auto_confirm(auto_confirm);
+        }
+        if let Some(json_errors) = self.json_errors {
+            command_executor.ctx().set_json_errors(json_errors);
+        }
+        if let Some(ref theme) = self.theme {
+            Theme::set(Theme::parse(theme)?);
+        }
+        if let Some(ref auto_backup_dir) = self.auto_backup_dir {
+            command_executor
+                .ctx()
+                .set_auto_backup_dir(Some(auto_backup_dir.to_string()));
+        }
+        if let Some(auto_backup_keep) = self.auto_backup_keep {
+            if auto_backup_keep == 0 {
+                return Err(
+                    "Number of automatic backups to keep must be greater than 0.".to_string(),
+                );
+            }
+            command_executor
+                .ctx()
+                .set_auto_backup_keep(auto_backup_keep);
+        }
+        if let Some(ref prompt_template) = self.prompt_template {
+            command_executor
+                .ctx()
+                .set_prompt_template(Some(prompt_template.to_string()));
+        }
+        if let Some(ref default_pool) = self.default_pool {
+            command_executor
+                .ctx()
+                .set_default_pool(Some(default_pool.to_string()));
+            if command_executor.ctx().get_connected_pool().is_none() {
+                command_executor
+                    .execute(&format!("pool connect {}", default_pool))
+                    .map_err(|_| {
+                        format!("Unable to connect to default pool \"{}\"", default_pool)
+                    })?;
+            }
+        }
         Ok(())
     }
 }
@@ -129,64 +361,127 @@ fn build_executor() -> CommandExecutor {
         .add_command(common::about_command::new())
         .add_command(common::exit_command::new())
         .add_command(common::prompt_command::new())
+        .add_command(common::set_command::new())
         .add_command(common::show_command::new())
         .add_command(common::load_plugin_command::new())
         .add_command(common::init_logger_command::new())
+        .add_group(config::group::new())
+        .add_command(config::show_command::new())
+        .finalize_group()
         .add_group(did::group::new())
         .add_command(did::new_command::new())
         .add_command(did::set_metadata_command::new())
+        .add_command(did::alias_command::new())
         .add_command(did::import_command::new())
         .add_command(did::use_command::new())
         .add_command(did::rotate_key_command::new())
         .add_command(did::list_command::new())
+        .add_command(did::find_command::new())
+        .add_command(did::genseed_command::new())
+        .add_command(did::convert_verkey_command::new())
         .add_command(did::qualify_command::new())
+        .add_command(did::copy_command::new())
+        .add_command(did::delete_command::new())
+        .add_command(ledger::attrib::set_endpoint_command::new())
+        .add_command(ledger::attrib::get_endpoint_command::new())
+        .finalize_group()
+        .add_group(key::group::new())
+        .add_command(key::create_command::new())
+        .add_command(key::gen_bls_command::new())
+        .add_command(key::list_command::new())
+        .add_command(key::delete_command::new())
+        .add_command(key::sign_command::new())
+        .add_command(key::verify_command::new())
         .finalize_group()
         .add_group(pool::group::new())
         .add_command(pool::create_command::new())
+        .add_command(pool::clone_command::new())
+        .add_command(pool::localnet_genesis_command::new())
+        .add_command(pool::inspect_genesis_command::new())
         .add_command(pool::connect_command::new())
         .add_command(pool::refresh_command::new())
+        .add_command(pool::auto_refresh_command::new())
+        .add_command(pool::ping_command::new())
         .add_command(pool::list_command::new())
         .add_command(pool::disconnect_command::new())
         .add_command(pool::delete_command::new())
         .add_command(pool::show_taa_command::new())
         .add_command(pool::set_protocol_version_command::new())
         .finalize_group()
+        .add_group(network::group::new())
+        .add_command(network::add_command::new())
+        .add_command(network::use_command::new())
+        .add_command(network::list_command::new())
+        .add_command(network::delete_command::new())
+        .finalize_group()
+        .add_group(session::group::new())
+        .add_command(session::save_command::new())
+        .add_command(session::restore_command::new())
+        .finalize_group()
         .add_group(wallet::group::new())
         .add_command(wallet::create_command::new())
         .add_command(wallet::attach_command::new())
         .add_command(wallet::open_command::new())
         .add_command(wallet::list_command::new())
+        .add_command(wallet::status_command::new())
+        .add_command(wallet::stats_command::new())
+        .add_command(wallet::search_command::new())
+        .add_command(wallet::tag_command::new())
         .add_command(wallet::close_command::new())
         .add_command(wallet::delete_command::new())
         .add_command(wallet::detach_command::new())
+        .add_command(wallet::change_storage_credentials_command::new())
         .add_command(wallet::export_command::new())
         .add_command(wallet::import_command::new())
+        .add_command(wallet::check_command::new())
+        .add_command(wallet::rebuild_dids_command::new())
+        .add_command(wallet::create_profile_command::new())
+        .add_command(wallet::list_profiles_command::new())
         .finalize_group()
         .add_group(ledger::group::new())
         .add_command(ledger::nym::nym_command::new())
+        .add_command(ledger::nym::nym_blacklist_command::new())
+        .add_command(ledger::nym::nym_batch_command::new())
+        .add_command(ledger::nym::seed_identities_command::new())
         .add_command(ledger::nym::get_nym_command::new())
+        .add_command(ledger::did_document::did_document_command::new())
         .add_command(ledger::attrib::attrib_command::new())
         .add_command(ledger::attrib::get_attrib_command::new())
         .add_command(ledger::schema::schema_command::new())
         .add_command(ledger::schema::get_schema_command::new())
+        .add_command(ledger::rich_schema::rich_schema_command::new())
+        .add_command(ledger::rich_schema::get_rich_schema_by_id_command::new())
+        .add_command(ledger::rich_schema::get_rich_schema_by_metadata_command::new())
         .add_command(ledger::validator_info::get_validator_info_command::new())
+        .add_command(ledger::verify_txn::verify_txn_command::new())
         .add_command(ledger::cred_def::cred_def_command::new())
         .add_command(ledger::cred_def::get_cred_def_command::new())
         .add_command(ledger::node::node_command::new())
+        .add_command(ledger::node::node_demote_command::new())
+        .add_command(ledger::node::node_promote_command::new())
         .add_command(ledger::pool_config::pool_config_command::new())
         .add_command(ledger::pool_restart::pool_restart_command::new())
         .add_command(ledger::pool_upgrade::pool_upgrade_command::new())
+        .add_command(ledger::pool_upgrade::pool_upgrade_schedule_command::new())
         .add_command(ledger::custom::custom_command::new())
         .add_command(ledger::sign_multi::sign_multi_command::new())
+        .add_command(ledger::multisig::multisig_command::new())
         .add_command(ledger::auth_rule::auth_rule_command::new())
+        .add_command(ledger::auth_rule::auth_rule_wizard_command::new())
         .add_command(ledger::auth_rule::auth_rules_command::new())
         .add_command(ledger::auth_rule::get_auth_rule_command::new())
+        .add_command(ledger::auth_rule::auth_rules_diff_command::new())
         .add_command(ledger::transaction::save_transaction_command::new())
         .add_command(ledger::transaction::load_transaction_command::new())
+        .add_command(ledger::transaction::export_request_command::new())
+        .add_command(ledger::transaction::import_request_command::new())
+        .add_command(ledger::transaction::submit_signed_command::new())
         .add_command(ledger::transaction_author_agreement::taa_command::new())
         .add_command(ledger::transaction_author_agreement::aml_command::new())
         .add_command(ledger::transaction_author_agreement::get_acceptance_mechanisms_command::new())
+        .add_command(ledger::transaction_author_agreement::get_taa_command::new())
         .add_command(ledger::endorser::endorse_transaction_command::new())
+        .add_command(ledger::endorser::endorse_inbox_command::new())
         .add_command(ledger::transaction_author_agreement::taa_disable_all_command::new())
         .add_command(ledger::frozen_ledger::ledgers_freeze_command::new())
         .add_command(ledger::frozen_ledger::get_frozen_ledgers_command::new())
@@ -194,10 +489,19 @@ fn build_executor() -> CommandExecutor {
         .finalize()
 }
 
-fn execute_stdin(command_executor: CommandExecutor) {
+fn execute_stdin(command_executor: CommandExecutor) -> i32 {
     match Interface::new("indy-cli-rs") {
-        Ok(reader) => execute_interactive(command_executor, reader),
-        Err(_) => execute_batch(&command_executor, None),
+        Ok(reader) => {
+            execute_interactive(command_executor, reader);
+            0
+        }
+        Err(_) => {
+            if execute_batch(&command_executor, None) {
+                0
+            } else {
+                batch_exit_code(&command_executor)
+            }
+        }
     }
 }
 
@@ -208,7 +512,7 @@ where
     let command_executor = Arc::new(command_executor);
     reader.set_completer(command_executor.clone());
     reader.set_prompt(&command_executor.ctx().get_prompt()).ok();
-    history::load(&mut reader).ok();
+    history::load(&mut reader, command_executor.ctx().get_history_size()).ok();
 
     while let Ok(read_result) = reader.read_line() {
         match read_result {
@@ -218,7 +522,8 @@ where
                     continue;
                 }
 
-                let _ = command_executor.execute(&line).is_ok();
+                let substituted = batch::substitute(line, &command_executor.ctx().get_variables());
+                _execute_line(&command_executor, &substituted, Path::new("."), 0);
                 history::add(line, &reader).ok();
                 reader.set_prompt(&command_executor.ctx().get_prompt()).ok();
 
@@ -239,21 +544,40 @@ where
     }
 }
 
-fn execute_batch(command_executor: &CommandExecutor, script_path: Option<&str>) {
+fn execute_batch(command_executor: &CommandExecutor, script_path: Option<&str>) -> bool {
     command_executor.ctx().set_batch_mode();
-    if let Some(script_path) = script_path {
-        let file = match File::open(script_path) {
-            Ok(file) => file,
-            Err(err) => {
-                return println_err!("Can't open script file {}\nError: {}", script_path, err)
-            }
-        };
-        _iter_batch(command_executor, BufReader::new(file));
+    let res = if let Some(script_path) = script_path {
+        _execute_batch_file(command_executor, Path::new(script_path), 0)
     } else {
         let stdin = std::io::stdin();
-        _iter_batch(command_executor, stdin.lock());
+        _iter_batch(command_executor, stdin.lock(), Path::new("."), 0)
     };
     command_executor.ctx().set_not_batch_mode();
+    res
+}
+
+fn _execute_batch_file(
+    command_executor: &CommandExecutor,
+    script_path: &Path,
+    depth: usize,
+) -> bool {
+    let file = match File::open(script_path) {
+        Ok(file) => file,
+        Err(err) => {
+            println_err!(
+                "Can't open script file {}\nError: {}",
+                script_path.display(),
+                err
+            );
+            command_executor.ctx().set_last_error(Some(CommandError {
+                code: ErrorCode::FileSystemError,
+                message: err.to_string(),
+            }));
+            return false;
+        }
+    };
+    let base_dir = script_path.parent().unwrap_or_else(|| Path::new("."));
+    _iter_batch(command_executor, BufReader::new(file), base_dir, depth)
 }
 
 fn _print_help() {
@@ -269,6 +593,22 @@ fn _print_help() {
         "\tBatch - all commands will be read from text file or pipe and executed in series."
     );
     println_acc!("\tUsage: indy-cli-rs <path-to-text-file>");
+    println_acc!(
+        "\tScripts can define variables with \"set NAME=value\", reference them as {}",
+        "$NAME, ${NAME} or {{NAME}}"
+    );
+    println_acc!("\tand execute other scripts with \"include <path-to-text-file>\".");
+    println_acc!("\tVariables are available in the interactive mode as well: \"unset NAME\" removes the variable,");
+    println_acc!("\t\"show NAME\" prints its value and \"show\" lists all variables.");
+    println_acc!(
+        "\tBatch execution stops at the first failed command (unless the line starts with \"-\")"
+    );
+    println_acc!("\tand the process exits with the code of the failure: 1 - command failed, 2 - invalid command,");
+    println_acc!("\t10 - not found, 20 - duplicate, 30 - rejected by the Ledger, 31 - Ledger timeout, 32 - pool error,");
+    println_acc!(
+        "\t40 - invalid input, 41 - invalid state, 50 - wallet error, 51 - invalid wallet key,"
+    );
+    println_acc!("\t60 - file system error, 70 - cancelled.");
     println!();
     println_acc!("Options:");
     println_acc!("\tLoad plugins in Libindy.");
@@ -277,17 +617,35 @@ fn _print_help() {
     println_acc!("\tInit logger according to a config file. \n\tIndy Cli uses `log4rs` logging framework: https://crates.io/crates/log4rs");
     println_acc!("\tUsage: indy-cli-rs --logger-config <path-to-config-file>");
     println!();
+    println_acc!("\tAnswer `yes` to all interactive confirmations (is equal to usage of \"set auto-confirm=true\" command).");
+    println_acc!("\tUsage: indy-cli-rs --yes");
+    println!();
+    println_acc!("\tPrint plain text without colors and progress animation, e.g. for CI logs (is equal to usage of \"set theme=none\" command).");
+    println_acc!("\tUsage: indy-cli-rs --no-color");
+    println!();
+    println_acc!("\tWrite JSON report with elapsed time of every executed command (and build/sign/submit/parse phases of ledger commands).");
+    println_acc!("\tUsage: indy-cli-rs --profile <path-to-report-file>");
+    println!();
     println_acc!(
         "\tUse config file for CLI initialization. A config file can contain the following fields:"
     );
-    println_acc!("\t\tplugins - a list of plugins to load in Libindy (is equal to usage of \"--plugins\" option).");
+    println_acc!("\t\tledgerPlugins - a list of ledger plugin definition files to load (is equal to usage of \"load-plugin\" command).");
     println_acc!("\t\tloggerConfig - path to a logger config file (is equal to usage of \"--logger-config\" option).");
     println_acc!("\t\ttaaAcceptanceMechanism - transaction author agreement acceptance mechanism to use for sending write transactions to the Ledger.");
+    println_acc!(
+        "\t\troleTitles - object mapping role codes to titles displayed in ledger command output."
+    );
+    println_acc!("\t\ttxnTitles - object mapping transaction type codes to titles displayed in ledger command output.");
     println_acc!("\tUsage: indy-cli-rs --config <path-to-config-json-file>");
     println!();
 }
 
-fn _iter_batch<T>(command_executor: &CommandExecutor, reader: T)
+fn _iter_batch<T>(
+    command_executor: &CommandExecutor,
+    reader: T,
+    base_dir: &Path,
+    depth: usize,
+) -> bool
 where
     T: std::io::BufRead,
 {
@@ -296,7 +654,8 @@ where
         let line = if let Ok(line) = line {
             line
         } else {
-            return println_err!("Can't parse line #{}", line_num);
+            println_err!("Can't parse line #{}", line_num);
+            return false;
         };
 
         if line.starts_with('#') || line.is_empty() {
@@ -304,14 +663,20 @@ where
             continue;
         }
 
-        println!("{}", line);
+        println!("{}", batch::mask_line(&line));
         let (line, force) = if line.starts_with('-') {
             (line[1..].as_ref(), true)
         } else {
             (line[0..].as_ref(), false)
         };
-        if command_executor.execute(line).is_err() && !force {
-            return println_err!("Batch execution failed at line #{}", line_num);
+
+        command_executor.ctx().set_last_error(None);
+        let substituted = batch::substitute(line, &command_executor.ctx().get_variables());
+        let res = _execute_line(command_executor, &substituted, base_dir, depth);
+
+        if !res && !force {
+            println_err!("Batch execution failed at line #{}", line_num);
+            return false;
         }
         println!();
         line_num += 1;
@@ -320,6 +685,67 @@ where
             break;
         }
     }
+    true
+}
+
+fn _execute_line(
+    command_executor: &CommandExecutor,
+    line: &str,
+    base_dir: &Path,
+    depth: usize,
+) -> bool {
+    match batch::parse_directive(line) {
+        Some(BatchDirective::SetVariable(name, value)) => {
+            command_executor.ctx().set_variable(name, value);
+            println_succ!("Variable \"{}\" has been set", name);
+            true
+        }
+        Some(BatchDirective::UnsetVariable(name)) => {
+            match command_executor.ctx().unset_variable(name) {
+                Some(_) => {
+                    println_succ!("Variable \"{}\" has been unset", name);
+                    true
+                }
+                None => {
+                    println_err!("Variable \"{}\" is not defined", name);
+                    false
+                }
+            }
+        }
+        Some(BatchDirective::ShowVariable(None)) => {
+            let variables = command_executor.ctx().get_variables();
+            if variables.is_empty() {
+                println!("There are no variables defined");
+            }
+            for (name, value) in variables {
+                println!("{}={}", name, value);
+            }
+            true
+        }
+        Some(BatchDirective::ShowVariable(Some(name)))
+            if command_executor.ctx().get_variables().contains_key(name) =>
+        {
+            println!("{}", command_executor.ctx().get_variables()[name]);
+            true
+        }
+        Some(BatchDirective::Include(path)) => {
+            if depth >= batch::MAX_INCLUDE_DEPTH {
+                println_err!(
+                    "Script \"{}\" can't be included: maximum include depth {} exceeded",
+                    path,
+                    batch::MAX_INCLUDE_DEPTH
+                );
+                return false;
+            }
+            _execute_batch_file(command_executor, &base_dir.join(path), depth + 1)
+        }
+        Some(BatchDirective::Setting(name, value)) => command_executor
+            .execute(&format!("set {}={}", name, value))
+            .is_ok(),
+        Some(BatchDirective::ShowVariable(Some(_))) | None => {
+            command_executor.execute(line).is_ok()
+        }
+    }
 }
 
 impl<Term: Terminal> Completer<Term> for CommandExecutor {
@@ -342,3 +768,20 @@ impl<Term: Terminal> Completer<Term> for CommandExecutor {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_line_works_for_theme_setting() {
+        let command_executor = build_executor();
+        let execute = |line| _execute_line(&command_executor, line, Path::new("."), 0);
+
+        assert!(!execute("set theme=blue"));
+        assert!(!execute("set theme blue"));
+        assert!(execute("set theme dark"));
+        assert_eq!(Theme::Dark, Theme::current());
+        assert!(command_executor.ctx().get_variables().is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{
+    command_executor::{read_user_input, CommandContext},
+    utils::term::is_term,
+};
+
+use std::{
+    env,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+const DEFAULT_PAGE_SIZE: usize = 40;
+
+/// Prints the content page by page if it does not fit into the terminal.
+/// The pager set by `PAGER` environment variable is used if available.
+pub fn print_paged(ctx: &CommandContext, content: &str) {
+    let page_size = page_size();
+    let lines = content.lines().collect::<Vec<&str>>();
+
+    if ctx.is_batch_mode() || cfg!(test) || !is_term() || lines.len() <= page_size {
+        println!("{}", content);
+        return;
+    }
+
+    if let Ok(pager) = env::var("PAGER") {
+        match run_pager(&pager, content) {
+            Ok(()) => return,
+            Err(err) => println_warn!("Unable to run \"{}\" pager: {}", pager, err),
+        }
+    }
+
+    let pages = lines.chunks(page_size).collect::<Vec<&[&str]>>();
+    for (index, page) in pages.iter().enumerate() {
+        println!("{}", page.join("\n"));
+
+        if index + 1 == pages.len() {
+            break;
+        }
+
+        match read_user_input(ctx, "-- More -- (Enter to continue, q to quit) ") {
+            Some(reply) if reply != "q" => continue,
+            _ => break,
+        }
+    }
+}
+
+fn page_size() -> usize {
+    // leave a room for the prompt
+    env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse::<usize>().ok())
+        .map(|lines| lines.saturating_sub(2).max(1))
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+}
+
+fn run_pager(pager: &str, content: &str) -> std::io::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(pager)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may be closed before reading the whole content
+        stdin.write_all(content.as_bytes()).ok();
+    }
+
+    child.wait().map(|_| ())
+}
@@ -3,4 +3,10 @@
     https://digital.gov.bc.ca/digital-trust
 */
 pub const KEY_TYPE: &'static str = "ed25519";
+pub const KEY_TYPE_SECP256K1: &'static str = "secp256k1";
 pub const CATEGORY_DID: &'static str = "did";
+pub const CATEGORY_DID_ALIAS: &'static str = "did_alias";
+pub const CATEGORY_BLS_KEY: &'static str = "bls_key";
+pub const METADATA_TAG_PREFIX: &'static str = "meta:";
+/// Tag of the keys created for DIDs: verkeys, next verkeys and key agreement keys.
+pub const KEY_TAG_DID_KEY: &'static str = "did_key";
@@ -0,0 +1,195 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::error::{CliError, CliResult};
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+const ED25519_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2018/v1";
+const ED25519_KEY_TYPE: &str = "Ed25519VerificationKey2018";
+const VERKEY_FRAGMENT: &str = "verkey";
+const DIDCOMM_FRAGMENT: &str = "did-communication";
+
+const ENDPOINT_DIDCOMM: &str = "endpoint";
+const ENDPOINT_ROUTING_KEYS: &str = "routingKeys";
+const ENDPOINT_TYPES: &str = "types";
+
+/// DID Document resolved from the Ledger data of a DID according to did:indy and did:sov methods:
+/// the verification method is made from NYM verkey, services are made either from NYM `diddocContent`
+/// or, if there is no such content, from the `endpoint` ATTRIB.
+pub struct DidDocument;
+
+impl DidDocument {
+    /// `id` is the fully qualified DID, `verkey` is the full (not abbreviated) NYM verkey.
+    pub fn build(
+        id: &str,
+        verkey: &str,
+        endpoint: Option<&JsonMap<String, JsonValue>>,
+        diddoc_content: Option<&JsonValue>,
+    ) -> CliResult<JsonValue> {
+        let verkey_id = format!("{}#{}", id, VERKEY_FRAGMENT);
+
+        let mut document = json!({
+            "@context": [DID_CONTEXT, ED25519_CONTEXT],
+            "id": id,
+            "verificationMethod": [{
+                "id": verkey_id,
+                "type": ED25519_KEY_TYPE,
+                "controller": id,
+                "publicKeyBase58": verkey,
+            }],
+            "authentication": [verkey_id],
+        });
+
+        match (diddoc_content, endpoint) {
+            (Some(content), _) => Self::merge_content(&mut document, content)?,
+            (None, Some(endpoint)) => {
+                let services = Self::endpoint_services(id, &verkey_id, endpoint);
+                if !services.is_empty() {
+                    document["service"] = JsonValue::Array(services);
+                }
+            }
+            (None, None) => {}
+        }
+
+        Ok(document)
+    }
+
+    /// Appends `diddocContent` set on the Ledger to the base document.
+    /// Arrays are extended, other fields are added unless they are already present.
+    fn merge_content(document: &mut JsonValue, content: &JsonValue) -> CliResult<()> {
+        let content = content.as_object().ok_or_else(|| {
+            CliError::InvalidEntityState("NYM diddocContent must be a JSON object".to_string())
+        })?;
+
+        for (key, value) in content {
+            if key == "id" && value != &document["id"] {
+                return Err(CliError::InvalidEntityState(format!(
+                    "NYM diddocContent has conflicting id \"{}\"",
+                    value
+                )));
+            }
+            match (&mut document[key], value) {
+                (JsonValue::Array(items), JsonValue::Array(values)) => {
+                    for value in values {
+                        if !items.contains(value) {
+                            items.push(value.clone());
+                        }
+                    }
+                }
+                (JsonValue::Array(items), value) => {
+                    if !items.contains(value) {
+                        items.push(value.clone());
+                    }
+                }
+                (JsonValue::Null, value) => {
+                    document[key] = value.clone();
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn endpoint_services(
+        id: &str,
+        verkey_id: &str,
+        endpoint: &JsonMap<String, JsonValue>,
+    ) -> Vec<JsonValue> {
+        let mut services = Vec::new();
+
+        for (endpoint_type, address) in endpoint {
+            let address = match address.as_str() {
+                Some(address) => address,
+                None => continue,
+            };
+            if endpoint_type == ENDPOINT_DIDCOMM {
+                let routing_keys = endpoint
+                    .get(ENDPOINT_ROUTING_KEYS)
+                    .cloned()
+                    .unwrap_or_else(|| json!([]));
+                let mut service = json!({
+                    "id": format!("{}#{}", id, DIDCOMM_FRAGMENT),
+                    "type": DIDCOMM_FRAGMENT,
+                    "serviceEndpoint": address,
+                    "recipientKeys": [verkey_id],
+                    "routingKeys": routing_keys,
+                    "priority": 0,
+                });
+                if let Some(types) = endpoint.get(ENDPOINT_TYPES) {
+                    service["types"] = types.clone();
+                }
+                services.push(service);
+            } else {
+                services.push(json!({
+                    "id": format!("{}#{}", id, endpoint_type),
+                    "type": endpoint_type,
+                    "serviceEndpoint": address,
+                }));
+            }
+        }
+
+        services
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "did:sov:VsKV7grR1BUE29mG2Fm2kX";
+    const VERKEY: &str = "GjZWsBLgZCR18aL468JAT7w9CZRiBnpxUPPgyQxh4voa";
+
+    #[test]
+    fn build_works_for_verkey_only() {
+        let document = DidDocument::build(ID, VERKEY, None, None).unwrap();
+        assert_eq!(ID, document["id"]);
+        assert_eq!(VERKEY, document["verificationMethod"][0]["publicKeyBase58"]);
+        assert_eq!(
+            json!([format!("{}#verkey", ID)]),
+            document["authentication"]
+        );
+        assert!(document["service"].is_null());
+    }
+
+    #[test]
+    fn build_works_for_endpoint() {
+        let endpoint = json!({
+            "endpoint": "https://agent.example.com",
+            "routingKeys": ["routing"],
+            "profile": "https://example.com/profile"
+        });
+        let document = DidDocument::build(ID, VERKEY, endpoint.as_object(), None).unwrap();
+        let services = document["service"].as_array().unwrap();
+        assert_eq!(2, services.len());
+
+        let didcomm = services
+            .iter()
+            .find(|service| service["type"] == "did-communication")
+            .unwrap();
+        assert_eq!("https://agent.example.com", didcomm["serviceEndpoint"]);
+        assert_eq!(json!(["routing"]), didcomm["routingKeys"]);
+        assert_eq!(json!([format!("{}#verkey", ID)]), didcomm["recipientKeys"]);
+    }
+
+    #[test]
+    fn build_works_for_diddoc_content() {
+        let endpoint = json!({"endpoint": "https://agent.example.com"});
+        let content = json!({
+            "@context": ["https://identity.foundation/didcomm-messaging/service-endpoint/v1"],
+            "service": [{"id": format!("{}#didcomm-1", ID), "type": "DIDCommMessaging"}]
+        });
+        let document =
+            DidDocument::build(ID, VERKEY, endpoint.as_object(), Some(&content)).unwrap();
+        assert_eq!(3, document["@context"].as_array().unwrap().len());
+        assert_eq!(content["service"], document["service"]);
+    }
+
+    #[test]
+    fn build_fails_for_conflicting_id() {
+        let content = json!({"id": "did:sov:other"});
+        DidDocument::build(ID, VERKEY, None, Some(&content)).unwrap_err();
+    }
+}
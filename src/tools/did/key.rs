@@ -4,38 +4,339 @@
 */
 use crate::{
     error::{CliError, CliResult},
-    tools::did::seed::Seed,
+    tools::{
+        did::{
+            constants::{CATEGORY_BLS_KEY, KEY_TAG_DID_KEY, KEY_TYPE, KEY_TYPE_SECP256K1},
+            seed::Seed,
+        },
+        ledger::proof::bls_generator,
+    },
+    utils::futures::block_on,
 };
 
 use crate::tools::wallet::Wallet;
-use aries_askar::kms::{KeyAlg, LocalKey, SecretBytes};
+use aries_askar::{
+    kms::{crypto_box_seal, crypto_box_seal_open, KeyAlg, KeyEntry, LocalKey, SecretBytes},
+    EntryTag,
+};
+use indy_blssignatures::{Bls, ProofOfPossession, SignKey, VerKey};
 use indy_utils::base58;
 
 pub struct Key(LocalKey);
 
+/// BLS key of a pool node in the form of NODE transaction `blskey` and `blskey_pop` fields.
+#[derive(Debug, Serialize)]
+pub struct BlsKeyInfo {
+    pub name: String,
+    pub blskey: String,
+    pub blskey_pop: String,
+}
+
+/// Wallet record of BLS key: base58 encoded secret key, public key and its proof of possession.
+#[derive(Serialize, Deserialize)]
+struct BlsKeyRecord {
+    sign_key: String,
+    blskey: String,
+    blskey_pop: String,
+    metadata: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyInfo {
+    pub name: String,
+    pub algorithm: String,
+    pub verkey: String,
+    pub metadata: Option<String>,
+}
+
 impl Key {
     pub async fn create(
         store: &Wallet,
+        alg: KeyAlg,
         seed: Option<&str>,
         metadata: Option<&str>,
     ) -> CliResult<Key> {
-        let keypair = match seed {
-            Some(seed) => {
-                let seed = Seed::from_str(seed)?;
-                LocalKey::from_secret_bytes(KeyAlg::Ed25519, seed.value())?
-            }
-            None => LocalKey::generate(KeyAlg::Ed25519, false)?,
+        let key = match seed {
+            Some(seed) => Key(LocalKey::from_secret_bytes(
+                alg,
+                Seed::from_str(seed)?.value(),
+            )?),
+            None => Key(LocalKey::generate(alg, false)?),
         };
 
-        let key = Key(keypair);
-
         let verkey = key.verkey()?;
 
-        store.insert_key(&verkey, &key.value(), metadata).await?;
+        store
+            .insert_key(&verkey, &key.value(), metadata, Some(&Self::did_key_tags()))
+            .await?;
 
         Ok(key)
     }
 
+    /// Stores X25519 key agreement key either converted from the signing key or generated.
+    pub async fn create_key_agreement(
+        store: &Wallet,
+        signing_key: &Key,
+        derive: bool,
+    ) -> CliResult<String> {
+        let key = if derive {
+            Key(signing_key.0.convert_key(KeyAlg::X25519)?)
+        } else {
+            Key(LocalKey::generate(KeyAlg::X25519, false)?)
+        };
+
+        let public_key = key.verkey()?;
+
+        store
+            .insert_key(&public_key, &key.value(), None, Some(&Self::did_key_tags()))
+            .await?;
+
+        Ok(public_key)
+    }
+
+    /// Tags marking the key as owned by a DID, so wallet maintenance never takes standalone keys for DID keys.
+    pub fn did_key_tags() -> Vec<EntryTag> {
+        vec![EntryTag::Encrypted(
+            KEY_TAG_DID_KEY.to_string(),
+            "true".to_string(),
+        )]
+    }
+
+    pub fn is_did_key(entry: &KeyEntry) -> bool {
+        entry
+            .tags_as_slice()
+            .iter()
+            .any(|tag| tag.name() == KEY_TAG_DID_KEY)
+    }
+
+    pub fn from_seed(seed: &Seed) -> CliResult<Key> {
+        let keypair = LocalKey::from_secret_bytes(KeyAlg::Ed25519, seed.value())?;
+        Ok(Key(keypair))
+    }
+
+    /// Creates and stores a key of any supported algorithm under the given name.
+    /// The base58 encoded public key is used as the name if none is provided.
+    pub fn create_named(
+        store: &Wallet,
+        name: Option<&str>,
+        alg: KeyAlg,
+        seed: Option<&str>,
+        metadata: Option<&str>,
+    ) -> CliResult<KeyInfo> {
+        block_on(async move {
+            let key = match seed {
+                Some(seed) => Key(LocalKey::from_secret_bytes(
+                    alg,
+                    Seed::from_str(seed)?.value(),
+                )?),
+                None => Key(LocalKey::generate(alg, false)?),
+            };
+
+            let verkey = key.verkey()?;
+            let name = name.map(String::from).unwrap_or_else(|| verkey.clone());
+
+            if store.fetch_key(&name).await.is_ok() {
+                return Err(CliError::Duplicate(format!(
+                    "Key \"{}\" already exists in the wallet.",
+                    name
+                )));
+            }
+
+            store
+                .insert_key(&name, &key.value(), metadata, None)
+                .await?;
+
+            Ok(KeyInfo {
+                name,
+                algorithm: alg.as_str().to_string(),
+                verkey,
+                metadata: metadata.map(String::from),
+            })
+        })
+    }
+
+    /// Creates BLS key of a pool node on the BN254 curve used by indy-node and stores it under the given name
+    /// (base58 encoded public key by default). The wallet key storage does not support the curve,
+    /// so the key is stored as a record. Returns the base58 encoded public key together with its proof of possession.
+    pub fn create_bls(
+        store: &Wallet,
+        name: Option<&str>,
+        seed: Option<&str>,
+        metadata: Option<&str>,
+    ) -> CliResult<BlsKeyInfo> {
+        block_on(async move {
+            let seed = match seed {
+                Some(seed) => Seed::from_str(seed)?,
+                None => Seed::generate(),
+            };
+            let invalid = |_| CliError::InvalidInput("Unable to create BLS key".to_string());
+
+            let sign_key = SignKey::new(Some(seed.value())).map_err(invalid)?;
+            let verkey = VerKey::new(&bls_generator()?, &sign_key).map_err(invalid)?;
+            let pop = ProofOfPossession::new(&verkey, &sign_key).map_err(invalid)?;
+
+            let blskey = base58::encode(verkey.as_bytes());
+            let blskey_pop = base58::encode(pop.as_bytes());
+            let name = name.map(String::from).unwrap_or_else(|| blskey.clone());
+
+            if store
+                .fetch_record(CATEGORY_BLS_KEY, &name, false)
+                .await?
+                .is_some()
+            {
+                return Err(CliError::Duplicate(format!(
+                    "BLS key \"{}\" already exists in the wallet.",
+                    name
+                )));
+            }
+
+            let record = BlsKeyRecord {
+                sign_key: base58::encode(sign_key.as_bytes()),
+                blskey: blskey.clone(),
+                blskey_pop: blskey_pop.clone(),
+                metadata: metadata.map(String::from),
+            };
+            store
+                .store_record(
+                    CATEGORY_BLS_KEY,
+                    &name,
+                    &serde_json::to_vec(&record)?,
+                    None,
+                    true,
+                )
+                .await?;
+
+            Ok(BlsKeyInfo {
+                name,
+                blskey,
+                blskey_pop,
+            })
+        })
+    }
+
+    /// Checks the proof of possession of base58 encoded BLS public key.
+    pub fn verify_bls_proof_of_possession(blskey: &str, blskey_pop: &str) -> CliResult<bool> {
+        let invalid = |field: &str| CliError::InvalidInput(format!("Invalid BLS {}", field));
+
+        let verkey = base58::decode(blskey)
+            .ok()
+            .and_then(|bytes| VerKey::from_bytes(&bytes).ok())
+            .ok_or_else(|| invalid("key"))?;
+        let pop = base58::decode(blskey_pop)
+            .ok()
+            .and_then(|bytes| ProofOfPossession::from_bytes(&bytes).ok())
+            .ok_or_else(|| invalid("proof of possession"))?;
+
+        Bls::verify_proof_of_posession(&pop, &verkey, &bls_generator()?)
+            .map_err(|_| invalid("proof of possession"))
+    }
+
+    pub fn list(store: &Wallet) -> CliResult<Vec<KeyInfo>> {
+        block_on(async move {
+            store
+                .fetch_all_keys()
+                .await?
+                .iter()
+                .map(KeyInfo::from_entry)
+                .collect::<CliResult<Vec<KeyInfo>>>()
+        })
+    }
+
+    pub fn delete(store: &Wallet, name: &str) -> CliResult<()> {
+        block_on(async move {
+            store.fetch_key(name).await?;
+            store.remove_key(name).await
+        })
+    }
+
+    pub fn sign_message(store: &Wallet, name: &str, message: &[u8]) -> CliResult<Vec<u8>> {
+        block_on(async move { Self::sign(store, name, message).await })
+    }
+
+    /// Verifies the signature either by a key stored in the wallet or by an Ed25519 verkey.
+    pub fn verify_message(
+        store: Option<&Wallet>,
+        name: Option<&str>,
+        verkey: Option<&str>,
+        message: &[u8],
+        signature: &[u8],
+    ) -> CliResult<bool> {
+        let key = match (store, name, verkey) {
+            (Some(store), Some(name), _) => block_on(async move { store.fetch_key(name).await })?,
+            (_, None, Some(verkey)) => {
+                let verkey = base58::decode(verkey).map_err(|_| {
+                    CliError::InvalidInput(format!("Invalid verkey \"{}\" provided.", verkey))
+                })?;
+                LocalKey::from_public_bytes(KeyAlg::Ed25519, &verkey)?
+            }
+            _ => {
+                return Err(CliError::InvalidInput(
+                    "Either stored key name or verkey must be provided.".to_string(),
+                ))
+            }
+        };
+        key.verify_signature(message, signature, None)
+            .map_err(CliError::from)
+    }
+
+    /// Encrypts the message into a sealed box addressed to the stored key, so only the wallet owner can open it.
+    /// Ed25519 keys are converted into X25519 keys for the encryption.
+    pub fn seal_message(store: &Wallet, name: &str, message: &[u8]) -> CliResult<Vec<u8>> {
+        block_on(async move {
+            let key = Self::encryption_key(store, name).await?;
+            Ok(crypto_box_seal(&key, message)?.to_vec())
+        })
+    }
+
+    /// Opens the sealed box created by `seal_message` with the same stored key.
+    pub fn open_sealed_message(
+        store: &Wallet,
+        name: &str,
+        ciphertext: &[u8],
+    ) -> CliResult<Vec<u8>> {
+        block_on(async move {
+            let key = Self::encryption_key(store, name).await?;
+            Ok(crypto_box_seal_open(&key, ciphertext)?.to_vec())
+        })
+    }
+
+    async fn encryption_key(store: &Wallet, name: &str) -> CliResult<LocalKey> {
+        let key = store.fetch_key(name).await?;
+        match key.algorithm() {
+            KeyAlg::X25519 => Ok(key),
+            KeyAlg::Ed25519 => key.convert_key(KeyAlg::X25519).map_err(CliError::from),
+            alg => Err(CliError::InvalidInput(format!(
+                "Key \"{}\" has \"{}\" algorithm. Only ed25519 and x25519 keys can be used for encryption.",
+                name,
+                alg.as_str()
+            ))),
+        }
+    }
+
+    pub fn parse_alg(alg: &str) -> CliResult<KeyAlg> {
+        match alg.to_lowercase().as_str() {
+            "ed25519" => Ok(KeyAlg::Ed25519),
+            "x25519" => Ok(KeyAlg::X25519),
+            "secp256k1" | "k256" => Ok(KeyAlg::K256),
+            _ => Err(CliError::InvalidInput(format!(
+                "Unsupported key algorithm \"{}\". One of: ed25519, x25519, secp256k1.",
+                alg
+            ))),
+        }
+    }
+
+    /// Returns the verkey type stored in DID records for the signing key algorithm.
+    pub fn did_key_type(alg: KeyAlg) -> CliResult<&'static str> {
+        match alg {
+            KeyAlg::Ed25519 => Ok(KEY_TYPE),
+            KeyAlg::K256 => Ok(KEY_TYPE_SECP256K1),
+            _ => Err(CliError::InvalidInput(format!(
+                "Key algorithm \"{}\" cannot be used for DIDs. One of: ed25519, secp256k1.",
+                alg.as_str()
+            ))),
+        }
+    }
+
     fn value(&self) -> &LocalKey {
         &self.0
     }
@@ -56,4 +357,35 @@ impl Key {
             .sign_message(bytes, None)
             .map_err(CliError::from)
     }
+
+    /// Signs with the stored key making sure it has the expected algorithm.
+    pub async fn sign_with_alg(
+        store: &Wallet,
+        id: &str,
+        alg: KeyAlg,
+        bytes: &[u8],
+    ) -> CliResult<Vec<u8>> {
+        let key = store.fetch_key(id).await?;
+        if key.algorithm() != alg {
+            return Err(CliError::InvalidEntityState(format!(
+                "Key \"{}\" has \"{}\" algorithm but \"{}\" is expected.",
+                id,
+                key.algorithm().as_str(),
+                alg.as_str()
+            )));
+        }
+        key.sign_message(bytes, None).map_err(CliError::from)
+    }
+}
+
+impl KeyInfo {
+    fn from_entry(entry: &KeyEntry) -> CliResult<KeyInfo> {
+        let key = entry.load_local_key()?;
+        Ok(KeyInfo {
+            name: entry.name().to_string(),
+            algorithm: key.algorithm().as_str().to_string(),
+            verkey: base58::encode(key.to_public_bytes()?),
+            metadata: entry.metadata().map(String::from),
+        })
+    }
 }
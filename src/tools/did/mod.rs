@@ -3,6 +3,7 @@
     https://digital.gov.bc.ca/digital-trust
 */
 pub mod constants;
+pub mod document;
 pub mod key;
 pub mod seed;
 
@@ -12,14 +13,23 @@ use crate::{
 };
 
 use crate::tools::wallet::Wallet;
-use aries_askar::{Entry, EntryTag};
+use aries_askar::{kms::KeyAlg, Entry, EntryTag, TagFilter};
 use indy_utils::{base58, did::DidValue, keys::EncodedVerKey, Qualifiable};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::HashSet;
 
 use self::{
-    constants::{CATEGORY_DID, KEY_TYPE},
+    constants::{
+        CATEGORY_DID, CATEGORY_DID_ALIAS, KEY_TYPE, KEY_TYPE_SECP256K1, METADATA_TAG_PREFIX,
+    },
     key::Key,
+    seed::Seed,
 };
 
+const ED25519_VERKEY_BYTES: usize = 32;
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const MULTIBASE_BASE58_PREFIX: char = 'z';
+
 pub struct Did {}
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -30,6 +40,37 @@ pub struct DidInfo {
     pub method: Option<String>,
     pub metadata: Option<String>,
     pub next_verkey: Option<String>,
+    pub key_agreement: Option<String>,
+}
+
+/// Outcome of copying DIDs from another wallet.
+#[derive(Debug, Default)]
+pub struct DidCopyReport {
+    pub copied: Vec<String>,
+    /// DIDs which were not copied with the reason.
+    pub conflicts: Vec<(String, String)>,
+}
+
+/// How X25519 key agreement key is obtained for a new DID.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyAgreement {
+    /// Convert the Ed25519 signing key into X25519 key.
+    Derive,
+    /// Generate an independent X25519 key.
+    Generate,
+}
+
+impl KeyAgreement {
+    pub fn from_str(value: &str) -> CliResult<KeyAgreement> {
+        match value {
+            "derive" => Ok(KeyAgreement::Derive),
+            "generate" => Ok(KeyAgreement::Generate),
+            _ => Err(CliError::InvalidInput(format!(
+                "Invalid key agreement mode \"{}\". One of: derive, generate.",
+                value
+            ))),
+        }
+    }
 }
 
 impl Did {
@@ -39,15 +80,25 @@ impl Did {
         seed: Option<&str>,
         metadata: Option<&str>,
         method: Option<&str>,
+        key_agreement: Option<KeyAgreement>,
+        key_alg: Option<KeyAlg>,
     ) -> CliResult<(String, String)> {
         block_on(async move {
-            let key = Key::create(store, seed, metadata).await?;
+            let key_alg = key_alg.unwrap_or(KeyAlg::Ed25519);
+            let verkey_type = Key::did_key_type(key_alg)?;
+            if key_agreement == Some(KeyAgreement::Derive) && key_alg != KeyAlg::Ed25519 {
+                return Err(CliError::InvalidInput(format!(
+                    "Key agreement key can be derived only from ed25519 key. Use `generate` mode for {} DIDs.",
+                    verkey_type
+                )));
+            }
+
+            let key = Key::create(store, key_alg, seed, metadata).await?;
 
             let verkey = key.verkey()?;
-            let verkey_bytes = key.verkey_bytes()?;
             let mut did = match did {
                 Some(did) => did.to_string(),
-                None => base58::encode(&verkey_bytes[0..16]),
+                None => Self::did_from_key(&key)?,
             };
 
             let existing_did = Self::get_opt_record(store, &did, false).await?;
@@ -60,13 +111,21 @@ impl Did {
                 did = DidValue(did.to_string()).to_qualified(method)?.to_string();
             }
 
+            let key_agreement = match key_agreement {
+                Some(mode) => Some(
+                    Key::create_key_agreement(store, &key, mode == KeyAgreement::Derive).await?,
+                ),
+                None => None,
+            };
+
             let did_info = DidInfo {
                 did: did.clone(),
                 verkey: verkey.clone(),
-                verkey_type: KEY_TYPE.to_string(),
+                verkey_type: verkey_type.to_string(),
                 method: method.map(String::from),
                 metadata: metadata.map(String::from),
                 next_verkey: None,
+                key_agreement,
             };
 
             store
@@ -83,11 +142,22 @@ impl Did {
         })
     }
 
+    /// Calculates DID and verkey for the seed without storing anything into the wallet.
+    pub fn derive(seed: &Seed) -> CliResult<(String, String)> {
+        let key = Key::from_seed(seed)?;
+        Ok((Self::did_from_key(&key)?, key.verkey()?))
+    }
+
+    fn did_from_key(key: &Key) -> CliResult<String> {
+        let verkey_bytes = key.verkey_bytes()?;
+        Ok(base58::encode(&verkey_bytes[0..16]))
+    }
+
     pub fn replace_keys_start(store: &Wallet, did: &str, seed: Option<&str>) -> CliResult<String> {
         block_on(async move {
             let (did_entry, mut did_info) = Self::get_record(store, &did, true).await?;
 
-            let key = Key::create(store, seed, None).await?;
+            let key = Key::create(store, did_info.key_alg()?, seed, None).await?;
             let verkey = key.verkey()?;
 
             did_info.next_verkey = Some(verkey.clone());
@@ -115,7 +185,8 @@ impl Did {
                 CliError::InvalidEntityState(format!("Next key is not set for the DID {}.", did))
             })?;
 
-            did_info.verkey = next_verkey;
+            // the previous key is kept to verify old signatures, but it is not a DID key anymore
+            let previous_verkey = std::mem::replace(&mut did_info.verkey, next_verkey);
             did_info.next_verkey = None;
 
             let value = serde_json::to_vec(&did_info)?;
@@ -129,31 +200,78 @@ impl Did {
                 )
                 .await?;
 
+            if store.fetch_key_entry(&previous_verkey).await?.is_some() {
+                store.update_key_tags(&previous_verkey, &[]).await?;
+            }
+
             Ok(())
         })
     }
 
     pub fn set_metadata(store: &Wallet, did: &str, metadata: &str) -> CliResult<()> {
         block_on(async move {
-            let (did_entry, mut did_info) = Self::get_record(store, &did, true).await?;
+            let (_, mut did_info) = Self::get_record(store, &did, true).await?;
 
             did_info.metadata = Some(metadata.to_string());
 
-            let value = serde_json::to_vec(&did_info)?;
-            store
-                .store_record(
-                    CATEGORY_DID,
-                    &did_info.did,
-                    &value,
-                    Some(&did_entry.tags),
-                    false,
-                )
-                .await?;
+            Self::store_metadata(store, &did_info).await
+        })
+    }
 
-            Ok(())
+    pub fn merge_metadata(
+        store: &Wallet,
+        did: &str,
+        fields: JsonMap<String, JsonValue>,
+    ) -> CliResult<String> {
+        block_on(async move {
+            let (_, mut did_info) = Self::get_record(store, &did, true).await?;
+
+            // existing free-form metadata cannot be merged so it is replaced
+            let mut metadata = did_info.metadata_fields().unwrap_or_default();
+
+            for (key, value) in fields {
+                match value {
+                    JsonValue::Null => metadata.remove(&key),
+                    JsonValue::String(ref value_) if value_.is_empty() => metadata.remove(&key),
+                    value => metadata.insert(key, value),
+                };
+            }
+
+            let metadata = JsonValue::Object(metadata).to_string();
+            did_info.metadata = Some(metadata.clone());
+
+            Self::store_metadata(store, &did_info).await?;
+
+            Ok(metadata)
+        })
+    }
+
+    pub fn find_by_metadata(store: &Wallet, key: &str, value: &str) -> CliResult<Vec<DidInfo>> {
+        block_on(async move {
+            let tag_filter = TagFilter::is_eq(format!("{}{}", METADATA_TAG_PREFIX, key), value);
+
+            store
+                .search_records(CATEGORY_DID, Some(tag_filter), None)
+                .await?
+                .iter()
+                .map(|did| serde_json::from_slice(&did.value).map_err(CliError::from))
+                .collect::<CliResult<Vec<DidInfo>>>()
         })
     }
 
+    async fn store_metadata(store: &Wallet, did_info: &DidInfo) -> CliResult<()> {
+        let value = serde_json::to_vec(&did_info)?;
+        store
+            .store_record(
+                CATEGORY_DID,
+                &did_info.did,
+                &value,
+                Some(&did_info.tags()),
+                false,
+            )
+            .await
+    }
+
     pub fn get(store: &Wallet, did: &DidValue) -> CliResult<DidInfo> {
         block_on(async move {
             let (_, did_info) = Self::get_record(store, &did, true).await?;
@@ -172,6 +290,209 @@ impl Did {
         })
     }
 
+    /// Copies DID records together with their keys from another wallet.
+    /// DIDs already existing in the target wallet are skipped and reported as conflicts.
+    pub fn copy(from: &Wallet, to: &Wallet, dids: Option<&[&str]>) -> CliResult<DidCopyReport> {
+        block_on(async move {
+            let mut report = DidCopyReport::default();
+            let mut found = HashSet::new();
+
+            for entry in from.fetch_all_records(CATEGORY_DID).await? {
+                if let Some(dids) = dids {
+                    if !dids.contains(&entry.name.as_str()) {
+                        continue;
+                    }
+                }
+                found.insert(entry.name.clone());
+
+                let did_info = match DidInfo::from_bytes(&entry.value) {
+                    Ok(did_info) => did_info,
+                    Err(_) => {
+                        report
+                            .conflicts
+                            .push((entry.name, "DID record cannot be parsed".to_string()));
+                        continue;
+                    }
+                };
+
+                if Self::get_opt_record(to, &entry.name, false)
+                    .await?
+                    .is_some()
+                {
+                    report
+                        .conflicts
+                        .push((entry.name, "DID already exists in the wallet".to_string()));
+                    continue;
+                }
+
+                let key_names = [
+                    Some(&did_info.verkey),
+                    did_info.next_verkey.as_ref(),
+                    did_info.key_agreement.as_ref(),
+                ];
+
+                let mut keys = Vec::new();
+                let mut missing_key = None;
+                for name in key_names.into_iter().flatten() {
+                    match from.fetch_key_entry(name).await? {
+                        Some(key) => keys.push(key),
+                        None => {
+                            missing_key = Some(name.to_string());
+                            break;
+                        }
+                    }
+                }
+                if let Some(name) = missing_key {
+                    report.conflicts.push((
+                        entry.name,
+                        format!("Key \"{}\" does not exist in the source wallet", name),
+                    ));
+                    continue;
+                }
+
+                for key in keys {
+                    // keys are named by their public keys, so the same name means the same key
+                    if to.fetch_key_entry(key.name()).await?.is_none() {
+                        to.insert_key(
+                            key.name(),
+                            &key.load_local_key()?,
+                            key.metadata(),
+                            Some(key.tags_as_slice()),
+                        )
+                        .await?;
+                    }
+                }
+
+                to.store_record(
+                    CATEGORY_DID,
+                    &entry.name,
+                    &entry.value,
+                    Some(&entry.tags),
+                    true,
+                )
+                .await?;
+                report.copied.push(entry.name);
+            }
+
+            if let Some(dids) = dids {
+                for did in dids.iter().filter(|did| !found.contains(**did)) {
+                    report.conflicts.push((
+                        did.to_string(),
+                        "DID does not exist in the source wallet".to_string(),
+                    ));
+                }
+            }
+
+            Ok(report)
+        })
+    }
+
+    /// Removes the DID record together with its keys.
+    /// Keys still referenced by other DIDs of the wallet are kept.
+    pub fn delete(store: &Wallet, did: &DidValue) -> CliResult<DidInfo> {
+        block_on(async move {
+            let (_, did_info) = Self::get_record(store, &did, true).await?;
+
+            Self::remove(store, &did_info.did).await?;
+
+            let remaining = store.fetch_all_records(CATEGORY_DID).await?;
+            let is_referenced = |name: &str| {
+                remaining.iter().any(|entry| {
+                    DidInfo::from_bytes(&entry.value)
+                        .map(|other| {
+                            other.verkey == name
+                                || other.next_verkey.as_deref() == Some(name)
+                                || other.key_agreement.as_deref() == Some(name)
+                        })
+                        .unwrap_or(false)
+                })
+            };
+
+            let key_names = [
+                Some(&did_info.verkey),
+                did_info.next_verkey.as_ref(),
+                did_info.key_agreement.as_ref(),
+            ];
+            for name in key_names.into_iter().flatten() {
+                if !is_referenced(name) && store.fetch_key_entry(name).await?.is_some() {
+                    store.remove_key(name).await?;
+                }
+            }
+
+            Ok(did_info)
+        })
+    }
+
+    /// Assigns the local alias to the DID replacing the DID previously assigned to the alias.
+    /// The DID does not have to be stored in the wallet, so identities of others can be aliased too.
+    pub fn set_alias(store: &Wallet, alias: &str, did: &DidValue) -> CliResult<()> {
+        block_on(async move {
+            let exists = store
+                .fetch_record(CATEGORY_DID_ALIAS, alias, true)
+                .await?
+                .is_some();
+            store
+                .store_record(
+                    CATEGORY_DID_ALIAS,
+                    alias,
+                    did.to_string().as_bytes(),
+                    None,
+                    !exists,
+                )
+                .await
+        })
+    }
+
+    pub fn get_alias(store: &Wallet, alias: &str) -> CliResult<Option<String>> {
+        block_on(async move {
+            store
+                .fetch_record(CATEGORY_DID_ALIAS, alias, false)
+                .await?
+                .map(|entry| {
+                    String::from_utf8(entry.value.to_vec()).map_err(|_| {
+                        CliError::InvalidEntityState(format!(
+                            "DID alias \"{}\" record is corrupted.",
+                            alias
+                        ))
+                    })
+                })
+                .transpose()
+        })
+    }
+
+    pub fn delete_alias(store: &Wallet, alias: &str) -> CliResult<()> {
+        block_on(async move {
+            if store
+                .fetch_record(CATEGORY_DID_ALIAS, alias, true)
+                .await?
+                .is_none()
+            {
+                return Err(CliError::NotFound(format!(
+                    "DID alias \"{}\" does not exist in the wallet.",
+                    alias
+                )));
+            }
+            store.remove_record(CATEGORY_DID_ALIAS, alias).await
+        })
+    }
+
+    /// Returns pairs of alias and DID sorted by alias.
+    pub fn list_aliases(store: &Wallet) -> CliResult<Vec<(String, String)>> {
+        block_on(async move {
+            let mut aliases = store
+                .fetch_all_records(CATEGORY_DID_ALIAS)
+                .await?
+                .into_iter()
+                .map(|entry| {
+                    let did = String::from_utf8_lossy(&entry.value).to_string();
+                    (entry.name, did)
+                })
+                .collect::<Vec<(String, String)>>();
+            aliases.sort();
+            Ok(aliases)
+        })
+    }
+
     pub fn abbreviate_verkey(did: &str, verkey: &str) -> CliResult<String> {
         let did = DidValue(did.to_string()).to_short().to_string();
         EncodedVerKey::from_did_and_verkey(&did, verkey)?
@@ -179,6 +500,52 @@ impl Did {
             .map_err(CliError::from)
     }
 
+    /// Restores the full verkey from the abbreviated form (`~` followed by the key part after DID bytes).
+    pub fn expand_verkey(did: &str, verkey: &str) -> CliResult<String> {
+        let abbreviated = match verkey.strip_prefix('~') {
+            Some(abbreviated) => abbreviated,
+            None => return Ok(verkey.to_string()),
+        };
+        let did = DidValue(did.to_string()).to_short().to_string();
+        let invalid = |_| CliError::InvalidInput(format!("Invalid verkey {} provided.", verkey));
+        let mut verkey_bytes = base58::decode(&did).map_err(invalid)?;
+        verkey_bytes.extend(base58::decode(abbreviated).map_err(invalid)?);
+        Ok(base58::encode(verkey_bytes))
+    }
+
+    /// Returns the DID which would be created for the verkey: first 16 bytes of the key encoded in base58.
+    pub fn did_for_verkey(verkey: &str) -> CliResult<String> {
+        let verkey_bytes = base58::decode(verkey)
+            .map_err(|_| CliError::InvalidInput(format!("Invalid verkey {} provided.", verkey)))?;
+        if verkey_bytes.len() < 16 {
+            return Err(CliError::InvalidInput(format!(
+                "Invalid verkey {} provided.",
+                verkey
+            )));
+        }
+        Ok(base58::encode(&verkey_bytes[0..16]))
+    }
+
+    /// Encodes Ed25519 verkey as multibase (base58btc) value prefixed with `ed25519-pub` multicodec
+    /// as used by `did:key` and `publicKeyMultibase` of DID Documents.
+    pub fn multibase_verkey(verkey: &str) -> CliResult<String> {
+        let verkey_bytes = base58::decode(verkey)
+            .map_err(|_| CliError::InvalidInput(format!("Invalid verkey {} provided.", verkey)))?;
+        if verkey_bytes.len() != ED25519_VERKEY_BYTES {
+            return Err(CliError::InvalidInput(format!(
+                "Only {} bytes Ed25519 verkey can be encoded as multibase.",
+                ED25519_VERKEY_BYTES
+            )));
+        }
+        let mut bytes = ED25519_MULTICODEC.to_vec();
+        bytes.extend(verkey_bytes);
+        Ok(format!(
+            "{}{}",
+            MULTIBASE_BASE58_PREFIX,
+            base58::encode(bytes)
+        ))
+    }
+
     pub fn qualify(store: &Wallet, did: &DidValue, method: &str) -> CliResult<DidValue> {
         block_on(async {
             let (entry, did_info) = Self::get_opt_record(store, &did.to_string(), true)
@@ -210,7 +577,7 @@ impl Did {
     pub async fn sign(store: &Wallet, did: &str, bytes: &[u8]) -> CliResult<Vec<u8>> {
         let (_, did_info) = Self::get_record(store, &did, true).await?;
 
-        Key::sign(store, &did_info.verkey, bytes).await
+        Key::sign_with_alg(store, &did_info.verkey, did_info.key_alg()?, bytes).await
     }
 
     async fn remove(store: &Wallet, name: &str) -> CliResult<()> {
@@ -258,7 +625,7 @@ impl DidInfo {
     pub fn tags(&self) -> Vec<EntryTag> {
         let mut tags = vec![
             EntryTag::Encrypted("verkey".to_string(), self.verkey.to_string()),
-            EntryTag::Encrypted("verkey_type".to_string(), KEY_TYPE.to_string()),
+            EntryTag::Encrypted("verkey_type".to_string(), self.verkey_type.to_string()),
         ];
         if let Some(ref method) = self.method {
             tags.push(EntryTag::Encrypted(
@@ -266,6 +633,36 @@ impl DidInfo {
                 method.to_string(),
             ))
         }
+        if let Some(metadata) = self.metadata_fields() {
+            for (key, value) in metadata {
+                let value = match value {
+                    JsonValue::String(value) => value,
+                    value => value.to_string(),
+                };
+                tags.push(EntryTag::Encrypted(
+                    format!("{}{}", METADATA_TAG_PREFIX, key),
+                    value,
+                ))
+            }
+        }
         tags
     }
+
+    /// Algorithm of the DID signing key according to the stored verkey type.
+    pub fn key_alg(&self) -> CliResult<KeyAlg> {
+        match self.verkey_type.as_str() {
+            KEY_TYPE => Ok(KeyAlg::Ed25519),
+            KEY_TYPE_SECP256K1 => Ok(KeyAlg::K256),
+            verkey_type => Err(CliError::InvalidEntityState(format!(
+                "Unsupported verkey type \"{}\" of DID {}.",
+                verkey_type, self.did
+            ))),
+        }
+    }
+
+    pub fn metadata_fields(&self) -> Option<JsonMap<String, JsonValue>> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| serde_json::from_str::<JsonMap<String, JsonValue>>(metadata).ok())
+    }
 }
@@ -4,6 +4,7 @@
 */
 use crate::error::{CliError, CliResult};
 
+use dryoc::rng::copy_randombytes;
 use hex::FromHex;
 use indy_utils::base64;
 
@@ -12,10 +13,24 @@ const SEED_BYTES: usize = 32;
 pub struct Seed(Vec<u8>);
 
 impl Seed {
+    pub fn generate() -> Seed {
+        let mut seed = vec![0u8; SEED_BYTES];
+        copy_randombytes(&mut seed);
+        Seed(seed)
+    }
+
     pub fn value(&self) -> &[u8] {
         self.0.as_slice()
     }
 
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+
+    pub fn to_base64(&self) -> String {
+        base64::encode(&self.0)
+    }
+
     pub fn from_str(seed: &str) -> CliResult<Seed> {
         if seed.as_bytes().len() == SEED_BYTES {
             // is acceptable seed length
@@ -0,0 +1,202 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::futures::{block_on_cancellable, with_timeout},
+};
+
+use futures_util::future::join_all;
+use indy_vdr::{
+    common::error::{VdrError, VdrErrorKind},
+    pool::{
+        helpers::perform_ledger_request, LocalPool, PreparedRequest, RequestMethod, RequestResult,
+    },
+};
+use std::{thread, time::Duration};
+
+/// Transport used to send ledger requests of the connected pool.
+/// `timeout` limits the time to wait for the reply of every request if it is set.
+pub trait LedgerBackend {
+    fn submit_request(
+        &self,
+        request: &PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> CliResult<String>;
+
+    /// Submits the requests at once returning their replies in the order of the requests.
+    /// Fails only if the whole operation has been interrupted.
+    fn submit_requests(
+        &self,
+        requests: &[&PreparedRequest],
+        timeout: Option<Duration>,
+    ) -> CliResult<Vec<CliResult<String>>> {
+        Ok(requests
+            .iter()
+            .map(|request| self.submit_request(request, timeout))
+            .collect())
+    }
+}
+
+/// Direct connection to the pool nodes.
+impl LedgerBackend for LocalPool {
+    fn submit_request(
+        &self,
+        request: &PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> CliResult<String> {
+        block_on_cancellable(perform_request(self, request, timeout))
+    }
+
+    /// All requests are sent over the same pool connection and awaited together.
+    fn submit_requests(
+        &self,
+        requests: &[&PreparedRequest],
+        timeout: Option<Duration>,
+    ) -> CliResult<Vec<CliResult<String>>> {
+        block_on_cancellable(async move {
+            Ok(join_all(
+                requests
+                    .iter()
+                    .map(|request| perform_request(self, request, timeout)),
+            )
+            .await)
+        })
+    }
+}
+
+async fn perform_request(
+    pool: &LocalPool,
+    request: &PreparedRequest,
+    timeout: Option<Duration>,
+) -> CliResult<String> {
+    let (request_result, _) = match timeout {
+        Some(timeout) => with_timeout(perform_ledger_request(pool, request), timeout)
+            .await
+            .ok_or_else(|| VdrError::from(VdrErrorKind::PoolTimeout))??,
+        None => perform_ledger_request(pool, request).await?,
+    };
+    match request_result {
+        RequestResult::Reply(message) => Ok(message),
+        RequestResult::Failed(error) => Err(error.into()),
+    }
+}
+
+/// indy-vdr-proxy HTTP endpoint submitting requests on behalf of the CLI.
+/// Only read requests are sent through the proxy: write transactions are rejected.
+#[derive(Clone)]
+pub struct ProxyBackend {
+    pub url: String,
+    pub timeout: Option<Duration>,
+}
+
+impl ProxyBackend {
+    pub fn new(url: &str) -> CliResult<ProxyBackend> {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(CliError::InvalidInput(format!(
+                "Invalid ledger proxy URL \"{}\". It must start with http:// or https://",
+                url
+            )));
+        }
+        Ok(ProxyBackend {
+            url: url.trim_end_matches('/').to_string(),
+            timeout: None,
+        })
+    }
+
+    pub fn with_timeout(&self, timeout: u64) -> ProxyBackend {
+        ProxyBackend {
+            url: self.url.clone(),
+            timeout: Some(Duration::from_secs(timeout)),
+        }
+    }
+}
+
+impl LedgerBackend for ProxyBackend {
+    /// `timeout` overrides the timeout the proxy has been connected with.
+    fn submit_request(
+        &self,
+        request: &PreparedRequest,
+        timeout: Option<Duration>,
+    ) -> CliResult<String> {
+        if let RequestMethod::Consensus = request.method {
+            return Err(CliError::InvalidEntityState(format!(
+                "Ledger proxy \"{}\" can be used only for read requests. Connect to the pool nodes to send transactions.",
+                self.url
+            )));
+        }
+
+        let mut proxy_request =
+            ureq::post(&format!("{}/submit", self.url)).set("Content-Type", "application/json");
+        if let Some(timeout) = timeout.or(self.timeout) {
+            proxy_request = proxy_request.timeout(timeout);
+        }
+        let response = proxy_request.send_string(&request.req_json.to_string());
+
+        match response {
+            Ok(response) => response.into_string().map_err(CliError::from),
+            Err(ureq::Error::Status(code, response)) => Err(CliError::ProxyError(format!(
+                "{} {}",
+                code,
+                response.into_string().unwrap_or_default()
+            ))),
+            Err(err) => Err(CliError::ProxyError(err.to_string())),
+        }
+    }
+
+    /// The proxy client is blocking, so every request is sent from its own thread.
+    fn submit_requests(
+        &self,
+        requests: &[&PreparedRequest],
+        timeout: Option<Duration>,
+    ) -> CliResult<Vec<CliResult<String>>> {
+        Ok(thread::scope(|scope| {
+            let handles = requests
+                .iter()
+                .map(|request| scope.spawn(move || self.submit_request(request, timeout)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle.join().unwrap_or_else(|_| {
+                        Err(CliError::ProxyError(
+                            "Request submission thread has panicked".to_string(),
+                        ))
+                    })
+                })
+                .collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ledger::Ledger;
+    use indy_utils::did::DidValue;
+
+    const DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+
+    #[test]
+    fn proxy_rejects_write_request() {
+        let proxy = ProxyBackend::new("http://127.0.0.1:1").unwrap();
+        let did = DidValue(DID.to_string());
+        let request = Ledger::build_nym_request(None, &did, &did, None, None, None).unwrap();
+        match proxy.submit_request(&request, None) {
+            Err(CliError::InvalidEntityState(_)) => {}
+            _ => panic!("write request must be rejected"),
+        }
+    }
+
+    #[test]
+    fn proxy_sends_read_request() {
+        let proxy = ProxyBackend::new("http://127.0.0.1:1").unwrap();
+        let did = DidValue(DID.to_string());
+        let request = Ledger::build_get_nym_request(None, None, &did).unwrap();
+        match proxy.submit_request(&request, None) {
+            Err(CliError::ProxyError(_)) => {}
+            _ => panic!("read request must be sent to the proxy"),
+        }
+    }
+}
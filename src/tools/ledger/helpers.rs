@@ -4,11 +4,16 @@
 */
 use indy_vdr::ledger::constants::*;
 use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
 
 pub struct LedgerHelpers;
 
 impl LedgerHelpers {
-    pub fn get_role_title(role: &JsonValue) -> JsonValue {
+    /// Returns the title of the role code. Custom titles take precedence over the default ones.
+    pub fn get_role_title(role: &JsonValue, custom_titles: &BTreeMap<String, String>) -> JsonValue {
+        if let Some(title) = role.as_str().and_then(|role| custom_titles.get(role)) {
+            return JsonValue::String(title.to_string());
+        }
         JsonValue::String(
             match role.as_str() {
                 Some(TRUSTEE) => "TRUSTEE",
@@ -21,7 +26,17 @@ impl LedgerHelpers {
         )
     }
 
-    pub fn get_txn_title(txn_type: &JsonValue) -> JsonValue {
+    /// Returns the title of the transaction type code. Custom titles take precedence over the default ones.
+    pub fn get_txn_title(
+        txn_type: &JsonValue,
+        custom_titles: &BTreeMap<String, String>,
+    ) -> JsonValue {
+        if let Some(title) = txn_type
+            .as_str()
+            .and_then(|txn_type| custom_titles.get(txn_type))
+        {
+            return JsonValue::String(title.to_string());
+        }
         JsonValue::String(
             match txn_type.as_str() {
                 Some(NODE) => "NODE",
@@ -2,42 +2,80 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
+pub mod backend;
 pub mod helpers;
+pub mod multisig;
+pub mod plugin;
+pub mod proof;
+pub mod reject_reason;
 pub mod response;
 
 use crate::{
     error::{CliError, CliResult},
     tools::did::Did,
-    utils::futures::block_on,
+    utils::futures::{block_on, block_on_cancellable, block_on_cancellable_with_ticks},
 };
 
 use crate::tools::{pool::Pool, wallet::Wallet};
-use indy_utils::did::DidValue;
+use futures_util::StreamExt;
+use indy_utils::{did::DidValue, hash::SHA256};
 use indy_vdr::{
+    common::error::{VdrError, VdrErrorKind},
     ledger::{
-        identifiers::{CredentialDefinitionId, SchemaId},
+        identifiers::{CredentialDefinitionId, RichSchemaId, SchemaId},
         requests::{
             auth_rule::{AddAuthRuleData, AuthRuleData, AuthRules, Constraint, EditAuthRuleData},
             author_agreement::{AcceptanceMechanisms, GetTxnAuthorAgreementData},
             cred_def::CredentialDefinition,
             node::NodeOperationData,
             pool::Schedule,
+            rich_schema::RichSchema,
             schema::Schema,
         },
         RequestBuilder,
     },
     pool::{
-        helpers::{perform_ledger_action, perform_ledger_request},
-        NodeReplies, Pool as PoolImpl, PreparedRequest, ProtocolVersion, RequestResult,
+        helpers::perform_ledger_action, NodeReplies, Pool as PoolImpl, PoolRequest,
+        PreparedRequest, ProtocolVersion, RequestEvent, RequestResult, TimingResult,
     },
 };
 use serde_json::Value as JsonValue;
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 pub use self::{
+    backend::{LedgerBackend, ProxyBackend},
     helpers::LedgerHelpers,
+    plugin::{PluginCommandDefinition, PluginDefinition},
     response::{parse_transaction_response, Response, ResponseType},
 };
 
+/// Identifier used by read requests sent without an active DID.
+const DEFAULT_REQUEST_IDENTIFIER: &str = "LibindyDid111111111111";
+
+/// Reply recorded for the nodes which did not reply to the action in time.
+pub const ACTION_TIMEOUT_REPLY: &str = "timeout";
+
+/// How often the nodes still processing an action are reported.
+const ACTION_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Events reported by `Ledger::submit_action` while node replies are being collected.
+pub enum ActionProgress<'a> {
+    /// Node replied to the request or did not reply in time.
+    Replied {
+        node: &'a str,
+        reply: &'a str,
+        elapsed: Duration,
+    },
+    /// Some nodes have not replied yet.
+    Waiting {
+        pending: &'a [String],
+        elapsed: Duration,
+    },
+}
+
 pub struct Ledger {}
 
 impl Ledger {
@@ -47,30 +85,178 @@ impl Ledger {
         submitter_did: &DidValue,
         request: &mut PreparedRequest,
     ) -> CliResult<String> {
-        block_on(async move {
-            let signature = Self::_sign(request, store, submitter_did).await?;
-            request.set_signature(&signature)?;
-            Self::_submit_request(request, pool).await
-        })
+        Self::sign_request(store, submitter_did, request)?;
+        Self::submit_request(pool, request)
     }
 
     pub fn submit_request(pool: &Pool, request: &PreparedRequest) -> CliResult<String> {
-        block_on(async { Self::_submit_request(request, pool).await })
+        pool.backend().submit_request(request, pool.request_timeout)
+    }
+
+    /// Submits the request repeating it up to `retries` times if the ledger did not reply in time.
+    pub fn submit_request_with_retries(
+        pool: &Pool,
+        request: &PreparedRequest,
+        retries: u64,
+    ) -> CliResult<String> {
+        let mut attempt = 0;
+        loop {
+            match Self::submit_request(pool, request) {
+                Err(err) if attempt < retries && err.is_transient() => {
+                    attempt += 1;
+                    warn!(
+                        "Ledger request failed: {}. Retrying {} of {}",
+                        err, attempt, retries
+                    );
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Submits the requests concurrently returning the replies in the order of the requests.
+    /// Requests failed with a transient error are resubmitted together up to `retries` times.
+    pub fn submit_requests(
+        pool: &Pool,
+        requests: &[&PreparedRequest],
+        retries: u64,
+    ) -> CliResult<Vec<CliResult<String>>> {
+        let mut replies = pool
+            .backend()
+            .submit_requests(requests, pool.request_timeout)?;
+        for attempt in 1..=retries {
+            let failed = replies
+                .iter()
+                .enumerate()
+                .filter(|(_, reply)| matches!(reply, Err(err) if err.is_transient()))
+                .map(|(index, _)| index)
+                .collect::<Vec<usize>>();
+            if failed.is_empty() {
+                break;
+            }
+            warn!(
+                "{} ledger request(s) failed. Retrying {} of {}",
+                failed.len(),
+                attempt,
+                retries
+            );
+            let retried = pool.backend().submit_requests(
+                &failed
+                    .iter()
+                    .map(|index| requests[*index])
+                    .collect::<Vec<&PreparedRequest>>(),
+                pool.request_timeout,
+            )?;
+            for (index, reply) in failed.into_iter().zip(retried) {
+                replies[index] = reply;
+            }
+        }
+        Ok(replies)
     }
 
+    /// Sends the request to the selected (or all) nodes reporting every node reply as soon as it arrives.
+    /// Nodes which did not reply in time get `timeout` as the reply.
     pub fn submit_action(
         pool: &Pool,
         request: &PreparedRequest,
         nodes: Option<&str>,
         timeout: Option<i64>,
+        on_progress: &mut dyn FnMut(ActionProgress),
     ) -> CliResult<NodeReplies<String>> {
-        let nodes: Option<Vec<String>> = match nodes {
-            Some(nodes) => Some(serde_json::from_str::<Vec<String>>(nodes)?),
-            None => None,
+        Self::ensure_node_actions_supported(pool)?;
+
+        let nodes = Self::parse_action_nodes(nodes)?;
+        let targets: Vec<String> = match nodes {
+            Some(nodes) => {
+                let aliases = pool.pool.get_node_aliases();
+                nodes
+                    .into_iter()
+                    .filter(|node| aliases.contains(node))
+                    .collect()
+            }
+            None => pool.pool.get_node_aliases(),
         };
+        if targets.is_empty() {
+            return Err(CliError::InvalidInput(format!(
+                "None of the requested nodes belongs to the pool \"{}\".",
+                pool.name
+            )));
+        }
 
-        block_on(async {
-            let (request_result, _) = perform_ledger_action(
+        let started = Instant::now();
+        let pending = RefCell::new(targets.clone());
+        let on_progress = RefCell::new(on_progress);
+
+        block_on_cancellable_with_ticks(
+            async {
+                let mut pool_request = pool
+                    .pool
+                    .create_request(request.req_id.to_string(), request.req_json.to_string())
+                    .await?;
+                let timeout = timeout.unwrap_or(pool_request.pool_config().reply_timeout);
+                pool_request.send_to(targets.clone(), timeout)?;
+
+                let mut replies = NodeReplies::new();
+                while replies.len() < targets.len() {
+                    let (node, reply) = match pool_request.next().await {
+                        Some(RequestEvent::Received(node, reply, _)) => {
+                            match serde_json::from_str::<JsonValue>(&reply)
+                                .ok()
+                                .as_ref()
+                                .and_then(|reply| reply["op"].as_str())
+                            {
+                                Some("REPLY") | Some("REQNACK") | Some("REJECT") => {
+                                    pool_request.clean_timeout(node.clone())?;
+                                    (node, reply)
+                                }
+                                Some("REQACK") => {
+                                    pool_request.extend_timeout(node, timeout)?;
+                                    continue;
+                                }
+                                _ => continue,
+                            }
+                        }
+                        Some(RequestEvent::Timeout(node)) => {
+                            (node, ACTION_TIMEOUT_REPLY.to_string())
+                        }
+                        None => return Err(VdrError::from(VdrErrorKind::PoolTimeout).into()),
+                    };
+                    if replies.contains_key(&node) {
+                        continue;
+                    }
+
+                    pending.borrow_mut().retain(|pending| pending != &node);
+                    (*on_progress.borrow_mut())(ActionProgress::Replied {
+                        node: &node,
+                        reply: &reply,
+                        elapsed: started.elapsed(),
+                    });
+                    replies.insert(node, reply);
+                }
+                Ok(replies)
+            },
+            ACTION_PROGRESS_INTERVAL,
+            &mut |elapsed| {
+                (*on_progress.borrow_mut())(ActionProgress::Waiting {
+                    pending: &pending.borrow(),
+                    elapsed,
+                })
+            },
+        )
+    }
+
+    pub fn submit_action_with_timing(
+        pool: &Pool,
+        request: &PreparedRequest,
+        nodes: Option<&str>,
+        timeout: Option<i64>,
+    ) -> CliResult<(NodeReplies<String>, Option<TimingResult>)> {
+        Self::ensure_node_actions_supported(pool)?;
+
+        let nodes = Self::parse_action_nodes(nodes)?;
+
+        block_on_cancellable(async {
+            let (request_result, timing) = perform_ledger_action(
                 &pool.pool,
                 request.req_id.to_string(),
                 request.req_json.to_string(),
@@ -79,12 +265,29 @@ impl Ledger {
             )
             .await?;
             match request_result {
-                RequestResult::Reply(message) => Ok(message),
+                RequestResult::Reply(message) => Ok((message, timing)),
                 RequestResult::Failed(error) => Err(error.into()),
             }
         })
     }
 
+    fn ensure_node_actions_supported(pool: &Pool) -> CliResult<()> {
+        if pool.proxy.is_some() {
+            return Err(CliError::InvalidEntityState(format!(
+                "Pool \"{}\" is connected through ledger proxy which does not support node actions.",
+                pool.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn parse_action_nodes(nodes: Option<&str>) -> CliResult<Option<Vec<String>>> {
+        match nodes {
+            Some(nodes) => Ok(Some(serde_json::from_str::<Vec<String>>(nodes)?)),
+            None => Ok(None),
+        }
+    }
+
     pub fn sign_request(
         store: &Wallet,
         did: &DidValue,
@@ -138,6 +341,24 @@ impl Ledger {
             .map_err(CliError::from)
     }
 
+    /// Builds GET_NYM request for the NYM state at the given transaction seqNo or time (Indy Node 1.13+).
+    pub fn build_historical_get_nym_request(
+        pool: Option<&Pool>,
+        submitter_did: Option<&DidValue>,
+        target_did: &DidValue,
+        seq_no: Option<i32>,
+        timestamp: Option<u64>,
+    ) -> CliResult<PreparedRequest> {
+        let mut request = Self::build_get_nym_request(pool, submitter_did, target_did)?;
+        if let Some(seq_no) = seq_no {
+            request.req_json["operation"]["seqNo"] = json!(seq_no);
+        }
+        if let Some(timestamp) = timestamp {
+            request.req_json["operation"]["timestamp"] = json!(timestamp);
+        }
+        Ok(request)
+    }
+
     pub fn build_attrib_request(
         pool: Option<&Pool>,
         submitter_did: &DidValue,
@@ -196,6 +417,43 @@ impl Ledger {
             .map_err(CliError::from)
     }
 
+    pub fn build_rich_schema_request(
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        rich_schema: RichSchema,
+    ) -> CliResult<PreparedRequest> {
+        Self::_request_builder(pool)
+            .build_rich_schema_request(submitter_did, rich_schema)
+            .map_err(CliError::from)
+    }
+
+    pub fn build_get_rich_schema_by_id_request(
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        id: &RichSchemaId,
+    ) -> CliResult<PreparedRequest> {
+        Self::_request_builder(pool)
+            .build_get_rich_schema_by_id(submitter_did, id)
+            .map_err(CliError::from)
+    }
+
+    pub fn build_get_rich_schema_by_metadata_request(
+        pool: Option<&Pool>,
+        submitter_did: &DidValue,
+        rs_type: &str,
+        name: &str,
+        version: &str,
+    ) -> CliResult<PreparedRequest> {
+        Self::_request_builder(pool)
+            .build_get_rich_schema_by_metadata(
+                submitter_did,
+                rs_type.to_string(),
+                name.to_string(),
+                version.to_string(),
+            )
+            .map_err(CliError::from)
+    }
+
     pub fn build_cred_def_request(
         pool: Option<&Pool>,
         submitter_did: &DidValue,
@@ -215,6 +473,17 @@ impl Ledger {
             .map_err(CliError::from)
     }
 
+    pub fn build_get_txn_request(
+        pool: Option<&Pool>,
+        submitter_did: Option<&DidValue>,
+        ledger_type: i32,
+        seq_no: i32,
+    ) -> CliResult<PreparedRequest> {
+        Self::_request_builder(pool)
+            .build_get_txn_request(submitter_did, ledger_type, seq_no)
+            .map_err(CliError::from)
+    }
+
     pub fn build_get_cred_def_request(
         pool: Option<&Pool>,
         submitter_did: Option<&DidValue>,
@@ -484,6 +753,41 @@ impl Ledger {
             .map_err(CliError::from)
     }
 
+    /// Builds request with an arbitrary operation, e.g. a transaction type added by an indy-node plugin.
+    pub fn build_custom_request(
+        pool: Option<&Pool>,
+        submitter_did: Option<&DidValue>,
+        operation: JsonValue,
+    ) -> CliResult<PreparedRequest> {
+        let req_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+        let identifier = submitter_did
+            .map(|did| did.to_short().to_string())
+            .unwrap_or_else(|| DEFAULT_REQUEST_IDENTIFIER.to_string());
+
+        let request = json!({
+            "reqId": req_id,
+            "identifier": identifier,
+            "operation": operation,
+            "protocolVersion": Self::_request_builder(pool).protocol_version.to_id(),
+        });
+
+        PreparedRequest::from_request_json(request.to_string()).map_err(CliError::from)
+    }
+
+    /// Digest identifying the request effect on the Ledger: its submitter and operation.
+    /// Request id, signatures and transaction author agreement acceptance are not taken into account,
+    /// so resubmission of the same transaction gives the same digest.
+    pub fn request_digest(request: &PreparedRequest) -> String {
+        let content = json!({
+            "identifier": request.req_json["identifier"],
+            "operation": request.req_json["operation"],
+        });
+        hex::encode(SHA256::digest(content.to_string().as_bytes()))
+    }
+
     pub fn build_get_frozen_ledgers_request(
         pool: Option<&Pool>,
         submitter_did: &DidValue,
@@ -498,14 +802,6 @@ impl Ledger {
             .unwrap_or_else(|| RequestBuilder::new(ProtocolVersion::Node1_4))
     }
 
-    async fn _submit_request(request: &PreparedRequest, pool: &Pool) -> CliResult<String> {
-        let (request_result, _) = perform_ledger_request(&pool.pool, request).await?;
-        match request_result {
-            RequestResult::Reply(message) => Ok(message),
-            RequestResult::Failed(error) => Err(error.into()),
-        }
-    }
-
     async fn _sign(
         request: &mut PreparedRequest,
         store: &Wallet,
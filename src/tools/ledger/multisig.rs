@@ -0,0 +1,173 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::file::{read_file, write_file},
+};
+
+use indy_utils::did::DidValue;
+use indy_vdr::pool::PreparedRequest;
+use serde_json::Value as JsonValue;
+
+/// Coordination file of a multi-signature ceremony.
+/// Holds the transaction together with the signers expected to sign it and the required number of signatures.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigFile {
+    pub txn: JsonValue,
+    pub signers: Vec<String>,
+    pub threshold: usize,
+    #[serde(default)]
+    pub signed: Vec<MultisigSignature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultisigSignature {
+    pub did: String,
+    pub signed_at: u64,
+}
+
+impl MultisigFile {
+    pub fn new(txn: JsonValue, signers: &[&str], threshold: Option<usize>) -> CliResult<Self> {
+        let mut unique_signers: Vec<String> = Vec::new();
+        for signer in signers {
+            let signer = short_did(signer);
+            if !unique_signers.contains(&signer) {
+                unique_signers.push(signer);
+            }
+        }
+
+        if unique_signers.is_empty() {
+            return Err(CliError::InvalidInput(
+                "At least one signer must be specified".to_string(),
+            ));
+        }
+
+        let threshold = threshold.unwrap_or(unique_signers.len());
+        if threshold == 0 || threshold > unique_signers.len() {
+            return Err(CliError::InvalidInput(format!(
+                "Threshold must be between 1 and the number of signers ({})",
+                unique_signers.len()
+            )));
+        }
+
+        Ok(MultisigFile {
+            txn,
+            signers: unique_signers,
+            threshold,
+            signed: Vec::new(),
+        })
+    }
+
+    pub fn read(path: &str) -> CliResult<Self> {
+        let content = read_file(path).map_err(CliError::NotFound)?;
+        serde_json::from_str(&content).map_err(|err| {
+            CliError::InvalidInput(format!(
+                "Invalid multi-signature file \"{}\": {}",
+                path, err
+            ))
+        })
+    }
+
+    pub fn write(&self, path: &str) -> CliResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_file(path, &content).map_err(CliError::InvalidEntityState)
+    }
+
+    pub fn request(&self) -> CliResult<PreparedRequest> {
+        PreparedRequest::from_request_json(self.txn.to_string()).map_err(CliError::from)
+    }
+
+    pub fn is_signer(&self, did: &DidValue) -> bool {
+        self.signers.contains(&did.to_short().to_string())
+    }
+
+    /// Returns the expected signers whose signatures are present in the transaction.
+    pub fn signed_dids(&self) -> Vec<&str> {
+        let signatures = self.txn["signatures"].as_object();
+        self.signers
+            .iter()
+            .filter(|signer| {
+                signatures
+                    .map(|signatures| signatures.contains_key(signer.as_str()))
+                    .unwrap_or(false)
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn is_signed_by(&self, did: &DidValue) -> bool {
+        let did = did.to_short().to_string();
+        self.signed_dids().contains(&did.as_str())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.signed_dids().len() >= self.threshold
+    }
+
+    /// Stores the signed transaction and records who signed it.
+    pub fn add_signature(&mut self, did: &DidValue, request: &PreparedRequest, signed_at: u64) {
+        self.txn = request.req_json.clone();
+        self.signed.push(MultisigSignature {
+            did: did.to_short().to_string(),
+            signed_at,
+        });
+    }
+}
+
+fn short_did(did: &str) -> String {
+    DidValue(did.trim().to_string()).to_short().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNER_1: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const SIGNER_2: &str = "VsKV7grR1BUE29mG2Fm2kX";
+
+    fn txn(signatures: JsonValue) -> JsonValue {
+        json!({
+            "reqId": 1,
+            "identifier": SIGNER_1,
+            "operation": {"type": "105", "dest": SIGNER_1},
+            "protocolVersion": 2,
+            "signatures": signatures,
+        })
+    }
+
+    #[test]
+    fn new_works_for_default_threshold() {
+        let file =
+            MultisigFile::new(txn(json!({})), &[SIGNER_1, SIGNER_2, SIGNER_1], None).unwrap();
+        assert_eq!(vec![SIGNER_1, SIGNER_2], file.signers);
+        assert_eq!(2, file.threshold);
+    }
+
+    #[test]
+    fn new_fails_for_invalid_threshold() {
+        MultisigFile::new(txn(json!({})), &[SIGNER_1, SIGNER_2], Some(3)).unwrap_err();
+        MultisigFile::new(txn(json!({})), &[SIGNER_1, SIGNER_2], Some(0)).unwrap_err();
+    }
+
+    #[test]
+    fn is_complete_works() {
+        let mut file = MultisigFile::new(txn(json!({})), &[SIGNER_1, SIGNER_2], Some(1)).unwrap();
+        assert!(!file.is_complete());
+
+        file.txn = txn(json!({ SIGNER_2: "signature" }));
+        assert_eq!(vec![SIGNER_2], file.signed_dids());
+        assert!(file.is_signed_by(&DidValue(SIGNER_2.to_string())));
+        assert!(file.is_complete());
+    }
+
+    #[test]
+    fn is_complete_ignores_unexpected_signers() {
+        let mut file = MultisigFile::new(txn(json!({})), &[SIGNER_1], None).unwrap();
+        file.txn = txn(json!({ SIGNER_2: "signature" }));
+        assert!(!file.is_complete());
+    }
+}
@@ -0,0 +1,316 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::file::read_file,
+};
+
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::HashMap;
+
+/// Ledger plugin definition describing transactions added to the Ledger by an indy-node plugin.
+/// Every command is exposed in the `ledger` group and builds its request from the operation template.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDefinition {
+    pub name: String,
+    pub commands: Vec<PluginCommandDefinition>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCommandDefinition {
+    pub name: String,
+    pub help: String,
+    pub txn_type: String,
+    pub txn_title: Option<String>,
+    #[serde(default = "default_write")]
+    pub write: bool,
+    #[serde(default)]
+    pub params: Vec<PluginParam>,
+    #[serde(default)]
+    pub operation: JsonMap<String, JsonValue>,
+    #[serde(default)]
+    pub examples: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PluginParam {
+    pub name: String,
+    pub help: String,
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default, rename = "type")]
+    pub type_: PluginParamType,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginParamType {
+    #[default]
+    String,
+    Number,
+    Bool,
+    Json,
+}
+
+/// Parameters every plugin command gets in addition to the ones of its definition.
+pub const RESERVED_PARAMS: [&str; 7] = [
+    "sign",
+    "send",
+    "endorser",
+    "timeout",
+    "idempotent",
+    "receipt",
+    "help",
+];
+
+fn default_write() -> bool {
+    true
+}
+
+impl PluginDefinition {
+    pub fn read(path: &str) -> CliResult<PluginDefinition> {
+        let content = read_file(path).map_err(CliError::NotFound)?;
+        let definition: PluginDefinition = serde_json::from_str(&content).map_err(|err| {
+            CliError::InvalidInput(format!("Invalid plugin definition \"{}\": {}", path, err))
+        })?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    fn validate(&self) -> CliResult<()> {
+        for (index, command) in self.commands.iter().enumerate() {
+            if self.commands[..index]
+                .iter()
+                .any(|other| other.name == command.name)
+            {
+                return Err(CliError::InvalidInput(format!(
+                    "Plugin \"{}\" command \"{}\" is defined twice",
+                    self.name, command.name
+                )));
+            }
+            command.validate().map_err(|err| {
+                CliError::InvalidInput(format!(
+                    "Plugin \"{}\" command \"{}\": {}",
+                    self.name, command.name, err
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl PluginCommandDefinition {
+    fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() || self.name.contains(char::is_whitespace) {
+            return Err("command name must be a non-empty word".to_string());
+        }
+        if self.txn_type.is_empty() {
+            return Err("transaction type must not be empty".to_string());
+        }
+        if self.operation.contains_key("type") {
+            return Err(
+                "operation template must not contain \"type\" field, use \"txnType\" instead"
+                    .to_string(),
+            );
+        }
+        for (index, param) in self.params.iter().enumerate() {
+            if RESERVED_PARAMS.contains(&param.name.as_str()) {
+                return Err(format!("parameter name \"{}\" is reserved", param.name));
+            }
+            if self.params[..index]
+                .iter()
+                .any(|other| other.name == param.name)
+            {
+                return Err(format!("parameter \"{}\" is defined twice", param.name));
+            }
+        }
+        for name in template_references(&JsonValue::Object(self.operation.clone())) {
+            if !self.params.iter().any(|param| param.name == name) {
+                return Err(format!(
+                    "operation template references unknown parameter \"{}\"",
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds request operation from the template.
+    /// String values of the form `$name` are replaced by the typed parameter values,
+    /// fields referencing omitted optional parameters are dropped.
+    pub fn build_operation(&self, values: &HashMap<&str, &str>) -> CliResult<JsonValue> {
+        let mut typed_values = HashMap::new();
+        for param in self.params.iter() {
+            match values.get(param.name.as_str()) {
+                Some(value) => {
+                    typed_values.insert(param.name.as_str(), param.parse(value)?);
+                }
+                None if param.optional => {}
+                None => {
+                    return Err(CliError::InvalidInput(format!(
+                        "Parameter \"{}\" is required",
+                        param.name
+                    )))
+                }
+            }
+        }
+
+        let mut operation = match render(&JsonValue::Object(self.operation.clone()), &typed_values)
+        {
+            Some(operation) => operation,
+            None => JsonValue::Object(JsonMap::new()),
+        };
+        operation["type"] = JsonValue::String(self.txn_type.to_string());
+        Ok(operation)
+    }
+}
+
+impl PluginParam {
+    fn parse(&self, value: &str) -> CliResult<JsonValue> {
+        let invalid = || {
+            CliError::InvalidInput(format!(
+                "Parameter \"{}\" has invalid value \"{}\"",
+                self.name, value
+            ))
+        };
+        match self.type_ {
+            PluginParamType::String => Ok(JsonValue::String(value.to_string())),
+            PluginParamType::Number => serde_json::from_str::<serde_json::Number>(value)
+                .map(JsonValue::Number)
+                .map_err(|_| invalid()),
+            PluginParamType::Bool => value
+                .parse::<bool>()
+                .map(JsonValue::Bool)
+                .map_err(|_| invalid()),
+            PluginParamType::Json => {
+                serde_json::from_str::<JsonValue>(value).map_err(|_| invalid())
+            }
+        }
+    }
+}
+
+fn template_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix("${")
+        .and_then(|name| name.strip_suffix('}'))
+        .or_else(|| value.strip_prefix('$'))
+        .filter(|name| !name.is_empty())
+}
+
+fn template_references(template: &JsonValue) -> Vec<String> {
+    match template {
+        JsonValue::String(value) => template_reference(value)
+            .map(|name| vec![name.to_string()])
+            .unwrap_or_default(),
+        JsonValue::Array(values) => values.iter().flat_map(template_references).collect(),
+        JsonValue::Object(values) => values.values().flat_map(template_references).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns `None` if the value references an omitted parameter.
+fn render(template: &JsonValue, values: &HashMap<&str, JsonValue>) -> Option<JsonValue> {
+    match template {
+        JsonValue::String(value) => match template_reference(value) {
+            Some(name) => values.get(name).cloned(),
+            None => Some(template.clone()),
+        },
+        JsonValue::Array(items) => Some(JsonValue::Array(
+            items
+                .iter()
+                .filter_map(|item| render(item, values))
+                .collect(),
+        )),
+        JsonValue::Object(fields) => Some(JsonValue::Object(
+            fields
+                .iter()
+                .filter_map(|(key, value)| render(value, values).map(|value| (key.clone(), value)))
+                .collect(),
+        )),
+        _ => Some(template.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> PluginCommandDefinition {
+        serde_json::from_value(json!({
+            "name": "set-fees",
+            "help": "Set transaction fees",
+            "txnType": "20000",
+            "params": [
+                {"name": "fees", "help": "Fees", "type": "json"},
+                {"name": "ledger_id", "help": "Ledger", "optional": true, "type": "number"},
+                {"name": "comment", "help": "Comment", "optional": true}
+            ],
+            "operation": {"fees": "$fees", "ledgerId": "${ledger_id}", "meta": {"comment": "$comment", "version": "1.0"}}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn build_operation_works() {
+        let mut values = HashMap::new();
+        values.insert("fees", r#"{"1":4}"#);
+        values.insert("ledger_id", "1001");
+        values.insert("comment", "new fees");
+
+        let operation = definition().build_operation(&values).unwrap();
+        assert_eq!(
+            json!({
+                "type": "20000",
+                "fees": {"1": 4},
+                "ledgerId": 1001,
+                "meta": {"comment": "new fees", "version": "1.0"}
+            }),
+            operation
+        );
+    }
+
+    #[test]
+    fn build_operation_works_for_omitted_optional_params() {
+        let mut values = HashMap::new();
+        values.insert("fees", r#"{"1":4}"#);
+
+        let operation = definition().build_operation(&values).unwrap();
+        assert_eq!(
+            json!({"type": "20000", "fees": {"1": 4}, "meta": {"version": "1.0"}}),
+            operation
+        );
+    }
+
+    #[test]
+    fn build_operation_fails_for_invalid_values() {
+        let mut values = HashMap::new();
+        definition().build_operation(&values).unwrap_err();
+
+        values.insert("fees", r#"{"1":4}"#);
+        values.insert("ledger_id", "first");
+        definition().build_operation(&values).unwrap_err();
+    }
+
+    #[test]
+    fn validate_fails_for_unknown_reference() {
+        let mut definition = definition();
+        definition
+            .operation
+            .insert("amount".to_string(), json!("$amount"));
+        definition.validate().unwrap_err();
+    }
+
+    #[test]
+    fn validate_fails_for_reserved_param() {
+        let mut definition = definition();
+        definition.params[0].name = "send".to_string();
+        definition.validate().unwrap_err();
+    }
+}
@@ -0,0 +1,260 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::error::{CliError, CliResult};
+
+use indy_blssignatures::{Bls, Generator, MultiSignature, VerKey};
+use indy_utils::base58;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+/// Generator of the BLS keys used by indy-node to sign ledger roots.
+const BLS_GENERATOR: &str = "3LHpUjiyFC2q2hD7MnwwNmVXiuaFbQx2XkAFJWzswCjgN1utjsCeLzHsKk1nJvFEaS4fcrUmVAkdhtPCYbrVyATZcmzwJReTcJqwqBCPTmTQ9uWPwz6rEncKb2pYYYFcdHa8N17HzVyTqKfgPi4X9pMetfT3A5xCHq54R2pDNYWVLDX";
+
+/// Generator of the BLS keys of indy nodes.
+pub fn bls_generator() -> CliResult<Generator> {
+    Generator::from_bytes(&decode_hash(BLS_GENERATOR)?)
+        .map_err(|_| CliError::InvalidInput("Invalid BLS generator".to_string()))
+}
+
+/// Fields added by nodes to GET_TXN reply data which are not a part of the stored transaction.
+const MERKLE_INFO_FIELDS: [&str; 3] = ["auditPath", "ledgerSize", "rootHash"];
+
+/// Computes the merkle tree leaf hash of the transaction as it is stored in the ledger
+/// (msgpack serialized transaction prefixed with 0x00, RFC 6962).
+pub fn txn_leaf_hash(txn: &JsonValue) -> CliResult<Vec<u8>> {
+    let mut txn = txn.clone();
+    if let Some(txn) = txn.as_object_mut() {
+        for field in MERKLE_INFO_FIELDS {
+            txn.remove(field);
+        }
+    }
+    let serialized = rmp_serde::to_vec(&txn).map_err(|err| {
+        CliError::InvalidInput(format!("Unable to serialize transaction: {}", err))
+    })?;
+    Ok(Sha256::new()
+        .chain_update([0x00])
+        .chain_update(serialized)
+        .finalize()
+        .to_vec())
+}
+
+fn node_hash(left: &[u8], right: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .to_vec()
+}
+
+/// Computes the root hash of the tree of `tree_size` leaves from the leaf hash at `index`
+/// and its audit path (RFC 6962 inclusion proof). Returns `None` if the path does not fit the tree.
+pub fn audit_path_root(
+    leaf_hash: &[u8],
+    index: u64,
+    tree_size: u64,
+    audit_path: &[Vec<u8>],
+) -> Option<Vec<u8>> {
+    if index >= tree_size {
+        return None;
+    }
+    let (mut index, mut last) = (index, tree_size - 1);
+    let mut hash = leaf_hash.to_vec();
+
+    for sibling in audit_path {
+        if last == 0 {
+            return None;
+        }
+        if index % 2 == 1 || index == last {
+            hash = node_hash(sibling, &hash);
+            while index % 2 == 0 && index != 0 {
+                index >>= 1;
+                last >>= 1;
+            }
+        } else {
+            hash = node_hash(&hash, sibling);
+        }
+        index >>= 1;
+        last >>= 1;
+    }
+
+    match last {
+        0 => Some(hash),
+        _ => None,
+    }
+}
+
+/// Decodes base58 encoded audit path of a ledger reply.
+pub fn decode_audit_path(audit_path: &JsonValue) -> CliResult<Vec<Vec<u8>>> {
+    audit_path
+        .as_array()
+        .ok_or_else(|| CliError::InvalidInput("Audit path is missing".to_string()))?
+        .iter()
+        .map(|hash| decode_hash(hash.as_str().unwrap_or_default()))
+        .collect()
+}
+
+pub fn decode_hash(hash: &str) -> CliResult<Vec<u8>> {
+    base58::decode(hash)
+        .map_err(|_| CliError::InvalidInput(format!("Invalid base58 hash: \"{}\"", hash)))
+}
+
+/// Serializes the signed value of a multi-signature the way nodes do before signing it:
+/// `key:value` pairs of sorted keys joined with `|`.
+pub fn serialize_multi_signature_value(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::Bool(true) => "True".to_string(),
+        JsonValue::Bool(false) => "False".to_string(),
+        JsonValue::Number(number) => number.to_string(),
+        JsonValue::String(string) => string.to_string(),
+        JsonValue::Array(array) => array
+            .iter()
+            .map(serialize_multi_signature_value)
+            .collect::<Vec<String>>()
+            .join(","),
+        JsonValue::Object(object) => {
+            let mut keys: Vec<&String> = object.keys().collect();
+            keys.sort();
+            keys.into_iter()
+                .map(|key| format!("{}:{}", key, serialize_multi_signature_value(&object[key])))
+                .collect::<Vec<String>>()
+                .join("|")
+        }
+    }
+}
+
+/// Verifies BLS multi-signature of the nodes over the signed value
+/// against the base58 encoded BLS keys of the participating nodes.
+pub fn verify_multi_signature(
+    signature: &str,
+    value: &JsonValue,
+    blskeys: &[&str],
+) -> CliResult<bool> {
+    let signature = MultiSignature::from_bytes(&decode_hash(signature)?).map_err(|_| {
+        CliError::InvalidInput("Invalid multi-signature has been received".to_string())
+    })?;
+    let verkeys = blskeys
+        .iter()
+        .map(|blskey| {
+            base58::decode(blskey)
+                .ok()
+                .and_then(|bytes| VerKey::from_bytes(&bytes).ok())
+                .ok_or_else(|| {
+                    CliError::InvalidInput(format!("Invalid node BLS key: \"{}\"", blskey))
+                })
+        })
+        .collect::<CliResult<Vec<VerKey>>>()?;
+    let generator = bls_generator()?;
+
+    let message = serialize_multi_signature_value(value);
+    Bls::verify_multi_sig(
+        &signature,
+        message.as_bytes(),
+        &verkeys.iter().collect::<Vec<&VerKey>>(),
+        &generator,
+    )
+    .map_err(|_| CliError::InvalidInput("Unable to verify multi-signature".to_string()))
+}
+
+/// Minimal number of nodes which must sign ledger roots in the pool of `nodes` validators.
+pub fn multi_signature_quorum(nodes: usize) -> usize {
+    nodes - nodes.saturating_sub(1) / 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indy_blssignatures::{SignKey, Signature};
+
+    fn leaf(value: u8) -> Vec<u8> {
+        Sha256::new()
+            .chain_update([0x00, value])
+            .finalize()
+            .to_vec()
+    }
+
+    #[test]
+    fn audit_path_root_works() {
+        let (l0, l1, l2) = (leaf(0), leaf(1), leaf(2));
+        let root = node_hash(&node_hash(&l0, &l1), &l2);
+
+        let path = vec![l1.clone(), l2.clone()];
+        assert_eq!(Some(root.clone()), audit_path_root(&l0, 0, 3, &path));
+
+        let path = vec![node_hash(&l0, &l1)];
+        assert_eq!(Some(root), audit_path_root(&l2, 2, 3, &path));
+    }
+
+    #[test]
+    fn audit_path_root_fails_for_wrong_proof() {
+        let (l0, l1, l2) = (leaf(0), leaf(1), leaf(2));
+        let root = node_hash(&node_hash(&l0, &l1), &l2);
+
+        let path = vec![l1.clone(), l2];
+        assert_ne!(Some(root), audit_path_root(&l1, 0, 3, &path));
+        assert_eq!(None, audit_path_root(&l0, 0, 2, &path));
+        assert_eq!(None, audit_path_root(&l0, 3, 3, &path));
+    }
+
+    #[test]
+    fn txn_leaf_hash_ignores_merkle_info() {
+        let txn = json!({"txn": {"type": "1"}, "txnMetadata": {"seqNo": 1}, "ver": "1"});
+        let mut reply = txn.clone();
+        reply["auditPath"] = json!(["3b9a"]);
+        reply["ledgerSize"] = json!(1);
+        reply["rootHash"] = json!("3b9a");
+        assert_eq!(txn_leaf_hash(&txn).unwrap(), txn_leaf_hash(&reply).unwrap());
+    }
+
+    #[test]
+    fn serialize_multi_signature_value_works() {
+        let value = json!({
+            "txn_root_hash": "txn",
+            "ledger_id": 1,
+            "timestamp": 1600000000,
+            "state_root_hash": "state",
+            "pool_state_root_hash": "pool",
+        });
+        assert_eq!(
+            "ledger_id:1|pool_state_root_hash:pool|state_root_hash:state|timestamp:1600000000|txn_root_hash:txn",
+            serialize_multi_signature_value(&value)
+        );
+    }
+
+    #[test]
+    fn verify_multi_signature_works() {
+        let generator = bls_generator().unwrap();
+        let value = json!({"ledger_id": 1, "txn_root_hash": "txn"});
+        let message = serialize_multi_signature_value(&value);
+
+        let keys: Vec<(SignKey, String)> = (0..2)
+            .map(|_| {
+                let sign_key = SignKey::new(None).unwrap();
+                let verkey = VerKey::new(&generator, &sign_key).unwrap();
+                (sign_key, base58::encode(verkey.as_bytes()))
+            })
+            .collect();
+        let signatures: Vec<Signature> = keys
+            .iter()
+            .map(|(sign_key, _)| Bls::sign(message.as_bytes(), sign_key).unwrap())
+            .collect();
+        let signature =
+            MultiSignature::new(&signatures.iter().collect::<Vec<&Signature>>()).unwrap();
+        let signature = base58::encode(signature.as_bytes());
+        let blskeys: Vec<&str> = keys.iter().map(|(_, blskey)| blskey.as_str()).collect();
+
+        assert!(verify_multi_signature(&signature, &value, &blskeys).unwrap());
+        assert!(!verify_multi_signature(&signature, &json!({"ledger_id": 2}), &blskeys).unwrap());
+        assert!(!verify_multi_signature(&signature, &value, &blskeys[..1]).unwrap());
+    }
+
+    #[test]
+    fn multi_signature_quorum_works() {
+        assert_eq!(1, multi_signature_quorum(1));
+        assert_eq!(3, multi_signature_quorum(4));
+        assert_eq!(5, multi_signature_quorum(7));
+    }
+}
@@ -0,0 +1,86 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+
+/// Fragments of common indy-node REQNACK/REJECT reasons (compared ignoring case)
+/// with hints on how to fix the transaction. The first matching entry is used.
+const REJECT_HINTS: [(&[&str], &str); 9] = [
+    (
+        &["missingsignature", "missed signature"],
+        "The request has not been signed. Send it with `sign=true` or sign the stored transaction with `ledger sign-multi`.",
+    ),
+    (
+        &["insufficientcorrectsignatures", "invalid signature", "couldnotauthenticate"],
+        "The signature does not match the verkey of the submitter DID on the ledger. Check the active DID with `ledger get-nym` and finish a pending key rotation if there is one.",
+    ),
+    (
+        &["unknownidentifier", "is not found in the ledger"],
+        "The submitter DID is not written on the ledger. Ask a Trustee or Steward to publish it with `ledger nym`.",
+    ),
+    (
+        &["endorser' field must be explicitly set", "endorser must sign"],
+        "The transaction has to be endorsed: pass `endorser=<did>` to prepare it and let the endorser sign and send it with `ledger endorse`.",
+    ),
+    (
+        &["unauthorizedclientrequest", "not enough", "role cannot", "rule for this action is"],
+        "The submitter DID does not have the role required by the ledger auth rules. Check the rule with `ledger get-auth-rule` or ask an Endorser to endorse the transaction (`endorser=<did>`).",
+    ),
+    (
+        &["incorrect txn author agreement", "taa digest", "does not match the digest"],
+        "The accepted Transaction Author Agreement differs from the one active on the ledger. Accept the current one with `pool show-taa`.",
+    ),
+    (
+        &["acceptance mechanism", "not among acceptable"],
+        "The acceptance mechanism is not allowed by the ledger. List the allowed ones with `ledger get-acceptance-mechanisms` and set one with `pool show-taa`.",
+    ),
+    (
+        &["txn author agreement", "transaction author agreement"],
+        "The ledger requires a Transaction Author Agreement acceptance. Accept it with `pool show-taa` before sending write transactions.",
+    ),
+    (
+        &["one and only one schema"],
+        "A schema with the same name and version has already been published by this DID. Increase the schema version or fetch the existing one with `ledger get-schema`.",
+    ),
+];
+
+/// Returns an actionable hint for a Ledger reject reason if the reason is a known one.
+pub fn reject_hint(reason: &str) -> Option<&'static str> {
+    let reason = reason.to_lowercase();
+    REJECT_HINTS
+        .iter()
+        .find(|(fragments, _)| fragments.iter().any(|fragment| reason.contains(fragment)))
+        .map(|(_, hint)| *hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_hint_works() {
+        assert!(reject_hint("client request invalid: MissingSignature()")
+            .unwrap()
+            .contains("sign=true"));
+        assert!(reject_hint(
+            "client request invalid: UnauthorizedClientRequest('Rule for this action is: 1 TRUSTEE signature is required')"
+        )
+        .unwrap()
+        .contains("get-auth-rule"));
+        assert!(reject_hint(
+            "client request invalid: InvalidClientTaaAcceptanceError('Incorrect Txn Author Agreement(digest=abc) in the request')"
+        )
+        .unwrap()
+        .contains("show-taa"));
+        assert!(reject_hint(
+            "V4SGRU86Z58d6TV7PBUe6f can have one and only one SCHEMA with name gvt and version 1.0"
+        )
+        .unwrap()
+        .contains("schema version"));
+    }
+
+    #[test]
+    fn reject_hint_works_for_unknown_reason() {
+        assert_eq!(None, reject_hint("Something went wrong"));
+    }
+}
@@ -2,7 +2,10 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::utils::time::timestamp_to_datetime;
+use crate::{
+    error::{CliError, CliResult},
+    utils::time::timestamp_to_datetime,
+};
 
 use serde_json::Value as JsonValue;
 
@@ -23,14 +26,14 @@ pub struct Response<T> {
 
 pub fn parse_transaction_response(
     mut result: JsonValue,
-) -> Result<(Vec<(&'static str, &'static str)>, JsonValue, JsonValue), ()> {
+) -> CliResult<(Vec<(&'static str, &'static str)>, JsonValue, JsonValue)> {
     match result["ver"].clone().as_str() {
         None => Ok(parse_transaction_response_v0(&mut result)),
         Some("1") => Ok(parse_transaction_response_v1(&mut result)),
-        ver => Err(println_err!(
+        ver => Err(CliError::InvalidInput(format!(
             "Unsupported transaction response format: {:?}",
             ver
-        )),
+        ))),
     }
 }
 
@@ -4,5 +4,7 @@
 */
 pub mod did;
 pub mod ledger;
+pub mod network;
 pub mod pool;
+pub mod session;
 pub mod wallet;
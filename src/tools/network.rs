@@ -0,0 +1,87 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::environment::EnvironmentUtils,
+};
+
+use std::{
+    fs,
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+/// Network profile wiring together the pool to connect, TAA acceptance mechanism and the DID to use.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    pub name: String,
+    pub pool: String,
+    pub taa_mechanism: Option<String>,
+    pub default_did: Option<String>,
+}
+
+impl NetworkConfig {
+    pub fn store(&self) -> CliResult<()> {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .create(EnvironmentUtils::networks_path())?;
+
+        let mut config_file = File::create(self.path())?;
+        let config_json = json!(self).to_string();
+        config_file.write_all(config_json.as_bytes())?;
+        config_file.sync_all()?;
+        Ok(())
+    }
+
+    pub fn read(name: &str) -> CliResult<Self> {
+        let path = EnvironmentUtils::network_config_path(name);
+        if !path.exists() {
+            return Err(CliError::NotFound(format!(
+                "Network \"{}\" does not exist.",
+                name
+            )));
+        }
+
+        let mut config_json = String::new();
+        let mut file = File::open(path)?;
+        file.read_to_string(&mut config_json)?;
+
+        serde_json::from_str(&config_json).map_err(CliError::from)
+    }
+
+    pub fn delete(&self) -> CliResult<()> {
+        fs::remove_file(self.path()).map_err(CliError::from)
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path().exists()
+    }
+
+    pub fn list() -> Vec<NetworkConfig> {
+        let mut configs = Vec::new();
+
+        if let Ok(entries) = fs::read_dir(EnvironmentUtils::networks_path()) {
+            for entry in entries.flatten() {
+                let mut config_json = String::new();
+
+                if let Some(config) = File::open(entry.path())
+                    .ok()
+                    .and_then(|mut file| file.read_to_string(&mut config_json).ok())
+                    .and_then(|_| serde_json::from_str::<NetworkConfig>(&config_json).ok())
+                {
+                    configs.push(config);
+                }
+            }
+        }
+
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+        configs
+    }
+
+    fn path(&self) -> PathBuf {
+        EnvironmentUtils::network_config_path(&self.name)
+    }
+}
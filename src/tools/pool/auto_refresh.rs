@@ -0,0 +1,191 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{error::CliResult, utils::futures::block_on};
+
+use indy_vdr::{
+    config::PoolConfig as OpenPoolConfig,
+    pool::{helpers::perform_refresh, Pool as PoolImpl, PoolBuilder, PoolTransactions},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use super::Pool;
+
+/// New pool transactions received by the background catch-up.
+#[derive(Debug, PartialEq)]
+pub struct RefreshUpdate {
+    /// Pool ledger size the transactions follow.
+    pub txn_count: usize,
+    pub transactions: Vec<String>,
+}
+
+impl RefreshUpdate {
+    /// Returns the transactions missing in the pool ledger of `txn_count` size,
+    /// so the ones already received by `pool refresh` meanwhile are not added twice.
+    /// Nothing is returned if the transactions do not follow the pool ledger.
+    pub fn missing_transactions(&self, txn_count: usize) -> &[String] {
+        if txn_count < self.txn_count {
+            return &[];
+        }
+        let skip = txn_count - self.txn_count;
+        &self.transactions[skip.min(self.transactions.len())..]
+    }
+
+    fn merge(&mut self, update: RefreshUpdate) {
+        if self.txn_count + self.transactions.len() == update.txn_count {
+            self.transactions.extend(update.transactions);
+        }
+    }
+}
+
+#[derive(Default)]
+struct AutoRefreshState {
+    stopped: AtomicBool,
+    pending: Mutex<Option<Result<RefreshUpdate, String>>>,
+}
+
+/// Periodic catch-up of the connected pool ledger running in a background thread.
+/// The connected pool is not shared with the thread: it catches up its own copy of the pool ledger
+/// and hands the new transactions over to be applied to the connected pool before the next command.
+pub struct PoolAutoRefresh {
+    pub pool_name: String,
+    pub interval: Duration,
+    state: Arc<AutoRefreshState>,
+    thread: JoinHandle<()>,
+}
+
+impl PoolAutoRefresh {
+    pub fn start(pool: &Pool, interval: Duration) -> CliResult<PoolAutoRefresh> {
+        let config: OpenPoolConfig = pool.pool.get_config().to_owned();
+        let transactions = PoolTransactions::from(pool.pool.get_merkle_tree());
+        let txn_count = pool.txn_count();
+        let state = Arc::new(AutoRefreshState::default());
+
+        let thread = {
+            let state = state.clone();
+            thread::Builder::new()
+                .name(format!("pool-auto-refresh-{}", pool.name))
+                .spawn(move || run(config, transactions, txn_count, interval, state))?
+        };
+
+        Ok(PoolAutoRefresh {
+            pool_name: pool.name.to_string(),
+            interval,
+            state,
+            thread,
+        })
+    }
+
+    /// Takes the transactions received since the last call or the error of the last catch-up.
+    pub fn take_update(&self) -> Option<Result<RefreshUpdate, String>> {
+        self.state.pending.lock().unwrap().take()
+    }
+}
+
+/// The thread is not joined: a catch-up in progress is completed in background and its result is dropped.
+impl Drop for PoolAutoRefresh {
+    fn drop(&mut self) {
+        self.state.stopped.store(true, Ordering::SeqCst);
+        self.thread.thread().unpark();
+    }
+}
+
+fn run(
+    config: OpenPoolConfig,
+    mut transactions: PoolTransactions,
+    mut txn_count: usize,
+    interval: Duration,
+    state: Arc<AutoRefreshState>,
+) {
+    loop {
+        let deadline = Instant::now() + interval;
+        loop {
+            if state.stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            thread::park_timeout(deadline - now);
+        }
+
+        let res = catch_up(&config, &transactions).and_then(|new_transactions| {
+            transactions.extend_from_json(&new_transactions)?;
+            Ok(new_transactions)
+        });
+
+        if state.stopped.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut pending = state.pending.lock().unwrap();
+        match res {
+            Ok(new_transactions) if new_transactions.is_empty() => {}
+            Ok(new_transactions) => {
+                let update = RefreshUpdate {
+                    txn_count,
+                    transactions: new_transactions,
+                };
+                txn_count += update.transactions.len();
+                match pending.as_mut() {
+                    Some(Ok(pending)) => pending.merge(update),
+                    _ => *pending = Some(Ok(update)),
+                }
+            }
+            Err(err) => {
+                warn!("Pool auto-refresh has failed: {}", err.message(None));
+                if pending.is_none() {
+                    *pending = Some(Err(err.message(None)));
+                }
+            }
+        }
+    }
+}
+
+fn catch_up(config: &OpenPoolConfig, transactions: &PoolTransactions) -> CliResult<Vec<String>> {
+    let pool = PoolBuilder::from(config.to_owned())
+        .transactions(transactions.clone())?
+        .into_shared()?;
+    let (new_transactions, _) = block_on(perform_refresh(&pool))?;
+    Ok(new_transactions.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(txn_count: usize, transactions: &[&str]) -> RefreshUpdate {
+        RefreshUpdate {
+            txn_count,
+            transactions: transactions.iter().map(|txn| txn.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn missing_transactions_works() {
+        let update = update(4, &["5", "6"]);
+        assert_eq!(&["5", "6"], update.missing_transactions(4));
+        assert_eq!(&["6"], update.missing_transactions(5));
+        assert!(update.missing_transactions(6).is_empty());
+        assert!(update.missing_transactions(10).is_empty());
+        assert!(update.missing_transactions(3).is_empty());
+    }
+
+    #[test]
+    fn merge_works() {
+        let mut pending = update(4, &["5"]);
+        pending.merge(update(5, &["6", "7"]));
+        assert_eq!(update(4, &["5", "6", "7"]), pending);
+
+        pending.merge(update(5, &["6"]));
+        assert_eq!(update(4, &["5", "6", "7"]), pending);
+    }
+}
@@ -0,0 +1,266 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use super::NODE_TXN_TYPE;
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// Node described by a NODE transaction of the pool genesis file.
+#[derive(Debug)]
+pub struct GenesisNode {
+    /// Line number of the transaction in the file.
+    pub line: usize,
+    pub alias: Option<String>,
+    pub dest: Option<String>,
+    pub node_address: Option<String>,
+    pub client_address: Option<String>,
+    pub bls_key: bool,
+    pub steward: Option<String>,
+    pub services: Vec<String>,
+}
+
+/// Result of the genesis file inspection: the nodes which could be parsed and problems found in the file.
+#[derive(Debug, Default)]
+pub struct GenesisReport {
+    pub nodes: Vec<GenesisNode>,
+    pub problems: Vec<String>,
+}
+
+impl GenesisReport {
+    /// Parses pool genesis transactions (one JSON object per line) without connecting to the pool.
+    /// Malformed lines, NODE transactions missing required fields and nodes sharing
+    /// alias, identifier or address are reported as problems instead of failing on the first one.
+    pub fn inspect(transactions: &str) -> GenesisReport {
+        let mut report = GenesisReport::default();
+        let mut protocol_version = None;
+
+        for (index, line) in transactions.lines().enumerate() {
+            let line_no = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let txn = match serde_json::from_str::<JsonValue>(line) {
+                Ok(txn @ JsonValue::Object(_)) => txn,
+                Ok(_) => {
+                    report.problems.push(format!(
+                        "Line {}: transaction is not a JSON object",
+                        line_no
+                    ));
+                    continue;
+                }
+                Err(err) => {
+                    report
+                        .problems
+                        .push(format!("Line {}: invalid JSON: {}", line_no, err));
+                    continue;
+                }
+            };
+
+            // protocol version 2 wraps the transaction into `txn` object, version 1 is flat
+            let (version, txn_type, data, steward) = if txn["txn"].is_object() {
+                (
+                    2,
+                    &txn["txn"]["type"],
+                    &txn["txn"]["data"],
+                    &txn["txn"]["metadata"]["from"],
+                )
+            } else {
+                (1, &txn["type"], &txn, &txn["identifier"])
+            };
+            match protocol_version {
+                None => protocol_version = Some(version),
+                Some(expected) if expected != version => report.problems.push(format!(
+                    "Line {}: transaction format differs from the previous transactions (protocol version {} and {} are mixed)",
+                    line_no, expected, version
+                )),
+                Some(_) => {}
+            }
+
+            if txn_type.as_str() != Some(NODE_TXN_TYPE) {
+                report.problems.push(format!(
+                    "Line {}: unexpected transaction type {}, only NODE (\"{}\") transactions are expected",
+                    line_no, txn_type, NODE_TXN_TYPE
+                ));
+                continue;
+            }
+
+            report.nodes.push(Self::parse_node(
+                line_no,
+                data,
+                steward,
+                &mut report.problems,
+            ));
+        }
+
+        if report.nodes.is_empty() && report.problems.is_empty() {
+            report
+                .problems
+                .push("There are no pool transactions in the file".to_string());
+        }
+
+        report.check_duplicates();
+        report
+    }
+
+    pub fn bls_keys_count(&self) -> usize {
+        self.nodes.iter().filter(|node| node.bls_key).count()
+    }
+
+    pub fn validators_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.services.iter().any(|service| service == "VALIDATOR"))
+            .count()
+    }
+
+    fn parse_node(
+        line: usize,
+        data: &JsonValue,
+        steward: &JsonValue,
+        problems: &mut Vec<String>,
+    ) -> GenesisNode {
+        let node_data = &data["data"];
+        let string = |value: &JsonValue| value.as_str().filter(|value| !value.is_empty());
+        let address = |ip: &str, port: &str| match (string(&node_data[ip]), &node_data[port]) {
+            (Some(ip), JsonValue::Number(port)) => Some(format!("{}:{}", ip, port)),
+            _ => None,
+        };
+
+        let node = GenesisNode {
+            line,
+            alias: string(&node_data["alias"]).map(String::from),
+            dest: string(&data["dest"]).map(String::from),
+            node_address: address("node_ip", "node_port"),
+            client_address: address("client_ip", "client_port"),
+            bls_key: string(&node_data["blskey"]).is_some(),
+            steward: string(steward).map(String::from),
+            services: node_data["services"]
+                .as_array()
+                .map(|services| {
+                    services
+                        .iter()
+                        .filter_map(|service| service.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        };
+
+        let missing = [
+            ("alias", node.alias.is_none()),
+            ("dest", node.dest.is_none()),
+            ("node_ip/node_port", node.node_address.is_none()),
+            ("client_ip/client_port", node.client_address.is_none()),
+        ]
+        .iter()
+        .filter(|(_, missing)| *missing)
+        .map(|(field, _)| *field)
+        .collect::<Vec<&str>>();
+        if !missing.is_empty() {
+            problems.push(format!(
+                "Line {}: NODE transaction misses {}",
+                line,
+                missing.join(", ")
+            ));
+        }
+        if node.bls_key && string(&node_data["blskey_pop"]).is_none() {
+            problems.push(format!(
+                "Line {}: BLS key is set without proof of possession (blskey_pop)",
+                line
+            ));
+        }
+
+        node
+    }
+
+    fn check_duplicates(&mut self) {
+        let fields: [(&str, fn(&GenesisNode) -> Option<&String>); 4] = [
+            ("alias", |node| node.alias.as_ref()),
+            ("identifier (dest)", |node| node.dest.as_ref()),
+            ("node address", |node| node.node_address.as_ref()),
+            ("client address", |node| node.client_address.as_ref()),
+        ];
+
+        for (name, field) in fields {
+            let mut lines: HashMap<&String, Vec<usize>> = HashMap::new();
+            for node in self.nodes.iter() {
+                if let Some(value) = field(node) {
+                    lines.entry(value).or_default().push(node.line);
+                }
+            }
+            let mut duplicates = lines
+                .into_iter()
+                .filter(|(_, lines)| lines.len() > 1)
+                .map(|(value, lines)| {
+                    format!(
+                        "Lines {}: nodes share the same {} \"{}\"",
+                        lines
+                            .iter()
+                            .map(|line| line.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        name,
+                        value
+                    )
+                })
+                .collect::<Vec<String>>();
+            duplicates.sort();
+            self.problems.extend(duplicates);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::pool::localnet::localnet_genesis;
+
+    #[test]
+    fn inspect_works() {
+        let genesis = localnet_genesis(4, &["127.0.0.1"]).unwrap();
+        let report = GenesisReport::inspect(&genesis);
+        assert!(report.problems.is_empty());
+        assert_eq!(4, report.nodes.len());
+        assert_eq!(4, report.validators_count());
+        assert_eq!(0, report.bls_keys_count());
+        assert_eq!(Some("Node2"), report.nodes[1].alias.as_deref());
+        assert_eq!(
+            Some("127.0.0.1:9704"),
+            report.nodes[1].client_address.as_deref()
+        );
+        assert_eq!(
+            Some("Th7MpTaRZVRYnPiabds81Y"),
+            report.nodes[0].steward.as_deref()
+        );
+    }
+
+    #[test]
+    fn inspect_works_for_problems() {
+        let genesis = localnet_genesis(2, &["127.0.0.1"]).unwrap();
+        let first = genesis.lines().next().unwrap();
+        let transactions = format!(
+            "{}\n{}\n{{\"txn\": \n{}",
+            genesis,
+            first,
+            json!({"txn": {"type": "1", "data": {}}})
+        );
+
+        let report = GenesisReport::inspect(&transactions);
+        assert_eq!(3, report.nodes.len());
+        // invalid JSON, unexpected transaction type, duplicated alias, dest and addresses
+        assert_eq!(6, report.problems.len());
+        assert!(report.problems[0].starts_with("Line 4: invalid JSON"));
+        assert_eq!(
+            "Lines 1, 3: nodes share the same alias \"Node1\"",
+            report.problems[2]
+        );
+    }
+
+    #[test]
+    fn inspect_works_for_empty_file() {
+        let report = GenesisReport::inspect("\n");
+        assert_eq!(1, report.problems.len());
+    }
+}
@@ -0,0 +1,158 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    tools::did::{seed::Seed, Did},
+};
+
+use indy_utils::hash::SHA256;
+use serde_json::Value as JsonValue;
+
+/// Ports of the first node. Every next node uses the following pair of ports.
+const FIRST_NODE_PORT: u16 = 9701;
+
+/// Seed length expected by the docker pool scripts: the name is padded with leading zeros.
+const SEED_LENGTH: usize = 32;
+
+/// Node of a local docker pool started the same way as `von-network` and `indy-sdk` CI pools:
+/// keys are derived from well-known seeds `000...Node<N>` and `000...Steward<N>`.
+#[derive(Debug)]
+pub struct LocalnetNode {
+    pub alias: String,
+    pub ip: String,
+    pub node_port: u16,
+    pub client_port: u16,
+    pub dest: String,
+    pub steward_did: String,
+}
+
+impl LocalnetNode {
+    pub fn new(index: usize, ip: &str) -> CliResult<LocalnetNode> {
+        let alias = format!("Node{}", index);
+        let (_, dest) = Did::derive(&Seed::from_str(&well_known_seed(&alias))?)?;
+        let (steward_did, _) = Did::derive(&Seed::from_str(&well_known_seed(&format!(
+            "Steward{}",
+            index
+        )))?)?;
+        let node_port = FIRST_NODE_PORT + 2 * (index as u16 - 1);
+
+        Ok(LocalnetNode {
+            alias,
+            ip: ip.to_string(),
+            node_port,
+            client_port: node_port + 1,
+            dest,
+            steward_did,
+        })
+    }
+
+    /// NODE transaction in the format of pool genesis transactions (protocol version 2).
+    /// BLS keys are not included as they can't be derived from the node seed here.
+    pub fn genesis_transaction(&self, seq_no: usize) -> JsonValue {
+        json!({
+            "reqSignature": {},
+            "txn": {
+                "data": {
+                    "data": {
+                        "alias": self.alias,
+                        "client_ip": self.ip,
+                        "client_port": self.client_port,
+                        "node_ip": self.ip,
+                        "node_port": self.node_port,
+                        "services": ["VALIDATOR"]
+                    },
+                    "dest": self.dest
+                },
+                "metadata": {
+                    "from": self.steward_did
+                },
+                "type": "0"
+            },
+            "txnMetadata": {
+                "seqNo": seq_no,
+                "txnId": hex::encode(SHA256::digest(self.alias.as_bytes()))
+            },
+            "ver": "1"
+        })
+    }
+}
+
+/// Builds pool genesis transactions (one JSON object per line) of the local pool.
+/// Either a single IP shared by all nodes or an IP per node is expected.
+pub fn localnet_genesis(nodes: usize, ips: &[&str]) -> CliResult<String> {
+    if nodes == 0 || nodes > 99 {
+        return Err(CliError::InvalidInput(format!(
+            "Invalid number of nodes \"{}\". Local pool can contain from 1 to 99 nodes.",
+            nodes
+        )));
+    }
+    if ips.len() != 1 && ips.len() != nodes {
+        return Err(CliError::InvalidInput(format!(
+            "Either one IP for all nodes or {} IPs (one per node) must be specified, but {} given.",
+            nodes,
+            ips.len()
+        )));
+    }
+
+    let transactions = (1..=nodes)
+        .map(|index| {
+            let ip = if ips.len() == 1 {
+                ips[0]
+            } else {
+                ips[index - 1]
+            };
+            LocalnetNode::new(index, ip).map(|node| node.genesis_transaction(index).to_string())
+        })
+        .collect::<CliResult<Vec<String>>>()?;
+
+    Ok(transactions.join("\n"))
+}
+
+fn well_known_seed(name: &str) -> String {
+    format!("{:0>width$}", name, width = SEED_LENGTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localnet_node_works() {
+        let node = LocalnetNode::new(1, "127.0.0.1").unwrap();
+        assert_eq!("Gw6pDLhcBcoQesN72qfotTgFa7cbuqZpkX3Xo6pLhPhv", node.dest);
+        assert_eq!("Th7MpTaRZVRYnPiabds81Y", node.steward_did);
+        assert_eq!(9701, node.node_port);
+        assert_eq!(9702, node.client_port);
+
+        let txn = node.genesis_transaction(1);
+        assert_eq!(
+            "fea82e10e894419fe2bea7d96296a6d46f50f93f9eeda954ec461b2ed2950b62",
+            txn["txnMetadata"]["txnId"]
+        );
+    }
+
+    #[test]
+    fn localnet_genesis_works() {
+        let genesis =
+            localnet_genesis(4, &["10.0.0.2", "10.0.0.3", "10.0.0.4", "10.0.0.5"]).unwrap();
+        let transactions = genesis
+            .lines()
+            .map(|line| serde_json::from_str::<JsonValue>(line).unwrap())
+            .collect::<Vec<JsonValue>>();
+        assert_eq!(4, transactions.len());
+        assert_eq!("Node4", transactions[3]["txn"]["data"]["data"]["alias"]);
+        assert_eq!(
+            "10.0.0.5",
+            transactions[3]["txn"]["data"]["data"]["node_ip"]
+        );
+        assert_eq!(9707, transactions[3]["txn"]["data"]["data"]["node_port"]);
+    }
+
+    #[test]
+    fn localnet_genesis_works_for_invalid_ips() {
+        localnet_genesis(4, &["10.0.0.2", "10.0.0.3"]).unwrap_err();
+        localnet_genesis(0, &["127.0.0.1"]).unwrap_err();
+    }
+}
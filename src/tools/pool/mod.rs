@@ -4,21 +4,125 @@
 */
 use crate::{
     error::{CliError, CliResult},
-    utils::futures::block_on,
+    tools::ledger::{LedgerBackend, ProxyBackend},
+    utils::futures::block_on_cancellable_with_ticks,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use std::collections::HashMap;
 
+use indy_utils::base58;
 use indy_vdr::{
     config::PoolConfig as OpenPoolConfig,
     pool::{helpers::perform_refresh, LocalPool, Pool as PoolImpl, PoolBuilder, PoolTransactions},
 };
-use pool_config::{PoolConfig, PoolDirectory};
+use pool_config::{JournalEntry, PoolConfig, PoolDirectory, PoolTransactionsCache};
 
+pub mod auto_refresh;
+pub mod genesis;
+pub mod localnet;
 pub mod pool_config;
 
+const NODE_TXN_TYPE: &str = "0";
+
+/// How often the progress of a running catch-up is reported.
+const REFRESH_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Catch-up events reported by `Pool::refresh_with_progress`.
+pub enum RefreshProgress<'a> {
+    /// Catch-up has been started from the locally known pool transactions.
+    Started {
+        txn_count: usize,
+        nodes: usize,
+    },
+    /// Catch-up is still running.
+    Waiting {
+        elapsed: Duration,
+    },
+    Finished(&'a RefreshStats),
+}
+
+/// Summary of a completed catch-up.
+#[derive(Debug)]
+pub struct RefreshStats {
+    /// The number of new pool transactions received from the nodes.
+    pub fetched: usize,
+    /// The number of nodes replied to the ledger status request.
+    pub responded: usize,
+    pub nodes: usize,
+    /// Pool ledger size after the catch-up.
+    pub txn_count: usize,
+    /// Base58 encoded merkle tree root of the pool ledger after the catch-up.
+    pub merkle_root: String,
+    pub elapsed: Duration,
+}
+
+/// Node data merged from all NODE transactions of the pool ledger sharing the same node identifier.
+#[derive(Debug)]
+pub struct NodeInfo {
+    pub dest: String,
+    pub data: serde_json::Value,
+}
+
+impl NodeInfo {
+    pub fn alias(&self) -> Option<&str> {
+        self.data["alias"].as_str()
+    }
+
+    pub fn is_validator(&self) -> bool {
+        self.data["services"]
+            .as_array()
+            .map(|services| services.iter().any(|service| service == "VALIDATOR"))
+            .unwrap_or(false)
+    }
+}
+
+/// Ledger metadata read from the pool transactions without connecting to the pool.
+#[derive(Debug, PartialEq)]
+pub struct GenesisInfo {
+    /// Protocol version required by the format of the transactions:
+    /// 1 for legacy flat transactions, 2 for transactions wrapped into `txn` object.
+    pub protocol_version: usize,
+    pub node_txn_count: usize,
+}
+
 pub struct Pool {
     pub pool: LocalPool,
     pub name: String,
+    pub proxy: Option<ProxyBackend>,
+    /// Time to wait for the reply of every request sent to the pool (the pool config is used if it is not set).
+    pub request_timeout: Option<Duration>,
+}
+
+impl GenesisInfo {
+    pub fn parse(transactions: &str) -> CliResult<GenesisInfo> {
+        let mut protocol_version = None;
+        let mut node_txn_count = 0;
+
+        for line in transactions.lines().filter(|line| !line.trim().is_empty()) {
+            let txn = serde_json::from_str::<serde_json::Value>(line)?;
+            let (version, txn_type) = if txn["txn"].is_object() {
+                (2, &txn["txn"]["type"])
+            } else {
+                (1, &txn["type"])
+            };
+            protocol_version.get_or_insert(version);
+            if txn_type.as_str() == Some(NODE_TXN_TYPE) {
+                node_txn_count += 1;
+            }
+        }
+
+        match protocol_version {
+            Some(protocol_version) => Ok(GenesisInfo {
+                protocol_version,
+                node_txn_count,
+            }),
+            None => Err(CliError::InvalidInput(
+                "Pool transactions are empty".to_string(),
+            )),
+        }
+    }
 }
 
 impl Pool {
@@ -28,10 +132,71 @@ impl Pool {
             .map_err(CliError::from)
     }
 
+    /// Creates the pool config from genesis transactions (one JSON object per line) passed in memory.
+    pub fn create_with_transactions(name: &str, transactions: &str) -> CliResult<()> {
+        PoolDirectory::from(name)
+            .store_transactions(&mut transactions.as_bytes())
+            .map_err(CliError::from)
+    }
+
     pub fn open(
         name: &str,
         config: OpenPoolConfig,
         pre_ordered_nodes: Option<Vec<&str>>,
+        max_age: Option<u64>,
+    ) -> CliResult<Pool> {
+        Self::open_with_progress(name, config, pre_ordered_nodes, max_age, &mut |_| {})
+    }
+
+    /// Opens the pool reporting the progress of the pool ledger catch-up.
+    pub fn open_with_progress(
+        name: &str,
+        config: OpenPoolConfig,
+        pre_ordered_nodes: Option<Vec<&str>>,
+        max_age: Option<u64>,
+        on_progress: &mut dyn FnMut(RefreshProgress),
+    ) -> CliResult<Pool> {
+        let pool = Self::build(name, config, pre_ordered_nodes)?;
+
+        let cache = PoolDirectory::from(name).read_transactions_cache();
+        if let (Some(max_age), Some(cache)) = (max_age, cache) {
+            if pool.is_transactions_cache_valid(&cache, max_age) {
+                return Ok(pool);
+            }
+        }
+
+        pool.refresh_with_progress(on_progress)
+            .map_err(|_| CliError::NotFound(format!("Unable to connect to pool \"{}\"", name)))?;
+
+        Ok(pool)
+    }
+
+    /// Connects to the pool through indy-vdr-proxy instead of the pool nodes.
+    /// Genesis transactions are still used to build requests, but no catch-up is performed.
+    pub fn open_proxy(name: &str, config: OpenPoolConfig, url: &str) -> CliResult<Pool> {
+        let proxy = ProxyBackend::new(url)?;
+        let mut pool = Self::build(name, config, None)?;
+        pool.proxy = Some(proxy);
+        Ok(pool)
+    }
+
+    pub fn read_genesis_info(name: &str) -> CliResult<GenesisInfo> {
+        let content = std::fs::read_to_string(Self::genesis_txn_path(name)?)?;
+        GenesisInfo::parse(&content)
+    }
+
+    /// Path of the genesis transactions file used by the pool config.
+    pub fn genesis_txn_path(name: &str) -> CliResult<String> {
+        PoolDirectory::from(name)
+            .read_config()
+            .map(|config| config.genesis_txn)
+            .map_err(|_| CliError::NotFound(format!("Pool \"{}\" does not exist.", name)))
+    }
+
+    fn build(
+        name: &str,
+        config: OpenPoolConfig,
+        pre_ordered_nodes: Option<Vec<&str>>,
     ) -> CliResult<Pool> {
         let pool_transactions_file = PoolDirectory::from(name)
             .read_config()
@@ -52,39 +217,234 @@ impl Pool {
             .node_weights(weight_nodes)
             .into_local()?;
 
-        let pool = Pool {
+        Ok(Pool {
             pool,
             name: name.to_string(),
+            proxy: None,
+            request_timeout: None,
+        })
+    }
+
+    /// Rebuilds the pool with another reply timeout (in sec) keeping already known transactions.
+    pub fn with_reply_timeout(&self, timeout: u64) -> CliResult<Pool> {
+        let config = OpenPoolConfig {
+            reply_timeout: timeout as i64,
+            ..self.pool.get_config().to_owned()
         };
 
-        pool.refresh()
-            .map_err(|_| CliError::NotFound(format!("Unable to connect to pool \"{}\"", name)))?;
+        let pool = PoolBuilder::from(config)
+            .transactions(PoolTransactions::from(self.pool.get_merkle_tree()))?
+            .into_local()?;
 
-        Ok(pool)
+        Ok(Pool {
+            pool,
+            name: self.name.to_string(),
+            proxy: self.proxy.as_ref().map(|proxy| proxy.with_timeout(timeout)),
+            request_timeout: self.request_timeout,
+        })
+    }
+
+    /// Returns the pool sharing the same connection, which waits for the reply of every request up to `timeout` (in sec).
+    pub fn with_request_timeout(&self, timeout: u64) -> CliResult<Pool> {
+        if timeout == 0 {
+            return Err(CliError::InvalidInput(
+                "Request timeout must be greater than 0".to_string(),
+            ));
+        }
+        Ok(Pool {
+            pool: self.pool.clone(),
+            name: self.name.to_string(),
+            proxy: self.proxy.clone(),
+            request_timeout: Some(Duration::from_secs(timeout)),
+        })
+    }
+
+    pub fn backend(&self) -> &dyn LedgerBackend {
+        match self.proxy {
+            Some(ref proxy) => proxy,
+            None => &self.pool,
+        }
     }
 
     pub fn refresh(&self) -> CliResult<Option<Pool>> {
-        let (transactions, _) = block_on(async move { perform_refresh(&self.pool).await })?;
+        self.refresh_with_progress(&mut |_| {})
+    }
+
+    /// Performs catch-up of the pool ledger reporting its progress through `on_progress`.
+    /// Returns the rebuilt pool if new transactions have been received.
+    pub fn refresh_with_progress(
+        &self,
+        on_progress: &mut dyn FnMut(RefreshProgress),
+    ) -> CliResult<Option<Pool>> {
+        if self.proxy.is_some() {
+            return Err(CliError::InvalidEntityState(format!(
+                "Pool \"{}\" is connected through ledger proxy and cannot be refreshed.",
+                self.name
+            )));
+        }
+
+        let nodes = self.pool.get_node_aliases().len();
+        on_progress(RefreshProgress::Started {
+            txn_count: self.pool.get_merkle_tree().count(),
+            nodes,
+        });
+
+        let started = Instant::now();
+        let (transactions, timing) = block_on_cancellable_with_ticks(
+            async move { perform_refresh(&self.pool).await.map_err(CliError::from) },
+            REFRESH_PROGRESS_INTERVAL,
+            &mut |elapsed| on_progress(RefreshProgress::Waiting { elapsed }),
+        )?;
+
+        let fetched = transactions.as_ref().map(Vec::len).unwrap_or(0);
+        let responded = timing.as_ref().map(|timing| timing.len()).unwrap_or(0);
+        let elapsed = started.elapsed();
+        let report = |pool: &Pool, on_progress: &mut dyn FnMut(RefreshProgress)| {
+            let merkle_tree = pool.pool.get_merkle_tree();
+            on_progress(RefreshProgress::Finished(&RefreshStats {
+                fetched,
+                responded,
+                nodes,
+                txn_count: merkle_tree.count(),
+                merkle_root: base58::encode(merkle_tree.root_hash()),
+                elapsed,
+            }));
+        };
 
         match transactions {
             Some(new_transactions) if new_transactions.len() > 0 => {
-                let mut transactions = PoolTransactions::from(self.pool.get_merkle_tree());
-                transactions.extend_from_json(new_transactions)?;
+                let pool = self.with_new_transactions(&new_transactions)?;
+
+                report(&pool, on_progress);
+                Ok(Some(pool))
+            }
+            _ => {
+                self.store_transactions_cache()?;
 
-                let pool = PoolBuilder::from(self.pool.get_config().to_owned())
-                    .transactions(transactions)?
-                    .into_local()?;
+                report(self, on_progress);
+                Ok(None)
+            }
+        }
+    }
 
-                PoolDirectory::from(&self.name)
-                    .store_pool_transactions(&self.pool.get_json_transactions()?)?;
+    /// The number of the locally known pool ledger transactions.
+    pub fn txn_count(&self) -> usize {
+        self.pool.get_merkle_tree().count()
+    }
+
+    /// Rebuilds the pool with new transactions received by catch-up
+    /// and stores the extended pool ledger into the pool directory.
+    pub fn with_new_transactions(&self, new_transactions: &[String]) -> CliResult<Pool> {
+        let mut transactions = PoolTransactions::from(self.pool.get_merkle_tree());
+        transactions.extend_from_json(new_transactions)?;
 
-                Ok(Some(Pool {
-                    pool,
-                    name: self.name.to_string(),
-                }))
+        let pool = PoolBuilder::from(self.pool.get_config().to_owned())
+            .transactions(transactions)?
+            .into_local()?;
+
+        let pool = Pool {
+            pool,
+            name: self.name.to_string(),
+            proxy: None,
+            request_timeout: self.request_timeout,
+        };
+
+        PoolDirectory::from(&self.name)
+            .store_pool_transactions(&pool.pool.get_json_transactions()?)?;
+        pool.store_transactions_cache()?;
+        Ok(pool)
+    }
+
+    fn transactions_cache(&self) -> PoolTransactionsCache {
+        let merkle_tree = self.pool.get_merkle_tree();
+        PoolTransactionsCache {
+            merkle_root: hex::encode(merkle_tree.root_hash()),
+            txn_count: merkle_tree.count(),
+            timestamp: now(),
+        }
+    }
+
+    fn store_transactions_cache(&self) -> CliResult<()> {
+        PoolDirectory::from(&self.name).store_transactions_cache(&self.transactions_cache())
+    }
+
+    fn is_transactions_cache_valid(&self, cache: &PoolTransactionsCache, max_age: u64) -> bool {
+        let actual = self.transactions_cache();
+        actual.merkle_root == cache.merkle_root
+            && actual.txn_count == cache.txn_count
+            && actual.timestamp.saturating_sub(cache.timestamp) < max_age
+    }
+
+    /// Identifies the network by the first node of its pool ledger,
+    /// so pools configured under different local names still match.
+    pub fn genesis_node(&self) -> CliResult<String> {
+        let transactions = self.pool.get_json_transactions()?;
+        transactions
+            .first()
+            .and_then(|txn| serde_json::from_str::<serde_json::Value>(txn).ok())
+            .and_then(|txn| txn["txn"]["data"]["dest"].as_str().map(String::from))
+            .ok_or_else(|| {
+                CliError::InvalidEntityState(format!(
+                    "Unable to read genesis transactions of pool \"{}\"",
+                    self.name
+                ))
+            })
+    }
+
+    /// Looks up the current node data in the pool ledger transactions known to the connection.
+    pub fn find_node(&self, alias: &str) -> CliResult<Option<NodeInfo>> {
+        Ok(self
+            .nodes()?
+            .into_iter()
+            .find(|node| node.alias() == Some(alias)))
+    }
+
+    /// Returns the current data of all nodes in the order they were added to the pool ledger.
+    /// NODE transactions only contain changed fields, so all of them are merged in order.
+    pub fn nodes(&self) -> CliResult<Vec<NodeInfo>> {
+        let mut nodes: Vec<NodeInfo> = Vec::new();
+
+        for txn in self.pool.get_json_transactions()? {
+            let txn = serde_json::from_str::<serde_json::Value>(&txn)?;
+            let txn_data = &txn["txn"]["data"];
+            let (dest, data) = match (txn_data["dest"].as_str(), txn_data["data"].as_object()) {
+                (Some(dest), Some(data)) => (dest, data),
+                _ => continue,
+            };
+
+            match nodes.iter_mut().find(|node| node.dest == dest) {
+                Some(node) => {
+                    for (key, value) in data {
+                        node.data[key] = value.clone();
+                    }
+                }
+                None => nodes.push(NodeInfo {
+                    dest: dest.to_string(),
+                    data: serde_json::Value::Object(data.clone()),
+                }),
             }
-            _ => Ok(None),
         }
+
+        Ok(nodes)
+    }
+
+    /// Looks up the accepted write transaction with the given request digest in the pool journal.
+    pub fn find_journal_entry(&self, digest: &str) -> CliResult<Option<JournalEntry>> {
+        let journal = PoolDirectory::from(&self.name).read_journal()?;
+        Ok(journal
+            .entries
+            .into_iter()
+            .find(|entry| entry.digest == digest))
+    }
+
+    pub fn add_journal_entry(&self, entry: JournalEntry) -> CliResult<()> {
+        let directory = PoolDirectory::from(&self.name);
+        let mut journal = directory.read_journal()?;
+        journal
+            .entries
+            .retain(|existing| existing.digest != entry.digest);
+        journal.entries.push(entry);
+        directory.store_journal(&journal)
     }
 
     pub fn list() -> CliResult<String> {
@@ -101,3 +461,10 @@ impl Pool {
             .map_err(CliError::from)
     }
 }
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -19,18 +19,47 @@ pub struct PoolConfig {
     pub genesis_txn: String,
 }
 
+/// Describes the local copy of pool transactions stored after the last catch-up.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoolTransactionsCache {
+    pub merkle_root: String,
+    pub txn_count: usize,
+    pub timestamp: u64,
+}
+
+/// Write transactions accepted by the Ledger which were submitted with journaling enabled.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TransactionJournal {
+    pub entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub digest: String,
+    pub txn_type: String,
+    pub seq_no: u64,
+    pub txn_time: Option<u64>,
+}
+
 pub struct PoolDirectory {
     pub name: String,
 }
 
 impl PoolDirectory {
-    pub(crate) fn from(name: &str) -> Self {
+    pub fn from(name: &str) -> Self {
         PoolDirectory {
             name: name.to_string(),
         }
     }
 
-    pub(crate) fn store_config(&self, config: &PoolConfig) -> CliResult<()> {
+    pub fn store_config(&self, config: &PoolConfig) -> CliResult<()> {
+        let mut genesis_txn = File::open(&config.genesis_txn)?;
+        self.store_transactions(&mut genesis_txn)
+    }
+
+    /// Creates the pool directory with genesis transactions read from the reader.
+    pub fn store_transactions<R: Read>(&self, genesis_txn: &mut R) -> CliResult<()> {
         let mut path = self.path();
 
         if path.as_path().exists() {
@@ -47,9 +76,8 @@ impl PoolDirectory {
             path.push(&self.name);
             path.set_extension("txn");
 
-            let mut gt_fin = File::open(&config.genesis_txn)?;
             let mut gt_fout = File::create(path.as_path())?;
-            io::copy(&mut gt_fin, &mut gt_fout)?;
+            io::copy(genesis_txn, &mut gt_fout)?;
         }
         let txn_path = path.to_string_lossy().to_string();
 
@@ -70,7 +98,7 @@ impl PoolDirectory {
         Ok(())
     }
 
-    pub(crate) fn read_config(&self) -> CliResult<PoolConfig> {
+    pub fn read_config(&self) -> CliResult<PoolConfig> {
         let path = EnvironmentUtils::pool_config_path(&self.name);
 
         let mut config_json = String::new();
@@ -82,7 +110,7 @@ impl PoolDirectory {
         Ok(config)
     }
 
-    pub(crate) fn delete_config(&self) -> CliResult<()> {
+    pub fn delete_config(&self) -> CliResult<()> {
         let path = self.path();
         if !path.as_path().exists() {
             return Err(CliError::NotFound(format!(
@@ -93,7 +121,7 @@ impl PoolDirectory {
         fs::remove_dir_all(path).map_err(CliError::from)
     }
 
-    pub(crate) fn list_pools() -> CliResult<String> {
+    pub fn list_pools() -> CliResult<String> {
         let mut pools = Vec::new();
         let pool_home_path = EnvironmentUtils::pool_home_path();
 
@@ -119,13 +147,49 @@ impl PoolDirectory {
         Ok(pools)
     }
 
-    pub(crate) fn store_pool_transactions(&self, transactions: &Vec<String>) -> CliResult<()> {
+    pub fn store_pool_transactions(&self, transactions: &Vec<String>) -> CliResult<()> {
         let path = EnvironmentUtils::pool_transactions_path(&self.name);
         let mut f = File::create(path.as_path())?;
         f.write_all(transactions.join("\n").as_bytes())?;
         Ok(())
     }
 
+    pub fn store_transactions_cache(&self, cache: &PoolTransactionsCache) -> CliResult<()> {
+        let path = EnvironmentUtils::pool_transactions_cache_path(&self.name);
+        let mut f = File::create(path.as_path())?;
+        f.write_all(json!(cache).to_string().as_bytes())?;
+        f.flush()?;
+        Ok(())
+    }
+
+    pub fn read_transactions_cache(&self) -> Option<PoolTransactionsCache> {
+        let path = EnvironmentUtils::pool_transactions_cache_path(&self.name);
+        let cache_json = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&cache_json).ok()
+    }
+
+    pub fn read_journal(&self) -> CliResult<TransactionJournal> {
+        let path = EnvironmentUtils::pool_journal_path(&self.name);
+        if !path.exists() {
+            return Ok(TransactionJournal::default());
+        }
+        let journal_json = fs::read_to_string(path)?;
+        serde_json::from_str(&journal_json).map_err(|err| {
+            CliError::InvalidEntityState(format!(
+                "Transaction journal of pool \"{}\" is corrupted: {}",
+                self.name, err
+            ))
+        })
+    }
+
+    pub fn store_journal(&self, journal: &TransactionJournal) -> CliResult<()> {
+        let path = EnvironmentUtils::pool_journal_path(&self.name);
+        let mut f = File::create(path.as_path())?;
+        f.write_all(json!(journal).to_string().as_bytes())?;
+        f.flush()?;
+        Ok(())
+    }
+
     fn path(&self) -> PathBuf {
         EnvironmentUtils::pool_path(&self.name)
     }
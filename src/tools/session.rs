@@ -0,0 +1,80 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::file::{read_file, write_file},
+};
+
+/// Non-secret part of the CLI context which can be stored into a file and restored later.
+/// Wallet keys are never stored, so the wallet has to be opened again after restoring.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    pub pool: Option<String>,
+    pub wallet: Option<String>,
+    pub did: Option<String>,
+    pub protocol_version: Option<usize>,
+    pub taa: Option<SessionTaa>,
+    pub transaction: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTaa {
+    pub text: String,
+    pub version: String,
+    pub acceptance_mechanism: String,
+    pub time_of_acceptance: u64,
+}
+
+impl SessionState {
+    pub fn read(path: &str) -> CliResult<Self> {
+        let content = read_file(path).map_err(CliError::NotFound)?;
+        serde_json::from_str(&content).map_err(|err| {
+            CliError::InvalidInput(format!("Invalid session file \"{}\": {}", path, err))
+        })
+    }
+
+    pub fn write(&self, path: &str) -> CliResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_file(path, &content).map_err(CliError::InvalidEntityState)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::environment::EnvironmentUtils;
+
+    #[test]
+    fn write_read_works() {
+        let path = EnvironmentUtils::tmp_file_path("session_write_read_works.json");
+        let path = path.to_str().unwrap();
+        let state = SessionState {
+            pool: Some("pool1".to_string()),
+            did: Some("V4SGRU86Z58d6TV7PBUe6f".to_string()),
+            protocol_version: Some(2),
+            taa: Some(SessionTaa {
+                text: "text".to_string(),
+                version: "1.0".to_string(),
+                acceptance_mechanism: "on_file".to_string(),
+                time_of_acceptance: 1700000000,
+            }),
+            ..SessionState::default()
+        };
+        state.write(path).unwrap();
+        assert_eq!(state, SessionState::read(path).unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn read_fails_for_invalid_file() {
+        let path = EnvironmentUtils::tmp_file_path("session_read_fails_for_invalid_file.json");
+        let path = path.to_str().unwrap();
+        write_file(path, "[]").unwrap();
+        SessionState::read(path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -2,8 +2,12 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::error::{CliError, CliResult};
-use std::{ffi::OsStr, fs, path::PathBuf};
+use crate::{
+    error::{CliError, CliResult},
+    tools::wallet::stream_backup::is_stream_backup,
+};
+use chrono::Utc;
+use std::{ffi::OsStr, fs, fs::File, path::PathBuf};
 
 pub struct WalletBackup {
     path: PathBuf,
@@ -13,10 +17,13 @@ pub struct WalletBackup {
 pub enum BackupKind {
     Askar,
     Libindy,
+    Stream,
 }
 
 pub const DEFAULT_BACKUP_NAME: &'static str = "backup";
 
+const AUTO_BACKUP_EXTENSION: &str = "backup";
+
 impl WalletBackup {
     pub fn from_file(path: &str) -> CliResult<Self> {
         let path = PathBuf::from(path);
@@ -45,6 +52,25 @@ impl WalletBackup {
             .unwrap_or(DEFAULT_BACKUP_NAME.to_string())
     }
 
+    pub fn create_file(&self) -> CliResult<File> {
+        if self.exists() {
+            return Err(CliError::Duplicate(format!(
+                "Wallet backup \"{}\" already exists",
+                self.path.to_string_lossy()
+            )));
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::DirBuilder::new().recursive(true).create(parent)?;
+        }
+
+        File::create(&self.path).map_err(CliError::from)
+    }
+
+    pub fn open_file(&self) -> CliResult<File> {
+        File::open(&self.path).map_err(CliError::from)
+    }
+
     pub fn exists(&self) -> bool {
         self.path.exists()
     }
@@ -60,8 +86,103 @@ impl WalletBackup {
         match extension {
             // if extension of backup file is `db` consider it as Askar backup
             Some("db") => Ok(BackupKind::Askar),
+            // if the file starts with the streaming export signature consider it as a Stream backup
+            _ if is_stream_backup(self.open_file()?) => Ok(BackupKind::Stream),
             // else consider it as a Libindy backup
             _ => Ok(BackupKind::Libindy),
         }
     }
 }
+
+/// Directory of automatic backups made before destructive wallet operations.
+/// Backups are named `<wallet>-<UTC time>-<operation>.backup`, so names of a wallet backups sort by creation time.
+pub struct AutoBackupDir {
+    path: PathBuf,
+}
+
+impl AutoBackupDir {
+    pub fn new(path: &str) -> Self {
+        AutoBackupDir {
+            path: PathBuf::from(path),
+        }
+    }
+
+    pub fn backup_path(&self, wallet: &str, operation: &str) -> PathBuf {
+        self.path.join(format!(
+            "{}-{}-{}.{}",
+            wallet,
+            Utc::now().format("%Y%m%dT%H%M%S%3fZ"),
+            operation,
+            AUTO_BACKUP_EXTENSION
+        ))
+    }
+
+    /// Lists automatic backups of the wallet starting from the oldest one.
+    pub fn list(&self, wallet: &str) -> CliResult<Vec<PathBuf>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let prefix = format!("{}-", wallet);
+        let mut backups = fs::read_dir(&self.path)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension().and_then(OsStr::to_str) == Some(AUTO_BACKUP_EXTENSION)
+                    // the time after the prefix tells backups of `wallet` from backups of `wallet-1`
+                    && path
+                        .file_name()
+                        .and_then(OsStr::to_str)
+                        .and_then(|name| name.strip_prefix(&prefix))
+                        .map_or(false, |rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            })
+            .collect::<Vec<PathBuf>>();
+        backups.sort();
+        Ok(backups)
+    }
+
+    /// Removes the oldest backups of the wallet so that only `keep` latest ones remain.
+    pub fn prune(&self, wallet: &str, keep: usize) -> CliResult<Vec<PathBuf>> {
+        let backups = self.list(wallet)?;
+        let count = backups.len().saturating_sub(keep);
+        let removed = backups.into_iter().take(count).collect::<Vec<PathBuf>>();
+        for path in removed.iter() {
+            fs::remove_file(path)?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::environment::EnvironmentUtils;
+
+    #[test]
+    fn auto_backup_prune_works() {
+        let path = EnvironmentUtils::tmp_file_path("auto_backup_prune_works");
+        fs::remove_dir_all(&path).ok();
+        fs::create_dir_all(&path).unwrap();
+        let dir = AutoBackupDir::new(path.to_str().unwrap());
+
+        for name in [
+            "wallet-20230101T000000000Z-delete.backup",
+            "wallet-20230102T000000000Z-rekey.backup",
+            "wallet-20230103T000000000Z-delete.backup",
+            "wallet-1-20230101T000000000Z-delete.backup",
+            "wallet-notes.txt",
+        ] {
+            File::create(path.join(name)).unwrap();
+        }
+
+        assert_eq!(3, dir.list("wallet").unwrap().len());
+        let removed = dir.prune("wallet", 2).unwrap();
+        assert_eq!(
+            vec![path.join("wallet-20230101T000000000Z-delete.backup")],
+            removed
+        );
+        assert_eq!(2, dir.list("wallet").unwrap().len());
+        assert_eq!(1, dir.list("wallet-1").unwrap().len());
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
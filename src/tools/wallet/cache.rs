@@ -0,0 +1,211 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use aries_askar::any::AnyStore;
+use dryoc::rng::copy_randombytes;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::{
+    error::CliResult,
+    tools::wallet::{lock::WalletLock, Credentials},
+};
+
+const DIGEST_KEY_BYTES: usize = 32;
+const HMAC_BLOCK_BYTES: usize = 64;
+
+/// Askar derives the store key from the passphrase internally (the salt is kept in the store),
+/// so the derived key can't be passed to it later. To avoid repeating the Argon2 derivation
+/// when the same wallet is opened again within the session, closed stores are kept open in memory
+/// together with the wallet lock until `Wallet::close_cached` is called on exit.
+static STORES: Mutex<Option<HashMap<String, CachedStore>>> = Mutex::new(None);
+
+/// Random key of the credentials digest generated once per process,
+/// so the digest can't be matched against digests of guessed passphrases.
+static DIGEST_KEY: OnceLock<[u8; DIGEST_KEY_BYTES]> = OnceLock::new();
+
+pub(super) struct CachedStore {
+    key_digest: Vec<u8>,
+    pub(super) store: AnyStore,
+    pub(super) lock: Option<WalletLock>,
+}
+
+impl CachedStore {
+    pub(super) async fn close(self) -> CliResult<()> {
+        self.store.close().await?;
+        if let Some(lock) = self.lock {
+            lock.release()?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) struct StoreCache;
+
+impl StoreCache {
+    /// Returns the digest identifying the credentials if the store opened with them should be cached.
+    /// Only wallets protected with the expensive `argon2m` (default) key derivation are cached.
+    pub(super) fn key_digest(key: &str, key_derivation_method: Option<&str>) -> Option<Vec<u8>> {
+        match key_derivation_method {
+            None | Some("argon2m") => Some(hmac_sha256(digest_key(), key.as_bytes())),
+            _ => None,
+        }
+    }
+
+    pub(super) fn credentials_digest(credentials: &Credentials) -> Option<Vec<u8>> {
+        match credentials.rekey {
+            Some(ref rekey) => {
+                Self::key_digest(rekey, credentials.rekey_derivation_method.as_deref())
+            }
+            None => Self::key_digest(
+                &credentials.key,
+                credentials.key_derivation_method.as_deref(),
+            ),
+        }
+    }
+
+    /// Puts the store into the cache keeping the wallet lock held.
+    /// Returns the store cached under the same id before, if any.
+    pub(super) fn put(
+        id: &str,
+        key_digest: Vec<u8>,
+        store: AnyStore,
+        lock: Option<WalletLock>,
+    ) -> Option<CachedStore> {
+        STORES
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(
+                id.to_string(),
+                CachedStore {
+                    key_digest,
+                    store,
+                    lock,
+                },
+            )
+    }
+
+    /// Takes the cached store out of the cache.
+    /// Returns `Ok` with the store opened with the same key, or `Err` with the store opened with another key.
+    pub(super) fn take(id: &str, key_digest: &[u8]) -> Option<Result<CachedStore, CachedStore>> {
+        let cached = STORES.lock().unwrap().as_mut()?.remove(id)?;
+        if digests_equal(&cached.key_digest, key_digest) {
+            Some(Ok(cached))
+        } else {
+            Some(Err(cached))
+        }
+    }
+
+    pub(super) fn remove(id: &str) -> Option<CachedStore> {
+        STORES.lock().unwrap().as_mut()?.remove(id)
+    }
+
+    pub(super) fn drain() -> Vec<CachedStore> {
+        STORES
+            .lock()
+            .unwrap()
+            .take()
+            .map(|stores| stores.into_values().collect())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn contains(id: &str) -> bool {
+        STORES
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|stores| stores.contains_key(id))
+            .unwrap_or(false)
+    }
+}
+
+fn digest_key() -> &'static [u8] {
+    DIGEST_KEY.get_or_init(|| {
+        let mut key = [0u8; DIGEST_KEY_BYTES];
+        copy_randombytes(&mut key);
+        key
+    })
+}
+
+/// HMAC-SHA256 (RFC 2104) for keys not longer than the SHA256 block.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut inner_pad = [0x36u8; HMAC_BLOCK_BYTES];
+    let mut outer_pad = [0x5cu8; HMAC_BLOCK_BYTES];
+    for (index, byte) in key.iter().enumerate() {
+        inner_pad[index] ^= byte;
+        outer_pad[index] ^= byte;
+    }
+    let inner = Sha256::new()
+        .chain_update(inner_pad)
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(outer_pad)
+        .chain_update(inner)
+        .finalize()
+        .to_vec()
+}
+
+/// Compares the digests in constant time, so the comparison does not reveal how many leading bytes match.
+fn digests_equal(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right.iter())
+            .fold(0u8, |diff, (left, right)| diff | (left ^ right))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_digest_works() {
+        assert!(StoreCache::key_digest("key", None).is_some());
+        assert!(StoreCache::key_digest("key", Some("argon2m")).is_some());
+        assert!(StoreCache::key_digest("key", Some("argon2i")).is_none());
+        assert!(StoreCache::key_digest("key", Some("raw")).is_none());
+        assert_ne!(
+            StoreCache::key_digest("key", None),
+            StoreCache::key_digest("other", None)
+        );
+        assert_eq!(
+            StoreCache::key_digest("key", None),
+            StoreCache::key_digest("key", Some("argon2m"))
+        );
+    }
+
+    #[test]
+    fn key_digest_works_for_keyed_digest() {
+        assert_ne!(
+            StoreCache::key_digest("key", None).unwrap(),
+            Sha256::digest(b"key").to_vec()
+        );
+    }
+
+    #[test]
+    fn hmac_sha256_works() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn digests_equal_works() {
+        let digest = StoreCache::key_digest("key", None).unwrap();
+        assert!(digests_equal(&digest, &digest.clone()));
+        assert!(!digests_equal(
+            &digest,
+            &StoreCache::key_digest("other", None).unwrap()
+        ));
+        assert!(!digests_equal(&digest, &digest[1..]));
+    }
+}
@@ -0,0 +1,269 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::CliResult,
+    tools::{
+        did::{constants::CATEGORY_DID, key::Key, DidInfo},
+        wallet::Wallet,
+    },
+    utils::futures::block_on,
+};
+
+use aries_askar::kms::KeyAlg;
+use indy_utils::base58;
+use std::collections::HashSet;
+
+/// Problem found in the wallet by the integrity check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalletIssue {
+    /// DID record which cannot be parsed. Repaired by removing the record.
+    InvalidDidRecord { id: String },
+    /// DID verkey which is not a base58 encoded public key of the DID key type. Cannot be repaired.
+    InvalidVerkey { did: String, verkey: String },
+    /// DID without the key to sign with. Cannot be repaired.
+    MissingKey { did: String, verkey: String },
+    /// Next verkey of interrupted key rotation or import without stored key. Repaired by dropping it.
+    DanglingNextVerkey { did: String, verkey: String },
+    /// Key agreement key of DID which is not stored. Repaired by dropping it.
+    DanglingKeyAgreement { did: String, key: String },
+    /// Key created for a DID which is not used by any DID anymore.
+    /// It is only reported as it may still be needed, e.g. to restore the lost DID record.
+    OrphanedKey { name: String },
+}
+
+#[derive(Debug, Default)]
+pub struct WalletCheckReport {
+    pub dids: usize,
+    pub keys: usize,
+    pub issues: Vec<WalletIssue>,
+}
+
+impl WalletIssue {
+    pub fn subject(&self) -> &str {
+        match self {
+            WalletIssue::InvalidDidRecord { id } => id,
+            WalletIssue::InvalidVerkey { did, .. }
+            | WalletIssue::MissingKey { did, .. }
+            | WalletIssue::DanglingNextVerkey { did, .. }
+            | WalletIssue::DanglingKeyAgreement { did, .. } => did,
+            WalletIssue::OrphanedKey { name } => name,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            WalletIssue::InvalidDidRecord { .. } => "DID record cannot be parsed".to_string(),
+            WalletIssue::InvalidVerkey { verkey, .. } => format!("Invalid verkey \"{}\"", verkey),
+            WalletIssue::MissingKey { verkey, .. } => {
+                format!("Key for verkey \"{}\" does not exist", verkey)
+            }
+            WalletIssue::DanglingNextVerkey { verkey, .. } => {
+                format!("Key for next verkey \"{}\" does not exist", verkey)
+            }
+            WalletIssue::DanglingKeyAgreement { key, .. } => {
+                format!("Key agreement key \"{}\" does not exist", key)
+            }
+            WalletIssue::OrphanedKey { .. } => "DID key is not used by any DID".to_string(),
+        }
+    }
+
+    pub fn repair_action(&self) -> Option<&'static str> {
+        match self {
+            WalletIssue::InvalidDidRecord { .. } => Some("Remove DID record"),
+            WalletIssue::DanglingNextVerkey { .. } => Some("Drop next verkey"),
+            WalletIssue::DanglingKeyAgreement { .. } => Some("Drop key agreement key"),
+            WalletIssue::InvalidVerkey { .. }
+            | WalletIssue::MissingKey { .. }
+            | WalletIssue::OrphanedKey { .. } => None,
+        }
+    }
+}
+
+/// Length of the encoded public key of DID verkey: raw Ed25519 key or compressed secp256k1 point.
+fn verkey_length(alg: KeyAlg) -> usize {
+    match alg {
+        KeyAlg::K256 => 33,
+        _ => 32,
+    }
+}
+
+impl Wallet {
+    pub fn check(&self) -> CliResult<WalletCheckReport> {
+        block_on(async move {
+            let mut report = WalletCheckReport::default();
+
+            let keys = self.fetch_all_keys().await?;
+            let key_names: HashSet<String> =
+                keys.iter().map(|key| key.name().to_string()).collect();
+            report.keys = key_names.len();
+
+            let mut used_keys = HashSet::new();
+
+            for entry in self.fetch_all_records(CATEGORY_DID).await? {
+                report.dids += 1;
+
+                let did_info = match DidInfo::from_bytes(&entry.value) {
+                    Ok(did_info) => did_info,
+                    Err(_) => {
+                        report
+                            .issues
+                            .push(WalletIssue::InvalidDidRecord { id: entry.name });
+                        continue;
+                    }
+                };
+
+                used_keys.insert(did_info.verkey.clone());
+
+                let verkey_valid = match (did_info.key_alg(), base58::decode(&did_info.verkey)) {
+                    (Ok(alg), Ok(verkey)) => verkey.len() == verkey_length(alg),
+                    _ => false,
+                };
+                if !verkey_valid {
+                    report.issues.push(WalletIssue::InvalidVerkey {
+                        did: did_info.did.clone(),
+                        verkey: did_info.verkey.clone(),
+                    });
+                } else if !key_names.contains(&did_info.verkey) {
+                    report.issues.push(WalletIssue::MissingKey {
+                        did: did_info.did.clone(),
+                        verkey: did_info.verkey.clone(),
+                    });
+                }
+
+                if let Some(next_verkey) = did_info.next_verkey {
+                    if !key_names.contains(&next_verkey) {
+                        report.issues.push(WalletIssue::DanglingNextVerkey {
+                            did: did_info.did.clone(),
+                            verkey: next_verkey.clone(),
+                        });
+                    }
+                    used_keys.insert(next_verkey);
+                }
+
+                if let Some(key_agreement) = did_info.key_agreement {
+                    if !key_names.contains(&key_agreement) {
+                        report.issues.push(WalletIssue::DanglingKeyAgreement {
+                            did: did_info.did.clone(),
+                            key: key_agreement.clone(),
+                        });
+                    }
+                    used_keys.insert(key_agreement);
+                }
+            }
+
+            // only keys marked as created for a DID are expected to be used by one
+            for key in keys.iter() {
+                if Key::is_did_key(key) && !used_keys.contains(key.name()) {
+                    report.issues.push(WalletIssue::OrphanedKey {
+                        name: key.name().to_string(),
+                    });
+                }
+            }
+
+            Ok(report)
+        })
+    }
+
+    pub fn repair(&self, issue: &WalletIssue) -> CliResult<()> {
+        block_on(async move {
+            match issue {
+                WalletIssue::InvalidDidRecord { id } => self.remove_record(CATEGORY_DID, id).await,
+                WalletIssue::DanglingNextVerkey { did, .. }
+                | WalletIssue::DanglingKeyAgreement { did, .. } => {
+                    let entry = match self.fetch_record(CATEGORY_DID, did, true).await? {
+                        Some(entry) => entry,
+                        None => return Ok(()),
+                    };
+                    let mut did_info = DidInfo::from_bytes(&entry.value)?;
+                    match issue {
+                        WalletIssue::DanglingNextVerkey { .. } => did_info.next_verkey = None,
+                        _ => did_info.key_agreement = None,
+                    }
+                    self.store_record(
+                        CATEGORY_DID,
+                        &did_info.did,
+                        &did_info.to_bytes()?,
+                        Some(&entry.tags),
+                        false,
+                    )
+                    .await
+                }
+                WalletIssue::InvalidVerkey { .. }
+                | WalletIssue::MissingKey { .. }
+                | WalletIssue::OrphanedKey { .. } => Ok(()),
+            }
+        })
+    }
+
+    /// Builds DID records for the DID signing keys named after their public key which are not used by any DID,
+    /// e.g. after the DID records have been lost by a failed import. The DID is the base58 encoded
+    /// first 16 bytes of the verkey and the key metadata becomes the DID metadata.
+    /// Qualified DIDs can't be recovered, so unqualified ones are built.
+    pub fn find_lost_dids(&self) -> CliResult<Vec<DidInfo>> {
+        block_on(async move {
+            let mut used_keys = HashSet::new();
+            let mut dids = HashSet::new();
+            for entry in self.fetch_all_records(CATEGORY_DID).await? {
+                if let Ok(did_info) = DidInfo::from_bytes(&entry.value) {
+                    used_keys.insert(did_info.verkey);
+                    used_keys.extend(did_info.next_verkey);
+                    used_keys.extend(did_info.key_agreement);
+                }
+                dids.insert(entry.name);
+            }
+
+            let mut lost_dids = Vec::new();
+            for key in self.fetch_all_keys().await? {
+                // standalone keys and verkeys replaced by rotation never identify a DID
+                if !Key::is_did_key(&key) || used_keys.contains(key.name()) {
+                    continue;
+                }
+                let local_key = match key.load_local_key() {
+                    Ok(local_key) => local_key,
+                    Err(_) => continue,
+                };
+                // key agreement keys and keys of other algorithms never identify a DID
+                let verkey_type = match Key::did_key_type(local_key.algorithm()) {
+                    Ok(verkey_type) => verkey_type,
+                    Err(_) => continue,
+                };
+                let verkey_bytes = local_key.to_public_bytes()?;
+                let verkey = base58::encode(&verkey_bytes);
+                if verkey != key.name() {
+                    continue;
+                }
+
+                let did = base58::encode(&verkey_bytes[0..16]);
+                if dids.contains(&did) {
+                    continue;
+                }
+                lost_dids.push(DidInfo {
+                    did,
+                    verkey,
+                    verkey_type: verkey_type.to_string(),
+                    metadata: key.metadata().map(String::from),
+                    ..DidInfo::default()
+                });
+            }
+            lost_dids.sort_by(|a, b| a.did.cmp(&b.did));
+
+            Ok(lost_dids)
+        })
+    }
+
+    /// Stores the DID record built by `find_lost_dids` together with its search tags.
+    pub fn restore_did(&self, did_info: &DidInfo) -> CliResult<()> {
+        block_on(async move {
+            self.store_record(
+                CATEGORY_DID,
+                &did_info.did,
+                &did_info.to_bytes()?,
+                Some(&did_info.tags()),
+                true,
+            )
+            .await
+        })
+    }
+}
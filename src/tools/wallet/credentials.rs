@@ -8,6 +8,44 @@ use crate::{
 };
 
 use aries_askar::{Argon2Level, KdfMethod, PassKey, StoreKeyMethod};
+use std::collections::HashSet;
+
+/// Minimal estimated entropy (in bits) of a new wallet passphrase.
+const MIN_KEY_ENTROPY_BITS: f64 = 50.0;
+const MIN_KEY_UNIQUE_CHARS: usize = 5;
+
+impl Credentials {
+    /// Checks that a new passphrase is not too weak. Entropy is estimated from the length of the
+    /// passphrase and the classes of characters (lowercase, uppercase, digits, others) it contains.
+    pub fn check_key_strength(key: &str) -> CliResult<()> {
+        let mut pool_size = 0;
+        if key.chars().any(|ch| ch.is_ascii_lowercase()) {
+            pool_size += 26;
+        }
+        if key.chars().any(|ch| ch.is_ascii_uppercase()) {
+            pool_size += 26;
+        }
+        if key.chars().any(|ch| ch.is_ascii_digit()) {
+            pool_size += 10;
+        }
+        if key.chars().any(|ch| !ch.is_ascii_alphanumeric()) {
+            pool_size += 33;
+        }
+
+        let unique_chars = key.chars().collect::<HashSet<char>>().len();
+        let entropy = key.chars().count() as f64 * (pool_size as f64).log2();
+
+        if unique_chars < MIN_KEY_UNIQUE_CHARS || entropy < MIN_KEY_ENTROPY_BITS {
+            return Err(CliError::InvalidInput(format!(
+                "Wallet key is too weak: estimated entropy is {:.0} bits while at least {:.0} bits are required. \
+                Use a longer passphrase mixing letters, digits and symbols.",
+                entropy.max(0.0),
+                MIN_KEY_ENTROPY_BITS
+            )));
+        }
+        Ok(())
+    }
+}
 
 pub struct WalletCredentials<'a> {
     pub key: PassKey<'a>,
@@ -65,3 +103,21 @@ impl<'a> WalletCredentials<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_key_strength_works() {
+        Credentials::check_key_strength("correct-Horse-battery-7").unwrap();
+    }
+
+    #[test]
+    fn check_key_strength_works_for_weak_keys() {
+        Credentials::check_key_strength("").unwrap_err();
+        Credentials::check_key_strength("key").unwrap_err();
+        Credentials::check_key_strength("12345678").unwrap_err();
+        Credentials::check_key_strength("aaaaaaaaaaaaaaaaaaaa").unwrap_err();
+    }
+}
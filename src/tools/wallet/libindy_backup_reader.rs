@@ -15,6 +15,7 @@ use aries_askar::{
         repr::KeySecretBytes,
     },
     kms::SecretBytes,
+    EntryTag,
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use dryoc::utils::sodium_increment;
@@ -78,7 +79,7 @@ impl LibindyBackupReader {
 
         let mut record = vec![0u8; record_len];
         self.reader.read_exact(&mut record).map_err(|err| {
-            println_err!("{:?}", err);
+            error!("Unable to read backup record: {:?}", err);
             CliError::InvalidInput(
                 "Invalid backup content: Unable to read backup record1".to_string(),
             )
@@ -330,6 +331,22 @@ pub struct BackupRecord {
     pub tags: HashMap<String, String>,
 }
 
+impl BackupRecord {
+    /// Libindy marks unencrypted tags with `~` prefix of the name.
+    pub fn entry_tags(&self) -> Vec<EntryTag> {
+        let mut tags = self
+            .tags
+            .iter()
+            .map(|(name, value)| match name.strip_prefix('~') {
+                Some(name) => EntryTag::Plaintext(name.to_string(), value.to_string()),
+                None => EntryTag::Encrypted(name.to_string(), value.to_string()),
+            })
+            .collect::<Vec<EntryTag>>();
+        tags.sort_by(|a, b| a.name().cmp(b.name()));
+        tags
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DidRecord {
     pub did: String,
@@ -394,3 +411,28 @@ pub struct DidMetadataRecord {
 impl LibindyBackupRecord for DidMetadataRecord {
     const TYPE: &'static str = "Indy::DidMetadata";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_tags_works() {
+        let record = BackupRecord {
+            type_: "Indy::Credential".to_string(),
+            id: "cred1".to_string(),
+            value: "{}".to_string(),
+            tags: HashMap::from([
+                ("schema_id".to_string(), "1".to_string()),
+                ("~attr::name".to_string(), "Alice".to_string()),
+            ]),
+        };
+        assert_eq!(
+            vec![
+                EntryTag::Plaintext("attr::name".to_string(), "Alice".to_string()),
+                EntryTag::Encrypted("schema_id".to_string(), "1".to_string()),
+            ],
+            record.entry_tags()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    utils::environment::EnvironmentUtils,
+};
+
+use fs2::FileExt;
+use std::{
+    fs,
+    fs::{File, OpenOptions},
+    path::PathBuf,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+const LOCK_RETRY_INTERVAL_MS: u64 = 100;
+
+/// Advisory lock preventing several CLI processes from using the same wallet at once.
+/// The lock is released when the value is dropped.
+#[derive(Debug)]
+pub struct WalletLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl WalletLock {
+    pub fn acquire(id: &str, timeout: Option<u64>) -> CliResult<WalletLock> {
+        let path = EnvironmentUtils::wallet_lock_path(id);
+
+        if let Some(parent) = path.parent() {
+            fs::DirBuilder::new().recursive(true).create(parent)?;
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .open(&path)?;
+
+        let deadline = Instant::now() + Duration::from_secs(timeout.unwrap_or(0));
+
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => return Ok(WalletLock { file, path }),
+                Err(_) if Instant::now() < deadline => {
+                    sleep(Duration::from_millis(LOCK_RETRY_INTERVAL_MS))
+                }
+                Err(_) => {
+                    return Err(CliError::InvalidEntityState(format!(
+                        "Wallet \"{}\" is used by another process.",
+                        id
+                    )))
+                }
+            }
+        }
+    }
+
+    pub fn release(self) -> CliResult<()> {
+        self.file.unlock().map_err(CliError::from)
+    }
+
+    pub fn release_and_remove(self) -> CliResult<()> {
+        let path = self.path.clone();
+        self.release()?;
+        fs::remove_file(path).map_err(CliError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_works_for_names_with_same_stem() {
+        let first = WalletLock::acquire("lock_test.v1", None).unwrap();
+        let second = WalletLock::acquire("lock_test.v2", None).unwrap();
+        assert_ne!(first.path, second.path);
+        first.release_and_remove().unwrap();
+        second.release_and_remove().unwrap();
+    }
+
+    #[test]
+    fn acquire_fails_for_locked_wallet() {
+        let lock = WalletLock::acquire("lock_test_locked", None).unwrap();
+        match WalletLock::acquire("lock_test_locked", None) {
+            Err(CliError::InvalidEntityState(_)) => {}
+            _ => panic!("wallet lock must be held only once"),
+        }
+        lock.release_and_remove().unwrap();
+    }
+
+    #[test]
+    fn release_and_remove_works() {
+        let lock = WalletLock::acquire("lock_test_remove", None).unwrap();
+        let path = lock.path.clone();
+        assert!(path.exists());
+        lock.release_and_remove().unwrap();
+        assert!(!path.exists());
+    }
+}
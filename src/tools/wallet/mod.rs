@@ -3,19 +3,27 @@
     https://digital.gov.bc.ca/digital-trust
 */
 pub mod backup;
+mod cache;
+pub mod check;
 mod credentials;
 pub mod libindy_backup_reader;
+mod lock;
+pub mod stats;
+pub mod stream_backup;
+pub mod tags;
 mod uri;
 pub mod wallet_config;
 
 use crate::{
     error::{CliError, CliResult},
-    tools::did::constants::CATEGORY_DID,
+    tools::did::constants::{CATEGORY_DID, CATEGORY_DID_ALIAS},
     utils::futures::block_on,
 };
 
 use self::{
+    cache::StoreCache,
     credentials::WalletCredentials,
+    lock::WalletLock,
     uri::{StorageType, WalletUri},
 };
 
@@ -27,21 +35,38 @@ use crate::tools::{
         libindy_backup_reader::{
             DidMetadataRecord, DidRecord, KeyRecord, LibindyBackupReader, TemporaryDidRecord,
         },
+        stream_backup::{StreamReader, StreamRecord, StreamWriter},
     },
 };
 use aries_askar::{
     any::AnyStore,
-    kms::{KeyAlg, LocalKey},
-    Entry, EntryTag, Error as AskarError, ErrorKind as AskarErrorKind, ManageBackend,
+    kms::{KeyAlg, KeyEntry, LocalKey},
+    Entry, EntryTag, Error as AskarError, ErrorKind as AskarErrorKind, ManageBackend, TagFilter,
 };
 use backup::WalletBackup;
+use futures_channel::mpsc;
+use futures_util::{future::join, SinkExt, StreamExt};
 use serde_json::Value as JsonValue;
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{BufReader, BufWriter},
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use wallet_config::{WalletConfig, WalletDirectory};
 
 #[derive(Debug)]
 pub struct Wallet {
     pub name: String,
     pub store: AnyStore,
+    /// Askar profile the records are stored in (the default profile of the store if not set).
+    pub profile: Option<String>,
+    /// Whether the wallet key has been replaced with `rekey` when the wallet was opened.
+    pub rekeyed: bool,
+    /// Whether the wallet has been opened with `readonly=true`: all changes of the store are rejected.
+    pub read_only: bool,
+    lock: Option<WalletLock>,
+    key_digest: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -58,6 +83,8 @@ pub struct ExportConfig {
     pub path: String,
     pub key: String,
     pub key_derivation_method: Option<String>,
+    /// Export format: askar (SQLite store, used by default) or stream.
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +92,27 @@ pub struct ImportConfig {
     pub path: String,
     pub key: String,
     pub key_derivation_method: Option<String>,
+    /// Libindy backups only: keep record types and tags not known to CLI instead of dropping them.
+    #[serde(default)]
+    pub preserve_unknown: bool,
+}
+
+/// Number of records inserted within one transaction while copying stores.
+const COPY_BATCH_SIZE: usize = 1000;
+/// Number of fetched pages which may wait for the writer while copying stores.
+const COPY_CHANNEL_CAPACITY: usize = 16;
+
+enum CopyBatch {
+    Entries(Vec<Entry>),
+    Keys(Vec<KeyEntry>),
+}
+
+/// Result of the backup validation performed without importing it.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub records: BTreeMap<String, usize>,
+    pub unsupported: BTreeMap<String, usize>,
+    pub invalid: Vec<String>,
 }
 
 impl Wallet {
@@ -80,6 +128,9 @@ impl Wallet {
             let wallet_uri = WalletUri::build(config, credentials, None)?;
             let credentials = WalletCredentials::build(credentials)?;
 
+            // store kept open for a previous wallet with the same name must not be reused
+            Self::close_cached_store(&config.id).await?;
+
             config.create_path()?;
 
             let store = wallet_uri
@@ -101,43 +152,269 @@ impl Wallet {
         })
     }
 
-    pub fn open(config: &WalletConfig, credentials: &Credentials) -> CliResult<Wallet> {
+    pub fn open(
+        config: &WalletConfig,
+        credentials: &Credentials,
+        profile: Option<&str>,
+        open_timeout: Option<u64>,
+    ) -> CliResult<Wallet> {
+        let wallet = Self::open_store(config, credentials, open_timeout)?;
+
+        let profile = match profile {
+            Some(profile) => profile,
+            None => return Ok(wallet),
+        };
+
+        match block_on(wallet.has_profile(profile)) {
+            Ok(true) => Ok(Wallet {
+                profile: Some(profile.to_string()),
+                ..wallet
+            }),
+            Ok(false) => {
+                wallet.close()?;
+                Err(CliError::NotFound(format!(
+                    "Profile \"{}\" does not exist in wallet \"{}\".",
+                    profile, config.id
+                )))
+            }
+            Err(err) => {
+                wallet.close()?;
+                Err(err)
+            }
+        }
+    }
+
+    fn open_store(
+        config: &WalletConfig,
+        credentials: &Credentials,
+        open_timeout: Option<u64>,
+    ) -> CliResult<Wallet> {
         block_on(async move {
             let wallet_uri = WalletUri::build(config, credentials, None)?;
+            let open_key_digest = StoreCache::key_digest(
+                &credentials.key,
+                credentials.key_derivation_method.as_deref(),
+            );
+            let key_digest = StoreCache::credentials_digest(credentials);
             let credentials = WalletCredentials::build(credentials)?;
 
-            let mut store: AnyStore = wallet_uri
-                .value()
-                .open_backend(Some(credentials.key_method), credentials.key.as_ref(), None)
-                .await
-                .map_err(|err: AskarError| match err.kind() {
-                    AskarErrorKind::NotFound => CliError::NotFound(format!(
-                        "Wallet \"{}\" not found or unavailable.",
-                        config.id
-                    )),
-                    _ => CliError::from(err),
-                })?;
-
-            if let (Some(rekey), Some(rekey_method)) = (credentials.rekey, credentials.rekey_method)
+            // reuse the store opened with the same key before to skip the key derivation,
+            // the cached store keeps holding the wallet lock
+            let cached_store = match open_key_digest
+                .as_ref()
+                .and_then(|digest| StoreCache::take(&config.id, digest))
             {
-                store.rekey(rekey_method, rekey).await?;
-            }
+                Some(Ok(cached)) => Some((cached.store, cached.lock)),
+                Some(Err(cached)) => {
+                    cached.close().await?;
+                    None
+                }
+                None => None,
+            };
+
+            let (mut store, lock): (AnyStore, Option<WalletLock>) = match cached_store {
+                Some(cached_store) => cached_store,
+                None => {
+                    let lock = Self::lock(config, open_timeout)?;
+                    let store = wallet_uri
+                        .value()
+                        .open_backend(Some(credentials.key_method), credentials.key.as_ref(), None)
+                        .await
+                        .map_err(|err: AskarError| match err.kind() {
+                            AskarErrorKind::NotFound => CliError::NotFound(format!(
+                                "Wallet \"{}\" not found or unavailable.",
+                                config.id
+                            )),
+                            _ => CliError::from(err),
+                        })?;
+                    (store, lock)
+                }
+            };
+
+            let rekeyed = match (credentials.rekey, credentials.rekey_method) {
+                (Some(rekey), Some(rekey_method)) => {
+                    store.rekey(rekey_method, rekey).await?;
+                    true
+                }
+                _ => false,
+            };
 
             Ok(Wallet {
                 store,
                 name: config.id.to_string(),
+                profile: None,
+                rekeyed,
+                read_only: false,
+                lock,
+                key_digest,
             })
         })
     }
 
+    /// Replaces the storage credentials stored in the wallet config after checking that the storage
+    /// can be opened with the new ones. The wallet key is not changed.
+    pub fn change_storage_credentials(
+        config: &mut WalletConfig,
+        credentials: &Credentials,
+    ) -> CliResult<()> {
+        if !matches!(
+            WalletUri::map_storage_type(&config.storage_type)?,
+            StorageType::Postgres
+        ) {
+            return Err(CliError::InvalidEntityState(format!(
+                "Wallet \"{}\" uses \"{}\" storage which has no storage credentials.",
+                config.id, config.storage_type
+            )));
+        }
+        if credentials.storage_credentials.is_none() {
+            return Err(CliError::InvalidInput(
+                "No 'storage_credentials' provided for postgres store".to_string(),
+            ));
+        }
+
+        let wallet_uri = WalletUri::build(config, credentials, None)?;
+        let wallet_credentials = WalletCredentials::build(credentials)?;
+
+        // a new connection is opened instead of reusing the cached store to check the credentials
+        block_on(async move {
+            let store = wallet_uri
+                .value()
+                .open_backend(
+                    Some(wallet_credentials.key_method),
+                    wallet_credentials.key.as_ref(),
+                    None,
+                )
+                .await?;
+            store.close().await?;
+            Ok::<(), CliError>(())
+        })?;
+
+        config.storage_credentials = credentials.storage_credentials.clone();
+        config.store()
+    }
+
+    /// Switches the opened wallet into read-only mode, so records, keys and profiles cannot be changed.
+    pub fn into_read_only(self) -> Wallet {
+        Wallet {
+            read_only: true,
+            ..self
+        }
+    }
+
+    fn ensure_writable(&self) -> CliResult<()> {
+        match self.read_only {
+            true => Err(CliError::InvalidEntityState(format!(
+                "Wallet \"{}\" is opened in read-only mode. Reopen it without `readonly=true` to change it.",
+                self.name
+            ))),
+            false => Ok(()),
+        }
+    }
+
     pub fn close(self) -> CliResult<()> {
-        block_on(async move { self.store.close().await.map_err(CliError::from) })
+        block_on(async move {
+            match self.key_digest {
+                // keep the store open and locked to skip the key derivation if the wallet is opened again
+                Some(key_digest) => {
+                    if let Some(cached) =
+                        StoreCache::put(&self.name, key_digest, self.store, self.lock)
+                    {
+                        cached.close().await?;
+                    }
+                }
+                None => {
+                    self.store.close().await?;
+                    if let Some(lock) = self.lock {
+                        lock.release()?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Creates a new profile in the store. Records of different profiles are isolated from each other.
+    pub fn create_profile(&self, name: &str) -> CliResult<String> {
+        self.ensure_writable()?;
+        block_on(async move {
+            if self.has_profile(name).await? {
+                return Err(CliError::Duplicate(format!(
+                    "Profile \"{}\" already exists in wallet \"{}\".",
+                    name, self.name
+                )));
+            }
+            self.store
+                .create_profile(Some(name.to_string()))
+                .await
+                .map_err(CliError::from)
+        })
+    }
+
+    /// Returns names of all profiles of the store and the name of the default one.
+    pub fn list_profiles(&self) -> CliResult<(Vec<String>, String)> {
+        block_on(async move {
+            let profiles = self.store.list_profiles().await?;
+            let default_profile = self.store.get_default_profile().await?;
+            Ok((profiles, default_profile))
+        })
+    }
+
+    /// Returns the number of records of the opened profile: DIDs, DID aliases and keys.
+    pub fn count_records(&self) -> CliResult<BTreeMap<String, usize>> {
+        block_on(async move {
+            let mut session = self.store.session(self.profile.clone()).await?;
+            let mut counts = BTreeMap::new();
+            for category in [CATEGORY_DID, CATEGORY_DID_ALIAS] {
+                let count = session.count(category, None).await?;
+                counts.insert(category.to_string(), count as usize);
+            }
+            let keys = session
+                .fetch_all_keys(None, None, None, None, false)
+                .await?
+                .len();
+            session.commit().await?;
+
+            counts.insert("key".to_string(), keys);
+            Ok(counts)
+        })
+    }
+
+    async fn has_profile(&self, name: &str) -> CliResult<bool> {
+        let profiles = self.store.list_profiles().await?;
+        Ok(profiles.iter().any(|profile| profile == name))
+    }
+
+    /// Closes stores kept open after closing wallets. Must be called before exit.
+    pub fn close_cached() -> CliResult<()> {
+        block_on(async move {
+            for cached in StoreCache::drain() {
+                cached.close().await?;
+            }
+            Ok(())
+        })
+    }
+
+    async fn close_cached_store(id: &str) -> CliResult<()> {
+        if let Some(cached) = StoreCache::remove(id) {
+            cached.close().await?;
+        }
+        Ok(())
+    }
+
+    #[doc(hidden)]
+    pub fn is_cached(id: &str) -> bool {
+        StoreCache::contains(id)
     }
 
     pub fn delete(config: &WalletConfig, credentials: &Credentials) -> CliResult<()> {
         block_on(async move {
             let wallet_uri = WalletUri::build(config, credentials, None)?;
 
+            // the cached store holds the wallet lock, so it must be closed first
+            Self::close_cached_store(&config.id).await?;
+
+            let lock = Self::lock(config, None)?;
+
             let removed = wallet_uri.value().remove_backend().await?;
             if !removed {
                 return Err(CliError::InvalidEntityState(format!(
@@ -146,15 +423,67 @@ impl Wallet {
                 )));
             }
             WalletDirectory::from_id(&config.id).delete()?;
+
+            if let Some(lock) = lock {
+                lock.release_and_remove()?;
+            }
             Ok(())
         })
     }
 
+    // SQLite storage does not protect a wallet from being used by several processes,
+    // so we take an exclusive file lock for the time the wallet is in use.
+    fn lock(config: &WalletConfig, timeout: Option<u64>) -> CliResult<Option<WalletLock>> {
+        match WalletUri::map_storage_type(&config.storage_type)? {
+            StorageType::Sqlite => Ok(Some(WalletLock::acquire(&config.id, timeout)?)),
+            StorageType::Postgres => Ok(None),
+        }
+    }
+
     pub fn list() -> Vec<JsonValue> {
         WalletDirectory::list_wallets()
     }
 
-    pub fn export(&self, export_config: &ExportConfig) -> CliResult<()> {
+    /// Searches records of the category matching WQL tag query, e.g. `{"verkey": "..."}`.
+    /// Plaintext tags are queried with `~` name prefix.
+    pub fn search(
+        &self,
+        category: &str,
+        query: Option<&str>,
+        limit: Option<usize>,
+    ) -> CliResult<Vec<Entry>> {
+        let tag_filter = query
+            .map(|query| {
+                TagFilter::from_str(query).map_err(|err| {
+                    CliError::InvalidInput(format!("Invalid tag query \"{}\": {}", query, err))
+                })
+            })
+            .transpose()?;
+
+        block_on(self.search_records(category, tag_filter, limit))
+    }
+
+    /// `on_progress` is called with the number of records copied so far while exporting into Askar store.
+    pub fn export(
+        &self,
+        export_config: &ExportConfig,
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<()> {
+        match export_config.format.as_deref() {
+            None | Some("askar") => self.export_askar_backup(export_config, on_progress),
+            Some("stream") => self.export_stream_backup(export_config),
+            Some(format) => Err(CliError::InvalidInput(format!(
+                "Unsupported export format \"{}\" provided.",
+                format
+            ))),
+        }
+    }
+
+    fn export_askar_backup(
+        &self,
+        export_config: &ExportConfig,
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<()> {
         block_on(async move {
             let backup = WalletBackup::from_file(&export_config.path)?;
 
@@ -188,19 +517,83 @@ impl Wallet {
                 )
                 .await?;
 
-            Self::copy_records_from_askar_store(&self.store, &backup_store).await?;
+            let result = Self::copy_records_from_askar_store(
+                &self.store,
+                self.profile.clone(),
+                &backup_store,
+                on_progress,
+            )
+            .await;
 
-            backup_store.close().await?;
+            let result = result.and(backup_store.close().await.map_err(CliError::from));
 
-            Ok(())
+            // do not leave partially written export
+            if result.is_err() {
+                std::fs::remove_file(&export_config.path).ok();
+            }
+            result
         })
     }
 
+    fn export_stream_backup(&self, export_config: &ExportConfig) -> CliResult<()> {
+        block_on(async move {
+            let backup = WalletBackup::from_file(&export_config.path)?;
+            let file = backup.create_file()?;
+
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+
+            let result = self
+                .write_stream_backup(StreamWriter::init(
+                    BufWriter::new(file),
+                    &export_config.key,
+                    export_config.key_derivation_method.as_deref(),
+                    time,
+                )?)
+                .await;
+
+            // do not leave partially written export
+            if result.is_err() {
+                std::fs::remove_file(&export_config.path).ok();
+            }
+            result
+        })
+    }
+
+    async fn write_stream_backup<W: std::io::Write>(
+        &self,
+        mut writer: StreamWriter<W>,
+    ) -> CliResult<()> {
+        let mut session = self.store.session(self.profile.clone()).await?;
+
+        let did_entries = session.fetch_all(CATEGORY_DID, None, None, false).await?;
+        for entry in did_entries {
+            writer.write_record(&StreamRecord::from_entry(&entry))?;
+        }
+
+        let key_entries = session
+            .fetch_all_keys(None, None, None, None, false)
+            .await?;
+        for entry in key_entries {
+            writer.write_record(&StreamRecord::from_key_entry(&entry)?)?;
+        }
+
+        session.commit().await?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    /// `on_progress` is called with the number of records copied so far while importing Askar store.
+    /// Returns the numbers of unsupported libindy records which have been skipped.
     pub fn import(
         config: &WalletConfig,
         credentials: &Credentials,
         import_config: &ImportConfig,
-    ) -> CliResult<()> {
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<ImportReport> {
         block_on(async move {
             let backup = WalletBackup::from_file(&import_config.path)?;
             if !backup.exists() {
@@ -217,25 +610,61 @@ impl Wallet {
                 )));
             }
 
-            match backup.kind()? {
+            Self::close_cached_store(&config.id).await?;
+
+            let unsupported = match backup.kind()? {
                 BackupKind::Askar => {
-                    Self::import_askar_backup(&backup, &config, &credentials, &import_config).await
+                    Self::import_askar_backup(
+                        &backup,
+                        &config,
+                        &credentials,
+                        &import_config,
+                        on_progress,
+                    )
+                    .await?;
+                    BTreeMap::new()
                 }
                 BackupKind::Libindy => {
                     Self::import_libindy_backup(&backup, &config, &credentials, &import_config)
-                        .await
+                        .await?
                 }
+                BackupKind::Stream => {
+                    Self::import_stream_backup(&backup, &config, &credentials, &import_config)
+                        .await?;
+                    BTreeMap::new()
+                }
+            };
+
+            Ok(ImportReport {
+                unsupported,
+                ..ImportReport::default()
+            })
+        })
+    }
+
+    pub fn inspect_backup(import_config: &ImportConfig) -> CliResult<ImportReport> {
+        block_on(async move {
+            let backup = WalletBackup::from_file(&import_config.path)?;
+            if !backup.exists() {
+                return Err(CliError::NotFound(format!(
+                    "Wallet backup \"{}\" does not exist",
+                    import_config.path
+                )));
+            }
+
+            match backup.kind()? {
+                BackupKind::Askar => Self::inspect_askar_backup(&backup, &import_config).await,
+                BackupKind::Libindy => Self::inspect_libindy_backup(&import_config),
+                BackupKind::Stream => Self::inspect_stream_backup(&backup, &import_config),
             }
         })
     }
 
-    async fn import_askar_backup(
+    async fn open_askar_backup(
         backup: &WalletBackup,
-        config: &WalletConfig,
-        credentials: &Credentials,
         import_config: &ImportConfig,
-    ) -> CliResult<()> {
-        // prepare config and credentials for backup and new wallet
+    ) -> CliResult<AnyStore> {
+        // prepare config and credentials for backup
         let backup_config = WalletConfig {
             id: backup.id(),
             storage_type: StorageType::Sqlite.to_str().to_string(),
@@ -254,11 +683,7 @@ impl Wallet {
         )?;
         let backup_wallet_credentials = WalletCredentials::build(&backup_credentials)?;
 
-        let new_wallet_uri = WalletUri::build(&config, &credentials, None)?;
-        let new_wallet_credentials = WalletCredentials::build(&credentials)?;
-
-        // open backup storage
-        let backup_store: AnyStore = backup_wallet_uri
+        backup_wallet_uri
             .value()
             .open_backend(
                 Some(backup_wallet_credentials.key_method),
@@ -269,7 +694,168 @@ impl Wallet {
             .map_err(|err: AskarError| match err.kind() {
                 AskarErrorKind::NotFound => CliError::NotFound(err.to_string()),
                 _ => CliError::from(err),
-            })?;
+            })
+    }
+
+    async fn inspect_askar_backup(
+        backup: &WalletBackup,
+        import_config: &ImportConfig,
+    ) -> CliResult<ImportReport> {
+        let backup_store = Self::open_askar_backup(backup, import_config).await?;
+        let mut session = backup_store.session(None).await?;
+
+        let mut report = ImportReport::default();
+
+        let did_entries = session.fetch_all(CATEGORY_DID, None, None, false).await?;
+        for entry in did_entries {
+            match DidInfo::from_bytes(&entry.value) {
+                Ok(_) => *report.records.entry(CATEGORY_DID.to_string()).or_default() += 1,
+                Err(err) => report.invalid.push(format!(
+                    "{} record \"{}\": {}",
+                    CATEGORY_DID,
+                    entry.name,
+                    err.message(None)
+                )),
+            }
+        }
+
+        let key_entries = session
+            .fetch_all_keys(None, None, None, None, false)
+            .await?;
+        for entry in key_entries {
+            match entry.load_local_key() {
+                Ok(_) => *report.records.entry("key".to_string()).or_default() += 1,
+                Err(err) => {
+                    report
+                        .invalid
+                        .push(format!("key record \"{}\": {}", entry.name(), err))
+                }
+            }
+        }
+
+        session.commit().await?;
+        backup_store.close().await?;
+
+        Ok(report)
+    }
+
+    fn inspect_libindy_backup(import_config: &ImportConfig) -> CliResult<ImportReport> {
+        let mut backup_reader = LibindyBackupReader::init(import_config)?;
+
+        let mut report = ImportReport::default();
+        let mut dids = HashSet::new();
+
+        while let Some(record) = backup_reader.read_record()? {
+            let validation = match record.type_.as_str() {
+                KeyRecord::TYPE => KeyRecord::from_str(&record.value)
+                    .and_then(|key_record| key_record.key_bytes())
+                    .and_then(|key_bytes| {
+                        LocalKey::from_seed(KeyAlg::Ed25519, &key_bytes, None)
+                            .map_err(CliError::from)
+                    })
+                    .map(|_| ()),
+                DidRecord::TYPE => DidRecord::from_str(&record.value).map(|did_record| {
+                    dids.insert(did_record.did);
+                }),
+                TemporaryDidRecord::TYPE => {
+                    TemporaryDidRecord::from_str(&record.value).and_then(|temporary_did_record| {
+                        Self::ensure_backup_did(&dids, &temporary_did_record.did)
+                    })
+                }
+                DidMetadataRecord::TYPE => DidMetadataRecord::from_str(&record.value)
+                    .and_then(|_| Self::ensure_backup_did(&dids, &record.id)),
+                _ => {
+                    *report.unsupported.entry(record.type_.clone()).or_default() += 1;
+                    continue;
+                }
+            };
+
+            match validation {
+                Ok(()) => *report.records.entry(record.type_).or_default() += 1,
+                Err(err) => report.invalid.push(format!(
+                    "{} record \"{}\": {}",
+                    record.type_,
+                    record.id,
+                    err.message(None)
+                )),
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn inspect_stream_backup(
+        backup: &WalletBackup,
+        import_config: &ImportConfig,
+    ) -> CliResult<ImportReport> {
+        let mut backup_reader =
+            StreamReader::init(BufReader::new(backup.open_file()?), &import_config.key)?;
+
+        let mut report = ImportReport::default();
+
+        while let Some(record) = backup_reader.read_record()? {
+            match record {
+                StreamRecord::Entry {
+                    category,
+                    name,
+                    value,
+                    ..
+                } => {
+                    let validation = StreamRecord::entry_value(&value).and_then(|value| {
+                        match category.as_str() {
+                            CATEGORY_DID => DidInfo::from_bytes(&value).map(|_| ()),
+                            _ => Ok(()),
+                        }
+                    });
+                    match validation {
+                        Ok(()) => *report.records.entry(category).or_default() += 1,
+                        Err(err) => report.invalid.push(format!(
+                            "{} record \"{}\": {}",
+                            category,
+                            name,
+                            err.message(None)
+                        )),
+                    }
+                }
+                StreamRecord::Key { name, jwk, .. } => match StreamRecord::local_key(&jwk) {
+                    Ok(_) => *report.records.entry("key".to_string()).or_default() += 1,
+                    Err(err) => report.invalid.push(format!(
+                        "key record \"{}\": {}",
+                        name,
+                        err.message(None)
+                    )),
+                },
+                StreamRecord::End { .. } => {}
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn ensure_backup_did(dids: &HashSet<String>, did: &str) -> CliResult<()> {
+        if dids.contains(did) {
+            Ok(())
+        } else {
+            Err(CliError::NotFound(format!(
+                "DID {} does not exits in the backup.",
+                did
+            )))
+        }
+    }
+
+    async fn import_askar_backup(
+        backup: &WalletBackup,
+        config: &WalletConfig,
+        credentials: &Credentials,
+        import_config: &ImportConfig,
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<()> {
+        // prepare config and credentials for new wallet
+        let new_wallet_uri = WalletUri::build(&config, &credentials, None)?;
+        let new_wallet_credentials = WalletCredentials::build(&credentials)?;
+
+        // open backup storage
+        let backup_store = Self::open_askar_backup(backup, import_config).await?;
 
         // create directory for new wallet and provision it
         config.create_path()?;
@@ -285,13 +871,19 @@ impl Wallet {
             .await?;
 
         // copy all records from the backup into the new wallet
-        Self::copy_records_from_askar_store(&backup_store, &new_store).await?;
+        let result =
+            Self::copy_records_from_askar_store(&backup_store, None, &new_store, on_progress).await;
 
         // finish
-        backup_store.close().await?;
-        new_store.close().await?;
+        let result = result
+            .and(backup_store.close().await.map_err(CliError::from))
+            .and(new_store.close().await.map_err(CliError::from));
 
-        Ok(())
+        // do not leave a partially imported wallet
+        if result.is_err() {
+            WalletDirectory::from_id(&config.id).delete().ok();
+        }
+        result
     }
 
     async fn import_libindy_backup(
@@ -299,7 +891,7 @@ impl Wallet {
         config: &WalletConfig,
         credentials: &Credentials,
         import_config: &ImportConfig,
-    ) -> CliResult<()> {
+    ) -> CliResult<BTreeMap<String, usize>> {
         // prepare config and credentials for new wallet
         let new_wallet_uri = WalletUri::build(&config, &credentials, None)?;
         let new_wallet_credentials = WalletCredentials::build(&credentials)?;
@@ -321,72 +913,214 @@ impl Wallet {
             .await?;
 
         // copy all records from the backup into the new wallet
-        Self::copy_records_from_libindy_backup(&mut backup_reader, &new_store).await?;
+        let skipped = Self::copy_records_from_libindy_backup(
+            &mut backup_reader,
+            &new_store,
+            import_config.preserve_unknown,
+        )
+        .await?;
 
         // finish
         new_store.close().await?;
 
-        Ok(())
+        Ok(skipped)
     }
 
-    async fn copy_records_from_askar_store(from: &AnyStore, to: &AnyStore) -> CliResult<()> {
-        let mut from_session = from.session(None).await?;
-        let mut to_session = to.session(None).await?;
+    async fn import_stream_backup(
+        backup: &WalletBackup,
+        config: &WalletConfig,
+        credentials: &Credentials,
+        import_config: &ImportConfig,
+    ) -> CliResult<()> {
+        // prepare config and credentials for new wallet
+        let new_wallet_uri = WalletUri::build(&config, &credentials, None)?;
+        let new_wallet_credentials = WalletCredentials::build(&credentials)?;
+
+        // init stream backup reader: verifies the manifest and the export key
+        let mut backup_reader =
+            StreamReader::init(BufReader::new(backup.open_file()?), &import_config.key)?;
 
-        let did_entries = from_session
-            .fetch_all(CATEGORY_DID, None, None, false)
+        // create directory for new wallet and provision it
+        config.create_path()?;
+
+        let new_store = new_wallet_uri
+            .value()
+            .provision_backend(
+                new_wallet_credentials.key_method,
+                new_wallet_credentials.key.as_ref(),
+                None,
+                false,
+            )
             .await?;
 
-        for entry in did_entries {
-            to_session
-                .insert(
-                    &entry.category,
-                    &entry.name,
-                    &entry.value,
-                    Some(&entry.tags),
-                    None,
-                )
-                .await
-                .ok();
+        // copy all records from the backup into the new wallet
+        let result = Self::copy_records_from_stream_backup(&mut backup_reader, &new_store).await;
+
+        // finish
+        let result = result.and(new_store.close().await.map_err(CliError::from));
+
+        // integrity check or insertion failed: do not leave a partially imported wallet
+        if result.is_err() {
+            WalletDirectory::from_id(&config.id).delete().ok();
         }
+        result
+    }
 
-        let key_entries = from_session
-            .fetch_all_keys(None, None, None, None, false)
+    /// Copies DID records and keys between stores.
+    /// Records are read page by page and passed through a bounded channel to the writer,
+    /// which inserts them in transactions of `COPY_BATCH_SIZE` records, so reading and writing overlap.
+    async fn copy_records_from_askar_store(
+        from: &AnyStore,
+        from_profile: Option<String>,
+        to: &AnyStore,
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<()> {
+        let (sender, receiver) = mpsc::channel::<CopyBatch>(COPY_CHANNEL_CAPACITY);
+
+        let (read, write) = join(
+            Self::read_askar_store_batches(from, from_profile, sender),
+            Self::write_askar_store_batches(to, receiver, on_progress),
+        )
+        .await;
+
+        // the writer commits whatever it has received, so a reading failure has to be reported as well
+        read?;
+        write
+    }
+
+    async fn read_askar_store_batches(
+        from: &AnyStore,
+        from_profile: Option<String>,
+        mut sender: mpsc::Sender<CopyBatch>,
+    ) -> CliResult<()> {
+        let mut scan = from
+            .scan(
+                from_profile.clone(),
+                CATEGORY_DID.to_string(),
+                None,
+                None,
+                None,
+            )
             .await?;
 
-        for entry in key_entries {
-            to_session
-                .insert_key(
-                    entry.name(),
-                    &entry.load_local_key()?,
-                    entry.metadata(),
-                    None,
-                    None,
-                )
-                .await
-                .ok();
+        while let Some(entries) = scan.fetch_next().await? {
+            if sender.send(CopyBatch::Entries(entries)).await.is_err() {
+                // writer has stopped, its error will be reported
+                return Ok(());
+            }
         }
 
-        to_session.commit().await?;
-        from_session.commit().await?;
+        let mut session = from.session(from_profile).await?;
+        let key_entries = session
+            .fetch_all_keys(None, None, None, None, false)
+            .await?;
+        session.commit().await?;
+
+        let mut key_entries = key_entries.into_iter().peekable();
+        while key_entries.peek().is_some() {
+            let batch = key_entries.by_ref().take(COPY_BATCH_SIZE).collect();
+            if sender.send(CopyBatch::Keys(batch)).await.is_err() {
+                return Ok(());
+            }
+        }
 
         Ok(())
     }
 
+    /// Inserts the received records committing every `COPY_BATCH_SIZE` records.
+    /// The first failed insertion rolls back the pending transaction and stops the copy.
+    async fn write_askar_store_batches(
+        to: &AnyStore,
+        mut receiver: mpsc::Receiver<CopyBatch>,
+        on_progress: &dyn Fn(usize),
+    ) -> CliResult<()> {
+        let mut copied = 0;
+        let mut pending = 0;
+        let mut transaction = to.transaction(None).await?;
+
+        while let Some(batch) = receiver.next().await {
+            let inserted = async {
+                let mut count = 0;
+                match batch {
+                    CopyBatch::Entries(entries) => {
+                        for entry in entries {
+                            transaction
+                                .insert(
+                                    &entry.category,
+                                    &entry.name,
+                                    &entry.value,
+                                    Some(&entry.tags),
+                                    None,
+                                )
+                                .await?;
+                            count += 1;
+                        }
+                    }
+                    CopyBatch::Keys(key_entries) => {
+                        for entry in key_entries {
+                            transaction
+                                .insert_key(
+                                    entry.name(),
+                                    &entry.load_local_key()?,
+                                    entry.metadata(),
+                                    Some(entry.tags_as_slice()),
+                                    None,
+                                )
+                                .await?;
+                            count += 1;
+                        }
+                    }
+                }
+                Ok::<_, CliError>(count)
+            }
+            .await;
+
+            match inserted {
+                Ok(count) => pending += count,
+                Err(err) => {
+                    transaction.rollback().await.ok();
+                    return Err(err);
+                }
+            }
+
+            if pending >= COPY_BATCH_SIZE {
+                transaction.commit().await?;
+                copied += pending;
+                pending = 0;
+                on_progress(copied);
+                transaction = to.transaction(None).await?;
+            }
+        }
+
+        transaction.commit().await.map_err(CliError::from)
+    }
+
+    /// Converts libindy records into CLI records. With `preserve_unknown` the original tags are
+    /// kept on the converted records and records of unknown types are stored as is under their type.
+    /// Otherwise such records are skipped and their numbers by type are returned.
     async fn copy_records_from_libindy_backup(
         backup_reader: &mut LibindyBackupReader,
         to: &AnyStore,
-    ) -> CliResult<()> {
+        preserve_unknown: bool,
+    ) -> CliResult<BTreeMap<String, usize>> {
         let mut to_session = to.session(None).await?;
+        let mut skipped = BTreeMap::<String, usize>::new();
 
         while let Some(record) = backup_reader.read_record()? {
+            let original_tags = if preserve_unknown {
+                record.entry_tags()
+            } else {
+                Vec::new()
+            };
+
             match record.type_.as_str() {
                 KeyRecord::TYPE => {
                     let key_record = KeyRecord::from_str(&record.value)?;
                     let key = LocalKey::from_seed(KeyAlg::Ed25519, &key_record.key_bytes()?, None)?;
+                    let tags = Some(original_tags.as_slice()).filter(|tags| !tags.is_empty());
 
                     to_session
-                        .insert_key(&record.id, &key, None, None, None)
+                        .insert_key(&record.id, &key, None, tags, None)
                         .await
                         .ok();
                 }
@@ -405,7 +1139,7 @@ impl Wallet {
                             CATEGORY_DID,
                             &did_info.did,
                             &did_info.to_bytes()?,
-                            Some(&did_info.tags()),
+                            Some(&Self::merge_tags(did_info.tags(), original_tags)),
                             None,
                         )
                         .await
@@ -432,7 +1166,7 @@ impl Wallet {
                             CATEGORY_DID,
                             &did_info.did,
                             &did_info.to_bytes()?,
-                            Some(&did_entry.tags),
+                            Some(&Self::merge_tags(did_entry.tags, original_tags)),
                             None,
                         )
                         .await
@@ -459,17 +1193,86 @@ impl Wallet {
                             CATEGORY_DID,
                             &did_info.did,
                             &did_info.to_bytes()?,
-                            Some(&did_entry.tags),
+                            Some(&Self::merge_tags(did_entry.tags, original_tags)),
                             None,
                         )
                         .await
                         .ok();
                 }
-                _ => {
-                    println_warn!("Unsupported record type {}", record.type_);
-                    println_warn!("Record");
-                    println_warn!("{:?}", record);
+                _ if preserve_unknown => {
+                    to_session
+                        .insert(
+                            &record.type_,
+                            &record.id,
+                            record.value.as_bytes(),
+                            Some(&original_tags),
+                            None,
+                        )
+                        .await?;
                 }
+                _ => *skipped.entry(record.type_).or_default() += 1,
+            }
+        }
+
+        to_session.commit().await?;
+
+        Ok(skipped)
+    }
+
+    /// Adds the original tags which are not set by CLI itself.
+    fn merge_tags(mut tags: Vec<EntryTag>, original_tags: Vec<EntryTag>) -> Vec<EntryTag> {
+        for tag in original_tags {
+            if !tags.iter().any(|existing| existing.name() == tag.name()) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    async fn copy_records_from_stream_backup<R: std::io::Read>(
+        backup_reader: &mut StreamReader<R>,
+        to: &AnyStore,
+    ) -> CliResult<()> {
+        let mut to_session = to.session(None).await?;
+
+        while let Some(record) = backup_reader.read_record()? {
+            match record {
+                StreamRecord::Entry {
+                    category,
+                    name,
+                    value,
+                    tags,
+                } => {
+                    let tags = tags
+                        .iter()
+                        .map(|tag| tag.to_entry_tag())
+                        .collect::<Vec<EntryTag>>();
+                    to_session
+                        .insert(
+                            &category,
+                            &name,
+                            &StreamRecord::entry_value(&value)?,
+                            Some(&tags),
+                            None,
+                        )
+                        .await?;
+                }
+                StreamRecord::Key {
+                    name,
+                    metadata,
+                    jwk,
+                } => {
+                    to_session
+                        .insert_key(
+                            &name,
+                            &StreamRecord::local_key(&jwk)?,
+                            metadata.as_deref(),
+                            None,
+                            None,
+                        )
+                        .await?;
+                }
+                StreamRecord::End { .. } => {}
             }
         }
 
@@ -484,7 +1287,8 @@ impl Wallet {
         tags: Option<&[EntryTag]>,
         new: bool,
     ) -> CliResult<()> {
-        let mut session = self.store.session(None).await?;
+        self.ensure_writable()?;
+        let mut session = self.store.session(self.profile.clone()).await?;
         if new {
             session.insert(category, id, value, tags, None).await?
         } else {
@@ -494,20 +1298,52 @@ impl Wallet {
     }
 
     pub async fn fetch_all_records(&self, category: &str) -> CliResult<Vec<Entry>> {
-        let mut session = self.store.session(None).await?;
+        let mut session = self.store.session(self.profile.clone()).await?;
         session
             .fetch_all(category, None, None, false)
             .await
             .map_err(CliError::from)
     }
 
+    /// Searches records by tags using a store scan, so the records are fetched page by page
+    /// and loading stops as soon as `limit` records are found.
+    pub async fn search_records(
+        &self,
+        category: &str,
+        tag_filter: Option<TagFilter>,
+        limit: Option<usize>,
+    ) -> CliResult<Vec<Entry>> {
+        let mut scan = self
+            .store
+            .scan(
+                self.profile.clone(),
+                category.to_string(),
+                tag_filter,
+                None,
+                limit.map(|limit| limit as i64),
+            )
+            .await?;
+
+        let mut records = Vec::new();
+        while let Some(page) = scan.fetch_next().await? {
+            records.extend(page);
+            if limit.map(|limit| records.len() >= limit).unwrap_or(false) {
+                break;
+            }
+        }
+        if let Some(limit) = limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+
     pub async fn fetch_record(
         &self,
         category: &str,
         id: &str,
         for_update: bool,
     ) -> CliResult<Option<Entry>> {
-        let mut session = self.store.session(None).await?;
+        let mut session = self.store.session(self.profile.clone()).await?;
         session
             .fetch(category, &id, for_update)
             .await
@@ -515,7 +1351,8 @@ impl Wallet {
     }
 
     pub async fn remove_record(&self, category: &str, id: &str) -> CliResult<()> {
-        let mut session = self.store.session(None).await?;
+        self.ensure_writable()?;
+        let mut session = self.store.session(self.profile.clone()).await?;
         session.remove(category, id).await.map_err(CliError::from)?;
         session.commit().await.map_err(CliError::from)
     }
@@ -525,16 +1362,50 @@ impl Wallet {
         id: &str,
         key: &LocalKey,
         metadata: Option<&str>,
+        tags: Option<&[EntryTag]>,
     ) -> CliResult<()> {
-        let mut session = self.store.session(None).await?;
+        self.ensure_writable()?;
+        let mut session = self.store.session(self.profile.clone()).await?;
+        session
+            .insert_key(id, key, metadata, tags, None)
+            .await
+            .map_err(CliError::from)
+    }
+
+    /// Replaces the tags of the key keeping its metadata.
+    pub async fn update_key_tags(&self, id: &str, tags: &[EntryTag]) -> CliResult<()> {
+        self.ensure_writable()?;
+        let mut session = self.store.session(self.profile.clone()).await?;
+        let entry = session.fetch_key(id, true).await?.ok_or_else(|| {
+            CliError::NotFound(format!("Key {} does not exits in the wallet!", id))
+        })?;
         session
-            .insert_key(id, key, metadata, None, None)
+            .update_key(id, entry.metadata(), Some(tags), None)
             .await
             .map_err(CliError::from)
     }
 
+    pub async fn fetch_all_keys(&self) -> CliResult<Vec<KeyEntry>> {
+        let mut session = self.store.session(self.profile.clone()).await?;
+        session
+            .fetch_all_keys(None, None, None, None, false)
+            .await
+            .map_err(CliError::from)
+    }
+
+    pub async fn remove_key(&self, id: &str) -> CliResult<()> {
+        self.ensure_writable()?;
+        let mut session = self.store.session(self.profile.clone()).await?;
+        session.remove_key(id).await.map_err(CliError::from)
+    }
+
+    pub async fn fetch_key_entry(&self, id: &str) -> CliResult<Option<KeyEntry>> {
+        let mut session = self.store.session(self.profile.clone()).await?;
+        session.fetch_key(id, false).await.map_err(CliError::from)
+    }
+
     pub async fn fetch_key(&self, id: &str) -> CliResult<LocalKey> {
-        let mut session = self.store.session(None).await?;
+        let mut session = self.store.session(self.profile.clone()).await?;
         session
             .fetch_key(id, false)
             .await?
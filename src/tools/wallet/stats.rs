@@ -0,0 +1,70 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::CliResult,
+    tools::{
+        did::{constants::CATEGORY_DID, DidInfo},
+        wallet::{
+            uri::{StorageType, WalletUri},
+            wallet_config::{WalletConfig, WalletDirectory},
+            Wallet,
+        },
+    },
+    utils::futures::block_on,
+};
+
+use std::collections::BTreeMap;
+
+/// Content summary of the opened wallet profile.
+#[derive(Debug, Default)]
+pub struct WalletStats {
+    /// Number of records per category known to CLI (keys included).
+    pub records: BTreeMap<String, usize>,
+    /// Number of keys per key algorithm.
+    pub key_algorithms: BTreeMap<String, usize>,
+    /// Records of known categories which cannot be interpreted (e.g. DID records of another format).
+    pub unknown: usize,
+    /// Size of the database files in bytes. Only known for SQLite wallets.
+    pub storage_size: Option<u64>,
+}
+
+impl Wallet {
+    /// Counts records of the categories used by CLI. Askar encrypts category names,
+    /// so records stored by other applications under other categories can't be counted.
+    pub fn stats(&self) -> CliResult<WalletStats> {
+        let records = self.count_records()?;
+
+        block_on(async move {
+            let mut stats = WalletStats {
+                records,
+                ..WalletStats::default()
+            };
+
+            for key in self.fetch_all_keys().await? {
+                let algorithm = key.algorithm().unwrap_or("unknown").to_string();
+                *stats.key_algorithms.entry(algorithm).or_default() += 1;
+            }
+
+            stats.unknown = self
+                .fetch_all_records(CATEGORY_DID)
+                .await?
+                .iter()
+                .filter(|entry| DidInfo::from_bytes(&entry.value).is_err())
+                .count();
+
+            stats.storage_size = WalletConfig::read(&self.name)
+                .ok()
+                .filter(|config| {
+                    matches!(
+                        WalletUri::map_storage_type(&config.storage_type),
+                        Ok(StorageType::Sqlite)
+                    )
+                })
+                .map(|_| WalletDirectory::from_id(&self.name).storage_stats().0);
+
+            Ok(stats)
+        })
+    }
+}
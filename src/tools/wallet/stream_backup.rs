@@ -0,0 +1,507 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+
+//! Streaming wallet export format.
+//!
+//! Layout of the file:
+//! ```text
+//! MAGIC (16 bytes)
+//! manifest length (u32 LE) | manifest (JSON)
+//! SHA-256 of the manifest (32 bytes)
+//! chunk*: final flag (u8) | ciphertext length (u32 LE) | ChaCha20-Poly1305 ciphertext with tag
+//! ```
+//! Chunks are encrypted with a key derived from the export passphrase. The nonce from the manifest is
+//! incremented for every chunk and the associated data of a chunk is the manifest digest followed by the
+//! final flag, so modified, reordered or truncated chunks are detected. Decrypted chunks form a stream of
+//! records, each is a length (u32 LE) followed by the JSON of [`StreamRecord`]. The stream ends with the
+//! `end` record holding the number of records written.
+//!
+//! The format is specific to the CLI: it is not the tar based layout of askar-tools exports,
+//! so such exports cannot be imported and the CLI exports cannot be read by askar-tools.
+
+use aries_askar::{
+    crypto::{
+        alg::chacha20::{Chacha20Key, C20P},
+        encrypt::KeyAeadInPlace,
+        kdf::{
+            argon2::{Argon2, Params, PARAMS_INTERACTIVE, PARAMS_MODERATE, SALT_LENGTH},
+            KeyDerivation,
+        },
+        random::fill_random,
+        repr::KeySecretBytes,
+    },
+    kms::{KeyEntry, LocalKey, SecretBytes},
+    Entry, EntryTag,
+};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use dryoc::utils::sodium_increment;
+use indy_utils::{base58, hash::SHA256};
+use std::io::{Read, Write};
+
+use crate::error::{CliError, CliResult};
+
+pub const MAGIC: &[u8; 16] = b"INDY-CLI-STREAM\0";
+pub const FORMAT_VERSION: u32 = 1;
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+const NONCE_BYTES: usize = 12;
+const KEY_BYTES: usize = 32;
+const HASH_BYTES: usize = 32;
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamManifest {
+    pub version: u32,
+    /// Export key derivation method: argon2m, argon2i or raw.
+    pub key_derivation_method: String,
+    pub salt: String,
+    pub nonce: String,
+    pub chunk_size: usize,
+    /// Export time in seconds from UNIX Epoch
+    pub time: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StreamRecord {
+    Entry {
+        category: String,
+        name: String,
+        /// Hex encoded value
+        value: String,
+        tags: Vec<StreamTag>,
+    },
+    Key {
+        name: String,
+        metadata: Option<String>,
+        /// Secret JWK of the key
+        jwk: String,
+    },
+    End {
+        records: usize,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct StreamTag {
+    pub name: String,
+    pub value: String,
+    pub plaintext: bool,
+}
+
+impl StreamRecord {
+    pub fn from_entry(entry: &Entry) -> Self {
+        StreamRecord::Entry {
+            category: entry.category.to_string(),
+            name: entry.name.to_string(),
+            value: hex::encode(&entry.value),
+            tags: entry.tags.iter().map(StreamTag::from_entry_tag).collect(),
+        }
+    }
+
+    pub fn from_key_entry(entry: &KeyEntry) -> CliResult<Self> {
+        let jwk = entry.load_local_key()?.to_jwk_secret()?;
+        let jwk = std::str::from_utf8(jwk.as_ref())
+            .map_err(|_| CliError::InvalidEntityState("Unable to export key".to_string()))?;
+        Ok(StreamRecord::Key {
+            name: entry.name().to_string(),
+            metadata: entry.metadata().map(String::from),
+            jwk: jwk.to_string(),
+        })
+    }
+
+    pub fn entry_value(value: &str) -> CliResult<Vec<u8>> {
+        hex::decode(value).map_err(|_| invalid_content("Unable to decode record value"))
+    }
+
+    pub fn local_key(jwk: &str) -> CliResult<LocalKey> {
+        LocalKey::from_jwk(jwk).map_err(CliError::from)
+    }
+}
+
+impl StreamTag {
+    fn from_entry_tag(tag: &EntryTag) -> Self {
+        match tag {
+            EntryTag::Encrypted(name, value) => StreamTag {
+                name: name.to_string(),
+                value: value.to_string(),
+                plaintext: false,
+            },
+            EntryTag::Plaintext(name, value) => StreamTag {
+                name: name.to_string(),
+                value: value.to_string(),
+                plaintext: true,
+            },
+        }
+    }
+
+    pub fn to_entry_tag(&self) -> EntryTag {
+        if self.plaintext {
+            EntryTag::Plaintext(self.name.to_string(), self.value.to_string())
+        } else {
+            EntryTag::Encrypted(self.name.to_string(), self.value.to_string())
+        }
+    }
+}
+
+pub struct StreamWriter<W: Write> {
+    inner: W,
+    key: Chacha20Key<C20P>,
+    nonce: Vec<u8>,
+    aad: Vec<u8>,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    records: usize,
+}
+
+impl<W: Write> StreamWriter<W> {
+    pub fn init(
+        mut inner: W,
+        passphrase: &str,
+        key_derivation_method: Option<&str>,
+        time: u64,
+    ) -> CliResult<Self> {
+        let key_derivation_method = key_derivation_method.unwrap_or("argon2m");
+
+        let mut salt = vec![0u8; SALT_LENGTH];
+        fill_random(&mut salt);
+        let mut nonce = vec![0u8; NONCE_BYTES];
+        fill_random(&mut nonce);
+
+        let key = derive_key(passphrase, key_derivation_method, &salt)?;
+
+        let manifest = StreamManifest {
+            version: FORMAT_VERSION,
+            key_derivation_method: key_derivation_method.to_string(),
+            salt: hex::encode(&salt),
+            nonce: hex::encode(&nonce),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            time,
+        };
+        let manifest_bytes = serde_json::to_vec(&manifest)?;
+        let manifest_hash = SHA256::digest(&manifest_bytes);
+
+        inner.write_all(MAGIC)?;
+        inner.write_u32::<LittleEndian>(manifest_bytes.len() as u32)?;
+        inner.write_all(&manifest_bytes)?;
+        inner.write_all(&manifest_hash)?;
+
+        Ok(StreamWriter {
+            inner,
+            key,
+            nonce,
+            aad: manifest_hash,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            buffer: Vec::new(),
+            records: 0,
+        })
+    }
+
+    pub fn write_record(&mut self, record: &StreamRecord) -> CliResult<()> {
+        let record = serde_json::to_vec(record)?;
+        self.buffer.write_u32::<LittleEndian>(record.len() as u32)?;
+        self.buffer.extend_from_slice(&record);
+        self.records += 1;
+
+        while self.buffer.len() >= self.chunk_size {
+            let rest = self.buffer.split_off(self.chunk_size);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            self.write_chunk(chunk, false)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `end` record and the final chunk. Returns the number of written records.
+    pub fn finish(mut self) -> CliResult<usize> {
+        let records = self.records;
+        self.write_record(&StreamRecord::End { records })?;
+        let chunk = std::mem::take(&mut self.buffer);
+        self.write_chunk(chunk, true)?;
+        self.inner.flush()?;
+        Ok(records)
+    }
+
+    fn write_chunk(&mut self, chunk: Vec<u8>, is_final: bool) -> CliResult<()> {
+        let mut buffer = SecretBytes::from(chunk);
+        self.key
+            .encrypt_in_place(&mut buffer, &self.nonce, &chunk_aad(&self.aad, is_final))
+            .map_err(|_| {
+                CliError::InvalidEntityState("Unable to encrypt export data".to_string())
+            })?;
+        sodium_increment(&mut self.nonce);
+
+        self.inner.write_u8(is_final as u8)?;
+        self.inner.write_u32::<LittleEndian>(buffer.len() as u32)?;
+        self.inner.write_all(buffer.as_ref())?;
+        Ok(())
+    }
+}
+
+pub struct StreamReader<R: Read> {
+    inner: R,
+    key: Chacha20Key<C20P>,
+    nonce: Vec<u8>,
+    aad: Vec<u8>,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    is_final_read: bool,
+    records: usize,
+    pub manifest: StreamManifest,
+}
+
+impl<R: Read> StreamReader<R> {
+    pub fn init(mut inner: R, passphrase: &str) -> CliResult<Self> {
+        let mut magic = [0u8; 16];
+        inner
+            .read_exact(&mut magic)
+            .map_err(|_| invalid_content("Unable to read the header"))?;
+        if &magic != MAGIC {
+            return Err(invalid_content("Unknown format"));
+        }
+
+        let manifest_len = inner
+            .read_u32::<LittleEndian>()
+            .map_err(|_| invalid_content("Unable to read the manifest"))?
+            as usize;
+        if manifest_len > MAX_CHUNK_SIZE {
+            return Err(invalid_content("Unable to read the manifest"));
+        }
+        let mut manifest_bytes = vec![0u8; manifest_len];
+        inner
+            .read_exact(&mut manifest_bytes)
+            .map_err(|_| invalid_content("Unable to read the manifest"))?;
+
+        let mut manifest_hash = vec![0u8; HASH_BYTES];
+        inner
+            .read_exact(&mut manifest_hash)
+            .map_err(|_| invalid_content("Unable to read the manifest digest"))?;
+        if SHA256::digest(&manifest_bytes) != manifest_hash {
+            return Err(invalid_content("Manifest digest mismatch"));
+        }
+
+        let manifest: StreamManifest = serde_json::from_slice(&manifest_bytes)
+            .map_err(|_| invalid_content("Unable to parse the manifest"))?;
+        if manifest.version != FORMAT_VERSION {
+            return Err(invalid_content("Unsupported version"));
+        }
+        if manifest.chunk_size == 0 || manifest.chunk_size > MAX_CHUNK_SIZE {
+            return Err(invalid_content("Invalid chunk size"));
+        }
+
+        let salt =
+            hex::decode(&manifest.salt).map_err(|_| invalid_content("Unable to decode salt"))?;
+        let nonce =
+            hex::decode(&manifest.nonce).map_err(|_| invalid_content("Unable to decode nonce"))?;
+        if nonce.len() != NONCE_BYTES {
+            return Err(invalid_content("Invalid nonce"));
+        }
+        let key = derive_key(passphrase, &manifest.key_derivation_method, &salt)?;
+
+        Ok(StreamReader {
+            inner,
+            key,
+            nonce,
+            aad: manifest_hash,
+            chunk_size: manifest.chunk_size,
+            buffer: Vec::new(),
+            is_final_read: false,
+            records: 0,
+            manifest,
+        })
+    }
+
+    /// Returns the next record or `None` once the `end` record is reached.
+    pub fn read_record(&mut self) -> CliResult<Option<StreamRecord>> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize;
+        let record: StreamRecord = serde_json::from_slice(&self.take(len)?)
+            .map_err(|_| invalid_content("Unable to parse record"))?;
+
+        match record {
+            StreamRecord::End { records } => {
+                if records != self.records {
+                    return Err(invalid_content("Number of records mismatch"));
+                }
+                Ok(None)
+            }
+            record => {
+                self.records += 1;
+                Ok(Some(record))
+            }
+        }
+    }
+
+    fn take(&mut self, len: usize) -> CliResult<Vec<u8>> {
+        while self.buffer.len() < len {
+            self.read_chunk()?;
+        }
+        let rest = self.buffer.split_off(len);
+        Ok(std::mem::replace(&mut self.buffer, rest))
+    }
+
+    fn read_chunk(&mut self) -> CliResult<()> {
+        if self.is_final_read {
+            return Err(invalid_content("Unexpected end of records"));
+        }
+
+        let is_final = self
+            .inner
+            .read_u8()
+            .map_err(|_| invalid_content("Export is truncated"))?
+            == 1;
+        let len = self
+            .inner
+            .read_u32::<LittleEndian>()
+            .map_err(|_| invalid_content("Export is truncated"))? as usize;
+        if len > self.chunk_size + 16 {
+            return Err(invalid_content("Invalid chunk size"));
+        }
+
+        let mut chunk = vec![0u8; len];
+        self.inner
+            .read_exact(&mut chunk)
+            .map_err(|_| invalid_content("Export is truncated"))?;
+
+        let mut chunk = SecretBytes::from(chunk);
+        self.key
+            .decrypt_in_place(&mut chunk, &self.nonce, &chunk_aad(&self.aad, is_final))
+            .map_err(|_| {
+                CliError::InvalidInput(
+                    "Unable to decrypt the export: the key is wrong or the content is modified"
+                        .to_string(),
+                )
+            })?;
+        sodium_increment(&mut self.nonce);
+
+        self.is_final_read = is_final;
+        self.buffer.extend_from_slice(chunk.as_ref());
+        Ok(())
+    }
+}
+
+/// Checks whether the file content starts with the streaming export signature.
+pub fn is_stream_backup<R: Read>(mut reader: R) -> bool {
+    let mut magic = [0u8; 16];
+    reader.read_exact(&mut magic).is_ok() && &magic == MAGIC
+}
+
+fn chunk_aad(manifest_hash: &[u8], is_final: bool) -> Vec<u8> {
+    let mut aad = manifest_hash.to_vec();
+    aad.push(is_final as u8);
+    aad
+}
+
+fn derive_key(
+    passphrase: &str,
+    key_derivation_method: &str,
+    salt: &[u8],
+) -> CliResult<Chacha20Key<C20P>> {
+    let key = match key_derivation_method {
+        "argon2m" => derive_argon2_key(passphrase, salt, PARAMS_MODERATE)?,
+        "argon2i" => derive_argon2_key(passphrase, salt, PARAMS_INTERACTIVE)?,
+        "raw" => base58::decode(passphrase)
+            .ok()
+            .filter(|key| key.len() == KEY_BYTES)
+            .ok_or_else(|| CliError::InvalidInput("Invalid raw export key provided".to_string()))?,
+        value => {
+            return Err(CliError::InvalidInput(format!(
+                "Unsupported key derivation method \"{}\" provided for the export.",
+                value
+            )))
+        }
+    };
+    Chacha20Key::from_secret_bytes(&key)
+        .map_err(|_| CliError::InvalidInput("Unable to derive export key".to_string()))
+}
+
+fn derive_argon2_key(passphrase: &str, salt: &[u8], params: Params) -> CliResult<Vec<u8>> {
+    let mut key = [0u8; KEY_BYTES];
+    Argon2::new(passphrase.as_bytes(), salt, params)
+        .and_then(|mut argon2| argon2.derive_key_bytes(&mut key))
+        .map_err(|_| CliError::InvalidInput("Unable to derive export key".to_string()))?;
+    Ok(key.to_vec())
+}
+
+fn invalid_content(reason: &str) -> CliError {
+    CliError::InvalidInput(format!("Invalid export content: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "export_key";
+
+    fn entry(name: &str, value: &[u8]) -> StreamRecord {
+        StreamRecord::Entry {
+            category: "did".to_string(),
+            name: name.to_string(),
+            value: hex::encode(value),
+            tags: vec![StreamTag {
+                name: "verkey".to_string(),
+                value: "verkey".to_string(),
+                plaintext: false,
+            }],
+        }
+    }
+
+    fn export(records: &[StreamRecord]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut writer = StreamWriter::init(&mut data, KEY, Some("argon2i"), 0).unwrap();
+        for record in records {
+            writer.write_record(record).unwrap();
+        }
+        assert_eq!(records.len(), writer.finish().unwrap());
+        data
+    }
+
+    fn import(data: &[u8], key: &str) -> CliResult<Vec<StreamRecord>> {
+        let mut reader = StreamReader::init(data, key)?;
+        let mut records = Vec::new();
+        while let Some(record) = reader.read_record()? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    #[test]
+    fn export_import_works() {
+        let records = vec![entry("did1", b"value1"), entry("did2", b"value2")];
+        let data = export(&records);
+        assert!(is_stream_backup(data.as_slice()));
+        assert_eq!(records, import(&data, KEY).unwrap());
+    }
+
+    #[test]
+    fn export_import_works_for_several_chunks() {
+        let value = vec![7u8; DEFAULT_CHUNK_SIZE];
+        let records = (0..5)
+            .map(|i| entry(&format!("did{}", i), &value))
+            .collect::<Vec<_>>();
+        let data = export(&records);
+        assert_eq!(records, import(&data, KEY).unwrap());
+    }
+
+    #[test]
+    fn import_fails_for_wrong_key() {
+        let data = export(&[entry("did1", b"value1")]);
+        import(&data, "other_key").unwrap_err();
+    }
+
+    #[test]
+    fn import_fails_for_modified_content() {
+        let mut data = export(&[entry("did1", b"value1")]);
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        import(&data, KEY).unwrap_err();
+    }
+
+    #[test]
+    fn import_fails_for_truncated_content() {
+        let value = vec![7u8; DEFAULT_CHUNK_SIZE];
+        let data = export(&[entry("did1", &value), entry("did2", &value)]);
+        import(&data[..data.len() / 2], KEY).unwrap_err();
+    }
+}
@@ -0,0 +1,96 @@
+/*
+    Copyright © 2023 Province of British Columbia
+    https://digital.gov.bc.ca/digital-trust
+*/
+use crate::{
+    error::{CliError, CliResult},
+    tools::wallet::Wallet,
+    utils::futures::block_on,
+};
+
+use aries_askar::EntryTag;
+
+impl Wallet {
+    /// Adds the tag to the record. A tag of the same name and kind (encrypted or plaintext) is replaced.
+    /// Returns the resulting tags of the record.
+    pub fn add_record_tag(
+        &self,
+        category: &str,
+        id: &str,
+        name: &str,
+        value: &str,
+        encrypted: bool,
+    ) -> CliResult<Vec<EntryTag>> {
+        self.update_record_tags(category, id, |tags| {
+            tags.retain(|tag| !is_tag(tag, name, None, encrypted));
+            tags.push(match encrypted {
+                true => EntryTag::Encrypted(name.to_string(), value.to_string()),
+                false => EntryTag::Plaintext(name.to_string(), value.to_string()),
+            });
+            Ok(())
+        })
+    }
+
+    /// Removes the tag of the given name and kind from the record.
+    /// If the value is passed, the tag is removed only when it holds this value.
+    /// Returns the resulting tags of the record.
+    pub fn remove_record_tag(
+        &self,
+        category: &str,
+        id: &str,
+        name: &str,
+        value: Option<&str>,
+        encrypted: bool,
+    ) -> CliResult<Vec<EntryTag>> {
+        self.update_record_tags(category, id, |tags| {
+            let count = tags.len();
+            tags.retain(|tag| !is_tag(tag, name, value, encrypted));
+            if tags.len() == count {
+                return Err(CliError::NotFound(format!(
+                    "Record \"{}\" of \"{}\" category has no such \"{}\" tag",
+                    id, category, name
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    // Fetches the record and replaces it with the same value and the updated tags.
+    fn update_record_tags(
+        &self,
+        category: &str,
+        id: &str,
+        update: impl FnOnce(&mut Vec<EntryTag>) -> CliResult<()>,
+    ) -> CliResult<Vec<EntryTag>> {
+        block_on(async move {
+            let entry = self
+                .fetch_record(category, id, true)
+                .await?
+                .ok_or_else(|| {
+                    CliError::NotFound(format!(
+                        "Record \"{}\" of \"{}\" category does not exist in the wallet",
+                        id, category
+                    ))
+                })?;
+
+            let mut tags = entry.tags;
+            update(&mut tags)?;
+
+            self.store_record(category, id, &entry.value, Some(&tags), false)
+                .await?;
+            Ok(tags)
+        })
+    }
+}
+
+fn is_tag(tag: &EntryTag, name: &str, value: Option<&str>, encrypted: bool) -> bool {
+    let (tag_name, tag_value, tag_encrypted) = match tag {
+        EntryTag::Encrypted(name, value) => (name, value, true),
+        EntryTag::Plaintext(name, value) => (name, value, false),
+    };
+    let same_value = match value {
+        Some(value) => tag_value == value,
+        None => true,
+    };
+    tag_name == name && tag_encrypted == encrypted && same_value
+}
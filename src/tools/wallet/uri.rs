@@ -89,13 +89,13 @@ impl WalletUri {
             .ok_or(CliError::InvalidInput(
                 "No 'storage_config' provided for postgres store".to_string(),
             ))?;
-        let storage_credentials =
-            credentials
-                .storage_credentials
-                .as_ref()
-                .ok_or(CliError::InvalidInput(
-                    "No 'storage_credentials' provided for postgres store".to_string(),
-                ))?;
+        let storage_credentials = credentials
+            .storage_credentials
+            .as_ref()
+            .or(config.storage_credentials.as_ref())
+            .ok_or(CliError::InvalidInput(
+                "No 'storage_credentials' provided for postgres store".to_string(),
+            ))?;
 
         let config_url = storage_config["url"]
             .as_str()
@@ -146,7 +146,7 @@ impl WalletUri {
         Ok(uri)
     }
 
-    fn map_storage_type(storage_type: &str) -> CliResult<StorageType> {
+    pub fn map_storage_type(storage_type: &str) -> CliResult<StorageType> {
         match storage_type {
             "default" | "sqlite" | "sqlite_storage" => Ok(StorageType::Sqlite),
             "postgres" | "postgres_storage" => Ok(StorageType::Postgres),
@@ -2,7 +2,10 @@
     Copyright © 2023 Province of British Columbia
     https://digital.gov.bc.ca/digital-trust
 */
-use crate::utils::environment::EnvironmentUtils;
+use crate::{
+    tools::wallet::uri::{StorageType, WalletUri},
+    utils::{environment::EnvironmentUtils, time::timestamp_to_datetime},
+};
 
 use crate::error::{CliError, CliResult};
 use serde_json::Value as JsonValue;
@@ -11,6 +14,7 @@ use std::{
     fs::File,
     io::{Read, Write},
     path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -18,10 +22,16 @@ pub struct WalletConfig {
     pub id: String,
     pub storage_type: String,
     pub storage_config: Option<JsonValue>,
+    /// Storage credentials used when they are not passed on wallet opening.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_credentials: Option<JsonValue>,
+    /// DID activated with `did use` the last time. It is activated again when the wallet is opened.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_did: Option<String>,
 }
 
 impl WalletConfig {
-    pub(crate) fn store(&self) -> CliResult<()> {
+    pub fn store(&self) -> CliResult<()> {
         Self::create_wallets_directory()?;
 
         let mut config_file = File::create(&self.path())?;
@@ -31,7 +41,7 @@ impl WalletConfig {
         Ok(())
     }
 
-    pub(crate) fn read(id: &str) -> CliResult<Self> {
+    pub fn read(id: &str) -> CliResult<Self> {
         let path = EnvironmentUtils::wallet_config_path(id);
 
         let mut config_json = String::new();
@@ -41,15 +51,25 @@ impl WalletConfig {
         serde_json::from_str(&config_json).map_err(CliError::from)
     }
 
-    pub(crate) fn delete(&self) -> CliResult<()> {
+    /// Remembers the active DID of the wallet (or forgets it if `None` is passed).
+    pub fn store_active_did(id: &str, did: Option<&str>) -> CliResult<()> {
+        let mut config = Self::read(id)?;
+        if config.active_did.as_deref() == did {
+            return Ok(());
+        }
+        config.active_did = did.map(String::from);
+        config.store()
+    }
+
+    pub fn delete(&self) -> CliResult<()> {
         fs::remove_file(&self.path()).map_err(CliError::from)
     }
 
-    pub(crate) fn exists(&self) -> bool {
+    pub fn exists(&self) -> bool {
         self.path().exists()
     }
 
-    pub(crate) fn create_path(&self) -> CliResult<()> {
+    pub fn create_path(&self) -> CliResult<()> {
         WalletDirectory::from_id(&self.id).create()
     }
 
@@ -57,7 +77,7 @@ impl WalletConfig {
         EnvironmentUtils::wallet_config_path(&self.id)
     }
 
-    pub(crate) fn create_wallets_directory() -> CliResult<()> {
+    pub fn create_wallets_directory() -> CliResult<()> {
         fs::DirBuilder::new()
             .recursive(true)
             .create(EnvironmentUtils::wallets_path())
@@ -71,7 +91,7 @@ pub struct WalletDirectory {
 }
 
 impl WalletDirectory {
-    pub(crate) fn from_id(id: &str) -> WalletDirectory {
+    pub fn from_id(id: &str) -> WalletDirectory {
         let path = EnvironmentUtils::wallet_path(id);
         WalletDirectory {
             id: id.to_string(),
@@ -79,14 +99,14 @@ impl WalletDirectory {
         }
     }
 
-    pub(crate) fn create(&self) -> CliResult<()> {
+    pub fn create(&self) -> CliResult<()> {
         fs::DirBuilder::new()
             .recursive(true)
             .create(&self.path)
             .map_err(CliError::from)
     }
 
-    pub(crate) fn delete(&self) -> CliResult<()> {
+    pub fn delete(&self) -> CliResult<()> {
         if !self.path.exists() {
             return Err(CliError::NotFound(format!(
                 "Wallet \"{}\" does not exist",
@@ -96,7 +116,7 @@ impl WalletDirectory {
         fs::remove_dir_all(self.path.as_path()).map_err(CliError::from)
     }
 
-    pub(crate) fn list_wallets() -> Vec<JsonValue> {
+    pub fn list_wallets() -> Vec<JsonValue> {
         let mut configs: Vec<JsonValue> = Vec::new();
 
         if let Ok(entries) = fs::read_dir(EnvironmentUtils::wallets_path()) {
@@ -113,10 +133,103 @@ impl WalletDirectory {
                     .ok()
                     .and_then(|mut f| f.read_to_string(&mut config_json).ok())
                     .and_then(|_| serde_json::from_str::<JsonValue>(config_json.as_str()).ok())
-                    .map(|config| configs.push(config));
+                    .map(|config| configs.push(Self::with_storage_info(config)));
             }
         }
 
         configs
     }
+
+    /// Adds storage location, size and timestamps to the wallet config to show them in the list.
+    fn with_storage_info(mut config: JsonValue) -> JsonValue {
+        let id = config["id"].as_str().unwrap_or_default().to_string();
+        let storage_type = config["storage_type"].as_str().unwrap_or_default();
+
+        match WalletUri::map_storage_type(storage_type) {
+            Ok(StorageType::Sqlite) => {
+                let directory = WalletDirectory::from_id(&id);
+                let (size, created, modified) = directory.storage_stats();
+
+                let mut db_path = directory.path.clone();
+                db_path.push(&id);
+                db_path.set_extension("db");
+
+                config["location"] = json!(fs::canonicalize(&db_path)
+                    .unwrap_or(db_path)
+                    .to_string_lossy());
+                config["size"] = json!(format_size(size));
+                config["created"] = json!(created.map(timestamp_to_datetime));
+                config["modified"] = json!(modified.map(timestamp_to_datetime));
+            }
+            Ok(StorageType::Postgres) => {
+                config["location"] = config["storage_config"]["url"].clone();
+            }
+            Err(_) => {}
+        }
+
+        config
+    }
+
+    /// Returns total size of the wallet files with the earliest creation and the latest modification times.
+    pub fn storage_stats(&self) -> (u64, Option<i64>, Option<i64>) {
+        let mut size = 0;
+        let mut created: Option<i64> = None;
+        let mut modified: Option<i64> = None;
+
+        let entries = match fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(_) => return (size, created, modified),
+        };
+
+        for metadata in entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|metadata| metadata.is_file())
+        {
+            size += metadata.len();
+            if let Some(time) = metadata.created().ok().and_then(to_timestamp) {
+                created = Some(created.map_or(time, |created| created.min(time)));
+            }
+            if let Some(time) = metadata.modified().ok().and_then(to_timestamp) {
+                modified = Some(modified.map_or(time, |modified| modified.max(time)));
+            }
+        }
+
+        (size, created, modified)
+    }
+}
+
+fn to_timestamp(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs() as i64)
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_size_works() {
+        assert_eq!("512 B", format_size(512));
+        assert_eq!("1.5 KB", format_size(1536));
+        assert_eq!("2.0 MB", format_size(2 * 1024 * 1024));
+    }
 }
@@ -1,7 +1,4 @@
-use std::path::PathBuf;
-
-#[cfg(test)]
-use std::env;
+use std::{env, path::PathBuf};
 
 pub struct EnvironmentUtils {}
 
@@ -29,6 +26,12 @@ impl EnvironmentUtils {
         path
     }
 
+    pub fn wallet_lock_path(wallet_name: &str) -> PathBuf {
+        let mut path = EnvironmentUtils::wallet_home_path();
+        path.push(format!("{}.lock", wallet_name));
+        path
+    }
+
     pub fn wallets_path() -> PathBuf {
         let mut path = EnvironmentUtils::indy_home_path();
         path.push("wallets");
@@ -62,6 +65,22 @@ impl EnvironmentUtils {
         path
     }
 
+    pub fn pool_transactions_cache_path(pool_name: &str) -> PathBuf {
+        let mut path = EnvironmentUtils::pool_home_path();
+        path.push(pool_name);
+        path.push("transactions_cache");
+        path.set_extension("json");
+        path
+    }
+
+    pub fn pool_journal_path(pool_name: &str) -> PathBuf {
+        let mut path = EnvironmentUtils::pool_home_path();
+        path.push(pool_name);
+        path.push("journal");
+        path.set_extension("json");
+        path
+    }
+
     pub fn pool_config_path(id: &str) -> PathBuf {
         let mut path = Self::pool_home_path();
         path.push(id);
@@ -70,20 +89,42 @@ impl EnvironmentUtils {
         path
     }
 
-    #[cfg(test)]
+    pub fn networks_path() -> PathBuf {
+        let mut path = EnvironmentUtils::indy_home_path();
+        path.push("networks");
+        path
+    }
+
+    pub fn network_config_path(name: &str) -> PathBuf {
+        let mut path = Self::networks_path();
+        path.push(name);
+        path.set_extension("json");
+        path
+    }
+
+    /// Temporary directory used by tests.
+    #[doc(hidden)]
     pub fn tmp_path() -> PathBuf {
         let mut path = env::temp_dir();
         path.push("indy_client");
         path
     }
 
-    #[cfg(test)]
+    #[doc(hidden)]
     pub fn tmp_file_path(file_name: &str) -> PathBuf {
         let mut path = EnvironmentUtils::tmp_path();
         path.push(file_name);
         path
     }
 
+    /// Configuration file applied on every start if it exists.
+    pub fn cli_config_path() -> PathBuf {
+        let mut path = dirs::home_dir().unwrap_or(PathBuf::from("/home/indy"));
+        path.push(".indy-cli-rs");
+        path.push("config.toml");
+        path
+    }
+
     pub fn history_file_path() -> PathBuf {
         let mut path = EnvironmentUtils::indy_home_path();
         path.push("history");
@@ -125,6 +166,29 @@ mod tests {
         assert!(path.to_string_lossy().contains("wallet1"));
     }
 
+    #[test]
+    fn wallet_lock_path_works() {
+        let path = EnvironmentUtils::wallet_lock_path("wallet1");
+
+        assert!(path.is_absolute());
+        assert_eq!(
+            EnvironmentUtils::wallet_home_path().join("wallet1.lock"),
+            path
+        );
+    }
+
+    #[test]
+    fn wallet_lock_path_works_for_name_with_dot() {
+        assert_eq!(
+            EnvironmentUtils::wallet_home_path().join("wallet.v1.lock"),
+            EnvironmentUtils::wallet_lock_path("wallet.v1")
+        );
+        assert_ne!(
+            EnvironmentUtils::wallet_lock_path("wallet.v1"),
+            EnvironmentUtils::wallet_lock_path("wallet.v2")
+        );
+    }
+
     #[test]
     fn pool_home_path_works() {
         let path = EnvironmentUtils::pool_home_path();
@@ -1,3 +1,4 @@
+use indy_utils::base58;
 use std::{
     fs::{DirBuilder, File, OpenOptions},
     io::{BufRead, BufReader, Read, Write},
@@ -52,3 +53,104 @@ where
     file.flush()
         .map_err(|err| format!("Can't write content: \"{}\" to the file: {}", content, err))
 }
+
+/// Length of a raw (not derived) wallet key.
+const RAW_KEY_LENGTH: usize = 32;
+
+/// Reads a raw wallet key from the file containing either 32 key bytes or their base58 encoding.
+/// Returns the key base58 encoded as expected for the `raw` key derivation method.
+/// The file must not be accessible by the group or others.
+pub fn read_key_file<P: AsRef<Path>>(file: P) -> Result<String, String> {
+    let file = file.as_ref();
+    let metadata = std::fs::metadata(file)
+        .map_err(|err| format!("Can't read the key file \"{}\": {}", file.display(), err))?;
+
+    if !metadata.is_file() {
+        return Err(format!("Key file \"{}\" is not a file", file.display()));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        if mode & 0o077 != 0 {
+            return Err(format!(
+                "Key file \"{}\" is accessible by other users (permissions {:o}). Restrict them to the owner: chmod 600",
+                file.display(),
+                mode & 0o777
+            ));
+        }
+    }
+
+    let mut content = Vec::new();
+    File::open(file)
+        .and_then(|mut file| file.read_to_end(&mut content))
+        .map_err(|err| format!("Can't read the key file \"{}\": {}", file.display(), err))?;
+
+    if content.len() == RAW_KEY_LENGTH {
+        return Ok(base58::encode(&content));
+    }
+
+    match std::str::from_utf8(&content).map(str::trim) {
+        Ok(key) if matches!(base58::decode(key), Ok(bytes) if bytes.len() == RAW_KEY_LENGTH) => {
+            Ok(key.to_string())
+        }
+        _ => Err(format!(
+            "Key file \"{}\" contains neither {} raw bytes nor base58 encoded key",
+            file.display(),
+            RAW_KEY_LENGTH
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::environment::EnvironmentUtils;
+
+    const KEY: &str = "6nxtSiXFvBd593Y2DCed2dYvRY1PGK9WMtxCBjLzKgex";
+
+    fn key_file(name: &str, content: &[u8], mode: u32) -> PathBuf {
+        let path = EnvironmentUtils::tmp_file_path(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn read_key_file_works_for_base58_key() {
+        let path = key_file("key_file_base58", format!("{}\n", KEY).as_bytes(), 0o600);
+        assert_eq!(KEY, read_key_file(&path).unwrap());
+    }
+
+    #[test]
+    fn read_key_file_works_for_raw_bytes() {
+        let bytes = base58::decode(KEY).unwrap();
+        let path = key_file("key_file_raw", &bytes, 0o400);
+        assert_eq!(KEY, read_key_file(&path).unwrap());
+    }
+
+    #[test]
+    fn read_key_file_works_for_invalid_key() {
+        let path = key_file("key_file_invalid", b"short key", 0o600);
+        read_key_file(&path).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn read_key_file_works_for_open_permissions() {
+        let path = key_file("key_file_open", KEY.as_bytes(), 0o644);
+        read_key_file(&path).unwrap_err();
+    }
+
+    #[test]
+    fn read_key_file_works_for_missing_file() {
+        read_key_file(EnvironmentUtils::tmp_file_path("key_file_missing")).unwrap_err();
+    }
+}
@@ -1 +1,312 @@
+use crate::error::{CliError, CliResult};
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
 pub use aries_askar::future::block_on;
+
+static ACTIVE_TOKEN: Mutex<Option<CancellationToken>> = Mutex::new(None);
+
+/// Cancellation flag shared between an in-flight operation and the Ctrl-C handler.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenInner>,
+}
+
+#[derive(Default)]
+struct TokenInner {
+    cancelled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.inner.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn register(&self, waker: &Waker) {
+        *self.inner.waker.lock().unwrap() = Some(waker.clone());
+    }
+}
+
+/// Future resolving to `CliError::Cancelled` as soon as its token is cancelled.
+/// The wrapped future is dropped together with it, which aborts the in-flight request.
+struct Cancellable<F> {
+    future: Pin<Box<F>>,
+    token: CancellationToken,
+}
+
+impl<F, T> Future for Cancellable<F>
+where
+    F: Future<Output = CliResult<T>>,
+{
+    type Output = CliResult<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(Err(CliError::Cancelled));
+        }
+        self.token.register(cx.waker());
+        // the token might have been cancelled before the waker was registered
+        if self.token.is_cancelled() {
+            return Poll::Ready(Err(CliError::Cancelled));
+        }
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Installs Ctrl-C handler cancelling the operation started with `block_on_cancellable`.
+/// If there is no such operation in progress `on_exit` is called and the process is terminated as by default.
+pub fn init_cancellation(on_exit: fn()) {
+    let res = ctrlc::set_handler(move || match ACTIVE_TOKEN.lock().unwrap().as_ref() {
+        Some(token) => token.cancel(),
+        None => {
+            on_exit();
+            std::process::exit(130)
+        }
+    });
+    if let Err(err) = res {
+        warn!("Unable to set Ctrl-C handler: {}", err);
+    }
+}
+
+/// Runs the future to completion unless Ctrl-C is pressed meanwhile.
+pub fn block_on_cancellable<F, T>(future: F) -> CliResult<T>
+where
+    F: Future<Output = CliResult<T>>,
+{
+    let token = CancellationToken::new();
+    let previous = ACTIVE_TOKEN.lock().unwrap().replace(token.clone());
+
+    let res = block_on(Cancellable {
+        future: Box::pin(future),
+        token,
+    });
+
+    *ACTIVE_TOKEN.lock().unwrap() = previous;
+    res
+}
+
+/// Future calling `on_tick` with the elapsed time at least every `interval` while the wrapped one is pending.
+/// The executor is woken up by a helper thread, so ticks are reported even if the wrapped future is idle.
+struct Ticking<'a, F> {
+    future: Pin<Box<F>>,
+    started: Instant,
+    last_tick: Instant,
+    interval: Duration,
+    on_tick: &'a mut dyn FnMut(Duration),
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<'a, F, T> Future for Ticking<'a, F>
+where
+    F: Future<Output = CliResult<T>>,
+{
+    type Output = CliResult<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        let now = Instant::now();
+        if now.duration_since(self.last_tick) >= self.interval {
+            self.last_tick = now;
+            let elapsed = now.duration_since(self.started);
+            (self.on_tick)(elapsed);
+        }
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Runs the future as `block_on_cancellable` does, reporting the elapsed time every `interval`.
+pub fn block_on_cancellable_with_ticks<F, T>(
+    future: F,
+    interval: Duration,
+    on_tick: &mut dyn FnMut(Duration),
+) -> CliResult<T>
+where
+    F: Future<Output = CliResult<T>>,
+{
+    let waker: Arc<Mutex<Option<Waker>>> = Arc::new(Mutex::new(None));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let ticker = {
+        let waker = waker.clone();
+        let done = done.clone();
+        thread::spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                thread::park_timeout(interval);
+                if let Some(waker) = waker.lock().unwrap().as_ref() {
+                    waker.wake_by_ref();
+                }
+            }
+        })
+    };
+
+    let now = Instant::now();
+    let res = block_on_cancellable(Ticking {
+        future: Box::pin(future),
+        started: now,
+        last_tick: now,
+        interval,
+        on_tick,
+        waker,
+    });
+
+    done.store(true, Ordering::SeqCst);
+    ticker.thread().unpark();
+    ticker.join().ok();
+    res
+}
+
+/// Future resolving to `None` if the wrapped one has not completed within the timeout.
+/// The executor is woken up by a helper thread once the timeout elapses.
+pub struct Timeout<F> {
+    future: Pin<Box<F>>,
+    timeout: Duration,
+    timer: Option<(Arc<TimerState>, thread::Thread)>,
+}
+
+#[derive(Default)]
+struct TimerState {
+    elapsed: AtomicBool,
+    done: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Wraps the future so it gives up waiting after `timeout`.
+pub fn with_timeout<F: Future>(future: F, timeout: Duration) -> Timeout<F> {
+    Timeout {
+        future: Box::pin(future),
+        timeout,
+        timer: None,
+    }
+}
+
+impl<F: Future> Future for Timeout<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let timeout = self.timeout;
+        let (state, _) = self.timer.get_or_insert_with(|| {
+            let state = Arc::new(TimerState::default());
+            let timer_state = state.clone();
+            let deadline = Instant::now() + timeout;
+            let timer = thread::spawn(move || {
+                while !timer_state.done.load(Ordering::SeqCst) {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        timer_state.elapsed.store(true, Ordering::SeqCst);
+                        if let Some(waker) = timer_state.waker.lock().unwrap().take() {
+                            waker.wake();
+                        }
+                        break;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+            });
+            (state, timer.thread().clone())
+        });
+        *state.waker.lock().unwrap() = Some(cx.waker().clone());
+        let state = state.clone();
+
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+        if state.elapsed.load(Ordering::SeqCst) {
+            return Poll::Ready(None);
+        }
+        Poll::Pending
+    }
+}
+
+impl<F> Drop for Timeout<F> {
+    fn drop(&mut self) {
+        if let Some((state, timer)) = self.timer.take() {
+            state.done.store(true, Ordering::SeqCst);
+            timer.unpark();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_on_cancellable_works() {
+        assert_eq!(1, block_on_cancellable(async { Ok(1) }).unwrap());
+    }
+
+    #[test]
+    fn block_on_cancellable_with_ticks_works() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+        let mut ticks = 0;
+        let res = block_on_cancellable_with_ticks(
+            std::future::poll_fn(|_| {
+                if Instant::now() >= deadline {
+                    Poll::Ready(Ok(1))
+                } else {
+                    Poll::Pending
+                }
+            }),
+            Duration::from_millis(10),
+            &mut |_| ticks += 1,
+        );
+        assert_eq!(1, res.unwrap());
+        assert!(ticks > 0);
+    }
+
+    #[test]
+    fn cancellable_works_for_cancelled_token() {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let res: CliResult<()> = block_on(Cancellable {
+            future: Box::pin(async move {
+                cancel.cancel();
+                std::future::pending::<()>().await;
+                Ok(())
+            }),
+            token,
+        });
+        assert!(matches!(res, Err(CliError::Cancelled)));
+    }
+
+    #[test]
+    fn with_timeout_works() {
+        assert_eq!(
+            Some(1),
+            block_on(with_timeout(async { 1 }, Duration::from_secs(1)))
+        );
+    }
+
+    #[test]
+    fn with_timeout_works_for_elapsed_timeout() {
+        let started = Instant::now();
+        let res = block_on(with_timeout(
+            std::future::pending::<()>(),
+            Duration::from_millis(50),
+        ));
+        assert_eq!(None, res);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}